@@ -5,28 +5,43 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-use clap::{Arg, Command};
+use clap::builder::ValueParser;
+use clap::{Arg, ArgAction, Command};
 use clap_complete::Shell;
 use std::cmp;
 use std::ffi::OsStr;
 use std::ffi::OsString;
+use std::fs;
 use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::symlink;
+#[cfg(windows)]
+use std::os::windows::fs::symlink_file as symlink;
 use std::path::{Path, PathBuf};
 use std::process;
+use uucore::completion::GENERATE_COMPLETION;
 use uucore::display::Quotable;
+use uucore::error::strip_errno;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 include!(concat!(env!("OUT_DIR"), "/uutils_map.rs"));
 
-fn usage<T>(utils: &UtilityMap<T>, name: &str) {
-    println!("{name} {VERSION} (multi-call binary)\n");
-    println!("Usage: {name} [function [arguments...]]\n");
-    println!("Currently defined functions:\n");
+fn sorted_utils<T>(utils: &UtilityMap<T>) -> Vec<&'static str> {
     #[allow(clippy::map_clone)]
     let mut utils: Vec<&str> = utils.keys().map(|&s| s).collect();
     utils.sort_unstable();
-    let display_list = utils.join(", ");
+    utils
+}
+
+fn usage<T>(utils: &UtilityMap<T>, name: &str) {
+    println!("{name} {VERSION} (multi-call binary)\n");
+    println!("Usage: {name} [function [arguments...]]");
+    println!("       {name} --list");
+    println!("       {name} manpage UTIL");
+    println!("       {name} --install [--symlink|--hardlink] [--force] DIR\n");
+    println!("Currently defined functions:\n");
+    let display_list = sorted_utils(utils).join(", ");
     let width = cmp::min(textwrap::termwidth(), 100) - 4 * 2; // (opinion/heuristic) max 100 chars wide with 4 character side indentions
     println!(
         "{}",
@@ -41,12 +56,20 @@ fn binary_path(args: &mut impl Iterator<Item = OsString>) -> PathBuf {
     }
 }
 
+/// The name the binary was invoked as, without directory and extension.
+///
+/// Names that aren't valid unicode can't be a utility, so they are treated
+/// like the name of the multi-call binary itself.
 fn name(binary_path: &Path) -> &str {
-    binary_path.file_stem().unwrap().to_str().unwrap()
+    binary_path
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .unwrap_or("coreutils")
 }
 
 fn main() {
     uucore::panic::mute_sigpipe_panic();
+    uucore::broken_pipe::restore_default();
 
     let utils = util_map();
     let mut args = uucore::args_os();
@@ -55,7 +78,10 @@ fn main() {
     let binary_as_util = name(&binary);
 
     // binary name equals util name?
-    if let Some(&(uumain, _)) = utils.get(binary_as_util) {
+    if let Some(&(uumain, uu_app)) = utils.get(binary_as_util) {
+        if uucore::completion::requested() {
+            uucore::completion::generate(uu_app());
+        }
         process::exit(uumain((vec![binary.into()].into_iter()).chain(args)));
     }
 
@@ -90,8 +116,31 @@ fn main() {
             gen_completions(args, &utils);
         }
 
+        if util == "manpage" {
+            gen_manpage(args, &utils);
+        }
+
+        if util == "--list" {
+            // One utility per line, e.g. to create links to the binary for each of them
+            for util in sorted_utils(&utils) {
+                println!("{util}");
+            }
+            process::exit(0);
+        }
+
+        if util == GENERATE_COMPLETION {
+            uucore::completion::generate_for(gen_coreutils_app(&utils), binary_as_util, args);
+        }
+
+        if util == "--install" {
+            install_links(args, &utils, binary_as_util);
+        }
+
         match utils.get(util) {
-            Some(&(uumain, _)) => {
+            Some(&(uumain, uu_app)) => {
+                if uucore::completion::requested() {
+                    uucore::completion::generate(uu_app());
+                }
                 process::exit(uumain((vec![util_os].into_iter()).chain(args)));
             }
             None => {
@@ -167,10 +216,166 @@ fn gen_completions<T: uucore::Args>(
     process::exit(0);
 }
 
+/// Creates a link to the multi-call binary for every utility in the directory given as
+/// parameter, e.g. to provision a container image at first boot
+fn install_links<T>(
+    args: impl Iterator<Item = OsString>,
+    util_map: &UtilityMap<T>,
+    name: &str,
+) -> ! {
+    let matches = Command::new("install")
+        .bin_name(format!("{name} --install"))
+        .about("Creates a link to this binary for every utility in DIR")
+        .arg(
+            Arg::new("symlink")
+                .long("symlink")
+                .short('s')
+                .help("create symbolic links (the default)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("hardlink")
+                .long("hardlink")
+                .short('H')
+                .help("create hard links")
+                .conflicts_with("symlink")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .short('f')
+                .help("replace existing files")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dir")
+                .value_name("DIR")
+                .value_parser(ValueParser::os_string())
+                .required(true),
+        )
+        .get_matches_from(std::iter::once(OsString::from("--install")).chain(args));
+
+    let dir = Path::new(matches.get_one::<OsString>("dir").unwrap());
+    let hardlink = matches.get_flag("hardlink");
+    let force = matches.get_flag("force");
+
+    let binary = match std::env::current_exe() {
+        Ok(binary) => binary,
+        Err(e) => {
+            eprintln!("{name}: cannot locate the binary: {}", strip_errno(&e));
+            process::exit(1);
+        }
+    };
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!(
+            "{name}: cannot create directory {}: {}",
+            dir.quote(),
+            strip_errno(&e)
+        );
+        process::exit(1);
+    }
+
+    let mut code = 0;
+    for util in sorted_utils(util_map) {
+        let link = dir.join(format!("{util}{}", std::env::consts::EXE_SUFFIX));
+        if link.symlink_metadata().is_ok() {
+            if links_to(&link, &binary) {
+                continue;
+            }
+            if force {
+                if let Err(e) = fs::remove_file(&link) {
+                    eprintln!(
+                        "{name}: cannot remove {}: {}",
+                        link.quote(),
+                        strip_errno(&e)
+                    );
+                    code = 1;
+                    continue;
+                }
+            }
+        }
+        let result = if hardlink {
+            fs::hard_link(&binary, &link)
+        } else {
+            symlink(&binary, &link)
+        };
+        if let Err(e) = result {
+            eprintln!(
+                "{name}: cannot create link {}: {}",
+                link.quote(),
+                strip_errno(&e)
+            );
+            code = 1;
+        }
+    }
+    process::exit(code);
+}
+
+/// Whether `path` already leads to `binary`, in which case it is left alone
+fn links_to(path: &Path, binary: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::metadata(path), fs::metadata(binary)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        matches!(
+            (fs::canonicalize(path), fs::canonicalize(binary)),
+            (Ok(a), Ok(b)) if a == b
+        )
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn symlink(_original: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Prints the manpage for the utility in the first parameter to stdout
+fn gen_manpage<T: uucore::Args>(
+    args: impl Iterator<Item = OsString>,
+    util_map: &UtilityMap<T>,
+) -> ! {
+    let all_utilities: Vec<_> = std::iter::once("coreutils")
+        .chain(util_map.keys().copied())
+        .collect();
+
+    let matches = Command::new("manpage")
+        .about("Prints manpage to stdout")
+        .arg(
+            Arg::new("utility")
+                .value_parser(clap::builder::PossibleValuesParser::new(all_utilities))
+                .required(true),
+        )
+        .get_matches_from(std::iter::once(OsString::from("manpage")).chain(args));
+
+    let utility = matches.get_one::<String>("utility").unwrap();
+
+    let command = if utility == "coreutils" {
+        gen_coreutils_app(util_map)
+    } else {
+        let (name, (_, sub_app)) = util_map.get_entry(utility).unwrap();
+        // The utility is named after the util name, which is "manpage" here
+        sub_app().name(name)
+    };
+
+    let man = clap_mangen::Man::new(command);
+    man.render(&mut io::stdout())
+        .expect("Man page generation failed");
+    io::stdout().flush().unwrap();
+    process::exit(0);
+}
+
 fn gen_coreutils_app<T: uucore::Args>(util_map: &UtilityMap<T>) -> Command {
-    let mut command = Command::new("coreutils");
-    for (_, (_, sub_app)) in util_map {
-        command = command.subcommand(sub_app());
+    let mut command = Command::new("coreutils").version(VERSION);
+    for (name, (_, sub_app)) in util_map {
+        // The utilities are named after the util name, which is not theirs here
+        command = command.subcommand(sub_app().name(name));
     }
     command
 }
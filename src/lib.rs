@@ -0,0 +1,200 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// spell-checker:ignore uumain
+
+//! Running the utilities from another program, without starting a new
+//! process for each of them.
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use coreutils::{run, Stdio};
+//!
+//! let stdio = Stdio {
+//!     stdout: Some(File::create("listing").unwrap()),
+//!     ..Stdio::default()
+//! };
+//! let code = run("ls", ["-l", "/tmp"], stdio).unwrap();
+//! ```
+//!
+//! The utilities were written as programs, which shows in a few ways:
+//!
+//! * The standard streams of the whole process are replaced while a
+//!   utility runs, so runs are done one at a time, and output of other
+//!   threads in the meantime ends up in the streams given to the utility.
+//! * Input that a utility reads from stdin but doesn't use may be kept in
+//!   the buffer of [`std::io::stdin`].
+//! * A utility that gives up on a fatal error may exit the process.
+use clap::Command;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Write};
+use std::iter;
+use std::sync::{Mutex, PoisonError};
+
+use once_cell::sync::Lazy;
+
+include!(concat!(env!("OUT_DIR"), "/uutils_map.rs"));
+
+/// The standard streams of a utility started by [`run`]. Those that are
+/// `None` are the ones of the calling process.
+#[derive(Default)]
+pub struct Stdio {
+    pub stdin: Option<File>,
+    pub stdout: Option<File>,
+    pub stderr: Option<File>,
+}
+
+/// Runs are serialized, as they share the standard streams, the exit code
+/// and the name of the utility.
+static RUN_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// Run the utility `util` with the arguments `args`, which don't include
+/// the name of the utility, and return its exit code.
+///
+/// An error is returned if there is no such utility, or if the streams in
+/// `stdio` can't be put in place.
+pub fn run<I, S>(util: &str, args: I, stdio: Stdio) -> io::Result<i32>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<OsString>,
+{
+    let utils = util_map::<std::vec::IntoIter<OsString>>();
+    let (&name, &(uumain, _)) = utils.get_entry(util).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{util}: function/utility not found"),
+        )
+    })?;
+    let args: Vec<OsString> = iter::once(OsString::from(name))
+        .chain(args.into_iter().map(Into::into))
+        .collect();
+
+    let _lock = RUN_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    // Whatever the caller wrote so far goes to its own stdout.
+    io::stdout().flush()?;
+    let mut redirects = Vec::new();
+    for (stream, file) in [
+        (redirect::STDIN, &stdio.stdin),
+        (redirect::STDOUT, &stdio.stdout),
+        (redirect::STDERR, &stdio.stderr),
+    ] {
+        if let Some(file) = file {
+            redirects.push(redirect::Redirect::new(stream, file)?);
+        }
+    }
+
+    uucore::set_util_name(Some(name));
+    uucore::error::set_exit_code(0);
+    let code = uumain(args.into_iter());
+    let flushed = io::stdout().flush();
+    uucore::set_util_name(None);
+
+    drop(redirects);
+    flushed?;
+    Ok(code)
+}
+
+#[cfg(unix)]
+mod redirect {
+    use std::fs::File;
+    use std::io;
+    use std::os::raw::c_int;
+    use std::os::unix::io::AsRawFd;
+
+    pub const STDIN: c_int = libc::STDIN_FILENO;
+    pub const STDOUT: c_int = libc::STDOUT_FILENO;
+    pub const STDERR: c_int = libc::STDERR_FILENO;
+
+    /// A standard stream that is replaced by a file until this is dropped.
+    pub struct Redirect {
+        fd: c_int,
+        saved: c_int,
+    }
+
+    impl Redirect {
+        pub fn new(fd: c_int, file: &File) -> io::Result<Self> {
+            let saved = unsafe { libc::dup(fd) };
+            if saved < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if unsafe { libc::dup2(file.as_raw_fd(), fd) } < 0 {
+                let error = io::Error::last_os_error();
+                unsafe { libc::close(saved) };
+                return Err(error);
+            }
+            Ok(Self { fd, saved })
+        }
+    }
+
+    impl Drop for Redirect {
+        fn drop(&mut self) {
+            unsafe {
+                libc::dup2(self.saved, self.fd);
+                libc::close(self.saved);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod redirect {
+    use std::fs::File;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::Console::{GetStdHandle, SetStdHandle, STD_HANDLE};
+    pub use windows_sys::Win32::System::Console::{
+        STD_ERROR_HANDLE as STDERR, STD_INPUT_HANDLE as STDIN, STD_OUTPUT_HANDLE as STDOUT,
+    };
+
+    /// A standard stream that is replaced by a file until this is dropped.
+    ///
+    /// The standard library looks the handles up for every read and write,
+    /// so replacing them is enough for its streams to follow.
+    pub struct Redirect {
+        stream: STD_HANDLE,
+        saved: HANDLE,
+    }
+
+    impl Redirect {
+        pub fn new(stream: STD_HANDLE, file: &File) -> io::Result<Self> {
+            let saved = unsafe { GetStdHandle(stream) };
+            if unsafe { SetStdHandle(stream, file.as_raw_handle() as HANDLE) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Self { stream, saved })
+        }
+    }
+
+    impl Drop for Redirect {
+        fn drop(&mut self) {
+            unsafe { SetStdHandle(self.stream, self.saved) };
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod redirect {
+    use std::fs::File;
+    use std::io;
+
+    pub const STDIN: i32 = 0;
+    pub const STDOUT: i32 = 1;
+    pub const STDERR: i32 = 2;
+
+    pub struct Redirect;
+
+    impl Redirect {
+        pub fn new(_stream: i32, _file: &File) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the standard streams can't be replaced on this platform",
+            ))
+        }
+    }
+}
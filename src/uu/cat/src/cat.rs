@@ -18,6 +18,7 @@ use thiserror::Error;
 use uucore::display::Quotable;
 use uucore::error::UResult;
 use uucore::fs::FileInformation;
+use uucore::input::Reader;
 
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
@@ -34,6 +35,8 @@ use std::os::unix::fs::FileTypeExt;
 #[cfg(unix)]
 use std::os::unix::net::UnixStream;
 use uucore::format_usage;
+#[cfg(unix)]
+use uucore::input::STREAM_BUFFER_SIZE;
 
 static USAGE: &str = "{} [OPTION]... [FILE]...";
 static ABOUT: &str = "Concatenate FILE(s), or standard input, to standard output
@@ -143,6 +146,8 @@ impl<T> FdReadable for T where T: Read {}
 /// Represents an open file handle, stream, or other device
 struct InputHandle<R: FdReadable> {
     reader: R,
+    /// The size of the buffer to read `reader` with.
+    buffer_size: usize,
     is_interactive: bool,
 }
 
@@ -330,8 +335,9 @@ fn cat_path(
 ) -> CatResult<()> {
     match get_input_type(path)? {
         InputType::StdIn => {
-            let stdin = io::stdin();
+            let stdin = Reader::stdin();
             let mut handle = InputHandle {
+                buffer_size: stdin.buffer_size(),
                 reader: stdin,
                 is_interactive: atty::is(atty::Stream::Stdin),
             };
@@ -344,6 +350,7 @@ fn cat_path(
             socket.shutdown(Shutdown::Write)?;
             let mut handle = InputHandle {
                 reader: socket,
+                buffer_size: STREAM_BUFFER_SIZE,
                 is_interactive: false,
             };
             cat_handle(&mut handle, options, state)
@@ -359,7 +366,9 @@ fn cat_path(
                 }
             }
 
+            let file = Reader::from_file(file);
             let mut handle = InputHandle {
+                buffer_size: file.buffer_size(),
                 reader: file,
                 is_interactive: false,
             };
@@ -444,7 +453,7 @@ fn write_fast<R: FdReadable>(handle: &mut InputHandle<R>) -> CatResult<()> {
     }
     // If we're not on Linux or Android, or the splice() call failed,
     // fall back on slower writing.
-    let mut buf = [0; 1024 * 64];
+    let mut buf = vec![0; handle.buffer_size];
     while let Ok(n) = handle.reader.read(&mut buf) {
         if n == 0 {
             break;
@@ -461,7 +470,7 @@ fn write_lines<R: FdReadable>(
     options: &OutputOptions,
     state: &mut OutputState,
 ) -> CatResult<()> {
-    let mut in_buf = [0; 1024 * 31];
+    let mut in_buf = vec![0; handle.buffer_size];
     let stdout = io::stdout();
     let mut writer = stdout.lock();
 
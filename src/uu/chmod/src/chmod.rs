@@ -5,20 +5,30 @@
 // For the full copyright and license information, please view the LICENSE
 // file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) Chmoder cmode fmode fperm fref ugoa RFILE RFILE's
+// spell-checker:ignore (ToDO) Chmoder cmode fmode fperm fref ugoa RFILE RFILE's DACL
 
 use clap::{crate_version, Arg, ArgAction, Command};
+#[cfg(unix)]
 use std::fs;
+#[cfg(unix)]
+use std::io;
+#[cfg(unix)]
 use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path::Path;
 use uucore::display::Quotable;
 use uucore::error::{ExitCode, UResult, USimpleError, UUsageError};
+#[cfg(unix)]
 use uucore::fs::display_permissions_unix;
+#[cfg(unix)]
 use uucore::libc::mode_t;
-#[cfg(not(windows))]
 use uucore::mode;
 use uucore::{format_usage, show_error};
 
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use windows::{display_mode, get_mode, set_mode};
+
 static ABOUT: &str = "Change the mode of each FILE to MODE.
  With --reference, change the mode of each FILE to that of RFILE.";
 
@@ -32,6 +42,7 @@ mod options {
     pub const RECURSIVE: &str = "recursive";
     pub const MODE: &str = "MODE";
     pub const FILE: &str = "FILE";
+    pub const DACL: &str = "dacl";
 }
 
 const USAGE: &str = "\
@@ -51,18 +62,17 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // a possible MODE prefix '-' needs to be removed (e.g. "chmod -x FILE").
     let mode_had_minus_prefix = mode::strip_minus_from_mode(&mut args);
 
-    let after_help = get_long_usage();
-
-    let matches = uu_app().after_help(after_help).try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let changes = matches.get_flag(options::CHANGES);
     let quiet = matches.get_flag(options::QUIET);
     let verbose = matches.get_flag(options::VERBOSE);
     let preserve_root = matches.get_flag(options::PRESERVE_ROOT);
     let recursive = matches.get_flag(options::RECURSIVE);
+    let dacl = matches.get_flag(options::DACL);
     let fmode = match matches.get_one::<String>(options::REFERENCE) {
-        Some(fref) => match fs::metadata(fref) {
-            Ok(meta) => Some(meta.mode()),
+        Some(fref) => match get_mode(Path::new(fref), dacl) {
+            Ok(mode) => Some(mode),
             Err(err) => {
                 return Err(USimpleError::new(
                     1,
@@ -103,6 +113,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         verbose,
         preserve_root,
         recursive,
+        dacl,
         fmode,
         cmode,
     };
@@ -115,6 +126,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::CHANGES)
@@ -163,6 +175,15 @@ pub fn uu_app() -> Command {
                 .value_hint(clap::ValueHint::FilePath)
                 .help("use RFILE's mode instead of MODE values"),
         )
+        .arg(
+            Arg::new(options::DACL)
+                .long(options::DACL)
+                .help(
+                    "also change the access of the owner, the group and Everyone \
+                    in the DACL (Windows only)",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::MODE).required_unless_present(options::REFERENCE), // It would be nice if clap could parse with delimiter, e.g. "g-x,u+x",
                                                                                  // however .multiple_occurrences(true) cannot be used here because FILE already needs that.
@@ -182,6 +203,7 @@ struct Chmoder {
     verbose: bool,
     preserve_root: bool,
     recursive: bool,
+    dacl: bool,
     fmode: Option<u32>,
     cmode: Option<String>,
 }
@@ -247,19 +269,15 @@ impl Chmoder {
         r
     }
 
-    #[cfg(windows)]
-    fn chmod_file(&self, file: &Path) -> UResult<()> {
-        // chmod is useless on Windows
-        // it doesn't set any permissions at all
-        // instead it just sets the readonly attribute on the file
-        Ok(())
-    }
-    #[cfg(unix)]
     fn chmod_file(&self, file: &Path) -> UResult<()> {
-        use uucore::mode::get_umask;
+        #[cfg(unix)]
+        let umask = mode::get_umask();
+        // Windows has no umask.
+        #[cfg(windows)]
+        let umask = 0;
 
-        let fperm = match fs::metadata(file) {
-            Ok(meta) => meta.mode() & 0o7777,
+        let fperm = match get_mode(file, self.dacl) {
+            Ok(mode) => mode & 0o7777,
             Err(err) => {
                 if file.is_symlink() {
                     if self.verbose {
@@ -293,7 +311,7 @@ impl Chmoder {
                     let result = if mode.contains(arr) {
                         mode::parse_numeric(new_mode, mode, file.is_dir()).map(|v| (v, v))
                     } else {
-                        mode::parse_symbolic(new_mode, mode, get_umask(), file.is_dir()).map(|m| {
+                        mode::parse_symbolic(new_mode, mode, umask, file.is_dir()).map(|m| {
                             // calculate the new mode as if umask was 0
                             let naive_mode = mode::parse_symbolic(
                                 naively_expected_new_mode,
@@ -327,8 +345,8 @@ impl Chmoder {
                         format!(
                             "{}: new permissions are {}, not {}",
                             file.maybe_quote(),
-                            display_permissions_unix(new_mode as mode_t, false),
-                            display_permissions_unix(naively_expected_new_mode as mode_t, false)
+                            display_mode(new_mode),
+                            display_mode(naively_expected_new_mode)
                         ),
                     ));
                 }
@@ -338,7 +356,6 @@ impl Chmoder {
         Ok(())
     }
 
-    #[cfg(unix)]
     fn change_file(&self, fperm: u32, mode: u32, file: &Path) -> Result<(), i32> {
         if fperm == mode {
             if self.verbose && !self.changes {
@@ -346,11 +363,11 @@ impl Chmoder {
                     "mode of {} retained as {:04o} ({})",
                     file.quote(),
                     fperm,
-                    display_permissions_unix(fperm as mode_t, false),
+                    display_mode(fperm),
                 );
             }
             Ok(())
-        } else if let Err(err) = fs::set_permissions(file, fs::Permissions::from_mode(mode)) {
+        } else if let Err(err) = set_mode(file, mode, self.dacl) {
             if !self.quiet {
                 show_error!("{}", err);
             }
@@ -359,9 +376,9 @@ impl Chmoder {
                     "failed to change mode of file {} from {:04o} ({}) to {:04o} ({})",
                     file.quote(),
                     fperm,
-                    display_permissions_unix(fperm as mode_t, false),
+                    display_mode(fperm),
                     mode,
-                    display_permissions_unix(mode as mode_t, false)
+                    display_mode(mode)
                 );
             }
             Err(1)
@@ -371,12 +388,30 @@ impl Chmoder {
                     "mode of {} changed from {:04o} ({}) to {:04o} ({})",
                     file.quote(),
                     fperm,
-                    display_permissions_unix(fperm as mode_t, false),
+                    display_mode(fperm),
                     mode,
-                    display_permissions_unix(mode as mode_t, false)
+                    display_mode(mode)
                 );
             }
             Ok(())
         }
     }
 }
+
+/// The mode of `path`, following symbolic links.
+#[cfg(unix)]
+fn get_mode(path: &Path, _dacl: bool) -> io::Result<u32> {
+    fs::metadata(path).map(|meta| meta.mode())
+}
+
+/// Set the mode of `path` to `mode`, following symbolic links.
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32, _dacl: bool) -> io::Result<()> {
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+/// The permissions of `mode`, like `rwxr-xr-x`.
+#[cfg(unix)]
+fn display_mode(mode: u32) -> String {
+    display_permissions_unix(mode as mode_t, false)
+}
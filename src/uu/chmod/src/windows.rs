@@ -0,0 +1,267 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore DACL PSID rwxrwxrwx trustee
+
+//! Modes on Windows.
+//!
+//! Windows files have no mode, so one is made up from the read-only
+//! attribute, which stands for the write permission of the owner. With
+//! `--dacl`, the permissions of the owner, the group and others are instead
+//! those that the DACL of the file grants to its owner, to its group and to
+//! Everyone.
+use std::ffi::c_void;
+use std::fs;
+use std::io;
+use std::mem;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::ptr;
+
+use windows_sys::Win32::Foundation::ERROR_SUCCESS;
+use windows_sys::Win32::Security::Authorization::{
+    GetEffectiveRightsFromAclW, GetNamedSecurityInfoW, SetEntriesInAclW, SetNamedSecurityInfoW,
+    EXPLICIT_ACCESS_W, NO_MULTIPLE_TRUSTEE, REVOKE_ACCESS, SET_ACCESS, SE_FILE_OBJECT,
+    TRUSTEE_IS_SID, TRUSTEE_IS_UNKNOWN, TRUSTEE_W,
+};
+use windows_sys::Win32::Security::{
+    CreateWellKnownSid, EqualSid, WinWorldSid, ACL, DACL_SECURITY_INFORMATION,
+    GROUP_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION,
+};
+use windows_sys::Win32::Storage::FileSystem::{
+    FILE_GENERIC_EXECUTE, FILE_GENERIC_READ, FILE_GENERIC_WRITE,
+};
+use windows_sys::Win32::System::Memory::LocalFree;
+
+// From winnt.h
+const SECURITY_MAX_SID_SIZE: usize = 68;
+
+/// The rights that stand for the read, write and execute permissions.
+const RIGHTS: [(u32, u32); 3] = [
+    (0o4, FILE_GENERIC_READ),
+    (0o2, FILE_GENERIC_WRITE),
+    (0o1, FILE_GENERIC_EXECUTE),
+];
+
+/// The mode of `path`, following symbolic links.
+///
+/// Without `dacl`, every permission is granted, except writing if the file
+/// is read-only, which matches what `ls` shows.
+pub fn get_mode(path: &Path, dacl: bool) -> io::Result<u32> {
+    let readonly = fs::metadata(path)?.permissions().readonly();
+    let mode = if dacl {
+        let security = SecurityInfo::of(path)?;
+        let world = world_sid()?;
+        let mut mode = 0;
+        for (sid, shift) in [
+            (security.owner, 6),
+            (security.group, 3),
+            (world_ptr(&world), 0),
+        ] {
+            mode |= rights_to_bits(security.effective_rights(sid)?) << shift;
+        }
+        mode
+    } else {
+        0o777
+    };
+    Ok(if readonly { mode & !0o222 } else { mode })
+}
+
+/// Set the mode of `path` to `mode`, following symbolic links.
+///
+/// The write permission of the owner decides whether the file is
+/// read-only. With `dacl`, the explicit entries of the DACL for the owner,
+/// the group and Everyone are replaced by ones granting the permissions of
+/// `mode`. Other entries, like inherited ones, are left alone, so access
+/// may still be granted through them.
+pub fn set_mode(path: &Path, mode: u32, dacl: bool) -> io::Result<()> {
+    if dacl {
+        let security = SecurityInfo::of(path)?;
+        let world = world_sid()?;
+        let mut trustees = vec![(security.owner, mode >> 6)];
+        // Files created by administrators can have the same owner and group.
+        if unsafe { EqualSid(security.owner, security.group) } == 0 {
+            trustees.push((security.group, mode >> 3));
+        }
+        trustees.push((world_ptr(&world), mode));
+        let entries: Vec<EXPLICIT_ACCESS_W> = trustees
+            .into_iter()
+            .map(|(sid, bits)| {
+                let rights = bits_to_rights(bits & 0o7);
+                EXPLICIT_ACCESS_W {
+                    grfAccessPermissions: rights,
+                    grfAccessMode: if rights == 0 {
+                        REVOKE_ACCESS
+                    } else {
+                        SET_ACCESS
+                    },
+                    grfInheritance: 0,
+                    Trustee: trustee(sid),
+                }
+            })
+            .collect();
+
+        let mut new_dacl: *mut ACL = ptr::null_mut();
+        let error = unsafe {
+            SetEntriesInAclW(
+                entries.len() as u32,
+                entries.as_ptr(),
+                security.dacl,
+                &mut new_dacl,
+            )
+        };
+        if error != ERROR_SUCCESS {
+            return Err(io::Error::from_raw_os_error(error as i32));
+        }
+        let mut name = to_wide(path);
+        let error = unsafe {
+            SetNamedSecurityInfoW(
+                name.as_mut_ptr(),
+                SE_FILE_OBJECT,
+                DACL_SECURITY_INFORMATION,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                new_dacl,
+                ptr::null(),
+            )
+        };
+        unsafe { LocalFree(new_dacl as _) };
+        if error != ERROR_SUCCESS {
+            return Err(io::Error::from_raw_os_error(error as i32));
+        }
+    }
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    let readonly = mode & 0o200 == 0;
+    if permissions.readonly() != readonly {
+        permissions.set_readonly(readonly);
+        fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+/// The permissions of `mode`, like `rwxr-xr-x`.
+pub fn display_mode(mode: u32) -> String {
+    (0..9)
+        .rev()
+        .map(|bit| {
+            if mode & (1 << bit) == 0 {
+                '-'
+            } else {
+                ['x', 'w', 'r'][bit % 3]
+            }
+        })
+        .collect()
+}
+
+/// The owner, group and DACL of a file, which point into its security
+/// descriptor.
+struct SecurityInfo {
+    owner: *mut c_void,
+    group: *mut c_void,
+    dacl: *mut ACL,
+    descriptor: *mut c_void,
+}
+
+impl SecurityInfo {
+    fn of(path: &Path) -> io::Result<Self> {
+        let mut info = Self {
+            owner: ptr::null_mut(),
+            group: ptr::null_mut(),
+            dacl: ptr::null_mut(),
+            descriptor: ptr::null_mut(),
+        };
+        let error = unsafe {
+            GetNamedSecurityInfoW(
+                to_wide(path).as_ptr(),
+                SE_FILE_OBJECT,
+                OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION | DACL_SECURITY_INFORMATION,
+                &mut info.owner,
+                &mut info.group,
+                &mut info.dacl,
+                ptr::null_mut(),
+                &mut info.descriptor,
+            )
+        };
+        if error != ERROR_SUCCESS {
+            return Err(io::Error::from_raw_os_error(error as i32));
+        }
+        Ok(info)
+    }
+
+    /// The rights that the DACL grants to `sid`.
+    fn effective_rights(&self, sid: *mut c_void) -> io::Result<u32> {
+        // A missing DACL grants everything to everyone.
+        if self.dacl.is_null() {
+            return Ok(bits_to_rights(0o7));
+        }
+        let mut rights = 0;
+        let error = unsafe { GetEffectiveRightsFromAclW(self.dacl, &trustee(sid), &mut rights) };
+        if error != ERROR_SUCCESS {
+            return Err(io::Error::from_raw_os_error(error as i32));
+        }
+        Ok(rights)
+    }
+}
+
+impl Drop for SecurityInfo {
+    fn drop(&mut self) {
+        unsafe { LocalFree(self.descriptor as _) };
+    }
+}
+
+/// The SID of Everyone, in a buffer aligned like a SID.
+fn world_sid() -> io::Result<Vec<u32>> {
+    let mut sid = vec![0u32; SECURITY_MAX_SID_SIZE / 4];
+    let mut size = SECURITY_MAX_SID_SIZE as u32;
+    let ok = unsafe {
+        CreateWellKnownSid(
+            WinWorldSid,
+            ptr::null_mut(),
+            sid.as_mut_ptr().cast(),
+            &mut size,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(sid)
+}
+
+fn world_ptr(sid: &[u32]) -> *mut c_void {
+    sid.as_ptr() as *mut c_void
+}
+
+/// A trustee designated by `sid`.
+fn trustee(sid: *mut c_void) -> TRUSTEE_W {
+    // SAFETY: `TRUSTEE_W` is a plain C struct, for which all zeroes is valid.
+    let mut trustee: TRUSTEE_W = unsafe { mem::zeroed() };
+    trustee.MultipleTrusteeOperation = NO_MULTIPLE_TRUSTEE;
+    trustee.TrusteeForm = TRUSTEE_IS_SID;
+    trustee.TrusteeType = TRUSTEE_IS_UNKNOWN;
+    trustee.ptstrName = sid.cast();
+    trustee
+}
+
+/// The `rwx` bits of the rights in `rights`.
+fn rights_to_bits(rights: u32) -> u32 {
+    RIGHTS
+        .iter()
+        .filter(|(_, right)| rights & right == *right)
+        .fold(0, |bits, (bit, _)| bits | bit)
+}
+
+/// The rights standing for the `rwx` bits in `bits`.
+fn bits_to_rights(bits: u32) -> u32 {
+    RIGHTS
+        .iter()
+        .filter(|(bit, _)| bits & bit != 0)
+        .fold(0, |rights, (_, right)| rights | right)
+}
+
+/// `path` as a NUL terminated wide string, as taken by the Windows API.
+fn to_wide(path: &Path) -> Vec<u16> {
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
@@ -7,24 +7,38 @@
 
 // spell-checker:ignore (ToDO) COMFOLLOW Passwd RFILE RFILE's derefer dgid duid groupname
 
+#[cfg(unix)]
 use uucore::display::Quotable;
+#[cfg(unix)]
 pub use uucore::entries::{self, Group, Locate, Passwd};
 use uucore::format_usage;
-use uucore::perms::{chown_base, options, IfFrom};
+use uucore::perms::options;
+#[cfg(unix)]
+use uucore::perms::{chown_base, IfFrom};
 
-use uucore::error::{FromIo, UResult, USimpleError};
+use uucore::error::UResult;
+#[cfg(unix)]
+use uucore::error::{FromIo, USimpleError};
 
-use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
+#[cfg(unix)]
+use clap::ArgMatches;
+use clap::{crate_version, Arg, ArgAction, Command};
 
+#[cfg(unix)]
 use std::fs;
+#[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
+#[cfg(windows)]
+mod windows;
+
 static ABOUT: &str = "Change file owner and group";
 
 const USAGE: &str = "\
     {} [OPTION]... [OWNER][:[GROUP]] FILE...
     {} [OPTION]... --reference=RFILE FILE...";
 
+#[cfg(unix)]
 fn parse_gid_uid_and_filter(matches: &ArgMatches) -> UResult<(Option<u32>, Option<u32>, IfFrom)> {
     let filter = if let Some(spec) = matches.get_one::<String>(options::FROM) {
         match parse_spec(spec, ':')? {
@@ -54,13 +68,20 @@ fn parse_gid_uid_and_filter(matches: &ArgMatches) -> UResult<(Option<u32>, Optio
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    chown_base(
+    #[cfg(unix)]
+    return chown_base(
         uu_app(),
         args,
         options::ARG_OWNER,
         parse_gid_uid_and_filter,
         false,
-    )
+    );
+    #[cfg(windows)]
+    return windows::chown(&uucore::perms::chown_matches(
+        uu_app(),
+        args,
+        options::ARG_OWNER,
+    )?);
 }
 
 pub fn uu_app() -> Command {
@@ -194,6 +215,7 @@ pub fn uu_app() -> Command {
 /// name. The `sep` argument specifies which character to use as a
 /// separator between the owner and group; calling code should set
 /// this to `':'`.
+#[cfg(unix)]
 fn parse_spec(spec: &str, sep: char) -> UResult<(Option<u32>, Option<u32>)> {
     assert!(['.', ':'].contains(&sep));
     let mut args = spec.splitn(2, sep);
@@ -262,7 +284,7 @@ fn parse_spec(spec: &str, sep: char) -> UResult<(Option<u32>, Option<u32>)> {
     Ok((uid, gid))
 }
 
-#[cfg(test)]
+#[cfg(all(test, unix))]
 mod test {
     use super::*;
 
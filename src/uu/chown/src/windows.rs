@@ -0,0 +1,422 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore PSID RFILE
+
+//! Ownership on Windows, where owners and groups are SIDs.
+//!
+//! Owners and groups are given as account names, like `user`,
+//! `DOMAIN\user` or `Administrators`, or as SIDs, like `S-1-5-32-544`.
+use std::ffi::{c_void, OsStr, OsString};
+use std::fs;
+use std::io;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::path::Path;
+use std::ptr;
+
+use clap::ArgMatches;
+use windows_sys::Win32::Foundation::{CloseHandle, ERROR_SUCCESS, HANDLE};
+use windows_sys::Win32::Security::Authorization::{
+    ConvertSidToStringSidW, ConvertStringSidToSidW, GetNamedSecurityInfoW, SetNamedSecurityInfoW,
+    SE_FILE_OBJECT,
+};
+use windows_sys::Win32::Security::{
+    AdjustTokenPrivileges, EqualSid, GetLengthSid, LookupAccountNameW, LookupAccountSidW,
+    LookupPrivilegeValueW, GROUP_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION,
+    SE_PRIVILEGE_ENABLED, TOKEN_ADJUST_PRIVILEGES, TOKEN_PRIVILEGES,
+};
+use windows_sys::Win32::System::Memory::LocalFree;
+use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+use uucore::display::Quotable;
+use uucore::error::{FromIo, UResult, USimpleError};
+use uucore::perms::{options, verbosity_level, VerbosityLevel};
+use uucore::show_error;
+
+/// A security identifier, standing for a user or a group.
+pub struct Sid(Vec<u32>);
+
+impl Sid {
+    /// Look up the account `name`, or parse it if it is a SID.
+    pub fn lookup(name: &str) -> io::Result<Self> {
+        let wide_name = to_wide(OsStr::new(name));
+        if name.starts_with("S-") {
+            let mut sid = ptr::null_mut();
+            if unsafe { ConvertStringSidToSidW(wide_name.as_ptr(), &mut sid) } == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let result = unsafe { Self::copy(sid) };
+            unsafe { LocalFree(sid as _) };
+            return Ok(result);
+        }
+
+        let mut sid_size = 0;
+        let mut domain_size = 0;
+        let mut sid_use = 0;
+        unsafe {
+            LookupAccountNameW(
+                ptr::null(),
+                wide_name.as_ptr(),
+                ptr::null_mut(),
+                &mut sid_size,
+                ptr::null_mut(),
+                &mut domain_size,
+                &mut sid_use,
+            )
+        };
+        if sid_size == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // The buffer is made of `u32`s to be aligned like a SID.
+        let mut sid = vec![0u32; (sid_size as usize + 3) / 4];
+        let mut domain = vec![0u16; domain_size as usize];
+        let ok = unsafe {
+            LookupAccountNameW(
+                ptr::null(),
+                wide_name.as_ptr(),
+                sid.as_mut_ptr().cast(),
+                &mut sid_size,
+                domain.as_mut_ptr(),
+                &mut domain_size,
+                &mut sid_use,
+            )
+        };
+        if ok == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self(sid))
+    }
+
+    /// Copy the SID that `sid` points to.
+    ///
+    /// # Safety
+    ///
+    /// `sid` must point to a valid SID.
+    unsafe fn copy(sid: *mut c_void) -> Self {
+        let len = GetLengthSid(sid) as usize;
+        let mut copy = vec![0u32; (len + 3) / 4];
+        ptr::copy_nonoverlapping(sid.cast::<u8>(), copy.as_mut_ptr().cast::<u8>(), len);
+        Self(copy)
+    }
+
+    fn as_ptr(&self) -> *mut c_void {
+        self.0.as_ptr() as *mut c_void
+    }
+
+    /// The name of the account, or the SID itself if it has none, like the
+    /// SIDs of deleted accounts.
+    pub fn display_name(&self) -> String {
+        let mut name_size = 0;
+        let mut domain_size = 0;
+        let mut sid_use = 0;
+        unsafe {
+            LookupAccountSidW(
+                ptr::null(),
+                self.as_ptr(),
+                ptr::null_mut(),
+                &mut name_size,
+                ptr::null_mut(),
+                &mut domain_size,
+                &mut sid_use,
+            )
+        };
+        let mut name = vec![0u16; name_size as usize];
+        let mut domain = vec![0u16; domain_size as usize];
+        let ok = unsafe {
+            LookupAccountSidW(
+                ptr::null(),
+                self.as_ptr(),
+                name.as_mut_ptr(),
+                &mut name_size,
+                domain.as_mut_ptr(),
+                &mut domain_size,
+                &mut sid_use,
+            )
+        };
+        if name_size != 0 && ok != 0 {
+            name.truncate(name_size as usize);
+            return OsString::from_wide(&name).to_string_lossy().into_owned();
+        }
+
+        let mut string = ptr::null_mut();
+        if unsafe { ConvertSidToStringSidW(self.as_ptr(), &mut string) } == 0 {
+            return String::new();
+        }
+        let len = (0..)
+            .take_while(|&i| unsafe { *string.add(i) } != 0)
+            .count();
+        let result = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(string, len) });
+        unsafe { LocalFree(string as _) };
+        result
+    }
+}
+
+impl PartialEq for Sid {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { EqualSid(self.as_ptr(), other.as_ptr()) != 0 }
+    }
+}
+
+/// The owner and the group of `path`, following symbolic links.
+pub fn owner_and_group(path: &Path) -> io::Result<(Sid, Sid)> {
+    let mut owner = ptr::null_mut();
+    let mut group = ptr::null_mut();
+    let mut descriptor = ptr::null_mut();
+    let error = unsafe {
+        GetNamedSecurityInfoW(
+            to_wide(path.as_os_str()).as_ptr(),
+            SE_FILE_OBJECT,
+            OWNER_SECURITY_INFORMATION | GROUP_SECURITY_INFORMATION,
+            &mut owner,
+            &mut group,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut descriptor,
+        )
+    };
+    if error != ERROR_SUCCESS {
+        return Err(io::Error::from_raw_os_error(error as i32));
+    }
+    // The owner and the group point into the descriptor.
+    let result = unsafe { (Sid::copy(owner), Sid::copy(group)) };
+    unsafe { LocalFree(descriptor as _) };
+    Ok(result)
+}
+
+/// Set the owner and the group of `path`, following symbolic links.
+pub fn set_owner_and_group(
+    path: &Path,
+    owner: Option<&Sid>,
+    group: Option<&Sid>,
+) -> io::Result<()> {
+    let mut information = 0;
+    if owner.is_some() {
+        information |= OWNER_SECURITY_INFORMATION;
+    }
+    if group.is_some() {
+        information |= GROUP_SECURITY_INFORMATION;
+    }
+    let mut name = to_wide(path.as_os_str());
+    let error = unsafe {
+        SetNamedSecurityInfoW(
+            name.as_mut_ptr(),
+            SE_FILE_OBJECT,
+            information,
+            owner.map_or(ptr::null_mut(), Sid::as_ptr),
+            group.map_or(ptr::null_mut(), Sid::as_ptr),
+            ptr::null(),
+            ptr::null(),
+        )
+    };
+    if error != ERROR_SUCCESS {
+        return Err(io::Error::from_raw_os_error(error as i32));
+    }
+    Ok(())
+}
+
+/// Enable the privileges that allow giving files to anyone rather than to
+/// oneself only. Administrators hold them, but they are disabled by
+/// default. Without them, the change fails with a permission error.
+fn enable_privileges() {
+    let mut token: HANDLE = 0;
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_ADJUST_PRIVILEGES, &mut token) } == 0 {
+        return;
+    }
+    for name in ["SeRestorePrivilege", "SeTakeOwnershipPrivilege"] {
+        // SAFETY: `TOKEN_PRIVILEGES` is a plain C struct, for which all
+        // zeroes is valid.
+        let mut privileges: TOKEN_PRIVILEGES = unsafe { std::mem::zeroed() };
+        privileges.PrivilegeCount = 1;
+        privileges.Privileges[0].Attributes = SE_PRIVILEGE_ENABLED;
+        let name = to_wide(OsStr::new(name));
+        let found = unsafe {
+            LookupPrivilegeValueW(
+                ptr::null(),
+                name.as_ptr(),
+                &mut privileges.Privileges[0].Luid,
+            )
+        };
+        if found != 0 {
+            unsafe {
+                AdjustTokenPrivileges(token, 0, &privileges, 0, ptr::null_mut(), ptr::null_mut())
+            };
+        }
+    }
+    unsafe { CloseHandle(token) };
+}
+
+/// Parse `spec`, of the form `[OWNER][:[GROUP]]`, into an owner and a
+/// group.
+fn parse_spec(spec: &str) -> UResult<(Option<Sid>, Option<Sid>)> {
+    let (user, group) = spec.split_once(':').unwrap_or((spec, ""));
+    let owner = if user.is_empty() {
+        None
+    } else {
+        Some(
+            Sid::lookup(user)
+                .map_err(|_| USimpleError::new(1, format!("invalid user: {}", spec.quote())))?,
+        )
+    };
+    let group = if group.is_empty() {
+        None
+    } else {
+        Some(
+            Sid::lookup(group)
+                .map_err(|_| USimpleError::new(1, format!("invalid group: {}", spec.quote())))?,
+        )
+    };
+    Ok((owner, group))
+}
+
+struct Chowner {
+    owner: Option<Sid>,
+    group: Option<Sid>,
+    from_owner: Option<Sid>,
+    from_group: Option<Sid>,
+    verbosity: VerbosityLevel,
+    recursive: bool,
+    preserve_root: bool,
+}
+
+/// Change the ownership of the files given in `matches`, as parsed by
+/// `uucore::perms::chown_matches`.
+pub fn chown(matches: &ArgMatches) -> UResult<()> {
+    let (owner, group) = match matches.get_one::<String>(options::REFERENCE) {
+        Some(file) => {
+            let (owner, group) = owner_and_group(Path::new(file))
+                .map_err_context(|| format!("failed to get attributes of {}", file.quote()))?;
+            (Some(owner), Some(group))
+        }
+        None => parse_spec(matches.get_one::<String>(options::ARG_OWNER).unwrap())?,
+    };
+    let (from_owner, from_group) = match matches.get_one::<String>(options::FROM) {
+        Some(spec) => parse_spec(spec)?,
+        None => (None, None),
+    };
+    let chowner = Chowner {
+        owner,
+        group,
+        from_owner,
+        from_group,
+        verbosity: verbosity_level(matches),
+        recursive: matches.get_flag(options::RECURSIVE),
+        preserve_root: matches.get_flag(options::preserve_root::PRESERVE),
+    };
+
+    enable_privileges();
+    let mut ret = 0;
+    for file in matches.get_many::<String>(options::ARG_FILES).unwrap() {
+        ret |= chowner.traverse(Path::new(file));
+    }
+    if ret != 0 {
+        return Err(ret.into());
+    }
+    Ok(())
+}
+
+impl Chowner {
+    fn traverse(&self, path: &Path) -> i32 {
+        if self.recursive && self.preserve_root && is_root(path) {
+            show_error!("it is dangerous to operate recursively on {}", path.quote());
+            show_error!("use --no-preserve-root to override this failsafe");
+            return 1;
+        }
+        let mut ret = self.chown_file(path);
+        // Symbolic links and junctions to directories are not followed.
+        if self.recursive && fs::symlink_metadata(path).map_or(false, |m| m.is_dir()) {
+            match fs::read_dir(path) {
+                Ok(entries) => {
+                    for entry in entries {
+                        match entry {
+                            Ok(entry) => ret |= self.traverse(&entry.path()),
+                            Err(e) => {
+                                if self.verbosity != VerbosityLevel::Silent {
+                                    show_error!("{}", e);
+                                }
+                                ret = 1;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    if self.verbosity != VerbosityLevel::Silent {
+                        show_error!("cannot read directory {}: {}", path.quote(), e);
+                    }
+                    ret = 1;
+                }
+            }
+        }
+        ret
+    }
+
+    fn chown_file(&self, path: &Path) -> i32 {
+        let (owner, group) = match owner_and_group(path) {
+            Ok(owner_and_group) => owner_and_group,
+            Err(e) => {
+                if self.verbosity != VerbosityLevel::Silent {
+                    show_error!("cannot access {}: {}", path.quote(), e);
+                }
+                return 1;
+            }
+        };
+        if self
+            .from_owner
+            .as_ref()
+            .map_or(false, |from| *from != owner)
+            || self
+                .from_group
+                .as_ref()
+                .map_or(false, |from| *from != group)
+        {
+            return 0;
+        }
+
+        let new_owner = self.owner.as_ref().unwrap_or(&owner);
+        let new_group = self.group.as_ref().unwrap_or(&group);
+        let from = format!("{}:{}", owner.display_name(), group.display_name());
+        let to = format!("{}:{}", new_owner.display_name(), new_group.display_name());
+        if let Err(e) = set_owner_and_group(path, self.owner.as_ref(), self.group.as_ref()) {
+            match self.verbosity {
+                VerbosityLevel::Silent => {}
+                VerbosityLevel::Verbose => show_error!(
+                    "changing ownership of {}: {}\nfailed to change ownership of {} from {} to {}",
+                    path.quote(),
+                    e,
+                    path.quote(),
+                    from,
+                    to
+                ),
+                _ => show_error!("changing ownership of {}: {}", path.quote(), e),
+            }
+            return 1;
+        }
+        if *new_owner != owner || *new_group != group {
+            if matches!(
+                self.verbosity,
+                VerbosityLevel::Changes | VerbosityLevel::Verbose
+            ) {
+                println!(
+                    "changed ownership of {} from {} to {}",
+                    path.quote(),
+                    from,
+                    to
+                );
+            }
+        } else if self.verbosity == VerbosityLevel::Verbose {
+            println!("ownership of {} retained as {}", path.quote(), to);
+        }
+        0
+    }
+}
+
+/// Whether `path` is the root of a drive or a share.
+fn is_root(path: &Path) -> bool {
+    fs::canonicalize(path).map_or(false, |path| path.parent().is_none())
+}
+
+/// `s` as a NUL terminated wide string, as taken by the Windows API.
+fn to_wide(s: &OsStr) -> Vec<u16> {
+    s.encode_wide().chain(Some(0)).collect()
+}
@@ -19,7 +19,7 @@ use std::process;
 use uucore::error::{set_exit_code, UClapError, UResult, UUsageError};
 use uucore::fs::{canonicalize, MissingHandling, ResolveMode};
 use uucore::libc::{self, chroot, setgid, setgroups, setuid};
-use uucore::{entries, format_usage};
+use uucore::{entries, exec, format_usage};
 
 static ABOUT: &str = "Run COMMAND with root directory set to NEWROOT.";
 static USAGE: &str = "{} [OPTION]... NEWROOT [COMMAND [ARG]...]";
@@ -92,20 +92,10 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // NOTE: Tests can only trigger code beyond this point if they're invoked with root permissions
     set_context(newroot, &matches)?;
 
-    let pstatus = match process::Command::new(chroot_command)
+    let pstatus = process::Command::new(chroot_command)
         .args(chroot_args)
         .status()
-    {
-        Ok(status) => status,
-        Err(e) => {
-            return Err(if e.kind() == std::io::ErrorKind::NotFound {
-                ChrootError::CommandNotFound(command[0].to_string(), e)
-            } else {
-                ChrootError::CommandFailed(command[0].to_string(), e)
-            }
-            .into())
-        }
-    };
+        .map_err(|e| exec::run_error(chroot_command, e))?;
 
     let code = if pstatus.success() {
         0
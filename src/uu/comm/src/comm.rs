@@ -9,11 +9,12 @@
 
 use std::cmp::Ordering;
 use std::fs::File;
-use std::io::{self, stdin, BufRead, BufReader, Stdin};
+use std::io::{self, stdin, BufRead, BufReader, Write};
 use std::path::Path;
 use uucore::error::FromIo;
 use uucore::error::UResult;
 use uucore::format_usage;
+use uucore::lines::{self, records, LineEnding, Record, Records};
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 
@@ -30,6 +31,7 @@ mod options {
     pub const FILE_1: &str = "FILE1";
     pub const FILE_2: &str = "FILE2";
     pub const TOTAL: &str = "total";
+    pub const ZERO_TERMINATED: &str = "zero-terminated";
 }
 
 fn mkdelim(col: usize, opts: &ArgMatches) -> String {
@@ -49,97 +51,92 @@ fn mkdelim(col: usize, opts: &ArgMatches) -> String {
     s
 }
 
-fn ensure_nl(line: &mut String) {
-    if !line.ends_with('\n') {
-        line.push('\n');
-    }
+/// The lines of one of the files being compared, with the name to report
+/// errors with.
+struct Input<'a> {
+    name: &'a str,
+    lines: Records<Box<dyn BufRead>>,
 }
 
-enum LineReader {
-    Stdin(Stdin),
-    FileIn(BufReader<File>),
-}
+impl<'a> Input<'a> {
+    fn open(name: &'a str, line_ending: LineEnding, crlf: bool) -> UResult<Self> {
+        let reader: Box<dyn BufRead> = match name {
+            "-" => Box::new(BufReader::new(stdin())),
+            _ => {
+                let f = File::open(Path::new(name)).map_err_context(|| name.to_string())?;
+                Box::new(BufReader::new(f))
+            }
+        };
+        Ok(Self {
+            name,
+            lines: records(reader, line_ending, crlf),
+        })
+    }
 
-impl LineReader {
-    fn read_line(&mut self, buf: &mut String) -> io::Result<usize> {
-        match *self {
-            Self::Stdin(ref mut r) => r.read_line(buf),
-            Self::FileIn(ref mut r) => r.read_line(buf),
-        }
+    fn next_line(&mut self) -> UResult<Option<Record>> {
+        let name = self.name;
+        self.lines
+            .next()
+            .transpose()
+            .map_err_context(|| name.to_string())
     }
 }
 
-fn comm(a: &mut LineReader, b: &mut LineReader, opts: &ArgMatches) {
+fn comm(a: &mut Input, b: &mut Input, line_ending: LineEnding, opts: &ArgMatches) -> UResult<()> {
     let delim: Vec<String> = (0..4).map(|col| mkdelim(col, opts)).collect();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
 
-    let ra = &mut String::new();
-    let mut na = a.read_line(ra);
-    let rb = &mut String::new();
-    let mut nb = b.read_line(rb);
+    let mut ra = a.next_line()?;
+    let mut rb = b.next_line()?;
 
     let mut total_col_1 = 0;
     let mut total_col_2 = 0;
     let mut total_col_3 = 0;
 
-    while na.is_ok() || nb.is_ok() {
-        let ord = match (na.is_ok(), nb.is_ok()) {
-            (false, true) => Ordering::Greater,
-            (true, false) => Ordering::Less,
-            (true, true) => match (&na, &nb) {
-                (&Ok(0), &Ok(0)) => break,
-                (&Ok(0), _) => Ordering::Greater,
-                (_, &Ok(0)) => Ordering::Less,
-                _ => ra.cmp(&rb),
+    loop {
+        let (col, line) = match (&ra, &rb) {
+            (None, None) => break,
+            (Some(la), None) => (1, la),
+            (None, Some(lb)) => (2, lb),
+            (Some(la), Some(lb)) => match la.key().cmp(lb.key()) {
+                Ordering::Less => (1, la),
+                Ordering::Greater => (2, lb),
+                Ordering::Equal => (3, la),
             },
-            _ => unreachable!(),
         };
 
-        match ord {
-            Ordering::Less => {
-                if !opts.get_flag(options::COLUMN_1) {
-                    ensure_nl(ra);
-                    print!("{}{}", delim[1], ra);
-                }
-                ra.clear();
-                na = a.read_line(ra);
-                total_col_1 += 1;
-            }
-            Ordering::Greater => {
-                if !opts.get_flag(options::COLUMN_2) {
-                    ensure_nl(rb);
-                    print!("{}{}", delim[2], rb);
-                }
-                rb.clear();
-                nb = b.read_line(rb);
-                total_col_2 += 1;
-            }
-            Ordering::Equal => {
-                if !opts.get_flag(options::COLUMN_3) {
-                    ensure_nl(ra);
-                    print!("{}{}", delim[3], ra);
-                }
-                ra.clear();
-                rb.clear();
-                na = a.read_line(ra);
-                nb = b.read_line(rb);
-                total_col_3 += 1;
-            }
+        let suppressed = match col {
+            1 => options::COLUMN_1,
+            2 => options::COLUMN_2,
+            _ => options::COLUMN_3,
+        };
+        if !opts.get_flag(suppressed) {
+            stdout.write_all(delim[col].as_bytes())?;
+            stdout.write_all(line.line())?;
+            write!(stdout, "{line_ending}")?;
         }
-    }
 
-    if opts.get_flag(options::TOTAL) {
-        println!("{total_col_1}\t{total_col_2}\t{total_col_3}\ttotal");
+        if col != 2 {
+            ra = a.next_line()?;
+        }
+        if col != 1 {
+            rb = b.next_line()?;
+        }
+        match col {
+            1 => total_col_1 += 1,
+            2 => total_col_2 += 1,
+            _ => total_col_3 += 1,
+        }
     }
-}
 
-fn open_file(name: &str) -> io::Result<LineReader> {
-    match name {
-        "-" => Ok(LineReader::Stdin(stdin())),
-        _ => {
-            let f = File::open(Path::new(name))?;
-            Ok(LineReader::FileIn(BufReader::new(f)))
-        }
+    if opts.get_flag(options::TOTAL) {
+        write!(
+            stdout,
+            "{total_col_1}\t{total_col_2}\t{total_col_3}\ttotal{line_ending}"
+        )?;
     }
+    Ok(())
 }
 
 #[uucore::main]
@@ -149,11 +146,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
     let filename1 = matches.get_one::<String>(options::FILE_1).unwrap();
     let filename2 = matches.get_one::<String>(options::FILE_2).unwrap();
-    let mut f1 = open_file(filename1).map_err_context(|| filename1.to_string())?;
-    let mut f2 = open_file(filename2).map_err_context(|| filename2.to_string())?;
+    let line_ending = LineEnding::from_zero_flag(matches.get_flag(options::ZERO_TERMINATED));
+    let crlf = matches.get_flag(lines::arguments::OPT_CRLF);
+    let mut f1 = Input::open(filename1, line_ending, crlf)?;
+    let mut f2 = Input::open(filename2, line_ending, crlf)?;
 
-    comm(&mut f1, &mut f2, &matches);
-    Ok(())
+    comm(&mut f1, &mut f2, line_ending, &matches)
 }
 
 pub fn uu_app() -> Command {
@@ -181,6 +179,7 @@ pub fn uu_app() -> Command {
                 .help("suppress column 3 (lines that appear in both files)")
                 .action(ArgAction::SetTrue),
         )
+        .arg(lines::arguments::crlf())
         .arg(
             Arg::new(options::DELIMITER)
                 .long(options::DELIMITER)
@@ -189,6 +188,13 @@ pub fn uu_app() -> Command {
                 .default_value(options::DELIMITER_DEFAULT)
                 .hide_default_value(true),
         )
+        .arg(
+            Arg::new(options::ZERO_TERMINATED)
+                .long(options::ZERO_TERMINATED)
+                .short('z')
+                .help("line delimiter is NUL, not newline")
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::FILE_1)
                 .required(true)
@@ -0,0 +1,38 @@
+# The messages of cp, in English, which are the reference for the catalogs
+# in other languages. See uucore::i18n for the format.
+
+error-not-implemented = Option '{ $option }' not yet implemented.
+error-try-help = Try '{ $command } --help' for more information.
+error-not-a-directory = '{ $path }' is not a directory
+error-target-not-a-directory = target: { $path } is not a directory
+error-invalid-attribute = invalid attribute { $value }
+error-invalid-bwlimit = invalid --bwlimit argument { $value }
+error-invalid-reflink = invalid argument { $value } for 'reflink'
+error-invalid-sparse = invalid argument { $value } for 'sparse'
+error-cannot-stat = cannot stat { $path }
+error-no-such-file = cannot stat { $path }: No such file or directory
+error-cannot-create-hard-link = cannot create hard link { $path }
+error-directory-with-non-directory = cannot overwrite directory { $path } with non-directory
+error-non-directory-with-directory = cannot overwrite non-directory with directory
+error-get-context = failed to get security context of { $path }: { $error }
+error-set-context = failed to set security context for { $path }: { $error }
+error-same-file = { $source } and { $dest } are the same file
+error-backup-destroys-source = backing up { $dest } might destroy source;  { $source } not copied
+error-through-new-symlink = will not copy '{ $source }' through just-created symlink '{ $dest }'
+error-dangling-symlink = not writing through dangling symlink '{ $dest }'
+error-fifo-exists = cannot create fifo { $path }: File exists
+error-cannot-create-special-file = cannot create special file { $path }
+error-free-space = cannot read the free space of { $path }: { $error }
+error-not-enough-space = not enough space in { $path }: { $needed } needed, { $available } available
+error-cannot-open = cannot open { $path } for reading
+error-omitting-directory = omitting directory { $path }
+error-directory-into-itself = cannot copy a directory, { $source }, into itself, { $dest }
+error-cyclic-symlink = cannot copy cyclic symbolic link { $path }
+warning-source-twice = source { $path } specified more than once
+warning-socket = cannot copy socket { $path }, skipping
+prompt-overwrite = overwrite { $path }?
+error-backup-no-clobber = options --backup and --no-clobber are mutually exclusive
+error-missing-operand = missing file operand
+error-extra-operand = extra operand { $operand }
+error-parents-not-directory = with --parents, the destination must be a directory
+error-current-directory = failed to get current directory { $error }
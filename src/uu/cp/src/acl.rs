@@ -0,0 +1,77 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+// spell-checker:ignore exacl getfacl setfacl ENOTSUP EOPNOTSUPP
+//! Copy the access control lists of a file, as part of `--preserve=mode`.
+//!
+//! This relies on `exacl`, which handles POSIX.1e ACLs on Linux and
+//! FreeBSD, NFSv4 ACLs on FreeBSD (e.g. on ZFS) and extended ACLs on
+//! macOS. Which kind a file has is decided by its file system.
+use std::io;
+use std::path::Path;
+
+use exacl::AclEntry;
+
+/// Copy the ACL of `source` to `dest`.
+///
+/// A file system without ACL support is only an error if the ACL of
+/// `source` grants more than its mode bits, which are copied separately.
+pub(crate) fn copy_acl(source: &Path, dest: &Path) -> io::Result<()> {
+    let acl = match exacl::getfacl(source, None) {
+        Ok(acl) => acl,
+        Err(e) if is_unsupported(&e) => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    if let Err(e) = exacl::setfacl(&[dest], &acl, None) {
+        if !(is_unsupported(&e) && is_trivial(&acl)) {
+            return Err(e);
+        }
+    }
+
+    // POSIX.1e directories also have a default ACL, which is inherited by
+    // the entries created in them. NFSv4 and macOS ACLs express inheritance
+    // with flags on the entries instead, so they have been copied above.
+    #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+    if source.is_dir() {
+        use exacl::AclOption;
+
+        match exacl::getfacl(source, AclOption::DEFAULT_ACL) {
+            // Even an empty default ACL is set, since `dest` may have
+            // inherited one from its parent.
+            Ok(default) => {
+                if let Err(e) = exacl::setfacl(&[dest], &default, AclOption::DEFAULT_ACL) {
+                    if !(is_unsupported(&e) && default.is_empty()) {
+                        return Err(e);
+                    }
+                }
+            }
+            // FreeBSD rejects the request for a file with an NFSv4 ACL.
+            Err(e) if is_unsupported(&e) || e.raw_os_error() == Some(libc::EINVAL) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether `error` means that the file system doesn't support ACLs.
+fn is_unsupported(error: &io::Error) -> bool {
+    // `ENOTSUP` and `EOPNOTSUPP` are the same on some platforms only.
+    error.raw_os_error().map_or(false, |code| {
+        code == libc::ENOTSUP || code == libc::EOPNOTSUPP || code == libc::ENOSYS
+    })
+}
+
+/// Whether `acl` is fully described by the mode bits, that is, it only has
+/// entries for the owner, the group and others.
+fn is_trivial(acl: &[AclEntry]) -> bool {
+    acl.iter().all(|entry| {
+        // A mask is only needed once there are entries for other users.
+        #[cfg(any(target_os = "linux", target_os = "freebsd"))]
+        if entry.kind == exacl::AclEntryKind::Mask {
+            return false;
+        }
+        entry.name.is_empty()
+    })
+}
@@ -0,0 +1,246 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+// spell-checker:ignore bwlimit
+//! Throttle the data copy loop to a fixed number of bytes per second.
+//!
+//! See the [`copy`] function for more information.
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use uucore::fs::create_dest;
+
+use crate::progress::{Progress, ProgressWriter};
+
+/// Where a [`TokenBucket`] gets the time from, and how it waits.
+pub(crate) trait Clock {
+    fn now(&self) -> Instant;
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// The clock of the system, which the copies are throttled with.
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A token bucket refilled at a constant rate of `rate` bytes per second.
+///
+/// The bucket holds at most one second worth of tokens, so after an idle
+/// period (for example, while waiting on a slow source) the copy can
+/// burst for at most one second before being throttled again.
+pub(crate) struct TokenBucket<C = SystemClock> {
+    rate: u64,
+    tokens: f64,
+    last_refill: Instant,
+    clock: C,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(rate: u64) -> Self {
+        Self::with_clock(rate, SystemClock)
+    }
+}
+
+impl<C: Clock> TokenBucket<C> {
+    pub(crate) fn with_clock(rate: u64, clock: C) -> Self {
+        Self {
+            rate,
+            tokens: rate as f64,
+            last_refill: clock.now(),
+            clock,
+        }
+    }
+
+    /// Record that `n` bytes have been transferred, sleeping if that
+    /// exceeds the number of bytes allowed so far.
+    ///
+    /// The bucket is allowed to go into debt so that a single transfer
+    /// larger than its capacity is paid back by a longer sleep.
+    pub(crate) fn consume(&mut self, n: u64) {
+        let now = self.clock.now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        let rate = self.rate as f64;
+        self.tokens = (self.tokens + elapsed * rate).min(rate) - n as f64;
+        self.last_refill = now;
+        if self.tokens < 0.0 {
+            self.clock
+                .sleep(Duration::from_secs_f64(-self.tokens / rate));
+        }
+    }
+}
+
+/// A writer that throttles writes to the inner writer with a [`TokenBucket`].
+pub(crate) struct RateLimitedWriter<W> {
+    inner: W,
+    bucket: TokenBucket,
+}
+
+impl<W> RateLimitedWriter<W> {
+    pub(crate) fn new(inner: W, rate: u64) -> Self {
+        Self {
+            inner,
+            bucket: TokenBucket::new(rate),
+        }
+    }
+}
+
+impl<W: Write> Write for RateLimitedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bucket.consume(n as u64);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Wrap `inner` so that the data written to it is throttled to `bwlimit`
+/// bytes per second, if given, and reported to `progress`, if given.
+pub(crate) fn writer<'a, W: Write + 'a>(
+    inner: W,
+    bwlimit: Option<u64>,
+    progress: Option<&'a Progress>,
+) -> Box<dyn Write + 'a> {
+    let inner: Box<dyn Write + 'a> = match bwlimit {
+        Some(rate) => Box::new(RateLimitedWriter::new(inner, rate)),
+        None => Box::new(inner),
+    };
+    match progress {
+        Some(progress) => Box::new(ProgressWriter::new(inner, progress)),
+        None => inner,
+    }
+}
+
+/// Copy the contents and permissions of `source` to `dest`, like
+/// [`std::fs::copy`], but opening `dest` with [`create_dest`].
+///
+/// If `bwlimit` is given, the data is written at no more than that many
+/// bytes per second. If `progress` is given, it is advanced as the data
+/// is written. Either bypasses any in-kernel copy acceleration, since
+/// every byte has to pass through the wrapping writer.
+pub(crate) fn copy(
+    source: &Path,
+    dest: &Path,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> io::Result<u64> {
+    // `CopyFileExW` also copies the alternate data streams and attributes.
+    #[cfg(windows)]
+    if bwlimit.is_none() && progress.is_none() {
+        return std::fs::copy(source, dest);
+    }
+    let mut src_file = File::open(source)?;
+    let permissions = src_file.metadata()?.permissions();
+    #[cfg(unix)]
+    let mode = std::os::unix::fs::PermissionsExt::mode(&permissions);
+    #[cfg(not(unix))]
+    let mode = 0o666;
+    let dst_file = create_dest(dest, mode, false)?;
+    let num_bytes_copied = if bwlimit.is_none() && progress.is_none() {
+        // Without a wrapping writer, this uses `copy_file_range` and the like.
+        io::copy(&mut src_file, &mut &dst_file)?
+    } else {
+        let mut writer = writer(&dst_file, bwlimit, progress);
+        let num_bytes_copied = io::copy(&mut src_file, &mut writer)?;
+        drop(writer);
+        num_bytes_copied
+    };
+    dst_file.set_permissions(permissions)?;
+    Ok(num_bytes_copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Clock, TokenBucket};
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    /// A clock that only moves when it sleeps or is advanced, and records
+    /// how long it slept.
+    #[derive(Clone)]
+    struct FakeClock {
+        now: Rc<Cell<Instant>>,
+        slept: Rc<Cell<Duration>>,
+    }
+
+    impl FakeClock {
+        fn new() -> Self {
+            Self {
+                now: Rc::new(Cell::new(Instant::now())),
+                slept: Rc::new(Cell::new(Duration::ZERO)),
+            }
+        }
+
+        fn advance(&self, duration: Duration) {
+            self.now.set(self.now.get() + duration);
+        }
+
+        /// The time slept since the last call.
+        fn take_slept(&self) -> Duration {
+            self.slept.replace(Duration::ZERO)
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+
+        fn sleep(&mut self, duration: Duration) {
+            self.advance(duration);
+            self.slept.set(self.slept.get() + duration);
+        }
+    }
+
+    #[test]
+    fn test_token_bucket_throttles() {
+        let clock = FakeClock::new();
+        let mut bucket = TokenBucket::with_clock(1000, clock.clone());
+
+        // The first second worth of data goes through at once.
+        bucket.consume(1000);
+        assert_eq!(clock.take_slept(), Duration::ZERO);
+
+        // Then the data waits for the tokens to be refilled.
+        bucket.consume(500);
+        assert_eq!(clock.take_slept(), Duration::from_millis(500));
+        bucket.consume(250);
+        assert_eq!(clock.take_slept(), Duration::from_millis(250));
+
+        // The time spent elsewhere counts.
+        clock.advance(Duration::from_millis(250));
+        bucket.consume(500);
+        assert_eq!(clock.take_slept(), Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_token_bucket_caps_bursts() {
+        let clock = FakeClock::new();
+        let mut bucket = TokenBucket::with_clock(1000, clock.clone());
+        bucket.consume(1000);
+
+        // An idle period only allows one second worth of data.
+        clock.advance(Duration::from_secs(10));
+        bucket.consume(1500);
+        assert_eq!(clock.take_slept(), Duration::from_millis(500));
+
+        // A transfer larger than the bucket is paid back by a longer sleep.
+        bucket.consume(3000);
+        assert_eq!(clock.take_slept(), Duration::from_secs(3));
+    }
+}
@@ -6,50 +6,31 @@
 //! Recursively copy the contents of a directory.
 //!
 //! See the [`copy_directory`] function for more information.
-#[cfg(windows)]
-use std::borrow::Cow;
 use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf, StripPrefixError};
 
-use indicatif::ProgressBar;
 use uucore::display::Quotable;
-use uucore::error::UIoError;
-use uucore::fs::{canonicalize, FileInformation, MissingHandling, ResolveMode};
+use uucore::error::{UError, UIoError};
+use uucore::fs::{
+    canonicalize, display_path, extended_length_path, FileInformation, MissingHandling, ResolveMode,
+};
+use uucore::fsext::file_system_id;
 use uucore::show;
 use uucore::show_error;
+use uucore::translate;
 use uucore::uio_error;
-use walkdir::{DirEntry, WalkDir};
 
+use crate::progress::Progress;
+use crate::summary::Summary;
+use crate::walk::{DirEntry, WalkDir};
 use crate::{
-    aligned_ancestors, context_for, copy_attributes, copy_file, copy_link, preserve_hardlinks,
-    CopyResult, Error, Options, TargetSlice,
+    aligned_ancestors, context_for, copy_attributes, copy_file, copy_link, copy_parents_attributes,
+    create_parents, preserve_hardlinks, CopyResult, Error, HardLinks, Options, TargetSlice,
 };
 
-/// Ensure a Windows path starts with a `\\?`.
-#[cfg(target_os = "windows")]
-fn adjust_canonicalization(p: &Path) -> Cow<Path> {
-    // In some cases, \\? can be missing on some Windows paths.  Add it at the
-    // beginning unless the path is prefixed with a device namespace.
-    const VERBATIM_PREFIX: &str = r#"\\?"#;
-    const DEVICE_NS_PREFIX: &str = r#"\\."#;
-
-    let has_prefix = p
-        .components()
-        .next()
-        .and_then(|comp| comp.as_os_str().to_str())
-        .map(|p_str| p_str.starts_with(VERBATIM_PREFIX) || p_str.starts_with(DEVICE_NS_PREFIX))
-        .unwrap_or_default();
-
-    if has_prefix {
-        p.into()
-    } else {
-        Path::new(VERBATIM_PREFIX).join(p).into()
-    }
-}
-
 /// Get a descendant path relative to the given parent directory.
 ///
 /// If `root_parent` is `None`, then this just returns the `path`
@@ -62,14 +43,6 @@ fn get_local_to_root_parent(
 ) -> Result<PathBuf, StripPrefixError> {
     match root_parent {
         Some(parent) => {
-            // On Windows, some paths are starting with \\?
-            // but not always, so, make sure that we are consistent for strip_prefix
-            // See https://docs.microsoft.com/en-us/windows/win32/fileio/naming-a-file for more info
-            #[cfg(windows)]
-            let (path, parent) = (
-                adjust_canonicalization(path),
-                adjust_canonicalization(parent),
-            );
             let path = path.strip_prefix(parent)?;
             Ok(path.to_path_buf())
         }
@@ -110,7 +83,7 @@ impl<'a> Context<'a> {
 ///
 /// For convenience while traversing a directory, the [`Entry::new`]
 /// function allows creating an entry from a [`Context`] and a
-/// [`walkdir::DirEntry`].
+/// [`DirEntry`] of the walk.
 ///
 /// # Examples
 ///
@@ -195,12 +168,13 @@ where
 
 /// Copy a single entry during a directory traversal.
 fn copy_direntry(
-    progress_bar: &Option<ProgressBar>,
+    progress_bar: &Option<Progress>,
+    summary: &Summary,
     entry: Entry,
     options: &Options,
     symlinked_files: &mut HashSet<FileInformation>,
     preserve_hard_links: bool,
-    hard_links: &mut Vec<(String, u64)>,
+    hard_links: &mut HardLinks,
 ) -> CopyResult<()> {
     let Entry {
         source_absolute,
@@ -212,7 +186,9 @@ fn copy_direntry(
     // If the source is a symbolic link and the options tell us not to
     // dereference the link, then copy the link object itself.
     if source_absolute.is_symlink() && !options.dereference {
-        return copy_link(&source_absolute, &local_to_target, symlinked_files);
+        copy_link(&source_absolute, &local_to_target, symlinked_files)?;
+        summary.copied(0);
+        return Ok(());
     }
 
     // If the source is a directory and the destination does not
@@ -222,7 +198,7 @@ fn copy_direntry(
         && !local_to_target.exists()
     {
         if target_is_file {
-            return Err("cannot overwrite non-directory with directory".into());
+            return Err(translate!("error-non-directory-with-directory").into());
         } else {
             // TODO Since the calling code is traversing from the root
             // of the directory structure, I don't think
@@ -245,6 +221,7 @@ fn copy_direntry(
             if !found_hard_link {
                 match copy_file(
                     progress_bar,
+                    summary,
                     &source_absolute,
                     local_to_target.as_path(),
                     options,
@@ -273,6 +250,7 @@ fn copy_direntry(
             // cause us to continue walking the directory?
             match copy_file(
                 progress_bar,
+                summary,
                 &source_absolute,
                 local_to_target.as_path(),
                 options,
@@ -283,11 +261,16 @@ fn copy_direntry(
                 Err(Error::IoErrContext(e, _))
                     if e.kind() == std::io::ErrorKind::PermissionDenied =>
                 {
-                    show!(uio_error!(
+                    let error = uio_error!(
                         e,
-                        "cannot open {} for reading",
-                        source_relative.quote(),
-                    ));
+                        "{}",
+                        translate!(
+                            "error-cannot-open",
+                            path = display_path(&source_relative).quote()
+                        ),
+                    );
+                    summary.failed(&error);
+                    show!(error);
                 }
                 Err(e) => return Err(e),
             }
@@ -304,22 +287,26 @@ fn copy_direntry(
 ///
 /// Any errors encountered copying files in the tree will be logged but
 /// will not cause a short-circuit.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn copy_directory(
-    progress_bar: &Option<ProgressBar>,
+    progress_bar: &Option<Progress>,
+    summary: &Summary,
     root: &Path,
     target: &TargetSlice,
     options: &Options,
     symlinked_files: &mut HashSet<FileInformation>,
+    hard_links: &mut HardLinks,
     source_in_command_line: bool,
 ) -> CopyResult<()> {
     if !options.recursive {
-        return Err(format!("omitting directory {}", root.quote()).into());
+        return Err(translate!("error-omitting-directory", path = root.quote()).into());
     }
 
     // if no-dereference is enabled and this is a symlink, copy it as a file
     if !options.dereference(source_in_command_line) && root.is_symlink() {
         return copy_file(
             progress_bar,
+            summary,
             root,
             target,
             options,
@@ -330,10 +317,10 @@ pub(crate) fn copy_directory(
 
     // check if root is a prefix of target
     if path_has_prefix(target, root)? {
-        return Err(format!(
-            "cannot copy a directory, {}, into itself, {}",
-            root.quote(),
-            target.join(root.file_name().unwrap()).quote()
+        return Err(translate!(
+            "error-directory-into-itself",
+            source = root.quote(),
+            dest = target.join(root.file_name().unwrap()).quote()
         )
         .into());
     }
@@ -346,10 +333,11 @@ pub(crate) fn copy_directory(
     // a -> d/a
     // a/b -> d/a/b
     //
+    let mut parents = Vec::new();
     let tmp = if options.parents {
         if let Some(parent) = root.parent() {
             let new_target = target.join(parent);
-            std::fs::create_dir_all(&new_target)?;
+            parents = create_parents(root, &target.join(root), options)?;
 
             if options.verbose {
                 // For example, if copying file `a/b/c` and its parents
@@ -372,7 +360,12 @@ pub(crate) fn copy_directory(
     };
     let target = tmp.as_path();
 
-    let mut hard_links: Vec<(String, u64)> = vec![];
+    // Deep trees easily go beyond `MAX_PATH` on Windows, so the whole
+    // traversal is done with extended-length paths. This also keeps the
+    // paths consistent for `get_local_to_root_parent`.
+    let (root, target) = (extended_length_path(root), extended_length_path(target));
+    let (root, target) = (root.as_ref(), target.as_ref());
+
     let preserve_hard_links = options.preserve_hard_links();
 
     // Collect some paths here that are invariant during the traversal
@@ -380,33 +373,133 @@ pub(crate) fn copy_directory(
     // the target directory.
     let context = match Context::new(root, target) {
         Ok(c) => c,
-        Err(e) => return Err(format!("failed to get current directory {e}").into()),
+        Err(e) => return Err(translate!("error-current-directory", error = e).into()),
+    };
+
+    // When symbolic links are followed, the copy may be reached again
+    // through a link pointing above the source, or into the copy itself.
+    // It is not descended into, or it would feed on itself. The copy is
+    // recognized by the device and inode of its top directory, and by its
+    // canonical path, so that the traversal takes the same memory however
+    // large the tree is.
+    let mut copy_root: Option<(FileInformation, PathBuf)> = None;
+
+    // The directories whose attributes are still to be copied, with their
+    // depth in the tree. Writing into a directory changes its modification
+    // time, and a read-only one can't be written into, so its attributes
+    // are copied once all its contents are, which is when the traversal
+    // gets back to its depth.
+    let mut pending_dirs: Vec<(PathBuf, PathBuf, usize)> = Vec::new();
+
+    // With `--one-file-system`, like in GNU cp, a directory on another file
+    // system than the root, which is a mount point, is still copied, but
+    // without its contents.
+    let root_file_system = if options.one_file_system {
+        Some(file_system_id(root, true)?)
+    } else {
+        None
     };
 
     // Traverse the contents of the directory, copying each one.
-    for direntry_result in WalkDir::new(root)
-        .same_file_system(options.one_file_system)
-        .follow_links(options.dereference)
-    {
+    let mut walker = WalkDir::new(root, options.dereference);
+    while let Some(direntry_result) = walker.next() {
         match direntry_result {
             Ok(direntry) => {
+                finish_directories(&mut pending_dirs, direntry.depth(), options, summary);
                 let entry = Entry::new(&context, &direntry)?;
+                let is_dir = options.dereference && direntry.file_type().is_dir();
+                if let (true, Some(copy_root)) = (is_dir, &copy_root) {
+                    if is_in_copy(&direntry, copy_root) {
+                        let error = Error::Error(translate!(
+                            "error-directory-into-itself",
+                            source = display_path(direntry.path()).quote(),
+                            dest = display_path(&entry.local_to_target).quote()
+                        ));
+                        summary.failed(&error);
+                        show!(error);
+                        walker.skip_current_dir();
+                        continue;
+                    }
+                }
+                let source = entry.source_absolute.clone();
+                let dest = entry.local_to_target.clone();
                 copy_direntry(
                     progress_bar,
+                    summary,
                     entry,
                     options,
                     symlinked_files,
                     preserve_hard_links,
-                    &mut hard_links,
+                    hard_links,
                 )?;
+                if is_dir && copy_root.is_none() {
+                    copy_root = FileInformation::from_path(&dest, true).ok().zip(
+                        canonicalize(&dest, MissingHandling::Normal, ResolveMode::Physical).ok(),
+                    );
+                }
+                if direntry.file_type().is_dir() {
+                    if root_file_system.is_some()
+                        && direntry.depth() > 0
+                        && file_system_id(direntry.path(), true).ok() != root_file_system
+                    {
+                        walker.skip_current_dir();
+                    }
+                    pending_dirs.push((source, dest, direntry.depth()));
+                }
+            }
+            // Like GNU cp, refuse to follow a symbolic link to one of the
+            // directories being copied, as it would never end.
+            Err(e) if e.is_loop() => {
+                let error = Error::Error(translate!(
+                    "error-cyclic-symlink",
+                    path = display_path(e.path()).quote()
+                ));
+                summary.failed(&error);
+                show!(error);
             }
             // Print an error message, but continue traversing the directory.
-            Err(e) => show_error!("{}", e),
+            Err(e) => {
+                summary.failed(&e);
+                show_error!("{}", e);
+            }
         }
     }
-    // Copy the attributes from the root directory to the target directory.
-    copy_attributes(root, target, &options.attributes)?;
-    Ok(())
+    finish_directories(&mut pending_dirs, 0, options, summary);
+    copy_parents_attributes(&parents, options)
+}
+
+/// Copy the attributes of the directories in `pending_dirs` that are at
+/// `depth` or deeper, the deepest first, as all their contents are copied.
+fn finish_directories(
+    pending_dirs: &mut Vec<(PathBuf, PathBuf, usize)>,
+    depth: usize,
+    options: &Options,
+    summary: &Summary,
+) {
+    while let Some((source, dest, _)) = pending_dirs.last().filter(|dir| dir.2 >= depth) {
+        if let Err(error) = copy_attributes(source, dest, &options.attributes) {
+            summary.failed(&error);
+            show!(error);
+        }
+        pending_dirs.pop();
+    }
+}
+
+/// Whether the directory `direntry` is the copy whose top directory has the
+/// information and canonical path in `copy_root`, or is inside it.
+///
+/// A directory inside the copy is reached either through its top
+/// directory, or through a symbolic link pointing into it.
+fn is_in_copy(direntry: &DirEntry, copy_root: &(FileInformation, PathBuf)) -> bool {
+    let (info, path) = copy_root;
+    FileInformation::from_path(direntry.path(), true).map_or(false, |i| i == *info)
+        || direntry.path_is_symlink()
+            && canonicalize(
+                direntry.path(),
+                MissingHandling::Normal,
+                ResolveMode::Physical,
+            )
+            .map_or(false, |p| p.starts_with(path))
 }
 
 /// Decide whether the second path is a prefix of the first.
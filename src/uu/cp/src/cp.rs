@@ -9,43 +9,68 @@
 // For the full copyright and license information, please view the LICENSE file
 // that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) copydir ficlone fiemap ftruncate linkgs lstat nlink nlinks pathbuf pwrite reflink strs xattrs symlinked deduplicated advcpmv
+// spell-checker:ignore (ToDO) bwlimit copydir ficlone fiemap ftruncate linkgs lstat nlink nlinks pathbuf pwrite reflink strs xattrs symlinked deduplicated advcpmv
 
 use quick_error::quick_error;
 use std::borrow::Cow;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::env;
 #[cfg(not(windows))]
 use std::ffi::CString;
-use std::fs::{self, File, OpenOptions};
+use std::fs::{self, OpenOptions};
 use std::io;
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
 #[cfg(unix)]
 use std::os::unix::fs::{FileTypeExt, PermissionsExt};
-use std::path::{Path, PathBuf, StripPrefixError};
+use std::path::{Component, Path, PathBuf, StripPrefixError};
 use std::string::ToString;
+use std::sync::Arc;
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use filetime::FileTime;
-use indicatif::{ProgressBar, ProgressStyle};
 #[cfg(unix)]
 use libc::mkfifo;
 use quick_error::ResultExt;
 
 use platform::copy_on_write;
-use uucore::backup_control::{self, BackupMode};
+use uucore::backup_control;
+#[cfg(unix)]
+use uucore::capabilities::{self, Capability};
 use uucore::display::Quotable;
 use uucore::error::{set_exit_code, UClapError, UError, UResult, UUsageError};
 use uucore::fs::{
-    canonicalize, paths_refer_to_same_file, FileInformation, MissingHandling, ResolveMode,
+    canonicalize, create_dest, display_path, long_path, paths_refer_to_same_file,
+    source_date_epoch, FileInformation, MissingHandling, ResolveMode,
 };
-use uucore::{crash, format_usage, prompt_yes, show_error, show_warning};
+#[cfg(any(unix, windows))]
+use uucore::fsext::{statfs, FsMeta};
+#[cfg(unix)]
+use uucore::fsxattr::{self, XattrPolicy};
+use uucore::human_size::{human_readable, HumanReadable};
+use uucore::parse_size::parse_size;
+use uucore::report;
+use uucore::update_control::{self, UpdateAction};
+use uucore::version::VersionInfo;
+use uucore::{crash, format_usage, prompt_yes, show_error, show_warning, translate};
 
 use crate::copydir::copy_directory;
-
+pub use crate::progress::Observer;
+use crate::progress::Progress;
+use crate::summary::Summary;
+pub use uucore::backup_control::BackupMode;
+pub use uucore::update_control::UpdateMode;
+
+#[cfg(feature = "feat_acl")]
+mod acl;
+mod bwlimit;
 mod copydir;
+mod crtime;
 mod platform;
+mod progress;
+mod status;
+mod summary;
+mod walk;
 quick_error! {
     #[derive(Debug)]
     pub enum Error {
@@ -71,8 +96,6 @@ quick_error! {
         /// and not all files were copied.
         NotAllFilesCopied {}
 
-        /// Simple walkdir::Error wrapper
-        WalkDirErr(err: walkdir::Error) { from() display("{}", err) source(err) }
 
         /// Simple std::path::StripPrefixError wrapper
         StripPrefixError(err: StripPrefixError) { from() }
@@ -86,12 +109,12 @@ quick_error! {
         /// All standard options are included as an an implementation
         /// path, but those that are not implemented yet should return
         /// a NotImplemented error.
-        NotImplemented(opt: String) { display("Option '{}' not yet implemented.", opt) }
+        NotImplemented(opt: String) { display("{}", translate!("error-not-implemented", option = opt)) }
 
         /// Invalid arguments to backup
-        Backup(description: String) { display("{}\nTry '{} --help' for more information.", description, uucore::execution_phrase()) }
+        Backup(description: String) { display("{}\n{}", description, translate!("error-try-help", command = uucore::execution_phrase())) }
 
-        NotADirectory(path: String) { display("'{}' is not a directory", path) }
+        NotADirectory(path: String) { display("{}", translate!("error-not-a-directory", path = path)) }
     }
 }
 
@@ -134,6 +157,21 @@ pub enum ReflinkMode {
     Never,
 }
 
+impl Default for ReflinkMode {
+    /// Clone files where it is cheap to find out whether that is possible.
+    fn default() -> Self {
+        if cfg!(any(
+            target_os = "linux",
+            target_os = "android",
+            target_os = "macos"
+        )) {
+            Self::Auto
+        } else {
+            Self::Never
+        }
+    }
+}
+
 /// Possible arguments for `--sparse`.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum SparseMode {
@@ -152,7 +190,6 @@ pub enum CopyMode {
     Link,
     SymLink,
     Copy,
-    Update,
     AttrOnly,
 }
 
@@ -161,7 +198,10 @@ pub struct Attributes {
     #[cfg(unix)]
     ownership: Preserve,
     mode: Preserve,
+    /// Access control lists, which are part of the mode for `--preserve`.
+    acl: Preserve,
     timestamps: Preserve,
+    crtimes: Preserve,
     context: Preserve,
     links: Preserve,
     xattr: Preserve,
@@ -174,7 +214,9 @@ impl Attributes {
             self.ownership = self.ownership.max(other.ownership);
         }
         self.mode = self.mode.max(other.mode);
+        self.acl = self.acl.max(other.acl);
         self.timestamps = self.timestamps.max(other.timestamps);
+        self.crtimes = self.crtimes.max(other.crtimes);
         self.context = self.context.max(other.context);
         self.links = self.links.max(other.links);
         self.xattr = self.xattr.max(other.xattr);
@@ -204,14 +246,19 @@ impl Preserve {
 }
 
 /// Re-usable, extensible copy options
+///
+/// Programs using [`copy`] as a library build them with
+/// [`Options::builder`].
 #[allow(dead_code)]
 pub struct Options {
     attributes_only: bool,
     backup: BackupMode,
+    bwlimit: Option<u64>,
     copy_contents: bool,
     cli_dereference: bool,
     copy_mode: CopyMode,
     dereference: bool,
+    direct: bool,
     no_target_dir: bool,
     one_file_system: bool,
     overwrite: OverwriteMode,
@@ -220,12 +267,21 @@ pub struct Options {
     strip_trailing_slashes: bool,
     reflink_mode: ReflinkMode,
     attributes: Attributes,
+    /// Whether `--no-preserve` covers the mode, in which case new files
+    /// don't take the mode of their source either.
+    explicit_no_preserve_mode: bool,
     recursive: bool,
     backup_suffix: String,
     target_dir: Option<String>,
-    update: bool,
+    update: UpdateMode,
     verbose: bool,
     progress_bar: bool,
+    summary: bool,
+    /// Whether to check for free space in the target before copying.
+    check_space: bool,
+    /// Whether to print the status of the run on `SIGUSR1` and `SIGINFO`.
+    report_status: bool,
+    observer: Option<Arc<dyn Observer>>,
 }
 
 static ABOUT: &str = "Copy SOURCE to DEST, or multiple SOURCE(s) to DIRECTORY.";
@@ -240,10 +296,13 @@ const USAGE: &str = "\
 mod options {
     pub const ARCHIVE: &str = "archive";
     pub const ATTRIBUTES_ONLY: &str = "attributes-only";
+    pub const BWLIMIT: &str = "bwlimit";
+    pub const CHECK_SPACE: &str = "check-space";
     pub const CLI_SYMBOLIC_LINKS: &str = "cli-symbolic-links";
     pub const CONTEXT: &str = "context";
     pub const COPY_CONTENTS: &str = "copy-contents";
     pub const DEREFERENCE: &str = "dereference";
+    pub const DIRECT: &str = "direct";
     pub const FORCE: &str = "force";
     pub const INTERACTIVE: &str = "interactive";
     pub const LINK: &str = "link";
@@ -264,9 +323,9 @@ mod options {
     pub const REMOVE_DESTINATION: &str = "remove-destination";
     pub const SPARSE: &str = "sparse";
     pub const STRIP_TRAILING_SLASHES: &str = "strip-trailing-slashes";
+    pub const SUMMARY: &str = "summary";
     pub const SYMBOLIC_LINK: &str = "symbolic-link";
     pub const TARGET_DIRECTORY: &str = "target-directory";
-    pub const UPDATE: &str = "update";
     pub const VERBOSE: &str = "verbose";
 }
 
@@ -275,6 +334,7 @@ static PRESERVABLE_ATTRIBUTES: &[&str] = &[
     "mode",
     "ownership",
     "timestamps",
+    "crtimes",
     "context",
     "links",
     "xattr",
@@ -282,8 +342,15 @@ static PRESERVABLE_ATTRIBUTES: &[&str] = &[
 ];
 
 #[cfg(not(unix))]
-static PRESERVABLE_ATTRIBUTES: &[&str] =
-    &["mode", "timestamps", "context", "links", "xattr", "all"];
+static PRESERVABLE_ATTRIBUTES: &[&str] = &[
+    "mode",
+    "timestamps",
+    "crtimes",
+    "context",
+    "links",
+    "xattr",
+    "all",
+];
 
 pub fn uu_app() -> Command {
     const MODE_ARGS: &[&str] = &[
@@ -297,6 +364,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(after_help())
         .infer_long_args(true)
         .arg(
             Arg::new(options::TARGET_DIRECTORY)
@@ -394,16 +462,8 @@ pub fn uu_app() -> Command {
         .arg(backup_control::arguments::backup())
         .arg(backup_control::arguments::backup_no_args())
         .arg(backup_control::arguments::suffix())
-        .arg(
-            Arg::new(options::UPDATE)
-                .short('u')
-                .long(options::UPDATE)
-                .help(
-                    "copy only when the SOURCE file is newer than the destination file \
-                    or when the destination file is missing",
-                )
-                .action(ArgAction::SetTrue),
-        )
+        .arg(update_control::arguments::update())
+        .arg(update_control::arguments::update_no_args())
         .arg(
             Arg::new(options::REFLINK)
                 .long(options::REFLINK)
@@ -432,36 +492,36 @@ pub fn uu_app() -> Command {
                 ))
                 .num_args(0..)
                 .value_name("ATTR_LIST")
-                .overrides_with_all([
-                    options::ARCHIVE,
-                    options::PRESERVE_DEFAULT_ATTRIBUTES,
-                    options::NO_PRESERVE,
-                ])
+                .overrides_with_all([options::ARCHIVE, options::PRESERVE_DEFAULT_ATTRIBUTES])
                 // -d sets this option
                 // --archive sets this option
                 .help(
                     "Preserve the specified attributes (default: mode, ownership (unix only), \
-                     timestamps), if possible additional attributes: context, links, xattr, all",
+                     timestamps), if possible additional attributes: crtimes, context, links, \
+                     xattr, all",
                 ),
         )
         .arg(
             Arg::new(options::PRESERVE_DEFAULT_ATTRIBUTES)
                 .short('p')
                 .long(options::PRESERVE_DEFAULT_ATTRIBUTES)
-                .overrides_with_all([options::PRESERVE, options::NO_PRESERVE, options::ARCHIVE])
+                .overrides_with_all([options::PRESERVE, options::ARCHIVE])
                 .help("same as --preserve=mode,ownership(unix only),timestamps")
                 .action(ArgAction::SetTrue),
         )
         .arg(
             Arg::new(options::NO_PRESERVE)
                 .long(options::NO_PRESERVE)
+                .action(ArgAction::Append)
+                .use_value_delimiter(true)
+                .value_parser(clap::builder::PossibleValuesParser::new(
+                    PRESERVABLE_ATTRIBUTES,
+                ))
                 .value_name("ATTR_LIST")
-                .overrides_with_all([
-                    options::PRESERVE_DEFAULT_ATTRIBUTES,
-                    options::PRESERVE,
-                    options::ARCHIVE,
-                ])
-                .help("don't preserve the specified attributes"),
+                .help(
+                    "don't preserve the specified attributes, even if --preserve, -p or -a \
+                     would",
+                ),
         )
         .arg(
             Arg::new(options::PARENTS)
@@ -497,11 +557,7 @@ pub fn uu_app() -> Command {
             Arg::new(options::ARCHIVE)
                 .short('a')
                 .long(options::ARCHIVE)
-                .overrides_with_all([
-                    options::PRESERVE_DEFAULT_ATTRIBUTES,
-                    options::PRESERVE,
-                    options::NO_PRESERVE,
-                ])
+                .overrides_with_all([options::PRESERVE_DEFAULT_ATTRIBUTES, options::PRESERVE])
                 .help("Same as -dR --preserve=all")
                 .action(ArgAction::SetTrue),
         )
@@ -555,6 +611,47 @@ pub fn uu_app() -> Command {
                 Note: this feature is not supported by GNU coreutils.",
                 ),
         )
+        .arg(
+            Arg::new(options::CHECK_SPACE)
+                .long(options::CHECK_SPACE)
+                .action(ArgAction::SetTrue)
+                .help(
+                    "check that the file system of DEST has room for the data \
+                    of the SOURCEs before starting to copy. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
+        .arg(
+            Arg::new(options::SUMMARY)
+                .long(options::SUMMARY)
+                .action(ArgAction::SetTrue)
+                .help(
+                    "print a report of what was copied, skipped and failed, \
+                    and how long it took, at the end of the run. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
+        .arg(
+            Arg::new(options::BWLIMIT)
+                .long(options::BWLIMIT)
+                .value_name("RATE")
+                .help(
+                    "limit the rate at which file data is written to RATE bytes per \
+                    second (e.g. 500K, 10M). \n\
+                Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
+        .arg(
+            Arg::new(options::DIRECT)
+                .long(options::DIRECT)
+                .help(
+                    "copy file data with direct I/O, bypassing the page cache, where \
+                    the file system supports it. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(report::arguments::report())
         .arg(
             Arg::new(options::PATHS)
                 .action(ArgAction::Append)
@@ -562,15 +659,45 @@ pub fn uu_app() -> Command {
         )
 }
 
-#[uucore::main]
+fn after_help() -> String {
+    format!(
+        "{}\n\n{}",
+        backup_control::BACKUP_CONTROL_LONG_HELP,
+        update_control::UPDATE_CONTROL_LONG_HELP
+    )
+}
+
+/// What `cp --version=json` reports, on top of the version.
+fn version_info() -> VersionInfo {
+    VersionInfo::new(crate_version!())
+        .feature("selinux", cfg!(feature = "feat_selinux"))
+        .feature("acl", cfg!(feature = "feat_acl"))
+        .capability(
+            "reflink",
+            cfg!(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                windows
+            )),
+        )
+        .capability(
+            "sparse",
+            cfg!(any(target_os = "linux", target_os = "android")),
+        )
+        .capability(
+            "direct",
+            cfg!(any(target_os = "linux", target_os = "android")),
+        )
+}
+
+#[uucore::main(version_info)]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(backup_control::BACKUP_CONTROL_LONG_HELP)
-        .try_get_matches_from(args);
+    let matches = uu_app().try_get_matches_from(args);
 
     // The error is parsed here because we do not want version or help being printed to stderr.
     if let Err(e) = matches {
-        let mut app = uu_app().after_help(backup_control::BACKUP_CONTROL_LONG_HELP);
+        let mut app = uu_app();
 
         match e.kind() {
             clap::error::ErrorKind::DisplayHelp => {
@@ -585,7 +712,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         if options.overwrite == OverwriteMode::NoClobber && options.backup != BackupMode::NoBackup {
             return Err(UUsageError::new(
                 EXIT_ERR,
-                "options --backup and --no-clobber are mutually exclusive",
+                translate!("error-backup-no-clobber"),
             ));
         }
 
@@ -595,6 +722,9 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             .unwrap_or_default();
 
         let (sources, target) = parse_path_args(&paths, &options)?;
+        if let Some(format) = report::determine_report_format(&matches) {
+            report::enable(format);
+        }
 
         if let Err(error) = copy(&sources, &target, &options) {
             match error {
@@ -641,8 +771,6 @@ impl CopyMode {
             Self::Link
         } else if matches.get_flag(options::SYMBOLIC_LINK) {
             Self::SymLink
-        } else if matches.get_flag(options::UPDATE) {
-            Self::Update
         } else if matches.get_flag(options::ATTRIBUTES_ONLY) {
             Self::AttrOnly
         } else {
@@ -651,16 +779,39 @@ impl CopyMode {
     }
 }
 
-impl Attributes {
-    // TODO: ownership is required if the user is root, for non-root users it's not required.
-    // See: https://github.com/coreutils/coreutils/blob/master/src/copy.c#L3181
+impl Default for Attributes {
+    /// The mode, ownership and timestamps, like `--preserve` without a
+    /// list.
+    fn default() -> Self {
+        Self {
+            #[cfg(unix)]
+            ownership: Self::preserve_ownership(),
+            mode: Preserve::Yes { required: true },
+            acl: Self::preserve_acl(),
+            timestamps: Preserve::Yes { required: true },
+            crtimes: Preserve::No,
+            context: Preserve::No,
+            links: Preserve::No,
+            xattr: Preserve::No,
+        }
+    }
+}
 
-    fn all() -> Self {
+impl Attributes {
+    /// Everything, like `--preserve=all`.
+    pub fn all() -> Self {
         Self {
             #[cfg(unix)]
-            ownership: Preserve::Yes { required: true },
+            ownership: Self::preserve_ownership(),
             mode: Preserve::Yes { required: true },
+            acl: Self::preserve_acl(),
             timestamps: Preserve::Yes { required: true },
+            // Creation times can only be set on Windows and macOS.
+            crtimes: if cfg!(any(windows, target_os = "macos")) {
+                Preserve::Yes { required: false }
+            } else {
+                Preserve::No
+            },
             context: {
                 #[cfg(feature = "feat_selinux")]
                 {
@@ -676,47 +827,88 @@ impl Attributes {
         }
     }
 
-    fn default() -> Self {
-        Self {
-            #[cfg(unix)]
-            ownership: Preserve::Yes { required: true },
-            mode: Preserve::Yes { required: true },
-            timestamps: Preserve::Yes { required: true },
-            context: Preserve::No,
-            links: Preserve::No,
-            xattr: Preserve::No,
-        }
-    }
-
-    fn none() -> Self {
+    /// Nothing, which is what `cp` does without `--preserve`.
+    pub fn none() -> Self {
         Self {
             #[cfg(unix)]
             ownership: Preserve::No,
             mode: Preserve::No,
+            acl: Preserve::No,
             timestamps: Preserve::No,
+            crtimes: Preserve::No,
             context: Preserve::No,
             links: Preserve::No,
             xattr: Preserve::No,
         }
     }
 
+    /// How ACLs are preserved along with the mode, which depends on whether
+    /// ACL support was compiled in.
+    fn preserve_acl() -> Preserve {
+        if cfg!(feature = "feat_acl") {
+            Preserve::Yes { required: true }
+        } else {
+            Preserve::No
+        }
+    }
+
+    /// How the ownership is preserved, which is required only from a process
+    /// that is allowed to give files away. Other processes keep the owner
+    /// they can, without a word, like with GNU `cp`.
+    #[cfg(unix)]
+    fn preserve_ownership() -> Preserve {
+        Preserve::Yes {
+            required: capabilities::has(Capability::Chown),
+        }
+    }
+
     /// Tries to match string containing a parameter to preserve with the corresponding entry in the
     /// Attributes struct.
     fn try_set_from_string(&mut self, value: &str) -> Result<(), Error> {
-        let preserve_yes_required = Preserve::Yes { required: true };
+        self.try_update_from_string(value, true)
+    }
+
+    /// Tries to match string containing a parameter not to preserve with the corresponding entry
+    /// in the Attributes struct, for `--no-preserve`.
+    fn try_unset_from_string(&mut self, value: &str) -> Result<(), Error> {
+        self.try_update_from_string(value, false)
+    }
+
+    fn try_update_from_string(&mut self, value: &str, preserve: bool) -> Result<(), Error> {
+        let level = || {
+            if preserve {
+                Preserve::Yes { required: true }
+            } else {
+                Preserve::No
+            }
+        };
 
         match &*value.to_lowercase() {
-            "mode" => self.mode = preserve_yes_required,
+            "mode" => {
+                self.mode = level();
+                self.acl = if preserve {
+                    Self::preserve_acl()
+                } else {
+                    Preserve::No
+                };
+            }
             #[cfg(unix)]
-            "ownership" => self.ownership = preserve_yes_required,
-            "timestamps" => self.timestamps = preserve_yes_required,
-            "context" => self.context = preserve_yes_required,
-            "links" => self.links = preserve_yes_required,
-            "xattr" => self.xattr = preserve_yes_required,
+            "ownership" => {
+                self.ownership = if preserve {
+                    Self::preserve_ownership()
+                } else {
+                    Preserve::No
+                };
+            }
+            "timestamps" => self.timestamps = level(),
+            "crtimes" => self.crtimes = level(),
+            "context" => self.context = level(),
+            "links" => self.links = level(),
+            "xattr" => self.xattr = level(),
             _ => {
-                return Err(Error::InvalidArgument(format!(
-                    "invalid attribute {}",
-                    value.quote()
+                return Err(Error::InvalidArgument(translate!(
+                    "error-invalid-attribute",
+                    value = value.quote()
                 )));
             }
         };
@@ -767,7 +959,7 @@ impl Options {
         };
 
         // Parse attributes to preserve
-        let attributes: Attributes = if matches.contains_id(options::PRESERVE) {
+        let mut attributes: Attributes = if matches.contains_id(options::PRESERVE) {
             match matches.get_many::<String>(options::PRESERVE) {
                 None => Attributes::default(),
                 Some(attribute_strs) => {
@@ -802,6 +994,20 @@ impl Options {
             Attributes::none()
         };
 
+        // Then take away those that are not to be preserved, so that for
+        // example `-a --no-preserve=ownership` preserves all the others.
+        let mut explicit_no_preserve_mode = false;
+        if let Some(attribute_strs) = matches.get_many::<String>(options::NO_PRESERVE) {
+            for attribute_str in attribute_strs {
+                if attribute_str == "all" {
+                    attributes = Attributes::none();
+                } else {
+                    attributes.try_unset_from_string(attribute_str)?;
+                }
+                explicit_no_preserve_mode |= attribute_str == "all" || attribute_str == "mode";
+            }
+        }
+
         #[cfg(not(feature = "feat_selinux"))]
         if let Preserve::Yes { required } = attributes.context {
             let selinux_disabled_error =
@@ -813,6 +1019,19 @@ impl Options {
             }
         }
 
+        let bwlimit = match matches.get_one::<String>(options::BWLIMIT) {
+            Some(rate) => match parse_size(rate) {
+                Ok(rate) if rate > 0 => Some(rate),
+                _ => {
+                    return Err(Error::InvalidArgument(translate!(
+                        "error-invalid-bwlimit",
+                        value = rate.quote()
+                    )));
+                }
+            },
+            None => None,
+        };
+
         let options = Self {
             attributes_only: matches.get_flag(options::ATTRIBUTES_ONLY),
             copy_contents: matches.get_flag(options::COPY_CONTENTS),
@@ -824,9 +1043,10 @@ impl Options {
                 || matches.get_flag(options::ARCHIVE)
                 || recursive)
                 || matches.get_flag(options::DEREFERENCE),
+            direct: matches.get_flag(options::DIRECT),
             one_file_system: matches.get_flag(options::ONE_FILE_SYSTEM),
             parents: matches.get_flag(options::PARENTS),
-            update: matches.get_flag(options::UPDATE),
+            update: update_control::determine_update_mode(matches),
             verbose: matches.get_flag(options::VERBOSE),
             strip_trailing_slashes: matches.get_flag(options::STRIP_TRAILING_SLASHES),
             reflink_mode: {
@@ -836,25 +1056,14 @@ impl Options {
                         "auto" => ReflinkMode::Auto,
                         "never" => ReflinkMode::Never,
                         value => {
-                            return Err(Error::InvalidArgument(format!(
-                                "invalid argument {} for \'reflink\'",
-                                value.quote()
+                            return Err(Error::InvalidArgument(translate!(
+                                "error-invalid-reflink",
+                                value = value.quote()
                             )));
                         }
                     }
                 } else {
-                    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
-                    {
-                        ReflinkMode::Auto
-                    }
-                    #[cfg(not(any(
-                        target_os = "linux",
-                        target_os = "android",
-                        target_os = "macos"
-                    )))]
-                    {
-                        ReflinkMode::Never
-                    }
+                    ReflinkMode::default()
                 }
             },
             sparse_mode: {
@@ -864,8 +1073,9 @@ impl Options {
                         "auto" => SparseMode::Auto,
                         "never" => SparseMode::Never,
                         _ => {
-                            return Err(Error::InvalidArgument(format!(
-                                "invalid argument {val} for \'sparse\'"
+                            return Err(Error::InvalidArgument(translate!(
+                                "error-invalid-sparse",
+                                value = val
                             )));
                         }
                     }
@@ -875,12 +1085,18 @@ impl Options {
             },
             backup: backup_mode,
             backup_suffix,
+            bwlimit,
             overwrite,
             no_target_dir,
             attributes,
+            explicit_no_preserve_mode,
             recursive,
             target_dir,
             progress_bar: matches.get_flag(options::PROGRESS_BAR),
+            summary: matches.get_flag(options::SUMMARY),
+            check_space: matches.get_flag(options::CHECK_SPACE),
+            report_status: true,
+            observer: None,
         };
 
         Ok(options)
@@ -903,6 +1119,182 @@ impl Options {
     }
 }
 
+/// A builder of [`Options`], for programs that use [`copy`] as a library.
+///
+/// It starts from what `cp` does without any option: files are copied
+/// without their attributes, following symbolic links, and existing
+/// files are overwritten.
+///
+/// ```no_run
+/// use std::path::PathBuf;
+/// use uu_cp::{copy, Attributes, Options};
+///
+/// let options = Options::builder()
+///     .recursive(true)
+///     .preserve(Attributes::all())
+///     .build();
+/// copy(&[PathBuf::from("src")], "backup".as_ref(), &options).unwrap();
+/// ```
+pub struct OptionsBuilder {
+    options: Options,
+    dereference: Option<bool>,
+}
+
+impl Options {
+    /// Start building options, see [`OptionsBuilder`].
+    pub fn builder() -> OptionsBuilder {
+        OptionsBuilder {
+            options: Self {
+                attributes_only: false,
+                backup: BackupMode::NoBackup,
+                bwlimit: None,
+                copy_contents: false,
+                cli_dereference: false,
+                copy_mode: CopyMode::Copy,
+                dereference: true,
+                direct: false,
+                no_target_dir: false,
+                one_file_system: false,
+                overwrite: OverwriteMode::Clobber(ClobberMode::Standard),
+                parents: false,
+                sparse_mode: SparseMode::Auto,
+                strip_trailing_slashes: false,
+                reflink_mode: ReflinkMode::default(),
+                attributes: Attributes::none(),
+                explicit_no_preserve_mode: false,
+                recursive: false,
+                backup_suffix: "~".to_string(),
+                target_dir: None,
+                update: UpdateMode::ReplaceAll,
+                verbose: false,
+                progress_bar: false,
+                summary: false,
+                check_space: false,
+                report_status: false,
+                observer: None,
+            },
+            dereference: None,
+        }
+    }
+}
+
+impl OptionsBuilder {
+    /// Copy directories recursively, like `-R`. Unless [`dereference`] is
+    /// set, this stops following symbolic links, as `-R` does.
+    ///
+    /// [`dereference`]: Self::dereference
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.options.recursive = recursive;
+        self
+    }
+
+    /// Whether to follow symbolic links in the sources, like `-L` and `-P`.
+    pub fn dereference(mut self, dereference: bool) -> Self {
+        self.dereference = Some(dereference);
+        self
+    }
+
+    /// How to copy: the data, links to the sources, or only attributes.
+    pub fn copy_mode(mut self, copy_mode: CopyMode) -> Self {
+        self.options.attributes_only = matches!(copy_mode, CopyMode::AttrOnly);
+        self.options.copy_mode = copy_mode;
+        self
+    }
+
+    /// The attributes to preserve, like `--preserve`.
+    pub fn preserve(mut self, attributes: Attributes) -> Self {
+        self.options.attributes = attributes;
+        self
+    }
+
+    /// What to do with destinations that exist, like `-f`, `-i` and `-n`.
+    /// Interactive modes prompt on the terminal.
+    pub fn overwrite(mut self, overwrite: OverwriteMode) -> Self {
+        self.options.overwrite = overwrite;
+        self
+    }
+
+    /// Which existing destinations to replace, like `--update`.
+    pub fn update(mut self, update: UpdateMode) -> Self {
+        self.options.update = update;
+        self
+    }
+
+    /// Back up existing destinations, like `--backup` and `--suffix`.
+    pub fn backup(mut self, backup: BackupMode, suffix: impl Into<String>) -> Self {
+        self.options.backup = backup;
+        self.options.backup_suffix = suffix.into();
+        self
+    }
+
+    /// Whether to clone files, like `--reflink`.
+    pub fn reflink(mut self, reflink_mode: ReflinkMode) -> Self {
+        self.options.reflink_mode = reflink_mode;
+        self
+    }
+
+    /// How to handle sparse files, like `--sparse`.
+    pub fn sparse(mut self, sparse_mode: SparseMode) -> Self {
+        self.options.sparse_mode = sparse_mode;
+        self
+    }
+
+    /// Limit the transfer rate to `bytes_per_second`, like `--bwlimit`.
+    pub fn bwlimit(mut self, bytes_per_second: Option<u64>) -> Self {
+        self.options.bwlimit = bytes_per_second;
+        self
+    }
+
+    /// Bypass the page cache, like `--direct`.
+    pub fn direct(mut self, direct: bool) -> Self {
+        self.options.direct = direct;
+        self
+    }
+
+    /// Stay on the file system of each source, like `-x`.
+    pub fn one_file_system(mut self, one_file_system: bool) -> Self {
+        self.options.one_file_system = one_file_system;
+        self
+    }
+
+    /// Append the source paths to the target directory, like `--parents`.
+    pub fn parents(mut self, parents: bool) -> Self {
+        self.options.parents = parents;
+        self
+    }
+
+    /// Treat the target as a file even if it is a directory, like `-T`.
+    pub fn no_target_dir(mut self, no_target_dir: bool) -> Self {
+        self.options.no_target_dir = no_target_dir;
+        self
+    }
+
+    /// Print each copy to stdout, like `-v`.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.options.verbose = verbose;
+        self
+    }
+
+    /// Check that the target has room for the sources before copying,
+    /// like `--check-space`.
+    pub fn check_space(mut self, check_space: bool) -> Self {
+        self.options.check_space = check_space;
+        self
+    }
+
+    /// Report the progress of the copy to `observer`.
+    pub fn observer(mut self, observer: impl Observer + 'static) -> Self {
+        self.options.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Finish building the options.
+    pub fn build(mut self) -> Options {
+        self.options.dereference = self.dereference.unwrap_or(!self.options.recursive);
+        self.options
+    }
+}
+
 impl TargetType {
     /// Return TargetType required for `target`.
     ///
@@ -923,13 +1315,13 @@ fn parse_path_args(path_args: &[String], options: &Options) -> CopyResult<(Vec<S
 
     if paths.is_empty() {
         // No files specified
-        return Err("missing file operand".into());
+        return Err(translate!("error-missing-operand").into());
     }
 
     // Return an error if the user requested to copy more than one
     // file source to a file target
     if options.no_target_dir && options.target_dir.is_none() && paths.len() > 2 {
-        return Err(format!("extra operand {:?}", paths[2]).into());
+        return Err(translate!("error-extra-operand", operand = format!("{:?}", paths[2])).into());
     }
 
     let target = match options.target_dir {
@@ -963,31 +1355,41 @@ fn get_inode(file_info: &FileInformation) -> u64 {
     result
 }
 
+/// The destinations of the copied files having more than one hard link,
+/// keyed by the device and inode of their source, with the number of
+/// links to them that are yet to be seen.
+///
+/// It is shared by all sources of a single `cp` invocation. Entries are
+/// dropped once all the links of a file have been seen, so that it only
+/// grows with the files whose links are spread over the tree.
+pub(crate) type HardLinks = HashMap<(u64, u64), (PathBuf, u64)>;
+
 #[cfg(target_os = "redox")]
 fn preserve_hardlinks(
-    hard_links: &mut Vec<(String, u64)>,
-    source: &std::path::Path,
-    dest: &std::path::Path,
-    found_hard_link: &mut bool,
-) -> CopyResult<()> {
+    _hard_links: &mut HardLinks,
+    _source: &std::path::Path,
+    _dest: &std::path::Path,
+) -> CopyResult<bool> {
     // Redox does not currently support hard links
-    Ok(())
+    Ok(false)
 }
 
 /// Hard link a pair of files if needed _and_ record if this pair is a new hard link.
 #[cfg(not(target_os = "redox"))]
 fn preserve_hardlinks(
-    hard_links: &mut Vec<(String, u64)>,
+    hard_links: &mut HardLinks,
     source: &std::path::Path,
     dest: &std::path::Path,
 ) -> CopyResult<bool> {
     let info = FileInformation::from_path(source, false)
-        .context(format!("cannot stat {}", source.quote()))?;
-    let inode = get_inode(&info);
-    let nlinks = info.number_of_links();
-    let mut found_hard_link = false;
-    for (link, link_inode) in hard_links.iter() {
-        if *link_inode == inode {
+        .context(translate!("error-cannot-stat", path = source.quote()))?;
+    // Files without other links can't be the target of a later hard link
+    if info.number_of_links() <= 1 {
+        return Ok(false);
+    }
+    let key = (info.device(), get_inode(&info));
+    match hard_links.get_mut(&key) {
+        Some((link, remaining)) => {
             // Consider the following files:
             //
             // * `src/f` - a regular file
@@ -1001,17 +1403,24 @@ fn preserve_hardlinks(
             // `dest/src/f` and `dest/src/f` has the contents of
             // `src/f`, we delete the existing file to allow the hard
             // linking.
-            if file_or_link_exists(dest) && file_or_link_exists(Path::new(link)) {
+            if file_or_link_exists(dest) && file_or_link_exists(link) {
                 std::fs::remove_file(dest)?;
             }
-            std::fs::hard_link(link, dest).unwrap();
-            found_hard_link = true;
+            std::fs::hard_link(link.as_path(), dest).context(translate!(
+                "error-cannot-create-hard-link",
+                path = dest.quote()
+            ))?;
+            *remaining -= 1;
+            if *remaining == 0 {
+                hard_links.remove(&key);
+            }
+            Ok(true)
+        }
+        None => {
+            hard_links.insert(key, (dest.to_path_buf(), info.number_of_links() - 1));
+            Ok(false)
         }
     }
-    if !found_hard_link && nlinks > 1 {
-        hard_links.push((dest.to_str().unwrap().to_string(), inode));
-    }
-    Ok(found_hard_link)
 }
 
 /// When handling errors, we don't always want to show them to the user. This function handles that.
@@ -1034,40 +1443,54 @@ fn show_error_if_needed(error: &Error) -> bool {
 /// `Err(Error::NotAllFilesCopied)` if at least one non-fatal error was
 /// encountered.
 ///
-/// Behavior depends on path`options`, see [`Options`] for details.
+/// With `--summary`, a report of the run is printed at the end, whether
+/// or not all files were copied.
+///
+/// Behavior depends on path`options`, see [`Options`] for details. As
+/// with `cp`, errors that don't stop the copy are printed to stderr.
 ///
 /// [`Options`]: ./struct.Options.html
-fn copy(sources: &[Source], target: &TargetSlice, options: &Options) -> CopyResult<()> {
+pub fn copy(sources: &[Source], target: &TargetSlice, options: &Options) -> CopyResult<()> {
     let target_type = TargetType::determine(sources, target);
     verify_target_type(target, &target_type)?;
 
     let preserve_hard_links = options.preserve_hard_links();
 
-    let mut hard_links: Vec<(String, u64)> = vec![];
+    let mut hard_links = HardLinks::new();
 
     let mut non_fatal_errors = false;
     let mut seen_sources = HashSet::with_capacity(sources.len());
     let mut symlinked_files = HashSet::new();
 
-    let progress_bar = if options.progress_bar {
-        let pb = ProgressBar::new(disk_usage(sources, options.recursive)?)
-            .with_style(
-                ProgressStyle::with_template(
-                    "{msg}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7}",
-                )
-                .unwrap(),
-            )
-            .with_message(uucore::util_name());
-        pb.tick();
-        Some(pb)
+    let total_size = if options.progress_bar || options.check_space {
+        Some(disk_usage(sources, options.recursive)?)
     } else {
         None
     };
+    if let (true, Some(size)) = (options.check_space, total_size) {
+        check_free_space(target, size, options)?;
+    }
+
+    let progress_bar = if options.progress_bar || options.observer.is_some() {
+        let total_size = total_size.filter(|_| options.progress_bar);
+        Some(Progress::new(total_size, options.observer.clone()))
+    } else {
+        None
+    };
+    let summary = if options.report_status {
+        Summary::new().with_status()
+    } else {
+        Summary::new()
+    };
 
     for source in sources.iter() {
+        report::start_operand(source);
         if seen_sources.contains(source) {
             // FIXME: compare sources by the actual file they point to, not their path. (e.g. dir/file == dir/../dir/file in most cases)
-            show_warning!("source {} specified more than once", source.quote());
+            show_warning!(
+                "{}",
+                translate!("warning-source-twice", path = source.quote())
+            );
         } else {
             let found_hard_link = if preserve_hard_links && !source.is_dir() {
                 let dest = construct_dest_path(source, target, &target_type, options)?;
@@ -1075,23 +1498,32 @@ fn copy(sources: &[Source], target: &TargetSlice, options: &Options) -> CopyResu
             } else {
                 false
             };
-            if !found_hard_link {
-                if let Err(error) = copy_source(
-                    &progress_bar,
-                    source,
-                    target,
-                    &target_type,
-                    options,
-                    &mut symlinked_files,
-                ) {
-                    if show_error_if_needed(&error) {
-                        non_fatal_errors = true;
-                    }
+            if found_hard_link {
+                summary.copied(0);
+            } else if let Err(error) = copy_source(
+                &progress_bar,
+                &summary,
+                source,
+                target,
+                &target_type,
+                options,
+                &mut symlinked_files,
+                &mut hard_links,
+            ) {
+                summary.error(&error);
+                if show_error_if_needed(&error) {
+                    non_fatal_errors = true;
                 }
             }
             seen_sources.insert(source);
         }
     }
+    if options.summary {
+        match &progress_bar {
+            Some(pb) => pb.suspend(|| summary.print()),
+            None => summary.print(),
+        }
+    }
     if non_fatal_errors {
         Err(Error::NotAllFilesCopied)
     } else {
@@ -1106,15 +1538,11 @@ fn construct_dest_path(
     options: &Options,
 ) -> CopyResult<PathBuf> {
     if options.no_target_dir && target.is_dir() {
-        return Err(format!(
-            "cannot overwrite directory {} with non-directory",
-            target.quote()
-        )
-        .into());
+        return Err(translate!("error-directory-with-non-directory", path = target.quote()).into());
     }
 
     if options.parents && !target.is_dir() {
-        return Err("with --parents, the destination must be a directory".into());
+        return Err(translate!("error-parents-not-directory").into());
     }
 
     Ok(match *target_type {
@@ -1130,29 +1558,48 @@ fn construct_dest_path(
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 fn copy_source(
-    progress_bar: &Option<ProgressBar>,
+    progress_bar: &Option<Progress>,
+    summary: &Summary,
     source: &SourceSlice,
     target: &TargetSlice,
     target_type: &TargetType,
     options: &Options,
     symlinked_files: &mut HashSet<FileInformation>,
+    hard_links: &mut HardLinks,
 ) -> CopyResult<()> {
     let source_path = Path::new(&source);
     if source_path.is_dir() {
         // Copy as directory
-        copy_directory(progress_bar, source, target, options, symlinked_files, true)
+        copy_directory(
+            progress_bar,
+            summary,
+            source,
+            target,
+            options,
+            symlinked_files,
+            hard_links,
+            true,
+        )
     } else {
         // Copy as file
         let dest = construct_dest_path(source_path, target, target_type, options)?;
-        copy_file(
+        let parents = if options.parents {
+            create_parents(source_path, &dest, options)?
+        } else {
+            Vec::new()
+        };
+        let result = copy_file(
             progress_bar,
-            source_path,
-            dest.as_path(),
+            summary,
+            &long_path(source_path),
+            &long_path(&dest),
             options,
             symlinked_files,
             true,
-        )
+        );
+        result.and(copy_parents_attributes(&parents, options))
     }
 }
 
@@ -1161,7 +1608,7 @@ impl OverwriteMode {
         match *self {
             Self::NoClobber => Err(Error::NotAllFilesCopied),
             Self::Interactive(_) => {
-                if prompt_yes!("overwrite {}?", path.quote()) {
+                if prompt_yes!("{}", translate!("prompt-overwrite", path = path.quote())) {
                     Ok(())
                 } else {
                     Err(Error::Skipped)
@@ -1199,6 +1646,11 @@ pub(crate) fn copy_attributes(
     let context = &*format!("{} -> {}", source.quote(), dest.quote());
     let source_metadata = fs::symlink_metadata(source).context(context)?;
 
+    // Whether the copy has the owner of the source, or the set-user-ID and
+    // set-group-ID bits of the source would be given to someone else.
+    #[cfg(unix)]
+    let kept_owner = std::cell::Cell::new(true);
+
     // Ownership must be changed first to avoid interfering with mode change.
     #[cfg(unix)]
     handle_preserve(&attributes.ownership, || -> CopyResult<()> {
@@ -1209,10 +1661,11 @@ pub(crate) fn copy_attributes(
 
         let dest_uid = source_metadata.uid();
         let dest_gid = source_metadata.gid();
+        let dest_metadata = dest.symlink_metadata().context(context)?;
 
-        wrap_chown(
+        let result = wrap_chown(
             dest,
-            &dest.symlink_metadata().context(context)?,
+            &dest_metadata,
             Some(dest_uid),
             Some(dest_gid),
             false,
@@ -1220,8 +1673,24 @@ pub(crate) fn copy_attributes(
                 groups_only: false,
                 level: VerbosityLevel::Normal,
             },
-        )
-        .map_err(Error::Error)?;
+        );
+        if result.is_err() && attributes.ownership == (Preserve::Yes { required: false }) {
+            // Keep at least the group, if it is one of ours.
+            kept_owner.set(false);
+            let _ = wrap_chown(
+                dest,
+                &dest_metadata,
+                None,
+                Some(dest_gid),
+                false,
+                Verbosity {
+                    groups_only: true,
+                    level: VerbosityLevel::Silent,
+                },
+            );
+            return Ok(());
+        }
+        result.map_err(Error::Error)?;
 
         Ok(())
     })?;
@@ -1233,12 +1702,28 @@ pub(crate) fn copy_attributes(
         // do nothing, since every symbolic link has the same
         // permissions.
         if !dest.is_symlink() {
-            fs::set_permissions(dest, source_metadata.permissions()).context(context)?;
-            // FIXME: Implement this for windows as well
-            #[cfg(feature = "feat_acl")]
-            exacl::getfacl(source, None)
-                .and_then(|acl| exacl::setfacl(&[dest], &acl, None))
-                .map_err(|err| Error::Error(err.to_string()))?;
+            #[allow(unused_mut)]
+            let mut permissions = source_metadata.permissions();
+            #[cfg(unix)]
+            if !kept_owner.get() {
+                permissions.set_mode(permissions.mode() & !0o6000);
+            }
+            fs::set_permissions(dest, permissions).context(context)?;
+            // The permissions only cover the read-only attribute.
+            #[cfg(windows)]
+            platform::copy_file_attributes(&source_metadata, dest).context(context)?;
+        }
+
+        Ok(())
+    })?;
+
+    // The ACL comes after the mode, since changing the mode also changes
+    // the ACL entries that mirror it.
+    #[cfg(feature = "feat_acl")]
+    handle_preserve(&attributes.acl, || -> CopyResult<()> {
+        // Like the mode, ACLs don't apply to symbolic links.
+        if !dest.is_symlink() {
+            acl::copy_acl(source, dest).context(context)?;
         }
 
         Ok(())
@@ -1246,7 +1731,11 @@ pub(crate) fn copy_attributes(
 
     handle_preserve(&attributes.timestamps, || -> CopyResult<()> {
         let atime = FileTime::from_last_access_time(&source_metadata);
-        let mtime = FileTime::from_last_modification_time(&source_metadata);
+        let mut mtime = FileTime::from_last_modification_time(&source_metadata);
+        // A reproducible build makes no file newer than SOURCE_DATE_EPOCH.
+        if let Some(epoch) = source_date_epoch() {
+            mtime = mtime.min(FileTime::from_system_time(epoch));
+        }
         if dest.is_symlink() {
             filetime::set_symlink_file_times(dest, atime, mtime)?;
         } else {
@@ -1256,23 +1745,23 @@ pub(crate) fn copy_attributes(
         Ok(())
     })?;
 
+    handle_preserve(&attributes.crtimes, || -> CopyResult<()> {
+        // A symbolic link keeps the creation time it was created with.
+        if !dest.is_symlink() {
+            crtime::set_creation_time(dest, &source_metadata).context(context)?;
+        }
+
+        Ok(())
+    })?;
+
     #[cfg(feature = "feat_selinux")]
     handle_preserve(&attributes.context, || -> CopyResult<()> {
-        let context = selinux::SecurityContext::of_path(source, false, false).map_err(|e| {
-            format!(
-                "failed to get security context of {}: {}",
-                source.display(),
-                e
-            )
-        })?;
+        let context = selinux::SecurityContext::of_path(source, false, false)
+            .map_err(|e| translate!("error-get-context", path = source.display(), error = e))?;
         if let Some(context) = context {
-            context.set_for_path(dest, false, false).map_err(|e| {
-                format!(
-                    "failed to set security context for {}: {}",
-                    dest.display(),
-                    e
-                )
-            })?;
+            context
+                .set_for_path(dest, false, false)
+                .map_err(|e| translate!("error-set-context", path = dest.display(), error = e))?;
         }
 
         Ok(())
@@ -1281,25 +1770,17 @@ pub(crate) fn copy_attributes(
     handle_preserve(&attributes.xattr, || -> CopyResult<()> {
         #[cfg(unix)]
         {
-            let xattrs = xattr::list(source)?;
-            for attr in xattrs {
-                if let Some(attr_value) = xattr::get(source, attr.clone())? {
-                    xattr::set(dest, attr, &attr_value[..])?;
-                }
-            }
+            let policy = match attributes.xattr {
+                Preserve::Yes { required: true } => XattrPolicy::Required,
+                _ => XattrPolicy::BestEffort,
+            };
+            fsxattr::copy_xattrs(source, dest, policy).context(context)?;
         }
-        #[cfg(not(unix))]
-        {
-            // The documentation for GNU cp states:
-            //
-            // > Try to preserve SELinux security context and
-            // > extended attributes (xattr), but ignore any failure
-            // > to do that and print no corresponding diagnostic.
-            //
-            // so we simply do nothing here.
-            //
-            // TODO Silently ignore failures in the `#[cfg(unix)]`
-            // block instead of terminating immediately on errors.
+        // Alternate data streams are the closest thing to extended
+        // attributes on Windows.
+        #[cfg(windows)]
+        if !dest.is_symlink() {
+            platform::copy_streams(source, dest).context(context)?;
         }
 
         Ok(())
@@ -1329,7 +1810,11 @@ fn symlink_file(
 }
 
 fn context_for(src: &Path, dest: &Path) -> String {
-    format!("{} -> {}", src.quote(), dest.quote())
+    format!(
+        "{} -> {}",
+        display_path(src).quote(),
+        display_path(dest).quote()
+    )
 }
 
 /// Implements a simple backup copy for the destination file.
@@ -1359,6 +1844,9 @@ fn is_forbidden_copy_to_same_file(
 }
 
 /// Back up, remove, or leave intact the destination file, depending on the options.
+///
+/// Returns `Err(Error::Skipped)` if the destination is to be left intact,
+/// for example because of `--update` or a negative answer to `-i`.
 fn handle_existing_dest(
     source: &Path,
     dest: &Path,
@@ -1368,22 +1856,47 @@ fn handle_existing_dest(
     // Disallow copying a file to itself, unless `--force` and
     // `--backup` are both specified.
     if is_forbidden_copy_to_same_file(source, dest, options, source_in_command_line) {
-        return Err(format!("{} and {} are the same file", source.quote(), dest.quote()).into());
+        return Err(translate!(
+            "error-same-file",
+            source = source.quote(),
+            dest = dest.quote()
+        )
+        .into());
+    }
+
+    let source_metadata = if options.dereference(source_in_command_line) {
+        fs::metadata(source)
+    } else {
+        fs::symlink_metadata(source)
+    }
+    .context(context_for(source, dest))?;
+    let dest_metadata = fs::symlink_metadata(dest).context(context_for(source, dest))?;
+    match update_control::determine_update_action(
+        options.update,
+        options.overwrite == OverwriteMode::NoClobber,
+        &source_metadata,
+        &dest_metadata,
+    ) {
+        UpdateAction::Replace => {}
+        UpdateAction::Skip => return Err(Error::Skipped),
+        UpdateAction::SkipWithFailure => return Err(Error::NotAllFilesCopied),
     }
 
     options.overwrite.verify(dest)?;
 
-    let backup_path = backup_control::get_backup_path(options.backup, dest, &options.backup_suffix);
+    let backup_path =
+        backup_control::create_backup_path(options.backup, dest, &options.backup_suffix)?;
     if let Some(backup_path) = backup_path {
         if paths_refer_to_same_file(source, &backup_path, true) {
-            return Err(format!(
-                "backing up {} might destroy source;  {} not copied",
-                dest.quote(),
-                source.quote()
+            return Err(translate!(
+                "error-backup-destroys-source",
+                dest = dest.quote(),
+                source = source.quote()
             )
             .into());
         } else {
             backup_dest(dest, &backup_path)?;
+            backup_path.commit();
         }
     }
 
@@ -1453,27 +1966,115 @@ fn aligned_ancestors<'a>(source: &'a Path, dest: &'a Path) -> Vec<(&'a Path, &'a
     result
 }
 
+/// Create the missing ancestors of `dest`, the copy of `source` with
+/// `--parents`, as copies of the ancestors of `source`. This returns the
+/// directories that were created with their sources, the shallowest first,
+/// to copy their attributes once what goes in them is copied.
+///
+/// A symbolic link among the ancestors of `source` is copied as a link when
+/// links are not dereferenced, as long as it is relative and leads to a
+/// directory of the copy, which is created as well. Otherwise, like with
+/// GNU cp, the directory it points to is copied.
+pub(crate) fn create_parents(
+    source: &Path,
+    dest: &Path,
+    options: &Options,
+) -> CopyResult<Vec<(PathBuf, PathBuf)>> {
+    let ancestors = aligned_ancestors(source, dest);
+    let root = match ancestors
+        .first()
+        .and_then(|(_, dest_dir)| dest_dir.parent())
+    {
+        Some(root) => root,
+        None => return Ok(Vec::new()),
+    };
+    let mut created = Vec::new();
+    for (source_dir, dest_dir) in ancestors {
+        if file_or_link_exists(dest_dir) {
+            continue;
+        }
+        let context = context_for(source_dir, dest_dir);
+        let is_symlink = source_dir
+            .symlink_metadata()
+            .context(&*context)?
+            .file_type()
+            .is_symlink();
+        if is_symlink && !options.dereference(true) {
+            let link = fs::read_link(source_dir).context(&*context)?;
+            if let Some(resolved) = resolve_in_copy(&link, dest_dir, root) {
+                if !file_or_link_exists(&resolved) {
+                    fs::create_dir_all(&resolved).context(&*context)?;
+                    let source = source_dir.parent().unwrap_or(source_dir).join(&link);
+                    created.push((source, resolved));
+                }
+                #[cfg(not(windows))]
+                std::os::unix::fs::symlink(&link, dest_dir).context(&*context)?;
+                #[cfg(windows)]
+                std::os::windows::fs::symlink_dir(&link, dest_dir).context(&*context)?;
+                continue;
+            }
+        }
+        fs::create_dir(dest_dir).context(&*context)?;
+        created.push((source_dir.to_path_buf(), dest_dir.to_path_buf()));
+    }
+    Ok(created)
+}
+
+/// The path that the symbolic link `link`, copied to `dest`, points to, if
+/// that is inside `root`, the directory that the copy is made in.
+fn resolve_in_copy(link: &Path, dest: &Path, root: &Path) -> Option<PathBuf> {
+    let mut resolved = dest.parent()?.to_path_buf();
+    for component in link.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if resolved != root => {
+                resolved.pop();
+            }
+            Component::Normal(name) => resolved.push(name),
+            _ => return None,
+        }
+    }
+    Some(resolved)
+}
+
+/// Copy the attributes of the `parents` created by [`create_parents`], the
+/// deepest first.
+pub(crate) fn copy_parents_attributes(
+    parents: &[(PathBuf, PathBuf)],
+    options: &Options,
+) -> CopyResult<()> {
+    for (source, dest) in parents.iter().rev() {
+        copy_attributes(source, dest, &options.attributes)?;
+    }
+    Ok(())
+}
+
 /// Copy the a file from `source` to `dest`. `source` will be dereferenced if
 /// `options.dereference` is set to true. `dest` will be dereferenced only if
 /// the source was not a symlink.
 ///
 /// Behavior when copying to existing files is contingent on the
-/// `options.overwrite` mode. If a file is skipped, the return type
-/// should be `Error:Skipped`
+/// `options.overwrite` and `options.update` modes. If a file is skipped
+/// without failing, `Ok(())` is returned without touching `dest`.
 ///
 /// The original permissions of `source` will be copied to `dest`
-/// after a successful copy.
+/// after a successful copy. Copied and skipped files are counted in
+/// `summary`.
 fn copy_file(
-    progress_bar: &Option<ProgressBar>,
+    progress_bar: &Option<Progress>,
+    summary: &Summary,
     source: &Path,
     dest: &Path,
     options: &Options,
     symlinked_files: &mut HashSet<FileInformation>,
     source_in_command_line: bool,
 ) -> CopyResult<()> {
-    if options.update && options.overwrite == OverwriteMode::Interactive(ClobberMode::Standard) {
+    if options.update == UpdateMode::ReplaceIfOlder
+        && options.overwrite == OverwriteMode::Interactive(ClobberMode::Standard)
+    {
         // `cp -i --update old new` when `new` exists doesn't copy anything
         // and exit with 0
+        summary.skipped();
         return Ok(());
     }
 
@@ -1483,10 +2084,10 @@ fn copy_file(
             .map(|info| symlinked_files.contains(&info))
             .unwrap_or(false)
         {
-            return Err(Error::Error(format!(
-                "will not copy '{}' through just-created symlink '{}'",
-                source.display(),
-                dest.display()
+            return Err(Error::Error(translate!(
+                "error-through-new-symlink",
+                source = source.display(),
+                dest = dest.display()
             )));
         }
         let copy_contents = options.dereference(source_in_command_line) || !source.is_symlink();
@@ -1497,15 +2098,46 @@ fn copy_file(
                 OverwriteMode::Clobber(ClobberMode::RemoveDestination)
             )
         {
-            return Err(Error::Error(format!(
-                "not writing through dangling symlink '{}'",
-                dest.display()
+            return Err(Error::Error(translate!(
+                "error-dangling-symlink",
+                dest = dest.display()
             )));
         }
     }
 
+    // Calculate the context upfront before canonicalizing the path
+    let context = context_for(source, dest);
+    let context = context.as_str();
+
+    let source_metadata = {
+        let result = if options.dereference(source_in_command_line) {
+            fs::metadata(source)
+        } else {
+            fs::symlink_metadata(source)
+        };
+        result.context(context)?
+    };
+
+    // Like FIFOs and devices, sockets are special files that a recursive
+    // copy recreates rather than reading from, but a socket can't be
+    // recreated without binding it, so it is skipped instead.
+    #[cfg(unix)]
+    if source_metadata.file_type().is_socket() && options.recursive && !options.copy_contents {
+        show_warning!("{}", translate!("warning-socket", path = source.quote()));
+        summary.skipped();
+        return Ok(());
+    }
+
     if file_or_link_exists(dest) {
-        handle_existing_dest(source, dest, options, source_in_command_line)?;
+        match handle_existing_dest(source, dest, options, source_in_command_line) {
+            // A destination that is deliberately left alone is not an error,
+            // and must not abort a recursive copy either.
+            Err(Error::Skipped) => {
+                summary.skipped();
+                return Ok(());
+            }
+            result => result?,
+        }
     }
 
     if options.verbose {
@@ -1543,25 +2175,11 @@ fn copy_file(
         }
     }
 
-    // Calculate the context upfront before canonicalizing the path
-    let context = context_for(source, dest);
-    let context = context.as_str();
-
-    let source_metadata = {
-        let result = if options.dereference(source_in_command_line) {
-            fs::metadata(source)
-        } else {
-            fs::symlink_metadata(source)
-        };
-        result.context(context)?
-    };
-    let source_file_type = source_metadata.file_type();
-    let source_is_symlink = source_file_type.is_symlink();
-
-    #[cfg(unix)]
-    let source_is_fifo = source_file_type.is_fifo();
-    #[cfg(not(unix))]
-    let source_is_fifo = false;
+    summary.started(source, dest);
+    if let Some(progress_bar) = progress_bar {
+        let size = fs::metadata(source).map_or(0, |md| md.len());
+        progress_bar.start_file(source, dest, size);
+    }
 
     let dest_permissions = if dest.exists() {
         dest.symlink_metadata().context(context)?.permissions()
@@ -1572,7 +2190,13 @@ fn copy_file(
         {
             use uucore::mode::get_umask;
 
-            let mut mode = permissions.mode();
+            // With `--no-preserve=mode`, new files get the same mode as
+            // any other new file.
+            let mut mode = if options.explicit_no_preserve_mode {
+                0o666
+            } else {
+                permissions.mode()
+            };
 
             // remove sticky bit, suid and gid bit
             const SPECIAL_PERMS_MASK: u32 = 0o7000;
@@ -1589,10 +2213,14 @@ fn copy_file(
     match options.copy_mode {
         CopyMode::Link => {
             if dest.exists() {
-                let backup_path =
-                    backup_control::get_backup_path(options.backup, dest, &options.backup_suffix);
+                let backup_path = backup_control::create_backup_path(
+                    options.backup,
+                    dest,
+                    &options.backup_suffix,
+                )?;
                 if let Some(backup_path) = backup_path {
                     backup_dest(dest, &backup_path)?;
+                    backup_path.commit();
                     fs::remove_file(dest)?;
                 }
                 if options.overwrite == OverwriteMode::Clobber(ClobberMode::Force) {
@@ -1614,9 +2242,9 @@ fn copy_file(
                 dest,
                 options,
                 context,
-                source_is_symlink,
-                source_is_fifo,
+                &source_metadata,
                 symlinked_files,
+                progress_bar.as_ref(),
             )?;
         }
         CopyMode::SymLink => {
@@ -1625,37 +2253,6 @@ fn copy_file(
             }
             symlink_file(source, dest, context, symlinked_files)?;
         }
-        CopyMode::Update => {
-            if dest.exists() {
-                let dest_metadata = fs::symlink_metadata(dest)?;
-
-                let src_time = source_metadata.modified()?;
-                let dest_time = dest_metadata.modified()?;
-                if src_time <= dest_time {
-                    return Ok(());
-                } else {
-                    copy_helper(
-                        source,
-                        dest,
-                        options,
-                        context,
-                        source_is_symlink,
-                        source_is_fifo,
-                        symlinked_files,
-                    )?;
-                }
-            } else {
-                copy_helper(
-                    source,
-                    dest,
-                    options,
-                    context,
-                    source_is_symlink,
-                    source_is_fifo,
-                    symlinked_files,
-                )?;
-            }
-        }
         CopyMode::AttrOnly => {
             OpenOptions::new()
                 .write(true)
@@ -1680,47 +2277,91 @@ fn copy_file(
     copy_attributes(source, dest, &options.attributes)?;
 
     if let Some(progress_bar) = progress_bar {
-        progress_bar.inc(fs::metadata(source)?.len());
+        progress_bar.finish_file(source, dest);
     }
 
+    // Only the data of regular files is transferred; links and special
+    // files are recreated instead.
+    let bytes = if matches!(options.copy_mode, CopyMode::Copy) && source_metadata.is_file() {
+        source_metadata.len()
+    } else {
+        0
+    };
+    summary.copied(bytes);
+
     Ok(())
 }
 
 /// Copy the file from `source` to `dest` either using the normal `fs::copy` or a
 /// copy-on-write scheme if --reflink is specified and the filesystem supports it.
+///
+/// The data that is copied is reported to `progress`, if given.
+#[allow(clippy::too_many_arguments)]
 fn copy_helper(
     source: &Path,
     dest: &Path,
     options: &Options,
     context: &str,
-    source_is_symlink: bool,
-    source_is_fifo: bool,
+    source_metadata: &fs::Metadata,
     symlinked_files: &mut HashSet<FileInformation>,
+    progress: Option<&Progress>,
 ) -> CopyResult<()> {
     if options.parents {
         let parent = dest.parent().unwrap_or(dest);
         fs::create_dir_all(parent)?;
     }
 
-    if source.as_os_str() == "/dev/null" {
-        /* workaround a limitation of fs::copy
-         * https://github.com/rust-lang/rust/issues/79390
-         */
-        File::create(dest).context(dest.display().to_string())?;
-    } else if source_is_fifo && options.recursive && !options.copy_contents {
+    let source_file_type = source_metadata.file_type();
+    let source_is_symlink = source_file_type.is_symlink();
+
+    #[cfg(unix)]
+    let source_is_fifo = source_file_type.is_fifo();
+    #[cfg(not(unix))]
+    let source_is_fifo = false;
+
+    #[cfg(unix)]
+    let source_is_device = source_file_type.is_block_device() || source_file_type.is_char_device();
+    #[cfg(not(unix))]
+    let source_is_device = false;
+
+    if source_is_fifo && options.recursive && !options.copy_contents {
         #[cfg(unix)]
         copy_fifo(dest, options.overwrite)?;
+    } else if source_is_device && options.recursive && !options.copy_contents {
+        #[cfg(unix)]
+        copy_device(dest, source_metadata, options.overwrite)?;
     } else if source_is_symlink {
         copy_link(source, dest, symlinked_files)?;
     } else {
+        // Like GNU cp, write through a destination that is a symbolic link,
+        // which the checks above have let through. It is resolved now, as
+        // the destination is opened without following links, so that one
+        // swapped in later doesn't redirect the copy.
+        let dest = if dest.is_symlink() {
+            Cow::Owned(fs::canonicalize(dest).context(context)?)
+        } else {
+            Cow::Borrowed(dest)
+        };
+        if source.as_os_str() == "/dev/null" {
+            /* workaround a limitation of fs::copy
+             * https://github.com/rust-lang/rust/issues/79390
+             */
+            create_dest(&dest, 0o666, false).context(context)?;
+            return Ok(());
+        }
         copy_on_write(
             source,
-            dest,
+            &dest,
             options.reflink_mode,
             options.sparse_mode,
+            options.bwlimit,
+            options.direct,
             context,
             #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
             source_is_fifo,
+            #[cfg(target_os = "macos")]
+            &options.attributes,
+            progress,
         )?;
     }
 
@@ -1739,7 +2380,40 @@ fn copy_fifo(dest: &Path, overwrite: OverwriteMode) -> CopyResult<()> {
     let name = CString::new(dest.as_os_str().as_bytes()).unwrap();
     let err = unsafe { mkfifo(name.as_ptr(), 0o666) };
     if err == -1 {
-        return Err(format!("cannot create fifo {}: File exists", dest.quote()).into());
+        return Err(translate!("error-fifo-exists", path = dest.quote()).into());
+    }
+    Ok(())
+}
+
+/// "Copies" a block or character device by creating a new device node with
+/// the same device number, rather than copying the data it provides.
+#[cfg(unix)]
+fn copy_device(
+    dest: &Path,
+    source_metadata: &fs::Metadata,
+    overwrite: OverwriteMode,
+) -> CopyResult<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    if dest.exists() {
+        overwrite.verify(dest)?;
+        fs::remove_file(dest)?;
+    }
+
+    let name = CString::new(dest.as_os_str().as_bytes()).unwrap();
+    #[allow(clippy::unnecessary_cast)]
+    let err = unsafe {
+        libc::mknod(
+            name.as_ptr(),
+            source_metadata.mode() as libc::mode_t,
+            source_metadata.rdev() as libc::dev_t,
+        )
+    };
+    if err == -1 {
+        return Err(Error::IoErrContext(
+            io::Error::last_os_error(),
+            translate!("error-cannot-create-special-file", path = dest.quote()),
+        ));
     }
     Ok(())
 }
@@ -1756,8 +2430,8 @@ fn copy_link(
             Some(name) => dest.join(name).into(),
             None => crash!(
                 EXIT_ERR,
-                "cannot stat {}: No such file or directory",
-                source.quote()
+                "{}",
+                translate!("error-no-such-file", path = source.quote())
             ),
         }
     } else {
@@ -1775,13 +2449,11 @@ fn copy_link(
 pub fn verify_target_type(target: &Path, target_type: &TargetType) -> CopyResult<()> {
     match (target_type, target.is_dir()) {
         (&TargetType::Directory, false) => {
-            Err(format!("target: {} is not a directory", target.quote()).into())
+            Err(translate!("error-target-not-a-directory", path = target.quote()).into())
+        }
+        (&TargetType::File, true) => {
+            Err(translate!("error-directory-with-non-directory", path = target.quote()).into())
         }
-        (&TargetType::File, true) => Err(format!(
-            "cannot overwrite directory {} with non-directory",
-            target.quote()
-        )
-        .into()),
         _ => Ok(()),
     }
 }
@@ -1802,6 +2474,53 @@ pub fn localize_to_target(root: &Path, source: &Path, target: &Path) -> CopyResu
     Ok(target.join(local_to_root))
 }
 
+/// Fail if the file system of `target` has less than `size` bytes available,
+/// which is what copying the data of the sources takes at most.
+///
+/// Links and attributes take next to no space, so nothing is checked when
+/// only those are copied. Clones and sparse files may need less than
+/// `size`, and files that are overwritten free some space, so the check
+/// errs on the side of caution.
+#[cfg(any(unix, windows))]
+fn check_free_space(target: &TargetSlice, size: u64, options: &Options) -> CopyResult<()> {
+    if !matches!(options.copy_mode, CopyMode::Copy) {
+        return Ok(());
+    }
+    // The target may not exist yet, in which case it will be created in
+    // the closest directory that does.
+    let existing = target
+        .ancestors()
+        .find(|path| path.exists())
+        .filter(|path| !path.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+    #[cfg(unix)]
+    let path = existing.as_os_str().as_bytes().to_vec();
+    #[cfg(windows)]
+    let path = existing;
+    let statfs = statfs(path).map_err(|e| {
+        Error::Error(translate!(
+            "error-free-space",
+            path = existing.quote(),
+            error = e
+        ))
+    })?;
+    let available = statfs.avail_blocks() * statfs.block_size() as u64;
+    if size > available {
+        return Err(Error::Error(translate!(
+            "error-not-enough-space",
+            path = existing.quote(),
+            needed = human_readable(size, HumanReadable::Binary),
+            available = human_readable(available, HumanReadable::Binary)
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn check_free_space(_target: &TargetSlice, _size: u64, _options: &Options) -> CopyResult<()> {
+    Err(Error::NotImplemented(options::CHECK_SPACE.to_string()))
+}
+
 /// Get the total size of a slice of files and directories.
 ///
 /// This function is much like the `du` utility, by recursively getting the sizes of files in directories.
@@ -1841,8 +2560,11 @@ fn disk_usage_directory(p: &Path) -> io::Result<u64> {
 #[cfg(test)]
 mod tests {
 
-    use crate::{aligned_ancestors, localize_to_target};
+    use crate::{aligned_ancestors, copy, localize_to_target, Observer, Options};
+    use std::fs;
     use std::path::Path;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
 
     #[test]
     fn test_cp_localize_to_target() {
@@ -1863,4 +2585,54 @@ mod tests {
         ];
         assert_eq!(actual, expected);
     }
+
+    #[derive(Default)]
+    struct Counter {
+        started: AtomicU64,
+        bytes: AtomicU64,
+        finished: AtomicU64,
+    }
+
+    impl Observer for Arc<Counter> {
+        fn file_started(&self, _source: &Path, _dest: &Path, _size: u64) {
+            self.started.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn bytes_copied(&self, n: u64) {
+            self.bytes.fetch_add(n, Ordering::Relaxed);
+        }
+
+        fn file_finished(&self, _source: &Path, _dest: &Path) {
+            self.finished.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_copy_with_observer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        fs::create_dir_all(dir.join("source/sub")).unwrap();
+        fs::write(dir.join("source/a"), vec![1; 100_000]).unwrap();
+        fs::write(dir.join("source/sub/b"), "hello").unwrap();
+
+        let counter = Arc::new(Counter::default());
+        let options = Options::builder()
+            .recursive(true)
+            .observer(counter.clone())
+            .build();
+        let result = copy(&[dir.join("source")], &dir.join("dest"), &options);
+        result.unwrap();
+        assert_eq!(fs::read(dir.join("dest/sub/b")).unwrap(), b"hello");
+        assert_eq!(counter.started.load(Ordering::Relaxed), 2);
+        assert_eq!(counter.finished.load(Ordering::Relaxed), 2);
+        assert_eq!(counter.bytes.load(Ordering::Relaxed), 100_005);
+    }
+
+    #[test]
+    fn test_options_builder_dereference() {
+        assert!(Options::builder().build().dereference);
+        assert!(!Options::builder().recursive(true).build().dereference);
+        let options = Options::builder().recursive(true).dereference(true).build();
+        assert!(options.dereference);
+    }
 }
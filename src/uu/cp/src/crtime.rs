@@ -0,0 +1,96 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+// spell-checker:ignore crtime crtimes attrlist setattrlist birthtime
+//! Set the creation (birth) time of a file, for `--preserve=crtimes`.
+//!
+//! Only Windows and macOS provide a way to change the creation time of an
+//! existing file. On other platforms, including Linux, the creation time
+//! is set by the kernel and can be read with `statx` but never written.
+use std::fs::Metadata;
+use std::io;
+use std::path::Path;
+
+/// Set the creation time of `dest` to that of the file described by
+/// `source_metadata`.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_creation_time(dest: &Path, source_metadata: &Metadata) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::macos::fs::MetadataExt;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dest.as_os_str().as_bytes())?;
+    // SAFETY: `attrlist` is a plain C struct, for which all zeroes is valid.
+    let mut attributes: libc::attrlist = unsafe { std::mem::zeroed() };
+    attributes.bitmapcount = libc::ATTR_BIT_MAP_COUNT;
+    attributes.commonattr = libc::ATTR_CMN_CRTIME;
+    let mut crtime = libc::timespec {
+        tv_sec: source_metadata.st_birthtime(),
+        tv_nsec: source_metadata.st_birthtime_nsec(),
+    };
+    // SAFETY: `path` is NUL-terminated and the attribute buffer holds
+    // exactly the single timespec requested by `attributes`.
+    let ret = unsafe {
+        libc::setattrlist(
+            path.as_ptr(),
+            &mut attributes as *mut libc::attrlist as *mut libc::c_void,
+            &mut crtime as *mut libc::timespec as *mut libc::c_void,
+            std::mem::size_of::<libc::timespec>(),
+            libc::FSOPT_NOFOLLOW,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Set the creation time of `dest` to that of the file described by
+/// `source_metadata`.
+#[cfg(windows)]
+pub(crate) fn set_creation_time(dest: &Path, source_metadata: &Metadata) -> io::Result<()> {
+    use std::fs::OpenOptions;
+    use std::os::windows::fs::{MetadataExt, OpenOptionsExt};
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Foundation::{FILETIME, HANDLE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        SetFileTime, FILE_FLAG_BACKUP_SEMANTICS, FILE_WRITE_ATTRIBUTES,
+    };
+
+    // Both are counted in 100-nanosecond intervals since January 1, 1601.
+    let created = source_metadata.creation_time();
+    let crtime = FILETIME {
+        dwLowDateTime: created as u32,
+        dwHighDateTime: (created >> 32) as u32,
+    };
+    // FILE_FLAG_BACKUP_SEMANTICS is needed to open directories.
+    let file = OpenOptions::new()
+        .access_mode(FILE_WRITE_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(dest)?;
+    // SAFETY: the handle stays valid while `file` is alive, and the null
+    // pointers leave the access and modification times unchanged.
+    let ret = unsafe {
+        SetFileTime(
+            file.as_raw_handle() as HANDLE,
+            &crtime,
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+    if ret == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Set the creation time of `dest` to that of the file described by
+/// `source_metadata`.
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(crate) fn set_creation_time(_dest: &Path, _source_metadata: &Metadata) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "setting the creation time is not supported on this platform",
+    ))
+}
@@ -2,17 +2,20 @@
 //  *
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
-// spell-checker:ignore ficlone reflink ftruncate pwrite fiemap
+// spell-checker:ignore bwlimit ficlone reflink ftruncate pwrite fiemap EINVAL ENXIO EOVERFLOW ENOTTY EOPNOTSUPP IOWR
 use std::fs::{File, OpenOptions};
-use std::io::Read;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::os::raw::c_int;
 use std::os::unix::fs::OpenOptionsExt;
-use std::os::unix::io::AsRawFd;
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::Path;
 
 use quick_error::ResultExt;
 
-use uucore::mode::get_umask;
+use uucore::fs::create_dest;
 
+use crate::bwlimit::{self, TokenBucket};
+use crate::progress::Progress;
 use crate::{CopyResult, ReflinkMode, SparseMode};
 
 // From /usr/include/linux/fs.h:
@@ -30,20 +33,35 @@ enum CloneFallback {
     /// Raise an error.
     Error,
 
-    /// Use [`std::fs::copy`].
-    FSCopy,
+    /// Use [`bwlimit::copy`], throttled to the given number of bytes
+    /// per second, if any.
+    FSCopy(Option<u64>),
+
+    /// Use [`direct_copy`], throttled to the given number of bytes per
+    /// second, if any.
+    DirectCopy(Option<u64>),
+
+    /// Use [`sparse_auto_copy`], throttled to the given number of bytes
+    /// per second, if any.
+    SparseAutoCopy(Option<u64>),
 }
 
 /// Use the Linux `ioctl_ficlone` API to do a copy-on-write clone.
 ///
-/// `fallback` controls what to do if the system call fails.
+/// `fallback` controls what to do if the system call fails. A fallback
+/// copy reports the data it writes to `progress`, if given.
 #[cfg(any(target_os = "linux", target_os = "android"))]
-fn clone<P>(source: P, dest: P, fallback: CloneFallback) -> std::io::Result<()>
+fn clone<P>(
+    source: P,
+    dest: P,
+    fallback: CloneFallback,
+    progress: Option<&Progress>,
+) -> std::io::Result<()>
 where
     P: AsRef<Path>,
 {
     let src_file = File::open(&source)?;
-    let dst_file = File::create(&dest)?;
+    let dst_file = create_dest(dest.as_ref(), 0o666, false)?;
     let src_fd = src_file.as_raw_fd();
     let dst_fd = dst_file.as_raw_fd();
     let result = unsafe { libc::ioctl(dst_fd, FICLONE!(), src_fd) };
@@ -52,53 +70,364 @@ where
     }
     match fallback {
         CloneFallback::Error => Err(std::io::Error::last_os_error()),
-        CloneFallback::FSCopy => std::fs::copy(source, dest).map(|_| ()),
+        CloneFallback::FSCopy(bwlimit) => {
+            bwlimit::copy(source.as_ref(), dest.as_ref(), bwlimit, progress).map(|_| ())
+        }
+        CloneFallback::DirectCopy(bwlimit) => {
+            direct_copy(source.as_ref(), dest.as_ref(), bwlimit, progress)
+        }
+        CloneFallback::SparseAutoCopy(bwlimit) => {
+            sparse_auto_copy(source.as_ref(), dest.as_ref(), bwlimit, progress)
+        }
     }
 }
 
 /// Perform a sparse copy from one file to another.
+///
+/// Only the data of `source`, as found by [`data_extents`], is read, and
+/// the blocks of it that are all zeros are not written, so they become
+/// holes in `dest`.
+///
+/// If `bwlimit` is given, the non-zero blocks are written at no more than
+/// that many bytes per second. If `progress` is given, it is advanced as
+/// the source is read, and by the size of the holes that are skipped.
 #[cfg(any(target_os = "linux", target_os = "android"))]
-fn sparse_copy<P>(source: P, dest: P) -> std::io::Result<()>
+fn sparse_copy<P>(
+    source: P,
+    dest: P,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> std::io::Result<()>
 where
     P: AsRef<Path>,
 {
+    use std::os::unix::fs::FileExt;
     use std::os::unix::prelude::MetadataExt;
 
     let mut src_file = File::open(source)?;
-    let dst_file = File::create(dest)?;
-    let dst_fd = dst_file.as_raw_fd();
+    let dst_file = create_dest(dest.as_ref(), 0o666, false)?;
 
-    let size: usize = src_file.metadata()?.size().try_into().unwrap();
-    if unsafe { libc::ftruncate(dst_fd, size.try_into().unwrap()) } < 0 {
-        return Err(std::io::Error::last_os_error());
-    }
+    let size = src_file.metadata()?.size();
+    dst_file.set_len(size)?;
 
     let blksize = dst_file.metadata()?.blksize();
     let mut buf: Vec<u8> = vec![0; blksize.try_into().unwrap()];
-    let mut current_offset: usize = 0;
-
-    // TODO Perhaps we can employ the "fiemap ioctl" API to get the
-    // file extent mappings:
-    // https://www.kernel.org/doc/html/latest/filesystems/fiemap.html
-    while current_offset < size {
-        let this_read = src_file.read(&mut buf)?;
-        if buf.iter().any(|&x| x != 0) {
-            unsafe {
-                libc::pwrite(
-                    dst_fd,
-                    buf.as_ptr() as *const libc::c_void,
-                    this_read,
-                    current_offset.try_into().unwrap(),
-                )
-            };
+    let mut bucket = bwlimit.map(TokenBucket::new);
+    let extents = data_extents(&src_file, size)?.unwrap_or_else(|| vec![(0, size)]);
+    let mut current_offset = 0;
+
+    for (data, length) in extents {
+        if let Some(progress) = progress {
+            progress.inc(data - current_offset);
         }
-        current_offset += this_read;
+        src_file.seek(SeekFrom::Start(data))?;
+        current_offset = data;
+        let end = data + length;
+        while current_offset < end {
+            let len = buf.len().min((end - current_offset) as usize);
+            let this_read = src_file.read(&mut buf[..len])?;
+            if this_read == 0 {
+                // The source was truncated while being copied.
+                break;
+            }
+            let block = &buf[..this_read];
+            if block.iter().any(|&x| x != 0) {
+                dst_file.write_all_at(block, current_offset)?;
+                if let Some(bucket) = &mut bucket {
+                    bucket.consume(this_read as u64);
+                }
+            }
+            current_offset += this_read as u64;
+            if let Some(progress) = progress {
+                progress.inc(this_read as u64);
+            }
+        }
+    }
+    if let Some(progress) = progress {
+        progress.inc(size.saturating_sub(current_offset));
     }
     Ok(())
 }
 
+/// Copy `source` to `dest`, recreating the holes of `source` rather than
+/// reading through them. This makes copying mostly empty files, like disk
+/// images, much faster.
+///
+/// The data of `source` is found with [`data_extents`]. Files that have
+/// all their blocks allocated, and files on systems that can't tell where
+/// the holes are, are copied with [`bwlimit::copy`] instead.
+///
+/// If `bwlimit` is given, the data is written at no more than that many
+/// bytes per second. If `progress` is given, it is advanced as the data
+/// is written, and by the size of the holes that are skipped.
+fn sparse_auto_copy(
+    source: &Path,
+    dest: &Path,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> io::Result<()> {
+    use std::os::unix::prelude::MetadataExt;
+
+    let mut src_file = File::open(source)?;
+    let metadata = src_file.metadata()?;
+    // Like GNU cp, only look for holes when fewer blocks are allocated
+    // than the size of the file needs.
+    if !metadata.is_file() || metadata.blocks() * 512 >= metadata.size() {
+        return bwlimit::copy(source, dest, bwlimit, progress).map(|_| ());
+    }
+    let size = metadata.size();
+    let extents = match data_extents(&src_file, size)? {
+        Some(extents) => extents,
+        None => return bwlimit::copy(source, dest, bwlimit, progress).map(|_| ()),
+    };
+
+    let dst_file = create_dest(dest, 0o666, false)?;
+    let mut writer = bwlimit::writer(&dst_file, bwlimit, progress);
+    let mut offset = 0;
+    for (data, length) in extents {
+        if let Some(progress) = progress {
+            progress.inc(data - offset);
+        }
+        src_file.seek(SeekFrom::Start(data))?;
+        (&dst_file).seek(SeekFrom::Start(data))?;
+        io::copy(&mut (&mut src_file).take(length), &mut writer)?;
+        offset = data + length;
+    }
+    drop(writer);
+    if let Some(progress) = progress {
+        progress.inc(size - offset);
+    }
+    // This also recreates the hole at the end of the file, if any.
+    dst_file.set_len(size)?;
+    dst_file.set_permissions(metadata.permissions())
+}
+
+/// The extents of the first `size` bytes of `file` that hold data, as
+/// `(offset, length)` pairs in increasing order. Whatever is between them
+/// reads as zeros without taking space: the holes of the file.
+///
+/// The extents are found with `SEEK_DATA` and `SEEK_HOLE`, or with the
+/// `FS_IOC_FIEMAP` ioctl on the file systems that don't support those.
+/// This is `None` if neither is supported.
+fn data_extents(file: &File, size: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    let fd = file.as_raw_fd();
+    match seek_extents(fd, size)? {
+        Some(extents) => Ok(Some(extents)),
+        None => fiemap_extents(fd, size),
+    }
+}
+
+/// [`data_extents`] with `SEEK_DATA` and `SEEK_HOLE`.
+fn seek_extents(fd: RawFd, size: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    let mut extents = Vec::new();
+    let mut offset = 0;
+    while offset < size {
+        let data = match seek(fd, offset, libc::SEEK_DATA) {
+            Ok(data) if data >= size => break,
+            Ok(data) => data,
+            // The rest of the file is a hole.
+            Err(e) if e.raw_os_error() == Some(libc::ENXIO) => break,
+            // `SEEK_DATA` is not supported.
+            Err(e) if offset == 0 && e.raw_os_error() == Some(libc::EINVAL) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let hole = seek(fd, data, libc::SEEK_HOLE)?.min(size);
+        extents.push((data, hole - data));
+        offset = hole;
+    }
+    Ok(Some(extents))
+}
+
+// From /usr/include/linux/fs.h:
+// #define FS_IOC_FIEMAP	_IOWR('f', 11, struct fiemap)
+const FS_IOC_FIEMAP: u32 = 0xC020660B;
+
+// From /usr/include/linux/fiemap.h:
+const FIEMAP_FLAG_SYNC: u32 = 0x1;
+const FIEMAP_EXTENT_LAST: u32 = 0x1;
+const FIEMAP_EXTENT_UNWRITTEN: u32 = 0x800;
+
+/// The number of extents asked for by each `FS_IOC_FIEMAP` call.
+const FIEMAP_EXTENT_COUNT: usize = 64;
+
+/// `struct fiemap_extent` of `linux/fiemap.h`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct FiemapExtent {
+    logical: u64,
+    physical: u64,
+    length: u64,
+    reserved64: [u64; 2],
+    flags: u32,
+    reserved: [u32; 3],
+}
+
+/// `struct fiemap` of `linux/fiemap.h`, with room for
+/// [`FIEMAP_EXTENT_COUNT`] extents.
+#[repr(C)]
+struct Fiemap {
+    start: u64,
+    length: u64,
+    flags: u32,
+    mapped_extents: u32,
+    extent_count: u32,
+    reserved: u32,
+    extents: [FiemapExtent; FIEMAP_EXTENT_COUNT],
+}
+
+/// [`data_extents`] with the `FS_IOC_FIEMAP` ioctl.
+///
+/// The unwritten extents, which are allocated but read as zeros, are left
+/// out like holes.
+fn fiemap_extents(fd: RawFd, size: u64) -> io::Result<Option<Vec<(u64, u64)>>> {
+    let mut extents: Vec<(u64, u64)> = Vec::new();
+    let mut start = 0;
+    while start < size {
+        // SAFETY: all zeros is a valid `Fiemap`, which only holds integers.
+        let mut map: Fiemap = unsafe { std::mem::zeroed() };
+        map.start = start;
+        map.length = size - start;
+        map.flags = FIEMAP_FLAG_SYNC;
+        map.extent_count = FIEMAP_EXTENT_COUNT as u32;
+        if unsafe { libc::ioctl(fd, FS_IOC_FIEMAP as _, &mut map) } < 0 {
+            let e = io::Error::last_os_error();
+            return match e.raw_os_error() {
+                Some(libc::ENOTTY | libc::EOPNOTSUPP) if start == 0 => Ok(None),
+                _ => Err(e),
+            };
+        }
+
+        let mapped = &map.extents[..map.mapped_extents as usize];
+        let last = match mapped.last() {
+            Some(last) => *last,
+            // The rest of the file is a hole.
+            None => break,
+        };
+        for extent in mapped {
+            let begin = extent.logical.max(start);
+            let end = (extent.logical + extent.length).min(size);
+            if extent.flags & FIEMAP_EXTENT_UNWRITTEN != 0 || begin >= end {
+                continue;
+            }
+            match extents.last_mut() {
+                // Merge contiguous extents, to copy them at once.
+                Some((offset, length)) if *offset + *length == begin => *length += end - begin,
+                _ => extents.push((begin, end - begin)),
+            }
+        }
+        if last.flags & FIEMAP_EXTENT_LAST != 0 {
+            break;
+        }
+        if last.logical + last.length <= start {
+            break;
+        }
+        start = last.logical + last.length;
+    }
+    Ok(Some(extents))
+}
+
+/// Reposition `fd` at `offset` according to `whence`, returning the new
+/// offset.
+fn seek(fd: RawFd, offset: u64, whence: c_int) -> io::Result<u64> {
+    let offset =
+        libc::off_t::try_from(offset).map_err(|_| io::Error::from_raw_os_error(libc::EOVERFLOW))?;
+    match unsafe { libc::lseek(fd, offset, whence) } {
+        -1 => Err(io::Error::last_os_error()),
+        offset => Ok(offset as u64),
+    }
+}
+
+/// The alignment of the buffer, file offsets and transfer sizes used for
+/// `O_DIRECT` copies. This is a multiple of the logical block size of all
+/// common block devices.
+const DIRECT_IO_ALIGN: usize = 4096;
+
+/// The size of the buffer used for `O_DIRECT` copies.
+const DIRECT_IO_BUF_SIZE: usize = 1024 * 1024;
+
+/// Copy `source` to `dest` with `O_DIRECT`, bypassing the page cache.
+///
+/// If the file system of either file does not support `O_DIRECT`, this
+/// falls back to a regular [`bwlimit::copy`].
+fn direct_copy(
+    source: &Path,
+    dest: &Path,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> std::io::Result<()> {
+    match try_direct_copy(source, dest, bwlimit, progress) {
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+            bwlimit::copy(source, dest, bwlimit, progress).map(|_| ())
+        }
+        result => result,
+    }
+}
+
+/// Copy `source` to `dest` with `O_DIRECT`.
+///
+/// `O_DIRECT` requires every transfer to be aligned, so the last, partial
+/// block is written padded with zeros and the destination is then
+/// truncated to the size of the source.
+fn try_direct_copy(
+    source: &Path,
+    dest: &Path,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> std::io::Result<()> {
+    let mut src_file = OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_DIRECT)
+        .open(source)?;
+    let permissions = src_file.metadata()?.permissions();
+    let dst_file = create_dest(dest, 0o666, false)?;
+    let fd = dst_file.as_raw_fd();
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 || unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_DIRECT) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut writer = bwlimit::writer(&dst_file, bwlimit, progress);
+
+    let mut storage = vec![0; DIRECT_IO_BUF_SIZE + DIRECT_IO_ALIGN];
+    let start = storage.as_ptr().align_offset(DIRECT_IO_ALIGN);
+    let buf = &mut storage[start..start + DIRECT_IO_BUF_SIZE];
+    let mut size: u64 = 0;
+    let mut filled = 0;
+    loop {
+        // A read can be short before the end of the file, so the buffer is
+        // filled until it is full or the end of the file is reached.
+        let this_read = src_file.read(&mut buf[filled..])?;
+        filled += this_read;
+        size += this_read as u64;
+        if this_read == 0 {
+            if filled > 0 {
+                // Only the last block can be partial.
+                let padded = (filled + DIRECT_IO_ALIGN - 1) / DIRECT_IO_ALIGN * DIRECT_IO_ALIGN;
+                buf[filled..padded].fill(0);
+                writer.write_all(&buf[..padded])?;
+            }
+            break;
+        }
+        if filled == buf.len() {
+            writer.write_all(buf)?;
+            filled = 0;
+        }
+    }
+    drop(writer);
+    dst_file.set_len(size)?;
+    dst_file.set_permissions(permissions)
+}
+
 /// Copy the contents of the given source FIFO to the given file.
-fn copy_fifo_contents<P>(source: P, dest: P) -> std::io::Result<u64>
+///
+/// If `bwlimit` is given, the contents are written at no more than that
+/// many bytes per second. If `progress` is given, it is advanced as the
+/// contents are written.
+fn copy_fifo_contents<P>(
+    source: P,
+    dest: P,
+    bwlimit: Option<u64>,
+    progress: Option<&Progress>,
+) -> std::io::Result<u64>
 where
     P: AsRef<Path>,
 {
@@ -121,13 +450,11 @@ where
     // TODO Update the code below to respect the case where
     // `--preserve=ownership` is not true.
     let mut src_file = File::open(&source)?;
-    let mode = 0o622 & !get_umask();
-    let mut dst_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .mode(mode)
-        .open(&dest)?;
-    let num_bytes_copied = std::io::copy(&mut src_file, &mut dst_file)?;
+    let dst_file = create_dest(dest.as_ref(), 0o622, false)?;
+    let num_bytes_copied = std::io::copy(
+        &mut src_file,
+        &mut bwlimit::writer(&dst_file, bwlimit, progress),
+    )?;
     dst_file.set_permissions(src_file.metadata()?.permissions())?;
     Ok(num_bytes_copied)
 }
@@ -138,27 +465,58 @@ where
 /// `source` is a FIFO (also known as a named pipe). In this case,
 /// copy-on-write is not possible, so we copy the contents using
 /// [`std::io::copy`].
+///
+/// With `--sparse=auto`, data that has to be copied rather than cloned is
+/// copied without reading through the holes of `source`, see
+/// [`sparse_auto_copy`].
+///
+/// If `bwlimit` is given, any data that has to be copied rather than
+/// cloned is written at no more than that many bytes per second. If
+/// `direct` is set, that data is copied with `O_DIRECT`. If `progress` is
+/// given, it is advanced as that data is written.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn copy_on_write(
     source: &Path,
     dest: &Path,
     reflink_mode: ReflinkMode,
     sparse_mode: SparseMode,
+    bwlimit: Option<u64>,
+    direct: bool,
     context: &str,
     source_is_fifo: bool,
+    progress: Option<&Progress>,
 ) -> CopyResult<()> {
     let result = match (reflink_mode, sparse_mode) {
-        (ReflinkMode::Never, SparseMode::Always) => sparse_copy(source, dest),
-        (ReflinkMode::Never, _) => std::fs::copy(source, dest).map(|_| ()),
-        (ReflinkMode::Auto, SparseMode::Always) => sparse_copy(source, dest),
+        (_, SparseMode::Always) if direct => {
+            return Err("--direct cannot be used with --sparse=always".into())
+        }
+        (ReflinkMode::Never, SparseMode::Always) => sparse_copy(source, dest, bwlimit, progress),
+        (ReflinkMode::Never, _) if direct && !source_is_fifo => {
+            direct_copy(source, dest, bwlimit, progress)
+        }
+        (ReflinkMode::Never, SparseMode::Auto) if !source_is_fifo => {
+            sparse_auto_copy(source, dest, bwlimit, progress)
+        }
+        (ReflinkMode::Never, _) => bwlimit::copy(source, dest, bwlimit, progress).map(|_| ()),
+        (ReflinkMode::Auto, SparseMode::Always) => sparse_copy(source, dest, bwlimit, progress),
 
         (ReflinkMode::Auto, _) => {
             if source_is_fifo {
-                copy_fifo_contents(source, dest).map(|_| ())
+                copy_fifo_contents(source, dest, bwlimit, progress).map(|_| ())
             } else {
-                clone(source, dest, CloneFallback::FSCopy)
+                let fallback = if direct {
+                    CloneFallback::DirectCopy(bwlimit)
+                } else if sparse_mode == SparseMode::Auto {
+                    CloneFallback::SparseAutoCopy(bwlimit)
+                } else {
+                    CloneFallback::FSCopy(bwlimit)
+                };
+                clone(source, dest, fallback, progress)
             }
         }
-        (ReflinkMode::Always, SparseMode::Auto) => clone(source, dest, CloneFallback::Error),
+        (ReflinkMode::Always, SparseMode::Auto) => {
+            clone(source, dest, CloneFallback::Error, progress)
+        }
         (ReflinkMode::Always, _) => {
             return Err("`--reflink=always` can be used only with --sparse=auto".into())
         }
@@ -166,3 +524,37 @@ pub(crate) fn copy_on_write(
     result.context(context)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{fiemap_extents, seek_extents};
+    use std::io::{Seek, SeekFrom, Write};
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn test_fiemap_extents_match_seek_extents() {
+        const SIZE: u64 = 16 * 1024 * 1024;
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(b"head").unwrap();
+        file.seek(SeekFrom::Start(SIZE / 2)).unwrap();
+        file.write_all(b"middle").unwrap();
+        file.set_len(SIZE).unwrap();
+        file.sync_all().unwrap();
+
+        let fd = file.as_raw_fd();
+        let seek = seek_extents(fd, SIZE).unwrap();
+        let fiemap = fiemap_extents(fd, SIZE).unwrap();
+
+        if let (Some(seek), Some(fiemap)) = (seek, fiemap) {
+            // Both cover the data; their granularity may differ.
+            for extents in [&seek, &fiemap] {
+                assert!(extents.iter().any(|&(o, l)| o == 0 && l >= 4));
+                assert!(extents
+                    .iter()
+                    .any(|&(o, l)| o <= SIZE / 2 && o + l >= SIZE / 2 + 6));
+            }
+            let data = |extents: &[(u64, u64)]| extents.iter().map(|&(_, l)| l).sum::<u64>();
+            assert!(data(&seek) < SIZE && data(&fiemap) < SIZE);
+        }
+    }
+}
@@ -2,32 +2,100 @@
 //  *
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
-// spell-checker:ignore reflink
-use std::ffi::CString;
+// spell-checker:ignore bwlimit reflink copyfile
+use std::ffi::{c_void, CStr, CString};
 use std::fs::{self, File};
 use std::io;
+use std::os::raw::{c_char, c_int};
 use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
+use std::ptr;
 
 use quick_error::ResultExt;
+use uucore::fs::create_dest;
 
-use crate::{CopyResult, ReflinkMode, SparseMode};
+use crate::bwlimit;
+use crate::progress::Progress;
+use crate::{Attributes, CopyResult, Preserve, ReflinkMode, SparseMode};
+
+// From copyfile.h
+const COPYFILE_ACL: u32 = 1 << 0;
+const COPYFILE_STAT: u32 = 1 << 1;
+const COPYFILE_XATTR: u32 = 1 << 2;
+const COPYFILE_DATA: u32 = 1 << 3;
+const COPYFILE_NOFOLLOW_DST: u32 = 1 << 19;
+
+extern "C" {
+    fn copyfile(from: *const c_char, to: *const c_char, state: *mut c_void, flags: u32) -> c_int;
+}
+
+/// The `copyfile(3)` flags for the metadata that `attributes` preserves.
+///
+/// Extended attributes include the resource fork and the Finder info of
+/// files, and the status includes their BSD flags, like `hidden`. These
+/// all get preserved by `-a`, for which these flags and `COPYFILE_DATA`
+/// make up `COPYFILE_ALL`.
+fn copyfile_flags(attributes: &Attributes) -> u32 {
+    let preserved = |p: &Preserve| matches!(p, Preserve::Yes { .. });
+    let mut flags = 0;
+    if preserved(&attributes.xattr) {
+        flags |= COPYFILE_XATTR;
+    }
+    if preserved(&attributes.acl) {
+        flags |= COPYFILE_ACL;
+    }
+    if preserved(&attributes.mode)
+        && preserved(&attributes.ownership)
+        && preserved(&attributes.timestamps)
+    {
+        flags |= COPYFILE_STAT;
+    }
+    flags
+}
+
+/// Copy what `flags` selects from `src` to `dst` with `copyfile(3)`.
+///
+/// Like [`uucore::fs::create_dest`], a symbolic link at `dst` is not
+/// followed.
+fn copy_file_natively(src: &CStr, dst: &CStr, flags: u32) -> io::Result<()> {
+    let flags = flags | COPYFILE_NOFOLLOW_DST;
+    if unsafe { copyfile(src.as_ptr(), dst.as_ptr(), ptr::null_mut(), flags) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
 
 /// Copies `source` to `dest` using copy-on-write if possible.
 ///
 /// The `source_is_fifo` flag must be set to `true` if and only if
 /// `source` is a FIFO (also known as a named pipe).
+///
+/// Files that can't be cloned are copied with `copyfile(3)`, along with
+/// the metadata that `attributes` preserves, see [`copyfile_flags`].
+///
+/// If `bwlimit` is given, any data that has to be copied rather than
+/// cloned is written at no more than that many bytes per second. If
+/// `progress` is given, it is advanced as that data is written. The data
+/// is then copied by hand, and only the metadata with `copyfile(3)`.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn copy_on_write(
     source: &Path,
     dest: &Path,
     reflink_mode: ReflinkMode,
     sparse_mode: SparseMode,
+    bwlimit: Option<u64>,
+    direct: bool,
     context: &str,
     source_is_fifo: bool,
+    attributes: &Attributes,
+    progress: Option<&Progress>,
 ) -> CopyResult<()> {
     if sparse_mode != SparseMode::Auto {
         return Err("--sparse is only supported on linux".to_string().into());
     }
+    if direct {
+        return Err("--direct is only supported on linux".to_string().into());
+    }
 
     // Extract paths in a form suitable to be passed to a syscall.
     // The unwrap() is safe because they come from the command-line and so contain non nul
@@ -74,10 +142,21 @@ pub(crate) fn copy_on_write(
             _ => {
                 if source_is_fifo {
                     let mut src_file = File::open(source)?;
-                    let mut dst_file = File::create(dest)?;
-                    io::copy(&mut src_file, &mut dst_file).context(context)?
+                    let dst_file = create_dest(dest, 0o666, false)?;
+                    io::copy(
+                        &mut src_file,
+                        &mut bwlimit::writer(dst_file, bwlimit, progress),
+                    )
+                    .context(context)?;
+                } else if bwlimit.is_some() || progress.is_some() {
+                    bwlimit::copy(source, dest, bwlimit, progress).context(context)?;
+                    let flags = copyfile_flags(attributes);
+                    if flags != 0 {
+                        copy_file_natively(&src, &dst, flags).context(context)?;
+                    }
                 } else {
-                    fs::copy(source, dest).context(context)?
+                    let flags = COPYFILE_DATA | copyfile_flags(attributes);
+                    copy_file_natively(&src, &dst, flags).context(context)?;
                 }
             }
         };
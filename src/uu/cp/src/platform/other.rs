@@ -2,32 +2,43 @@
 //  *
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
-// spell-checker:ignore reflink
-use std::fs;
+// spell-checker:ignore bwlimit reflink
 use std::path::Path;
 
 use quick_error::ResultExt;
 
+use crate::bwlimit;
+use crate::progress::Progress;
 use crate::{CopyResult, ReflinkMode, SparseMode};
 
 /// Copies `source` to `dest` for systems without copy-on-write
+///
+/// If `bwlimit` is given, the data is written at no more than that many
+/// bytes per second. If `progress` is given, it is advanced as the data
+/// is written.
 pub(crate) fn copy_on_write(
     source: &Path,
     dest: &Path,
     reflink_mode: ReflinkMode,
     sparse_mode: SparseMode,
+    bwlimit: Option<u64>,
+    direct: bool,
     context: &str,
+    progress: Option<&Progress>,
 ) -> CopyResult<()> {
     if reflink_mode != ReflinkMode::Never {
-        return Err("--reflink is only supported on linux and macOS"
+        return Err("--reflink is only supported on linux, macOS and Windows"
             .to_string()
             .into());
     }
     if sparse_mode != SparseMode::Auto {
         return Err("--sparse is only supported on linux".to_string().into());
     }
+    if direct {
+        return Err("--direct is only supported on linux".to_string().into());
+    }
 
-    fs::copy(source, dest).context(context)?;
+    bwlimit::copy(source, dest, bwlimit, progress).context(context)?;
 
     Ok(())
 }
@@ -0,0 +1,345 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore bwlimit reflink refcounting FSCTL winioctl winnt
+
+//! Windows specific parts of copying.
+//!
+//! Besides their content, NTFS files can have alternate data streams, which
+//! play the role of extended attributes, and attributes like hidden or
+//! system, which complement the read-only attribute of their mode.
+//!
+//! ReFS, which Dev Drive volumes are formatted with, can also clone the
+//! clusters of a file instead of copying them.
+use std::ffi::{OsStr, OsString};
+use std::fs::{self, File, Metadata};
+use std::io;
+use std::mem;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::os::windows::fs::MetadataExt;
+use std::os::windows::io::AsRawHandle;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use quick_error::ResultExt;
+use windows_sys::Win32::Foundation::{
+    ERROR_HANDLE_EOF, ERROR_INVALID_PARAMETER, HANDLE, INVALID_HANDLE_VALUE,
+};
+use windows_sys::Win32::Storage::FileSystem::{
+    FindClose, FindFirstStreamW, FindNextStreamW, FindStreamInfoStandard,
+    GetVolumeInformationByHandleW, SetFileAttributesW, FILE_ATTRIBUTE_ARCHIVE,
+    FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_NORMAL, FILE_ATTRIBUTE_NOT_CONTENT_INDEXED,
+    FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_SPARSE_FILE, FILE_ATTRIBUTE_SYSTEM,
+    WIN32_FIND_STREAM_DATA,
+};
+use windows_sys::Win32::System::IO::DeviceIoControl;
+
+use crate::bwlimit;
+use crate::progress::Progress;
+use crate::{CopyResult, ReflinkMode, SparseMode};
+
+// From winnt.h and winioctl.h
+const FILE_SUPPORTS_BLOCK_REFCOUNTING: u32 = 0x0800_0000;
+const FSCTL_SET_SPARSE: u32 = 0x0009_00C4;
+const FSCTL_GET_INTEGRITY_INFORMATION: u32 = 0x0009_027C;
+const FSCTL_SET_INTEGRITY_INFORMATION: u32 = 0x0009_C280;
+const FSCTL_DUPLICATE_EXTENTS_TO_FILE: u32 = 0x0009_8344;
+
+/// The most that is cloned by a single request, which has to stay below
+/// 4 GiB and be a whole number of clusters.
+const MAX_CLONE_CHUNK: u64 = 1 << 30;
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct FSCTL_GET_INTEGRITY_INFORMATION_BUFFER {
+    ChecksumAlgorithm: u16,
+    Reserved: u16,
+    Flags: u32,
+    ChecksumChunkSizeInBytes: u32,
+    ClusterSizeInBytes: u32,
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct FSCTL_SET_INTEGRITY_INFORMATION_BUFFER {
+    ChecksumAlgorithm: u16,
+    Reserved: u16,
+    Flags: u32,
+}
+
+#[repr(C)]
+#[allow(non_snake_case)]
+struct DUPLICATE_EXTENTS_DATA {
+    FileHandle: HANDLE,
+    SourceFileOffset: i64,
+    TargetFileOffset: i64,
+    ByteCount: i64,
+}
+
+/// Copies `source` to `dest`, cloning its clusters if `reflink_mode` asks
+/// for it and the file system supports it.
+///
+/// If `bwlimit` is given, any data that has to be copied rather than
+/// cloned is written at no more than that many bytes per second. If
+/// `progress` is given, it is advanced as that data is written.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn copy_on_write(
+    source: &Path,
+    dest: &Path,
+    reflink_mode: ReflinkMode,
+    sparse_mode: SparseMode,
+    bwlimit: Option<u64>,
+    direct: bool,
+    context: &str,
+    progress: Option<&Progress>,
+) -> CopyResult<()> {
+    if sparse_mode != SparseMode::Auto {
+        return Err("--sparse is only supported on linux".to_string().into());
+    }
+    if direct {
+        return Err("--direct is only supported on linux".to_string().into());
+    }
+
+    let result = match reflink_mode {
+        ReflinkMode::Never => bwlimit::copy(source, dest, bwlimit, progress).map(|_| ()),
+        ReflinkMode::Auto => clone(source, dest)
+            .or_else(|_| bwlimit::copy(source, dest, bwlimit, progress).map(|_| ())),
+        ReflinkMode::Always => clone(source, dest),
+    };
+    result.context(context)?;
+    Ok(())
+}
+
+/// Clone the clusters of `source` into `dest`.
+///
+/// Only ReFS supports this, and only between files of the same volume.
+fn clone(source: &Path, dest: &Path) -> io::Result<()> {
+    let src_file = File::open(source)?;
+    if !supports_block_cloning(&src_file)? {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the file system does not support block cloning",
+        ));
+    }
+    let src_metadata = src_file.metadata()?;
+    // SAFETY: The buffer is a plain C struct, for which all zeroes is valid.
+    let mut integrity: FSCTL_GET_INTEGRITY_INFORMATION_BUFFER = unsafe { mem::zeroed() };
+    fsctl::<(), _>(
+        &src_file,
+        FSCTL_GET_INTEGRITY_INFORMATION,
+        None,
+        Some(&mut integrity),
+    )?;
+
+    // The clusters of `dest` must be laid out like those of `source`, so
+    // both have to agree on being sparse and on their integrity streams.
+    let dst_file = File::create(dest)?;
+    if src_metadata.file_attributes() & FILE_ATTRIBUTE_SPARSE_FILE != 0 {
+        fsctl::<(), ()>(&dst_file, FSCTL_SET_SPARSE, None, None)?;
+    }
+    let set_integrity = FSCTL_SET_INTEGRITY_INFORMATION_BUFFER {
+        ChecksumAlgorithm: integrity.ChecksumAlgorithm,
+        Reserved: 0,
+        Flags: integrity.Flags,
+    };
+    fsctl::<_, ()>(
+        &dst_file,
+        FSCTL_SET_INTEGRITY_INFORMATION,
+        Some(&set_integrity),
+        None,
+    )?;
+    let len = src_metadata.len();
+    dst_file.set_len(len)?;
+
+    // Clusters are cloned whole, even the last one, which may extend past
+    // the end of the file.
+    let cluster_size = u64::from(integrity.ClusterSizeInBytes).max(1);
+    let mut offset = 0;
+    while offset < len {
+        let count = (len - offset).min(MAX_CLONE_CHUNK);
+        let count = (count + cluster_size - 1) / cluster_size * cluster_size;
+        let data = DUPLICATE_EXTENTS_DATA {
+            FileHandle: src_file.as_raw_handle() as HANDLE,
+            SourceFileOffset: offset as i64,
+            TargetFileOffset: offset as i64,
+            ByteCount: count as i64,
+        };
+        fsctl::<_, ()>(
+            &dst_file,
+            FSCTL_DUPLICATE_EXTENTS_TO_FILE,
+            Some(&data),
+            None,
+        )?;
+        offset += count;
+    }
+    Ok(())
+}
+
+/// Whether the volume of `file` can clone clusters, as ReFS can.
+fn supports_block_cloning(file: &File) -> io::Result<bool> {
+    let mut flags = 0;
+    let ok = unsafe {
+        GetVolumeInformationByHandleW(
+            file.as_raw_handle() as HANDLE,
+            ptr::null_mut(),
+            0,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut flags,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(flags & FILE_SUPPORTS_BLOCK_REFCOUNTING != 0)
+}
+
+/// Send the file system control code `code` about `file`, along with
+/// `input`, and receive its answer in `output`.
+fn fsctl<I, O>(
+    file: &File,
+    code: u32,
+    input: Option<&I>,
+    output: Option<&mut O>,
+) -> io::Result<()> {
+    let (input, input_size) = match input {
+        Some(input) => ((input as *const I).cast(), mem::size_of::<I>() as u32),
+        None => (ptr::null(), 0),
+    };
+    let (output, output_size) = match output {
+        Some(output) => ((output as *mut O).cast(), mem::size_of::<O>() as u32),
+        None => (ptr::null_mut(), 0),
+    };
+    let mut bytes_returned = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle() as HANDLE,
+            code,
+            input,
+            input_size,
+            output,
+            output_size,
+            &mut bytes_returned,
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Set the attributes of `dest` that can be changed, like hidden or system,
+/// to those of the file described by `source_metadata`.
+pub(crate) fn copy_file_attributes(source_metadata: &Metadata, dest: &Path) -> io::Result<()> {
+    let attributes = source_metadata.file_attributes()
+        & (FILE_ATTRIBUTE_READONLY
+            | FILE_ATTRIBUTE_HIDDEN
+            | FILE_ATTRIBUTE_SYSTEM
+            | FILE_ATTRIBUTE_ARCHIVE
+            | FILE_ATTRIBUTE_NOT_CONTENT_INDEXED);
+    let attributes = if attributes == 0 {
+        FILE_ATTRIBUTE_NORMAL
+    } else {
+        attributes
+    };
+    if unsafe { SetFileAttributesW(to_wide(dest).as_ptr(), attributes) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Copy the alternate data streams of `source` to `dest`.
+///
+/// A file system without alternate data streams, like FAT, simply has
+/// nothing to copy.
+pub(crate) fn copy_streams(source: &Path, dest: &Path) -> io::Result<()> {
+    let names = match stream_names(source) {
+        Err(e) if e.raw_os_error() == Some(ERROR_INVALID_PARAMETER as i32) => return Ok(()),
+        result => result?,
+    };
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    // A read-only file can't be written, and that includes its streams.
+    let permissions = fs::metadata(dest)?.permissions();
+    if permissions.readonly() {
+        let mut writable = permissions.clone();
+        writable.set_readonly(false);
+        fs::set_permissions(dest, writable)?;
+    }
+    let result = names.iter().try_for_each(|name| {
+        let mut source_stream = File::open(with_stream(source, name))?;
+        let mut dest_stream = File::create(with_stream(dest, name))?;
+        io::copy(&mut source_stream, &mut dest_stream).map(|_| ())
+    });
+    if permissions.readonly() {
+        fs::set_permissions(dest, permissions)?;
+    }
+    result
+}
+
+/// The names of the alternate data streams of `path`, like `:name:$DATA`,
+/// without the unnamed stream holding the content of the file.
+fn stream_names(path: &Path) -> io::Result<Vec<OsString>> {
+    let mut names = Vec::new();
+    // SAFETY: `WIN32_FIND_STREAM_DATA` is a plain C struct, for which all
+    // zeroes is valid.
+    let mut data: WIN32_FIND_STREAM_DATA = unsafe { mem::zeroed() };
+    let handle = unsafe {
+        FindFirstStreamW(
+            to_wide(path).as_ptr(),
+            FindStreamInfoStandard,
+            ptr::addr_of_mut!(data).cast(),
+            0,
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        let error = io::Error::last_os_error();
+        return if error.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+            // Not even an unnamed stream, as for most directories
+            Ok(names)
+        } else {
+            Err(error)
+        };
+    }
+
+    let result = loop {
+        let len = data
+            .cStreamName
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(data.cStreamName.len());
+        let name = OsString::from_wide(&data.cStreamName[..len]);
+        if name != "::$DATA" {
+            names.push(name);
+        }
+        if unsafe { FindNextStreamW(handle, ptr::addr_of_mut!(data).cast()) } == 0 {
+            let error = io::Error::last_os_error();
+            if error.raw_os_error() == Some(ERROR_HANDLE_EOF as i32) {
+                break Ok(names);
+            }
+            break Err(error);
+        }
+    };
+    unsafe { FindClose(handle) };
+    result
+}
+
+/// The path of the stream `name` of the file `path`.
+fn with_stream(path: &Path, name: &OsStr) -> PathBuf {
+    let mut path = path.as_os_str().to_os_string();
+    path.push(name);
+    path.into()
+}
+
+/// `path` as a NUL terminated wide string, as taken by the Windows API.
+fn to_wide(path: &Path) -> Vec<u16> {
+    path.as_os_str().encode_wide().chain(Some(0)).collect()
+}
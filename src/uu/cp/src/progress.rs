@@ -0,0 +1,179 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+//! The progress display of `cp --progress`, and the [`Observer`] through
+//! which programs embedding the copier follow it.
+//!
+//! The display is made of two bars: one for the file being copied,
+//! showing its name and how much of it has been written so far, and one
+//! for the whole run, showing the transfer rate and the estimated time
+//! left. Both are advanced from inside the data copy loops, so that a
+//! single large file shows live progress.
+use std::cell::Cell;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+/// Callbacks through which a program using [`copy`](crate::copy) as a
+/// library follows the copy, see [`OptionsBuilder::observer`].
+///
+/// All methods do nothing by default. Files that are only linked or
+/// skipped are not reported.
+///
+/// [`OptionsBuilder::observer`]: crate::OptionsBuilder::observer
+pub trait Observer: Send + Sync {
+    /// The data of `source`, which is `size` bytes long, is about to be
+    /// copied to `dest`.
+    fn file_started(&self, _source: &Path, _dest: &Path, _size: u64) {}
+
+    /// `n` more bytes of the current file have been written.
+    fn bytes_copied(&self, _n: u64) {}
+
+    /// `source` has been copied to `dest`, including its attributes.
+    fn file_finished(&self, _source: &Path, _dest: &Path) {}
+}
+
+/// The bars of a `cp --progress` run.
+struct Bars {
+    multi: MultiProgress,
+    file: ProgressBar,
+    total: ProgressBar,
+}
+
+impl Bars {
+    fn new(total_size: u64) -> Self {
+        let multi = MultiProgress::new();
+        let file = multi.add(
+            ProgressBar::new(0).with_style(
+                ProgressStyle::with_template("{wide_msg} [{bar:30}] {bytes:>9}/{total_bytes:9}")
+                    .unwrap()
+                    .progress_chars("=> "),
+            ),
+        );
+        let total = multi.add(
+            ProgressBar::new(total_size)
+                .with_style(
+                    ProgressStyle::with_template(
+                        "{prefix}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7} \
+                         {binary_bytes_per_sec:>11} ETA {eta}",
+                    )
+                    .unwrap(),
+                )
+                .with_prefix(uucore::util_name()),
+        );
+        total.tick();
+        Self { multi, file, total }
+    }
+}
+
+impl Drop for Bars {
+    fn drop(&mut self) {
+        // Only the summary of the whole run is left on the screen, showing
+        // how far it got even if it was cut short by an error.
+        self.file.finish_and_clear();
+        self.total.abandon();
+    }
+}
+
+/// Where the progress of a run is reported: the bars of `--progress`,
+/// an [`Observer`], or both.
+pub(crate) struct Progress {
+    bars: Option<Bars>,
+    observer: Option<Arc<dyn Observer>>,
+    /// The size of the current file.
+    size: Cell<u64>,
+    /// How much of the current file has been reported.
+    done: Cell<u64>,
+}
+
+impl Progress {
+    /// Report to `observer`, and show bars for a run that is expected to
+    /// copy `total_size` bytes if that is given.
+    pub(crate) fn new(total_size: Option<u64>, observer: Option<Arc<dyn Observer>>) -> Self {
+        Self {
+            bars: total_size.map(Bars::new),
+            observer,
+            size: Cell::new(0),
+            done: Cell::new(0),
+        }
+    }
+
+    /// Start reporting on the copy of `source` to `dest`, which is `size`
+    /// bytes long.
+    pub(crate) fn start_file(&self, source: &Path, dest: &Path, size: u64) {
+        self.size.set(size);
+        self.done.set(0);
+        if let Some(bars) = &self.bars {
+            bars.file.reset();
+            bars.file.set_length(size);
+            bars.file.set_message(source.display().to_string());
+        }
+        if let Some(observer) = &self.observer {
+            observer.file_started(source, dest, size);
+        }
+    }
+
+    /// Record that `n` more bytes of the current file have been written.
+    pub(crate) fn inc(&self, n: u64) {
+        self.done.set(self.done.get() + n);
+        if let Some(bars) = &self.bars {
+            bars.file.inc(n);
+            bars.total.inc(n);
+        }
+        if let Some(observer) = &self.observer {
+            observer.bytes_copied(n);
+        }
+    }
+
+    /// Record that the copy of `source` to `dest` is complete.
+    ///
+    /// Any part of the file that was not reported by the copy loop, for
+    /// example because it was cloned or hard linked rather than copied,
+    /// is accounted for here.
+    pub(crate) fn finish_file(&self, source: &Path, dest: &Path) {
+        let (size, done) = (self.size.get(), self.done.get());
+        if size > done {
+            self.inc(size - done);
+        }
+        if let Some(observer) = &self.observer {
+            observer.file_finished(source, dest);
+        }
+    }
+
+    /// Hide the bars while running `f`, so that its output doesn't get
+    /// mixed up with them.
+    pub(crate) fn suspend<F: FnOnce() -> R, R>(&self, f: F) -> R {
+        match &self.bars {
+            Some(bars) => bars.multi.suspend(f),
+            None => f(),
+        }
+    }
+}
+
+/// A writer that reports the bytes written to the inner writer to a
+/// [`Progress`].
+pub(crate) struct ProgressWriter<'a, W> {
+    inner: W,
+    progress: &'a Progress,
+}
+
+impl<'a, W> ProgressWriter<'a, W> {
+    pub(crate) fn new(inner: W, progress: &'a Progress) -> Self {
+        Self { inner, progress }
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.progress.inc(n as u64);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,161 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+// spell-checker:ignore SIGINFO
+//! The status that `cp` prints when it receives `SIGUSR1`, or `SIGINFO` on
+//! the systems that have it, like `dd` does: the file being copied, how
+//! much data was copied so far, and at which rate.
+//!
+//! The signals are waited for on a thread of their own, which prints the
+//! status straight away, even in the middle of a large file. What was
+//! written of that file so far is the size of its destination, so the copy
+//! loops don't have to report anything, and keep their fast paths.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+
+use uucore::human_size::{human_readable, HumanReadable};
+
+use crate::context_for;
+
+/// What the status is made of, shared with the thread printing it.
+#[cfg_attr(not(unix), allow(dead_code))]
+struct Shared {
+    start: Instant,
+    /// The data of the files that were copied completely.
+    bytes: AtomicU64,
+    /// The file being copied, and its destination.
+    current: Mutex<Option<(PathBuf, PathBuf)>>,
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+impl Shared {
+    fn line(&self) -> String {
+        let current = self.current.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut bytes = self.bytes.load(Ordering::Relaxed);
+        let mut line = String::new();
+        if let Some((source, dest)) = &*current {
+            bytes += fs::metadata(dest).map_or(0, |m| m.len());
+            line = format!("{}: ", context_for(source, dest));
+        }
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 {
+            (bytes as f64 / elapsed) as u64
+        } else {
+            0
+        };
+        format!(
+            "{line}{bytes} bytes ({}) copied, {elapsed:.3} s, {}/s",
+            human_readable(bytes, HumanReadable::Binary),
+            human_readable(rate, HumanReadable::Binary)
+        )
+    }
+}
+
+/// The status of a run, which is printed to stderr on request for as long
+/// as this is alive.
+pub(crate) struct Status {
+    shared: Arc<Shared>,
+    #[cfg(unix)]
+    handle: signal_hook::iterator::Handle,
+}
+
+impl Status {
+    /// Start printing the status on `SIGUSR1` and `SIGINFO`, or return
+    /// `None` if the signals can't be caught.
+    #[cfg(unix)]
+    pub(crate) fn install() -> Option<Self> {
+        use signal_hook::consts::signal::SIGUSR1;
+        use signal_hook::iterator::Signals;
+
+        #[cfg(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        let signals = [SIGUSR1, signal_hook::consts::signal::SIGINFO];
+        #[cfg(not(any(
+            target_os = "macos",
+            target_os = "ios",
+            target_os = "freebsd",
+            target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        let signals = [SIGUSR1];
+
+        let mut signals = Signals::new(signals).ok()?;
+        let handle = signals.handle();
+        let shared = Arc::new(Shared {
+            start: Instant::now(),
+            bytes: AtomicU64::new(0),
+            current: Mutex::new(None),
+        });
+        let reported = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .spawn(move || {
+                for _ in signals.forever() {
+                    eprintln!("{}: {}", uucore::util_name(), reported.line());
+                }
+            })
+            .ok()?;
+        Some(Self { shared, handle })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn install() -> Option<Self> {
+        None
+    }
+
+    /// Record that `source` starts being copied to `dest`.
+    pub(crate) fn start_file(&self, source: &Path, dest: &Path) {
+        *self
+            .shared
+            .current
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) =
+            Some((source.to_path_buf(), dest.to_path_buf()));
+    }
+
+    /// Record that the current file was copied, with `bytes` bytes of data.
+    pub(crate) fn finish_file(&self, bytes: u64) {
+        let mut current = self
+            .shared
+            .current
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.shared.bytes.fetch_add(bytes, Ordering::Relaxed);
+        *current = None;
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Status {
+    fn drop(&mut self) {
+        self.handle.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Shared;
+    use std::sync::atomic::AtomicU64;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    #[test]
+    fn test_line() {
+        let shared = Shared {
+            start: Instant::now(),
+            bytes: AtomicU64::new(3 << 20),
+            current: Mutex::new(None),
+        };
+        assert!(shared.line().starts_with("3145728 bytes (3.0M) copied, "));
+    }
+}
@@ -0,0 +1,123 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+//! The end-of-run report of `cp --summary`.
+//!
+//! The counters are kept for every run, as they are cheap, and are only
+//! printed when `--summary` is given.
+use std::cell::{Cell, RefCell};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::status::Status;
+use crate::Error;
+
+/// How many reasons for failures are kept, so that a run going wrong on a
+/// huge tree doesn't pile them up in memory.
+const MAX_REASONS: usize = 100;
+
+/// What a run of `cp` did to the files it was given.
+pub(crate) struct Summary {
+    start: Instant,
+    copied: Cell<u64>,
+    bytes: Cell<u64>,
+    skipped: Cell<u64>,
+    failed: Cell<u64>,
+    /// The reasons for the first failures.
+    reasons: RefCell<Vec<String>>,
+    /// The status printed on request while the run goes on.
+    status: Option<Status>,
+}
+
+impl Summary {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            copied: Cell::new(0),
+            bytes: Cell::new(0),
+            skipped: Cell::new(0),
+            failed: Cell::new(0),
+            reasons: RefCell::new(Vec::new()),
+            status: None,
+        }
+    }
+
+    /// Print the status of the run when it is asked for with a signal,
+    /// see [`Status`].
+    pub(crate) fn with_status(mut self) -> Self {
+        self.status = Status::install();
+        self
+    }
+
+    /// Record that `source` starts being copied to `dest`.
+    pub(crate) fn started(&self, source: &Path, dest: &Path) {
+        if let Some(status) = &self.status {
+            status.start_file(source, dest);
+        }
+    }
+
+    /// Record that a file was copied, transferring `bytes` bytes of data.
+    pub(crate) fn copied(&self, bytes: u64) {
+        self.copied.set(self.copied.get() + 1);
+        self.bytes.set(self.bytes.get() + bytes);
+        if let Some(status) = &self.status {
+            status.finish_file(bytes);
+        }
+    }
+
+    /// Record that a file was deliberately left alone.
+    pub(crate) fn skipped(&self) {
+        self.skipped.set(self.skipped.get() + 1);
+    }
+
+    /// Record that a file could not be copied because of `reason`.
+    pub(crate) fn failed(&self, reason: impl ToString) {
+        self.failed.set(self.failed.get() + 1);
+        let mut reasons = self.reasons.borrow_mut();
+        if reasons.len() < MAX_REASONS {
+            reasons.push(reason.to_string());
+        }
+    }
+
+    /// Record the error a source ended with. Skipped files, including the
+    /// ones that `--no-clobber` makes the run fail for, are not failures.
+    pub(crate) fn error(&self, error: &Error) {
+        match error {
+            Error::Skipped | Error::NotAllFilesCopied => self.skipped(),
+            _ => self.failed(error),
+        }
+    }
+
+    /// Print the report to stdout.
+    pub(crate) fn print(&self) {
+        let reasons = self.reasons.borrow();
+        println!("files copied: {}", self.copied.get());
+        println!("bytes transferred: {}", self.bytes.get());
+        println!("files skipped: {}", self.skipped.get());
+        println!("files failed: {}", self.failed.get());
+        for reason in reasons.iter() {
+            println!("  {reason}");
+        }
+        let untold = self.failed.get() - reasons.len() as u64;
+        if untold > 0 {
+            println!("  ... and {untold} more");
+        }
+        println!("elapsed time: {:.3}s", self.start.elapsed().as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Summary, MAX_REASONS};
+
+    #[test]
+    fn test_reasons_are_capped() {
+        let summary = Summary::new();
+        for i in 0..MAX_REASONS + 50 {
+            summary.failed(i);
+        }
+        assert_eq!(summary.failed.get(), MAX_REASONS as u64 + 50);
+        assert_eq!(summary.reasons.borrow().len(), MAX_REASONS);
+    }
+}
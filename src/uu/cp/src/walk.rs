@@ -0,0 +1,452 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+// spell-checker:ignore direntry readdir
+//! A depth-first walk of a directory tree, whose directories are read ahead
+//! by a pool of threads.
+//!
+//! On network file systems and other high-latency storage, listing a
+//! directory and looking at each of its entries takes round trips that
+//! dominate the time of `cp -R`. While the contents of a directory are
+//! being copied, the directories coming next are listed, and their entries
+//! looked at, in the background.
+//!
+//! The entries come in the same order as with `walkdir`, each directory
+//! before its contents, and the contents of a directory in the order the
+//! file system lists them. Only the reading is done ahead, and only so far,
+//! so that a huge tree doesn't end up in memory. Likewise, directories are
+//! read [`BATCH`] entries at a time, so that the walk takes about as much
+//! memory for a directory of millions of files as for a small one.
+use std::fmt;
+use std::fs::{self, FileType};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::vec;
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use uucore::fs::FileInformation;
+
+/// How many directories are read at the same time. The threads mostly wait
+/// on the storage, so there are more of them than there are CPUs on most
+/// machines.
+const READERS: usize = 8;
+
+/// How many directories are read ahead at most, besides the ones the walk
+/// is in.
+const READ_AHEAD: usize = 64;
+
+/// How many entries of a directory are read, and looked at, at once.
+const BATCH: usize = 1024;
+
+/// Entries of a directory, as read at once.
+struct Batch {
+    children: Vec<Result<Child, Error>>,
+    /// The rest of the directory, if there is more to read.
+    rest: Option<fs::ReadDir>,
+}
+
+/// A batch of a directory that is being read, or was.
+type Listing = Receiver<Result<Batch, Error>>;
+
+/// An entry of a directory, as seen by the thread that read it.
+struct Child {
+    path: PathBuf,
+    file_type: FileType,
+    is_symlink: bool,
+    /// The device and inode of a directory, to recognize loops, when
+    /// symbolic links are followed.
+    info: Option<FileInformation>,
+    /// The contents of a directory, if it is read ahead.
+    listing: Option<Listing>,
+}
+
+impl Child {
+    fn new(path: PathBuf, follow_links: bool) -> Result<Self, Error> {
+        // The type comes from the metadata rather than from the listing,
+        // so that the attributes of the file are at hand when it is copied.
+        let metadata = fs::symlink_metadata(&path).map_err(|e| Error::io(&path, e))?;
+        let is_symlink = metadata.file_type().is_symlink();
+        let file_type = if follow_links && is_symlink {
+            fs::metadata(&path)
+                .map_err(|e| Error::io(&path, e))?
+                .file_type()
+        } else {
+            metadata.file_type()
+        };
+        let info = if follow_links && file_type.is_dir() {
+            FileInformation::from_path(&path, true).ok()
+        } else {
+            None
+        };
+        Ok(Self {
+            path,
+            file_type,
+            is_symlink,
+            info,
+            listing: None,
+        })
+    }
+}
+
+/// List the next batch of `entries`, the entries of the directory `dir`, or
+/// of `dir` from the start if `entries` is `None`, looking at each of them.
+fn read_dir(dir: &Path, entries: Option<fs::ReadDir>, follow_links: bool) -> Result<Batch, Error> {
+    let mut entries = match entries {
+        Some(entries) => entries,
+        None => fs::read_dir(dir).map_err(|e| Error::io(dir, e))?,
+    };
+    let children: Vec<_> = entries
+        .by_ref()
+        .take(BATCH)
+        .map(|entry| {
+            let entry = entry.map_err(|e| Error::io(dir, e))?;
+            Child::new(entry.path(), follow_links)
+        })
+        .collect();
+    let rest = (children.len() == BATCH).then(|| entries);
+    Ok(Batch { children, rest })
+}
+
+/// A directory the walk is in.
+struct Level {
+    path: PathBuf,
+    info: Option<FileInformation>,
+    /// The next batch of the directory, if there is one.
+    listing: Option<Listing>,
+    children: vec::IntoIter<Result<Child, Error>>,
+}
+
+/// An entry of the tree.
+pub(crate) struct DirEntry {
+    path: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    is_symlink: bool,
+}
+
+impl DirEntry {
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The depth of the entry in the tree, 0 for the root.
+    pub(crate) fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The type of the file, which is the one a symbolic link points to
+    /// when links are followed.
+    pub(crate) fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub(crate) fn path_is_symlink(&self) -> bool {
+        self.is_symlink
+    }
+}
+
+/// An error of the walk, which goes on after it.
+#[derive(Debug)]
+pub(crate) enum Error {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    /// A symbolic link to one of the directories the walk is in.
+    Loop {
+        ancestor: PathBuf,
+        child: PathBuf,
+    },
+}
+
+impl Error {
+    fn io(path: &Path, error: io::Error) -> Self {
+        Self::Io {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        match self {
+            Self::Io { path, .. } => path,
+            Self::Loop { child, .. } => child,
+        }
+    }
+
+    pub(crate) fn is_loop(&self) -> bool {
+        matches!(self, Self::Loop { .. })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => {
+                write!(f, "IO error for operation on {}: {}", path.display(), error)
+            }
+            Self::Loop { ancestor, child } => write!(
+                f,
+                "File system loop found: {} points to an ancestor {}",
+                child.display(),
+                ancestor.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A walk of the tree under a root, which is an iterator over its entries.
+pub(crate) struct WalkDir {
+    root: Option<PathBuf>,
+    follow_links: bool,
+    /// The readers, or `None` if no thread could be started, in which case
+    /// directories are read when the walk gets to them.
+    pool: Option<ThreadPool>,
+    stack: Vec<Level>,
+    /// How many directories are read ahead.
+    ahead: usize,
+}
+
+impl WalkDir {
+    pub(crate) fn new(root: &Path, follow_links: bool) -> Self {
+        Self {
+            root: Some(root.to_path_buf()),
+            follow_links,
+            pool: ThreadPoolBuilder::new().num_threads(READERS).build().ok(),
+            stack: Vec::new(),
+            ahead: 0,
+        }
+    }
+
+    /// Don't go through the contents of the directory the walk is in, which
+    /// is the one last returned when it is a directory.
+    pub(crate) fn skip_current_dir(&mut self) {
+        self.pop();
+    }
+
+    /// Start reading the next batch of the directory `path`, from where
+    /// `entries` is, or from the start if `entries` is `None`.
+    fn read(&self, path: PathBuf, entries: Option<fs::ReadDir>) -> Listing {
+        let (sender, receiver) = mpsc::channel();
+        let follow_links = self.follow_links;
+        match &self.pool {
+            Some(pool) => pool.spawn(move || {
+                // The walk may be over, nobody is waiting then.
+                let _ = sender.send(read_dir(&path, entries, follow_links));
+            }),
+            None => {
+                let _ = sender.send(read_dir(&path, entries, follow_links));
+            }
+        }
+        receiver
+    }
+
+    fn pop(&mut self) {
+        if let Some(level) = self.stack.pop() {
+            let discarded = level
+                .children
+                .as_slice()
+                .iter()
+                .filter(|child| child.as_ref().map_or(false, |c| c.listing.is_some()))
+                .count();
+            self.ahead -= discarded;
+        }
+    }
+
+    fn start(&mut self, root: PathBuf) -> Result<DirEntry, Error> {
+        let metadata = fs::symlink_metadata(&root).map_err(|e| Error::io(&root, e))?;
+        let is_symlink = metadata.file_type().is_symlink();
+        let file_type = if self.follow_links && is_symlink {
+            fs::metadata(&root)
+                .map_err(|e| Error::io(&root, e))?
+                .file_type()
+        } else {
+            metadata.file_type()
+        };
+        // Like with `walkdir`, the root is gone through even if it is a
+        // symbolic link that is not followed.
+        let is_dir =
+            file_type.is_dir() || is_symlink && fs::metadata(&root).map_or(false, |m| m.is_dir());
+        if is_dir {
+            let info = if self.follow_links {
+                FileInformation::from_path(&root, true).ok()
+            } else {
+                None
+            };
+            self.stack.push(Level {
+                listing: Some(self.read(root.clone(), None)),
+                path: root.clone(),
+                info,
+                children: Vec::new().into_iter(),
+            });
+        }
+        Ok(DirEntry {
+            path: root,
+            depth: 0,
+            file_type,
+            is_symlink,
+        })
+    }
+
+    fn enter(&mut self, mut child: Child) -> Result<DirEntry, Error> {
+        let listing = child.listing.take();
+        if listing.is_some() {
+            self.ahead -= 1;
+        }
+        let depth = self.stack.len();
+        if child.file_type.is_dir() {
+            if let Some(info) = &child.info {
+                if let Some(ancestor) = self
+                    .stack
+                    .iter()
+                    .find(|level| level.info.as_ref() == Some(info))
+                {
+                    return Err(Error::Loop {
+                        ancestor: ancestor.path.clone(),
+                        child: child.path,
+                    });
+                }
+            }
+            let listing = listing.unwrap_or_else(|| self.read(child.path.clone(), None));
+            self.stack.push(Level {
+                path: child.path.clone(),
+                info: child.info,
+                listing: Some(listing),
+                children: Vec::new().into_iter(),
+            });
+        }
+        Ok(DirEntry {
+            path: child.path,
+            depth,
+            file_type: child.file_type,
+            is_symlink: child.is_symlink,
+        })
+    }
+
+    /// Wait for the next batch of the directory the walk is in, once it
+    /// went through the previous one, and start reading the directories in
+    /// it, and the batch after it, ahead.
+    fn receive(&mut self) -> Result<(), Error> {
+        let level = match self.stack.last_mut() {
+            Some(level) if level.children.as_slice().is_empty() => level,
+            _ => return Ok(()),
+        };
+        let listing = match level.listing.take() {
+            Some(listing) => listing,
+            None => return Ok(()),
+        };
+        let path = level.path.clone();
+        let Batch { mut children, rest } = listing.recv().unwrap_or_else(|_| {
+            Err(Error::io(
+                &path,
+                io::Error::new(io::ErrorKind::Other, "the directory could not be read"),
+            ))
+        })?;
+        if self.pool.is_some() {
+            for child in children.iter_mut().flatten() {
+                if self.ahead >= READ_AHEAD {
+                    break;
+                }
+                if child.file_type.is_dir() {
+                    child.listing = Some(self.read(child.path.clone(), None));
+                    self.ahead += 1;
+                }
+            }
+        }
+        let next = rest.map(|rest| self.read(path, Some(rest)));
+        if let Some(level) = self.stack.last_mut() {
+            level.children = children.into_iter();
+            level.listing = next;
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for WalkDir {
+    type Item = Result<DirEntry, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(root) = self.root.take() {
+            return Some(self.start(root));
+        }
+        loop {
+            if let Err(e) = self.receive() {
+                self.pop();
+                return Some(Err(e));
+            }
+            match self.stack.last_mut()?.children.next() {
+                Some(Ok(child)) => return Some(self.enter(child)),
+                Some(Err(e)) => return Some(Err(e)),
+                None => self.pop(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WalkDir, BATCH};
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parents_before_children() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        for dir in ["a/b/c", "a/d", "e"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        fs::write(root.join("a/b/f"), "").unwrap();
+
+        let mut seen: Vec<PathBuf> = Vec::new();
+        for entry in WalkDir::new(root, false) {
+            let entry = entry.unwrap();
+            assert_eq!(
+                entry.depth(),
+                entry.path().strip_prefix(root).unwrap().iter().count()
+            );
+            if let Some(parent) = entry.path().parent().filter(|_| entry.depth() > 0) {
+                // A directory comes before its contents.
+                assert!(seen.iter().any(|p| p == parent));
+            }
+            seen.push(entry.path().to_path_buf());
+        }
+        assert_eq!(seen.len(), 7);
+
+        let mut walk = WalkDir::new(root, false);
+        let mut seen = 0;
+        while let Some(entry) = walk.next() {
+            let entry = entry.unwrap();
+            if entry.path().ends_with("a") {
+                walk.skip_current_dir();
+            }
+            seen += 1;
+        }
+        assert_eq!(seen, 3);
+    }
+
+    #[test]
+    fn test_large_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("d")).unwrap();
+        // More than two batches, with a directory to go into in between.
+        let count = 2 * BATCH + 1;
+        for i in 0..count {
+            fs::write(root.join(i.to_string()), "").unwrap();
+        }
+        fs::write(root.join("d/f"), "").unwrap();
+
+        let mut seen: Vec<PathBuf> = WalkDir::new(root, false)
+            .map(|entry| entry.unwrap().path().to_path_buf())
+            .collect();
+        assert_eq!(seen.len(), count + 3);
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), count + 3);
+    }
+}
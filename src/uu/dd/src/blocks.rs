@@ -12,55 +12,71 @@ use crate::progress::ReadStat;
 const NEWLINE: u8 = b'\n';
 const SPACE: u8 = b' ';
 
-/// Split a slice into chunks, padding or truncating as necessary.
+/// The position in the current record of `conv=block` or `conv=unblock`.
 ///
-/// The slice `buf` is split on newlines, then each block is resized
-/// to `cbs` bytes, padding with spaces if necessary. This function
-/// expects the input bytes to be ASCII-encoded.
+/// Records are not aligned with the reads, so this is kept from one read
+/// to the next.
+#[derive(Default)]
+pub(crate) struct BlockState {
+    /// The number of bytes of the current record that have been read.
+    col: usize,
+    /// The spaces of the current record that are only written by
+    /// `conv=unblock` if something else follows them.
+    pending_spaces: usize,
+}
+
+/// Convert the newline-terminated records of `buf` to records of `cbs`
+/// bytes, padding them with spaces or truncating them as necessary.
 ///
-/// If `sync` is true and there has been at least one partial record
-/// read from the input (as indicated in `rstat`), then leave an
-/// all-spaces block at the end. Otherwise, remove the last block if
-/// it is all spaces.
-fn block(buf: &[u8], cbs: usize, sync: bool, rstat: &mut ReadStat) -> Vec<Vec<u8>> {
-    let mut blocks = buf
-        .split(|&e| e == NEWLINE)
-        .map(|split| split.to_vec())
-        .fold(Vec::new(), |mut blocks, mut split| {
-            if split.len() > cbs {
+/// This function expects the input bytes to be ASCII-encoded. The last
+/// record is only padded by [`finish_block_unblock()`], since it may go on
+/// in the next read.
+fn block(buf: &[u8], cbs: usize, state: &mut BlockState, rstat: &mut ReadStat) -> Vec<u8> {
+    let mut blocks = Vec::with_capacity(buf.len());
+    for &byte in buf {
+        if byte == NEWLINE {
+            if state.col < cbs {
+                blocks.resize(blocks.len() + cbs - state.col, SPACE);
+            }
+            state.col = 0;
+        } else {
+            if state.col == cbs {
                 rstat.records_truncated += 1;
+            } else if state.col < cbs {
+                blocks.push(byte);
             }
-            split.resize(cbs, SPACE);
-            blocks.push(split);
-
-            blocks
-        });
-
-    // If `sync` is true and there has been at least one partial
-    // record read from the input, then leave the all-spaces block at
-    // the end. Otherwise, remove it.
-    if let Some(last) = blocks.last() {
-        if (!sync || rstat.reads_partial == 0) && last.iter().all(|&e| e == SPACE) {
-            blocks.pop();
+            state.col += 1;
         }
     }
-
     blocks
 }
 
-/// Trims padding from each cbs-length partition of buf
-/// as specified by conv=unblock and cbs=N
-/// Expects ascii encoded data
-fn unblock(buf: &[u8], cbs: usize) -> Vec<u8> {
-    buf.chunks(cbs).fold(Vec::new(), |mut acc, block| {
-        if let Some(last_char_idx) = block.iter().rposition(|&e| e != SPACE) {
-            // Include text up to last space.
-            acc.extend(&block[..=last_char_idx]);
+/// Convert the records of `cbs` bytes of `buf` to newline-terminated
+/// records, removing the spaces at their end.
+///
+/// This function expects the input bytes to be ASCII-encoded.
+fn unblock(buf: &[u8], cbs: usize, state: &mut BlockState) -> Vec<u8> {
+    let mut lines = Vec::with_capacity(buf.len() + buf.len() / cbs + 1);
+    for &byte in buf {
+        if state.col == cbs {
+            lines.push(NEWLINE);
+            state.col = 0;
+            state.pending_spaces = 0;
+        }
+        state.col += 1;
+        if byte == SPACE {
+            state.pending_spaces += 1;
+        } else {
+            lines.resize(lines.len() + state.pending_spaces, SPACE);
+            state.pending_spaces = 0;
+            lines.push(byte);
         }
+    }
+    lines
+}
 
-        acc.push(NEWLINE);
-        acc
-    })
+fn apply_conversion(buf: Vec<u8>, ct: &ConversionTable) -> impl Iterator<Item = u8> + '_ {
+    buf.into_iter().map(|b| ct[b as usize])
 }
 
 /// Apply the specified conversion, blocking, and/or unblocking in the right order.
@@ -71,7 +87,8 @@ fn unblock(buf: &[u8], cbs: usize) -> Vec<u8> {
 ///
 /// `buf` is the buffer of input bytes to transform. This function
 /// mutates this input and also returns a new buffer of bytes
-/// representing the result of the transformation.
+/// representing the result of the transformation. `state` is where the
+/// previous buffers left the current record.
 ///
 /// `rstat` maintains a running total of the number of partial and
 /// complete blocks read before calling this function. In certain
@@ -80,58 +97,87 @@ fn unblock(buf: &[u8], cbs: usize) -> Vec<u8> {
 pub(crate) fn conv_block_unblock_helper(
     buf: Vec<u8>,
     mode: &ConversionMode,
+    state: &mut BlockState,
     rstat: &mut ReadStat,
 ) -> Vec<u8> {
-    fn apply_conversion(buf: Vec<u8>, ct: &ConversionTable) -> impl Iterator<Item = u8> + '_ {
-        buf.into_iter().map(|b| ct[b as usize])
-    }
-
     match mode {
         ConversionMode::ConvertOnly(ct) => apply_conversion(buf, ct).collect(),
-        ConversionMode::BlockThenConvert(ct, cbs, sync) => {
-            let blocks = block(&buf, *cbs, *sync, rstat);
-            blocks
-                .into_iter()
-                .flat_map(|block| apply_conversion(block, ct))
-                .collect()
+        ConversionMode::BlockThenConvert(ct, cbs) => {
+            let buf = block(&buf, *cbs, state, rstat);
+            apply_conversion(buf, ct).collect()
         }
-        ConversionMode::ConvertThenBlock(ct, cbs, sync) => {
+        ConversionMode::ConvertThenBlock(ct, cbs) => {
             let buf: Vec<_> = apply_conversion(buf, ct).collect();
-            block(&buf, *cbs, *sync, rstat)
-                .into_iter()
-                .flatten()
-                .collect()
+            block(&buf, *cbs, state, rstat)
         }
-        ConversionMode::BlockOnly(cbs, sync) => block(&buf, *cbs, *sync, rstat)
-            .into_iter()
-            .flatten()
-            .collect(),
+        ConversionMode::BlockOnly(cbs) => block(&buf, *cbs, state, rstat),
         ConversionMode::UnblockThenConvert(ct, cbs) => {
-            let buf = unblock(&buf, *cbs);
+            let buf = unblock(&buf, *cbs, state);
             apply_conversion(buf, ct).collect()
         }
         ConversionMode::ConvertThenUnblock(ct, cbs) => {
             let buf: Vec<_> = apply_conversion(buf, ct).collect();
-            unblock(&buf, *cbs)
+            unblock(&buf, *cbs, state)
         }
-        ConversionMode::UnblockOnly(cbs) => unblock(&buf, *cbs),
+        ConversionMode::UnblockOnly(cbs) => unblock(&buf, *cbs, state),
+    }
+}
+
+/// End the record that the input left unterminated, once it is exhausted.
+///
+/// With `conv=block`, it is padded with spaces, and with `conv=unblock`,
+/// a newline is added if anything was written.
+pub(crate) fn finish_block_unblock(mode: &ConversionMode, state: &BlockState) -> Vec<u8> {
+    if state.col == 0 {
+        return vec![];
+    }
+    let padding = |cbs: usize| vec![SPACE; cbs.saturating_sub(state.col)];
+    match mode {
+        ConversionMode::ConvertOnly(_) => vec![],
+        ConversionMode::BlockThenConvert(ct, cbs) => apply_conversion(padding(*cbs), ct).collect(),
+        ConversionMode::ConvertThenBlock(_, cbs) | ConversionMode::BlockOnly(cbs) => padding(*cbs),
+        ConversionMode::UnblockThenConvert(ct, _) => apply_conversion(vec![NEWLINE], ct).collect(),
+        ConversionMode::ConvertThenUnblock(..) | ConversionMode::UnblockOnly(_) => vec![NEWLINE],
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::blocks::{block, unblock};
+    use crate::blocks::{block, finish_block_unblock, unblock, BlockState};
+    use crate::datastructures::ConversionMode;
     use crate::progress::ReadStat;
 
     const NEWLINE: u8 = b'\n';
     const SPACE: u8 = b' ';
 
+    /// Block all of the input, and split the output into its records.
+    fn block_all(buf: &[u8], cbs: usize, rs: &mut ReadStat) -> Vec<Vec<u8>> {
+        let mut state = BlockState::default();
+        let mut blocks = block(buf, cbs, &mut state, rs);
+        blocks.extend(finish_block_unblock(
+            &ConversionMode::BlockOnly(cbs),
+            &state,
+        ));
+        blocks.chunks(cbs).map(<[u8]>::to_vec).collect()
+    }
+
+    /// Unblock all of the input.
+    fn unblock_all(buf: &[u8], cbs: usize) -> Vec<u8> {
+        let mut state = BlockState::default();
+        let mut lines = unblock(buf, cbs, &mut state);
+        lines.extend(finish_block_unblock(
+            &ConversionMode::UnblockOnly(cbs),
+            &state,
+        ));
+        lines
+    }
+
     #[test]
     fn block_test_no_nl() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8],]);
     }
@@ -140,7 +186,7 @@ mod tests {
     fn block_test_no_nl_short_record() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let res = block_all(&buf, 8, &mut rs);
 
         assert_eq!(
             res,
@@ -152,7 +198,7 @@ mod tests {
     fn block_test_no_nl_trunc() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, 4u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         // Commented section(s) should be truncated and appear for reference only.
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8 /*, 4u8*/],]);
@@ -165,7 +211,7 @@ mod tests {
         let buf = [
             0u8, 1u8, 2u8, 3u8, 4u8, NEWLINE, 0u8, 1u8, 2u8, 3u8, 4u8, NEWLINE, 5u8, 6u8, 7u8, 8u8,
         ];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(
             res,
@@ -185,7 +231,7 @@ mod tests {
     fn block_test_surrounded_nl() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, 5u8, 6u8, 7u8, 8u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let res = block_all(&buf, 8, &mut rs);
 
         assert_eq!(
             res,
@@ -202,7 +248,7 @@ mod tests {
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, NEWLINE, 5u8, 6u8, 7u8, 8u8, 9u8,
         ];
-        let res = block(&buf, 8, false, &mut rs);
+        let res = block_all(&buf, 8, &mut rs);
 
         assert_eq!(
             res,
@@ -220,7 +266,7 @@ mod tests {
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, 4u8, 5u8, 6u8, 7u8, NEWLINE, 8u8, 9u8,
         ];
-        let res = block(&buf, 8, false, &mut rs);
+        let res = block_all(&buf, 8, &mut rs);
 
         assert_eq!(
             res,
@@ -236,7 +282,7 @@ mod tests {
     fn block_test_end_nl_diff_cbs_block() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, 3u8],]);
     }
@@ -245,7 +291,7 @@ mod tests {
     fn block_test_end_nl_same_cbs_block() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(res, vec![vec![0u8, 1u8, 2u8, SPACE]]);
     }
@@ -254,7 +300,7 @@ mod tests {
     fn block_test_double_end_nl() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, NEWLINE, NEWLINE];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(
             res,
@@ -266,7 +312,7 @@ mod tests {
     fn block_test_start_nl() {
         let mut rs = ReadStat::default();
         let buf = [NEWLINE, 0u8, 1u8, 2u8, 3u8];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(
             res,
@@ -278,7 +324,7 @@ mod tests {
     fn block_test_double_surrounded_nl_no_trunc() {
         let mut rs = ReadStat::default();
         let buf = [0u8, 1u8, 2u8, 3u8, NEWLINE, NEWLINE, 4u8, 5u8, 6u8, 7u8];
-        let res = block(&buf, 8, false, &mut rs);
+        let res = block_all(&buf, 8, &mut rs);
 
         assert_eq!(
             res,
@@ -296,7 +342,7 @@ mod tests {
         let buf = [
             0u8, 1u8, 2u8, 3u8, NEWLINE, NEWLINE, 4u8, 5u8, 6u8, 7u8, 8u8,
         ];
-        let res = block(&buf, 4, false, &mut rs);
+        let res = block_all(&buf, 4, &mut rs);
 
         assert_eq!(
             res,
@@ -310,10 +356,36 @@ mod tests {
         assert_eq!(rs.records_truncated, 1);
     }
 
+    #[test]
+    fn block_test_record_across_reads() {
+        let mut rs = ReadStat::default();
+        let mut state = BlockState::default();
+        let mut res = block(&[0u8, 1u8], 4, &mut state, &mut rs);
+        res.extend(block(&[2u8, NEWLINE, 3u8, 4u8], 4, &mut state, &mut rs));
+        res.extend(block(&[5u8, 6u8, 7u8], 4, &mut state, &mut rs));
+        res.extend(finish_block_unblock(&ConversionMode::BlockOnly(4), &state));
+
+        assert_eq!(res, vec![0u8, 1u8, 2u8, SPACE, 3u8, 4u8, 5u8, 6u8]);
+        assert_eq!(rs.records_truncated, 1);
+    }
+
+    #[test]
+    fn unblock_test_record_across_reads() {
+        let mut state = BlockState::default();
+        let mut res = unblock(&[0u8, SPACE, SPACE], 4, &mut state);
+        res.extend(unblock(&[SPACE, 1u8, 2u8], 4, &mut state));
+        res.extend(finish_block_unblock(
+            &ConversionMode::UnblockOnly(4),
+            &state,
+        ));
+
+        assert_eq!(res, vec![0u8, NEWLINE, 1u8, 2u8, NEWLINE]);
+    }
+
     #[test]
     fn unblock_test_full_cbs() {
         let buf = [0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8];
-        let res = unblock(&buf, 8);
+        let res = unblock_all(&buf, 8);
 
         assert_eq!(res, vec![0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, NEWLINE],);
     }
@@ -321,7 +393,7 @@ mod tests {
     #[test]
     fn unblock_test_all_space() {
         let buf = [SPACE, SPACE, SPACE, SPACE, SPACE, SPACE, SPACE, SPACE];
-        let res = unblock(&buf, 8);
+        let res = unblock_all(&buf, 8);
 
         assert_eq!(res, vec![NEWLINE],);
     }
@@ -329,7 +401,7 @@ mod tests {
     #[test]
     fn unblock_test_decoy_spaces() {
         let buf = [0u8, SPACE, SPACE, SPACE, SPACE, SPACE, SPACE, 7u8];
-        let res = unblock(&buf, 8);
+        let res = unblock_all(&buf, 8);
 
         assert_eq!(
             res,
@@ -340,7 +412,7 @@ mod tests {
     #[test]
     fn unblock_test_strip_single_cbs() {
         let buf = [0u8, 1u8, 2u8, 3u8, SPACE, SPACE, SPACE, SPACE];
-        let res = unblock(&buf, 8);
+        let res = unblock_all(&buf, 8);
 
         assert_eq!(res, vec![0u8, 1u8, 2u8, 3u8, NEWLINE],);
     }
@@ -357,7 +429,7 @@ mod tests {
         .flatten()
         .collect::<Vec<_>>();
 
-        let res = unblock(&buf, 8);
+        let res = unblock_all(&buf, 8);
 
         let exp = vec![
             vec![0u8, NEWLINE],
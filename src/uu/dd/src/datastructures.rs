@@ -19,10 +19,10 @@ type Cbs = usize;
 #[derive(Debug, PartialEq)]
 pub(crate) enum ConversionMode {
     ConvertOnly(&'static ConversionTable),
-    BlockOnly(Cbs, bool),
+    BlockOnly(Cbs),
     UnblockOnly(Cbs),
-    BlockThenConvert(&'static ConversionTable, Cbs, bool),
-    ConvertThenBlock(&'static ConversionTable, Cbs, bool),
+    BlockThenConvert(&'static ConversionTable, Cbs),
+    ConvertThenBlock(&'static ConversionTable, Cbs),
     UnblockThenConvert(&'static ConversionTable, Cbs),
     ConvertThenUnblock(&'static ConversionTable, Cbs),
 }
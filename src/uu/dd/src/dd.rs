@@ -19,7 +19,7 @@ mod progress;
 use progress::{gen_prog_updater, ProgUpdate, ReadStat, StatusLevel, WriteStat};
 
 mod blocks;
-use blocks::conv_block_unblock_helper;
+use blocks::{conv_block_unblock_helper, finish_block_unblock, BlockState};
 
 mod numbers;
 
@@ -38,7 +38,7 @@ use std::time;
 use clap::{crate_version, Arg, Command};
 use gcd::Gcd;
 use uucore::display::Quotable;
-use uucore::error::{FromIo, UResult};
+use uucore::error::{set_exit_code, FromIo, UIoError, UResult};
 use uucore::help_section;
 use uucore::show_error;
 
@@ -53,6 +53,9 @@ struct Settings {
     outfile: Option<String>,
     ibs: usize,
     obs: usize,
+    /// Whether the output is written in blocks of `obs` bytes, rather than
+    /// in the blocks that were read.
+    buffered: bool,
     skip: u64,
     seek: u64,
     count: Option<Num>,
@@ -229,6 +232,7 @@ impl<'a, R: Read> Input<'a, R> {
             reads_partial,
             // Records are not truncated when filling.
             records_truncated: 0,
+            bytes_total: bytes_total as u128,
         })
     }
 
@@ -238,6 +242,7 @@ impl<'a, R: Read> Input<'a, R> {
     fn fill_blocks(&mut self, buf: &mut Vec<u8>, pad: u8) -> std::io::Result<ReadStat> {
         let mut reads_complete = 0;
         let mut reads_partial = 0;
+        let mut bytes_total = 0;
         let mut base_idx = 0;
 
         while base_idx < buf.len() {
@@ -248,11 +253,13 @@ impl<'a, R: Read> Input<'a, R> {
                 0 => break,
                 rlen if rlen < target_len => {
                     reads_partial += 1;
+                    bytes_total += rlen;
                     let padding = vec![pad; target_len - rlen];
                     buf.splice(base_idx + rlen..next_blk, padding.into_iter());
                 }
-                _ => {
+                rlen => {
                     reads_complete += 1;
+                    bytes_total += rlen;
                 }
             }
 
@@ -264,6 +271,7 @@ impl<'a, R: Read> Input<'a, R> {
             reads_complete,
             reads_partial,
             records_truncated: 0,
+            bytes_total: bytes_total as u128,
         })
     }
 
@@ -302,7 +310,10 @@ enum Dest {
 impl Dest {
     fn fsync(&mut self) -> io::Result<()> {
         match self {
-            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stdout(stdout) => {
+                stdout.flush()?;
+                sync_stdout(File::sync_all)
+            }
             Self::File(f, _) => {
                 f.flush()?;
                 f.sync_all()
@@ -312,7 +323,10 @@ impl Dest {
 
     fn fdatasync(&mut self) -> io::Result<()> {
         match self {
-            Self::Stdout(stdout) => stdout.flush(),
+            Self::Stdout(stdout) => {
+                stdout.flush()?;
+                sync_stdout(File::sync_data)
+            }
             Self::File(f, _) => {
                 f.flush()?;
                 f.sync_data()
@@ -339,6 +353,21 @@ impl Dest {
     }
 }
 
+/// Flush the file that stdout is to disk with `sync`.
+#[cfg(unix)]
+fn sync_stdout(sync: fn(&File) -> io::Result<()>) -> io::Result<()> {
+    use std::os::unix::io::FromRawFd;
+
+    // SAFETY: stdout stays open, since the file is never dropped.
+    let file = std::mem::ManuallyDrop::new(unsafe { File::from_raw_fd(libc::STDOUT_FILENO) });
+    sync(&file)
+}
+
+#[cfg(not(unix))]
+fn sync_stdout(_sync: fn(&File) -> io::Result<()>) -> io::Result<()> {
+    Ok(())
+}
+
 /// Decide whether the given buffer is all zeros.
 fn is_sparse(buf: &[u8]) -> bool {
     buf.iter().all(|&e| e == 0u8)
@@ -463,13 +492,29 @@ impl<'a> Output<'a> {
     }
 
     /// Flush the output to disk, if configured to do so.
-    fn sync(&mut self) -> std::io::Result<()> {
-        if self.settings.oconv.fsync {
-            self.dst.fsync()
-        } else if self.settings.oconv.fdatasync {
-            self.dst.fdatasync()
+    ///
+    /// On error, the name of the call that failed is returned with it.
+    fn sync(&mut self) -> Result<(), (&'static str, io::Error)> {
+        let mut fsync = self.settings.oconv.fsync;
+        if self.settings.oconv.fdatasync {
+            match self.dst.fdatasync() {
+                Ok(()) => {}
+                // Like with GNU dd, fsync is used where fdatasync isn't
+                // supported.
+                Err(e)
+                    if matches!(
+                        e.kind(),
+                        io::ErrorKind::InvalidInput | io::ErrorKind::Unsupported
+                    ) =>
+                {
+                    fsync = true;
+                }
+                Err(e) => return Err(("fdatasync", e)),
+            }
+        }
+        if fsync {
+            self.dst.fsync().map_err(|e| ("fsync", e))
         } else {
-            // Intentionally do nothing in this case.
             Ok(())
         }
     }
@@ -533,25 +578,38 @@ impl<'a> Output<'a> {
         // This is the max size needed.
         let mut buf = vec![BUF_INIT_BYTE; bsize];
 
+        // The output that doesn't fill a block yet, when the output is
+        // buffered, and where the input left the current record of
+        // conv=block or conv=unblock.
+        let mut obuf = Vec::with_capacity(self.settings.obs);
+        let mut block_state = BlockState::default();
+
         // The main read/write loop.
         //
         // Each iteration reads blocks from the input and writes
         // blocks to this output. Read/write statistics are updated on
         // each iteration and cumulative statistics are reported to
         // the progress reporting thread.
-        while below_count_limit(&i.settings.count, &rstat, &wstat) {
+        while below_count_limit(&i.settings.count, &rstat) {
             // Read a block from the input then write the block to the output.
             //
             // As an optimization, make an educated guess about the
             // best buffer size for reading based on the number of
             // blocks already read and the number of blocks remaining.
-            let loop_bsize =
-                calc_loop_bsize(&i.settings.count, &rstat, &wstat, i.settings.ibs, bsize);
-            let rstat_update = read_helper(&mut i, &mut buf, loop_bsize)?;
+            let loop_bsize = calc_loop_bsize(&i.settings.count, &rstat, i.settings.ibs, bsize);
+            let rstat_update = read_helper(&mut i, &mut buf, loop_bsize, &mut block_state)?;
             if rstat_update.is_empty() {
                 break;
             }
-            let wstat_update = self.write_blocks(&buf)?;
+            let wstat_update = if self.settings.buffered {
+                obuf.extend_from_slice(&buf);
+                let full_len = obuf.len() - obuf.len() % self.settings.obs;
+                let wstat_update = self.write_blocks(&obuf[..full_len])?;
+                obuf.drain(..full_len);
+                wstat_update
+            } else {
+                self.write_blocks(&buf)?
+            };
 
             // Update the read/write stats and inform the progress thread once per second.
             //
@@ -567,6 +625,12 @@ impl<'a> Output<'a> {
                 prog_tx.send(prog_update).unwrap_or(());
             }
         }
+
+        // Write what is left of the output as a partial block.
+        if let Some(mode) = &i.settings.iconv.mode {
+            obuf.extend(finish_block_unblock(mode, &block_state));
+        }
+        wstat += self.write_blocks(&obuf)?;
         self.finalize(rstat, wstat, start, &prog_tx, output_thread)
     }
 
@@ -579,8 +643,18 @@ impl<'a> Output<'a> {
         prog_tx: &mpsc::Sender<ProgUpdate>,
         output_thread: thread::JoinHandle<T>,
     ) -> std::io::Result<()> {
-        // Flush the output, if configured to do so.
-        self.sync()?;
+        self.dst.flush()?;
+
+        // Flush the output to disk, if configured to do so. Like with GNU
+        // dd, a failure doesn't keep the statistics from being printed.
+        if let Err((call, e)) = self.sync() {
+            let name = match &self.settings.outfile {
+                Some(name) => name.quote().to_string(),
+                None => "standard output".quote().to_string(),
+            };
+            show_error!("{} failed for {}: {}", call, name, UIoError::from(e));
+            set_exit_code(1);
+        }
 
         // Truncate the file to the final cursor location.
         //
@@ -650,6 +724,7 @@ fn read_helper<R: Read>(
     i: &mut Input<R>,
     buf: &mut Vec<u8>,
     bsize: usize,
+    block_state: &mut BlockState,
 ) -> std::io::Result<ReadStat> {
     // Local Helper Fns -------------------------------------------------
     fn perform_swab(buf: &mut [u8]) {
@@ -678,7 +753,7 @@ fn read_helper<R: Read>(
 
     match i.settings.iconv.mode {
         Some(ref mode) => {
-            *buf = conv_block_unblock_helper(buf.clone(), mode, &mut rstat);
+            *buf = conv_block_unblock_helper(buf.clone(), mode, block_state, &mut rstat);
             Ok(rstat)
         }
         None => Ok(rstat),
@@ -699,13 +774,7 @@ fn calc_bsize(ibs: usize, obs: usize) -> usize {
 
 // Calculate the buffer size appropriate for this loop iteration, respecting
 // a count=N if present.
-fn calc_loop_bsize(
-    count: &Option<Num>,
-    rstat: &ReadStat,
-    wstat: &WriteStat,
-    ibs: usize,
-    ideal_bsize: usize,
-) -> usize {
+fn calc_loop_bsize(count: &Option<Num>, rstat: &ReadStat, ibs: usize, ideal_bsize: usize) -> usize {
     match count {
         Some(Num::Blocks(rmax)) => {
             let rsofar = rstat.reads_complete + rstat.reads_partial;
@@ -714,7 +783,7 @@ fn calc_loop_bsize(
         }
         Some(Num::Bytes(bmax)) => {
             let bmax: u128 = (*bmax).try_into().unwrap();
-            let bremain: u128 = bmax - wstat.bytes_total;
+            let bremain: u128 = bmax - rstat.bytes_total;
             cmp::min(ideal_bsize as u128, bremain) as usize
         }
         None => ideal_bsize,
@@ -723,7 +792,7 @@ fn calc_loop_bsize(
 
 // Decide if the current progress is below a count=N limit or return
 // true if no such limit is set.
-fn below_count_limit(count: &Option<Num>, rstat: &ReadStat, wstat: &WriteStat) -> bool {
+fn below_count_limit(count: &Option<Num>, rstat: &ReadStat) -> bool {
     match count {
         Some(Num::Blocks(n)) => {
             let n = *n;
@@ -731,7 +800,7 @@ fn below_count_limit(count: &Option<Num>, rstat: &ReadStat, wstat: &WriteStat) -
         }
         Some(Num::Bytes(n)) => {
             let n = (*n).try_into().unwrap();
-            wstat.bytes_total <= n
+            rstat.bytes_total <= n
         }
         None => true,
     }
@@ -86,10 +86,84 @@ pub(crate) fn to_magnitude_and_suffix(n: u128, suffix_type: SuffixType) -> Strin
     }
 }
 
+/// Format a transfer rate, in bytes per second, like GNU dd.
+///
+/// The rate is always given in kB/s at least, with one decimal below 100.
+/// Without any time elapsed, it is infinite.
+pub(crate) fn rate_to_magnitude_and_suffix(bytes: u128, seconds: f64) -> String {
+    if seconds <= 0.0 {
+        return "Infinity B".to_string();
+    }
+    let mut quotient = bytes as f64 / seconds / 1000.0;
+    let mut i = 1;
+    while quotient >= 1000.0 && i < SI_SUFFIXES.len() - 1 {
+        quotient /= 1000.0;
+        i += 1;
+    }
+    let magnitude = format!("{quotient:.1}");
+    if magnitude.len() > 4 {
+        format!("{quotient:.0} {}", SI_SUFFIXES[i])
+    } else {
+        format!("{magnitude} {}", SI_SUFFIXES[i])
+    }
+}
+
+/// Format a number of seconds like the `%g` of `printf`: with six
+/// significant digits, without trailing zeros, and with an exponent when it
+/// is very small or large.
+pub(crate) fn format_seconds(seconds: f64) -> String {
+    if seconds == 0.0 {
+        return "0".to_string();
+    }
+    // The exponent once rounded to six significant digits.
+    let scientific = format!("{seconds:.5e}");
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let exponent: i32 = exponent.parse().unwrap();
+    if (-4..6).contains(&exponent) {
+        let fixed = format!("{seconds:.*}", (5 - exponent) as usize);
+        trim_fraction(&fixed).to_string()
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{sign}{:02}", trim_fraction(mantissa), exponent.abs())
+    }
+}
+
+/// Remove the trailing zeros of the fractional part of `number`.
+fn trim_fraction(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::numbers::{to_magnitude_and_suffix, SuffixType};
+    use crate::numbers::{
+        format_seconds, rate_to_magnitude_and_suffix, to_magnitude_and_suffix, SuffixType,
+    };
+
+    #[test]
+    fn test_rate_to_magnitude_and_suffix() {
+        assert_eq!(rate_to_magnitude_and_suffix(0, 1.0), "0.0 kB");
+        assert_eq!(rate_to_magnitude_and_suffix(499, 1.0), "0.5 kB");
+        assert_eq!(rate_to_magnitude_and_suffix(98_900, 1.0), "98.9 kB");
+        assert_eq!(rate_to_magnitude_and_suffix(182_300_000, 1.0), "182 MB");
+        assert_eq!(rate_to_magnitude_and_suffix(999_999, 1.0), "1000 kB");
+        assert_eq!(rate_to_magnitude_and_suffix(1_000, 0.0), "Infinity B");
+    }
+
+    #[test]
+    fn test_format_seconds() {
+        assert_eq!(format_seconds(0.0), "0");
+        assert_eq!(format_seconds(1.0), "1");
+        assert_eq!(format_seconds(0.000161097), "0.000161097");
+        assert_eq!(format_seconds(0.0000888620), "8.8862e-05");
+        assert_eq!(format_seconds(1.002468), "1.00247");
+        assert_eq!(format_seconds(123456.7), "123457");
+        assert_eq!(format_seconds(1234567.0), "1.23457e+06");
+    }
 
     #[test]
     fn test_to_magnitude_and_suffix_powers_of_1024() {
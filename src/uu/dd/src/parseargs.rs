@@ -34,6 +34,7 @@ pub enum ParseError {
     Unimplemented(String),
     BsOutOfRange(String),
     InvalidNumber(String),
+    AmbiguousSuffix(String),
 }
 
 /// Contains a temporary state during parsing of the arguments
@@ -43,6 +44,7 @@ pub struct Parser {
     outfile: Option<String>,
     ibs: usize,
     obs: usize,
+    bs: Option<usize>,
     cbs: Option<usize>,
     skip: Num,
     seek: Num,
@@ -58,6 +60,7 @@ impl Default for Parser {
         Self {
             ibs: 512,
             obs: 512,
+            bs: None,
             cbs: None,
             infile: None,
             outfile: None,
@@ -190,8 +193,14 @@ impl Parser {
             None
         };
 
+        // bs= overrides ibs= and obs=, wherever they are given.
+        let (ibs, obs) = match self.bs {
+            Some(bs) => (bs, bs),
+            None => (self.ibs, self.obs),
+        };
+
         let iconv = IConvFlags {
-            mode: conversion_mode(conversion_table, block, non_ascii, conv.sync),
+            mode: conversion_mode(conversion_table, block, non_ascii),
             swab: conv.swab,
             sync: if conv.sync {
                 if block.is_some() {
@@ -217,23 +226,29 @@ impl Parser {
         let skip = self
             .skip
             .force_bytes_if(self.iflag.skip_bytes)
-            .to_bytes(self.ibs as u64);
+            .to_bytes(ibs as u64);
 
         let seek = self
             .seek
             .force_bytes_if(self.oflag.seek_bytes)
-            .to_bytes(self.obs as u64);
+            .to_bytes(obs as u64);
 
         let count = self.count.map(|c| c.force_bytes_if(self.iflag.count_bytes));
 
+        // Like with GNU dd, the input blocks are only written as they are
+        // read when bs= is given and they are not converted. Otherwise,
+        // they are gathered into output blocks.
+        let buffered = self.bs.is_none() || iconv.mode.is_some() || iconv.swab;
+
         Ok(Settings {
             skip,
             seek,
             count,
             iconv,
             oconv,
-            ibs: self.ibs,
-            obs: self.obs,
+            ibs,
+            obs,
+            buffered,
             infile: self.infile,
             outfile: self.outfile,
             iflags: self.iflag,
@@ -246,11 +261,7 @@ impl Parser {
         match operand.split_once('=') {
             None => return Err(ParseError::UnrecognizedOperand(operand.to_string())),
             Some((k, v)) => match k {
-                "bs" => {
-                    let bs = self.parse_bytes(k, v)?;
-                    self.ibs = bs;
-                    self.obs = bs;
-                }
+                "bs" => self.bs = Some(self.parse_bytes(k, v)?),
                 "cbs" => self.cbs = Some(self.parse_bytes(k, v)?),
                 "conv" => self.parse_conv_flags(v)?,
                 "count" => self.count = Some(self.parse_n(v)?),
@@ -447,6 +458,9 @@ impl std::fmt::Display for ParseError {
             Self::InvalidNumber(arg) => {
                 write!(f, "invalid number: ‘{arg}’")
             }
+            Self::AmbiguousSuffix(reason) => {
+                write!(f, "invalid number: {reason}")
+            }
         }
     }
 }
@@ -509,6 +523,9 @@ fn parse_bytes_no_x(full: &str, s: &str) -> Result<u64, ParseError> {
             Err(ParseSizeError::InvalidSuffix(_) | ParseSizeError::ParseFailure(_)) => {
                 return Err(ParseError::InvalidNumber(full.to_string()))
             }
+            Err(ParseSizeError::AmbiguousSuffix(reason)) => {
+                return Err(ParseError::AmbiguousSuffix(reason))
+            }
             Err(ParseSizeError::SizeTooBig(_)) => {
                 return Err(ParseError::MultiplierStringOverflow(full.to_string()))
             }
@@ -597,15 +614,14 @@ fn conversion_mode(
     ctable: Option<&'static ConversionTable>,
     block: Option<Block>,
     is_ascii: bool,
-    is_sync: bool,
 ) -> Option<ConversionMode> {
     match (ctable, block) {
         (Some(ct), None) => Some(ConversionMode::ConvertOnly(ct)),
         (Some(ct), Some(Block::Block(cbs))) => {
             if is_ascii {
-                Some(ConversionMode::ConvertThenBlock(ct, cbs, is_sync))
+                Some(ConversionMode::ConvertThenBlock(ct, cbs))
             } else {
-                Some(ConversionMode::BlockThenConvert(ct, cbs, is_sync))
+                Some(ConversionMode::BlockThenConvert(ct, cbs))
             }
         }
         (Some(ct), Some(Block::Unblock(cbs))) => {
@@ -615,7 +631,7 @@ fn conversion_mode(
                 Some(ConversionMode::UnblockThenConvert(ct, cbs))
             }
         }
-        (None, Some(Block::Block(cbs))) => Some(ConversionMode::BlockOnly(cbs, is_sync)),
+        (None, Some(Block::Block(cbs))) => Some(ConversionMode::BlockOnly(cbs)),
         (None, Some(Block::Unblock(cbs))) => Some(ConversionMode::UnblockOnly(cbs)),
         (None, None) => None,
     }
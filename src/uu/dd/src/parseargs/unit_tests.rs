@@ -278,6 +278,22 @@ fn test_override_multiple_options() {
     assert_eq!(settings.count, Some(Num::Bytes(1024)));
 }
 
+#[test]
+fn test_bs_overrides_ibs_obs() {
+    for args in [["bs=4", "ibs=3", "obs=5"], ["ibs=3", "obs=5", "bs=4"]] {
+        let settings = Parser::new().parse(&args).unwrap();
+        assert_eq!(settings.ibs, 4);
+        assert_eq!(settings.obs, 4);
+        assert!(!settings.buffered);
+    }
+
+    let settings = Parser::new().parse(&["bs=4", "conv=ucase"]).unwrap();
+    assert!(settings.buffered);
+
+    let settings = Parser::new().parse(&["ibs=3", "obs=5"]).unwrap();
+    assert!(settings.buffered);
+}
+
 // // ----- IConvFlags/Output -----
 
 #[test]
@@ -13,14 +13,15 @@ use std::io::Write;
 use std::sync::mpsc;
 use std::time::Duration;
 
-use crate::numbers::{to_magnitude_and_suffix, SuffixType};
+use crate::numbers::{
+    format_seconds, rate_to_magnitude_and_suffix, to_magnitude_and_suffix, SuffixType,
+};
 
 // On Linux, we register a signal handler that prints progress updates.
 #[cfg(target_os = "linux")]
 use signal_hook::consts::signal;
 #[cfg(target_os = "linux")]
 use std::{
-    env,
     error::Error,
     sync::{
         atomic::{AtomicUsize, Ordering},
@@ -129,7 +130,7 @@ impl ProgUpdate {
     /// let mut cursor = Cursor::new(vec![]);
     /// let rewrite = false;
     /// prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
-    /// assert_eq!(cursor.get_ref(), b"0 bytes copied, 1.0 s, 0.0 B/s\n");
+    /// assert_eq!(cursor.get_ref(), b"0 bytes copied, 1 s, 0.0 kB/s\n");
     /// ```
     fn write_prog_line(&self, w: &mut impl Write, rewrite: bool) -> std::io::Result<()> {
         // The total number of bytes written as a string, in SI and IEC format.
@@ -139,9 +140,15 @@ impl ProgUpdate {
 
         // Compute the throughput (bytes per second) as a string.
         let duration = self.duration.as_secs_f64();
-        let safe_millis = std::cmp::max(1, self.duration.as_millis());
-        let rate = 1000 * (btotal / safe_millis);
-        let transfer_rate = to_magnitude_and_suffix(rate, SuffixType::Si);
+        let transfer_rate = rate_to_magnitude_and_suffix(btotal, duration);
+
+        // The progress line is updated every second, so it shows whole
+        // seconds, while the final one is as precise as `%g` allows.
+        let duration = if rewrite {
+            format!("{duration:.0}")
+        } else {
+            format_seconds(duration)
+        };
 
         // If we are rewriting the progress line, do write a carriage
         // return (`\r`) at the beginning and don't write a newline
@@ -154,19 +161,19 @@ impl ProgUpdate {
         match btotal {
             1 => write!(
                 w,
-                "{carriage_return}{btotal} byte copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} byte copied, {duration} s, {transfer_rate}/s{newline}",
             ),
             0..=999 => write!(
                 w,
-                "{carriage_return}{btotal} bytes copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes copied, {duration} s, {transfer_rate}/s{newline}",
             ),
             1000..=1023 => write!(
                 w,
-                "{carriage_return}{btotal} bytes ({btotal_metric}) copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes ({btotal_metric}) copied, {duration} s, {transfer_rate}/s{newline}",
             ),
             _ => write!(
                 w,
-                "{carriage_return}{btotal} bytes ({btotal_metric}, {btotal_bin}) copied, {duration:.1} s, {transfer_rate}/s{newline}",
+                "{carriage_return}{btotal} bytes ({btotal_metric}, {btotal_bin}) copied, {duration} s, {transfer_rate}/s{newline}",
             ),
         }
     }
@@ -196,7 +203,7 @@ impl ProgUpdate {
     /// let mut iter = cursor.get_ref().split(|v| *v == b'\n');
     /// assert_eq!(iter.next().unwrap(), b"0+0 records in");
     /// assert_eq!(iter.next().unwrap(), b"0+0 records out");
-    /// assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1.0 s, 0.0 B/s");
+    /// assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1 s, 0.0 kB/s");
     /// assert_eq!(iter.next().unwrap(), b"");
     /// assert!(iter.next().is_none());
     /// ```
@@ -269,6 +276,9 @@ pub(crate) struct ReadStat {
     ///
     /// A truncated record can only occur in `conv=block` mode.
     pub(crate) records_truncated: u32,
+
+    /// The total number of bytes read.
+    pub(crate) bytes_total: u128,
 }
 
 impl ReadStat {
@@ -279,6 +289,7 @@ impl ReadStat {
             reads_complete: complete,
             reads_partial: partial,
             records_truncated: truncated,
+            bytes_total: 0,
         }
     }
 
@@ -308,6 +319,7 @@ impl std::ops::AddAssign for ReadStat {
             reads_complete: self.reads_complete + other.reads_complete,
             reads_partial: self.reads_partial + other.reads_partial,
             records_truncated: self.records_truncated + other.records_truncated,
+            bytes_total: self.bytes_total + other.bytes_total,
         }
     }
 }
@@ -429,11 +441,8 @@ pub(crate) fn gen_prog_updater(
 ) -> impl Fn() {
     // TODO: SIGINFO: Trigger progress line reprint. BSD-style Linux only.
     const SIGUSR1_USIZE: usize = signal::SIGUSR1 as usize;
-    fn posixly_correct() -> bool {
-        env::var("POSIXLY_CORRECT").is_ok()
-    }
     fn register_linux_signal_handler(sigval: Arc<AtomicUsize>) -> Result<(), Box<dyn Error>> {
-        if !posixly_correct() {
+        if !uucore::posix::posixly_correct() {
             signal_hook::flag::register_usize(signal::SIGUSR1, sigval, SIGUSR1_USIZE)?;
         }
 
@@ -545,31 +554,24 @@ mod tests {
         let mut cursor = Cursor::new(vec![]);
         let rewrite = false;
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
-        // TODO The expected output string below is what our code
-        // produces today, but it does not match GNU dd:
-        //
-        //     $ : | dd
-        //     0 bytes copied, 7.9151e-05 s, 0.0 kB/s
-        //
-        // The throughput still does not match GNU dd.
-        assert_eq!(cursor.get_ref(), b"0 bytes copied, 1.0 s, 0.0 B/s\n");
+        assert_eq!(cursor.get_ref(), b"0 bytes copied, 1 s, 0.0 kB/s\n");
 
         let prog_update = prog_update_write(1);
         let mut cursor = Cursor::new(vec![]);
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
-        assert_eq!(cursor.get_ref(), b"1 byte copied, 1.0 s, 0.0 B/s\n");
+        assert_eq!(cursor.get_ref(), b"1 byte copied, 1 s, 0.0 kB/s\n");
 
         let prog_update = prog_update_write(999);
         let mut cursor = Cursor::new(vec![]);
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
-        assert_eq!(cursor.get_ref(), b"999 bytes copied, 1.0 s, 0.0 B/s\n");
+        assert_eq!(cursor.get_ref(), b"999 bytes copied, 1 s, 1.0 kB/s\n");
 
         let prog_update = prog_update_write(1000);
         let mut cursor = Cursor::new(vec![]);
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
         assert_eq!(
             cursor.get_ref(),
-            b"1000 bytes (1.0 kB) copied, 1.0 s, 1.0 kB/s\n"
+            b"1000 bytes (1.0 kB) copied, 1 s, 1.0 kB/s\n"
         );
 
         let prog_update = prog_update_write(1023);
@@ -577,7 +579,7 @@ mod tests {
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
         assert_eq!(
             cursor.get_ref(),
-            b"1023 bytes (1.0 kB) copied, 1.0 s, 1.0 kB/s\n"
+            b"1023 bytes (1.0 kB) copied, 1 s, 1.0 kB/s\n"
         );
 
         let prog_update = prog_update_write(1024);
@@ -585,7 +587,7 @@ mod tests {
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
         assert_eq!(
             cursor.get_ref(),
-            b"1024 bytes (1.0 kB, 1.0 KiB) copied, 1.0 s, 1.0 kB/s\n"
+            b"1024 bytes (1.0 kB, 1.0 KiB) copied, 1 s, 1.0 kB/s\n"
         );
     }
 
@@ -604,7 +606,7 @@ mod tests {
         let mut iter = cursor.get_ref().split(|v| *v == b'\n');
         assert_eq!(iter.next().unwrap(), b"0+0 records in");
         assert_eq!(iter.next().unwrap(), b"0+0 records out");
-        assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1.0 s, 0.0 B/s");
+        assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1 s, 0.0 kB/s");
         assert_eq!(iter.next().unwrap(), b"");
         assert!(iter.next().is_none());
     }
@@ -623,10 +625,10 @@ mod tests {
         prog_update.write_prog_line(&mut cursor, rewrite).unwrap();
         prog_update.write_transfer_stats(&mut cursor, true).unwrap();
         let mut iter = cursor.get_ref().split(|v| *v == b'\n');
-        assert_eq!(iter.next().unwrap(), b"\r0 bytes copied, 1.0 s, 0.0 B/s");
+        assert_eq!(iter.next().unwrap(), b"\r0 bytes copied, 1 s, 0.0 kB/s");
         assert_eq!(iter.next().unwrap(), b"0+0 records in");
         assert_eq!(iter.next().unwrap(), b"0+0 records out");
-        assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1.0 s, 0.0 B/s");
+        assert_eq!(iter.next().unwrap(), b"0 bytes copied, 1 s, 0.0 kB/s");
         assert_eq!(iter.next().unwrap(), b"");
         assert!(iter.next().is_none());
     }
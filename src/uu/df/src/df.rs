@@ -18,6 +18,7 @@ use uucore::display::Quotable;
 use uucore::error::FromIo;
 use uucore::error::{UError, UResult, USimpleError};
 use uucore::fsext::{read_fs_list, MountInfo};
+use uucore::human_size::SizeUnit;
 use uucore::parse_size::ParseSizeError;
 use uucore::{format_usage, show};
 
@@ -28,7 +29,7 @@ use std::ffi::OsString;
 use std::fmt;
 use std::path::Path;
 
-use crate::blocks::{read_block_size, BlockSize};
+use crate::blocks::{read_size_unit, BlockSize};
 use crate::columns::{Column, ColumnError};
 use crate::filesystem::Filesystem;
 use crate::table::Table;
@@ -186,24 +187,38 @@ impl Options {
             }
         }
 
+        let size_unit = read_size_unit(matches).map_err(|e| match e {
+            ParseSizeError::InvalidSuffix(s) | ParseSizeError::AmbiguousSuffix(s) => {
+                OptionsError::InvalidSuffix(s)
+            }
+            ParseSizeError::SizeTooBig(_) => OptionsError::BlockSizeTooLarge(
+                matches
+                    .get_one::<String>(OPT_BLOCKSIZE)
+                    .unwrap()
+                    .to_string(),
+            ),
+            ParseSizeError::ParseFailure(s) => OptionsError::InvalidBlockSize(s),
+        })?;
+        let human_readable = if matches.get_flag(OPT_HUMAN_READABLE_BINARY) {
+            Some(HumanReadable::Binary)
+        } else if matches.get_flag(OPT_HUMAN_READABLE_DECIMAL) {
+            Some(HumanReadable::Decimal)
+        } else if let SizeUnit::HumanReadable(mode) = size_unit {
+            Some(mode)
+        } else {
+            None
+        };
+
         Ok(Self {
             show_local_fs: matches.get_flag(OPT_LOCAL),
             show_all_fs: matches.get_flag(OPT_ALL),
             sync: matches.get_flag(OPT_SYNC),
-            block_size: read_block_size(matches).map_err(|e| match e {
-                ParseSizeError::InvalidSuffix(s) => OptionsError::InvalidSuffix(s),
-                ParseSizeError::SizeTooBig(_) => OptionsError::BlockSizeTooLarge(
-                    matches
-                        .get_one::<String>(OPT_BLOCKSIZE)
-                        .unwrap()
-                        .to_string(),
-                ),
-                ParseSizeError::ParseFailure(s) => OptionsError::InvalidBlockSize(s),
-            })?,
+            block_size: match size_unit {
+                SizeUnit::Blocks(size) => BlockSize::Bytes(size),
+                SizeUnit::HumanReadable(_) => BlockSize::default(),
+            },
             header_mode: {
-                if matches.get_flag(OPT_HUMAN_READABLE_BINARY)
-                    || matches.get_flag(OPT_HUMAN_READABLE_DECIMAL)
-                {
+                if human_readable.is_some() {
                     HeaderMode::HumanReadable
                 } else if matches.get_flag(OPT_PORTABILITY) {
                     HeaderMode::PosixPortability
@@ -215,15 +230,7 @@ impl Options {
                     HeaderMode::Default
                 }
             },
-            human_readable: {
-                if matches.get_flag(OPT_HUMAN_READABLE_BINARY) {
-                    Some(HumanReadable::Binary)
-                } else if matches.get_flag(OPT_HUMAN_READABLE_DECIMAL) {
-                    Some(HumanReadable::Decimal)
-                } else {
-                    None
-                }
-            },
+            human_readable,
             include,
             exclude,
             show_total: matches.get_flag(OPT_TOTAL),
@@ -10,9 +10,7 @@
 // spell-checker:ignore canonicalized
 use std::path::Path;
 
-#[cfg(unix)]
-use uucore::fsext::statfs;
-use uucore::fsext::{FsUsage, MountInfo};
+use uucore::fsext::{self, statfs, FsUsage, MountInfo};
 
 /// Summary representation of a filesystem.
 ///
@@ -39,10 +37,10 @@ pub(crate) struct Filesystem {
 
 /// Find the mount info that best matches a given filesystem path.
 ///
-/// This function returns the element of `mounts` on which `path` is
-/// mounted. If there are no matches, this function returns
-/// [`None`]. If there are two or more matches, then the single
-/// [`MountInfo`] with the longest mount directory is returned.
+/// This function returns the element of `mounts` whose device is
+/// `path`, if any, and otherwise the one on which `path` is mounted,
+/// as found by [`uucore::fsext::mount_info_from_path`]. If there are
+/// no matches, this function returns [`None`].
 ///
 /// If `canonicalize` is `true`, then the `path` is canonicalized
 /// before checking whether it matches any mount directories.
@@ -60,8 +58,6 @@ fn mount_info_from_path<P>(
 where
     P: AsRef<Path>,
 {
-    // TODO Refactor this function with `Stater::find_mount_point()`
-    // in the `stat` crate.
     let path = if canonicalize {
         path.as_ref().canonicalize().ok()?
     } else {
@@ -72,12 +68,7 @@ where
         .iter()
         .find(|mi| mi.dev_name.eq(&path.to_string_lossy()));
 
-    maybe_mount_point.or_else(|| {
-        mounts
-            .iter()
-            .filter(|mi| path.starts_with(&mi.mount_dir))
-            .max_by_key(|mi| mi.mount_dir.len())
-    })
+    maybe_mount_point.or_else(|| fsext::mount_info_from_path(mounts, &path))
 }
 
 impl Filesystem {
@@ -96,10 +87,7 @@ impl Filesystem {
                 mount_info.dev_id.clone()
             }
         };
-        #[cfg(unix)]
         let usage = FsUsage::new(statfs(_stat_path).ok()?);
-        #[cfg(windows)]
-        let usage = FsUsage::new(Path::new(&_stat_path));
         Some(Self {
             mount_info,
             usage,
@@ -177,43 +165,17 @@ mod tests {
         }
 
         #[test]
-        fn test_exact_match() {
-            let mounts = [mount_info("/foo")];
-            let actual = mount_info_from_path(&mounts, "/foo", false).unwrap();
-            assert!(mount_info_eq(actual, &mounts[0]));
-        }
-
-        #[test]
-        fn test_prefix_match() {
-            let mounts = [mount_info("/foo")];
-            let actual = mount_info_from_path(&mounts, "/foo/bar", false).unwrap();
-            assert!(mount_info_eq(actual, &mounts[0]));
-        }
-
-        #[test]
-        fn test_multiple_matches() {
+        fn test_mount_dir_match() {
             let mounts = [mount_info("/foo"), mount_info("/foo/bar")];
-            let actual = mount_info_from_path(&mounts, "/foo/bar", false).unwrap();
+            let actual = mount_info_from_path(&mounts, "/foo/bar/baz", false).unwrap();
             assert!(mount_info_eq(actual, &mounts[1]));
         }
 
-        #[test]
-        fn test_no_match() {
-            let mounts = [mount_info("/foo")];
-            assert!(mount_info_from_path(&mounts, "/bar", false).is_none());
-        }
-
-        #[test]
-        fn test_partial_match() {
-            let mounts = [mount_info("/foo/bar")];
-            assert!(mount_info_from_path(&mounts, "/foo/baz", false).is_none());
-        }
-
         #[test]
         fn test_dev_name_match() {
             let mut mount_info = mount_info("/foo");
             mount_info.dev_name = "/dev/sda2".to_string();
-            let mounts = [mount_info];
+            let mounts = [mount_info, self::mount_info("/")];
             let actual = mount_info_from_path(&mounts, "/dev/sda2", false).unwrap();
             assert!(mount_info_eq(actual, &mounts[0]));
         }
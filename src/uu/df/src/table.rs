@@ -9,11 +9,11 @@
 //! collection of data rows ([`Row`]), one per filesystem.
 use unicode_width::UnicodeWidthStr;
 
-use crate::blocks::{to_magnitude_and_suffix, SuffixType};
 use crate::columns::{Alignment, Column};
 use crate::filesystem::Filesystem;
 use crate::{BlockSize, Options};
 use uucore::fsext::{FsUsage, MountInfo};
+use uucore::human_size::{to_magnitude_and_suffix, SuffixType};
 
 use std::fmt;
 use std::ops::AddAssign;
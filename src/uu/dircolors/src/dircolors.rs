@@ -329,8 +329,14 @@ where
     table.insert("multihardlink", "mh");
     table.insert("clrtoeol", "cl");
 
-    let term = env::var("TERM").unwrap_or_else(|_| "none".to_owned());
-    let term = term.as_str();
+    // Like with GNU dircolors, an unset or empty TERM is "none", and an unset
+    // COLORTERM is empty, so that the `COLORTERM ?*` of the internal database
+    // only matches the terminals that advertise their colors with it.
+    let term = env::var("TERM")
+        .ok()
+        .filter(|term| !term.is_empty())
+        .unwrap_or_else(|| "none".to_owned());
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
 
     let mut state = ParseState::Global;
 
@@ -354,7 +360,8 @@ where
         let lower = key.to_lowercase();
 
         if lower == "term" || lower == "colorterm" {
-            if term.fnmatch(val) {
+            let value = if lower == "term" { &term } else { &colorterm };
+            if value.fnmatch(val) {
                 state = ParseState::Matched;
             } else if state != ParseState::Matched {
                 state = ParseState::Pass;
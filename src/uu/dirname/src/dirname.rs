@@ -28,9 +28,7 @@ fn get_long_usage() -> &'static str {
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_lossy();
 
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let separator = if matches.get_flag(options::ZERO) {
         "\0"
@@ -77,6 +75,7 @@ pub fn uu_app() -> Command {
         .about(ABOUT)
         .version(crate_version!())
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::ZERO)
@@ -11,7 +11,6 @@ use clap::ArgAction;
 use clap::{crate_version, Arg, ArgMatches, Command};
 use glob::Pattern;
 use std::collections::HashSet;
-use std::env;
 use std::fs;
 use std::fs::File;
 #[cfg(not(windows))]
@@ -34,6 +33,9 @@ use std::{error::Error, fmt::Display};
 use uucore::display::{print_verbatim, Quotable};
 use uucore::error::FromIo;
 use uucore::error::{UError, UResult};
+use uucore::fs::long_path;
+use uucore::fsext::file_system_id;
+use uucore::human_size::{self, HumanReadable, SizeUnit};
 use uucore::parse_glob;
 use uucore::parse_size::{parse_size, ParseSizeError};
 use uucore::{crash, format_usage, show, show_error, show_warning};
@@ -94,7 +96,6 @@ const USAGE: &str = "\
     {} [OPTION]... --files0-from=F";
 
 // TODO: Support Z & Y (currently limited by size of u64)
-const UNITS: [(char, u32); 6] = [('E', 6), ('P', 5), ('T', 4), ('G', 3), ('M', 2), ('K', 1)];
 
 struct Options {
     all: bool,
@@ -128,9 +129,9 @@ struct Stat {
 impl Stat {
     fn new(path: PathBuf, options: &Options) -> Result<Self> {
         let metadata = if options.dereference {
-            fs::metadata(&path)?
+            fs::metadata(long_path(&path))?
         } else {
-            fs::symlink_metadata(&path)?
+            fs::symlink_metadata(long_path(&path))?
         };
 
         #[cfg(not(windows))]
@@ -197,7 +198,7 @@ fn get_size_on_disk(path: &Path) -> u64 {
 
     // bind file so it stays in scope until end of function
     // if it goes out of scope the handle below becomes invalid
-    let file = match fs::File::open(path) {
+    let file = match fs::File::open(long_path(path)) {
         Ok(file) => file,
         Err(_) => return size_on_disk, // opening directories will fail
     };
@@ -225,7 +226,7 @@ fn get_size_on_disk(path: &Path) -> u64 {
 fn get_file_info(path: &Path) -> Option<FileInfo> {
     let mut result = None;
 
-    let file = match fs::File::open(path) {
+    let file = match fs::File::open(long_path(path)) {
         Ok(file) => file,
         Err(_) => return result,
     };
@@ -252,24 +253,14 @@ fn get_file_info(path: &Path) -> Option<FileInfo> {
     result
 }
 
-fn read_block_size(s: Option<&str>) -> u64 {
-    if let Some(s) = s {
-        parse_size(s)
-            .unwrap_or_else(|e| crash!(1, "{}", format_error_message(&e, s, options::BLOCK_SIZE)))
-    } else {
-        for env_var in ["DU_BLOCK_SIZE", "BLOCK_SIZE", "BLOCKSIZE"] {
-            if let Ok(env_size) = env::var(env_var) {
-                if let Ok(v) = parse_size(&env_size) {
-                    return v;
-                }
-            }
-        }
-        if env::var("POSIXLY_CORRECT").is_ok() {
-            512
-        } else {
-            1024
-        }
-    }
+fn read_size_unit(s: Option<&str>) -> SizeUnit {
+    human_size::resolve_size_unit(s, Some("DU_BLOCK_SIZE")).unwrap_or_else(|e| {
+        crash!(
+            1,
+            "{}",
+            format_error_message(&e, s.unwrap_or_default(), options::BLOCK_SIZE)
+        )
+    })
 }
 
 fn choose_size(matches: &ArgMatches, stat: &Stat) -> u64 {
@@ -284,33 +275,53 @@ fn choose_size(matches: &ArgMatches, stat: &Stat) -> u64 {
     }
 }
 
-// this takes `my_stat` to avoid having to stat files multiple times.
-// XXX: this should use the impl Trait return type when it is stabilized
+impl Options {
+    /// Whether the entries `depth` levels below the operands are printed.
+    fn is_printed(&self, depth: usize) -> bool {
+        self.max_depth.map_or(true, |max_depth| depth <= max_depth)
+    }
+}
+
+/// Compute the disk usage of `my_stat`, which is `depth` levels below an
+/// operand, and return it with the usage of its contents added.
+///
+/// The entries below it that are printed are pushed to `printed`, each after
+/// its contents, in the order they are read. The deeper ones still count in
+/// the totals of their ancestors, so with `--summarize` nothing is pushed.
+/// This takes `my_stat` to avoid having to stat files multiple times.
 fn du(
     mut my_stat: Stat,
     options: &Options,
     depth: usize,
     inodes: &mut HashSet<FileInfo>,
     exclude: &[Pattern],
-) -> Box<dyn DoubleEndedIterator<Item = Stat>> {
-    let mut stats = vec![];
-    let mut futures = vec![];
-
+    printed: &mut Vec<Stat>,
+) -> Stat {
     if my_stat.is_dir {
-        let read = match fs::read_dir(&my_stat.path) {
+        // With `--one-file-system`, the subdirectories on other file systems
+        // than this directory are skipped.
+        let file_system = if options.one_file_system {
+            file_system_id(long_path(&my_stat.path), true).ok()
+        } else {
+            None
+        };
+        let read = match fs::read_dir(long_path(&my_stat.path)) {
             Ok(read) => read,
             Err(e) => {
                 show!(
                     e.map_err_context(|| format!("cannot read directory {}", my_stat.path.quote()))
                 );
-                return Box::new(iter::once(my_stat));
+                return my_stat;
             }
         };
 
         'file_loop: for f in read {
             match f {
                 Ok(entry) => {
-                    match Stat::new(entry.path(), options) {
+                    // Name the entry after `my_stat.path` rather than the
+                    // extended-length path it may have been read through.
+                    let entry_path = my_stat.path.join(entry.file_name());
+                    match Stat::new(entry_path, options) {
                         Ok(this_stat) => {
                             // We have an exclude list
                             for pattern in exclude {
@@ -336,28 +347,35 @@ fn du(
                                 inodes.insert(inode);
                             }
                             if this_stat.is_dir {
-                                if options.one_file_system {
-                                    if let (Some(this_inode), Some(my_inode)) =
-                                        (this_stat.inode, my_stat.inode)
-                                    {
-                                        if this_inode.dev_id != my_inode.dev_id {
-                                            continue;
-                                        }
-                                    }
+                                if file_system.is_some()
+                                    && file_system_id(long_path(&this_stat.path), false).ok()
+                                        != file_system
+                                {
+                                    continue;
+                                }
+                                let this_stat =
+                                    du(this_stat, options, depth + 1, inodes, exclude, printed);
+                                if !options.separate_dirs {
+                                    my_stat.size += this_stat.size;
+                                    my_stat.blocks += this_stat.blocks;
+                                    my_stat.inodes += this_stat.inodes;
+                                }
+                                if options.is_printed(depth + 1) {
+                                    printed.push(this_stat);
                                 }
-                                futures.push(du(this_stat, options, depth + 1, inodes, exclude));
                             } else {
                                 my_stat.size += this_stat.size;
                                 my_stat.blocks += this_stat.blocks;
                                 my_stat.inodes += 1;
-                                if options.all {
-                                    stats.push(this_stat);
+                                if options.all && options.is_printed(depth + 1) {
+                                    printed.push(this_stat);
                                 }
                             }
                         }
-                        Err(e) => show!(
-                            e.map_err_context(|| format!("cannot access {}", entry.path().quote()))
-                        ),
+                        Err(e) => show!(e.map_err_context(|| format!(
+                            "cannot access {}",
+                            my_stat.path.join(entry.file_name()).quote()
+                        ))),
                     }
                 }
                 Err(error) => show_error!("{}", error),
@@ -365,50 +383,7 @@ fn du(
         }
     }
 
-    stats.extend(futures.into_iter().flatten().filter(|stat| {
-        if !options.separate_dirs && stat.path.parent().unwrap() == my_stat.path {
-            my_stat.size += stat.size;
-            my_stat.blocks += stat.blocks;
-            my_stat.inodes += stat.inodes;
-        }
-        options
-            .max_depth
-            .map_or(true, |max_depth| depth < max_depth)
-    }));
-    stats.push(my_stat);
-    Box::new(stats.into_iter())
-}
-
-fn convert_size_human(size: u64, multiplier: u64, _block_size: u64) -> String {
-    for &(unit, power) in &UNITS {
-        let limit = multiplier.pow(power);
-        if size >= limit {
-            return format!("{:.1}{}", (size as f64) / (limit as f64), unit);
-        }
-    }
-    if size == 0 {
-        return "0".to_string();
-    }
-    format!("{size}B")
-}
-
-fn convert_size_b(size: u64, _multiplier: u64, _block_size: u64) -> String {
-    format!("{}", ((size as f64) / (1_f64)).ceil())
-}
-
-fn convert_size_k(size: u64, multiplier: u64, _block_size: u64) -> String {
-    format!("{}", ((size as f64) / (multiplier as f64)).ceil())
-}
-
-fn convert_size_m(size: u64, multiplier: u64, _block_size: u64) -> String {
-    format!(
-        "{}",
-        ((size as f64) / ((multiplier * multiplier) as f64)).ceil()
-    )
-}
-
-fn convert_size_other(size: u64, _multiplier: u64, block_size: u64) -> String {
-    format!("{}", ((size as f64) / (block_size as f64)).ceil())
+    my_stat
 }
 
 #[derive(Debug)]
@@ -521,7 +496,8 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let options = Options {
         all: matches.get_flag(options::ALL),
-        max_depth,
+        // Only the operands are printed with --summarize.
+        max_depth: if summarize { Some(0) } else { max_depth },
         total: matches.get_flag(options::TOTAL),
         separate_dirs: matches.get_flag(options::SEPARATE_DIRS),
         one_file_system: matches.get_flag(options::ONE_FILE_SYSTEM),
@@ -545,40 +521,33 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         show_warning!("options --apparent-size and -b are ineffective with --inodes");
     }
 
-    let block_size = read_block_size(
-        matches
-            .get_one::<String>(options::BLOCK_SIZE)
-            .map(|s| s.as_str()),
-    );
-
     let threshold = matches.get_one::<String>(options::THRESHOLD).map(|s| {
         Threshold::from_str(s)
             .unwrap_or_else(|e| crash!(1, "{}", format_error_message(&e, s, options::THRESHOLD)))
     });
 
-    let multiplier: u64 = if matches.get_flag(options::SI) {
-        1000
+    let size_unit = if matches.get_flag(options::SI) {
+        SizeUnit::HumanReadable(HumanReadable::Decimal)
+    } else if matches.get_flag(options::HUMAN_READABLE) {
+        SizeUnit::HumanReadable(HumanReadable::Binary)
+    } else if matches.get_flag(options::BYTES) {
+        SizeUnit::Blocks(1)
+    } else if matches.get_flag(options::BLOCK_SIZE_1K) {
+        SizeUnit::Blocks(1024)
+    } else if matches.get_flag(options::BLOCK_SIZE_1M) {
+        SizeUnit::Blocks(1024 * 1024)
     } else {
-        1024
-    };
-    let convert_size_fn = {
-        if matches.get_flag(options::HUMAN_READABLE) || matches.get_flag(options::SI) {
-            convert_size_human
-        } else if matches.get_flag(options::BYTES) {
-            convert_size_b
-        } else if matches.get_flag(options::BLOCK_SIZE_1K) {
-            convert_size_k
-        } else if matches.get_flag(options::BLOCK_SIZE_1M) {
-            convert_size_m
-        } else {
-            convert_size_other
-        }
+        read_size_unit(
+            matches
+                .get_one::<String>(options::BLOCK_SIZE)
+                .map(|s| s.as_str()),
+        )
     };
     let convert_size = |size: u64| {
         if options.inodes {
             size.to_string()
         } else {
-            convert_size_fn(size, multiplier, block_size)
+            size_unit.format(size)
         }
     };
 
@@ -616,12 +585,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             if let Some(inode) = stat.inode {
                 inodes.insert(inode);
             }
-            let iter = du(stat, &options, 0, &mut inodes, &excludes);
+            let mut printed = vec![];
+            let total = du(stat, &options, 0, &mut inodes, &excludes, &mut printed);
+            let len = printed.len() + 1;
 
-            // Sum up all the returned `Stat`s and display results
-            let (_, len) = iter.size_hint();
-            let len = len.unwrap();
-            for (index, stat) in iter.enumerate() {
+            // Display the printed entries, then the total of the operand
+            for (index, stat) in printed.into_iter().chain(iter::once(total)).enumerate() {
                 let size = choose_size(&matches, &stat);
 
                 if threshold.map_or(false, |threshold| threshold.should_exclude(size)) {
@@ -646,17 +615,13 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                         };
                         DateTime::<Local>::from(UNIX_EPOCH + Duration::from_secs(secs))
                     };
-                    if !summarize || index == len - 1 {
-                        let time_str = tm.format(time_format_str).to_string();
-                        print!("{}\t{}\t", convert_size(size), time_str);
-                        print_verbatim(stat.path).unwrap();
-                        print!("{line_separator}");
-                    }
-                } else if !summarize || index == len - 1 {
+                    let time_str = tm.format(time_format_str).to_string();
+                    print!("{}\t{}\t", convert_size(size), time_str);
+                } else {
                     print!("{}\t", convert_size(size));
-                    print_verbatim(stat.path).unwrap();
-                    print!("{line_separator}");
                 }
+                print_verbatim(stat.path).unwrap();
+                print!("{line_separator}");
                 if options.total && index == (len - 1) {
                     // The last element will be the total size of the the path under
                     // path_string.  We add it to the grand total.
@@ -950,6 +915,9 @@ fn format_error_message(error: &ParseSizeError, s: &str, option: &str) -> String
         ParseSizeError::InvalidSuffix(_) => {
             format!("invalid suffix in --{} argument {}", option, s.quote())
         }
+        ParseSizeError::AmbiguousSuffix(reason) => {
+            format!("invalid suffix in --{option} argument {reason}")
+        }
         ParseSizeError::ParseFailure(_) => format!("invalid --{} argument {}", option, s.quote()),
         ParseSizeError::SizeTooBig(_) => format!("--{} argument {} too large", option, s.quote()),
     }
@@ -961,14 +929,18 @@ mod test_du {
     use super::*;
 
     #[test]
-    fn test_read_block_size() {
+    fn test_read_size_unit() {
         let test_data = [
-            (Some("1024".to_string()), 1024),
-            (Some("K".to_string()), 1024),
-            (None, 1024),
+            (Some("1024".to_string()), SizeUnit::Blocks(1024)),
+            (Some("K".to_string()), SizeUnit::Blocks(1024)),
+            (
+                Some("human-readable".to_string()),
+                SizeUnit::HumanReadable(HumanReadable::Binary),
+            ),
+            (None, SizeUnit::Blocks(1024)),
         ];
         for it in &test_data {
-            assert_eq!(read_block_size(it.0.as_deref()), it.1);
+            assert_eq!(read_size_unit(it.0.as_deref()), it.1);
         }
     }
 }
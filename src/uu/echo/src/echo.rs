@@ -11,14 +11,15 @@ use std::io::{self, Write};
 use std::iter::Peekable;
 use std::str::Chars;
 use uucore::error::{FromIo, UResult};
-use uucore::format_usage;
+use uucore::{format_usage, posix};
 
 const ABOUT: &str = "Display a line of text";
 const USAGE: &str = "{} [OPTIONS]... [STRING]...";
 const AFTER_HELP: &str = r#"
  Echo the STRING(s) to standard output.
 
- If -e is in effect, the following sequences are recognized:
+ If -e is in effect, or POSIXLY_CORRECT is set, the following sequences are
+ recognized:
 
  \\\\      backslash
  \\a      alert (BEL)
@@ -113,10 +114,23 @@ fn print_escaped(input: &str, mut output: impl Write) -> io::Result<bool> {
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_lossy();
+    let posixly_correct = posix::posixly_correct();
+
+    // Like GNU echo, POSIXLY_CORRECT makes every argument a string, unless
+    // the first one is `-n`, and escapes are always interpreted.
+    if posixly_correct && args.get(1).map(String::as_str) != Some("-n") {
+        let values = match args.get(1..) {
+            Some(values) if !values.is_empty() => values.to_vec(),
+            _ => vec![String::new()],
+        };
+        return execute(false, true, &values)
+            .map_err_context(|| "could not write to stdout".to_string());
+    }
+
     let matches = uu_app().get_matches_from(args);
 
     let no_newline = matches.get_flag(options::NO_NEWLINE);
-    let escaped = matches.get_flag(options::ENABLE_BACKSLASH_ESCAPE);
+    let escaped = posixly_correct || matches.get_flag(options::ENABLE_BACKSLASH_ESCAPE);
     let values: Vec<String> = match matches.get_many::<String>(options::STRING) {
         Some(s) => s.map(|s| s.to_string()).collect(),
         None => vec![String::new()],
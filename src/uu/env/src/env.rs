@@ -10,20 +10,26 @@
 
 // spell-checker:ignore (ToDO) chdir execvp progname subcommand subcommands unsets setenv putenv spawnp SIGSEGV SIGBUS sigaction
 
+mod split_string;
+
 use clap::{crate_name, crate_version, Arg, ArgAction, Command};
 use ini::Ini;
 #[cfg(unix)]
 use nix::sys::signal::{raise, sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
 use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::env;
-use std::io::{self, Write};
+use std::ffi::{OsStr, OsString};
+use std::io::{self, BufWriter, Write};
 use std::iter::Iterator;
 #[cfg(unix)]
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
 use std::os::unix::process::ExitStatusExt;
 use std::process;
 use uucore::display::Quotable;
-use uucore::error::{UClapError, UResult, USimpleError, UUsageError};
-use uucore::{format_usage, show_warning};
+use uucore::error::{FromIo, UClapError, UResult, USimpleError, UUsageError};
+use uucore::{exec, format_usage, show_warning};
 
 const ABOUT: &str = "Set each NAME to VALUE in the environment and run COMMAND";
 const USAGE: &str = "{} [OPTION]... [-] [NAME=VALUE]... [COMMAND [ARG]...]";
@@ -41,14 +47,103 @@ struct Options<'a> {
     program: Vec<&'a str>,
 }
 
-// print name=value env pairs on screen
-// if null is true, separate pairs with a \0, \n otherwise
-fn print_env(null: bool) {
+#[cfg(unix)]
+fn write_os_str(w: &mut impl Write, s: &OsStr) -> io::Result<()> {
+    w.write_all(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_os_str(w: &mut impl Write, s: &OsStr) -> io::Result<()> {
+    w.write_all(s.to_string_lossy().as_bytes())
+}
+
+// print name=value env pairs on screen, in the order of the environment
+// if null is true, separate pairs with a \0, \n otherwise, which only -0 makes
+// unambiguous when a value contains a newline
+fn print_env(null: bool) -> io::Result<()> {
     let stdout_raw = io::stdout();
-    let mut stdout = stdout_raw.lock();
-    for (n, v) in env::vars() {
-        write!(stdout, "{}={}{}", n, v, if null { '\0' } else { '\n' }).unwrap();
+    let mut stdout = BufWriter::new(stdout_raw.lock());
+    for (n, v) in env::vars_os() {
+        write_os_str(&mut stdout, &n)?;
+        stdout.write_all(b"=")?;
+        write_os_str(&mut stdout, &v)?;
+        stdout.write_all(if null { b"\0" } else { b"\n" })?;
+    }
+    stdout.flush()
+}
+
+/// Put the arguments that `s` is split into in front of `args`.
+fn push_split(args: &mut VecDeque<OsString>, s: &str) -> UResult<()> {
+    let words = split_string::split(s).map_err(|e| USimpleError::new(125, e.to_string()))?;
+    for word in words.into_iter().rev() {
+        args.push_front(word.into());
+    }
+    Ok(())
+}
+
+/// Replace the strings of `-S` and `--split-string` in `args` with the
+/// arguments they are split into, which are then parsed like the others.
+///
+/// Like with GNU env, the options are looked for up to the first argument
+/// that isn't one, so the split arguments can hold options too, like in
+/// `#!/usr/bin/env -S -i PATH=/bin program`.
+fn split_strings(args: impl uucore::Args) -> UResult<Vec<OsString>> {
+    let mut args: VecDeque<OsString> = args.collect();
+    let mut result: Vec<OsString> = args.pop_front().into_iter().collect();
+    while let Some(arg) = args.pop_front() {
+        let s = match arg.to_str() {
+            Some(s) if s.starts_with('-') && s != "-" && s != "--" => s.to_string(),
+            _ => {
+                result.push(arg);
+                break;
+            }
+        };
+        if let Some(long) = s.strip_prefix("--") {
+            let (name, value) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (long, None),
+            };
+            let is_prefix_of = |option: &str| option.starts_with(name);
+            if is_prefix_of("split-string") {
+                match value.map(OsString::from).or_else(|| args.pop_front()) {
+                    Some(value) => push_split(&mut args, &value.to_string_lossy())?,
+                    // Let clap tell that the value is missing.
+                    None => result.push(arg),
+                }
+                continue;
+            }
+            result.push(arg);
+            if value.is_none() && ["chdir", "file", "unset"].into_iter().any(is_prefix_of) {
+                result.extend(args.pop_front());
+            }
+            continue;
+        }
+        // A cluster of short options, of which the last can take a value.
+        match s.char_indices().skip(1).find(|(_, c)| "CSfu".contains(*c)) {
+            Some((i, 'S')) => {
+                if i > 1 {
+                    result.push(s[..i].into());
+                }
+                let value = &s[i + 1..];
+                if !value.is_empty() {
+                    push_split(&mut args, value)?;
+                } else if let Some(value) = args.pop_front() {
+                    push_split(&mut args, &value.to_string_lossy())?;
+                } else {
+                    result.push("-S".into());
+                }
+            }
+            Some((i, _)) => {
+                result.push(arg);
+                if i + 1 == s.len() {
+                    result.extend(args.pop_front());
+                }
+            }
+            None => result.push(arg),
+        }
     }
+    result.extend(args);
+    Ok(result)
 }
 
 fn parse_name_value_opt<'a>(opts: &mut Options<'a>, opt: &'a str) -> UResult<bool> {
@@ -166,6 +261,17 @@ pub fn uu_app() -> Command {
                 (prior to any unset and/or set)",
                 ),
         )
+        .arg(
+            Arg::new("split-string")
+                .short('S')
+                .long("split-string")
+                .value_name("S")
+                .action(ArgAction::Append)
+                .help(
+                    "process and split S into separate arguments; used to pass multiple \
+                arguments on shebang lines",
+                ),
+        )
         .arg(
             Arg::new("unset")
                 .short('u')
@@ -179,6 +285,7 @@ pub fn uu_app() -> Command {
 
 fn run_env(args: impl uucore::Args) -> UResult<()> {
     let app = uu_app();
+    let args = split_strings(args)?;
     let matches = app.try_get_matches_from(args).with_exit_code(125)?;
 
     let ignore_env = matches.get_flag("ignore-environment");
@@ -250,7 +357,7 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
 
     // remove all env vars if told to ignore presets
     if opts.ignore_env {
-        for (ref name, _) in env::vars() {
+        for (ref name, _) in env::vars_os() {
             env::remove_var(name);
         }
     }
@@ -342,13 +449,12 @@ fn run_env(args: impl uucore::Args) -> UResult<()> {
                 #[cfg(not(unix))]
                 return Err(exit.code().unwrap().into());
             }
-            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Err(127.into()),
-            Err(_) => return Err(126.into()),
+            Err(err) => return Err(exec::command_error(&*prog, err)),
             Ok(_) => (),
         }
     } else {
         // no program provided, so just dump all env vars to stdout
-        print_env(opts.null);
+        print_env(opts.null).map_err_context(|| "write error".to_string())?;
     }
 
     Ok(())
@@ -0,0 +1,195 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Splitting the string of `-S` (`--split-string`) into arguments, the way
+//! GNU env does, so that a shebang line can give env more than one.
+//!
+//! The words are separated by whitespace, and can be quoted with `'` and
+//! `"`. Outside of single quotes, `${NAME}` is replaced with the value of
+//! the variable `NAME`, and the backslash escapes:
+//!
+//! * `\"`, `\#`, `\$`, `\'` and `\\` the character they are made of,
+//! * `\f`, `\n`, `\r`, `\t` and `\v` the usual control characters,
+//! * `\_` a space within double quotes, and separates words outside of
+//!   them,
+//! * `\c` ends the string, outside of double quotes.
+//!
+//! A `#` at the start of a word starts a comment, which ends the string.
+
+// spell-checker:ignore (ToDO) VARNAME
+
+use std::env;
+use std::fmt;
+
+/// What is wrong with a string given to `-S`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum SplitError {
+    UnterminatedQuote,
+    BackslashAtEnd,
+    InvalidSequence(char),
+    BackslashCInDoubleQuotes,
+    /// A `$` that doesn't start a `${NAME}`, with the rest of the string.
+    InvalidExpansion(String),
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnterminatedQuote => write!(f, "no terminating quote in -S string"),
+            Self::BackslashAtEnd => write!(f, "invalid backslash at end of string in -S"),
+            Self::InvalidSequence(c) => write!(f, "invalid sequence '\\{c}' in -S"),
+            Self::BackslashCInDoubleQuotes => {
+                write!(f, "'\\c' must not appear in double-quoted -S string")
+            }
+            Self::InvalidExpansion(rest) => write!(
+                f,
+                "only ${{VARNAME}} expansion is supported, error at: {rest}"
+            ),
+        }
+    }
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r')
+}
+
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c == '_' || c.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// The arguments that `s` is split into.
+pub fn split(s: &str) -> Result<Vec<String>, SplitError> {
+    let mut args = vec![];
+    // The word being read, which is None between the words.
+    let mut word: Option<String> = None;
+    let mut single_quoted = false;
+    let mut double_quoted = false;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let quoted = single_quoted || double_quoted;
+        let c = match c {
+            '\'' if !double_quoted => {
+                single_quoted = !single_quoted;
+                word.get_or_insert_with(String::new);
+                continue;
+            }
+            '"' if !single_quoted => {
+                double_quoted = !double_quoted;
+                word.get_or_insert_with(String::new);
+                continue;
+            }
+            c if !quoted && is_space(c) => {
+                args.extend(word.take());
+                continue;
+            }
+            '#' if !quoted && word.is_none() => break,
+            // Within single quotes, only \\ and \' are escapes.
+            '\\' if single_quoted && !matches!(chars.peek(), Some((_, '\\' | '\''))) => '\\',
+            '\\' => match chars.next().map(|(_, c)| c) {
+                Some(c @ ('"' | '#' | '$' | '\'' | '\\')) => c,
+                Some('_') if double_quoted => ' ',
+                Some('_') => {
+                    args.extend(word.take());
+                    continue;
+                }
+                Some('c') if double_quoted => return Err(SplitError::BackslashCInDoubleQuotes),
+                Some('c') => break,
+                Some('f') => '\x0c',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('v') => '\x0b',
+                Some(c) => return Err(SplitError::InvalidSequence(c)),
+                None => return Err(SplitError::BackslashAtEnd),
+            },
+            '$' if !single_quoted => {
+                let rest = &s[i..];
+                let name = rest
+                    .strip_prefix("${")
+                    .and_then(|rest| rest.split_once('}'))
+                    .map(|(name, _)| name)
+                    .filter(|name| is_name(name))
+                    .ok_or_else(|| SplitError::InvalidExpansion(rest.to_string()))?;
+                // Skip the name and the braces.
+                for _ in 0..name.len() + 2 {
+                    chars.next();
+                }
+                // Unlike an empty quoted string, an empty value doesn't
+                // make a word.
+                let value = env::var_os(name).unwrap_or_default();
+                if !value.is_empty() {
+                    word.get_or_insert_with(String::new)
+                        .push_str(&value.to_string_lossy());
+                }
+                continue;
+            }
+            c => c,
+        };
+        word.get_or_insert_with(String::new).push(c);
+    }
+    if single_quoted || double_quoted {
+        return Err(SplitError::UnterminatedQuote);
+    }
+    args.extend(word);
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_ok(s: &str) -> Vec<String> {
+        split(s).unwrap()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(split_ok(" a  b\tc\n"), ["a", "b", "c"]);
+        assert_eq!(split_ok(""), Vec::<String>::new());
+        assert_eq!(split_ok("a\\_b"), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_quotes() {
+        assert_eq!(split_ok(r#"'a b' "c d" """#), ["a b", "c d", ""]);
+        assert_eq!(split_ok(r#"'a\b\'\\' "\_\t\$""#), ["a\\b'\\", " \t$"]);
+        assert_eq!(split_ok(r#"a'b'"c""#), ["abc"]);
+    }
+
+    #[test]
+    fn test_comments_and_end() {
+        assert_eq!(split_ok("a#b #c d"), ["a#b"]);
+        assert_eq!(split_ok("a\\c b"), ["a"]);
+    }
+
+    #[test]
+    fn test_expansion() {
+        env::set_var("UU_ENV_SPLIT_TEST", "x y");
+        assert_eq!(
+            split_ok("a${UU_ENV_SPLIT_TEST}b '${UU_ENV_SPLIT_TEST}'"),
+            ["ax yb", "${UU_ENV_SPLIT_TEST}"]
+        );
+        assert_eq!(split_ok("${UU_ENV_SPLIT_UNSET} a"), ["a"]);
+        assert_eq!(split_ok("\"${UU_ENV_SPLIT_UNSET}\""), [""]);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(split("'a"), Err(SplitError::UnterminatedQuote));
+        assert_eq!(split("a\\"), Err(SplitError::BackslashAtEnd));
+        assert_eq!(split("\\x"), Err(SplitError::InvalidSequence('x')));
+        assert_eq!(split("\"\\c\""), Err(SplitError::BackslashCInDoubleQuotes));
+        assert_eq!(
+            split("$HOME"),
+            Err(SplitError::InvalidExpansion("$HOME".to_string()))
+        );
+        assert_eq!(
+            split("a ${1} b"),
+            Err(SplitError::InvalidExpansion("${1} b".to_string()))
+        );
+    }
+}
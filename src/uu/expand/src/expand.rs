@@ -19,7 +19,7 @@ use std::str::from_utf8;
 use unicode_width::UnicodeWidthChar;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult};
-use uucore::{crash, format_usage};
+use uucore::{format_usage, show};
 
 static ABOUT: &str = "Convert tabs in each FILE to spaces, writing to standard output.
  With no FILE, or when FILE is -, read standard input.";
@@ -251,7 +251,7 @@ fn expand_shortcuts(args: &[String]) -> Vec<String> {
     let mut processed_args = Vec::with_capacity(args.len());
 
     for arg in args {
-        if arg.starts_with('-') && arg[1..].chars().all(is_digit_or_comma) {
+        if arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(is_digit_or_comma) {
             arg[1..]
                 .split(',')
                 .filter(|s| !s.is_empty())
@@ -313,16 +313,12 @@ pub fn uu_app() -> Command {
         )
 }
 
-fn open(path: &str) -> BufReader<Box<dyn Read + 'static>> {
-    let file_buf;
+fn open(path: &str) -> UResult<BufReader<Box<dyn Read + 'static>>> {
     if path == "-" {
-        BufReader::new(Box::new(stdin()) as Box<dyn Read>)
+        Ok(BufReader::new(Box::new(stdin()) as Box<dyn Read>))
     } else {
-        file_buf = match File::open(path) {
-            Ok(a) => a,
-            Err(e) => crash!(1, "{}: {}\n", path.maybe_quote(), e),
-        };
-        BufReader::new(Box::new(file_buf) as Box<dyn Read>)
+        let file_buf = File::open(path).map_err_context(|| path.maybe_quote().to_string())?;
+        Ok(BufReader::new(Box::new(file_buf) as Box<dyn Read>))
     }
 }
 
@@ -382,7 +378,13 @@ fn expand(options: &Options) -> std::io::Result<()> {
     let mut buf = Vec::new();
 
     for file in &options.files {
-        let mut fh = open(file);
+        let mut fh = match open(file) {
+            Ok(fh) => fh,
+            Err(e) => {
+                show!(e);
+                continue;
+            }
+        };
 
         while match fh.read_until(b'\n', &mut buf) {
             Ok(s) => s > 0,
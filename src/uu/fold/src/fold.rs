@@ -9,11 +9,12 @@
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use std::fs::File;
-use std::io::{stdin, BufRead, BufReader, Read};
+use std::io::{self, stdin, BufReader, Read};
 use std::path::Path;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::format_usage;
+use uucore::lines::{lines, LineEnding};
 
 const TAB_WIDTH: usize = 8;
 
@@ -26,6 +27,7 @@ mod options {
     pub const SPACES: &str = "spaces";
     pub const WIDTH: &str = "width";
     pub const FILE: &str = "file";
+    pub const ZERO_TERMINATED: &str = "zero-terminated";
 }
 
 #[uucore::main]
@@ -37,6 +39,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let bytes = matches.get_flag(options::BYTES);
     let spaces = matches.get_flag(options::SPACES);
+    let line_ending = LineEnding::from_zero_flag(matches.get_flag(options::ZERO_TERMINATED));
     let poss_width = match matches.get_one::<String>(options::WIDTH) {
         Some(v) => Some(v.to_owned()),
         None => obs_width,
@@ -57,7 +60,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         None => vec!["-".to_owned()],
     };
 
-    fold(&files, bytes, spaces, width)
+    fold(&files, bytes, spaces, width, line_ending)
 }
 
 pub fn uu_app() -> Command {
@@ -91,6 +94,16 @@ pub fn uu_app() -> Command {
                 .value_name("WIDTH")
                 .allow_hyphen_values(true),
         )
+        .arg(
+            Arg::new(options::ZERO_TERMINATED)
+                .long(options::ZERO_TERMINATED)
+                .short('z')
+                .help(
+                    "line delimiter is NUL, not newline. \
+                     Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::FILE)
                 .hide(true)
@@ -111,7 +124,13 @@ fn handle_obsolete(args: &[String]) -> (Vec<String>, Option<String>) {
     (args.to_vec(), None)
 }
 
-fn fold(filenames: &[String], bytes: bool, spaces: bool, width: usize) -> UResult<()> {
+fn fold(
+    filenames: &[String],
+    bytes: bool,
+    spaces: bool,
+    width: usize,
+    line_ending: LineEnding,
+) -> UResult<()> {
     for filename in filenames {
         let filename: &str = filename;
         let mut stdin_buf;
@@ -125,14 +144,27 @@ fn fold(filenames: &[String], bytes: bool, spaces: bool, width: usize) -> UResul
         });
 
         if bytes {
-            fold_file_bytewise(buffer, spaces, width)?;
+            fold_file_bytewise(buffer, spaces, width, line_ending)?;
         } else {
-            fold_file(buffer, spaces, width)?;
+            fold_file(buffer, spaces, width, line_ending)?;
         }
     }
     Ok(())
 }
 
+/// The lines of `file`, which must be valid UTF-8, with their line endings.
+fn read_lines<T: Read>(
+    file: BufReader<T>,
+    line_ending: LineEnding,
+) -> impl Iterator<Item = UResult<String>> {
+    lines(file, line_ending as u8).map(|line| {
+        line.and_then(|line| {
+            String::from_utf8(line).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .map_err_context(|| "failed to read line".to_string())
+    })
+}
+
 /// Fold `file` to fit `width` (number of columns), counting all characters as
 /// one column.
 ///
@@ -141,21 +173,18 @@ fn fold(filenames: &[String], bytes: bool, spaces: bool, width: usize) -> UResul
 /// to all other characters in the stream.
 ///
 ///  If `spaces` is `true`, attempt to break lines at whitespace boundaries.
-fn fold_file_bytewise<T: Read>(mut file: BufReader<T>, spaces: bool, width: usize) -> UResult<()> {
-    let mut line = String::new();
-
-    loop {
-        if file
-            .read_line(&mut line)
-            .map_err_context(|| "failed to read line".to_string())?
-            == 0
-        {
-            break;
-        }
-
-        if line == "\n" {
-            println!();
-            line.truncate(0);
+fn fold_file_bytewise<T: Read>(
+    file: BufReader<T>,
+    spaces: bool,
+    width: usize,
+    line_ending: LineEnding,
+) -> UResult<()> {
+    let eol = line_ending.to_string();
+
+    for line in read_lines(file, line_ending) {
+        let line = line?;
+        if line == eol {
+            print!("{eol}");
             continue;
         }
 
@@ -179,7 +208,7 @@ fn fold_file_bytewise<T: Read>(mut file: BufReader<T>, spaces: bool, width: usiz
             // Don't duplicate trailing newlines: if the slice is "\n", the
             // previous iteration folded just before the end of the line and
             // has already printed this newline.
-            if slice == "\n" {
+            if slice == eol {
                 break;
             }
 
@@ -190,11 +219,9 @@ fn fold_file_bytewise<T: Read>(mut file: BufReader<T>, spaces: bool, width: usiz
             if at_eol {
                 print!("{slice}");
             } else {
-                println!("{slice}");
+                print!("{slice}{eol}");
             }
         }
-
-        line.truncate(0);
     }
 
     Ok(())
@@ -208,8 +235,13 @@ fn fold_file_bytewise<T: Read>(mut file: BufReader<T>, spaces: bool, width: usiz
 ///
 /// If `spaces` is `true`, attempt to break lines at whitespace boundaries.
 #[allow(unused_assignments)]
-fn fold_file<T: Read>(mut file: BufReader<T>, spaces: bool, width: usize) -> UResult<()> {
-    let mut line = String::new();
+fn fold_file<T: Read>(
+    file: BufReader<T>,
+    spaces: bool,
+    width: usize,
+    line_ending: LineEnding,
+) -> UResult<()> {
+    let eol = line_ending as u8 as char;
     let mut output = String::new();
     let mut col_count = 0;
     let mut last_space = None;
@@ -226,7 +258,7 @@ fn fold_file<T: Read>(mut file: BufReader<T>, spaces: bool, width: usize) -> URe
                 None => output.len(),
             };
 
-            println!("{}", &output[..consume]);
+            print!("{}{}", &output[..consume], eol);
             output.replace_range(..consume, "");
 
             // we know there are no tabs left in output, so each char counts
@@ -237,17 +269,9 @@ fn fold_file<T: Read>(mut file: BufReader<T>, spaces: bool, width: usize) -> URe
         };
     }
 
-    loop {
-        if file
-            .read_line(&mut line)
-            .map_err_context(|| "failed to read line".to_string())?
-            == 0
-        {
-            break;
-        }
-
-        for ch in line.chars() {
-            if ch == '\n' {
+    for line in read_lines(file, line_ending) {
+        for ch in line?.chars() {
+            if ch == eol {
                 // make sure to _not_ split output at whitespace, since we
                 // know the entire output will fit
                 last_space = None;
@@ -288,8 +312,6 @@ fn fold_file<T: Read>(mut file: BufReader<T>, spaces: bool, width: usize) -> URe
             print!("{output}");
             output.truncate(0);
         }
-
-        line.truncate(0);
     }
 
     Ok(())
@@ -7,31 +7,21 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) algo, algoname, regexes, nread, nonames
-
-mod digest;
-
-use self::digest::Digest;
-use self::digest::DigestWriter;
+// spell-checker:ignore (ToDO) algo, algoname, nread, nonames
 
 use clap::builder::ValueParser;
 use clap::crate_version;
 use clap::ArgAction;
 use clap::{Arg, ArgMatches, Command};
-use hex::encode;
-use md5::Md5;
-use regex::Regex;
-use sha1::Sha1;
-use sha2::{Sha224, Sha256, Sha384, Sha512};
-use sha3::{Sha3_224, Sha3_256, Sha3_384, Sha3_512, Shake128, Shake256};
 use std::cmp::Ordering;
 use std::error::Error;
 use std::ffi::{OsStr, OsString};
 use std::fs::File;
-use std::io::{self, stdin, BufRead, BufReader, Read};
+use std::io::{self, stdin, BufRead, BufReader};
 use std::iter;
 use std::num::ParseIntError;
 use std::path::Path;
+use uucore::checksum::{self, Algorithm, Digest, ReadStrategy};
 use uucore::crash;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult};
@@ -40,7 +30,7 @@ use uucore::show_warning;
 const NAME: &str = "hashsum";
 
 struct Options {
-    algoname: &'static str,
+    algo: Algorithm,
     digest: Box<dyn Digest + 'static>,
     binary: bool,
     check: bool,
@@ -51,187 +41,85 @@ struct Options {
     strict: bool,
     warn: bool,
     output_bits: usize,
+    read_strategy: ReadStrategy,
 }
 
-#[allow(clippy::cognitive_complexity)]
-fn detect_algo(
-    program: &str,
-    matches: &ArgMatches,
-) -> (&'static str, Box<dyn Digest + 'static>, usize) {
-    let mut alg: Option<Box<dyn Digest>> = None;
-    let mut name: &'static str = "";
-    let mut output_bits = 0;
-    match program {
-        "md5sum" => ("MD5", Box::new(Md5::new()) as Box<dyn Digest>, 128),
-        "sha1sum" => ("SHA1", Box::new(Sha1::new()) as Box<dyn Digest>, 160),
-        "sha224sum" => ("SHA224", Box::new(Sha224::new()) as Box<dyn Digest>, 224),
-        "sha256sum" => ("SHA256", Box::new(Sha256::new()) as Box<dyn Digest>, 256),
-        "sha384sum" => ("SHA384", Box::new(Sha384::new()) as Box<dyn Digest>, 384),
-        "sha512sum" => ("SHA512", Box::new(Sha512::new()) as Box<dyn Digest>, 512),
-        "b2sum" => (
-            "BLAKE2",
-            Box::new(blake2b_simd::State::new()) as Box<dyn Digest>,
-            512,
-        ),
-        "b3sum" => (
-            "BLAKE3",
-            Box::new(blake3::Hasher::new()) as Box<dyn Digest>,
-            256,
-        ),
-        "sha3sum" => match matches.get_one::<usize>("bits") {
-            Some(224) => (
-                "SHA3-224",
-                Box::new(Sha3_224::new()) as Box<dyn Digest>,
-                224,
-            ),
-            Some(256) => (
-                "SHA3-256",
-                Box::new(Sha3_256::new()) as Box<dyn Digest>,
-                256,
-            ),
-            Some(384) => (
-                "SHA3-384",
-                Box::new(Sha3_384::new()) as Box<dyn Digest>,
-                384,
-            ),
-            Some(512) => (
-                "SHA3-512",
-                Box::new(Sha3_512::new()) as Box<dyn Digest>,
-                512,
-            ),
-            Some(_) => crash!(
-                1,
-                "Invalid output size for SHA3 (expected 224, 256, 384, or 512)"
-            ),
-            None => crash!(1, "--bits required for SHA3"),
-        },
-        "sha3-224sum" => (
-            "SHA3-224",
-            Box::new(Sha3_224::new()) as Box<dyn Digest>,
-            224,
-        ),
-        "sha3-256sum" => (
-            "SHA3-256",
-            Box::new(Sha3_256::new()) as Box<dyn Digest>,
-            256,
-        ),
-        "sha3-384sum" => (
-            "SHA3-384",
-            Box::new(Sha3_384::new()) as Box<dyn Digest>,
-            384,
-        ),
-        "sha3-512sum" => (
-            "SHA3-512",
-            Box::new(Sha3_512::new()) as Box<dyn Digest>,
-            512,
-        ),
-        "shake128sum" => match matches.get_one::<usize>("bits") {
-            Some(bits) => (
-                "SHAKE128",
-                Box::new(Shake128::new()) as Box<dyn Digest>,
-                *bits,
-            ),
-            None => crash!(1, "--bits required for SHAKE-128"),
-        },
-        "shake256sum" => match matches.get_one::<usize>("bits") {
-            Some(bits) => (
-                "SHAKE256",
-                Box::new(Shake256::new()) as Box<dyn Digest>,
-                *bits,
-            ),
-            None => crash!(1, "--bits required for SHAKE-256"),
-        },
+fn detect_algo(program: &str, matches: &ArgMatches) -> (Algorithm, usize) {
+    let algo = match program {
+        "md5sum" => Algorithm::Md5,
+        "sha1sum" => Algorithm::Sha1,
+        "sha224sum" => Algorithm::Sha224,
+        "sha256sum" => Algorithm::Sha256,
+        "sha384sum" => Algorithm::Sha384,
+        "sha512sum" => Algorithm::Sha512,
+        "b2sum" => Algorithm::Blake2b,
+        "b3sum" => Algorithm::Blake3,
+        "sha3sum" => detect_sha3(matches),
+        "sha3-224sum" => Algorithm::Sha3_224,
+        "sha3-256sum" => Algorithm::Sha3_256,
+        "sha3-384sum" => Algorithm::Sha3_384,
+        "sha3-512sum" => Algorithm::Sha3_512,
+        "shake128sum" => Algorithm::Shake128,
+        "shake256sum" => Algorithm::Shake256,
         _ => {
-            {
-                let mut set_or_crash = |n, val, bits| {
+            let mut alg = None;
+            for (flag, algo) in [
+                ("md5", Algorithm::Md5),
+                ("sha1", Algorithm::Sha1),
+                ("sha224", Algorithm::Sha224),
+                ("sha256", Algorithm::Sha256),
+                ("sha384", Algorithm::Sha384),
+                ("sha512", Algorithm::Sha512),
+                ("b2sum", Algorithm::Blake2b),
+                ("b3sum", Algorithm::Blake3),
+                ("sha3", Algorithm::Sha3_256),
+                ("sha3-224", Algorithm::Sha3_224),
+                ("sha3-256", Algorithm::Sha3_256),
+                ("sha3-384", Algorithm::Sha3_384),
+                ("sha3-512", Algorithm::Sha3_512),
+                ("shake128", Algorithm::Shake128),
+                ("shake256", Algorithm::Shake256),
+            ] {
+                if matches.get_flag(flag) {
                     if alg.is_some() {
                         crash!(1, "You cannot combine multiple hash algorithms!")
                     };
-                    name = n;
-                    alg = Some(val);
-                    output_bits = bits;
-                };
-                if matches.get_flag("md5") {
-                    set_or_crash("MD5", Box::new(Md5::new()), 128);
-                }
-                if matches.get_flag("sha1") {
-                    set_or_crash("SHA1", Box::new(Sha1::new()), 160);
-                }
-                if matches.get_flag("sha224") {
-                    set_or_crash("SHA224", Box::new(Sha224::new()), 224);
-                }
-                if matches.get_flag("sha256") {
-                    set_or_crash("SHA256", Box::new(Sha256::new()), 256);
-                }
-                if matches.get_flag("sha384") {
-                    set_or_crash("SHA384", Box::new(Sha384::new()), 384);
-                }
-                if matches.get_flag("sha512") {
-                    set_or_crash("SHA512", Box::new(Sha512::new()), 512);
-                }
-                if matches.get_flag("b2sum") {
-                    set_or_crash("BLAKE2", Box::new(blake2b_simd::State::new()), 512);
-                }
-                if matches.get_flag("b3sum") {
-                    set_or_crash("BLAKE3", Box::new(blake3::Hasher::new()), 256);
-                }
-                if matches.get_flag("sha3") {
-                    match matches.get_one::<usize>("bits") {
-                        Some(224) => set_or_crash(
-                            "SHA3-224",
-                            Box::new(Sha3_224::new()) as Box<dyn Digest>,
-                            224,
-                        ),
-                        Some(256) => set_or_crash(
-                            "SHA3-256",
-                            Box::new(Sha3_256::new()) as Box<dyn Digest>,
-                            256,
-                        ),
-                        Some(384) => set_or_crash(
-                            "SHA3-384",
-                            Box::new(Sha3_384::new()) as Box<dyn Digest>,
-                            384,
-                        ),
-                        Some(512) => set_or_crash(
-                            "SHA3-512",
-                            Box::new(Sha3_512::new()) as Box<dyn Digest>,
-                            512,
-                        ),
-                        Some(_) => crash!(
-                            1,
-                            "Invalid output size for SHA3 (expected 224, 256, 384, or 512)"
-                        ),
-                        None => crash!(1, "--bits required for SHA3"),
-                    }
-                }
-                if matches.get_flag("sha3-224") {
-                    set_or_crash("SHA3-224", Box::new(Sha3_224::new()), 224);
-                }
-                if matches.get_flag("sha3-256") {
-                    set_or_crash("SHA3-256", Box::new(Sha3_256::new()), 256);
-                }
-                if matches.get_flag("sha3-384") {
-                    set_or_crash("SHA3-384", Box::new(Sha3_384::new()), 384);
-                }
-                if matches.get_flag("sha3-512") {
-                    set_or_crash("SHA3-512", Box::new(Sha3_512::new()), 512);
-                }
-                if matches.get_flag("shake128") {
-                    match matches.get_one::<usize>("bits") {
-                        Some(bits) => set_or_crash("SHAKE128", Box::new(Shake128::new()), *bits),
-                        None => crash!(1, "--bits required for SHAKE-128"),
-                    }
-                }
-                if matches.get_flag("shake256") {
-                    match matches.get_one::<usize>("bits") {
-                        Some(bits) => set_or_crash("SHAKE256", Box::new(Shake256::new()), *bits),
-                        None => crash!(1, "--bits required for SHAKE-256"),
-                    }
+                    alg = Some(if flag == "sha3" {
+                        detect_sha3(matches)
+                    } else {
+                        algo
+                    });
                 }
             }
-            let alg = alg.unwrap_or_else(|| crash!(1, "You must specify hash algorithm!"));
-            (name, alg, output_bits)
+            alg.unwrap_or_else(|| crash!(1, "You must specify hash algorithm!"))
         }
+    };
+    let output_bits = match algo {
+        Algorithm::Shake128 => match matches.get_one::<usize>("bits") {
+            Some(bits) => *bits,
+            None => crash!(1, "--bits required for SHAKE-128"),
+        },
+        Algorithm::Shake256 => match matches.get_one::<usize>("bits") {
+            Some(bits) => *bits,
+            None => crash!(1, "--bits required for SHAKE-256"),
+        },
+        _ => algo.output_bits(),
+    };
+    (algo, output_bits)
+}
+
+/// Select the SHA3 variant from the `--bits` option.
+fn detect_sha3(matches: &ArgMatches) -> Algorithm {
+    match matches.get_one::<usize>("bits") {
+        Some(224) => Algorithm::Sha3_224,
+        Some(256) => Algorithm::Sha3_256,
+        Some(384) => Algorithm::Sha3_384,
+        Some(512) => Algorithm::Sha3_512,
+        Some(_) => crash!(
+            1,
+            "Invalid output size for SHA3 (expected 224, 256, 384, or 512)"
+        ),
+        None => crash!(1, "--bits required for SHA3"),
     }
 }
 
@@ -262,7 +150,7 @@ pub fn uumain(mut args: impl uucore::Args) -> UResult<()> {
     //        least somewhat better from a user's perspective.
     let matches = command.try_get_matches_from(args)?;
 
-    let (name, algo, bits) = detect_algo(&binary_name, &matches);
+    let (algo, bits) = detect_algo(&binary_name, &matches);
 
     let binary = if matches.get_flag("binary") {
         true
@@ -281,10 +169,14 @@ pub fn uumain(mut args: impl uucore::Args) -> UResult<()> {
     let quiet = matches.get_flag("quiet") || status;
     let strict = matches.get_flag("strict");
     let warn = matches.get_flag("warn") && !status;
+    let read_strategy = matches
+        .get_one::<String>("io-mode")
+        .and_then(|mode| ReadStrategy::from_name(mode))
+        .unwrap_or_default();
 
     let opts = Options {
-        algoname: name,
-        digest: algo,
+        algo,
+        digest: algo.create_digest(),
         output_bits: bits,
         binary,
         check,
@@ -294,6 +186,7 @@ pub fn uumain(mut args: impl uucore::Args) -> UResult<()> {
         quiet,
         strict,
         warn,
+        read_strategy,
     };
 
     match matches.get_many::<OsString>("FILE") {
@@ -370,6 +263,13 @@ pub fn uu_app_common() -> Command {
                 .help("warn about improperly formatted checksum lines")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("io-mode")
+                .long("io-mode")
+                .help("how to read the files: 'auto', 'mmap' or 'read' (the default)")
+                .value_name("MODE")
+                .value_parser(["auto", "mmap", "read"]),
+        )
         .arg(
             Arg::new("FILE")
                 .index(1)
@@ -472,7 +372,6 @@ fn uu_app(binary_name: &str) -> Command {
 
 #[derive(Debug)]
 enum HashsumError {
-    InvalidRegex,
     InvalidFormat,
 }
 
@@ -480,9 +379,8 @@ impl Error for HashsumError {}
 impl UError for HashsumError {}
 
 impl std::fmt::Display for HashsumError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            Self::InvalidRegex => write!(f, "invalid regular expression"),
             Self::InvalidFormat => Ok(()),
         }
     }
@@ -500,76 +398,42 @@ where
     for filename in files {
         let filename = Path::new(filename);
 
-        let stdin_buf;
-        let file_buf;
-        let mut file = BufReader::new(if filename == OsStr::new("-") {
-            stdin_buf = stdin();
-            Box::new(stdin_buf) as Box<dyn Read>
-        } else {
-            file_buf =
-                File::open(filename).map_err_context(|| "failed to open file".to_string())?;
-            Box::new(file_buf) as Box<dyn Read>
-        });
         if options.check {
-            // Set up Regexes for line validation and parsing
-            //
-            // First, we compute the number of bytes we expect to be in
-            // the digest string. If the algorithm has a variable number
-            // of output bits, then we use the `+` modifier in the
-            // regular expression, otherwise we use the `{n}` modifier,
-            // where `n` is the number of bytes.
-            let bytes = options.digest.output_bits() / 4;
-            let modifier = if bytes > 0 {
-                format!("{{{bytes}}}")
+            let file = BufReader::new(if filename == OsStr::new("-") {
+                Box::new(stdin()) as Box<dyn io::Read>
             } else {
-                "+".to_string()
-            };
-            let gnu_re = Regex::new(&format!(
-                r"^(?P<digest>[a-fA-F0-9]{modifier}) (?P<binary>[ \*])(?P<fileName>.*)",
-            ))
-            .map_err(|_| HashsumError::InvalidRegex)?;
-            let bsd_re = Regex::new(&format!(
-                r"^{algorithm} \((?P<fileName>.*)\) = (?P<digest>[a-fA-F0-9]{digest_size})",
-                algorithm = options.algoname,
-                digest_size = modifier,
-            ))
-            .map_err(|_| HashsumError::InvalidRegex)?;
-
-            let buffer = file;
-            for (i, maybe_line) in buffer.lines().enumerate() {
+                Box::new(
+                    File::open(filename).map_err_context(|| "failed to open file".to_string())?,
+                ) as Box<dyn io::Read>
+            });
+            for (i, maybe_line) in file.lines().enumerate() {
                 let line = match maybe_line {
                     Ok(l) => l,
                     Err(e) => return Err(e.map_err_context(|| "failed to read file".to_string())),
                 };
-                let (ck_filename, sum, binary_check) = match gnu_re.captures(&line) {
-                    Some(caps) => (
-                        caps.name("fileName").unwrap().as_str(),
-                        caps.name("digest").unwrap().as_str().to_ascii_lowercase(),
-                        caps.name("binary").unwrap().as_str() == "*",
-                    ),
-                    None => match bsd_re.captures(&line) {
-                        Some(caps) => (
-                            caps.name("fileName").unwrap().as_str(),
-                            caps.name("digest").unwrap().as_str().to_ascii_lowercase(),
-                            true,
-                        ),
-                        None => {
-                            bad_format += 1;
-                            if options.strict {
-                                return Err(HashsumError::InvalidFormat.into());
-                            }
-                            if options.warn {
-                                show_warning!(
-                                    "{}: {}: improperly formatted {} checksum line",
-                                    filename.maybe_quote(),
-                                    i + 1,
-                                    options.algoname
-                                );
-                            }
-                            continue;
+                let parsed = match checksum::parse_checksum_line(
+                    &line,
+                    options.algo.tag(),
+                    options.digest.output_bits(),
+                ) {
+                    Some(parsed) => parsed,
+                    None => {
+                        bad_format += 1;
+                        if options.strict {
+                            return Err(HashsumError::InvalidFormat.into());
                         }
-                    },
+                        if options.warn {
+                            show_warning!(
+                                "{}: {}: improperly formatted {} checksum line",
+                                filename.maybe_quote(),
+                                i + 1,
+                                options.algo.tag()
+                            );
+                        }
+                        continue;
+                    }
                 };
+                let ck_filename = parsed.filename;
                 let f = match File::open(ck_filename) {
                     Err(_) => {
                         failed_open_file += 1;
@@ -583,15 +447,14 @@ where
                     }
                     Ok(file) => file,
                 };
-                let mut ckf = BufReader::new(Box::new(f) as Box<dyn Read>);
-                let real_sum = digest_reader(
-                    &mut options.digest,
-                    &mut ckf,
-                    binary_check,
-                    options.output_bits,
+                checksum::digest_file(
+                    &mut *options.digest,
+                    &f,
+                    parsed.binary,
+                    options.read_strategy,
                 )
-                .map_err_context(|| "failed to read input".to_string())?
-                .to_ascii_lowercase();
+                .map_err_context(|| "failed to read input".to_string())?;
+                let real_sum = checksum::digest_result(&mut *options.digest, options.output_bits);
                 // FIXME: Filenames with newlines should be treated specially.
                 // GNU appears to replace newlines by \n and backslashes by
                 // \\ and prepend a backslash (to the hash or filename) if it did
@@ -601,7 +464,7 @@ where
                 // If you can, try to preserve invalid unicode using OsStr(ing)Ext
                 // and display it using uucore::display::print_verbatim(). This is
                 // easier (and more important) on Unix than on Windows.
-                if sum == real_sum {
+                if parsed.digest == real_sum {
                     if !options.quiet {
                         println!("{ck_filename}: OK");
                     }
@@ -613,15 +476,22 @@ where
                 }
             }
         } else {
-            let sum = digest_reader(
-                &mut options.digest,
-                &mut file,
-                options.binary,
-                options.output_bits,
-            )
+            if filename == OsStr::new("-") {
+                checksum::digest_reader(&mut *options.digest, &mut stdin().lock(), options.binary)
+            } else {
+                let file =
+                    File::open(filename).map_err_context(|| "failed to open file".to_string())?;
+                checksum::digest_file(
+                    &mut *options.digest,
+                    &file,
+                    options.binary,
+                    options.read_strategy,
+                )
+            }
             .map_err_context(|| "failed to read input".to_string())?;
+            let sum = checksum::digest_result(&mut *options.digest, options.output_bits);
             if options.tag {
-                println!("{} ({}) = {}", options.algoname, filename.display(), sum);
+                println!("{} ({}) = {}", options.algo.tag(), filename.display(), sum);
             } else if options.nonames {
                 println!("{sum}");
             } else {
@@ -649,38 +519,3 @@ where
 
     Ok(())
 }
-
-fn digest_reader<T: Read>(
-    digest: &mut Box<dyn Digest>,
-    reader: &mut BufReader<T>,
-    binary: bool,
-    output_bits: usize,
-) -> io::Result<String> {
-    digest.reset();
-
-    // Read bytes from `reader` and write those bytes to `digest`.
-    //
-    // If `binary` is `false` and the operating system is Windows, then
-    // `DigestWriter` replaces "\r\n" with "\n" before it writes the
-    // bytes into `digest`. Otherwise, it just inserts the bytes as-is.
-    //
-    // In order to support replacing "\r\n", we must call `finalize()`
-    // in order to support the possibility that the last character read
-    // from the reader was "\r". (This character gets buffered by
-    // `DigestWriter` and only written if the following character is
-    // "\n". But when "\r" is the last character read, we need to force
-    // it to be written.)
-    let mut digest_writer = DigestWriter::new(digest, binary);
-    std::io::copy(reader, &mut digest_writer)?;
-    digest_writer.finalize();
-
-    if digest.output_bits() > 0 {
-        Ok(digest.result_str())
-    } else {
-        // Assume it's SHAKE.  result_str() doesn't work with shake (as of 8/30/2016)
-        let mut bytes = Vec::new();
-        bytes.resize((output_bits + 7) / 8, 0);
-        digest.result(&mut bytes);
-        Ok(encode(bytes))
-    }
-}
@@ -1 +1 @@
-uucore::bin!(uu_hashsum);
+uucore::bin!(uu_hashsum, uu_hashsum::uu_app_custom);
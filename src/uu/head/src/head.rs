@@ -7,9 +7,10 @@
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::ffi::OsString;
-use std::io::{self, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult, USimpleError};
+use uucore::input::Reader;
 use uucore::lines::lines;
 use uucore::{format_usage, show};
 
@@ -399,15 +400,15 @@ where
     }
 }
 
-fn head_backwards_file(input: &mut std::fs::File, options: &HeadOptions) -> std::io::Result<()> {
+fn head_backwards_file(input: &mut Reader, options: &HeadOptions) -> std::io::Result<()> {
     match options.mode {
         Mode::AllButLastBytes(n) => {
-            let size = input.metadata()?.len();
+            let size = input.size().unwrap_or_default();
             if n >= size {
                 return Ok(());
             } else {
                 read_n_bytes(
-                    &mut std::io::BufReader::with_capacity(BUF_SIZE, input),
+                    &mut BufReader::with_capacity(input.buffer_size(), input),
                     size - n,
                 )?;
             }
@@ -415,7 +416,7 @@ fn head_backwards_file(input: &mut std::fs::File, options: &HeadOptions) -> std:
         Mode::AllButLastLines(n) => {
             let found = find_nth_line_from_end(input, n, options.zeroed)?;
             read_n_bytes(
-                &mut std::io::BufReader::with_capacity(BUF_SIZE, input),
+                &mut BufReader::with_capacity(input.buffer_size(), input),
                 found,
             )?;
         }
@@ -424,20 +425,26 @@ fn head_backwards_file(input: &mut std::fs::File, options: &HeadOptions) -> std:
     Ok(())
 }
 
-fn head_file(input: &mut std::fs::File, options: &HeadOptions) -> std::io::Result<()> {
+fn head_file(mut input: Reader, options: &HeadOptions) -> std::io::Result<()> {
     match options.mode {
-        Mode::FirstBytes(n) => {
-            read_n_bytes(&mut std::io::BufReader::with_capacity(BUF_SIZE, input), n)
+        Mode::FirstBytes(n) => read_n_bytes(&mut input.buffered(), n),
+        Mode::FirstLines(n) => read_n_lines(&mut input.buffered(), n, options.zeroed),
+        Mode::AllButLastBytes(_) | Mode::AllButLastLines(_) if input.is_seekable() => {
+            head_backwards_file(&mut input, options)
+        }
+        // Pipes and devices can't be seeked, so they are read like the
+        // standard input.
+        Mode::AllButLastBytes(n) => read_but_last_n_bytes(&mut input.buffered(), to_usize(n)?),
+        Mode::AllButLastLines(n) => {
+            read_but_last_n_lines(input.buffered(), to_usize(n)?, options.zeroed)
         }
-        Mode::FirstLines(n) => read_n_lines(
-            &mut std::io::BufReader::with_capacity(BUF_SIZE, input),
-            n,
-            options.zeroed,
-        ),
-        Mode::AllButLastBytes(_) | Mode::AllButLastLines(_) => head_backwards_file(input, options),
     }
 }
 
+fn to_usize(n: u64) -> std::io::Result<usize> {
+    usize::try_from(n).map_err(|e| std::io::Error::new(ErrorKind::Other, e))
+}
+
 fn uu_head(options: &HeadOptions) -> UResult<()> {
     let mut first = true;
     for file in &options.files {
@@ -449,8 +456,7 @@ fn uu_head(options: &HeadOptions) -> UResult<()> {
                     }
                     println!("==> standard input <==");
                 }
-                let stdin = std::io::stdin();
-                let mut stdin = stdin.lock();
+                let mut stdin = Reader::stdin().buffered();
 
                 // Outputting "all-but-last" requires us to use a ring buffer with size n, so n
                 // must be converted from u64 to usize to fit in memory. If such conversion fails,
@@ -479,7 +485,7 @@ fn uu_head(options: &HeadOptions) -> UResult<()> {
                 }
             }
             (name, false) => {
-                let mut file = match std::fs::File::open(name) {
+                let file = match Reader::open(name) {
                     Ok(f) => f,
                     Err(err) => {
                         show!(err.map_err_context(|| format!(
@@ -495,7 +501,7 @@ fn uu_head(options: &HeadOptions) -> UResult<()> {
                     }
                     println!("==> {name} <==");
                 }
-                head_file(&mut file, options)
+                head_file(file, options)
             }
         };
         if res.is_err() {
@@ -121,9 +121,7 @@ struct State {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(get_description())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let users: Vec<String> = matches
         .get_many::<String>(options::ARG_USERS)
@@ -341,6 +339,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_description())
         .infer_long_args(true)
         .arg(
             Arg::new(options::OPT_AUDIT)
@@ -630,10 +629,7 @@ fn id_print(state: &mut State, groups: &[u32]) {
     );
 
     #[cfg(all(any(target_os = "linux", target_os = "android"), feature = "selinux"))]
-    if state.selinux_supported
-        && !state.user_specified
-        && std::env::var_os("POSIXLY_CORRECT").is_none()
-    {
+    if state.selinux_supported && !state.user_specified && !uucore::posix::posixly_correct() {
         // print SElinux context (does not depend on "-Z")
         if let Ok(context) = selinux::SecurityContext::current(false) {
             let bytes = context.as_bytes();
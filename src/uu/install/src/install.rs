@@ -16,8 +16,7 @@ use uucore::backup_control::{self, BackupMode};
 use uucore::display::Quotable;
 use uucore::entries::{grp2gid, usr2uid};
 use uucore::error::{FromIo, UError, UIoError, UResult, UUsageError};
-use uucore::fs::dir_strip_dot_for_creation;
-use uucore::mode::get_umask;
+use uucore::fs::{create_dest, dir_strip_dot_for_creation, source_date_epoch};
 use uucore::perms::{wrap_chown, Verbosity, VerbosityLevel};
 use uucore::{format_usage, show, show_error, show_if_err, uio_error};
 
@@ -367,7 +366,7 @@ fn behavior(matches: &ArgMatches) -> UResult<Behavior> {
 
     let specified_mode: Option<u32> = if matches.contains_id(OPT_MODE) {
         let x = matches.get_one::<String>(OPT_MODE).ok_or(1)?;
-        Some(mode::parse(x, considering_dir, get_umask()).map_err(|err| {
+        Some(mode::parse(x, considering_dir).map_err(|err| {
             show_error!("Invalid mode string: {}", err);
             1
         })?)
@@ -655,30 +654,22 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
         if b.verbose {
             println!("removed {}", to.quote());
         }
-        backup_path = backup_control::get_backup_path(b.backup_mode, to, &b.suffix);
-        if let Some(ref backup_path) = backup_path {
-            // TODO!!
-            if let Err(err) = fs::rename(to, backup_path) {
+        let backup = backup_control::create_backup_path(b.backup_mode, to, &b.suffix)
+            .map_err_context(|| format!("cannot backup {}", to.quote()))?;
+        if let Some(backup) = backup {
+            if let Err(err) = fs::rename(to, &backup) {
                 return Err(InstallError::BackupFailed(
                     to.to_path_buf(),
-                    backup_path.to_path_buf(),
+                    backup.to_path_buf(),
                     err,
                 )
                 .into());
             }
+            backup_path = Some(backup.commit());
         }
     }
 
-    if from.as_os_str() == "/dev/null" {
-        /* workaround a limitation of fs::copy
-         * https://github.com/rust-lang/rust/issues/79390
-         */
-        if let Err(err) = File::create(to) {
-            return Err(
-                InstallError::InstallFailed(from.to_path_buf(), to.to_path_buf(), err).into(),
-            );
-        }
-    } else if let Err(err) = fs::copy(from, to) {
+    if let Err(err) = copy_contents(from, to) {
         return Err(InstallError::InstallFailed(from.to_path_buf(), to.to_path_buf(), err).into());
     }
 
@@ -769,14 +760,20 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
         }
     }
 
-    if b.preserve_timestamps {
-        let meta = match fs::metadata(from) {
+    // The files that a reproducible build installs are never newer than
+    // SOURCE_DATE_EPOCH, whether their times are preserved or not.
+    let epoch = source_date_epoch().map(FileTime::from_system_time);
+    if b.preserve_timestamps || epoch.is_some() {
+        let meta = match fs::metadata(if b.preserve_timestamps { from } else { to }) {
             Ok(meta) => meta,
             Err(e) => return Err(InstallError::MetadataFailed(e).into()),
         };
 
-        let modified_time = FileTime::from_last_modification_time(&meta);
+        let mut modified_time = FileTime::from_last_modification_time(&meta);
         let accessed_time = FileTime::from_last_access_time(&meta);
+        if let Some(epoch) = epoch {
+            modified_time = modified_time.min(epoch);
+        }
 
         match set_file_times(to, accessed_time, modified_time) {
             Ok(_) => {}
@@ -795,6 +792,23 @@ fn copy(from: &Path, to: &Path, b: &Behavior) -> UResult<()> {
     Ok(())
 }
 
+/// Copy the contents of _from_ to a new file _to_.
+///
+/// Like GNU install, an existing _to_ is replaced rather than written into,
+/// which leaves a running program and the other links to it alone, and the
+/// new file is created exclusively with [`create_dest`], so that nothing
+/// swapped in for it in the meantime, like a symbolic link in a
+/// world-writable directory, gets written through.
+fn copy_contents(from: &Path, to: &Path) -> std::io::Result<u64> {
+    let mut src_file = File::open(from)?;
+    match fs::remove_file(to) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    let dst_file = create_dest(to, 0o600, true)?;
+    std::io::copy(&mut src_file, &mut &dst_file)
+}
+
 /// Return true if a file is necessary to copy. This is the case when:
 ///
 /// - _from_ or _to_ is nonexistent;
@@ -4,15 +4,11 @@ use std::path::Path;
 use uucore::mode;
 
 /// Takes a user-supplied string and tries to parse to u16 mode bitmask.
-pub fn parse(mode_string: &str, considering_dir: bool, umask: u32) -> Result<u32, String> {
-    let numbers: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-
-    // Passing 000 as the existing permissions seems to mirror GNU behavior.
-    if mode_string.contains(numbers) {
-        mode::parse_numeric(0, mode_string, considering_dir)
-    } else {
-        mode::parse_symbolic(0, mode_string, umask, considering_dir)
-    }
+///
+/// Like with GNU install, the mode changes no permissions, and the umask
+/// doesn't apply to it.
+pub fn parse(mode_string: &str, considering_dir: bool) -> Result<u32, String> {
+    mode::parse_creation_mode(mode_string, 0, 0, considering_dir)
 }
 
 /// chmod a file or directory on UNIX.
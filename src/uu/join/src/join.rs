@@ -22,6 +22,7 @@ use std::num::IntErrorKind;
 use std::os::unix::ffi::OsStrExt;
 use uucore::display::Quotable;
 use uucore::error::{set_exit_code, UError, UResult, USimpleError};
+use uucore::lines::LineEnding;
 use uucore::{crash, crash_if_err};
 
 #[derive(Debug)]
@@ -59,13 +60,6 @@ enum FileNum {
     File2,
 }
 
-#[repr(u8)]
-#[derive(Copy, Clone)]
-enum LineEnding {
-    Nul = 0,
-    Newline = b'\n',
-}
-
 #[derive(Copy, Clone, PartialEq)]
 enum Sep {
     Char(u8),
@@ -679,9 +673,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         settings.headers = true;
     }
 
-    if matches.get_flag("z") {
-        settings.line_ending = LineEnding::Nul;
-    }
+    settings.line_ending = LineEnding::from_zero_flag(matches.get_flag("z"));
 
     let file1 = matches.get_one::<String>("file1").unwrap();
     let file2 = matches.get_one::<String>("file2").unwrap();
@@ -9,7 +9,7 @@
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use uucore::display::Quotable;
-use uucore::error::{FromIo, UError, UResult};
+use uucore::error::{FromIo, UError, UResult, USimpleError};
 use uucore::fs::{make_path_relative_to, paths_refer_to_same_file};
 use uucore::{format_usage, prompt_yes, show_error};
 
@@ -21,17 +21,21 @@ use std::fs;
 
 #[cfg(any(unix, target_os = "redox"))]
 use std::os::unix::fs::symlink;
-#[cfg(windows)]
-use std::os::windows::fs::{symlink_dir, symlink_file};
 use std::path::{Path, PathBuf};
 use uucore::backup_control::{self, BackupMode};
 use uucore::fs::{canonicalize, MissingHandling, ResolveMode};
 
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+use crate::windows::{junction, symlink};
+
 pub struct Settings {
     overwrite: OverwriteMode,
     backup: BackupMode,
     suffix: String,
     symbolic: bool,
+    junction: bool,
     relative: bool,
     logical: bool,
     target_dir: Option<String>,
@@ -107,6 +111,7 @@ mod options {
     pub const INTERACTIVE: &str = "interactive";
     pub const NO_DEREFERENCE: &str = "no-dereference";
     pub const SYMBOLIC: &str = "symbolic";
+    pub const JUNCTION: &str = "junction";
     pub const LOGICAL: &str = "logical";
     pub const PHYSICAL: &str = "physical";
     pub const TARGET_DIRECTORY: &str = "target-directory";
@@ -119,13 +124,7 @@ static ARG_FILES: &str = "files";
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let long_usage = format!(
-        "{}\n\n{}",
-        LONG_USAGE,
-        backup_control::BACKUP_CONTROL_LONG_HELP
-    );
-
-    let matches = uu_app().after_help(long_usage).try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     /* the list of files */
 
@@ -136,6 +135,13 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         .collect();
 
     let symbolic = matches.get_flag(options::SYMBOLIC);
+    let junction = matches.get_flag(options::JUNCTION);
+    if junction && !cfg!(windows) {
+        return Err(USimpleError::new(
+            1,
+            "--junction is only supported on Windows",
+        ));
+    }
 
     let overwrite_mode = if matches.get_flag(options::FORCE) {
         OverwriteMode::Force
@@ -156,6 +162,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         backup: backup_mode,
         suffix: backup_suffix,
         symbolic,
+        junction,
         logical,
         relative: matches.get_flag(options::RELATIVE),
         target_dir: matches
@@ -174,6 +181,11 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(format!(
+            "{}\n\n{}",
+            LONG_USAGE,
+            backup_control::BACKUP_CONTROL_LONG_HELP
+        ))
         .infer_long_args(true)
         .arg(backup_control::arguments::backup())
         .arg(backup_control::arguments::backup_no_args())
@@ -232,6 +244,13 @@ pub fn uu_app() -> Command {
                 .overrides_with(options::SYMBOLIC)
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::JUNCTION)
+                .long(options::JUNCTION)
+                .help("make directory junctions instead of hard links (Windows only)")
+                .conflicts_with(options::SYMBOLIC)
+                .action(ArgAction::SetTrue),
+        )
         .arg(backup_control::arguments::suffix())
         .arg(
             Arg::new(options::TARGET_DIRECTORY)
@@ -385,20 +404,17 @@ fn link(src: &Path, dst: &Path, settings: &Settings) -> UResult<()> {
     };
 
     if dst.is_symlink() || dst.exists() {
-        backup_path = match settings.backup {
-            BackupMode::NoBackup => None,
-            BackupMode::SimpleBackup => Some(simple_backup_path(dst, &settings.suffix)),
-            BackupMode::NumberedBackup => Some(numbered_backup_path(dst)),
-            BackupMode::ExistingBackup => Some(existing_backup_path(dst, &settings.suffix)),
-        };
         if settings.backup == BackupMode::ExistingBackup && !settings.symbolic {
             // when ln --backup f f, it should detect that it is the same file
             if paths_refer_to_same_file(src, dst, true) {
                 return Err(LnError::SameFile(src.to_owned(), dst.to_owned()).into());
             }
         }
-        if let Some(ref p) = backup_path {
-            fs::rename(dst, p).map_err_context(|| format!("cannot backup {}", dst.quote()))?;
+        let backup = backup_control::create_backup_path(settings.backup, dst, &settings.suffix)
+            .map_err_context(|| format!("cannot backup {}", dst.quote()))?;
+        if let Some(p) = backup {
+            fs::rename(dst, &p).map_err_context(|| format!("cannot backup {}", dst.quote()))?;
+            backup_path = Some(p.commit());
         }
         match settings.overwrite {
             OverwriteMode::NoClobber => {}
@@ -421,7 +437,12 @@ fn link(src: &Path, dst: &Path, settings: &Settings) -> UResult<()> {
     }
 
     if settings.symbolic {
-        symlink(&source, dst)?;
+        symlink(&source, dst)
+            .map_err_context(|| format!("failed to create symbolic link {}", dst.quote()))?;
+    } else if settings.junction {
+        #[cfg(windows)]
+        junction(&source, dst)
+            .map_err_context(|| format!("failed to create junction {}", dst.quote()))?;
     } else {
         let p = if settings.logical && source.is_symlink() {
             // if we want to have an hard link,
@@ -450,37 +471,3 @@ fn link(src: &Path, dst: &Path, settings: &Settings) -> UResult<()> {
     }
     Ok(())
 }
-
-fn simple_backup_path(path: &Path, suffix: &str) -> PathBuf {
-    let mut p = path.as_os_str().to_str().unwrap().to_owned();
-    p.push_str(suffix);
-    PathBuf::from(p)
-}
-
-fn numbered_backup_path(path: &Path) -> PathBuf {
-    let mut i: u64 = 1;
-    loop {
-        let new_path = simple_backup_path(path, &format!(".~{i}~"));
-        if !new_path.exists() {
-            return new_path;
-        }
-        i += 1;
-    }
-}
-
-fn existing_backup_path(path: &Path, suffix: &str) -> PathBuf {
-    let test_path = simple_backup_path(path, ".~1~");
-    if test_path.exists() {
-        return numbered_backup_path(path);
-    }
-    simple_backup_path(path, suffix)
-}
-
-#[cfg(windows)]
-pub fn symlink<P1: AsRef<Path>, P2: AsRef<Path>>(src: P1, dst: P2) -> std::io::Result<()> {
-    if src.as_ref().is_dir() {
-        symlink_dir(src, dst)
-    } else {
-        symlink_file(src, dst)
-    }
-}
@@ -0,0 +1,157 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore FSCTL winioctl winnt
+
+//! Creation of symbolic links and directory junctions on Windows.
+use std::ffi::OsStr;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::fs::{symlink_dir, symlink_file, OpenOptionsExt};
+use std::os::windows::io::AsRawHandle;
+use std::path::{Component, Path, Prefix};
+use std::ptr;
+
+use windows_sys::Win32::Foundation::ERROR_PRIVILEGE_NOT_HELD;
+use windows_sys::Win32::Storage::FileSystem::{
+    FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT,
+};
+use windows_sys::Win32::System::IO::DeviceIoControl;
+
+// From winnt.h and winioctl.h
+const IO_REPARSE_TAG_MOUNT_POINT: u32 = 0xA000_0003;
+const FSCTL_SET_REPARSE_POINT: u32 = 0x0009_00A4;
+
+/// Create a symbolic link at `dst` pointing to `src`.
+///
+/// Windows has distinct symbolic links for files and directories, so the
+/// kind is chosen from what `src` is, resolving a relative `src` from the
+/// directory of the link like the link itself will be.
+///
+/// The standard library asks for an unprivileged symbolic link, which
+/// works when Developer Mode is enabled. Otherwise creating a symbolic link
+/// needs a privilege that only administrators have by default, so the
+/// error says so.
+pub fn symlink(src: &Path, dst: &Path) -> io::Result<()> {
+    let resolved = match dst.parent() {
+        Some(parent) if src.is_relative() => parent.join(src),
+        _ => src.to_path_buf(),
+    };
+    let result = if resolved.is_dir() {
+        symlink_dir(src, dst)
+    } else {
+        symlink_file(src, dst)
+    };
+    result.map_err(|e| {
+        if e.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD as i32) {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "creating symbolic links requires administrator privileges or Developer Mode",
+            )
+        } else {
+            e
+        }
+    })
+}
+
+/// Create a directory junction at `dst` pointing to the directory `src`.
+///
+/// Unlike symbolic links, junctions don't need any privilege, but they can
+/// only point to an absolute path of a directory on a local volume.
+pub fn junction(src: &Path, dst: &Path) -> io::Result<()> {
+    let target = fs::canonicalize(src)?;
+    if !target.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the target of a junction must be a directory",
+        ));
+    }
+    // `canonicalize` returns a verbatim path like `\\?\C:\dir`, of which the
+    // junction needs the `C:\dir` part.
+    match target.components().next() {
+        Some(Component::Prefix(prefix)) if matches!(prefix.kind(), Prefix::VerbatimDisk(_)) => {}
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the target of a junction must be on a local volume",
+            ))
+        }
+    }
+    let target: Vec<u16> = target.as_os_str().encode_wide().skip(4).collect();
+
+    let buffer = mount_point_reparse_buffer(&target)?;
+    fs::create_dir(dst)?;
+    let result = set_reparse_point(dst, &buffer);
+    if result.is_err() {
+        // Don't leave an empty directory behind
+        let _ = fs::remove_dir(dst);
+    }
+    result
+}
+
+/// Build the `REPARSE_DATA_BUFFER` of a mount point, i.e. a junction, to
+/// the absolute path `target`.
+fn mount_point_reparse_buffer(target: &[u16]) -> io::Result<Vec<u8>> {
+    // The "substitute name" is the NT path that is followed, the "print
+    // name" is what is shown to users. Both are NUL terminated.
+    let substitute_name: Vec<u16> = OsStr::new(r"\??\")
+        .encode_wide()
+        .chain(target.iter().copied())
+        .collect();
+    let print_name = target;
+
+    // The four offsets and lengths, followed by both names
+    let data_length = 8 + (substitute_name.len() + 1 + print_name.len() + 1) * 2;
+    let data_length = u16::try_from(data_length)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path too long for a junction"))?;
+    let substitute_name_length = (substitute_name.len() * 2) as u16;
+    let print_name_offset = substitute_name_length + 2;
+    let print_name_length = (print_name.len() * 2) as u16;
+
+    let mut buffer = Vec::with_capacity(8 + usize::from(data_length));
+    buffer.extend_from_slice(&IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+    buffer.extend_from_slice(&data_length.to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes()); // Reserved
+    buffer.extend_from_slice(&0u16.to_le_bytes()); // SubstituteNameOffset
+    buffer.extend_from_slice(&substitute_name_length.to_le_bytes());
+    buffer.extend_from_slice(&print_name_offset.to_le_bytes());
+    buffer.extend_from_slice(&print_name_length.to_le_bytes());
+    for c in substitute_name
+        .iter()
+        .chain(&[0])
+        .chain(print_name)
+        .chain(&[0])
+    {
+        buffer.extend_from_slice(&c.to_le_bytes());
+    }
+    Ok(buffer)
+}
+
+/// Turn the empty directory `dir` into a reparse point described by `buffer`.
+fn set_reparse_point(dir: &Path, buffer: &[u8]) -> io::Result<()> {
+    let dir = OpenOptions::new()
+        .write(true)
+        .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+        .open(dir)?;
+    let mut bytes_returned = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            dir.as_raw_handle() as _,
+            FSCTL_SET_REPARSE_POINT,
+            buffer.as_ptr().cast(),
+            buffer.len() as u32,
+            ptr::null_mut(),
+            0,
+            &mut bytes_returned,
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
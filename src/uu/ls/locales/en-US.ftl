@@ -0,0 +1,24 @@
+# The messages of ls, in English, which are the reference for the catalogs
+# in other languages. See uucore::i18n for the format.
+
+error-invalid-block-size = invalid --block-size argument { $value }
+error-invalid-time-style = invalid --time-style argument { $value }
+    Possible values are: { $possible }
+
+    For more information try --help
+error-invalid-line-width = invalid line width: { $value }
+error-io = general io error: { $error }
+error-no-such-file = cannot access '{ $path }': No such file or directory
+error-not-permitted = cannot access '{ $path }': Operation not permitted
+error-directory-permission-denied = cannot open directory '{ $path }': Permission denied
+error-file-permission-denied = cannot open file '{ $path }': Permission denied
+error-bad-file-descriptor = cannot open directory '{ $path }': Bad file descriptor
+error-unknown-io = unknown io error: '{ $path }', '{ $error }'
+error-already-listed = { $path }: not listing already-listed directory
+error-invalid-columns = ignoring invalid width in environment variable COLUMNS: { $value }
+error-unrecognized-prefix = unrecognized prefix: { $prefix }
+error-unparsable-ls-colors = unparsable value for LS_COLORS environment variable
+warning-invalid-ignore = Invalid pattern for ignore: { $pattern }
+warning-invalid-hide = Invalid pattern for hide: { $pattern }
+warning-get-context = failed to get security context of: { $path }
+warning-context-encoding = getting security context of: { $path }: { $error }
@@ -0,0 +1,114 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (ToDO) setlocale strxfrm strcoll
+
+//! The order of the file names in the collation of the locale, from
+//! `LC_ALL`, `LC_COLLATE` or `LANG`, like GNU ls sorts them with `strcoll`.
+//!
+//! The names are compared byte by byte in the C and POSIX locales, and when
+//! the C library has no collation for the locale.
+use std::cmp::Ordering;
+use std::ffi::{OsStr, OsString};
+
+/// The way the names are compared.
+pub(crate) struct Collator {
+    /// Whether the locale has a collation of its own.
+    locale: bool,
+}
+
+/// A name, which sorts in the collation it was made for.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct CollationKey {
+    /// The name transformed with `strxfrm`, whose bytes are in the order of
+    /// the collation.
+    transformed: Vec<u8>,
+    /// The name itself, which orders the names that collate equally.
+    name: OsString,
+}
+
+impl Collator {
+    /// The collation of the locale of the environment.
+    #[cfg(unix)]
+    pub(crate) fn from_env() -> Self {
+        use std::ffi::CStr;
+        use uucore::libc;
+
+        let locale = unsafe {
+            let name = libc::setlocale(libc::LC_COLLATE, b"\0".as_ptr().cast());
+            // The C locales with another character set, like `C.UTF-8`, keep
+            // the order of the bytes.
+            !name.is_null() && !is_c_locale(CStr::from_ptr(name).to_bytes())
+        };
+        Self { locale }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn from_env() -> Self {
+        Self { locale: false }
+    }
+
+    /// Whether the names are compared byte by byte.
+    pub(crate) fn is_bytewise(&self) -> bool {
+        !self.locale
+    }
+
+    /// Compares `a` and `b` in this collation.
+    pub(crate) fn compare(&self, a: &OsStr, b: &OsStr) -> Ordering {
+        if self.locale {
+            self.key(a).cmp(&self.key(b))
+        } else {
+            a.cmp(b)
+        }
+    }
+
+    /// The key of `name`, to sort many names with few calls to the C
+    /// library.
+    pub(crate) fn key(&self, name: &OsStr) -> CollationKey {
+        CollationKey {
+            transformed: if self.locale {
+                transform(name)
+            } else {
+                Vec::new()
+            },
+            name: name.to_owned(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_c_locale(name: &[u8]) -> bool {
+    name == b"C" || name == b"POSIX" || name.starts_with(b"C.")
+}
+
+#[cfg(unix)]
+fn transform(name: &OsStr) -> Vec<u8> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use uucore::libc;
+
+    // The names of files can't hold a NUL byte.
+    let name = match CString::new(name.as_bytes()) {
+        Ok(name) => name,
+        Err(_) => return Vec::new(),
+    };
+    let mut transformed = Vec::<u8>::new();
+    loop {
+        let capacity = transformed.capacity();
+        let len =
+            unsafe { libc::strxfrm(transformed.as_mut_ptr().cast(), name.as_ptr(), capacity) };
+        if len < capacity {
+            unsafe { transformed.set_len(len) };
+            return transformed;
+        }
+        // The NUL that ends it is written too.
+        transformed.reserve_exact(len + 1);
+    }
+}
+
+#[cfg(not(unix))]
+fn transform(_name: &OsStr) -> Vec<u8> {
+    Vec::new()
+}
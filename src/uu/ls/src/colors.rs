@@ -0,0 +1,548 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (ToDO) setuid setgid dircolors getxattr
+
+//! The colors of the file names, from the `LS_COLORS` environment variable
+//! as written by `dircolors`, and the way GNU ls uses them.
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::Path;
+use uucore::display::Quotable;
+use uucore::translate;
+
+use crate::stat::{FileType, Metadata};
+
+/// The kinds of files and of output that `LS_COLORS` gives a sequence to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Indicator {
+    /// `lc`: the start of a color sequence
+    Left,
+    /// `rc`: the end of a color sequence
+    Right,
+    /// `ec`: the sequence that ends a color, instead of `lc`, `rs` and `rc`
+    End,
+    /// `rs`: the color that resets to the ordinary colors
+    Reset,
+    /// `no`: the color of the text that isn't a file name
+    Normal,
+    /// `fi`: a regular file
+    File,
+    /// `di`: a directory
+    Dir,
+    /// `ln`: a symbolic link, or `target` to color it like what it points to
+    Link,
+    /// `pi`: a named pipe
+    Fifo,
+    /// `so`: a socket
+    Socket,
+    /// `bd`: a block device
+    BlockDevice,
+    /// `cd`: a character device
+    CharDevice,
+    /// `mi`: a missing file, like the target of a dangling symbolic link
+    Missing,
+    /// `or`: a dangling symbolic link
+    Orphan,
+    /// `ex`: an executable file
+    Exec,
+    /// `do`: a door
+    Door,
+    /// `su`: a setuid file
+    Setuid,
+    /// `sg`: a setgid file
+    Setgid,
+    /// `st`: a sticky directory that others can't write to
+    Sticky,
+    /// `ow`: a directory that others can write to, which isn't sticky
+    OtherWritable,
+    /// `tw`: a sticky directory that others can write to
+    StickyOtherWritable,
+    /// `ca`: a file with capabilities
+    Capability,
+    /// `mh`: a file with more than one hard link
+    MultiHardLink,
+}
+
+/// The names of the indicators, in the order of [`Indicator`], and their
+/// default sequences. `cl` is accepted, though ls has no use for it.
+const INDICATORS: [(&str, Option<&str>); 24] = [
+    ("lc", Some("\x1b[")),
+    ("rc", Some("m")),
+    ("ec", None),
+    ("rs", Some("0")),
+    ("no", None),
+    ("fi", None),
+    ("di", Some("01;34")),
+    ("ln", Some("01;36")),
+    ("pi", Some("33")),
+    ("so", Some("01;35")),
+    ("bd", Some("01;33")),
+    ("cd", Some("01;33")),
+    ("mi", None),
+    ("or", None),
+    ("ex", Some("01;32")),
+    ("do", Some("01;35")),
+    ("su", Some("37;41")),
+    ("sg", Some("30;43")),
+    ("st", Some("37;44")),
+    ("ow", Some("34;42")),
+    ("tw", Some("30;42")),
+    ("ca", None),
+    ("mh", None),
+    ("cl", Some("\x1b[K")),
+];
+
+/// Why `LS_COLORS` can't be used.
+#[derive(Debug)]
+pub enum LsColorsError {
+    /// A two letter indicator that isn't one of those known.
+    UnrecognizedPrefix(String),
+    Unparsable,
+}
+
+impl fmt::Display for LsColorsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnrecognizedPrefix(prefix) => write!(
+                f,
+                "{}",
+                translate!("error-unrecognized-prefix", prefix = prefix)
+            ),
+            Self::Unparsable => write!(f, "{}", translate!("error-unparsable-ls-colors")),
+        }
+    }
+}
+
+/// The sequences to color the file names with.
+pub struct LsColors {
+    indicators: Vec<Option<Vec<u8>>>,
+    /// The suffixes of the file names and their colors, the last ones of
+    /// `LS_COLORS` first, as they take precedence.
+    extensions: Vec<(Vec<u8>, Vec<u8>)>,
+    /// Whether `ln=target` colors the symbolic links like their targets.
+    symlink_as_referent: bool,
+    /// Whether a color was already written, after the sequence that gets
+    /// the terminal out of whatever color it was in.
+    used: Cell<bool>,
+}
+
+impl Default for LsColors {
+    fn default() -> Self {
+        Self {
+            indicators: INDICATORS
+                .iter()
+                .map(|(_, seq)| seq.map(|seq| seq.as_bytes().to_vec()))
+                .collect(),
+            extensions: vec![],
+            symlink_as_referent: false,
+            used: Cell::new(false),
+        }
+    }
+}
+
+/// Read the sequence that starts `s`, with its escapes and caret notation,
+/// up to the `:` that ends it, or the `=` if `equals_end` is set. It
+/// returns the sequence and the rest of `s`, from the separator on.
+fn parse_sequence(s: &[u8], equals_end: bool) -> Option<(Vec<u8>, &[u8])> {
+    let mut seq = vec![];
+    let mut i = 0;
+    while i < s.len() {
+        match s[i] {
+            b':' => break,
+            b'=' if equals_end => break,
+            b'\\' => {
+                i += 1;
+                let c = *s.get(i)?;
+                i += 1;
+                let byte = match c {
+                    b'0'..=b'7' => {
+                        let mut num = c - b'0';
+                        while let Some(d @ b'0'..=b'7') = s.get(i) {
+                            num = (num << 3).wrapping_add(d - b'0');
+                            i += 1;
+                        }
+                        num
+                    }
+                    b'x' | b'X' => {
+                        let mut num = 0u8;
+                        while let Some(d) = s.get(i).and_then(|d| (*d as char).to_digit(16)) {
+                            num = (num << 4).wrapping_add(d as u8);
+                            i += 1;
+                        }
+                        num
+                    }
+                    b'a' => 0x07,
+                    b'b' => 0x08,
+                    b'e' => 0x1b,
+                    b'f' => 0x0c,
+                    b'n' => b'\n',
+                    b'r' => b'\r',
+                    b't' => b'\t',
+                    b'v' => 0x0b,
+                    b'?' => 0x7f,
+                    b'_' => b' ',
+                    c => c,
+                };
+                seq.push(byte);
+            }
+            b'^' => {
+                i += 1;
+                match s.get(i) {
+                    Some(c @ b'@'..=b'~') => seq.push(c & 0o37),
+                    Some(b'?') => seq.push(0x7f),
+                    _ => return None,
+                }
+                i += 1;
+            }
+            c => {
+                seq.push(c);
+                i += 1;
+            }
+        }
+    }
+    Some((seq, &s[i..]))
+}
+
+#[cfg(unix)]
+fn os_str_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    Cow::Borrowed(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn os_str_bytes(s: &OsStr) -> Cow<'_, [u8]> {
+    Cow::Owned(s.to_string_lossy().into_owned().into_bytes())
+}
+
+#[cfg(unix)]
+fn mode(md: &Metadata) -> u32 {
+    md.mode()
+}
+
+#[cfg(not(unix))]
+fn mode(_md: &Metadata) -> u32 {
+    0
+}
+
+#[cfg(unix)]
+fn nlink(md: &Metadata) -> u64 {
+    md.nlink()
+}
+
+#[cfg(not(unix))]
+fn nlink(_md: &Metadata) -> u64 {
+    1
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn has_capability(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use uucore::libc;
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return false,
+    };
+    let name = b"security.capability\0";
+    // Only the size of the attribute is asked for.
+    let size =
+        unsafe { libc::getxattr(path.as_ptr(), name.as_ptr().cast(), std::ptr::null_mut(), 0) };
+    size > 0
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn has_capability(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(any(target_os = "solaris", target_os = "illumos"))]
+fn is_door(md: &Metadata) -> bool {
+    mode(md) & 0o170000 == 0o150000
+}
+
+#[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
+fn is_door(_md: &Metadata) -> bool {
+    false
+}
+
+impl LsColors {
+    /// The colors of `LS_COLORS`, or the default ones if it isn't set.
+    pub fn from_env() -> Result<Self, LsColorsError> {
+        match env::var_os("LS_COLORS") {
+            Some(s) if !s.is_empty() => Self::parse(&os_str_bytes(&s)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// The colors of a string in the format of `LS_COLORS`, over the
+    /// default ones.
+    pub fn parse(mut s: &[u8]) -> Result<Self, LsColorsError> {
+        let mut colors = Self::default();
+        loop {
+            match s {
+                [] => break,
+                [b':', rest @ ..] => s = rest,
+                [b'*', rest @ ..] => {
+                    let (suffix, rest) =
+                        parse_sequence(rest, true).ok_or(LsColorsError::Unparsable)?;
+                    let rest = rest.strip_prefix(b"=").ok_or(LsColorsError::Unparsable)?;
+                    let (seq, rest) =
+                        parse_sequence(rest, false).ok_or(LsColorsError::Unparsable)?;
+                    colors.extensions.insert(0, (suffix, seq));
+                    s = rest;
+                }
+                [a, b, b'=', rest @ ..] => {
+                    let label = [*a, *b];
+                    // Like GNU ls, a sequence that can't be read is reported
+                    // as if its indicator was unknown.
+                    let unrecognized = || {
+                        LsColorsError::UnrecognizedPrefix(
+                            String::from_utf8_lossy(&label).quote().to_string(),
+                        )
+                    };
+                    let i = INDICATORS
+                        .iter()
+                        .position(|(name, _)| name.as_bytes() == label)
+                        .ok_or_else(unrecognized)?;
+                    let (seq, rest) = parse_sequence(rest, false).ok_or_else(unrecognized)?;
+                    colors.indicators[i] = Some(seq);
+                    s = rest;
+                }
+                _ => return Err(LsColorsError::Unparsable),
+            }
+        }
+        colors.symlink_as_referent = colors.get(Indicator::Link) == Some(b"target");
+        Ok(colors)
+    }
+
+    fn get(&self, indicator: Indicator) -> Option<&[u8]> {
+        self.indicators[indicator as usize].as_deref()
+    }
+
+    /// Whether `indicator` has a sequence that changes the color.
+    fn is_colored(&self, indicator: Indicator) -> bool {
+        !matches!(self.get(indicator), None | Some(b"" | b"0" | b"00"))
+    }
+
+    /// Whether the colors of the symbolic links depend on their targets.
+    pub fn needs_link_target(&self) -> bool {
+        self.symlink_as_referent || self.is_colored(Indicator::Orphan)
+    }
+
+    /// Whether the color of a file of the type `file_type` depends on its
+    /// metadata, and not only on its type as `readdir` tells it.
+    pub fn needs_metadata(&self, file_type: &FileType) -> bool {
+        let indicators: &[Indicator] = if file_type.is_file() {
+            &[
+                Indicator::Setuid,
+                Indicator::Setgid,
+                Indicator::Exec,
+                Indicator::MultiHardLink,
+            ]
+        } else if file_type.is_dir() {
+            &[
+                Indicator::Sticky,
+                Indicator::OtherWritable,
+                Indicator::StickyOtherWritable,
+            ]
+        } else if !file_type.is_symlink() && cfg!(any(target_os = "solaris", target_os = "illumos"))
+        {
+            // The doors are only told apart by their mode.
+            &[Indicator::Door]
+        } else {
+            &[]
+        };
+        indicators
+            .iter()
+            .any(|&indicator| self.is_colored(indicator))
+    }
+
+    /// The kind of file that `file_type` is, as far as the colors tell them
+    /// apart. `md` is only needed if [`Self::needs_metadata`] holds.
+    fn indicator_for(&self, path: &Path, file_type: &FileType, md: Option<&Metadata>) -> Indicator {
+        let mode = md.map_or(0, mode);
+        if file_type.is_file() {
+            if mode & 0o4000 != 0 && self.is_colored(Indicator::Setuid) {
+                Indicator::Setuid
+            } else if mode & 0o2000 != 0 && self.is_colored(Indicator::Setgid) {
+                Indicator::Setgid
+            } else if self.is_colored(Indicator::Capability) && has_capability(path) {
+                Indicator::Capability
+            } else if mode & 0o111 != 0 && self.is_colored(Indicator::Exec) {
+                Indicator::Exec
+            } else if md.map_or(1, nlink) > 1 && self.is_colored(Indicator::MultiHardLink) {
+                Indicator::MultiHardLink
+            } else {
+                Indicator::File
+            }
+        } else if file_type.is_dir() {
+            let sticky = mode & 0o1000 != 0;
+            let other_writable = mode & 0o0002 != 0;
+            if sticky && other_writable && self.is_colored(Indicator::StickyOtherWritable) {
+                Indicator::StickyOtherWritable
+            } else if other_writable && self.is_colored(Indicator::OtherWritable) {
+                Indicator::OtherWritable
+            } else if sticky && self.is_colored(Indicator::Sticky) {
+                Indicator::Sticky
+            } else {
+                Indicator::Dir
+            }
+        } else if file_type.is_symlink() {
+            Indicator::Link
+        } else if md.map_or(false, is_door) {
+            Indicator::Door
+        } else {
+            #[cfg(unix)]
+            {
+                if file_type.is_fifo() {
+                    return Indicator::Fifo;
+                } else if file_type.is_socket() {
+                    return Indicator::Socket;
+                } else if file_type.is_block_device() {
+                    return Indicator::BlockDevice;
+                } else if file_type.is_char_device() {
+                    return Indicator::CharDevice;
+                }
+            }
+            // A file of any other type is colored like a dangling link.
+            Indicator::Orphan
+        }
+    }
+
+    /// The color of a file with the kind `indicator`, or of the suffix of
+    /// `name` for a regular file.
+    fn sequence_for(&self, indicator: Indicator, name: &OsStr) -> Option<&[u8]> {
+        if indicator == Indicator::File {
+            let name = os_str_bytes(name);
+            let extension = self.extensions.iter().find(|(suffix, _)| {
+                name.len() >= suffix.len()
+                    && name[name.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+            });
+            if let Some((_, seq)) = extension {
+                return Some(seq);
+            }
+        }
+        self.get(indicator)
+    }
+
+    /// The color of the file `name` at `path`, of the type `file_type`,
+    /// which is `None` if it is missing, and with the metadata `md`, which is
+    /// only needed if [`Self::needs_metadata`] holds. For a symbolic link,
+    /// `target` is the metadata of the file it points to, which is looked up
+    /// only if [`Self::needs_link_target`] holds, and is `None` if it is
+    /// dangling.
+    pub fn color_for(
+        &self,
+        path: &Path,
+        name: &OsStr,
+        file_type: Option<&FileType>,
+        md: Option<&Metadata>,
+        target: Option<&Metadata>,
+    ) -> Option<&[u8]> {
+        let file_type = match file_type {
+            Some(file_type) => file_type,
+            None if self.is_colored(Indicator::Missing) => {
+                return self.get(Indicator::Missing);
+            }
+            None => return self.get(Indicator::Orphan),
+        };
+        let is_link = file_type.is_symlink();
+        let indicator = match target {
+            Some(target) if is_link && self.symlink_as_referent => {
+                self.indicator_for(path, &target.file_type(), Some(target))
+            }
+            None if is_link && self.needs_link_target() => Indicator::Orphan,
+            _ => self.indicator_for(path, file_type, md),
+        };
+        self.sequence_for(indicator, name)
+    }
+
+    /// The color of the target `name` at `path` of a symbolic link, with
+    /// the metadata `md` that is `None` if it is missing.
+    pub fn color_for_target(
+        &self,
+        path: &Path,
+        name: &OsStr,
+        md: Option<&Metadata>,
+    ) -> Option<&[u8]> {
+        match md {
+            Some(md) => {
+                self.sequence_for(self.indicator_for(path, &md.file_type(), Some(md)), name)
+            }
+            None if self.is_colored(Indicator::Missing) => self.get(Indicator::Missing),
+            None => self.get(Indicator::Orphan),
+        }
+    }
+
+    /// Append `seq` to `out`, after the sequence that resets the colors
+    /// the first time.
+    fn put(&self, out: &mut Vec<u8>, seq: Option<&[u8]>) {
+        if !self.used.replace(true) {
+            self.put_end(out);
+        }
+        out.extend_from_slice(seq.unwrap_or_default());
+    }
+
+    /// Append the sequence that gets back to the ordinary colors.
+    fn put_end(&self, out: &mut Vec<u8>) {
+        match self.get(Indicator::End) {
+            Some(end) => self.put(out, Some(end)),
+            None => {
+                self.put(out, self.get(Indicator::Left));
+                self.put(out, self.get(Indicator::Reset));
+                self.put(out, self.get(Indicator::Right));
+            }
+        }
+    }
+
+    /// Append the sequence that starts the color `seq`.
+    fn put_color(&self, out: &mut Vec<u8>, seq: &[u8]) {
+        self.put(out, self.get(Indicator::Left));
+        self.put(out, Some(seq));
+        self.put(out, self.get(Indicator::Right));
+    }
+
+    /// `name` in the color `seq`, which is one that [`Self::color_for`] or
+    /// [`Self::color_for_target`] returned.
+    pub fn paint(&self, name: &str, seq: Option<&[u8]>) -> String {
+        let mut out = vec![];
+        let normal = self.is_colored(Indicator::Normal);
+        if normal {
+            self.put_color(&mut out, self.get(Indicator::Normal).unwrap_or_default());
+        }
+        if let Some(seq) = seq {
+            if normal {
+                // Start from the ordinary colors, not from a combination
+                // with those of `no`.
+                self.put(&mut out, self.get(Indicator::Left));
+                self.put(&mut out, self.get(Indicator::Right));
+            }
+            self.put_color(&mut out, seq);
+        }
+        out.extend_from_slice(name.as_bytes());
+        if seq.is_some() || normal {
+            self.put_end(&mut out);
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// Get the terminal back to its colors at the end of the output, unless
+    /// the sequences can't have left it in another one.
+    pub fn finish(&self, out: &mut impl Write) -> io::Result<()> {
+        let default =
+            self.get(Indicator::Left) == Some(b"\x1b[") && self.get(Indicator::Right) == Some(b"m");
+        if self.used.get() && !default {
+            out.write_all(self.get(Indicator::Left).unwrap_or_default())?;
+            out.write_all(self.get(Indicator::Right).unwrap_or_default())?;
+        }
+        Ok(())
+    }
+}
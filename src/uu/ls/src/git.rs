@@ -0,0 +1,227 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (ToDO) eza porcelain
+
+//! The `--git` column of the long listings: the status of each file in the
+//! git repository it is in, like exa and eza show it.
+//!
+//! The status of all the files of a directory is read with a single run of
+//! `git status`. It is made of two letters, for the changes that are staged
+//! and the ones that are not: `-` for none, `N` for a new file, `M` for a
+//! modified one, `D`, `R` and `T` for a deleted, renamed, or retyped one,
+//! `U` for a conflict, and `I` for an ignored file. A directory has the
+//! status of the files in it. The column is left out when no file is in a
+//! repository, or git can't be run.
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::PathData;
+
+/// The status of a file, as staged and as in the working tree.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Status {
+    staged: char,
+    unstaged: char,
+}
+
+impl Status {
+    const UNCHANGED: Self = Self {
+        staged: '-',
+        unstaged: '-',
+    };
+
+    /// The status of the `XY` code of `git status --porcelain`.
+    fn from_code(x: u8, y: u8) -> Self {
+        match (x, y) {
+            (b'?', b'?') => Self {
+                staged: '-',
+                unstaged: 'N',
+            },
+            (b'!', b'!') => Self {
+                staged: '-',
+                unstaged: 'I',
+            },
+            (b'U', _) | (_, b'U') | (b'A', b'A') | (b'D', b'D') => Self {
+                staged: 'U',
+                unstaged: 'U',
+            },
+            (x, y) => Self {
+                staged: letter(x),
+                unstaged: letter(y),
+            },
+        }
+    }
+
+    /// This status, with the changes of `other` that it doesn't have, for a
+    /// directory with files of both.
+    fn merge(self, other: Self) -> Self {
+        Self {
+            staged: if self.staged == '-' {
+                other.staged
+            } else {
+                self.staged
+            },
+            unstaged: if self.unstaged == '-' {
+                other.unstaged
+            } else {
+                self.unstaged
+            },
+        }
+    }
+}
+
+fn letter(code: u8) -> char {
+    match code {
+        b' ' => '-',
+        b'A' | b'C' => 'N',
+        code => code as char,
+    }
+}
+
+/// The statuses of the files of a directory of a repository.
+struct Directory {
+    /// The status of the entries that have changed, by name.
+    entries: HashMap<OsString, Status>,
+    /// The status of all the entries, when the whole directory is untracked
+    /// or ignored.
+    all: Option<Status>,
+}
+
+impl Directory {
+    /// Runs git in `dir`, which is not in a repository if it fails.
+    fn read(dir: &Path) -> Option<Self> {
+        let prefix = git(dir, &["rev-parse", "--show-prefix"])?;
+        let prefix = prefix.strip_suffix(b"\n").unwrap_or(&prefix);
+        let output = git(
+            dir,
+            &[
+                "status",
+                "--porcelain",
+                "-z",
+                "--ignored",
+                "--untracked-files=normal",
+                "--",
+                ".",
+            ],
+        )?;
+
+        let mut directory = Self {
+            entries: HashMap::new(),
+            all: None,
+        };
+        let mut records = output.split(|&b| b == b'\0');
+        while let Some(record) = records.next() {
+            if record.len() < 4 {
+                continue;
+            }
+            let status = Status::from_code(record[0], record[1]);
+            // The renamed and copied files are followed by their old path.
+            if matches!(record[0], b'R' | b'C') || matches!(record[1], b'R' | b'C') {
+                records.next();
+            }
+            // The paths are relative to the top of the repository.
+            let path = match record[3..].strip_prefix(prefix) {
+                Some(path) => path,
+                None => continue,
+            };
+            match path.split(|&b| b == b'/').next() {
+                Some(name) if !name.is_empty() => {
+                    let entry = directory
+                        .entries
+                        .entry(os_string(name))
+                        .or_insert(Status::UNCHANGED);
+                    *entry = entry.merge(status);
+                }
+                _ => directory.all = Some(status),
+            }
+        }
+        Some(directory)
+    }
+
+    fn status(&self, name: &OsStr) -> Status {
+        self.all
+            .or_else(|| self.entries.get(name).copied())
+            .unwrap_or(Status::UNCHANGED)
+    }
+}
+
+/// The statuses of the files of a listing, in the repositories of their
+/// directories.
+pub(crate) struct GitStatuses {
+    directories: HashMap<PathBuf, Option<Directory>>,
+}
+
+impl GitStatuses {
+    /// Reads the statuses of `items`, or nothing if none of them is in a
+    /// repository.
+    pub(crate) fn read(items: &[PathData]) -> Option<Self> {
+        let mut directories = HashMap::new();
+        for item in items {
+            let dir = parent(&item.p_buf);
+            if !directories.contains_key(dir) {
+                let directory = Directory::read(dir);
+                directories.insert(dir.to_path_buf(), directory);
+            }
+        }
+        if directories.values().all(Option::is_none) {
+            return None;
+        }
+        Some(Self { directories })
+    }
+
+    /// The column of `item`, which is blank when it is not in a repository.
+    pub(crate) fn display(&self, item: &PathData) -> String {
+        let directory = self
+            .directories
+            .get(parent(&item.p_buf))
+            .and_then(Option::as_ref);
+        match directory {
+            Some(directory) => {
+                let status = item
+                    .p_buf
+                    .file_name()
+                    .map_or(Status::UNCHANGED, |name| directory.status(name));
+                format!("{}{}", status.staged, status.unstaged)
+            }
+            None => "  ".to_string(),
+        }
+    }
+}
+
+/// The directory that holds the entry of `path`, whose name
+/// [`Path::file_name`] gives: the one of `dir/.` is `dir`, in its parent.
+fn parent(path: &Path) -> &Path {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// The output of git run in `dir` with `args`, if it succeeds.
+fn git(dir: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then(|| output.stdout)
+}
+
+#[cfg(unix)]
+fn os_string(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+    OsStr::from_bytes(bytes).to_owned()
+}
+
+#[cfg(not(unix))]
+fn os_string(bytes: &[u8]) -> OsString {
+    String::from_utf8_lossy(bytes).into_owned().into()
+}
@@ -0,0 +1,205 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! The `--json` output: an array with an object per file, for scripts that
+//! would otherwise parse the output of `ls -l`.
+//!
+//! The directories given on the command line hold their entries, and so do
+//! all the directories below them with `-R`. The `.` and `..` entries added
+//! by `-a` are never entered.
+use std::collections::HashSet;
+use std::fs;
+use std::io::{BufWriter, Stdout, Write};
+
+use chrono::{DateTime, Local, SecondsFormat};
+use uucore::error::{UError, UResult};
+use uucore::fs::FileInformation;
+use uucore::show;
+
+use super::{
+    display_uname, is_link, link_target, read_entries, Config, FileType, LsError, PathData,
+};
+
+/// Writes `files` and then `dirs`, with their entries, as a JSON array.
+pub(crate) fn display(
+    files: &[PathData],
+    dirs: &[PathData],
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+) -> UResult<()> {
+    write!(out, "[")?;
+    let mut first = true;
+    for (item, list_contents) in files
+        .iter()
+        .map(|item| (item, false))
+        .chain(dirs.iter().map(|item| (item, true)))
+    {
+        if !first {
+            write!(out, ",")?;
+        }
+        first = false;
+        let mut listed_ancestors = HashSet::new();
+        write_object(item, list_contents, config, out, &mut listed_ancestors)?;
+    }
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+fn write_object(
+    item: &PathData,
+    list_contents: bool,
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    listed_ancestors: &mut HashSet<FileInformation>,
+) -> UResult<()> {
+    write!(out, "{{\"name\":")?;
+    write_string(out, &item.display_name.to_string_lossy())?;
+
+    let file_type = item.file_type(out).copied();
+    write!(out, ",\"type\":")?;
+    write_optional_string(out, file_type.map(type_name))?;
+
+    let md = item.md(out);
+    match md {
+        Some(md) => write!(out, ",\"size\":{}", md.len())?,
+        None => write!(out, ",\"size\":null")?,
+    }
+
+    #[cfg(unix)]
+    let (mode, owner) = (
+        md.map(|md| format!("{:04o}", md.mode() & 0o7777)),
+        md.map(|md| display_uname(md, config)),
+    );
+    #[cfg(not(unix))]
+    let (mode, owner): (Option<String>, Option<String>) = (None, None);
+    write!(out, ",\"mode\":")?;
+    write_optional_string(out, mode.as_deref())?;
+    write!(out, ",\"owner\":")?;
+    write_optional_string(out, owner.as_deref())?;
+
+    let mtime = md
+        .and_then(|md| md.modified().ok())
+        .map(|time| DateTime::<Local>::from(time).to_rfc3339_opts(SecondsFormat::AutoSi, false));
+    write!(out, ",\"mtime\":")?;
+    write_optional_string(out, mtime.as_deref())?;
+
+    if is_link(item, out) {
+        let target = link_target(item);
+        write!(out, ",\"target\":")?;
+        write_optional_string(out, target.as_ref().map(|t| t.to_string_lossy()).as_deref())?;
+    }
+
+    if list_contents && file_type.map_or(false, |ft| ft.is_dir()) {
+        write!(out, ",\"entries\":")?;
+        write_entries(item, config, out, listed_ancestors)?;
+    }
+
+    write!(out, "}}")?;
+    Ok(())
+}
+
+/// Writes the entries of the directory `dir`, or `null` if they can't be
+/// read or `dir` is one of the directories it is in.
+fn write_entries(
+    dir: &PathData,
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    listed_ancestors: &mut HashSet<FileInformation>,
+) -> UResult<()> {
+    let (read_dir, info) = match fs::read_dir(&dir.p_buf).and_then(|rd| {
+        let info = FileInformation::from_path(&dir.p_buf, dir.must_dereference)?;
+        Ok((rd, info))
+    }) {
+        Err(err) => {
+            out.flush()?;
+            show!(LsError::IOErrorContext(
+                err,
+                dir.p_buf.clone(),
+                dir.command_line
+            ));
+            write!(out, "null")?;
+            return Ok(());
+        }
+        Ok(found) => found,
+    };
+    if !listed_ancestors.insert(info) {
+        out.flush()?;
+        show!(LsError::AlreadyListedError(dir.p_buf.clone()));
+        write!(out, "null")?;
+        return Ok(());
+    }
+
+    let entries = read_entries(dir, read_dir, config, out)?;
+    write!(out, "[")?;
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            write!(out, ",")?;
+        }
+        // The dots are sorted among the other entries, so they are told by
+        // their names.
+        let is_dot = entry.display_name == "." || entry.display_name == "..";
+        write_object(
+            entry,
+            config.recursive && !is_dot,
+            config,
+            out,
+            listed_ancestors,
+        )?;
+    }
+    write!(out, "]")?;
+
+    if let Ok(info) = FileInformation::from_path(&dir.p_buf, dir.must_dereference) {
+        listed_ancestors.remove(&info);
+    }
+    Ok(())
+}
+
+fn type_name(file_type: FileType) -> &'static str {
+    #[cfg(unix)]
+    {
+        if file_type.is_fifo() {
+            return "fifo";
+        } else if file_type.is_socket() {
+            return "socket";
+        } else if file_type.is_block_device() {
+            return "block";
+        } else if file_type.is_char_device() {
+            return "char";
+        }
+    }
+    if file_type.is_dir() {
+        "directory"
+    } else if file_type.is_symlink() {
+        "symlink"
+    } else if file_type.is_file() {
+        "file"
+    } else {
+        "unknown"
+    }
+}
+
+fn write_optional_string(out: &mut BufWriter<Stdout>, s: Option<&str>) -> std::io::Result<()> {
+    match s {
+        Some(s) => write_string(out, s),
+        None => write!(out, "null"),
+    }
+}
+
+/// Writes `s` as a JSON string literal.
+fn write_string(out: &mut BufWriter<Stdout>, s: &str) -> std::io::Result<()> {
+    write!(out, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(out, "\\\"")?,
+            '\\' => write!(out, "\\\\")?,
+            '\n' => write!(out, "\\n")?,
+            '\r' => write!(out, "\\r")?,
+            '\t' => write!(out, "\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => write!(out, "{}", c)?,
+        }
+    }
+    write!(out, "\"")
+}
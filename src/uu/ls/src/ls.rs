@@ -12,28 +12,22 @@ use clap::{
     crate_version, Arg, ArgAction, Command,
 };
 use glob::{MatchOptions, Pattern};
-use lscolors::LsColors;
-use number_prefix::NumberPrefix;
 use once_cell::unsync::OnceCell;
+use stat::{FileType, Metadata};
+#[cfg(unix)]
+use std::collections::HashMap;
 use std::collections::HashSet;
-#[cfg(windows)]
-use std::os::windows::fs::MetadataExt;
 use std::{
     cmp::Reverse,
+    env,
     error::Error,
-    ffi::{OsStr, OsString},
+    ffi::OsString,
     fmt::{Display, Write as FmtWrite},
-    fs::{self, DirEntry, FileType, Metadata, ReadDir},
+    fs::{self, DirEntry, ReadDir},
     io::{stdout, BufWriter, ErrorKind, Stdout, Write},
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
-#[cfg(unix)]
-use std::{
-    collections::HashMap,
-    os::unix::fs::{FileTypeExt, MetadataExt},
-    time::Duration,
-};
 use term_grid::{Cell, Direction, Filling, Grid, GridOptions};
 use unicode_width::UnicodeWidthStr;
 #[cfg(any(
@@ -50,11 +44,23 @@ use uucore::{
     display::Quotable,
     error::{set_exit_code, UError, UResult},
     format_usage,
-    fs::display_permissions,
-    parse_size::parse_size,
+    human_size::{self, HumanReadable, SizeUnit},
+    posix,
     version_cmp::version_cmp,
 };
-use uucore::{parse_glob, show, show_error, show_warning};
+use uucore::{parse_glob, show, show_error, show_warning, translate};
+
+mod collate;
+mod colors;
+mod git;
+mod json;
+mod stat;
+#[cfg(windows)]
+mod windows;
+
+use collate::Collator;
+use colors::{LsColors, LsColorsError};
+use git::GitStatuses;
 
 #[cfg(not(feature = "selinux"))]
 static CONTEXT_HELP_TEXT: &str = "print any security context of each file (not enabled)";
@@ -146,15 +152,15 @@ pub mod options {
     pub static HIDE: &str = "hide";
     pub static IGNORE: &str = "ignore";
     pub static CONTEXT: &str = "context";
+    pub static ATTRIBUTES: &str = "attributes";
     pub static GROUP_DIRECTORIES_FIRST: &str = "group-directories-first";
     pub static ZERO: &str = "zero";
     pub static DIRED: &str = "dired";
+    pub static JSON: &str = "json";
+    pub static GIT: &str = "git";
 }
 
 const DEFAULT_TERM_WIDTH: u16 = 80;
-const POSIXLY_CORRECT_BLOCK_SIZE: u64 = 512;
-#[cfg(unix)]
-const DEFAULT_BLOCK_SIZE: u64 = 1024;
 
 #[derive(Debug)]
 enum LsError {
@@ -184,58 +190,36 @@ impl Error for LsError {}
 
 impl Display for LsError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
+        let message = match self {
             Self::BlockSizeParseError(s) => {
-                write!(f, "invalid --block-size argument {}", s.quote())
-            }
-            Self::TimeStyleParseError(s, possible_time_styles) => {
-                write!(
-                    f,
-                    "invalid --time-style argument {}\nPossible values are: {:?}\n\nFor more information try --help",
-                    s.quote(),
-                    possible_time_styles
-                )
+                translate!("error-invalid-block-size", value = s.quote())
             }
-            Self::InvalidLineWidth(s) => write!(f, "invalid line width: {}", s.quote()),
-            Self::IOError(e) => write!(f, "general io error: {e}"),
+            Self::TimeStyleParseError(s, possible_time_styles) => translate!(
+                "error-invalid-time-style",
+                value = s.quote(),
+                possible = format!("{possible_time_styles:?}")
+            ),
+            Self::InvalidLineWidth(s) => translate!("error-invalid-line-width", value = s.quote()),
+            Self::IOError(e) => translate!("error-io", error = e),
             Self::IOErrorContext(e, p, _) => {
                 let error_kind = e.kind();
                 let errno = e.raw_os_error().unwrap_or(1i32);
+                let path = p.to_string_lossy();
 
                 match error_kind {
                     // No such file or directory
-                    ErrorKind::NotFound => {
-                        write!(
-                            f,
-                            "cannot access '{}': No such file or directory",
-                            p.to_string_lossy(),
-                        )
-                    }
+                    ErrorKind::NotFound => translate!("error-no-such-file", path = path),
                     // Permission denied and Operation not permitted
                     ErrorKind::PermissionDenied =>
                     {
                         #[allow(clippy::wildcard_in_or_patterns)]
                         match errno {
-                            1i32 => {
-                                write!(
-                                    f,
-                                    "cannot access '{}': Operation not permitted",
-                                    p.to_string_lossy(),
-                                )
-                            }
+                            1i32 => translate!("error-not-permitted", path = path),
                             13i32 | _ => {
                                 if p.is_dir() {
-                                    write!(
-                                        f,
-                                        "cannot open directory '{}': Permission denied",
-                                        p.to_string_lossy(),
-                                    )
+                                    translate!("error-directory-permission-denied", path = path)
                                 } else {
-                                    write!(
-                                        f,
-                                        "cannot open file '{}': Permission denied",
-                                        p.to_string_lossy(),
-                                    )
+                                    translate!("error-file-permission-denied", path = path)
                                 }
                             }
                         }
@@ -243,31 +227,21 @@ impl Display for LsError {
                     _ => match errno {
                         9i32 => {
                             // only should ever occur on a read_dir on a bad fd
-                            write!(
-                                f,
-                                "cannot open directory '{}': Bad file descriptor",
-                                p.to_string_lossy(),
-                            )
-                        }
-                        _ => {
-                            write!(
-                                f,
-                                "unknown io error: '{:?}', '{:?}'",
-                                p.to_string_lossy(),
-                                e
-                            )
+                            translate!("error-bad-file-descriptor", path = path)
                         }
+                        _ => translate!(
+                            "error-unknown-io",
+                            path = format!("{path:?}"),
+                            error = format!("{e:?}")
+                        ),
                     },
                 }
             }
             Self::AlreadyListedError(path) => {
-                write!(
-                    f,
-                    "{}: not listing already-listed directory",
-                    path.to_string_lossy()
-                )
+                translate!("error-already-listed", path = path.to_string_lossy())
             }
-        }
+        };
+        f.write_str(&message)
     }
 }
 
@@ -278,6 +252,7 @@ pub enum Format {
     OneLine,
     Across,
     Commas,
+    Json,
 }
 
 #[derive(PartialEq, Eq)]
@@ -290,13 +265,6 @@ enum Sort {
     Extension,
 }
 
-#[derive(PartialEq)]
-enum SizeFormat {
-    Bytes,
-    Binary,  // Powers of 1024, --human-readable, -h
-    Decimal, // Powers of 1000, --si
-}
-
 #[derive(PartialEq, Eq)]
 enum Files {
     All,
@@ -304,6 +272,7 @@ enum Files {
     Normal,
 }
 
+#[derive(PartialEq, Eq)]
 enum Time {
     Modification,
     Access,
@@ -320,6 +289,43 @@ enum TimeStyle {
     Format(String),
 }
 
+/// Replaces the `%N` (nanoseconds) of `date` formats, and its `%3N`, `%6N`
+/// and `%9N` forms, by the `%f` of chrono.
+fn convert_nanoseconds(format: &str) -> String {
+    let mut converted = String::with_capacity(format.len());
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            converted.push(c);
+            continue;
+        }
+        let rest = chars.as_str();
+        if let Some(after) = rest.strip_prefix('N') {
+            converted.push_str("%f");
+            chars = after.chars();
+        } else if let Some(digits @ ('3' | '6' | '9')) = rest.chars().next() {
+            match rest[1..].strip_prefix('N') {
+                Some(after) => {
+                    converted.push('%');
+                    converted.push(digits);
+                    converted.push('f');
+                    chars = after.chars();
+                }
+                None => converted.push('%'),
+            }
+        } else {
+            // Keep "%%" together, so that it isn't read as the start of
+            // another conversion.
+            converted.push('%');
+            if let Some(after) = rest.strip_prefix('%') {
+                converted.push('%');
+                chars = after.chars();
+            }
+        }
+    }
+    converted
+}
+
 fn parse_time_style(options: &clap::ArgMatches) -> Result<TimeStyle, LsError> {
     let possible_time_styles = vec![
         "full-iso".to_string(),
@@ -337,14 +343,24 @@ fn parse_time_style(options: &clap::ArgMatches) -> Result<TimeStyle, LsError> {
         {
             Ok(TimeStyle::FullIso)
         } else {
-            match field.as_str() {
+            // Like in GNU ls, a style prefixed with `posix-` only applies
+            // outside of the POSIX locale, which is what `POSIXLY_CORRECT`
+            // stands for here.
+            let mut field = field.as_str();
+            while let Some(style) = field.strip_prefix("posix-") {
+                if posix::posixly_correct() {
+                    return Ok(TimeStyle::Locale);
+                }
+                field = style;
+            }
+            match field {
                 "full-iso" => Ok(TimeStyle::FullIso),
                 "long-iso" => Ok(TimeStyle::LongIso),
                 "iso" => Ok(TimeStyle::Iso),
                 "locale" => Ok(TimeStyle::Locale),
-                _ => match field.chars().next().unwrap() {
-                    '+' => Ok(TimeStyle::Format(String::from(&field[1..]))),
-                    _ => Err(LsError::TimeStyleParseError(
+                _ => match field.strip_prefix('+') {
+                    Some(format) => Ok(TimeStyle::Format(convert_nanoseconds(format))),
+                    None => Err(LsError::TimeStyleParseError(
                         String::from(field),
                         possible_time_styles,
                     )),
@@ -378,11 +394,14 @@ pub struct Config {
     pub format: Format,
     files: Files,
     sort: Sort,
+    collator: Collator,
     recursive: bool,
     reverse: bool,
     dereference: Dereference,
     ignore_patterns: Vec<Pattern>,
-    size_format: SizeFormat,
+    // The unit of the sizes of the files, and of the space they use.
+    size_unit: SizeUnit,
+    block_unit: SizeUnit,
     directory: bool,
     time: Time,
     #[cfg(unix)]
@@ -390,16 +409,21 @@ pub struct Config {
     color: Option<LsColors>,
     long: LongFormat,
     alloc_size: bool,
-    block_size: Option<u64>,
     width: u16,
     // Dir and vdir needs access to this field
     pub quoting_style: QuotingStyle,
     indicator_style: IndicatorStyle,
     time_style: TimeStyle,
     context: bool,
+    #[cfg(windows)]
+    attributes: bool,
     selinux_supported: bool,
+    smack_supported: bool,
     group_directories_first: bool,
+    git: bool,
     eol: char,
+    // The fields of the metadata that the options need.
+    stat_mask: stat::Mask,
 }
 
 // Fields that can be removed or added to the long format
@@ -414,6 +438,7 @@ struct LongFormat {
 struct PaddingCollection {
     #[cfg(unix)]
     inode: usize,
+    alternate_access: usize,
     link_count: usize,
     uname: usize,
     group: usize,
@@ -430,7 +455,9 @@ impl Config {
     #[allow(clippy::cognitive_complexity)]
     pub fn from(options: &clap::ArgMatches) -> UResult<Self> {
         let context = options.get_flag(options::CONTEXT);
-        let (mut format, opt) = if let Some(format_) = options.get_one::<String>(options::FORMAT) {
+        let (mut format, opt) = if options.get_flag(options::JSON) {
+            (Format::Json, Some(options::JSON))
+        } else if let Some(format_) = options.get_one::<String>(options::FORMAT) {
             (
                 match format_.as_str() {
                     "long" | "verbose" => Format::Long,
@@ -472,7 +499,7 @@ impl Config {
         // The idea here is to not let these options override with the other
         // options, but manually whether they have an index that's greater than
         // the other format options. If so, we set the appropriate format.
-        if format != Format::Long {
+        if format != Format::Long && format != Format::Json {
             let idx = opt
                 .and_then(|opt| options.indices_of(opt).map(|x| x.max().unwrap()))
                 .unwrap_or(0);
@@ -512,6 +539,22 @@ impl Config {
             Files::Normal
         };
 
+        let time = if let Some(field) = options.get_one::<String>(options::TIME) {
+            match field.as_str() {
+                "ctime" | "status" => Time::Change,
+                "access" | "atime" | "use" => Time::Access,
+                "birth" | "creation" => Time::Birth,
+                // below should never happen as clap already restricts the values.
+                _ => unreachable!("Invalid field for --time"),
+            }
+        } else if options.get_flag(options::time::ACCESS) {
+            Time::Access
+        } else if options.get_flag(options::time::CHANGE) {
+            Time::Change
+        } else {
+            Time::Modification
+        };
+
         let sort = if let Some(field) = options.get_one::<String>(options::SORT) {
             match field.as_str() {
                 "none" => Sort::None,
@@ -533,26 +576,13 @@ impl Config {
             Sort::Version
         } else if options.get_flag(options::sort::EXTENSION) {
             Sort::Extension
+        } else if time != Time::Modification && format != Format::Long {
+            // Like GNU ls, -c, -u and --time without -l sort by that time.
+            Sort::Time
         } else {
             Sort::Name
         };
 
-        let time = if let Some(field) = options.get_one::<String>(options::TIME) {
-            match field.as_str() {
-                "ctime" | "status" => Time::Change,
-                "access" | "atime" | "use" => Time::Access,
-                "birth" | "creation" => Time::Birth,
-                // below should never happen as clap already restricts the values.
-                _ => unreachable!("Invalid field for --time"),
-            }
-        } else if options.get_flag(options::time::ACCESS) {
-            Time::Access
-        } else if options.get_flag(options::time::CHANGE) {
-            Time::Change
-        } else {
-            Time::Modification
-        };
-
         let mut needs_color = match options.get_one::<String>(options::COLOR) {
             None => options.contains_id(options::COLOR),
             Some(val) => match val.as_str() {
@@ -562,65 +592,41 @@ impl Config {
             },
         };
 
-        let cmd_line_bs = options.get_one::<String>(options::size::BLOCK_SIZE);
-        let opt_si = cmd_line_bs.is_some()
-            && options
-                .get_one::<String>(options::size::BLOCK_SIZE)
-                .unwrap()
-                .eq("si")
-            || options.get_flag(options::size::SI);
-        let opt_hr = (cmd_line_bs.is_some()
-            && options
-                .get_one::<String>(options::size::BLOCK_SIZE)
-                .unwrap()
-                .eq("human-readable"))
-            || options.get_flag(options::size::HUMAN_READABLE);
-        let opt_kb = options.get_flag(options::size::KIBIBYTES);
-
-        let bs_env_var = std::env::var_os("BLOCK_SIZE");
-        let ls_bs_env_var = std::env::var_os("LS_BLOCK_SIZE");
-        let pc_env_var = std::env::var_os("POSIXLY_CORRECT");
-
-        let size_format = if opt_si {
-            SizeFormat::Decimal
-        } else if opt_hr {
-            SizeFormat::Binary
-        } else {
-            SizeFormat::Bytes
-        };
-
-        let raw_bs = if let Some(cmd_line_bs) = cmd_line_bs {
-            OsString::from(cmd_line_bs)
-        } else if !opt_kb {
-            if let Some(ls_bs_env_var) = ls_bs_env_var {
-                ls_bs_env_var
-            } else if let Some(bs_env_var) = bs_env_var {
-                bs_env_var
-            } else {
-                OsString::from("")
-            }
-        } else {
-            OsString::from("")
-        };
-
-        let block_size: Option<u64> = if !opt_si && !opt_hr && !raw_bs.is_empty() {
-            match parse_size(&raw_bs.to_string_lossy()) {
-                Ok(size) => Some(size),
+        let cmd_line_unit = options
+            .get_one::<String>(options::size::BLOCK_SIZE)
+            .and_then(|bs| match human_size::parse_size_unit(bs) {
+                Ok(unit) => Some(unit),
                 Err(_) => {
-                    show!(LsError::BlockSizeParseError(
-                        cmd_line_bs.unwrap().to_owned()
-                    ));
+                    show!(LsError::BlockSizeParseError(bs.to_owned()));
                     None
                 }
-            }
-        } else if let Some(pc) = pc_env_var {
-            if pc.as_os_str() == OsStr::new("true") || pc == OsStr::new("1") {
-                Some(POSIXLY_CORRECT_BLOCK_SIZE)
-            } else {
-                None
-            }
+            });
+
+        let (size_unit, block_unit) = if options.get_flag(options::size::SI) {
+            let unit = SizeUnit::HumanReadable(HumanReadable::Decimal);
+            (unit, unit)
+        } else if options.get_flag(options::size::HUMAN_READABLE) {
+            let unit = SizeUnit::HumanReadable(HumanReadable::Binary);
+            (unit, unit)
+        } else if let Some(unit) = cmd_line_unit {
+            (unit, unit)
         } else {
-            None
+            let env_unit = human_size::size_unit_from_env(Some("LS_BLOCK_SIZE"));
+            // Like GNU ls, only LS_BLOCK_SIZE and BLOCK_SIZE apply to the
+            // sizes of the files, which are in bytes otherwise, and -k only
+            // applies to the space they use.
+            let size_unit =
+                if env::var_os("LS_BLOCK_SIZE").is_some() || env::var_os("BLOCK_SIZE").is_some() {
+                    env_unit
+                } else {
+                    SizeUnit::Blocks(1)
+                };
+            let block_unit = if options.get_flag(options::size::KIBIBYTES) {
+                SizeUnit::Blocks(1024)
+            } else {
+                env_unit
+            };
+            (size_unit, block_unit)
         };
 
         let long = {
@@ -661,8 +667,8 @@ impl Config {
                         Some(columns) => columns,
                         None => {
                             show_error!(
-                                "ignoring invalid width in environment variable COLUMNS: {}",
-                                columns.quote()
+                                "{}",
+                                translate!("error-invalid-columns", value = columns.quote())
                             );
                             DEFAULT_TERM_WIDTH
                         }
@@ -783,7 +789,10 @@ impl Config {
                 Ok(p) => {
                     ignore_patterns.push(p);
                 }
-                Err(_) => show_warning!("Invalid pattern for ignore: {}", pattern.quote()),
+                Err(_) => show_warning!(
+                    "{}",
+                    translate!("warning-invalid-ignore", pattern = pattern.quote())
+                ),
             }
         }
 
@@ -797,7 +806,10 @@ impl Config {
                     Ok(p) => {
                         ignore_patterns.push(p);
                     }
-                    Err(_) => show_warning!("Invalid pattern for hide: {}", pattern.quote()),
+                    Err(_) => show_warning!(
+                        "{}",
+                        translate!("warning-invalid-hide", pattern = pattern.quote())
+                    ),
                 }
             }
         }
@@ -876,8 +888,17 @@ impl Config {
             quoting_style = QuotingStyle::Literal { show_control };
         }
 
-        let color = if needs_color {
-            Some(LsColors::from_env().unwrap_or_default())
+        let color = if needs_color && format != Format::Json {
+            match LsColors::from_env() {
+                Ok(colors) => Some(colors),
+                Err(e) => {
+                    if let LsColorsError::UnrecognizedPrefix(_) = e {
+                        show_error!("{}", e);
+                    }
+                    show_error!("{}", LsColorsError::Unparsable);
+                    None
+                }
+            }
         } else {
             None
         };
@@ -891,21 +912,54 @@ impl Config {
         } else if options.get_flag(options::DIRECTORY)
             || indicator_style == IndicatorStyle::Classify
             || format == Format::Long
+            || format == Format::Json
         {
             Dereference::None
         } else {
             Dereference::DirArgs
         };
 
+        // The type and the mode are needed for most listings, while the
+        // other fields are only looked up for the options that use them.
+        let time_mask = match time {
+            Time::Modification => stat::MTIME,
+            Time::Access => stat::ATIME,
+            Time::Change => stat::CTIME,
+            Time::Birth => stat::BTIME,
+        };
+        let mut stat_mask = stat::TYPE | stat::MODE;
+        if format == Format::Long {
+            stat_mask |=
+                stat::NLINK | stat::UID | stat::GID | stat::SIZE | stat::BLOCKS | time_mask;
+        } else if format == Format::Json {
+            stat_mask |= stat::UID | stat::SIZE | stat::MTIME;
+        }
+        match sort {
+            Sort::Time => stat_mask |= time_mask,
+            Sort::Size => stat_mask |= stat::SIZE,
+            _ => {}
+        }
+        if options.get_flag(options::INODE) {
+            stat_mask |= stat::INO;
+        }
+        if options.get_flag(options::size::ALLOCATION_SIZE) {
+            stat_mask |= stat::BLOCKS;
+        }
+        if color.is_some() {
+            stat_mask |= stat::NLINK;
+        }
+
         Ok(Self {
             format,
             files,
             sort,
+            collator: Collator::from_env(),
             recursive: options.get_flag(options::RECURSIVE),
             reverse: options.get_flag(options::REVERSE),
             dereference,
             ignore_patterns,
-            size_format,
+            size_unit,
+            block_unit,
             directory: options.get_flag(options::DIRECTORY),
             time,
             color,
@@ -913,12 +967,13 @@ impl Config {
             inode: options.get_flag(options::INODE),
             long,
             alloc_size: options.get_flag(options::size::ALLOCATION_SIZE),
-            block_size,
             width,
             quoting_style,
             indicator_style,
             time_style,
             context,
+            #[cfg(windows)]
+            attributes: options.get_flag(options::ATTRIBUTES),
             selinux_supported: {
                 #[cfg(feature = "selinux")]
                 {
@@ -929,12 +984,18 @@ impl Config {
                     false
                 }
             },
+            // The kernel only makes the file system of Smack when it is the
+            // security module in use.
+            smack_supported: cfg!(all(feature = "selinux", target_os = "linux"))
+                && Path::new("/sys/fs/smackfs").is_dir(),
             group_directories_first: options.get_flag(options::GROUP_DIRECTORIES_FIRST),
+            git: options.get_flag(options::GIT),
             eol: if options.get_flag(options::ZERO) {
                 '\0'
             } else {
                 '\n'
             },
+            stat_mask,
         })
     }
 }
@@ -1063,6 +1124,26 @@ pub fn uu_app() -> Command {
                 .help("List entries separated by ASCII NUL characters.")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::JSON)
+                .long(options::JSON)
+                .conflicts_with_all([options::ZERO, options::DIRED])
+                .help(
+                    "Print a JSON array of objects, one per file, with its name, type, \
+                    size, mode, owner, modification time and symbolic link target. \
+                    Directories hold their contents in \"entries\".",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(options::GIT)
+                .long(options::GIT)
+                .help(
+                    "In long listings, show the git status of each file in its repository: \
+                    its staged and unstaged changes.",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::DIRED)
                 .long(options::DIRED)
@@ -1185,10 +1266,10 @@ pub fn uu_app() -> Command {
             Arg::new(options::TIME)
                 .long(options::TIME)
                 .help(
-                    "Show time in <field>:\n\
+                    "Show time in <field>, or sort by it without -l:\n\
                         \taccess time (-u): atime, access, use;\n\
-                        \tchange time (-t): ctime, status.\n\
-                        \tbirth time: birth, creation;",
+                        \tchange time (-c): ctime, status;\n\
+                        \tbirth time: birth, creation.",
                 )
                 .value_name("field")
                 .value_parser([
@@ -1604,6 +1685,15 @@ pub fn uu_app() -> Command {
                 .help(CONTEXT_HELP_TEXT)
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(options::ATTRIBUTES)
+                .long(options::ATTRIBUTES)
+                .help(
+                    "in a long listing, print the read-only, hidden, system and \
+                    archive attributes of each file (Windows only)",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::GROUP_DIRECTORIES_FIRST)
                 .long(options::GROUP_DIRECTORIES_FIRST)
@@ -1622,6 +1712,7 @@ pub fn uu_app() -> Command {
         )
         .after_help(
             "The TIME_STYLE argument can be full-iso, long-iso, iso, locale or +FORMAT. FORMAT is interpreted like in date. \
+            A style prefixed with posix- is only used when POSIXLY_CORRECT is not set. \
             Also the TIME_STYLE environment variable sets the default style to use.",
         )
 }
@@ -1640,7 +1731,12 @@ struct PathData {
     // PathBuf that all above data corresponds to
     p_buf: PathBuf,
     must_dereference: bool,
+    // The fields of the metadata to look up
+    stat_mask: stat::Mask,
     security_context: String,
+    // Whether the file has an access control list, which is only probed in
+    // long listings
+    acl: OnceCell<bool>,
     command_line: bool,
 }
 
@@ -1694,13 +1790,13 @@ impl PathData {
             must_dereference: bool,
         ) -> OnceCell<Option<FileType>> {
             if must_dereference {
-                if let Ok(md_pb) = p_buf.metadata() {
+                if let Ok(md_pb) = stat::metadata(p_buf, true, stat::TYPE) {
                     return OnceCell::from(Some(md_pb.file_type()));
                 }
             }
             if let Ok(ft_de) = de.file_type() {
-                OnceCell::from(Some(ft_de))
-            } else if let Ok(md_pb) = p_buf.symlink_metadata() {
+                OnceCell::from(Some(ft_de.into()))
+            } else if let Ok(md_pb) = stat::metadata(p_buf, false, stat::TYPE) {
                 OnceCell::from(Some(md_pb.file_type()))
             } else {
                 OnceCell::new()
@@ -1724,23 +1820,31 @@ impl PathData {
             display_name,
             p_buf,
             must_dereference,
+            stat_mask: config.stat_mask,
             security_context,
+            acl: OnceCell::new(),
             command_line,
         }
     }
 
+    fn has_acl(&self) -> bool {
+        *self
+            .acl
+            .get_or_init(|| has_acl(&self.p_buf, self.must_dereference))
+    }
+
     fn md(&self, out: &mut BufWriter<Stdout>) -> Option<&Metadata> {
         self.md
             .get_or_init(|| {
                 // check if we can use DirEntry metadata
                 if !self.must_dereference {
                     if let Some(dir_entry) = &self.de {
-                        return dir_entry.metadata().ok();
+                        return stat::entry_metadata(dir_entry, self.stat_mask).ok();
                     }
                 }
 
                 // if not, check if we can use Path metadata
-                match get_metadata(self.p_buf.as_path(), self.must_dereference) {
+                match stat::metadata(&self.p_buf, self.must_dereference, self.stat_mask) {
                     Err(err) => {
                         // FIXME: A bit tricky to propagate the result here
                         out.flush().unwrap();
@@ -1751,7 +1855,7 @@ impl PathData {
                         // back the non-dereferenced metadata upon an EBADF
                         if self.must_dereference && errno == 9i32 {
                             if let Some(dir_entry) = &self.de {
-                                return dir_entry.metadata().ok();
+                                return stat::entry_metadata(dir_entry, self.stat_mask).ok();
                             }
                         }
                         show!(LsError::IOErrorContext(
@@ -1811,6 +1915,10 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
     sort_entries(&mut files, config, &mut out);
     sort_entries(&mut dirs, config, &mut out);
 
+    if config.format == Format::Json {
+        return json::display(&files, &dirs, config, &mut out);
+    }
+
     display_items(&files, config, &mut out)?;
 
     for (pos, path_data) in dirs.iter().enumerate() {
@@ -1839,28 +1947,49 @@ pub fn list(locs: Vec<&Path>, config: &Config) -> UResult<()> {
             }
         }
         let mut listed_ancestors = HashSet::new();
-        listed_ancestors.insert(FileInformation::from_path(
-            &path_data.p_buf,
-            path_data.must_dereference,
-        )?);
+        match FileInformation::from_path(&path_data.p_buf, path_data.must_dereference) {
+            Ok(info) => listed_ancestors.insert(info),
+            Err(err) => {
+                out.flush()?;
+                show!(LsError::IOErrorContext(
+                    err,
+                    path_data.p_buf.clone(),
+                    path_data.command_line
+                ));
+                continue;
+            }
+        };
         enter_directory(path_data, read_dir, config, &mut out, &mut listed_ancestors)?;
     }
 
+    if let Some(colors) = &config.color {
+        colors.finish(&mut out)?;
+    }
+
     Ok(())
 }
 
 fn sort_entries(entries: &mut [PathData], config: &Config, out: &mut BufWriter<Stdout>) {
     match config.sort {
-        Sort::Time => entries.sort_by_key(|k| {
-            Reverse(
-                k.md(out)
+        // Like GNU ls, files with the same time, or whose time is unknown,
+        // as birth times often are, are sorted by name.
+        Sort::Time => entries.sort_by(|a, b| {
+            let time = |p: &PathData, out: &mut BufWriter<Stdout>| {
+                p.md(out)
                     .and_then(|md| get_system_time(md, config))
-                    .unwrap_or(UNIX_EPOCH),
-            )
+                    .unwrap_or(UNIX_EPOCH)
+            };
+            Reverse(time(a, out))
+                .cmp(&Reverse(time(b, out)))
+                .then_with(|| config.collator.compare(&a.display_name, &b.display_name))
         }),
         Sort::Size => entries.sort_by_key(|k| Reverse(k.md(out).map(|md| md.len()).unwrap_or(0))),
-        // The default sort in GNU ls is case insensitive
-        Sort::Name => entries.sort_by(|a, b| a.display_name.cmp(&b.display_name)),
+        // The names are in the order of the collation of the locale, which
+        // only needs to be computed once per name.
+        Sort::Name if config.collator.is_bytewise() => {
+            entries.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+        }
+        Sort::Name => entries.sort_by_cached_key(|p| config.collator.key(&p.display_name)),
         Sort::Version => entries
             .sort_by(|a, b| version_cmp(&a.p_buf.to_string_lossy(), &b.p_buf.to_string_lossy())),
         Sort::Extension => entries.sort_by(|a, b| {
@@ -1878,6 +2007,13 @@ fn sort_entries(entries: &mut [PathData], config: &Config, out: &mut BufWriter<S
 
     if config.group_directories_first && config.sort != Sort::None {
         entries.sort_by_key(|p| {
+            // The type from the directory entry is enough for all the files
+            // but the symbolic links, which are followed.
+            if let Some(Some(ft)) = p.ft.get() {
+                if !ft.is_symlink() {
+                    return !ft.is_dir();
+                }
+            }
             let md = {
                 // We will always try to deref symlinks to group directories, so PathData.md
                 // is not always useful.
@@ -1891,7 +2027,8 @@ fn sort_entries(entries: &mut [PathData], config: &Config, out: &mut BufWriter<S
             !match md {
                 None | Some(None) => {
                     // If it metadata cannot be determined, treat as a file.
-                    get_metadata(p.p_buf.as_path(), true).map_or_else(|_| false, |m| m.is_dir())
+                    stat::metadata(&p.p_buf, true, stat::TYPE)
+                        .map_or_else(|_| false, |m| m.is_dir())
                 }
                 Some(Some(m)) => m.is_dir(),
             }
@@ -1900,20 +2037,21 @@ fn sort_entries(entries: &mut [PathData], config: &Config, out: &mut BufWriter<S
 }
 
 fn is_hidden(file_path: &DirEntry) -> bool {
+    // Files with the hidden attribute are hidden on Windows, just like
+    // dotfiles are.
     #[cfg(windows)]
+    if file_path
+        .metadata()
+        .map_or(false, |md| windows::is_hidden(&md))
     {
-        let metadata = file_path.metadata().unwrap();
-        let attr = metadata.file_attributes();
-        (attr & 0x2) > 0
-    }
-    #[cfg(not(windows))]
-    {
-        file_path
-            .file_name()
-            .to_str()
-            .map(|res| res.starts_with('.'))
-            .unwrap_or(false)
+        return true;
     }
+
+    file_path
+        .file_name()
+        .to_str()
+        .map(|res| res.starts_with('.'))
+        .unwrap_or(false)
 }
 
 fn should_display(entry: &DirEntry, config: &Config) -> bool {
@@ -1936,13 +2074,14 @@ fn should_display(entry: &DirEntry, config: &Config) -> bool {
         .any(|p| p.matches_with(&file_name, options))
 }
 
-fn enter_directory(
+/// The entries of the directory of `path_data` to list, read from
+/// `read_dir`, sorted.
+fn read_entries(
     path_data: &PathData,
     read_dir: ReadDir,
     config: &Config,
     out: &mut BufWriter<Stdout>,
-    listed_ancestors: &mut HashSet<FileInformation>,
-) -> UResult<()> {
+) -> UResult<Vec<PathData>> {
     // Create vec of entries with initial dot files
     let mut entries: Vec<PathData> = if config.files == Files::All {
         vec![
@@ -1984,6 +2123,17 @@ fn enter_directory(
     }
 
     sort_entries(&mut entries, config, out);
+    Ok(entries)
+}
+
+fn enter_directory(
+    path_data: &PathData,
+    read_dir: ReadDir,
+    config: &Config,
+    out: &mut BufWriter<Stdout>,
+    listed_ancestors: &mut HashSet<FileInformation>,
+) -> UResult<()> {
+    let entries = read_entries(path_data, read_dir, config, out)?;
 
     // Print total after any error display
     if config.format == Format::Long || config.alloc_size {
@@ -1991,6 +2141,9 @@ fn enter_directory(
     }
 
     display_items(&entries, config, out)?;
+    // Write the block of this directory out before reading the next ones,
+    // which may take long with large trees.
+    out.flush()?;
 
     if config.recursive {
         for e in entries
@@ -2000,7 +2153,13 @@ fn enter_directory(
             .filter(|p| p.ft.get().unwrap().is_some())
             .filter(|p| p.ft.get().unwrap().unwrap().is_dir())
         {
-            match fs::read_dir(&e.p_buf) {
+            // The directories being listed are identified by their device and
+            // inode, so that the loops made by symbolic links or bind mounts
+            // are listed only once.
+            let (rd, info) = match fs::read_dir(&e.p_buf).and_then(|rd| {
+                let info = FileInformation::from_path(&e.p_buf, e.must_dereference)?;
+                Ok((rd, info))
+            }) {
                 Err(err) => {
                     out.flush()?;
                     show!(LsError::IOErrorContext(
@@ -2010,19 +2169,17 @@ fn enter_directory(
                     ));
                     continue;
                 }
-                Ok(rd) => {
-                    if !listed_ancestors
-                        .insert(FileInformation::from_path(&e.p_buf, e.must_dereference)?)
-                    {
-                        out.flush()?;
-                        show!(LsError::AlreadyListedError(e.p_buf.clone()));
-                    } else {
-                        writeln!(out, "\n{}:", e.p_buf.display())?;
-                        enter_directory(e, rd, config, out, listed_ancestors)?;
-                        listed_ancestors
-                            .remove(&FileInformation::from_path(&e.p_buf, e.must_dereference)?);
-                    }
-                }
+                Ok(found) => found,
+            };
+            if !listed_ancestors.insert(info) {
+                out.flush()?;
+                show!(LsError::AlreadyListedError(e.p_buf.clone()));
+                continue;
+            }
+            writeln!(out, "\n{}:", e.p_buf.display())?;
+            enter_directory(e, rd, config, out, listed_ancestors)?;
+            if let Ok(info) = FileInformation::from_path(&e.p_buf, e.must_dereference) {
+                listed_ancestors.remove(&info);
             }
         }
     }
@@ -2030,14 +2187,6 @@ fn enter_directory(
     Ok(())
 }
 
-fn get_metadata(p_buf: &Path, dereference: bool) -> std::io::Result<Metadata> {
-    if dereference {
-        p_buf.metadata()
-    } else {
-        p_buf.symlink_metadata()
-    }
-}
-
 fn display_dir_entry_size(
     entry: &PathData,
     config: &Config,
@@ -2077,15 +2226,12 @@ fn pad_right(string: &str, count: usize) -> String {
 fn display_total(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout>) -> UResult<()> {
     let mut total_size = 0;
     for item in items {
-        total_size += item
-            .md(out)
-            .as_ref()
-            .map_or(0, |md| get_block_size(md, config));
+        total_size += item.md(out).map_or(0, get_block_size);
     }
     write!(
         out,
         "total {}{}",
-        display_size(total_size, config),
+        config.block_unit.format(total_size),
         config.eol
     )?;
     Ok(())
@@ -2112,7 +2258,7 @@ fn display_additional_leading_info(
 
     if config.alloc_size {
         let s = if let Some(md) = item.md(out) {
-            display_size(get_block_size(md, config), config)
+            config.block_unit.format(get_block_size(md))
         } else {
             "?".to_owned()
         };
@@ -2133,6 +2279,11 @@ fn display_items(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout
 
     if config.format == Format::Long {
         let padding_collection = calculate_padding_collection(items, config, out);
+        let git_statuses = if config.git {
+            GitStatuses::read(items)
+        } else {
+            None
+        };
 
         for item in items {
             #[cfg(unix)]
@@ -2147,7 +2298,13 @@ fn display_items(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout
                     display_additional_leading_info(item, &padding_collection, config, out)?;
                 write!(out, "{more_info}")?;
             }
-            display_item_long(item, &padding_collection, config, out)?;
+            display_item_long(
+                item,
+                &padding_collection,
+                git_statuses.as_ref(),
+                config,
+                out,
+            )?;
         }
     } else {
         let mut longest_context_len = 1;
@@ -2211,31 +2368,15 @@ fn display_items(items: &[PathData], config: &Config, out: &mut BufWriter<Stdout
     Ok(())
 }
 
-#[allow(unused_variables)]
-fn get_block_size(md: &Metadata, config: &Config) -> u64 {
-    /* GNU ls will display sizes in terms of block size
-       md.len() will differ from this value when the file has some holes
-    */
+/// The space used by a file, in bytes, which is smaller than its size when it
+/// has holes.
+fn get_block_size(md: &Metadata) -> u64 {
     #[cfg(unix)]
     {
-        let raw_blocks = if md.file_type().is_char_device() || md.file_type().is_block_device() {
-            0u64
+        if md.file_type().is_char_device() || md.file_type().is_block_device() {
+            0
         } else {
             md.blocks() * 512
-        };
-        match config.size_format {
-            SizeFormat::Binary | SizeFormat::Decimal => raw_blocks,
-            SizeFormat::Bytes => {
-                if cfg!(unix) {
-                    if let Some(user_block_size) = config.block_size {
-                        raw_blocks / user_block_size
-                    } else {
-                        raw_blocks / DEFAULT_BLOCK_SIZE
-                    }
-                } else {
-                    raw_blocks
-                }
-            }
         }
     }
     #[cfg(not(unix))]
@@ -2318,21 +2459,20 @@ fn display_grid(
 fn display_item_long(
     item: &PathData,
     padding: &PaddingCollection,
+    git_statuses: Option<&GitStatuses>,
     config: &Config,
     out: &mut BufWriter<Stdout>,
 ) -> UResult<()> {
+    let git_status = git_statuses.map_or_else(String::new, |statuses| statuses.display(item) + " ");
     if let Some(md) = item.md(out) {
         write!(
             out,
             "{}{} {}",
-            display_permissions(md, true),
-            if item.security_context.len() > 1 {
-                // GNU `ls` uses a "." character to indicate a file with a security context,
-                // but not other alternate access method.
-                "."
-            } else {
-                ""
-            },
+            md.display_permissions(),
+            pad_right(
+                &alternate_access(item, config).map_or_else(String::new, String::from),
+                padding.alternate_access
+            ),
             pad_left(&display_symlink_count(md), padding.link_count)
         )?;
 
@@ -2360,6 +2500,11 @@ fn display_item_long(
             )?;
         }
 
+        #[cfg(windows)]
+        if config.attributes {
+            write!(out, " {}", windows::display_attributes(md))?;
+        }
+
         // Author is only different from owner on GNU/Hurd, so we reuse
         // the owner, since GNU/Hurd is not currently supported by Rust.
         if config.long.author {
@@ -2402,7 +2547,14 @@ fn display_item_long(
 
         let dfn = display_file_name(item, config, None, String::new(), out).contents;
 
-        write!(out, " {} {}{}", display_date(md, config), dfn, config.eol)?;
+        write!(
+            out,
+            " {} {}{}{}",
+            display_date(md, config),
+            git_status,
+            dfn,
+            config.eol
+        )?;
     } else {
         #[cfg(unix)]
         let leading_char = {
@@ -2441,13 +2593,10 @@ fn display_item_long(
             out,
             "{}{} {}",
             format_args!("{leading_char}?????????"),
-            if item.security_context.len() > 1 {
-                // GNU `ls` uses a "." character to indicate a file with a security context,
-                // but not other alternate access method.
-                "."
-            } else {
-                ""
-            },
+            pad_right(
+                &alternate_access(item, config).map_or_else(String::new, String::from),
+                padding.alternate_access
+            ),
             pad_left("?", padding.link_count)
         )?;
 
@@ -2467,6 +2616,11 @@ fn display_item_long(
             )?;
         }
 
+        #[cfg(windows)]
+        if config.attributes {
+            write!(out, " ????")?;
+        }
+
         // Author is only different from owner on GNU/Hurd, so we reuse
         // the owner, since GNU/Hurd is not currently supported by Rust.
         if config.long.author {
@@ -2478,9 +2632,10 @@ fn display_item_long(
 
         writeln!(
             out,
-            " {} {} {}",
+            " {} {} {}{}",
             pad_left("?", padding.size),
             pad_left("?", date_len),
+            git_status,
             dfn,
         )?;
     }
@@ -2488,6 +2643,25 @@ fn display_item_long(
     Ok(())
 }
 
+/// Whether `path` leads somewhere else: a symbolic link, or on Windows any
+/// reparse point, like a junction or an app execution alias.
+fn is_link(path: &PathData, out: &mut BufWriter<Stdout>) -> bool {
+    #[cfg(windows)]
+    if path.md(out).map_or(false, windows::is_reparse_point) {
+        return true;
+    }
+    path.file_type(out).map_or(false, |ft| ft.is_symlink())
+}
+
+/// The target of a path for which [`is_link`] holds, if it can be read.
+fn link_target(path: &PathData) -> Option<PathBuf> {
+    #[cfg(windows)]
+    if let Some(target) = windows::reparse_target(&path.p_buf) {
+        return Some(target);
+    }
+    path.p_buf.read_link().ok()
+}
+
 #[cfg(unix)]
 fn get_inode(metadata: &Metadata) -> String {
     format!("{}", metadata.ino())
@@ -2564,7 +2738,7 @@ fn display_group(_metadata: &Metadata, _config: &Config) -> String {
 #[cfg(unix)]
 fn get_system_time(md: &Metadata, config: &Config) -> Option<SystemTime> {
     match config.time {
-        Time::Change => Some(UNIX_EPOCH + Duration::new(md.ctime() as u64, md.ctime_nsec() as u32)),
+        Time::Change => md.changed().ok(),
         Time::Modification => md.modified().ok(),
         Time::Access => md.accessed().ok(),
         Time::Birth => md.created().ok(),
@@ -2588,56 +2762,39 @@ fn get_time(md: &Metadata, config: &Config) -> Option<chrono::DateTime<chrono::L
 
 fn display_date(metadata: &Metadata, config: &Config) -> String {
     match get_time(metadata, config) {
-        Some(time) => {
-            //Date is recent if from past 6 months
-            //According to GNU a Gregorian year has 365.2425 * 24 * 60 * 60 == 31556952 seconds on the average.
-            let recent = time + chrono::Duration::seconds(31_556_952 / 2) > chrono::Local::now();
-
-            match &config.time_style {
-                TimeStyle::FullIso => time.format("%Y-%m-%d %H:%M:%S.%f %z"),
-                TimeStyle::LongIso => time.format("%Y-%m-%d %H:%M"),
-                TimeStyle::Iso => time.format(if recent { "%m-%d %H:%M" } else { "%Y-%m-%d " }),
-                TimeStyle::Locale => {
-                    let fmt = if recent { "%b %e %H:%M" } else { "%b %e  %Y" };
-
-                    // spell-checker:ignore (word) datetime
-                    //In this version of chrono translating can be done
-                    //The function is chrono::datetime::DateTime::format_localized
-                    //However it's currently still hard to get the current pure-rust-locale
-                    //So it's not yet implemented
-
-                    time.format(fmt)
-                }
-                TimeStyle::Format(e) => time.format(e),
-            }
-            .to_string()
+        Some(time) => format_date(time, config),
+        // Like GNU ls, a time that is unknown, as birth times often are, is
+        // shown as a question mark in a column as wide as the dates.
+        None => {
+            let width = format_date(UNIX_EPOCH.into(), config).width();
+            format!("{:>width$}", "?", width = width)
         }
-        None => "???".into(),
     }
 }
 
-// There are a few peculiarities to how GNU formats the sizes:
-// 1. One decimal place is given if and only if the size is smaller than 10
-// 2. It rounds sizes up.
-// 3. The human-readable format uses powers for 1024, but does not display the "i"
-//    that is commonly used to denote Kibi, Mebi, etc.
-// 4. Kibi and Kilo are denoted differently ("k" and "K", respectively)
-fn format_prefixed(prefixed: &NumberPrefix<f64>) -> String {
-    match prefixed {
-        NumberPrefix::Standalone(bytes) => bytes.to_string(),
-        NumberPrefix::Prefixed(prefix, bytes) => {
-            // Remove the "i" from "Ki", "Mi", etc. if present
-            let prefix_str = prefix.symbol().trim_end_matches('i');
-
-            // Check whether we get more than 10 if we round up to the first decimal
-            // because we want do display 9.81 as "9.9", not as "10".
-            if (10.0 * bytes).ceil() >= 100.0 {
-                format!("{:.0}{}", bytes.ceil(), prefix_str)
-            } else {
-                format!("{:.1}{}", (10.0 * bytes).ceil() / 10.0, prefix_str)
-            }
+fn format_date(time: chrono::DateTime<chrono::Local>, config: &Config) -> String {
+    //Date is recent if from past 6 months
+    //According to GNU a Gregorian year has 365.2425 * 24 * 60 * 60 == 31556952 seconds on the average.
+    let recent = time + chrono::Duration::seconds(31_556_952 / 2) > chrono::Local::now();
+
+    match &config.time_style {
+        TimeStyle::FullIso => time.format("%Y-%m-%d %H:%M:%S.%f %z"),
+        TimeStyle::LongIso => time.format("%Y-%m-%d %H:%M"),
+        TimeStyle::Iso => time.format(if recent { "%m-%d %H:%M" } else { "%Y-%m-%d " }),
+        TimeStyle::Locale => {
+            let fmt = if recent { "%b %e %H:%M" } else { "%b %e  %Y" };
+
+            // spell-checker:ignore (word) datetime
+            //In this version of chrono translating can be done
+            //The function is chrono::datetime::DateTime::format_localized
+            //However it's currently still hard to get the current pure-rust-locale
+            //So it's not yet implemented
+
+            time.format(fmt)
         }
+        TimeStyle::Format(e) => time.format(e),
     }
+    .to_string()
 }
 
 #[allow(dead_code)]
@@ -2663,36 +2820,7 @@ fn display_len_or_rdev(metadata: &Metadata, config: &Config) -> SizeOrDeviceId {
             return SizeOrDeviceId::Device(major.to_string(), minor.to_string());
         }
     }
-    // Reported file len only adjusted for block_size when block_size is set
-    if let Some(user_block_size) = config.block_size {
-        // ordinary division of unsigned integers rounds down,
-        // this is similar to the Rust API for division that rounds up,
-        // currently in nightly only, however once
-        // https://github.com/rust-lang/rust/pull/88582 : "div_ceil"
-        // is stable we should use that instead
-        let len_adjusted = {
-            let d = metadata.len() / user_block_size;
-            let r = metadata.len() % user_block_size;
-            if r == 0 {
-                d
-            } else {
-                d + 1
-            }
-        };
-        SizeOrDeviceId::Size(display_size(len_adjusted, config))
-    } else {
-        SizeOrDeviceId::Size(display_size(metadata.len(), config))
-    }
-}
-
-fn display_size(size: u64, config: &Config) -> String {
-    // NOTE: The human-readable behavior deviates from the GNU ls.
-    // The GNU ls uses binary prefixes by default.
-    match config.size_format {
-        SizeFormat::Binary => format_prefixed(&NumberPrefix::binary(size as f64)),
-        SizeFormat::Decimal => format_prefixed(&NumberPrefix::decimal(size as f64)),
-        SizeFormat::Bytes => size.to_string(),
-    }
+    SizeOrDeviceId::Size(config.size_unit.format(metadata.len()))
 }
 
 #[cfg(unix)]
@@ -2706,7 +2834,9 @@ fn file_is_executable(md: &Metadata) -> bool {
     return md.mode() & ((S_IXUSR | S_IXGRP | S_IXOTH) as u32) != 0;
 }
 
-fn classify_file(path: &PathData, out: &mut BufWriter<Stdout>) -> Option<char> {
+/// The indicator of the type of `path`, where the executable files are only
+/// told apart, which needs their metadata, if `executable` is set.
+fn classify_file(path: &PathData, executable: bool, out: &mut BufWriter<Stdout>) -> Option<char> {
     let file_type = path.file_type(out)?;
 
     if file_type.is_dir() {
@@ -2720,7 +2850,10 @@ fn classify_file(path: &PathData, out: &mut BufWriter<Stdout>) -> Option<char> {
                 Some('=')
             } else if file_type.is_fifo() {
                 Some('|')
-            } else if file_type.is_file() && file_is_executable(path.md(out).as_ref().unwrap()) {
+            } else if executable
+                && file_type.is_file()
+                && path.md(out).map_or(false, file_is_executable)
+            {
                 Some('*')
             } else {
                 None
@@ -2736,7 +2869,7 @@ fn classify_file(path: &PathData, out: &mut BufWriter<Stdout>) -> Option<char> {
 /// This function relies on the following parameters in the provided `&Config`:
 /// * `config.quoting_style` to decide how we will escape `name` using [`escape_name`].
 /// * `config.inode` decides whether to display inode numbers beside names using [`get_inode`].
-/// * `config.color` decides whether it's going to color `name` using [`color_name`].
+/// * `config.color` decides whether it's going to color `name` using [`LsColors`].
 /// * `config.indicator_style` to append specific characters to `name` using [`classify_file`].
 /// * `config.format` to display symlink targets if `Format::Long`. This function is also
 ///   responsible for coloring symlink target names if `config.color` is specified.
@@ -2760,17 +2893,39 @@ fn display_file_name(
     let mut width = name.width();
 
     if let Some(ls_colors) = &config.color {
-        let md = path.md(out);
-        name = if md.is_some() {
-            color_name(name, &path.p_buf, md, ls_colors)
+        // The type from the directory entry is enough for most colors, so
+        // that the files aren't all looked up, unless they are followed.
+        let file_type = if path.must_dereference {
+            None
         } else {
-            color_name(
-                name,
-                &path.p_buf,
-                path.p_buf.symlink_metadata().ok().as_ref(),
-                ls_colors,
-            )
+            path.file_type(out).copied()
+        };
+        // A file that can't be followed is colored like the link itself.
+        let link_md;
+        let (file_type, md) = match file_type {
+            Some(file_type) if !ls_colors.needs_metadata(&file_type) => (Some(file_type), None),
+            _ => match path.md(out) {
+                Some(md) => (Some(md.file_type()), Some(md)),
+                None => {
+                    link_md = stat::metadata(&path.p_buf, false, path.stat_mask).ok();
+                    (link_md.as_ref().map(Metadata::file_type), link_md.as_ref())
+                }
+            },
+        };
+        let target_md = match file_type {
+            Some(file_type) if file_type.is_symlink() && ls_colors.needs_link_target() => {
+                stat::metadata(&path.p_buf, true, path.stat_mask).ok()
+            }
+            _ => None,
         };
+        let color = ls_colors.color_for(
+            &path.p_buf,
+            &path.display_name,
+            file_type.as_ref(),
+            md,
+            target_md.as_ref(),
+        );
+        name = ls_colors.paint(&name, color);
     }
 
     if config.format != Format::Long && !more_info.is_empty() {
@@ -2781,17 +2936,14 @@ fn display_file_name(
     }
 
     if config.indicator_style != IndicatorStyle::None {
-        let sym = classify_file(path, out);
+        let sym = classify_file(
+            path,
+            config.indicator_style == IndicatorStyle::Classify,
+            out,
+        );
 
         let char_opt = match config.indicator_style {
-            IndicatorStyle::Classify => sym,
-            IndicatorStyle::FileType => {
-                // Don't append an asterisk.
-                match sym {
-                    Some('*') => None,
-                    _ => sym,
-                }
-            }
+            IndicatorStyle::Classify | IndicatorStyle::FileType => sym,
             IndicatorStyle::Slash => {
                 // Append only a slash.
                 match sym {
@@ -2808,56 +2960,31 @@ fn display_file_name(
         }
     }
 
-    if config.format == Format::Long
-        && path.file_type(out).is_some()
-        && path.file_type(out).unwrap().is_symlink()
-        && !path.must_dereference
-    {
-        if let Ok(target) = path.p_buf.read_link() {
+    if config.format == Format::Long && is_link(path, out) && !path.must_dereference {
+        if let Some(target) = link_target(path) {
             name.push_str(" -> ");
 
             // We might as well color the symlink output after the arrow.
             // This makes extra system calls, but provides important information that
             // people run `ls -l --color` are very interested in.
             if let Some(ls_colors) = &config.color {
-                // We get the absolute path to be able to construct PathData with valid Metadata.
-                // This is because relative symlinks will fail to get_metadata.
+                // The target is looked up from the directory of the link.
                 let mut absolute_target = target.clone();
                 if target.is_relative() {
                     if let Some(parent) = path.p_buf.parent() {
                         absolute_target = parent.join(absolute_target);
                     }
                 }
-
-                let target_data = PathData::new(absolute_target, None, None, config, false);
-
-                // If we have a symlink to a valid file, we use the metadata of said file.
-                // Because we use an absolute path, we can assume this is guaranteed to exist.
-                // Otherwise, we use path.md(), which will guarantee we color to the same
-                // color of non-existent symlinks according to style_for_path_with_metadata.
-                if path.md(out).is_none()
-                    && get_metadata(target_data.p_buf.as_path(), target_data.must_dereference)
-                        .is_err()
-                {
-                    name.push_str(&path.p_buf.read_link().unwrap().to_string_lossy());
-                } else {
-                    // Use fn get_metadata instead of md() here and above because ls
-                    // should not exit with an err, if we are unable to obtain the target_metadata
-                    let target_metadata = match get_metadata(
-                        target_data.p_buf.as_path(),
-                        target_data.must_dereference,
-                    ) {
-                        Ok(md) => md,
-                        Err(_) => path.md(out).unwrap().to_owned(),
-                    };
-
-                    name.push_str(&color_name(
-                        escape_name(target.as_os_str(), &config.quoting_style),
-                        &target_data.p_buf,
-                        Some(&target_metadata),
-                        ls_colors,
-                    ));
-                }
+                let target_md = stat::metadata(&path.p_buf, true, path.stat_mask).ok();
+                let color = ls_colors.color_for_target(
+                    &absolute_target,
+                    target.as_os_str(),
+                    target_md.as_ref(),
+                );
+                name.push_str(&ls_colors.paint(
+                    &escape_name(target.as_os_str(), &config.quoting_style),
+                    color,
+                ));
             } else {
                 // If no coloring is required, we just use target as is.
                 // Apply the right quoting
@@ -2886,15 +3013,6 @@ fn display_file_name(
     }
 }
 
-fn color_name(name: String, path: &Path, md: Option<&Metadata>, ls_colors: &LsColors) -> String {
-    match ls_colors.style_for_path_with_metadata(path, md) {
-        Some(style) => {
-            return style.to_nu_ansi_term_style().paint(name).to_string();
-        }
-        None => name,
-    }
-}
-
 #[cfg(not(unix))]
 fn display_symlink_count(_metadata: &Metadata) -> String {
     // Currently not sure of how to get this on Windows, so I'm punting.
@@ -2912,7 +3030,77 @@ fn display_inode(metadata: &Metadata) -> String {
     get_inode(metadata)
 }
 
-// This returns the SELinux security context as UTF8 `String`.
+/// The character that follows the permissions of `item` in long listings,
+/// if it has an alternate access method: `+` for an access control list,
+/// or `.` for a security context alone, like in GNU ls.
+fn alternate_access(item: &PathData, config: &Config) -> Option<char> {
+    if item.has_acl() {
+        Some('+')
+    } else if config.context && item.security_context != "?" {
+        Some('.')
+    } else {
+        None
+    }
+}
+
+/// Whether the file at `path` has a POSIX access control list, which Linux
+/// keeps in extended attributes. Only the presence of the attributes is
+/// probed, since they are removed when the list is only the permissions.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn has_acl(path: &Path, dereference: bool) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return false,
+    };
+    let has_xattr =
+        |name: &[u8]| matches!(get_xattr(&path, name, dereference, &mut []), Ok(Some(_)));
+    // Only directories have a default list, for the files created in them.
+    has_xattr(b"system.posix_acl_access\0") || has_xattr(b"system.posix_acl_default\0")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn has_acl(_path: &Path, _dereference: bool) -> bool {
+    false
+}
+
+/// Reads the extended attribute `name`, which ends with a nul byte, of
+/// `path` into `value`, or only gets its size if `value` is empty. Returns
+/// `None` if the file, or its file system, doesn't have the attribute.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn get_xattr(
+    path: &std::ffi::CStr,
+    name: &[u8],
+    dereference: bool,
+    value: &mut [u8],
+) -> std::io::Result<Option<usize>> {
+    use uucore::libc;
+
+    let (buf, size) = if value.is_empty() {
+        (std::ptr::null_mut(), 0)
+    } else {
+        (value.as_mut_ptr().cast(), value.len())
+    };
+    let size = unsafe {
+        if dereference {
+            libc::getxattr(path.as_ptr(), name.as_ptr().cast(), buf, size)
+        } else {
+            libc::lgetxattr(path.as_ptr(), name.as_ptr().cast(), buf, size)
+        }
+    };
+    if size < 0 {
+        let err = std::io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::ENODATA | libc::ENOTSUP) => Ok(None),
+            _ => Err(err),
+        };
+    }
+    Ok(Some(size as usize))
+}
+
+// This returns the SELinux security context, or the Smack label, as UTF8 `String`.
 // In the long term this should be changed to `OsStr`, see discussions at #2621/#2656
 #[allow(unused_variables)]
 fn get_security_context(config: &Config, p_buf: &Path, must_dereference: bool) -> String {
@@ -2923,7 +3111,10 @@ fn get_security_context(config: &Config, p_buf: &Path, must_dereference: bool) -
             match selinux::SecurityContext::of_path(p_buf, must_dereference.to_owned(), false) {
                 Err(_r) => {
                     // TODO: show the actual reason why it failed
-                    show_warning!("failed to get security context of: {}", p_buf.quote());
+                    show_warning!(
+                        "{}",
+                        translate!("warning-get-context", path = p_buf.quote())
+                    );
                     substitute_string
                 }
                 Ok(None) => substitute_string,
@@ -2933,9 +3124,8 @@ fn get_security_context(config: &Config, p_buf: &Path, must_dereference: bool) -
                     let context = context.strip_suffix(&[0]).unwrap_or(context);
                     String::from_utf8(context.to_vec()).unwrap_or_else(|e| {
                         show_warning!(
-                            "getting security context of: {}: {}",
-                            p_buf.quote(),
-                            e.to_string()
+                            "{}",
+                            translate!("warning-context-encoding", path = p_buf.quote(), error = e)
                         );
                         String::from_utf8_lossy(context).into_owned()
                     })
@@ -2946,11 +3136,59 @@ fn get_security_context(config: &Config, p_buf: &Path, must_dereference: bool) -
         {
             substitute_string
         }
+    } else if config.smack_supported {
+        #[cfg(all(feature = "selinux", target_os = "linux"))]
+        {
+            match smack_label(p_buf, must_dereference) {
+                Err(_r) => {
+                    show_warning!(
+                        "{}",
+                        translate!("warning-get-context", path = p_buf.quote())
+                    );
+                    substitute_string
+                }
+                Ok(None) => substitute_string,
+                Ok(Some(label)) => String::from_utf8(label).unwrap_or_else(|e| {
+                    show_warning!(
+                        "{}",
+                        translate!("warning-context-encoding", path = p_buf.quote(), error = e)
+                    );
+                    String::from_utf8_lossy(e.as_bytes()).into_owned()
+                }),
+            }
+        }
+        #[cfg(not(all(feature = "selinux", target_os = "linux")))]
+        {
+            substitute_string
+        }
     } else {
         substitute_string
     }
 }
 
+/// The Smack label of `path`, which is kept in its `security.SMACK64`
+/// extended attribute, or `None` if it has none.
+#[cfg(all(feature = "selinux", target_os = "linux"))]
+fn smack_label(path: &Path, dereference: bool) -> std::io::Result<Option<Vec<u8>>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes())?;
+    let name = b"security.SMACK64\0";
+    // The size of the label is asked for first.
+    let size = match get_xattr(&path, name, dereference, &mut [])? {
+        Some(size) => size,
+        None => return Ok(None),
+    };
+    let mut label = vec![0u8; size];
+    let size = get_xattr(&path, name, dereference, &mut label)?.unwrap_or_default();
+    label.truncate(size);
+    if let Some(0) = label.last() {
+        label.pop();
+    }
+    Ok(Some(label))
+}
+
 #[cfg(unix)]
 fn calculate_padding_collection(
     items: &[PathData],
@@ -2959,6 +3197,7 @@ fn calculate_padding_collection(
 ) -> PaddingCollection {
     let mut padding_collections = PaddingCollection {
         inode: 1,
+        alternate_access: 0,
         link_count: 1,
         uname: 1,
         group: 1,
@@ -2982,12 +3221,17 @@ fn calculate_padding_collection(
 
         if config.alloc_size {
             if let Some(md) = item.md(out) {
-                let block_size_len = display_size(get_block_size(md, config), config).len();
+                let block_size_len = config.block_unit.format(get_block_size(md)).len();
                 padding_collections.block_size = block_size_len.max(padding_collections.block_size);
             }
         }
 
         if config.format == Format::Long {
+            // Like in GNU ls, the permissions of all the files are followed by
+            // a space as soon as one of them has an alternate access method.
+            if alternate_access(item, config).is_some() {
+                padding_collections.alternate_access = 1;
+            }
             let context_len = item.security_context.len();
             let (link_count_len, uname_len, group_len, size_len, major_len, minor_len) =
                 display_dir_entry_size(item, config, out);
@@ -3021,6 +3265,7 @@ fn calculate_padding_collection(
     out: &mut BufWriter<Stdout>,
 ) -> PaddingCollection {
     let mut padding_collections = PaddingCollection {
+        alternate_access: 0,
         link_count: 1,
         uname: 1,
         group: 1,
@@ -3032,7 +3277,7 @@ fn calculate_padding_collection(
     for item in items {
         if config.alloc_size {
             if let Some(md) = item.md(out) {
-                let block_size_len = display_size(get_block_size(md, config), config).len();
+                let block_size_len = config.block_unit.format(get_block_size(md)).len();
                 padding_collections.block_size = block_size_len.max(padding_collections.block_size);
             }
         }
@@ -0,0 +1,371 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore statx btime nlink rdev fstatat
+
+//! The metadata of the files, as far as `ls` shows it.
+//!
+//! On Linux, the files are looked up with `statx`, which is only asked for
+//! the fields that the options need, like the mode alone for the colors or
+//! the classification, so that the file systems that have to fetch or
+//! compute the others, like the network ones, don't. Elsewhere, the
+//! metadata comes from the standard library.
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The fields of the metadata to look up, as the `STATX_*` flags of Linux.
+pub type Mask = u32;
+
+pub const TYPE: Mask = 0x0001;
+pub const MODE: Mask = 0x0002;
+pub const NLINK: Mask = 0x0004;
+pub const UID: Mask = 0x0008;
+pub const GID: Mask = 0x0010;
+pub const ATIME: Mask = 0x0020;
+pub const MTIME: Mask = 0x0040;
+pub const CTIME: Mask = 0x0080;
+pub const INO: Mask = 0x0100;
+pub const SIZE: Mask = 0x0200;
+pub const BLOCKS: Mask = 0x0400;
+pub const BTIME: Mask = 0x0800;
+
+// The format bits of the modes, which are the same on all the systems.
+const S_IFMT: u32 = 0o170_000;
+const S_IFSOCK: u32 = 0o140_000;
+const S_IFLNK: u32 = 0o120_000;
+const S_IFREG: u32 = 0o100_000;
+const S_IFBLK: u32 = 0o060_000;
+const S_IFDIR: u32 = 0o040_000;
+const S_IFCHR: u32 = 0o020_000;
+const S_IFIFO: u32 = 0o010_000;
+
+/// The type of a file, from its directory entry or from its metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileType {
+    format: u32,
+}
+
+impl FileType {
+    fn from_mode(mode: u32) -> Self {
+        Self {
+            format: mode & S_IFMT,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.format == S_IFDIR
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.format == S_IFREG
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.format == S_IFLNK
+    }
+
+    #[cfg(unix)]
+    pub fn is_fifo(&self) -> bool {
+        self.format == S_IFIFO
+    }
+
+    #[cfg(unix)]
+    pub fn is_socket(&self) -> bool {
+        self.format == S_IFSOCK
+    }
+
+    #[cfg(unix)]
+    pub fn is_block_device(&self) -> bool {
+        self.format == S_IFBLK
+    }
+
+    #[cfg(unix)]
+    pub fn is_char_device(&self) -> bool {
+        self.format == S_IFCHR
+    }
+}
+
+impl From<fs::FileType> for FileType {
+    fn from(file_type: fs::FileType) -> Self {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            if file_type.is_fifo() {
+                return Self { format: S_IFIFO };
+            } else if file_type.is_socket() {
+                return Self { format: S_IFSOCK };
+            } else if file_type.is_block_device() {
+                return Self { format: S_IFBLK };
+            } else if file_type.is_char_device() {
+                return Self { format: S_IFCHR };
+            }
+        }
+        let format = if file_type.is_dir() {
+            S_IFDIR
+        } else if file_type.is_symlink() {
+            S_IFLNK
+        } else if file_type.is_file() {
+            S_IFREG
+        } else {
+            0
+        };
+        Self { format }
+    }
+}
+
+/// The metadata of a file. Only the fields that were asked for are sure to
+/// be set; the others are 0, or the times are unknown.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    file_type: FileType,
+    len: u64,
+    accessed: Option<SystemTime>,
+    modified: Option<SystemTime>,
+    created: Option<SystemTime>,
+    #[cfg(unix)]
+    changed: Option<SystemTime>,
+    #[cfg(unix)]
+    mode: u32,
+    #[cfg(unix)]
+    nlink: u64,
+    #[cfg(unix)]
+    uid: u32,
+    #[cfg(unix)]
+    gid: u32,
+    #[cfg(unix)]
+    ino: u64,
+    #[cfg(unix)]
+    rdev: u64,
+    #[cfg(unix)]
+    blocks: u64,
+    #[cfg(not(unix))]
+    readonly: bool,
+    #[cfg(windows)]
+    attributes: u32,
+}
+
+fn time_or_unknown(time: Option<SystemTime>) -> io::Result<SystemTime> {
+    time.ok_or_else(|| io::Error::new(io::ErrorKind::Other, "the time is unknown"))
+}
+
+impl Metadata {
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.file_type.is_dir()
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn accessed(&self) -> io::Result<SystemTime> {
+        time_or_unknown(self.accessed)
+    }
+
+    pub fn modified(&self) -> io::Result<SystemTime> {
+        time_or_unknown(self.modified)
+    }
+
+    pub fn created(&self) -> io::Result<SystemTime> {
+        time_or_unknown(self.created)
+    }
+
+    /// The time the status of the file was last changed.
+    #[cfg(unix)]
+    pub fn changed(&self) -> io::Result<SystemTime> {
+        time_or_unknown(self.changed)
+    }
+
+    #[cfg(unix)]
+    pub fn mode(&self) -> u32 {
+        self.mode
+    }
+
+    #[cfg(unix)]
+    pub fn nlink(&self) -> u64 {
+        self.nlink
+    }
+
+    #[cfg(unix)]
+    pub fn uid(&self) -> u32 {
+        self.uid
+    }
+
+    #[cfg(unix)]
+    pub fn gid(&self) -> u32 {
+        self.gid
+    }
+
+    #[cfg(unix)]
+    pub fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    #[cfg(unix)]
+    pub fn rdev(&self) -> u64 {
+        self.rdev
+    }
+
+    #[cfg(unix)]
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    #[cfg(windows)]
+    pub fn file_attributes(&self) -> u32 {
+        self.attributes
+    }
+
+    /// The permissions of the file as long listings show them, with its
+    /// type first, like `drwxr-xr-x`.
+    pub fn display_permissions(&self) -> String {
+        #[cfg(unix)]
+        {
+            uucore::fs::display_permissions_unix(self.mode as uucore::libc::mode_t, true)
+        }
+        #[cfg(not(unix))]
+        {
+            let write = if self.readonly { '-' } else { 'w' };
+            let file_type = if self.file_type.is_symlink() {
+                'l'
+            } else if self.file_type.is_dir() {
+                'd'
+            } else {
+                '-'
+            };
+            format!("{file_type}r{write}xr{write}xr{write}x")
+        }
+    }
+}
+
+impl From<fs::Metadata> for Metadata {
+    fn from(md: fs::Metadata) -> Self {
+        #[cfg(unix)]
+        use std::os::unix::fs::MetadataExt;
+        #[cfg(windows)]
+        use std::os::windows::fs::MetadataExt;
+
+        Self {
+            file_type: md.file_type().into(),
+            len: md.len(),
+            accessed: md.accessed().ok(),
+            modified: md.modified().ok(),
+            created: md.created().ok(),
+            #[cfg(unix)]
+            changed: Some(uucore::fs::system_time(md.ctime(), md.ctime_nsec())),
+            #[cfg(unix)]
+            mode: md.mode(),
+            #[cfg(unix)]
+            nlink: md.nlink(),
+            #[cfg(unix)]
+            uid: md.uid(),
+            #[cfg(unix)]
+            gid: md.gid(),
+            #[cfg(unix)]
+            ino: md.ino(),
+            #[cfg(unix)]
+            rdev: md.rdev(),
+            #[cfg(unix)]
+            blocks: md.blocks(),
+            #[cfg(not(unix))]
+            readonly: md.permissions().readonly(),
+            #[cfg(windows)]
+            attributes: md.file_attributes(),
+        }
+    }
+}
+
+/// Look up the fields `mask` of the metadata of `path`, or of the file it
+/// points to with `dereference`.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn metadata(path: &Path, dereference: bool, mask: Mask) -> io::Result<Metadata> {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+    use uucore::libc;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let flags = if dereference {
+        0
+    } else {
+        libc::AT_SYMLINK_NOFOLLOW
+    };
+    let mut buf = MaybeUninit::<libc::statx>::zeroed();
+    let ret = unsafe {
+        libc::statx(
+            libc::AT_FDCWD,
+            c_path.as_ptr(),
+            flags,
+            mask | TYPE,
+            buf.as_mut_ptr(),
+        )
+    };
+    if ret != 0 {
+        let err = io::Error::last_os_error();
+        // Like the standard library, fall back to `stat` where `statx` is
+        // missing or forbidden.
+        return match err.raw_os_error() {
+            Some(libc::ENOSYS | libc::EPERM) => std_metadata(path, dereference),
+            _ => Err(err),
+        };
+    }
+    let stx = unsafe { buf.assume_init() };
+
+    let got = |field: Mask| stx.stx_mask & field != 0;
+    let time = |field: Mask, t: libc::statx_timestamp| {
+        got(field).then(|| uucore::fs::system_time(t.tv_sec, t.tv_nsec.into()))
+    };
+    let mode = u32::from(stx.stx_mode);
+    Ok(Metadata {
+        file_type: FileType::from_mode(mode),
+        len: stx.stx_size,
+        accessed: time(ATIME, stx.stx_atime),
+        modified: time(MTIME, stx.stx_mtime),
+        created: time(BTIME, stx.stx_btime),
+        changed: time(CTIME, stx.stx_ctime),
+        mode,
+        nlink: stx.stx_nlink.into(),
+        uid: stx.stx_uid,
+        gid: stx.stx_gid,
+        ino: stx.stx_ino,
+        rdev: libc::makedev(stx.stx_rdev_major, stx.stx_rdev_minor),
+        blocks: stx.stx_blocks,
+    })
+}
+
+/// Look up the fields `mask` of the metadata of the file of `entry`, which
+/// isn't followed if it is a symbolic link.
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub fn entry_metadata(entry: &fs::DirEntry, mask: Mask) -> io::Result<Metadata> {
+    metadata(&entry.path(), false, mask)
+}
+
+/// Look up the metadata of the file of `entry`, which isn't followed if it
+/// is a symbolic link. All the fields are looked up, whatever `mask` is.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+pub fn entry_metadata(entry: &fs::DirEntry, _mask: Mask) -> io::Result<Metadata> {
+    entry.metadata().map(Metadata::from)
+}
+
+/// Look up the metadata of `path`, or of the file it points to with
+/// `dereference`. All the fields are looked up, whatever `mask` is.
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+pub fn metadata(path: &Path, dereference: bool, _mask: Mask) -> io::Result<Metadata> {
+    std_metadata(path, dereference)
+}
+
+fn std_metadata(path: &Path, dereference: bool) -> io::Result<Metadata> {
+    if dereference {
+        path.metadata()
+    } else {
+        path.symlink_metadata()
+    }
+    .map(Metadata::from)
+}
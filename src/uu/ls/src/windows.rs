@@ -0,0 +1,144 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore APPEXECLINK FSCTL RHSA winioctl winnt
+
+//! File attributes and reparse points on Windows.
+use std::ffi::OsString;
+use std::fs::{Metadata, OpenOptions};
+use std::os::windows::ffi::OsStringExt;
+use std::os::windows::fs::{MetadataExt, OpenOptionsExt};
+use std::os::windows::io::AsRawHandle;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use windows_sys::Win32::Storage::FileSystem::{
+    FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_READONLY,
+    FILE_ATTRIBUTE_REPARSE_POINT, FILE_ATTRIBUTE_SYSTEM, FILE_FLAG_BACKUP_SEMANTICS,
+    FILE_FLAG_OPEN_REPARSE_POINT,
+};
+use windows_sys::Win32::System::IO::DeviceIoControl;
+
+// From winnt.h and winioctl.h
+const IO_REPARSE_TAG_MOUNT_POINT: u32 = 0xA000_0003;
+const IO_REPARSE_TAG_SYMLINK: u32 = 0xA000_000C;
+const IO_REPARSE_TAG_APPEXECLINK: u32 = 0x8000_001B;
+const FSCTL_GET_REPARSE_POINT: u32 = 0x0009_00A8;
+const MAXIMUM_REPARSE_DATA_BUFFER_SIZE: usize = 16 * 1024;
+
+/// Whether the file has the hidden attribute.
+pub fn is_hidden(md: &Metadata) -> bool {
+    md.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+/// Whether the file is a reparse point, like a symbolic link, a junction or
+/// an app execution alias.
+pub fn is_reparse_point(md: &crate::stat::Metadata) -> bool {
+    md.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+}
+
+/// The read-only, hidden, system and archive attributes of the file, in the
+/// order `attrib` lists them, with a `-` for each one that isn't set.
+pub fn display_attributes(md: &crate::stat::Metadata) -> String {
+    let attributes = md.file_attributes();
+    [
+        (FILE_ATTRIBUTE_READONLY, 'R'),
+        (FILE_ATTRIBUTE_HIDDEN, 'H'),
+        (FILE_ATTRIBUTE_SYSTEM, 'S'),
+        (FILE_ATTRIBUTE_ARCHIVE, 'A'),
+    ]
+    .iter()
+    .map(|&(flag, c)| if attributes & flag != 0 { c } else { '-' })
+    .collect()
+}
+
+/// The path that the reparse point `path` leads to.
+///
+/// Besides symbolic links, this covers junctions and app execution aliases,
+/// which `std::fs::read_link` doesn't know about or shows as NT paths.
+/// `None` is returned for other kinds of reparse points, which don't lead
+/// anywhere a user could follow.
+pub fn reparse_target(path: &Path) -> Option<PathBuf> {
+    let file = OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .ok()?;
+    let mut buffer = vec![0u8; MAXIMUM_REPARSE_DATA_BUFFER_SIZE];
+    let mut bytes_returned = 0;
+    let ok = unsafe {
+        DeviceIoControl(
+            file.as_raw_handle() as _,
+            FSCTL_GET_REPARSE_POINT,
+            ptr::null(),
+            0,
+            buffer.as_mut_ptr().cast(),
+            buffer.len() as u32,
+            &mut bytes_returned,
+            ptr::null_mut(),
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    buffer.truncate(bytes_returned as usize);
+    parse_reparse_buffer(&buffer).map(|target| PathBuf::from(OsString::from_wide(&target)))
+}
+
+/// Extract the target from a `REPARSE_DATA_BUFFER`.
+fn parse_reparse_buffer(buffer: &[u8]) -> Option<Vec<u16>> {
+    let u16_at = |offset: usize| -> Option<u16> {
+        Some(u16::from_le_bytes(
+            buffer.get(offset..offset + 2)?.try_into().ok()?,
+        ))
+    };
+    let u32_at = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            buffer.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    let wide_at = |offset: usize, length: usize| -> Option<Vec<u16>> {
+        let bytes = buffer.get(offset..offset + length)?;
+        Some(
+            bytes
+                .chunks_exact(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .collect(),
+        )
+    };
+
+    // The header is the tag, the length of the data and a reserved field.
+    let data = match u32_at(0)? {
+        // Symbolic links have a flags field before their names.
+        IO_REPARSE_TAG_SYMLINK => 20,
+        IO_REPARSE_TAG_MOUNT_POINT => 16,
+        // App execution aliases have a version, followed by the package,
+        // the app and the executable as NUL terminated strings.
+        IO_REPARSE_TAG_APPEXECLINK => {
+            let strings = wide_at(12, buffer.len().checked_sub(12)?)?;
+            return strings
+                .split(|&c| c == 0)
+                .nth(2)
+                .filter(|target| !target.is_empty())
+                .map(<[u16]>::to_vec);
+        }
+        _ => return None,
+    };
+
+    // The "print name" is the path meant to be shown to users, but it may
+    // be missing, in which case the "substitute name" is used. That is an
+    // NT path, like `\??\C:\dir`, of which only `C:\dir` makes sense to a
+    // user.
+    let print_name = wide_at(data + usize::from(u16_at(12)?), usize::from(u16_at(14)?))?;
+    if !print_name.is_empty() {
+        return Some(print_name);
+    }
+    let substitute_name = wide_at(data + usize::from(u16_at(8)?), usize::from(u16_at(10)?))?;
+    let nt_prefix: Vec<u16> = r"\??\".encode_utf16().collect();
+    Some(match substitute_name.strip_prefix(nt_prefix.as_slice()) {
+        Some(path) => path.to_vec(),
+        None => substitute_name,
+    })
+}
@@ -5,7 +5,7 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) ugoa cmode
+// spell-checker:ignore (ToDO) ugoa
 
 use clap::builder::ValueParser;
 use clap::parser::ValuesRef;
@@ -17,9 +17,13 @@ use uucore::error::FromIo;
 use uucore::error::{UResult, USimpleError};
 #[cfg(not(windows))]
 use uucore::mode;
-use uucore::{display::Quotable, fs::dir_strip_dot_for_creation};
+use uucore::{
+    display::Quotable,
+    fs::{dir_strip_dot_for_creation, long_path},
+};
 use uucore::{format_usage, show, show_if_err};
 
+#[cfg(windows)]
 static DEFAULT_PERM: u32 = 0o755;
 
 static ABOUT: &str = "Create the given DIRECTORY(ies) if they do not exist";
@@ -43,26 +47,17 @@ fn get_mode(_matches: &ArgMatches, _mode_had_minus_prefix: bool) -> Result<u32,
 
 #[cfg(not(windows))]
 fn get_mode(matches: &ArgMatches, mode_had_minus_prefix: bool) -> Result<u32, String> {
-    let digits: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-    // Translate a ~str in octal form to u16, default to 755
-    // Not tested on Windows
-    let mut new_mode = DEFAULT_PERM;
     match matches.get_one::<String>(options::MODE) {
         Some(m) => {
-            for mode in m.split(',') {
-                if mode.contains(digits) {
-                    new_mode = mode::parse_numeric(new_mode, m, true)?;
-                } else {
-                    let cmode = if mode_had_minus_prefix {
-                        // clap parsing is finished, now put prefix back
-                        format!("-{mode}")
-                    } else {
-                        mode.to_string()
-                    };
-                    new_mode = mode::parse_symbolic(new_mode, &cmode, mode::get_umask(), true)?;
-                }
-            }
-            Ok(new_mode)
+            let m = if mode_had_minus_prefix {
+                // clap parsing is finished, now put prefix back
+                format!("-{m}")
+            } else {
+                m.to_string()
+            };
+            // Like with GNU, the mode changes a=rwx, not the default mode.
+            mode::parse_creation_mode(&m, 0o777, mode::get_umask(), true)
+                .map_err(|_| format!("invalid mode {}", m.quote()))
         }
         None => {
             // If no mode argument is specified return the mode derived from umask
@@ -92,9 +87,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // Linux-specific options, not implemented
     // opts.optflag("Z", "context", "set SELinux security context" +
     // " of each created directory to CTX"),
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let dirs = matches
         .get_many::<OsString>(options::DIRS)
@@ -113,6 +106,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::MODE)
@@ -203,7 +197,7 @@ fn create_dir(path: &Path, recursive: bool, verbose: bool) -> UResult<()> {
             }
         }
     }
-    match std::fs::create_dir(path) {
+    match std::fs::create_dir(long_path(path)) {
         Ok(()) => {
             if verbose {
                 println!(
@@ -8,9 +8,10 @@
 use clap::{crate_version, Arg, ArgAction, Command};
 use libc::mkfifo;
 use std::ffi::CString;
+use std::io;
 use uucore::display::Quotable;
-use uucore::error::{UResult, USimpleError};
-use uucore::{format_usage, show};
+use uucore::error::{FromIo, UError, UResult, USimpleError};
+use uucore::{format_usage, mode, show};
 
 static USAGE: &str = "{} [OPTION]... NAME...";
 static ABOUT: &str = "Create a FIFO with the given name.";
@@ -35,12 +36,19 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         return Err(USimpleError::new(1, "-Z is not implemented"));
     }
 
+    // Like with GNU, a mode that is given is set as is, whatever the umask.
     let mode = match matches.get_one::<String>(options::MODE) {
-        Some(m) => match usize::from_str_radix(m, 8) {
-            Ok(m) => m,
-            Err(e) => return Err(USimpleError::new(1, format!("invalid mode: {e}"))),
+        Some(m) => match mode::parse_mode(m) {
+            Ok(m) if m > 0o777 => {
+                return Err(USimpleError::new(
+                    1,
+                    "mode must specify only file permission bits",
+                ))
+            }
+            Ok(m) => Some(m),
+            Err(e) => return Err(USimpleError::new(1, format!("invalid mode ({e})"))),
         },
-        None => 0o666,
+        None => None,
     };
 
     let fifos: Vec<String> = match matches.get_many::<String>(options::FIFO) {
@@ -49,15 +57,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     };
 
     for f in fifos {
-        let err = unsafe {
-            let name = CString::new(f.as_bytes()).unwrap();
-            mkfifo(name.as_ptr(), mode as libc::mode_t)
-        };
+        let name = CString::new(f.as_bytes()).unwrap();
+        let err = unsafe { mkfifo(name.as_ptr(), mode.unwrap_or(0o666)) };
         if err == -1 {
             show!(USimpleError::new(
                 1,
                 format!("cannot create fifo {}: File exists", f.quote())
             ));
+        } else if let Some(mode) = mode {
+            if unsafe { libc::chmod(name.as_ptr(), mode) } == -1 {
+                show!(io::Error::last_os_error()
+                    .map_err_context(|| format!("cannot set permissions of {}", f.quote())));
+            }
         }
     }
 
@@ -75,7 +86,6 @@ pub fn uu_app() -> Command {
                 .short('m')
                 .long(options::MODE)
                 .help("file permissions for the fifo")
-                .default_value("0666")
                 .value_name("MODE"),
         )
         .arg(
@@ -46,7 +46,7 @@ fn makedev(maj: u64, min: u64) -> dev_t {
 }
 
 #[cfg(windows)]
-fn _mknod(file_name: &str, mode: mode_t, dev: dev_t) -> i32 {
+fn _mknod(file_name: &str, mode: mode_t, dev: dev_t, set_umask: bool) -> i32 {
     panic!("Unsupported for windows platform")
 }
 
@@ -57,13 +57,12 @@ enum FileType {
     Fifo,
 }
 
+/// Create the special file `file_name`, with exactly the permissions of
+/// `mode` when `set_umask` is set, because the user supplied them.
 #[cfg(unix)]
-fn _mknod(file_name: &str, mode: mode_t, dev: dev_t) -> i32 {
+fn _mknod(file_name: &str, mode: mode_t, dev: dev_t, set_umask: bool) -> i32 {
     let c_str = CString::new(file_name).expect("Failed to convert to CString");
 
-    // the user supplied a mode
-    let set_umask = mode & MODE_RW_UGO != MODE_RW_UGO;
-
     unsafe {
         // store prev umask
         let last_umask = if set_umask { libc::umask(0) } else { 0 };
@@ -95,6 +94,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let matches = uu_app().try_get_matches_from(args)?;
 
     let mode = get_mode(&matches).map_err(|e| USimpleError::new(1, e))?;
+    let set_umask = matches.contains_id("mode");
 
     let file_name = matches
         .get_one::<String>("name")
@@ -109,7 +109,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                 "Fifos do not have major and minor device numbers.",
             ))
         } else {
-            let exit_code = _mknod(file_name, S_IFIFO | mode, 0);
+            let exit_code = _mknod(file_name, S_IFIFO | mode, 0, set_umask);
             set_exit_code(exit_code);
             Ok(())
         }
@@ -127,8 +127,8 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             (Some(&major), Some(&minor)) => {
                 let dev = makedev(major, minor);
                 let exit_code = match file_type {
-                    FileType::Block => _mknod(file_name, S_IFBLK | mode, dev),
-                    FileType::Character => _mknod(file_name, S_IFCHR | mode, dev),
+                    FileType::Block => _mknod(file_name, S_IFBLK | mode, dev, set_umask),
+                    FileType::Character => _mknod(file_name, S_IFCHR | mode, dev, set_umask),
                     _ => unreachable!("file_type was validated to be only block or character"),
                 };
                 set_exit_code(exit_code);
@@ -12,21 +12,21 @@ use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use uucore::display::{println_verbatim, Quotable};
 use uucore::error::{FromIo, UError, UResult, UUsageError};
 use uucore::format_usage;
+use uucore::rand::RandomSource;
 
 use std::env;
 use std::error::Error;
 use std::fmt::Display;
-use std::io::ErrorKind;
-use std::iter;
+use std::fs;
+use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf, MAIN_SEPARATOR};
 
 #[cfg(unix)]
-use std::fs;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
 #[cfg(unix)]
 use std::os::unix::prelude::PermissionsExt;
 
-use rand::Rng;
-use tempfile::Builder;
+use rand::seq::SliceRandom;
 
 static ABOUT: &str = "Create a temporary file or directory.";
 const USAGE: &str = "{} [OPTION]... [TEMPLATE]";
@@ -42,9 +42,14 @@ static OPT_T: &str = "t";
 
 static ARG_TEMPLATE: &str = "template";
 
+/// The characters of the random part of the names.
+const NAME_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// How many names are tried before giving up, while the ones tried exist.
+const NUM_ATTEMPTS: u32 = 62 * 62 * 62;
+
 #[derive(Debug)]
 enum MkTempError {
-    PersistError(PathBuf),
     MustEndInX(String),
     TooFewXs(String),
 
@@ -72,7 +77,6 @@ impl Display for MkTempError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use MkTempError::*;
         match self {
-            PersistError(p) => write!(f, "could not persist file {}", p.quote()),
             MustEndInX(s) => write!(f, "with --suffix, template {} must end in X", s.quote()),
             TooFewXs(s) => write!(f, "too few X's in template {}", s.quote()),
             PrefixContainsDirSeparator(s) => {
@@ -356,7 +360,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     // application logic.
     let options = Options::from(&matches);
 
-    if env::var("POSIXLY_CORRECT").is_ok() {
+    if uucore::posix::posixly_correct() {
         // If POSIXLY_CORRECT was set, template MUST be the last argument.
         if is_tmpdir_argument_actually_the_template(&matches) || matches.contains_id(ARG_TEMPLATE) {
             // Template argument was provided, check if was the last one.
@@ -378,11 +382,13 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         suffix,
     } = Params::from(options)?;
 
+    let mut rng = RandomSource::from_matches(&matches)?;
+
     // Create the temporary file or directory, or simulate creating it.
     let res = if dry_run {
-        dry_exec(&tmpdir, &prefix, rand, &suffix)
+        dry_exec(&tmpdir, &prefix, rand, &suffix, &mut rng)
     } else {
-        exec(&tmpdir, &prefix, rand, &suffix, make_dir)
+        exec(&tmpdir, &prefix, rand, &suffix, make_dir, &mut rng)
     };
 
     if suppress_file_err {
@@ -454,30 +460,52 @@ pub fn uu_app() -> Command {
                 )
                 .action(ArgAction::SetTrue),
         )
+        .arg(uucore::rand::arguments::random_source())
         .arg(Arg::new(ARG_TEMPLATE).num_args(..=1))
 }
 
-pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<()> {
-    let len = prefix.len() + suffix.len() + rand;
-    let mut buf = Vec::with_capacity(len);
-    buf.extend(prefix.as_bytes());
-    buf.extend(iter::repeat(b'X').take(rand));
-    buf.extend(suffix.as_bytes());
-
-    // Randomize.
-    let bytes = &mut buf[prefix.len()..prefix.len() + rand];
-    rand::thread_rng().fill(bytes);
-    for byte in bytes.iter_mut() {
-        *byte = match *byte % 62 {
-            v @ 0..=9 => v + b'0',
-            v @ 10..=35 => v - 10 + b'a',
-            v @ 36..=61 => v - 36 + b'A',
-            _ => unreachable!(),
+/// The name made of `prefix`, `rand` random characters and `suffix`.
+fn random_name(prefix: &str, rand: usize, suffix: &str, rng: &mut RandomSource) -> String {
+    let chars: String = (0..rand)
+        .map(|_| *NAME_CHARS.choose(rng).unwrap() as char)
+        .collect();
+    format!("{prefix}{chars}{suffix}")
+}
+
+/// Creates the entry of a random name in `dir` with `create`, trying other
+/// names while it fails because the entry exists.
+fn create_random_entry<F>(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    rng: &mut RandomSource,
+    create: F,
+) -> io::Result<PathBuf>
+where
+    F: Fn(&Path) -> io::Result<()>,
+{
+    for _ in 0..NUM_ATTEMPTS {
+        let path = Path::new(dir).join(random_name(prefix, rand, suffix, rng));
+        match create(&path) {
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            res => return res.map(|_| path),
         }
     }
-    // We guarantee utf8.
-    let buf = String::from_utf8(buf).unwrap();
-    let tmpdir = Path::new(tmpdir).join(buf);
+    Err(io::Error::new(
+        ErrorKind::AlreadyExists,
+        "too many temporary files exist",
+    ))
+}
+
+pub fn dry_exec(
+    tmpdir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    rng: &mut RandomSource,
+) -> UResult<()> {
+    let tmpdir = Path::new(tmpdir).join(random_name(prefix, rand, suffix, rng));
     println_verbatim(tmpdir).map_err_context(|| "failed to print directory name".to_owned())
 }
 
@@ -492,13 +520,21 @@ pub fn dry_exec(tmpdir: &str, prefix: &str, rand: usize, suffix: &str) -> UResul
 ///
 /// If the temporary directory could not be written to disk or if the
 /// given directory `dir` does not exist.
-fn make_temp_dir(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<PathBuf> {
-    let mut builder = Builder::new();
-    builder.prefix(prefix).rand_bytes(rand).suffix(suffix);
-    match builder.tempdir_in(dir) {
-        Ok(d) => {
-            // `into_path` consumes the TempDir without removing it
-            let path = d.into_path();
+fn make_temp_dir(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    rng: &mut RandomSource,
+) -> UResult<PathBuf> {
+    let res = create_random_entry(dir, prefix, rand, suffix, rng, |path| {
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        builder.mode(0o700);
+        builder.create(path)
+    });
+    match res {
+        Ok(path) => {
             #[cfg(not(windows))]
             fs::set_permissions(&path, fs::Permissions::from_mode(0o700))?;
             Ok(path)
@@ -524,15 +560,22 @@ fn make_temp_dir(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<
 ///
 /// If the file could not be written to disk or if the directory does
 /// not exist.
-fn make_temp_file(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult<PathBuf> {
-    let mut builder = Builder::new();
-    builder.prefix(prefix).rand_bytes(rand).suffix(suffix);
-    match builder.tempfile_in(dir) {
-        // `keep` ensures that the file is not deleted
-        Ok(named_tempfile) => match named_tempfile.keep() {
-            Ok((_, pathbuf)) => Ok(pathbuf),
-            Err(e) => Err(MkTempError::PersistError(e.file.path().to_path_buf()).into()),
-        },
+fn make_temp_file(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    rng: &mut RandomSource,
+) -> UResult<PathBuf> {
+    let res = create_random_entry(dir, prefix, rand, suffix, rng, |path| {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        options.open(path).map(|_| ())
+    });
+    match res {
+        Ok(path) => Ok(path),
         Err(e) if e.kind() == ErrorKind::NotFound => {
             let filename = format!("{}{}{}", prefix, "X".repeat(rand), suffix);
             let path = Path::new(dir).join(filename);
@@ -543,11 +586,18 @@ fn make_temp_file(dir: &str, prefix: &str, rand: usize, suffix: &str) -> UResult
     }
 }
 
-fn exec(dir: &str, prefix: &str, rand: usize, suffix: &str, make_dir: bool) -> UResult<()> {
+fn exec(
+    dir: &str,
+    prefix: &str,
+    rand: usize,
+    suffix: &str,
+    make_dir: bool,
+    rng: &mut RandomSource,
+) -> UResult<()> {
     let path = if make_dir {
-        make_temp_dir(dir, prefix, rand, suffix)?
+        make_temp_dir(dir, prefix, rand, suffix, rng)?
     } else {
-        make_temp_file(dir, prefix, rand, suffix)?
+        make_temp_file(dir, prefix, rand, suffix, rng)?
     };
 
     // Get just the last component of the path to the created
@@ -555,11 +605,8 @@ fn exec(dir: &str, prefix: &str, rand: usize, suffix: &str, make_dir: bool) -> U
     let filename = path.file_name();
     let filename = filename.unwrap().to_str().unwrap();
 
-    // Join the directory to the path to get the path to print. We
-    // cannot use the path returned by the `Builder` because it gives
-    // the absolute path and we need to return a filename that matches
-    // the template given on the command-line which might be a
-    // relative path.
+    // Join the directory to the path to get the path to print, which
+    // matches the template given on the command-line.
     let path = Path::new(dir).join(filename);
 
     println_verbatim(path).map_err_context(|| "failed to print directory name".to_owned())
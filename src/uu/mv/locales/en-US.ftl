@@ -0,0 +1,29 @@
+# The messages of mv, in English, which are the reference for the catalogs
+# in other languages. See uucore::i18n for the format.
+
+error-no-such-file = cannot stat { $path }: No such file or directory
+error-same-file = { $source } and { $target } are the same file
+error-self-subdirectory = cannot move '{ $source }' to a subdirectory of itself, '{ $source }/{ $source }'
+error-move-to-subdirectory = cannot move '{ $source }' to a subdirectory of itself, '{ $target }/{ $name }'
+error-directory-to-non-directory = cannot overwrite directory { $target } with non-directory
+error-non-directory-to-directory = cannot overwrite non-directory { $target } with directory { $source }
+error-not-a-directory = target { $target } is not a directory
+error-cannot-move = cannot move { $source } to { $target }
+error-cannot-exchange = cannot exchange { $source } and { $target }
+error-backup-no-clobber = options --backup and --no-clobber are mutually exclusive
+error-extra-operand = extra operand { $operand }
+error-directory-not-empty = Directory not empty
+error-dangling-symlink = can't determine symlink type, since it is dangling
+error-no-symlinks = your operating system does not support symlinks
+error-cannot-preserve-xattrs = cannot preserve extended attributes of { $path }
+error-exchange-unsupported = exchanging files is not supported on this system or file system
+error-set-context = failed to set the security context of { $path }: { $error }
+warning-context-ignored = ignoring --context; it requires an SELinux-enabled kernel
+prompt-overwrite = overwrite { $path }?
+dry-run-rename = would rename { $source } to { $target }
+dry-run-copy = would copy { $source } to { $target } on another file system, then remove { $source }
+dry-run-exchange = would exchange { $source } and { $target }
+dry-run-overwrite = would overwrite { $target }
+dry-run-backup = would back up { $target } to { $backup }
+dry-run-trash = would move { $target } to the trash
+dry-run-skip = would skip { $target }, which exists
@@ -6,6 +6,7 @@ use std::error::Error;
 use std::fmt::{Display, Formatter, Result};
 
 use uucore::error::UError;
+use uucore::translate;
 
 #[derive(Debug)]
 pub enum MvError {
@@ -21,20 +22,18 @@ impl Error for MvError {}
 impl UError for MvError {}
 impl Display for MvError {
     fn fmt(&self, f: &mut Formatter) -> Result {
-        match self {
-            Self::NoSuchFile(s) => write!(f, "cannot stat {s}: No such file or directory"),
-            Self::SameFile(s, t) => write!(f, "{s} and {t} are the same file"),
-            Self::SelfSubdirectory(s) => write!(
-                f,
-                "cannot move '{s}' to a subdirectory of itself, '{s}/{s}'"
-            ),
+        let message = match self {
+            Self::NoSuchFile(s) => translate!("error-no-such-file", path = s),
+            Self::SameFile(s, t) => translate!("error-same-file", source = s, target = t),
+            Self::SelfSubdirectory(s) => translate!("error-self-subdirectory", source = s),
             Self::DirectoryToNonDirectory(t) => {
-                write!(f, "cannot overwrite directory {t} with non-directory")
+                translate!("error-directory-to-non-directory", target = t)
             }
             Self::NonDirectoryToDirectory(s, t) => {
-                write!(f, "cannot overwrite non-directory {t} with directory {s}")
+                translate!("error-non-directory-to-directory", source = s, target = t)
             }
-            Self::NotADirectory(t) => write!(f, "target {t} is not a directory"),
-        }
+            Self::NotADirectory(t) => translate!("error-not-a-directory", target = t),
+        };
+        f.write_str(&message)
     }
 }
@@ -13,10 +13,12 @@ mod error;
 use clap::builder::ValueParser;
 use clap::{crate_version, error::ErrorKind, Arg, ArgAction, ArgMatches, Command};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::env;
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 #[cfg(unix)]
 use std::os::unix;
 #[cfg(windows)]
@@ -24,13 +26,16 @@ use std::os::windows;
 use std::path::{Path, PathBuf};
 use uucore::backup_control::{self, BackupMode};
 use uucore::display::Quotable;
-use uucore::error::{FromIo, UError, UResult, USimpleError, UUsageError};
-use uucore::{format_usage, prompt_yes, show};
+use uucore::error::{set_exit_code, FromIo, UError, UResult, USimpleError, UUsageError};
+use uucore::fs::long_path;
+#[cfg(unix)]
+use uucore::fsxattr::{self, XattrPolicy};
+use uucore::report;
+use uucore::trash::trash;
+use uucore::update_control::{self, UpdateAction, UpdateMode};
+use uucore::{format_usage, show, show_error, show_warning, translate};
 
-use fs_extra::dir::{
-    get_size as dir_get_size, move_dir, move_dir_with_progress, CopyOptions as DirCopyOptions,
-    TransitProcess, TransitProcessResult,
-};
+use fs_extra::dir::get_size as dir_get_size;
 
 use crate::error::MvError;
 
@@ -38,12 +43,27 @@ pub struct Behavior {
     overwrite: OverwriteMode,
     backup: BackupMode,
     suffix: String,
-    update: bool,
+    update: UpdateMode,
     target_dir: Option<OsString>,
     no_target_dir: bool,
     verbose: bool,
     strip_slashes: bool,
     progress_bar: bool,
+    trash: bool,
+    exchange: bool,
+    dry_run: bool,
+    context: bool,
+    // The answers to the prompts of -i that hold for the next files too.
+    standing_answer: Cell<StandingAnswer>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum StandingAnswer {
+    None,
+    /// "a": overwrite all the next destinations without asking.
+    All,
+    /// "q": keep all the next destinations and move nothing else.
+    Quit,
 }
 
 #[derive(Clone, Eq, PartialEq)]
@@ -66,19 +86,17 @@ static OPT_NO_CLOBBER: &str = "no-clobber";
 static OPT_STRIP_TRAILING_SLASHES: &str = "strip-trailing-slashes";
 static OPT_TARGET_DIRECTORY: &str = "target-directory";
 static OPT_NO_TARGET_DIRECTORY: &str = "no-target-directory";
-static OPT_UPDATE: &str = "update";
 static OPT_VERBOSE: &str = "verbose";
 static OPT_PROGRESS: &str = "progress";
+static OPT_TRASH: &str = "trash";
+static OPT_EXCHANGE: &str = "exchange";
+static OPT_DRY_RUN: &str = "dry-run";
+static OPT_CONTEXT: &str = "context";
 static ARG_FILES: &str = "files";
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let help = format!(
-        "{}\n{}",
-        LONG_HELP,
-        backup_control::BACKUP_CONTROL_LONG_HELP
-    );
-    let mut app = uu_app().after_help(help);
+    let mut app = uu_app();
     let matches = app.try_get_matches_from_mut(args)?;
 
     if !matches.contains_id(OPT_TARGET_DIRECTORY)
@@ -107,10 +125,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let backup_mode = backup_control::determine_backup_mode(&matches)?;
 
     if overwrite_mode == OverwriteMode::NoClobber && backup_mode != BackupMode::NoBackup {
-        return Err(UUsageError::new(
-            1,
-            "options --backup and --no-clobber are mutually exclusive",
-        ));
+        return Err(UUsageError::new(1, translate!("error-backup-no-clobber")));
     }
 
     let backup_suffix = backup_control::determine_backup_suffix(&matches);
@@ -119,17 +134,30 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         overwrite: overwrite_mode,
         backup: backup_mode,
         suffix: backup_suffix,
-        update: matches.get_flag(OPT_UPDATE),
+        update: update_control::determine_update_mode(&matches),
         target_dir: matches
             .get_one::<OsString>(OPT_TARGET_DIRECTORY)
             .map(OsString::from),
         no_target_dir: matches.get_flag(OPT_NO_TARGET_DIRECTORY),
         verbose: matches.get_flag(OPT_VERBOSE),
         strip_slashes: matches.get_flag(OPT_STRIP_TRAILING_SLASHES),
-        progress_bar: matches.get_flag(OPT_PROGRESS),
+        // Nothing is moved in a dry run, so there is no progress to show.
+        progress_bar: matches.get_flag(OPT_PROGRESS) && !matches.get_flag(OPT_DRY_RUN),
+        trash: matches.get_flag(OPT_TRASH),
+        exchange: matches.get_flag(OPT_EXCHANGE),
+        dry_run: matches.get_flag(OPT_DRY_RUN),
+        context: matches.get_flag(OPT_CONTEXT) && is_selinux_enabled(),
+        standing_answer: Cell::new(StandingAnswer::None),
     };
 
-    exec(&files[..], &behavior)
+    if let Some(format) = report::determine_report_format(&matches) {
+        report::enable(format);
+    }
+    let result = exec(&files[..], &behavior);
+    if behavior.standing_answer.get() == StandingAnswer::Quit {
+        set_exit_code(1);
+    }
+    result
 }
 
 pub fn uu_app() -> Command {
@@ -137,6 +165,12 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(format!(
+            "{}\n{}\n\n{}",
+            LONG_HELP,
+            backup_control::BACKUP_CONTROL_LONG_HELP,
+            update_control::UPDATE_CONTROL_LONG_HELP
+        ))
         .infer_long_args(true)
         .arg(backup_control::arguments::backup())
         .arg(backup_control::arguments::backup_no_args())
@@ -185,16 +219,8 @@ pub fn uu_app() -> Command {
                 .help("treat DEST as a normal file")
                 .action(ArgAction::SetTrue),
         )
-        .arg(
-            Arg::new(OPT_UPDATE)
-                .short('u')
-                .long(OPT_UPDATE)
-                .help(
-                    "move only when the SOURCE file is newer than the destination file \
-                       or when the destination file is missing",
-                )
-                .action(ArgAction::SetTrue),
-        )
+        .arg(update_control::arguments::update())
+        .arg(update_control::arguments::update_no_args())
         .arg(
             Arg::new(OPT_VERBOSE)
                 .short('v')
@@ -212,6 +238,42 @@ pub fn uu_app() -> Command {
                 )
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new(OPT_TRASH)
+                .long(OPT_TRASH)
+                .help(
+                    "move files that would be overwritten to the trash. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(OPT_EXCHANGE)
+                .long(OPT_EXCHANGE)
+                .help(
+                    "exchange SOURCE and DEST atomically, which must both exist \n\
+                Note: this feature is only supported on Linux.",
+                )
+                .conflicts_with_all([OPT_TRASH, OPT_PROGRESS])
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(OPT_CONTEXT)
+                .short('Z')
+                .long(OPT_CONTEXT)
+                .help("set SELinux security context of destination file to default type")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new(OPT_DRY_RUN)
+                .long(OPT_DRY_RUN)
+                .help(
+                    "print what would be done, without changing any file \n\
+                Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
+        .arg(report::arguments::report())
         .arg(
             Arg::new(ARG_FILES)
                 .action(ArgAction::Append)
@@ -222,6 +284,19 @@ pub fn uu_app() -> Command {
         )
 }
 
+/// Whether `-Z` can relabel files, which is reported otherwise, like with
+/// GNU mv.
+fn is_selinux_enabled() -> bool {
+    #[cfg(feature = "feat_selinux")]
+    let enabled = selinux::kernel_support() != selinux::KernelSupport::Unsupported;
+    #[cfg(not(feature = "feat_selinux"))]
+    let enabled = false;
+    if !enabled {
+        show_warning!("{}", translate!("warning-context-ignored"));
+    }
+    enabled
+}
+
 fn determine_overwrite_mode(matches: &ArgMatches) -> OverwriteMode {
     // This does not exactly match the GNU implementation:
     // The GNU mv defaults to Force, but if more than one of the
@@ -257,8 +332,10 @@ fn exec(files: &[OsString], b: &Behavior) -> UResult<()> {
     match paths.len() {
         /* case 0/1 are not possible thanks to clap */
         2 => {
+            let multi_progress = b.progress_bar.then(MultiProgress::new);
             let source = &paths[0];
             let target = &paths[1];
+            report::start_operand(source);
             // Here we use the `symlink_metadata()` method instead of `exists()`,
             // since it handles dangling symlinks correctly. The method gives an
             // `Ok()` results unless the source does not exist, or the user
@@ -283,9 +360,13 @@ fn exec(files: &[OsString], b: &Behavior) -> UResult<()> {
 
             if target.is_dir() {
                 if b.no_target_dir {
-                    if source.is_dir() {
-                        rename(source, target, b, None).map_err_context(|| {
-                            format!("cannot move {} to {}", source.quote(), target.quote())
+                    if source.is_dir() || b.exchange {
+                        rename(source, target, b, multi_progress.as_ref()).map_err_context(|| {
+                            translate!(
+                                "error-cannot-move",
+                                source = source.quote(),
+                                target = target.quote()
+                            )
                         })
                     } else {
                         Err(MvError::DirectoryToNonDirectory(target.quote().to_string()).into())
@@ -293,11 +374,14 @@ fn exec(files: &[OsString], b: &Behavior) -> UResult<()> {
                 } else {
                     move_files_into_dir(&[source.clone()], target, b)
                 }
-            } else if target.exists() && source.is_dir() {
+            } else if target.exists() && source.is_dir() && !b.exchange {
+                if b.update == UpdateMode::ReplaceNone {
+                    return Ok(());
+                }
                 match b.overwrite {
                     OverwriteMode::NoClobber => return Ok(()),
                     OverwriteMode::Interactive => {
-                        if !prompt_yes!("overwrite {}? ", target.quote()) {
+                        if !prompt_overwrite(target, b) {
                             return Ok(());
                         }
                     }
@@ -308,15 +392,27 @@ fn exec(files: &[OsString], b: &Behavior) -> UResult<()> {
                     target.quote().to_string(),
                 )
                 .into())
+            } else if b.exchange {
+                rename(source, target, b, None).map_err_context(|| {
+                    translate!(
+                        "error-cannot-exchange",
+                        source = source.quote(),
+                        target = target.quote()
+                    )
+                })
             } else {
-                rename(source, target, b, None).map_err(|e| USimpleError::new(1, format!("{e}")))
+                rename(source, target, b, multi_progress.as_ref())
+                    .map_err(|e| USimpleError::new(1, format!("{e}")))
             }
         }
         _ => {
             if b.no_target_dir {
                 return Err(UUsageError::new(
                     1,
-                    format!("mv: extra operand {}", files[2].quote()),
+                    format!(
+                        "mv: {}",
+                        translate!("error-extra-operand", operand = files[2].quote())
+                    ),
                 ));
             }
             let target_dir = paths.last().unwrap();
@@ -353,6 +449,14 @@ fn move_files_into_dir(files: &[PathBuf], target_dir: &Path, b: &Behavior) -> UR
     };
 
     for sourcepath in files.iter() {
+        if b.standing_answer.get() == StandingAnswer::Quit {
+            break;
+        }
+        // The source of `mv SOURCE DIRECTORY` is already counted.
+        if files.len() > 1 || b.target_dir.is_some() {
+            report::start_operand(sourcepath);
+        }
+
         if let Some(ref pb) = count_progress {
             pb.set_message(sourcepath.to_string_lossy().to_string());
         }
@@ -373,11 +477,11 @@ fn move_files_into_dir(files: &[PathBuf], target_dir: &Path, b: &Behavior) -> UR
                 // and process of moving files is continued.
                 show!(USimpleError::new(
                     1,
-                    format!(
-                        "cannot move '{}' to a subdirectory of itself, '{}/{}'",
-                        sourcepath.display(),
-                        target_dir.display(),
-                        canonized_target_dir.components().last().map_or_else(
+                    translate!(
+                        "error-move-to-subdirectory",
+                        source = sourcepath.display(),
+                        target = target_dir.display(),
+                        name = canonized_target_dir.components().last().map_or_else(
                             || target_dir.display().to_string(),
                             |dir| { PathBuf::from(dir.as_os_str()).display().to_string() }
                         )
@@ -389,10 +493,10 @@ fn move_files_into_dir(files: &[PathBuf], target_dir: &Path, b: &Behavior) -> UR
 
         let rename_result = rename(sourcepath, &targetpath, b, multi_progress.as_ref())
             .map_err_context(|| {
-                format!(
-                    "cannot move {} to {}",
-                    sourcepath.quote(),
-                    targetpath.quote()
+                translate!(
+                    "error-cannot-move",
+                    source = sourcepath.quote(),
+                    target = targetpath.quote()
                 )
             });
 
@@ -410,6 +514,42 @@ fn move_files_into_dir(files: &[PathBuf], target_dir: &Path, b: &Behavior) -> UR
     Ok(())
 }
 
+/// Ask whether to overwrite `path`, unless a previous answer holds for all
+/// the files. Besides "y" and "n", the answers "a" (yes to all) and "q"
+/// (quit) are accepted to go through many files.
+fn prompt_overwrite(path: &Path, b: &Behavior) -> bool {
+    match b.standing_answer.get() {
+        StandingAnswer::All => return true,
+        StandingAnswer::Quit => return false,
+        StandingAnswer::None => {}
+    }
+
+    eprint!(
+        "{}: {} ",
+        uucore::util_name(),
+        translate!("prompt-overwrite", path = path.quote())
+    );
+    if io::stderr().flush().is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    if io::stdin().read_line(&mut reply).is_err() {
+        return false;
+    }
+    match reply.chars().next() {
+        Some('y' | 'Y') => true,
+        Some('a' | 'A') => {
+            b.standing_answer.set(StandingAnswer::All);
+            true
+        }
+        Some('q' | 'Q') => {
+            b.standing_answer.set(StandingAnswer::Quit);
+            false
+        }
+        _ => false,
+    }
+}
+
 fn rename(
     from: &Path,
     to: &Path,
@@ -418,31 +558,63 @@ fn rename(
 ) -> io::Result<()> {
     let mut backup_path = None;
 
-    if to.exists() {
-        if b.update && b.overwrite == OverwriteMode::Interactive {
-            // `mv -i --update old new` when `new` exists doesn't move anything
-            // and exit with 0
-            return Ok(());
+    if b.dry_run {
+        return print_dry_run(from, to, b);
+    }
+
+    if b.exchange {
+        exchange(from, to)?;
+        if b.verbose {
+            println!("{} <-> {}", from.quote(), to.quote());
         }
+        return Ok(());
+    }
 
-        match b.overwrite {
-            OverwriteMode::NoClobber => return Ok(()),
-            OverwriteMode::Interactive => {
-                if !prompt_yes!("overwrite {}?", to.quote()) {
-                    return Ok(());
+    if b.overwrite == OverwriteMode::NoClobber || b.update == UpdateMode::ReplaceNone {
+        // Check that `to` doesn't exist as it is renamed, not before, when
+        // the system allows it: another process may create it in between.
+        match rename_noreplace(from, to) {
+            Ok(true) => {
+                if b.context {
+                    set_default_context(to);
+                }
+                if b.verbose {
+                    print_verbose(from, to, None, multi_progress);
                 }
+                return Ok(());
             }
-            OverwriteMode::Force => {}
-        };
+            Ok(false) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
 
-        backup_path = backup_control::get_backup_path(b.backup, to, &b.suffix);
-        if let Some(ref backup_path) = backup_path {
-            rename_with_fallback(to, backup_path, multi_progress)?;
+    if to.exists() {
+        // Unlike with `cp`, the destinations kept by `mv -n` are no failure.
+        match update_control::determine_update_action(
+            b.update,
+            b.overwrite == OverwriteMode::NoClobber,
+            &from.symlink_metadata()?,
+            &to.symlink_metadata()?,
+        ) {
+            UpdateAction::Replace => {}
+            UpdateAction::Skip | UpdateAction::SkipWithFailure => return Ok(()),
         }
 
-        if b.update && fs::metadata(from)?.modified()? <= fs::metadata(to)?.modified()? {
+        if b.overwrite == OverwriteMode::Interactive && !prompt_overwrite(to, b) {
             return Ok(());
         }
+
+        if let Some(backup) = backup_control::create_backup_path(b.backup, to, &b.suffix)? {
+            rename_with_fallback(to, &backup, multi_progress)?;
+            backup_path = Some(backup.commit());
+        }
+
+        // Directories are only ever replaced when empty, so only files are
+        // worth keeping.
+        if b.trash && backup_path.is_none() && !to.is_dir() {
+            trash(to)?;
+        }
     }
 
     // "to" may no longer exist if it was backed up
@@ -452,34 +624,276 @@ fn rename(
             if is_empty_dir(to) {
                 fs::remove_dir(to)?;
             } else {
-                return Err(io::Error::new(io::ErrorKind::Other, "Directory not empty"));
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    translate!("error-directory-not-empty"),
+                ));
             }
         }
     }
 
     rename_with_fallback(from, to, multi_progress)?;
 
+    if b.context {
+        set_default_context(to);
+    }
+
     if b.verbose {
-        let message = match backup_path {
-            Some(path) => format!(
-                "{} -> {} (backup: {})",
-                from.quote(),
-                to.quote(),
-                path.quote()
+        print_verbose(from, to, backup_path.as_deref(), multi_progress);
+    }
+    Ok(())
+}
+
+/// Print what [`rename`] would do to move `from` to `to`, without doing it.
+fn print_dry_run(from: &Path, to: &Path, b: &Behavior) -> io::Result<()> {
+    let from_metadata = from.symlink_metadata()?;
+    if b.exchange {
+        to.symlink_metadata()?;
+        println!(
+            "{}",
+            translate!(
+                "dry-run-exchange",
+                source = from.quote(),
+                target = to.quote()
+            )
+        );
+        return Ok(());
+    }
+
+    if let Ok(to_metadata) = to.symlink_metadata() {
+        match update_control::determine_update_action(
+            b.update,
+            b.overwrite == OverwriteMode::NoClobber,
+            &from_metadata,
+            &to_metadata,
+        ) {
+            UpdateAction::Replace => {}
+            UpdateAction::Skip | UpdateAction::SkipWithFailure => {
+                println!("{}", translate!("dry-run-skip", target = to.quote()));
+                return Ok(());
+            }
+        }
+
+        match backup_control::get_backup_path(b.backup, to, &b.suffix) {
+            Some(backup_path) => println!(
+                "{}",
+                translate!(
+                    "dry-run-backup",
+                    target = to.quote(),
+                    backup = backup_path.quote()
+                )
             ),
-            None => format!("{} -> {}", from.quote(), to.quote()),
-        };
+            None if b.trash && !to_metadata.is_dir() => {
+                println!("{}", translate!("dry-run-trash", target = to.quote()));
+            }
+            None => {
+                if to_metadata.is_dir() && from_metadata.is_dir() && !is_empty_dir(to) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::Other,
+                        translate!("error-directory-not-empty"),
+                    ));
+                }
+                println!("{}", translate!("dry-run-overwrite", target = to.quote()));
+            }
+        }
+    }
 
-        match multi_progress {
-            Some(pb) => pb.suspend(|| {
-                println!("{message}");
-            }),
-            None => println!("{message}"),
-        };
+    if is_same_file_system(from, to)? {
+        println!(
+            "{}",
+            translate!("dry-run-rename", source = from.quote(), target = to.quote())
+        );
+    } else {
+        println!(
+            "{}",
+            translate!("dry-run-copy", source = from.quote(), target = to.quote())
+        );
+    }
+    Ok(())
+}
+
+/// Whether `from` can be renamed to `to`, rather than copied, because the
+/// directory of `to` is on the same file system.
+#[cfg(unix)]
+fn is_same_file_system(from: &Path, to: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let to_dir = match to.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    Ok(from.symlink_metadata()?.dev() == to_dir.metadata()?.dev())
+}
+
+/// Whether `from` can be renamed to `to`, rather than copied. The volume of
+/// a file is not available on stable Rust here, so this assumes it can.
+#[cfg(not(unix))]
+fn is_same_file_system(_from: &Path, _to: &Path) -> io::Result<bool> {
+    Ok(true)
+}
+
+fn print_verbose(
+    from: &Path,
+    to: &Path,
+    backup_path: Option<&Path>,
+    multi_progress: Option<&MultiProgress>,
+) {
+    let message = match backup_path {
+        Some(path) => format!(
+            "{} -> {} (backup: {})",
+            from.quote(),
+            to.quote(),
+            path.quote()
+        ),
+        None => format!("{} -> {}", from.quote(), to.quote()),
+    };
+
+    match multi_progress {
+        Some(pb) => pb.suspend(|| {
+            println!("{message}");
+        }),
+        None => println!("{message}"),
+    };
+}
+
+/// Relabel `path` with the default SELinux context of where it is now, as
+/// `restorecon` would, for `-Z`. Like with GNU mv, failing to do so is only
+/// reported.
+fn set_default_context(path: &Path) {
+    #[cfg(feature = "feat_selinux")]
+    if let Err(e) = try_set_default_context(path) {
+        show_error!(
+            "{}",
+            translate!("error-set-context", path = path.quote(), error = e)
+        );
     }
+    #[cfg(not(feature = "feat_selinux"))]
+    let _ = path;
+}
+
+#[cfg(feature = "feat_selinux")]
+fn try_set_default_context(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use selinux::label::{back_end, Labeler};
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let mode = path.symlink_metadata()?.mode();
+    // The rules of the policy are for absolute paths.
+    let absolute = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) if !dir.as_os_str().is_empty() => fs::canonicalize(dir)?.join(name),
+        (_, Some(name)) => env::current_dir()?.join(name),
+        _ => fs::canonicalize(path)?,
+    };
+    let labeler = Labeler::<back_end::File>::new(&[], false)?;
+    let context = labeler.look_up(
+        &CString::new(absolute.as_os_str().as_bytes())?,
+        mode as libc::c_int,
+    )?;
+    context.set_for_path(path, false, false)?;
     Ok(())
 }
 
+/// Rename `from` to `to` with `renameat2`, and the given `RENAME_*` flags.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn renameat2(from: &Path, to: &Path, flags: u32) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            from.as_ptr(),
+            libc::AT_FDCWD,
+            to.as_ptr(),
+            flags,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Rename `from` to `to` if `to` doesn't exist, atomically, failing with
+/// [`io::ErrorKind::AlreadyExists`] otherwise.
+///
+/// `false` is returned when nothing was done because that can't be done
+/// atomically, as on some file systems, or across file systems, so that the
+/// caller falls back to checking whether `to` exists before renaming.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::unnecessary_cast)] // The flags are `c_int`s on Android.
+fn rename_noreplace(from: &Path, to: &Path) -> io::Result<bool> {
+    match renameat2(from, to, libc::RENAME_NOREPLACE as u32) {
+        Ok(()) => Ok(true),
+        Err(e) => match e.raw_os_error() {
+            // Kernels older than 3.15 don't know `renameat2`, and some file
+            // systems, like those of NFS, don't support its flags.
+            Some(libc::ENOSYS | libc::EINVAL | libc::EXDEV) => Ok(false),
+            _ => Err(e),
+        },
+    }
+}
+
+#[cfg(windows)]
+fn rename_noreplace(from: &Path, to: &Path) -> io::Result<bool> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::ERROR_NOT_SAME_DEVICE;
+    use windows_sys::Win32::Storage::FileSystem::MoveFileExW;
+
+    let wide = |path: &Path| -> Vec<u16> {
+        long_path(path)
+            .as_os_str()
+            .encode_wide()
+            .chain(std::iter::once(0))
+            .collect()
+    };
+    // Without MOVEFILE_REPLACE_EXISTING, which `fs::rename` passes, the
+    // move fails if `to` exists.
+    if unsafe { MoveFileExW(wide(from).as_ptr(), wide(to).as_ptr(), 0) } != 0 {
+        return Ok(true);
+    }
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(code) if code == ERROR_NOT_SAME_DEVICE as i32 => Ok(false),
+        _ => Err(error),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", windows)))]
+fn rename_noreplace(_from: &Path, _to: &Path) -> io::Result<bool> {
+    Ok(false)
+}
+
+/// Swap `from` and `to` atomically, with `renameat2(RENAME_EXCHANGE)`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[allow(clippy::unnecessary_cast)] // The flags are `c_int`s on Android.
+fn exchange(from: &Path, to: &Path) -> io::Result<()> {
+    renameat2(from, to, libc::RENAME_EXCHANGE as u32).map_err(|e| {
+        match e.raw_os_error() {
+            // Kernels older than 3.15 don't know `renameat2`, and some file
+            // systems, like those of NFS, don't support the exchange.
+            Some(libc::ENOSYS | libc::EINVAL) => io::Error::new(
+                io::ErrorKind::Other,
+                translate!("error-exchange-unsupported"),
+            ),
+            _ => e,
+        }
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn exchange(_from: &Path, _to: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Other,
+        translate!("error-exchange-unsupported"),
+    ))
+}
+
 /// A wrapper around `fs::rename`, so that if it fails, we try falling back on
 /// copying and removing.
 fn rename_with_fallback(
@@ -487,6 +901,8 @@ fn rename_with_fallback(
     to: &Path,
     multi_progress: Option<&MultiProgress>,
 ) -> io::Result<()> {
+    let (from, to) = (long_path(from), long_path(to));
+    let (from, to) = (&*from, &*to);
     if fs::rename(from, to).is_err() {
         // Get metadata without following symlinks
         let metadata = from.symlink_metadata()?;
@@ -501,14 +917,6 @@ fn rename_with_fallback(
             if to.exists() {
                 fs::remove_dir_all(to)?;
             }
-            let options = DirCopyOptions {
-                // From the `fs_extra` documentation:
-                // "Recursively copy a directory with a new name or place it
-                // inside the destination. (same behaviors like cp -r in Unix)"
-                copy_inside: true,
-                ..DirCopyOptions::new()
-            };
-
             // Calculate total size of directory
             // Silently degrades:
             //    If finding the total size fails for whatever reason,
@@ -518,52 +926,236 @@ fn rename_with_fallback(
 
             let progress_bar =
                 if let (Some(multi_progress), Some(total_size)) = (multi_progress, total_size) {
-                    let bar = ProgressBar::new(total_size).with_style(
-                        ProgressStyle::with_template(
-                            "{msg}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7}",
-                        )
-                        .unwrap(),
-                    );
-
-                    Some(multi_progress.add(bar))
+                    Some(add_bytes_progress_bar(multi_progress, total_size))
                 } else {
                     None
                 };
 
-            let result = if let Some(ref pb) = progress_bar {
-                move_dir_with_progress(from, to, &options, |process_info: TransitProcess| {
-                    pb.set_position(process_info.copied_bytes);
-                    pb.set_message(process_info.file_name);
-                    TransitProcessResult::ContinueOrAbort
-                })
-            } else {
-                move_dir(from, to, &options)
-            };
-
-            if let Err(err) = result {
-                return match err.kind {
-                    fs_extra::error::ErrorKind::PermissionDenied => Err(io::Error::new(
-                        io::ErrorKind::PermissionDenied,
-                        "Permission denied",
-                    )),
-                    _ => Err(io::Error::new(io::ErrorKind::Other, format!("{err:?}"))),
-                };
-            }
+            copy_dir(from, to, progress_bar.as_ref(), &mut HashMap::new())?;
+            fs::remove_dir_all(from)?;
+        } else if let Some(multi_progress) = multi_progress {
+            let bar = add_bytes_progress_bar(multi_progress, metadata.len());
+            bar.set_message(from.to_string_lossy().to_string());
+            let result = copy_file(from, to, &metadata, Some(&bar)).and_then(|_| {
+                bar.suspend(|| preserve_xattrs(from, to));
+                fs::remove_file(from)
+            });
+            bar.finish_and_clear();
+            result?;
         } else {
-            fs::copy(from, to).and_then(|_| fs::remove_file(from))?;
+            copy_file(from, to, &metadata, None).and_then(|_| {
+                preserve_xattrs(from, to);
+                fs::remove_file(from)
+            })?;
         }
     }
     Ok(())
 }
 
+/// Copy the extended attributes of `from` to `to`, which hold the ACLs and
+/// the SELinux context on Linux, so that moving a file to another file
+/// system doesn't lose them. Like with GNU mv, the attributes that the file
+/// system of `to` doesn't support are dropped silently, and the others that
+/// can't be copied are reported without failing the move.
+#[cfg(unix)]
+fn preserve_xattrs(from: &Path, to: &Path) {
+    if let Err(e) = fsxattr::copy_xattrs(from, to, XattrPolicy::BestEffort) {
+        show_error!(
+            "{}",
+            e.map_err_context(|| translate!("error-cannot-preserve-xattrs", path = to.quote()))
+        );
+    }
+    if !fsxattr::is_copied(OsStr::new("security.selinux")) {
+        // Without SELinux, or without the right to relabel the file, it
+        // keeps the context of its new place, as with GNU mv.
+        let _ = fsxattr::copy_selinux_context(from, to);
+    }
+}
+
+#[cfg(not(unix))]
+fn preserve_xattrs(_from: &Path, _to: &Path) {}
+
+/// The copies of the files with several hard links that were copied so far
+/// by [`copy_dir`], by the device and inode of their source.
+type HardLinks = HashMap<(u64, u64), PathBuf>;
+
+/// Copy the directory `from` and everything in it to `to`, which must not
+/// exist, for a move to another file system, showing the progress of the
+/// copy on `bar`, if any.
+///
+/// Like with `cp -a`, symbolic links are copied as links, and the files that
+/// are hard links to the same file are copied once, and linked to the copy,
+/// rather than copied again. The extended attributes are copied as well.
+fn copy_dir(
+    from: &Path,
+    to: &Path,
+    bar: Option<&ProgressBar>,
+    hard_links: &mut HardLinks,
+) -> io::Result<()> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let (from, to) = (entry.path(), to.join(entry.file_name()));
+        let metadata = entry.metadata()?;
+        let file_type = metadata.file_type();
+        if file_type.is_dir() {
+            copy_dir(&from, &to, bar, hard_links)?;
+            continue;
+        }
+        if file_type.is_symlink() {
+            copy_symlink(&from, &to)?;
+            continue;
+        }
+        let key = hard_link_key(&metadata);
+        if let Some(copy) = key.and_then(|key| hard_links.get(&key)) {
+            fs::hard_link(copy, &to)?;
+            if let Some(bar) = bar {
+                bar.inc(metadata.len());
+            }
+            continue;
+        }
+        if let Some(bar) = bar {
+            bar.set_message(from.to_string_lossy().to_string());
+        }
+        copy_file(&from, &to, &metadata, bar)?;
+        match bar {
+            Some(bar) => bar.suspend(|| preserve_xattrs(&from, &to)),
+            None => preserve_xattrs(&from, &to),
+        }
+        if let Some(key) = key {
+            hard_links.insert(key, to);
+        }
+    }
+    match bar {
+        Some(bar) => bar.suspend(|| preserve_xattrs(from, to)),
+        None => preserve_xattrs(from, to),
+    }
+    fs::set_permissions(to, fs::symlink_metadata(from)?.permissions())
+}
+
+/// The key of `metadata` in [`HardLinks`], if it is the metadata of a file
+/// with other hard links.
+#[cfg(unix)]
+fn hard_link_key(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    (metadata.nlink() > 1).then(|| (metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn hard_link_key(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// Add a bar showing how many of `total_size` bytes were moved so far.
+fn add_bytes_progress_bar(multi_progress: &MultiProgress, total_size: u64) -> ProgressBar {
+    let bar = ProgressBar::new(total_size).with_style(
+        ProgressStyle::with_template(
+            "{msg}: [{elapsed_precise}] {wide_bar} {bytes:>7}/{total_bytes:7}",
+        )
+        .unwrap(),
+    );
+    multi_progress.add(bar)
+}
+
+/// Copy the contents and the permissions of the regular file `from` to `to`,
+/// like `fs::copy`, showing the progress of the copy on `bar`, if any.
+///
+/// The file is cloned if the file systems allow it, which is instant, like
+/// between two subvolumes of a Btrfs file system. Otherwise, it is copied by
+/// the kernel with `copy_file_range` on Linux, and cloned or copied with
+/// `fcopyfile` on macOS by `fs::copy`, before falling back to reading and
+/// writing it.
+fn copy_file(
+    from: &Path,
+    to: &Path,
+    metadata: &fs::Metadata,
+    bar: Option<&ProgressBar>,
+) -> io::Result<()> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if clone_file(from, to).is_ok() {
+        if let Some(bar) = bar {
+            bar.inc(metadata.len());
+        }
+        return fs::set_permissions(to, metadata.permissions());
+    }
+    let bar = match bar {
+        Some(bar) => bar,
+        None => return fs::copy(from, to).map(|_| ()),
+    };
+    let reader = fs::File::open(from)?;
+    let mut writer = fs::File::create(to)?;
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    copy_file_range(&reader, &writer, bar)?;
+    // What `copy_file_range` didn't copy, if anything, from where it stopped.
+    io::copy(&mut bar.wrap_read(reader), &mut writer)?;
+    fs::set_permissions(to, metadata.permissions())
+}
+
+/// Make `to` a copy-on-write clone of `from`, with the `FICLONE` ioctl.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn clone_file(from: &Path, to: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let source = fs::File::open(from)?;
+    let dest = fs::File::create(to)?;
+    // FICLONE, which is _IOW(0x94, 9, int) in /usr/include/linux/fs.h.
+    if unsafe { libc::ioctl(dest.as_raw_fd(), 0x40049409, source.as_raw_fd()) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Copy the rest of `reader` to `writer` with `copy_file_range`, as far as
+/// the file systems allow it, showing the progress on `bar`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn copy_file_range(reader: &fs::File, writer: &fs::File, bar: &ProgressBar) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+    use std::ptr;
+
+    const CHUNK_SIZE: usize = 1 << 20;
+
+    let mut copied = false;
+    loop {
+        let result = unsafe {
+            libc::copy_file_range(
+                reader.as_raw_fd(),
+                ptr::null_mut(),
+                writer.as_raw_fd(),
+                ptr::null_mut(),
+                CHUNK_SIZE,
+                0,
+            )
+        };
+        match result {
+            0 => return Ok(()),
+            // Unsupported by the kernel or the file systems: the files
+            // are read and written instead.
+            -1 if !copied => return Ok(()),
+            -1 => return Err(io::Error::last_os_error()),
+            n => {
+                copied = true;
+                bar.inc(n as u64);
+            }
+        }
+    }
+}
+
 /// Move the given symlink to the given destination. On Windows, dangling
 /// symlinks return an error.
 #[inline]
 fn rename_symlink_fallback(from: &Path, to: &Path) -> io::Result<()> {
+    copy_symlink(from, to)?;
+    fs::remove_file(from)
+}
+
+/// Make `to` a symbolic link to where the link `from` points to.
+fn copy_symlink(from: &Path, to: &Path) -> io::Result<()> {
     let path_symlink_points_to = fs::read_link(from)?;
     #[cfg(unix)]
     {
-        unix::fs::symlink(path_symlink_points_to, to).and_then(|_| fs::remove_file(from))?;
+        unix::fs::symlink(path_symlink_points_to, to)?;
     }
     #[cfg(windows)]
     {
@@ -573,11 +1165,10 @@ fn rename_symlink_fallback(from: &Path, to: &Path) -> io::Result<()> {
             } else {
                 windows::fs::symlink_file(&path_symlink_points_to, to)?;
             }
-            fs::remove_file(from)?;
         } else {
             return Err(io::Error::new(
                 io::ErrorKind::NotFound,
-                "can't determine symlink type, since it is dangling",
+                translate!("error-dangling-symlink"),
             ));
         }
     }
@@ -585,7 +1176,7 @@ fn rename_symlink_fallback(from: &Path, to: &Path) -> io::Result<()> {
     {
         return Err(io::Error::new(
             io::ErrorKind::Other,
-            "your operating system does not support symlinks",
+            translate!("error-no-symlinks"),
         ));
     }
     Ok(())
@@ -5,17 +5,16 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) getpriority execvp setpriority nstr PRIO cstrs ENOENT
+// spell-checker:ignore (ToDO) getpriority setpriority nstr PRIO
 
-use libc::{c_char, c_int, execvp, PRIO_PROCESS};
-use std::ffi::{CString, OsString};
+use libc::PRIO_PROCESS;
+use std::ffi::OsString;
 use std::io::{Error, Write};
-use std::ptr;
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use uucore::{
     error::{set_exit_code, UClapError, UResult, USimpleError, UUsageError},
-    format_usage, show_error,
+    exec, format_usage,
 };
 
 pub mod options {
@@ -169,26 +168,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         return Ok(());
     }
 
-    let cstrs: Vec<CString> = matches
-        .get_many::<String>(options::COMMAND)
-        .unwrap()
-        .map(|x| CString::new(x.as_bytes()).unwrap())
-        .collect();
-
-    let mut args: Vec<*const c_char> = cstrs.iter().map(|s| s.as_ptr()).collect();
-    args.push(ptr::null::<c_char>());
-    unsafe {
-        execvp(args[0], args.as_mut_ptr());
-    }
-
-    show_error!("execvp: {}", Error::last_os_error());
-    let exit_code = if Error::last_os_error().raw_os_error().unwrap() as c_int == libc::ENOENT {
-        127
-    } else {
-        126
-    };
-    set_exit_code(exit_code);
-    Ok(())
+    let mut command = matches.get_many::<String>(options::COMMAND).unwrap();
+    let program = command.next().unwrap();
+    Err(exec::command_error(
+        program.as_str(),
+        exec::exec(program, command),
+    ))
 }
 
 pub fn uu_app() -> Command {
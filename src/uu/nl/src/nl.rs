@@ -10,11 +10,12 @@
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use std::fs::File;
-use std::io::{stdin, BufRead, BufReader, Read};
+use std::io::{self, stdin, BufReader, Read};
 use std::iter::repeat;
 use std::path::Path;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::format_usage;
+use uucore::lines::{lines, LineEnding};
 
 mod helper;
 
@@ -40,6 +41,8 @@ pub struct Settings {
     renumber: bool,
     // The string appended to each line number output.
     number_separator: String,
+    // The variable corresponding to -z.
+    line_ending: LineEnding,
 }
 
 // NumberingStyle stores which lines are to be numbered.
@@ -79,6 +82,7 @@ pub mod options {
     pub const NUMBER_SEPARATOR: &str = "number-separator";
     pub const STARTING_LINE_NUMBER: &str = "starting-line-number";
     pub const NUMBER_WIDTH: &str = "number-width";
+    pub const ZERO_TERMINATED: &str = "zero-terminated";
 }
 
 #[uucore::main]
@@ -100,6 +104,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         number_format: NumberFormat::Right,
         renumber: true,
         number_separator: String::from("\t"),
+        line_ending: LineEnding::from_zero_flag(matches.get_flag(options::ZERO_TERMINATED)),
     };
 
     // Update the settings from the command line options, and terminate the
@@ -233,6 +238,16 @@ pub fn uu_app() -> Command {
                 .help("use NUMBER columns for line numbers")
                 .value_name("NUMBER"),
         )
+        .arg(
+            Arg::new(options::ZERO_TERMINATED)
+                .short('z')
+                .long(options::ZERO_TERMINATED)
+                .help(
+                    "line delimiter is NUL, not newline. \
+                     Note: this feature is not supported by GNU coreutils.",
+                )
+                .action(ArgAction::SetTrue),
+        )
 }
 
 // nl implements the main functionality for an individual buffer.
@@ -257,12 +272,15 @@ fn nl<T: Read>(reader: &mut BufReader<T>, settings: &Settings) -> UResult<()> {
         _ => &regexp,
     };
     let mut line_filter: fn(&str, &regex::Regex) -> bool = pass_regex;
-    for l in reader.lines() {
-        let mut l = l.map_err_context(|| "could not read line".to_string())?;
+    let line_ending = settings.line_ending;
+    for l in lines(reader, line_ending as u8) {
         // Sanitize the string. We want to print the newline ourselves.
-        if l.ends_with('\n') {
-            l.pop();
-        }
+        let l = l
+            .and_then(|l| {
+                String::from_utf8(line_ending.strip(&l).to_vec())
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            })
+            .map_err_context(|| "could not read line".to_string())?;
         // Next we iterate through the individual chars to see if this
         // is one of the special lines starting a new "section" in the
         // document.
@@ -296,7 +314,7 @@ fn nl<T: Read>(reader: &mut BufReader<T>, settings: &Settings) -> UResult<()> {
         if matched_groups > 0 {
             // The current line is a section delimiter, so we output
             // a blank line.
-            println!();
+            print!("{line_ending}");
             // However the line does not count as a blank line, so we
             // reset the counter used for --join-blank-lines.
             empty_line_count = 0;
@@ -349,7 +367,7 @@ fn nl<T: Read>(reader: &mut BufReader<T>, settings: &Settings) -> UResult<()> {
             // want to print one in the first place, or it is a blank
             // line but we are still collecting more blank lines via
             // the option --join-blank-lines.
-            println!("{line}");
+            print!("{line}{line_ending}");
             continue;
         }
         // If we make it here, then either we are printing a non-empty
@@ -364,13 +382,13 @@ fn nl<T: Read>(reader: &mut BufReader<T>, settings: &Settings) -> UResult<()> {
         };
         let fill: String = repeat(fill_char).take(w).collect();
         match settings.number_format {
-            NumberFormat::Left => println!(
-                "{1}{0}{2}{3}",
-                fill, line_no, settings.number_separator, line
+            NumberFormat::Left => print!(
+                "{1}{0}{2}{3}{4}",
+                fill, line_no, settings.number_separator, line, line_ending
             ),
-            _ => println!(
-                "{0}{1}{2}{3}",
-                fill, line_no, settings.number_separator, line
+            _ => print!(
+                "{0}{1}{2}{3}{4}",
+                fill, line_no, settings.number_separator, line, line_ending
             ),
         }
         // Now update the variables for the (potential) next
@@ -5,21 +5,20 @@
 //  * For the full copyright and license information, please view the LICENSE
 //  * file that was distributed with this source code.
 
-// spell-checker:ignore (ToDO) execvp SIGHUP cproc vprocmgr cstrs homeout
+// spell-checker:ignore (ToDO) SIGHUP cproc vprocmgr homeout
 
 use clap::{crate_version, Arg, ArgAction, Command};
-use libc::{c_char, dup2, execvp, signal};
+use libc::{dup2, signal};
 use libc::{SIGHUP, SIG_IGN};
 use std::env;
-use std::ffi::CString;
 use std::fmt::{Display, Formatter};
 use std::fs::{File, OpenOptions};
 use std::io::Error;
 use std::os::unix::prelude::*;
 use std::path::{Path, PathBuf};
 use uucore::display::Quotable;
-use uucore::error::{set_exit_code, UClapError, UError, UResult};
-use uucore::{format_usage, show_error};
+use uucore::error::{ExitCodePolicy, UClapError, UError, UResult};
+use uucore::{exec, format_usage, show_error};
 
 static ABOUT: &str = "Run COMMAND ignoring hangup signals.";
 static LONG_HELP: &str = "
@@ -32,11 +31,9 @@ const USAGE: &str = "\
     {} COMMAND [ARG]...
     {} FLAG";
 static NOHUP_OUT: &str = "nohup.out";
-// exit codes that match the GNU implementation
-static EXIT_CANCELED: i32 = 125;
-static EXIT_CANNOT_INVOKE: i32 = 126;
-static EXIT_ENOENT: i32 = 127;
-static POSIX_NOHUP_FAILURE: i32 = 127;
+const POLICY: ExitCodePolicy = ExitCodePolicy::COMMAND_RUNNER;
+// POSIX requires 127 instead of 125 when nohup itself fails
+const POSIX_NOHUP_FAILURE: i32 = 127;
 
 mod options {
     pub const CMD: &str = "cmd";
@@ -46,18 +43,15 @@ mod options {
 enum NohupError {
     CannotDetach,
     CannotReplace(&'static str, std::io::Error),
-    OpenFailed(i32, std::io::Error),
-    OpenFailed2(i32, std::io::Error, String, std::io::Error),
+    OpenFailed(std::io::Error),
+    OpenFailed2(std::io::Error, String, std::io::Error),
 }
 
 impl std::error::Error for NohupError {}
 
 impl UError for NohupError {
     fn code(&self) -> i32 {
-        match self {
-            Self::OpenFailed(code, _) | Self::OpenFailed2(code, _, _, _) => *code,
-            _ => 2,
-        }
+        internal_failure_code()
     }
 }
 
@@ -66,10 +60,10 @@ impl Display for NohupError {
         match self {
             Self::CannotDetach => write!(f, "Cannot detach from console"),
             Self::CannotReplace(s, e) => write!(f, "Cannot replace {s}: {e}"),
-            Self::OpenFailed(_, e) => {
+            Self::OpenFailed(e) => {
                 write!(f, "failed to open {}: {}", NOHUP_OUT.quote(), e)
             }
-            Self::OpenFailed2(_, e1, s, e2) => write!(
+            Self::OpenFailed2(e1, s, e2) => write!(
                 f,
                 "failed to open {}: {}\nfailed to open {}: {}",
                 NOHUP_OUT.quote(),
@@ -85,7 +79,9 @@ impl Display for NohupError {
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_lossy();
 
-    let matches = uu_app().try_get_matches_from(args).with_exit_code(125)?;
+    let matches = uu_app()
+        .try_get_matches_from(args)
+        .with_exit_code(internal_failure_code())?;
 
     replace_fds()?;
 
@@ -95,20 +91,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         return Err(NohupError::CannotDetach.into());
     };
 
-    let cstrs: Vec<CString> = matches
-        .get_many::<String>(options::CMD)
-        .unwrap()
-        .map(|x| CString::new(x.as_bytes()).unwrap())
-        .collect();
-    let mut args: Vec<*const c_char> = cstrs.iter().map(|s| s.as_ptr()).collect();
-    args.push(std::ptr::null());
-
-    let ret = unsafe { execvp(args[0], args.as_mut_ptr()) };
-    match ret {
-        libc::ENOENT => set_exit_code(EXIT_ENOENT),
-        _ => set_exit_code(EXIT_CANNOT_INVOKE),
-    }
-    Ok(())
+    let mut command = matches.get_many::<String>(options::CMD).unwrap();
+    let program = command.next().unwrap();
+    Err(exec::run_error(
+        program.as_str(),
+        exec::exec(program, command),
+    ))
 }
 
 pub fn uu_app() -> Command {
@@ -152,12 +140,16 @@ fn replace_fds() -> UResult<()> {
     Ok(())
 }
 
-fn find_stdout() -> UResult<File> {
-    let internal_failure_code = match std::env::var("POSIXLY_CORRECT") {
-        Ok(_) => POSIX_NOHUP_FAILURE,
-        Err(_) => EXIT_CANCELED,
-    };
+/// The exit code when nohup itself fails.
+fn internal_failure_code() -> i32 {
+    if uucore::posix::posixly_correct() {
+        POSIX_NOHUP_FAILURE
+    } else {
+        POLICY.failure
+    }
+}
 
+fn find_stdout() -> UResult<File> {
     match OpenOptions::new()
         .write(true)
         .create(true)
@@ -173,7 +165,7 @@ fn find_stdout() -> UResult<File> {
         }
         Err(e1) => {
             let home = match env::var("HOME") {
-                Err(_) => return Err(NohupError::OpenFailed(internal_failure_code, e1).into()),
+                Err(_) => return Err(NohupError::OpenFailed(e1).into()),
                 Ok(h) => h,
             };
             let mut homeout = PathBuf::from(home);
@@ -192,13 +184,7 @@ fn find_stdout() -> UResult<File> {
                     );
                     Ok(t)
                 }
-                Err(e2) => Err(NohupError::OpenFailed2(
-                    internal_failure_code,
-                    e1,
-                    homeout_str.to_string(),
-                    e2,
-                )
-                .into()),
+                Err(e2) => Err(NohupError::OpenFailed2(e1, homeout_str.to_string(), e2).into()),
             }
         }
     }
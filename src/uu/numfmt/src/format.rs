@@ -1,5 +1,6 @@
 // spell-checker:ignore powf
 use uucore::display::Quotable;
+use uucore::parse_size::strict_suffixes;
 
 use crate::options::{NumfmtOptions, RoundMethod, TransformOptions};
 use crate::units::{DisplayableSuffix, RawSuffix, Result, Suffix, Unit, IEC_BASES, SI_BASES};
@@ -154,6 +155,15 @@ fn remove_suffix(i: f64, s: Option<Suffix>, u: &Unit) -> Result<f64> {
 
 fn transform_from(s: &str, opts: &TransformOptions) -> Result<f64> {
     let (i, suffix) = parse_suffix(s)?;
+    // A suffix without 'i' is a power of 1000 here, but of 1024 in the
+    // sizes given to other utilities.
+    if opts.from == Unit::Auto && matches!(suffix, Some((_, false))) && strict_suffixes() {
+        return Err(format!(
+            "ambiguous suffix in input: {}, which --from=auto takes as a power of 1000 \
+             (use --from=si, or the 'i' suffixes for powers of 1024)",
+            s.quote()
+        ));
+    }
     let i = i * (opts.from_unit as f64);
 
     remove_suffix(i, suffix, &opts.from).map(|n| {
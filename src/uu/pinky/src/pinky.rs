@@ -51,9 +51,7 @@ fn get_long_usage() -> String {
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_ignore();
 
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let users: Vec<String> = matches
         .get_many::<String>(options::USER)
@@ -127,6 +125,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .disable_help_flag(true)
         .arg(
@@ -272,13 +271,10 @@ fn gecos_to_fullname(pw: &Passwd) -> Option<String> {
 
 impl Pinky {
     fn print_entry(&self, ut: &Utmpx) -> std::io::Result<()> {
-        let mut pts_path = PathBuf::from("/dev");
-        pts_path.push(ut.tty_device().as_str());
-
         let mesg;
         let last_change;
 
-        match pts_path.metadata() {
+        match ut.tty_metadata() {
             #[allow(clippy::unnecessary_cast)]
             Ok(meta) => {
                 mesg = if meta.mode() & S_IWGRP as u32 != 0 {
@@ -0,0 +1,25 @@
+# The messages of rm, in English, which are the reference for the catalogs
+# in other languages. See uucore::i18n for the format.
+
+error-missing-operand = missing operand
+error-no-such-file = cannot remove { $path }: No such file or directory
+error-cannot-remove = cannot remove { $path }: { $error }
+error-permission-denied = cannot remove { $path }: Permission denied
+error-is-a-directory = cannot remove { $path }: Is a directory
+error-directory-not-empty = cannot remove { $path }: Directory not empty
+error-could-not-remove-directory = could not remove directory { $path }
+error-recursing = recursing in { $path }: { $error }
+error-returning = cannot return to { $path }: { $error }
+verbose-removed = removed { $path }
+verbose-removed-directory = removed directory { $path }
+prompt-remove-arguments = remove { $count } arguments?
+prompt-remove-arguments-recursively = remove { $count } arguments recursively?
+prompt-remove-one-argument-recursively = remove 1 argument recursively?
+prompt-remove-symlink = remove symbolic link { $path }?
+prompt-remove-file = remove file { $path }?
+prompt-remove-empty-file = remove regular empty file { $path }?
+prompt-remove-write-protected-file = remove write-protected regular file { $path }?
+prompt-remove-write-protected-empty-file = remove write-protected regular empty file { $path }?
+prompt-remove-directory = remove directory { $path }?
+prompt-remove-write-protected-directory = remove write-protected directory { $path }?
+prompt-descend = descend into directory { $path }?
@@ -0,0 +1,63 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! The progress bar of `rm --progress`.
+//!
+//! The files of the operands are counted before anything is removed, so
+//! that the bar can show how many are left. The files that are kept, or
+//! can't be removed, are never counted as removed, which is why a run can
+//! end short of the total.
+
+use std::path::Path;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use walkdir::WalkDir;
+
+pub(crate) struct Progress {
+    bar: ProgressBar,
+}
+
+impl Progress {
+    /// Count the files of `files` and show an empty bar for them.
+    pub(crate) fn new(files: &[String]) -> Self {
+        let total = files.iter().map(|file| count_files(Path::new(file))).sum();
+        let bar = ProgressBar::new(total)
+            .with_style(
+                ProgressStyle::with_template(
+                    "{prefix}: [{elapsed_precise}] {wide_bar} {pos}/{len} files removed",
+                )
+                .unwrap(),
+            )
+            .with_prefix(uucore::util_name());
+        bar.tick();
+        Self { bar }
+    }
+
+    /// Record that `n` more files have been removed.
+    pub(crate) fn inc(&self, n: u64) {
+        self.bar.inc(n);
+    }
+
+    /// Hide the bar while `f` writes to the terminal.
+    pub(crate) fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        // The bar is left on the screen, showing how far the run got.
+        self.bar.abandon();
+    }
+}
+
+/// The number of files of the tree at `path`, including itself, without
+/// following symbolic links.
+pub(crate) fn count_files(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(Result::ok)
+        .count() as u64
+}
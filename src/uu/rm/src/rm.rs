@@ -7,18 +7,37 @@
 
 // spell-checker:ignore (path) eacces
 
-use clap::{crate_version, parser::ValueSource, Arg, ArgAction, Command};
+use clap::builder::PossibleValue;
+use clap::{crate_version, parser::ValueSource, Arg, ArgAction, ArgMatches, Command};
+#[cfg(unix)]
+use nix::sys::stat::FileStat;
 use remove_dir_all::remove_dir_all;
+use std::cell::Cell;
+#[cfg(not(unix))]
 use std::collections::VecDeque;
 use std::fs::{self, File, Metadata};
-use std::io::ErrorKind;
+use std::io::{self, ErrorKind};
 use std::ops::BitOr;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
 use std::path::{Path, PathBuf};
 use uucore::display::Quotable;
-use uucore::error::{UResult, USimpleError, UUsageError};
-use uucore::{format_usage, prompt_yes, show_error};
+use uucore::error::{UResult, UUsageError};
+use uucore::fs::long_path;
+use uucore::report;
+use uucore::trash::trash;
+use uucore::{format_usage, prompt_yes, show_error, translate};
+#[cfg(not(unix))]
 use walkdir::{DirEntry, WalkDir};
 
+mod progress;
+#[cfg(unix)]
+mod unix;
+#[cfg(windows)]
+mod windows;
+
+use crate::progress::{count_files, Progress};
+
 #[derive(Eq, PartialEq, Clone, Copy)]
 enum InteractiveMode {
     Never,
@@ -36,6 +55,11 @@ struct Options {
     recursive: bool,
     dir: bool,
     verbose: bool,
+    trash: bool,
+    /// Whether a file couldn't be moved to the trash, which rm tells by its
+    /// exit status.
+    trash_failed: Cell<bool>,
+    progress: Option<Progress>,
 }
 
 static ABOUT: &str = "Remove (unlink) the FILE(s)";
@@ -46,9 +70,11 @@ static OPT_FORCE: &str = "force";
 static OPT_NO_PRESERVE_ROOT: &str = "no-preserve-root";
 static OPT_ONE_FILE_SYSTEM: &str = "one-file-system";
 static OPT_PRESERVE_ROOT: &str = "preserve-root";
+static OPT_PROGRESS: &str = "progress";
 static OPT_PROMPT: &str = "prompt";
 static OPT_PROMPT_MORE: &str = "prompt-more";
 static OPT_RECURSIVE: &str = "recursive";
+static OPT_TRASH: &str = "trash";
 static OPT_VERBOSE: &str = "verbose";
 static PRESUME_INPUT_TTY: &str = "-presume-input-tty";
 
@@ -73,87 +99,109 @@ fn get_long_usage() -> String {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let files: Vec<String> = matches
         .get_many::<String>(ARG_FILES)
         .map(|v| v.map(ToString::to_string).collect())
         .unwrap_or_default();
 
-    let force_flag = matches.get_flag(OPT_FORCE);
-
-    // If -f(--force) is before any -i (or variants) we want prompts else no prompts
-    let force_prompt_never: bool = force_flag && {
-        let force_index = matches.index_of(OPT_FORCE).unwrap_or(0);
-        ![OPT_PROMPT, OPT_PROMPT_MORE, OPT_INTERACTIVE]
-            .iter()
-            .any(|flag| {
-                matches.value_source(flag) == Some(ValueSource::CommandLine)
-                    && matches.index_of(flag).unwrap_or(0) > force_index
+    // The last of -f and the prompting options wins, like with GNU rm: -i,
+    // -I, --interactive=once and --interactive=always after -f prompt again
+    // and report the missing files, while --interactive=never still ignores
+    // them.
+    let mode = if matches.get_flag(OPT_PROMPT) {
+        Some(InteractiveMode::Always)
+    } else if matches.get_flag(OPT_PROMPT_MORE) {
+        Some(InteractiveMode::Once)
+    } else {
+        matches
+            .get_one::<String>(OPT_INTERACTIVE)
+            .map(|when| match when.as_str() {
+                "never" | "no" | "none" => InteractiveMode::Never,
+                "once" => InteractiveMode::Once,
+                _ => InteractiveMode::Always,
             })
     };
+    let force_flag = matches.get_flag(OPT_FORCE);
+    let (force, interactive) = match mode {
+        Some(mode) if !force_flag || last_prompt_option(&matches) > matches.index_of(OPT_FORCE) => {
+            (force_flag && mode == InteractiveMode::Never, mode)
+        }
+        _ if force_flag => (true, InteractiveMode::Never),
+        _ => (false, InteractiveMode::PromptProtected),
+    };
 
-    if files.is_empty() && !force_flag {
+    if files.is_empty() && !force {
         // Still check by hand and not use clap
         // Because "rm -f" is a thing
-        return Err(UUsageError::new(1, "missing operand"));
+        return Err(UUsageError::new(1, translate!("error-missing-operand")));
     } else {
-        let options = Options {
-            force: force_flag,
-            interactive: {
-                if force_prompt_never {
-                    InteractiveMode::Never
-                } else if matches.get_flag(OPT_PROMPT) {
-                    InteractiveMode::Always
-                } else if matches.get_flag(OPT_PROMPT_MORE) {
-                    InteractiveMode::Once
-                } else if matches.contains_id(OPT_INTERACTIVE) {
-                    match matches.get_one::<String>(OPT_INTERACTIVE).unwrap().as_str() {
-                        "never" => InteractiveMode::Never,
-                        "once" => InteractiveMode::Once,
-                        "always" => InteractiveMode::Always,
-                        val => {
-                            return Err(USimpleError::new(
-                                1,
-                                format!("Invalid argument to interactive ({val})"),
-                            ))
-                        }
-                    }
-                } else {
-                    InteractiveMode::PromptProtected
-                }
-            },
+        let mut options = Options {
+            force,
+            interactive,
             one_fs: matches.get_flag(OPT_ONE_FILE_SYSTEM),
             preserve_root: !matches.get_flag(OPT_NO_PRESERVE_ROOT),
             recursive: matches.get_flag(OPT_RECURSIVE),
             dir: matches.get_flag(OPT_DIR),
             verbose: matches.get_flag(OPT_VERBOSE),
+            trash: matches.get_flag(OPT_TRASH),
+            trash_failed: Cell::new(false),
+            progress: None,
         };
-        if options.interactive == InteractiveMode::Once && (options.recursive || files.len() > 3) {
-            let msg = if options.recursive {
-                "Remove all arguments recursively?"
-            } else {
-                "Remove all arguments?"
-            };
-            if !prompt_yes!("{}", msg) {
-                return Ok(());
-            }
+        if options.interactive == InteractiveMode::Once && !prompt_once(&files, &options) {
+            return Ok(());
+        }
+        if matches.get_flag(OPT_PROGRESS) {
+            options.progress = Some(Progress::new(&files));
         }
 
+        if let Some(format) = report::determine_report_format(&matches) {
+            report::enable(format);
+        }
         if remove(&files, &options) {
-            return Err(1.into());
+            let code = if options.trash_failed.get() { 2 } else { 1 };
+            return Err(code.into());
         }
     }
     Ok(())
 }
 
+/// Ask once whether to go on, for -I, when removing more than three files or
+/// removing recursively.
+///
+/// The operands are what is counted, not the files of their trees, like
+/// with GNU rm.
+fn prompt_once(files: &[String], options: &Options) -> bool {
+    let msg = if options.recursive {
+        if files.len() == 1 {
+            translate!("prompt-remove-one-argument-recursively")
+        } else {
+            translate!("prompt-remove-arguments-recursively", count = files.len())
+        }
+    } else if files.len() > 3 {
+        translate!("prompt-remove-arguments", count = files.len())
+    } else {
+        return true;
+    };
+    prompt_yes!("{}", msg)
+}
+
+/// The position of the prompting option given last on the command line.
+fn last_prompt_option(matches: &ArgMatches) -> Option<usize> {
+    [OPT_PROMPT, OPT_PROMPT_MORE, OPT_INTERACTIVE]
+        .iter()
+        .filter(|flag| matches.value_source(flag) == Some(ValueSource::CommandLine))
+        .filter_map(|flag| matches.index_of(flag))
+        .max()
+}
+
 pub fn uu_app() -> Command {
     Command::new(uucore::util_name())
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .args_override_self(true)
         .arg(
@@ -186,6 +234,15 @@ pub fn uu_app() -> Command {
                     prompts always",
                 )
                 .value_name("WHEN")
+                .require_equals(true)
+                .num_args(0..=1)
+                .default_missing_value("always")
+                .value_parser([
+                    PossibleValue::new("never").alias("no").alias("none"),
+                    PossibleValue::new("once"),
+                    PossibleValue::new("always").alias("yes"),
+                ])
+                .hide_possible_values(true)
                 .overrides_with_all([OPT_PROMPT, OPT_PROMPT_MORE]),
         )
         .arg(
@@ -231,6 +288,24 @@ pub fn uu_app() -> Command {
                 .help("explain what is being done")
                 .action(ArgAction::SetTrue),
         )
+        .arg(
+            // The 'g' short flag is the one of cp and mv
+            Arg::new(OPT_PROGRESS)
+                .long(OPT_PROGRESS)
+                .short('g')
+                .action(ArgAction::SetTrue)
+                .help(
+                    "Display a progress bar. \n\
+                Note: this feature is not supported by GNU coreutils.",
+                ),
+        )
+        .arg(
+            Arg::new(OPT_TRASH)
+                .long(OPT_TRASH)
+                .help("move files to the trash instead of removing them")
+                .conflicts_with(OPT_NO_PRESERVE_ROOT)
+                .action(ArgAction::SetTrue),
+        )
         // From the GNU source code:
         // This is solely for testing.
         // Do not document.
@@ -246,6 +321,7 @@ pub fn uu_app() -> Command {
                 .hide(true)
                 .action(ArgAction::SetTrue),
         )
+        .arg(report::arguments::report())
         .arg(
             Arg::new(ARG_FILES)
                 .action(ArgAction::Append)
@@ -259,8 +335,9 @@ fn remove(files: &[String], options: &Options) -> bool {
     let mut had_err = false;
 
     for filename in files {
+        report::start_operand(filename);
         let file = Path::new(filename);
-        had_err = match file.symlink_metadata() {
+        had_err = match long_path(file).symlink_metadata() {
             Ok(metadata) => {
                 if metadata.is_dir() {
                     handle_dir(file, options)
@@ -277,8 +354,8 @@ fn remove(files: &[String], options: &Options) -> bool {
                 // outputting the error, but there's no easy eay.
                 if !options.force {
                     show_error!(
-                        "cannot remove {}: No such file or directory",
-                        filename.quote()
+                        "{}",
+                        translate!("error-no-such-file", path = filename.quote())
                     );
                     true
                 } else {
@@ -297,121 +374,446 @@ fn handle_dir(path: &Path, options: &Options) -> bool {
 
     let is_root = path.has_root() && path.parent().is_none();
     if options.recursive && (!is_root || !options.preserve_root) {
-        if options.interactive != InteractiveMode::Always && !options.verbose {
-            // we need the extra crate because apparently fs::remove_dir_all() does not function
-            // correctly on Windows
-            if let Err(e) = remove_dir_all(path) {
+        if is_removed_whole(options) {
+            let files = match options.progress {
+                Some(_) => count_files(path),
+                None => 0,
+            };
+            if let Err(e) = remove_tree(path, options) {
                 had_err = true;
                 if e.kind() == std::io::ErrorKind::PermissionDenied {
                     // GNU compatibility (rm/fail-eacces.sh)
                     // here, GNU doesn't use some kind of remove_dir_all
                     // It will show directory+file
-                    show_error!("cannot remove {}: {}", path.quote(), "Permission denied");
+                    show_error!(
+                        "{}",
+                        translate!("error-permission-denied", path = path.quote())
+                    );
                 } else {
-                    show_error!("cannot remove {}: {}", path.quote(), e);
+                    show_error!(
+                        "{}",
+                        translate!("error-cannot-remove", path = path.quote(), error = e)
+                    );
+                }
+            } else {
+                if let Some(progress) = &options.progress {
+                    progress.inc(files.saturating_sub(1));
                 }
+                report_removed(path, true, options);
             }
         } else {
-            let mut dirs: VecDeque<DirEntry> = VecDeque::new();
-            // The Paths to not descend into. We need to this because WalkDir doesn't have a way, afaik, to not descend into a directory
-            // So we have to just ignore paths as they come up if they start with a path we aren't descending into
-            let mut not_descended: Vec<PathBuf> = Vec::new();
-
-            'outer: for entry in WalkDir::new(path) {
-                match entry {
-                    Ok(entry) => {
-                        if options.interactive == InteractiveMode::Always {
-                            for not_descend in &not_descended {
-                                if entry.path().starts_with(not_descend) {
-                                    // We don't need to continue the rest of code in this loop if we are in a directory we don't want to descend into
-                                    continue 'outer;
-                                }
-                            }
+            had_err = remove_dir_recursive(path, options).bitor(had_err);
+        }
+    } else if options.dir && (!is_root || !options.preserve_root) {
+        had_err = remove_dir(path, options).bitor(had_err);
+    } else if options.recursive {
+        show_error!(
+            "{}",
+            translate!("error-could-not-remove-directory", path = path.quote())
+        );
+        had_err = true;
+    } else {
+        // GNU's rm error message does not include help
+        show_error!(
+            "{}",
+            translate!("error-is-a-directory", path = path.quote())
+        );
+        had_err = true;
+    }
+
+    had_err
+}
+
+/// Whether the tree of a directory operand is removed in one step, rather
+/// than file by file with [`remove_dir_recursive()`].
+#[cfg(not(unix))]
+fn is_removed_whole(options: &Options) -> bool {
+    // A tree moved to the trash is moved as a whole.
+    options.interactive != InteractiveMode::Always && (!options.verbose || options.trash)
+}
+
+#[cfg(unix)]
+fn is_removed_whole(options: &Options) -> bool {
+    // A tree moved to the trash is moved as a whole. The others are walked
+    // through the descriptors of their directories, see the unix module.
+    options.trash && options.interactive != InteractiveMode::Always
+}
+
+/// Remove the directory at `path` with its contents, file by file, to
+/// prompt for them or print them. Return whether there was an error.
+#[cfg(not(unix))]
+fn remove_dir_recursive(path: &Path, options: &Options) -> bool {
+    let mut had_err = false;
+    let mut dirs: VecDeque<DirEntry> = VecDeque::new();
+    // The Paths to not descend into. We need to this because WalkDir doesn't have a way, afaik, to not descend into a directory
+    // So we have to just ignore paths as they come up if they start with a path we aren't descending into
+    let mut not_descended: Vec<PathBuf> = Vec::new();
+
+    'outer: for entry in WalkDir::new(path) {
+        match entry {
+            Ok(entry) => {
+                if options.interactive == InteractiveMode::Always {
+                    for not_descend in &not_descended {
+                        if entry.path().starts_with(not_descend) {
+                            // We don't need to continue the rest of code in this loop if we are in a directory we don't want to descend into
+                            continue 'outer;
                         }
-                        let file_type = entry.file_type();
-                        if file_type.is_dir() {
-                            // If we are in Interactive Mode Always and the directory isn't empty we ask if we should descend else we push this directory onto dirs vector
-                            if options.interactive == InteractiveMode::Always
-                                && fs::read_dir(entry.path()).unwrap().count() != 0
-                            {
-                                // If we don't descend we push this directory onto our not_descended vector else we push this directory onto dirs vector
-                                if prompt_descend(entry.path()) {
-                                    dirs.push_back(entry);
-                                } else {
-                                    not_descended.push(entry.path().to_path_buf());
-                                }
-                            } else {
-                                dirs.push_back(entry);
-                            }
+                    }
+                }
+                let file_type = entry.file_type();
+                if file_type.is_dir() {
+                    // If we are in Interactive Mode Always and the directory isn't empty we ask if we should descend else we push this directory onto dirs vector
+                    if options.interactive == InteractiveMode::Always
+                        && fs::read_dir(entry.path()).unwrap().count() != 0
+                    {
+                        // If we don't descend we push this directory onto our not_descended vector else we push this directory onto dirs vector
+                        if prompt_descend(entry.path(), options) {
+                            dirs.push_back(entry);
                         } else {
-                            had_err = remove_file(entry.path(), options).bitor(had_err);
+                            not_descended.push(entry.path().to_path_buf());
                         }
+                    } else {
+                        dirs.push_back(entry);
                     }
-                    Err(e) => {
-                        had_err = true;
-                        show_error!("recursing in {}: {}", path.quote(), e);
-                    }
+                } else {
+                    had_err = remove_file(entry.path(), options).bitor(had_err);
                 }
             }
+            Err(e) => {
+                had_err = true;
+                show_error!(
+                    "{}",
+                    translate!("error-recursing", path = path.quote(), error = e)
+                );
+            }
+        }
+    }
+
+    for dir in dirs.iter().rev() {
+        had_err = remove_dir(dir.path(), options).bitor(had_err);
+    }
+
+    had_err
+}
+
+/// The outcome of the removal of a file of a tree.
+#[cfg(unix)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Removed,
+    /// The user declined to remove it or one of the files below it.
+    Kept,
+    Failed,
+}
+
+#[cfg(unix)]
+fn remove_dir_recursive(path: &Path, options: &Options) -> bool {
+    match unix::stat_at(unix::CWD, path) {
+        Ok(stat) => remove_tree_at(unix::CWD, path, path, &stat, options) == Outcome::Failed,
+        Err(e) => {
+            show_error!(
+                "{}",
+                translate!("error-cannot-remove", path = path.quote(), error = e)
+            );
+            true
+        }
+    }
+}
+
+/// The number of directories of a tree that are kept open while it is
+/// walked. The ancestors further up are closed, and reopened through `..`
+/// when the walk returns to them, so that deep trees don't run out of file
+/// descriptors.
+#[cfg(unix)]
+const MAX_OPEN_DIRS: usize = 32;
 
-            for dir in dirs.iter().rev() {
-                had_err = remove_dir(dir.path(), options).bitor(had_err);
+/// A directory of a tree being removed, with the names it has left to visit.
+#[cfg(unix)]
+struct Frame {
+    /// The open directory, or `None` when it was closed to bound the number
+    /// of open directories.
+    dir: Option<nix::dir::Dir>,
+    /// Its name in its parent.
+    name: PathBuf,
+    path: PathBuf,
+    stat: FileStat,
+    names: std::vec::IntoIter<std::ffi::OsString>,
+    /// The outcome of the removal of the entries visited so far.
+    outcome: Outcome,
+}
+
+#[cfg(unix)]
+impl Frame {
+    fn fd(&self) -> RawFd {
+        self.dir.as_ref().map_or(-1, |dir| dir.as_raw_fd())
+    }
+
+    fn add(&mut self, outcome: Outcome) {
+        if outcome == Outcome::Failed || self.outcome == Outcome::Removed {
+            self.outcome = outcome;
+        }
+    }
+}
+
+/// Open the directory `name` of the directory `dirfd`, which is at `path` and
+/// has the status `stat`, and read its entries, prompting to descend into it.
+///
+/// When the directory isn't walked, the error is the outcome of its removal.
+#[cfg(unix)]
+fn enter_dir(
+    dirfd: RawFd,
+    name: PathBuf,
+    path: PathBuf,
+    stat: FileStat,
+    options: &Options,
+) -> Result<Frame, Outcome> {
+    let mut dir = match unix::open_dir(dirfd, &name) {
+        Ok(dir) => dir,
+        // An empty directory can be removed even when it can't be read.
+        Err(e) => return Err(remove_at(dirfd, &name, &path, &stat, options, Some(e))),
+    };
+    let names = match unix::read_names(&mut dir) {
+        Ok(names) => names,
+        Err(e) => {
+            show_error!(
+                "{}",
+                translate!("error-recursing", path = path.quote(), error = e)
+            );
+            return Err(Outcome::Failed);
+        }
+    };
+
+    if options.interactive == InteractiveMode::Always
+        && !names.is_empty()
+        && !prompt_descend(&path, options)
+    {
+        return Err(Outcome::Kept);
+    }
+
+    Ok(Frame {
+        dir: Some(dir),
+        name,
+        path,
+        stat,
+        names: names.into_iter(),
+        outcome: Outcome::Removed,
+    })
+}
+
+/// Remove the directory `name` of the directory `dirfd`, which is at `path`
+/// and has the status `stat`, with its contents.
+///
+/// Like with GNU rm, a directory is only removed when all its contents were,
+/// so the failures to remove a file aren't reported again for its ancestors.
+///
+/// The tree is walked with a stack rather than by recursion, and only the
+/// last [`MAX_OPEN_DIRS`] directories of the stack are kept open. Like `fts`,
+/// a closed directory is reopened through the `..` of its child, and the walk
+/// stops if that isn't the same directory anymore.
+#[cfg(unix)]
+fn remove_tree_at(
+    dirfd: RawFd,
+    name: &Path,
+    path: &Path,
+    stat: &FileStat,
+    options: &Options,
+) -> Outcome {
+    let mut stack = match enter_dir(dirfd, name.into(), path.into(), *stat, options) {
+        Ok(frame) => vec![frame],
+        Err(outcome) => return outcome,
+    };
+
+    loop {
+        let top = stack.last_mut().unwrap();
+        if let Some(entry_name) = top.names.next() {
+            let entry_name = PathBuf::from(entry_name);
+            let entry_path = top.path.join(&entry_name);
+            let entry_outcome = match unix::stat_at(top.fd(), &entry_name) {
+                Ok(entry_stat) if unix::is_dir(&entry_stat) => {
+                    match enter_dir(top.fd(), entry_name, entry_path, entry_stat, options) {
+                        Ok(frame) => {
+                            stack.push(frame);
+                            let depth = stack.len();
+                            if depth > MAX_OPEN_DIRS {
+                                stack[depth - 1 - MAX_OPEN_DIRS].dir = None;
+                            }
+                            continue;
+                        }
+                        Err(outcome) => outcome,
+                    }
+                }
+                Ok(entry_stat) => remove_at(
+                    top.fd(),
+                    &entry_name,
+                    &entry_path,
+                    &entry_stat,
+                    options,
+                    None,
+                ),
+                // Removed in the meantime.
+                Err(e) if e.kind() == ErrorKind::NotFound => Outcome::Removed,
+                Err(e) => {
+                    show_cannot_remove(&entry_path, e);
+                    Outcome::Failed
+                }
+            };
+            top.add(entry_outcome);
+            continue;
+        }
+
+        let frame = stack.pop().unwrap();
+        let parent = match stack.last_mut() {
+            Some(parent) => parent,
+            None => {
+                drop(frame.dir);
+                return match frame.outcome {
+                    Outcome::Removed => remove_at(dirfd, name, path, stat, options, None),
+                    outcome => outcome,
+                };
+            }
+        };
+        if parent.dir.is_none() {
+            match unix::open_parent(frame.dir.as_ref().unwrap(), &parent.stat) {
+                Ok(dir) => parent.dir = Some(dir),
+                Err(e) => {
+                    show_error!(
+                        "{}",
+                        translate!("error-returning", path = parent.path.quote(), error = e)
+                    );
+                    return Outcome::Failed;
+                }
             }
         }
-    } else if options.dir && (!is_root || !options.preserve_root) {
-        had_err = remove_dir(path, options).bitor(had_err);
-    } else if options.recursive {
-        show_error!("could not remove directory {}", path.quote());
-        had_err = true;
+        drop(frame.dir);
+        let outcome = match frame.outcome {
+            Outcome::Removed => remove_at(
+                parent.fd(),
+                &frame.name,
+                &frame.path,
+                &frame.stat,
+                options,
+                None,
+            ),
+            outcome => outcome,
+        };
+        parent.add(outcome);
+    }
+}
+
+/// Remove the file or empty directory `name` of the directory `dirfd`, which
+/// is at `path` and has the status `stat`, prompting for it if needed.
+///
+/// `read_error` is the error that kept a directory from being read, which is
+/// reported rather than the failure to remove it.
+#[cfg(unix)]
+fn remove_at(
+    dirfd: RawFd,
+    name: &Path,
+    path: &Path,
+    stat: &FileStat,
+    options: &Options,
+    read_error: Option<io::Error>,
+) -> Outcome {
+    let is_dir = unix::is_dir(stat);
+    if options.interactive != InteractiveMode::Never {
+        let info = PromptInfo {
+            is_symlink: unix::is_symlink(stat),
+            is_dir,
+            is_empty: stat.st_size == 0,
+            write_protected: unix::is_write_protected(dirfd, name, stat),
+        };
+        if !prompt(path, options, &info) {
+            return Outcome::Kept;
+        }
+    }
+
+    let result = if options.trash {
+        move_to_trash(path, options)
+    } else {
+        unix::unlink_at(dirfd, name, is_dir)
+    };
+    match result {
+        Ok(()) => {
+            report_removed(path, is_dir, options);
+            Outcome::Removed
+        }
+        Err(e) => {
+            show_cannot_remove(path, read_error.unwrap_or(e));
+            Outcome::Failed
+        }
+    }
+}
+
+#[cfg(unix)]
+fn show_cannot_remove(path: &Path, error: io::Error) {
+    if error.kind() == ErrorKind::PermissionDenied {
+        // GNU compatibility (rm/fail-eacces.sh)
+        show_error!(
+            "{}",
+            translate!("error-permission-denied", path = path.quote())
+        );
+    } else if error.raw_os_error() == Some(libc::ENOTEMPTY) {
+        show_error!(
+            "{}",
+            translate!("error-directory-not-empty", path = path.quote())
+        );
     } else {
         show_error!(
-            "cannot remove {}: Is a directory", // GNU's rm error message does not include help
-            path.quote()
+            "{}",
+            translate!("error-cannot-remove", path = path.quote(), error = error)
         );
-        had_err = true;
     }
-
-    had_err
 }
 
 fn remove_dir(path: &Path, options: &Options) -> bool {
     if prompt_file(path, options, true) {
-        if let Ok(mut read_dir) = fs::read_dir(path) {
+        if let Ok(mut read_dir) = fs::read_dir(long_path(path)) {
             if options.dir || options.recursive {
                 if read_dir.next().is_none() {
-                    match fs::remove_dir(path) {
-                        Ok(_) => {
-                            if options.verbose {
-                                println!("removed directory {}", normalize(path).quote());
-                            }
-                        }
+                    match rmdir(path, options) {
+                        Ok(_) => report_removed(path, true, options),
                         Err(e) => {
                             if e.kind() == std::io::ErrorKind::PermissionDenied {
                                 // GNU compatibility (rm/fail-eacces.sh)
                                 show_error!(
-                                    "cannot remove {}: {}",
-                                    path.quote(),
-                                    "Permission denied"
+                                    "{}",
+                                    translate!("error-permission-denied", path = path.quote())
                                 );
                             } else {
-                                show_error!("cannot remove {}: {}", path.quote(), e);
+                                show_error!(
+                                    "{}",
+                                    translate!(
+                                        "error-cannot-remove",
+                                        path = path.quote(),
+                                        error = e
+                                    )
+                                );
                             }
                             return true;
                         }
                     }
                 } else {
                     // directory can be read but is not empty
-                    show_error!("cannot remove {}: Directory not empty", path.quote());
+                    show_error!(
+                        "{}",
+                        translate!("error-directory-not-empty", path = path.quote())
+                    );
                     return true;
                 }
             } else {
                 // called to remove a symlink_dir (windows) without "-r"/"-R" or "-d"
-                show_error!("cannot remove {}: Is a directory", path.quote());
+                show_error!(
+                    "{}",
+                    translate!("error-is-a-directory", path = path.quote())
+                );
                 return true;
             }
         } else {
             // GNU's rm shows this message if directory is empty but not readable
-            show_error!("cannot remove {}: Directory not empty", path.quote());
+            show_error!(
+                "{}",
+                translate!("error-directory-not-empty", path = path.quote())
+            );
             return true;
         }
     }
@@ -419,20 +821,43 @@ fn remove_dir(path: &Path, options: &Options) -> bool {
     false
 }
 
+/// Print that the file or directory at `path` was removed with `--verbose`,
+/// and count it in the progress bar.
+fn report_removed(path: &Path, is_dir: bool, options: &Options) {
+    if options.verbose {
+        let path = normalize(path);
+        let message = if is_dir {
+            translate!("verbose-removed-directory", path = path.quote())
+        } else {
+            translate!("verbose-removed", path = path.quote())
+        };
+        match &options.progress {
+            // Hide the bar so that it doesn't overlap with the message.
+            Some(progress) => progress.suspend(|| println!("{message}")),
+            None => println!("{message}"),
+        }
+    }
+    if let Some(progress) = &options.progress {
+        progress.inc(1);
+    }
+}
+
 fn remove_file(path: &Path, options: &Options) -> bool {
     if prompt_file(path, options, false) {
-        match fs::remove_file(path) {
-            Ok(_) => {
-                if options.verbose {
-                    println!("removed {}", normalize(path).quote());
-                }
-            }
+        match unlink(path, options) {
+            Ok(_) => report_removed(path, false, options),
             Err(e) => {
                 if e.kind() == std::io::ErrorKind::PermissionDenied {
                     // GNU compatibility (rm/fail-eacces.sh)
-                    show_error!("cannot remove {}: {}", path.quote(), "Permission denied");
+                    show_error!(
+                        "{}",
+                        translate!("error-permission-denied", path = path.quote())
+                    );
                 } else {
-                    show_error!("cannot remove {}: {}", path.quote(), e);
+                    show_error!(
+                        "{}",
+                        translate!("error-cannot-remove", path = path.quote(), error = e)
+                    );
                 }
                 return true;
             }
@@ -442,123 +867,201 @@ fn remove_file(path: &Path, options: &Options) -> bool {
     false
 }
 
+/// Move `path` to the trash, and remember if it can't be.
+fn move_to_trash(path: &Path, options: &Options) -> io::Result<()> {
+    let result = trash(path);
+    if result.is_err() {
+        options.trash_failed.set(true);
+    }
+    result
+}
+
+/// Remove the directory at `path` with all its contents, or move it to the
+/// trash with `--trash`.
+#[cfg(windows)]
+fn remove_tree(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        // we need the extra crate because apparently fs::remove_dir_all() does not function
+        // correctly on Windows
+        windows::retry(|| remove_dir_all(long_path(path)))
+    }
+}
+
+#[cfg(not(windows))]
+fn remove_tree(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        remove_dir_all(long_path(path))
+    }
+}
+
+/// Remove the empty directory at `path`, or move it to the trash with
+/// `--trash`.
+#[cfg(windows)]
+fn rmdir(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        windows::remove_dir(&long_path(path), options.force)
+    }
+}
+
+#[cfg(not(windows))]
+fn rmdir(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        fs::remove_dir(long_path(path))
+    }
+}
+
+/// Remove the file at `path`, or move it to the trash with `--trash`.
+#[cfg(windows)]
+fn unlink(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        windows::remove_file(&long_path(path), options.force)
+    }
+}
+
+#[cfg(not(windows))]
+fn unlink(path: &Path, options: &Options) -> io::Result<()> {
+    if options.trash {
+        move_to_trash(path, options)
+    } else {
+        fs::remove_file(long_path(path))
+    }
+}
+
+/// What the prompts tell about a file.
+struct PromptInfo {
+    is_symlink: bool,
+    is_dir: bool,
+    is_empty: bool,
+    write_protected: bool,
+}
+
 fn prompt_file(path: &Path, options: &Options, is_dir: bool) -> bool {
     // If interactive is Never we never want to send prompts
     if options.interactive == InteractiveMode::Never {
         return true;
     }
-    // If interactive is Always we want to check if the file is symlink to prompt the right message
-    if options.interactive == InteractiveMode::Always {
-        if let Ok(metadata) = fs::symlink_metadata(path) {
-            if metadata.is_symlink() {
-                return prompt_yes!("remove symbolic link {}?", path.quote());
-            }
-        }
-    }
-    if is_dir {
+    let is_symlink = fs::symlink_metadata(path).map_or(false, |metadata| metadata.is_symlink());
+    let (is_empty, write_protected) = if is_dir {
         // We can't use metadata.permissions.readonly for directories because it only works on files
         // So we have to handle wether a directory is writable on not manually
-        if let Ok(metadata) = fs::metadata(path) {
-            handle_writable_directory(path, options, &metadata)
-        } else {
-            true
-        }
+        let write_protected =
+            fs::metadata(path).map_or(false, |metadata| is_write_protected_directory(&metadata));
+        (false, write_protected)
     } else {
         // File::open(path) doesn't open the file in write mode so we need to use file options to open it in also write mode to check if it can written too
         match File::options().read(true).write(true).open(path) {
-            Ok(file) => {
-                if let Ok(metadata) = file.metadata() {
-                    if metadata.permissions().readonly() {
-                        if metadata.len() == 0 {
-                            prompt_yes!(
-                                "remove write-protected regular empty file {}?",
-                                path.quote()
-                            )
-                        } else {
-                            prompt_yes!("remove write-protected regular file {}?", path.quote())
-                        }
-                    } else if options.interactive == InteractiveMode::Always {
-                        if metadata.len() == 0 {
-                            prompt_yes!("remove regular empty file {}?", path.quote())
-                        } else {
-                            prompt_yes!("remove file {}?", path.quote())
-                        }
-                    } else {
-                        true
-                    }
-                } else {
-                    true
-                }
-            }
-            Err(err) => {
-                if err.kind() == ErrorKind::PermissionDenied {
-                    if let Ok(metadata) = fs::metadata(path) {
-                        if metadata.len() == 0 {
-                            prompt_yes!(
-                                "remove write-protected regular empty file {}?",
-                                path.quote()
-                            )
-                        } else {
-                            prompt_yes!("remove write-protected regular file {}?", path.quote())
-                        }
-                    } else {
-                        prompt_yes!("remove write-protected regular file {}?", path.quote())
-                    }
-                } else {
-                    true
-                }
+            Ok(file) => file.metadata().map_or((false, false), |metadata| {
+                (metadata.len() == 0, metadata.permissions().readonly())
+            }),
+            Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+                let is_empty = fs::metadata(path).map_or(false, |metadata| metadata.len() == 0);
+                (is_empty, true)
             }
+            Err(_) => (false, false),
+        }
+    };
+    prompt(
+        path,
+        options,
+        &PromptInfo {
+            is_symlink,
+            is_dir,
+            is_empty,
+            write_protected,
+        },
+    )
+}
+
+/// Ask whether to remove the file at `path`, if `options` and `info` call
+/// for it.
+fn prompt(path: &Path, options: &Options, info: &PromptInfo) -> bool {
+    let always = options.interactive == InteractiveMode::Always;
+    let message = if info.is_symlink {
+        // A symbolic link is never write-protected.
+        if !always {
+            return true;
+        }
+        translate!("prompt-remove-symlink", path = path.quote())
+    } else if info.is_dir {
+        if info.write_protected {
+            translate!(
+                "prompt-remove-write-protected-directory",
+                path = path.quote()
+            )
+        } else if always {
+            translate!("prompt-remove-directory", path = path.quote())
+        } else {
+            return true;
         }
+    } else if info.write_protected {
+        if info.is_empty {
+            translate!(
+                "prompt-remove-write-protected-empty-file",
+                path = path.quote()
+            )
+        } else {
+            translate!("prompt-remove-write-protected-file", path = path.quote())
+        }
+    } else if always {
+        if info.is_empty {
+            translate!("prompt-remove-empty-file", path = path.quote())
+        } else {
+            translate!("prompt-remove-file", path = path.quote())
+        }
+    } else {
+        return true;
+    };
+    ask(&message, options)
+}
+
+/// Ask the user `question`, hiding the progress bar meanwhile.
+fn ask(question: &str, options: &Options) -> bool {
+    match &options.progress {
+        Some(progress) => progress.suspend(|| prompt_yes!("{}", question)),
+        None => prompt_yes!("{}", question),
     }
 }
 
 // For directories finding if they are writable or not is a hassle. In Unix we can use the built-in rust crate to to check mode bits. But other os don't have something similar afaik
 // Most cases are covered by keep eye out for edge cases
 #[cfg(unix)]
-fn handle_writable_directory(path: &Path, options: &Options, metadata: &Metadata) -> bool {
+fn is_write_protected_directory(metadata: &Metadata) -> bool {
     use std::os::unix::fs::PermissionsExt;
     let mode = metadata.permissions().mode();
     // Check if directory has user write permissions
     // Why is S_IWUSR showing up as a u16 on macos?
     #[allow(clippy::unnecessary_cast)]
     let user_writable = (mode & (libc::S_IWUSR as u32)) != 0;
-    if !user_writable {
-        prompt_yes!("remove write-protected directory {}?", path.quote())
-    } else if options.interactive == InteractiveMode::Always {
-        prompt_yes!("remove directory {}?", path.quote())
-    } else {
-        true
-    }
+    !user_writable
 }
 
 // For windows we can use windows metadata trait and file attributes to see if a directory is readonly
 #[cfg(windows)]
-fn handle_writable_directory(path: &Path, options: &Options, metadata: &Metadata) -> bool {
+fn is_write_protected_directory(metadata: &Metadata) -> bool {
     use std::os::windows::prelude::MetadataExt;
     use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_READONLY;
-    let not_user_writable = (metadata.file_attributes() & FILE_ATTRIBUTE_READONLY) != 0;
-    if not_user_writable {
-        prompt_yes!("remove write-protected directory {}?", path.quote())
-    } else if options.interactive == InteractiveMode::Always {
-        prompt_yes!("remove directory {}?", path.quote())
-    } else {
-        true
-    }
+    (metadata.file_attributes() & FILE_ATTRIBUTE_READONLY) != 0
 }
 
 // I have this here for completeness but it will always return "remove directory {}" because metadata.permissions().readonly() only works for file not directories
 #[cfg(not(windows))]
 #[cfg(not(unix))]
-fn handle_writable_directory(path: &Path, options: &Options, metadata: &Metadata) -> bool {
-    if options.interactive == InteractiveMode::Always {
-        prompt_yes!("remove directory {}?", path.quote())
-    } else {
-        true
-    }
+fn is_write_protected_directory(_metadata: &Metadata) -> bool {
+    false
 }
 
-fn prompt_descend(path: &Path) -> bool {
-    prompt_yes!("descend into directory {}?", path.quote())
+fn prompt_descend(path: &Path, options: &Options) -> bool {
+    ask(&translate!("prompt-descend", path = path.quote()), options)
 }
 
 fn normalize(path: &Path) -> PathBuf {
@@ -0,0 +1,111 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Removing files on Unix.
+//!
+//! The trees are walked through the file descriptors of their directories:
+//! each file is looked up, checked and removed relative to the directory it
+//! was read from, with `fstatat`, `faccessat` and `unlinkat`. Replacing a
+//! directory with a symbolic link while it is being removed then can't make
+//! `rm` remove files elsewhere, and the trees can be deeper than `PATH_MAX`.
+
+// spell-checker:ignore (libc) fstatat faccessat unlinkat EACCES IWUSR
+
+use std::ffi::{CString, OsStr, OsString};
+use std::io;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::Path;
+
+use nix::dir::Dir;
+use nix::fcntl::{AtFlags, OFlag};
+use nix::sys::stat::{fstat, fstatat, FileStat, Mode, SFlag};
+use nix::unistd::{unlinkat, UnlinkatFlags};
+
+/// The directory that the paths of the operands are relative to.
+pub const CWD: RawFd = libc::AT_FDCWD;
+
+/// Open the directory `name` of the directory `dirfd`, without following
+/// it if it is a symbolic link.
+pub fn open_dir(dirfd: RawFd, name: &Path) -> io::Result<Dir> {
+    let flags = OFlag::O_RDONLY | OFlag::O_DIRECTORY | OFlag::O_NOFOLLOW | OFlag::O_CLOEXEC;
+    Ok(Dir::openat(dirfd, name, flags, Mode::empty())?)
+}
+
+/// Open the parent of the open directory `dir`, checking that it is still the
+/// directory with the status `stat`, like `fts` does when it returns to a
+/// directory it left.
+pub fn open_parent(dir: &Dir, stat: &FileStat) -> io::Result<Dir> {
+    let parent = open_dir(dir.as_raw_fd(), Path::new(".."))?;
+    let parent_stat = fstat(parent.as_raw_fd())?;
+    if parent_stat.st_dev != stat.st_dev || parent_stat.st_ino != stat.st_ino {
+        return Err(io::Error::from_raw_os_error(libc::ENOENT));
+    }
+    Ok(parent)
+}
+
+/// The names of the entries of `dir`, without `.` and `..`.
+pub fn read_names(dir: &mut Dir) -> io::Result<Vec<OsString>> {
+    let mut names = vec![];
+    for entry in dir.iter() {
+        let name = OsStr::from_bytes(entry?.file_name().to_bytes()).to_os_string();
+        if name != "." && name != ".." {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
+
+/// The status of the file `name` of the directory `dirfd`, not following
+/// symbolic links.
+pub fn stat_at(dirfd: RawFd, name: &Path) -> io::Result<FileStat> {
+    Ok(fstatat(dirfd, name, AtFlags::AT_SYMLINK_NOFOLLOW)?)
+}
+
+fn file_type(stat: &FileStat) -> SFlag {
+    SFlag::from_bits_truncate(stat.st_mode) & SFlag::S_IFMT
+}
+
+pub fn is_dir(stat: &FileStat) -> bool {
+    file_type(stat) == SFlag::S_IFDIR
+}
+
+pub fn is_symlink(stat: &FileStat) -> bool {
+    file_type(stat) == SFlag::S_IFLNK
+}
+
+/// Whether the user is asked about removing the file `name` of `dirfd`,
+/// whose status is `stat`, as write-protected.
+///
+/// Like with the paths, a file is when it has no write permission bits or
+/// the user can't write it, and a directory when its owner can't.
+pub fn is_write_protected(dirfd: RawFd, name: &Path, stat: &FileStat) -> bool {
+    let mode = stat.st_mode as libc::mode_t;
+    if is_dir(stat) {
+        return mode & libc::S_IWUSR == 0;
+    }
+    mode & (libc::S_IWUSR | libc::S_IWGRP | libc::S_IWOTH) == 0 || !is_writable(dirfd, name)
+}
+
+fn is_writable(dirfd: RawFd, name: &Path) -> bool {
+    let name = match CString::new(name.as_os_str().as_bytes()) {
+        Ok(name) => name,
+        Err(_) => return true,
+    };
+    // SAFETY: `name` is a valid C string.
+    let result = unsafe { libc::faccessat(dirfd, name.as_ptr(), libc::W_OK, libc::AT_EACCESS) };
+    result == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::EACCES)
+}
+
+/// Remove the file or, with `is_dir`, the empty directory `name` of the
+/// directory `dirfd`.
+pub fn unlink_at(dirfd: RawFd, name: &Path, is_dir: bool) -> io::Result<()> {
+    let flags = if is_dir {
+        UnlinkatFlags::RemoveDir
+    } else {
+        UnlinkatFlags::NoRemoveDir
+    };
+    Ok(unlinkat(Some(dirfd), name, flags)?)
+}
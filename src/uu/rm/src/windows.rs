@@ -0,0 +1,93 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Removing files on Windows.
+//!
+//! Windows refuses to delete read-only files, and fails to delete files
+//! that another process has open, which virus scanners and search indexers
+//! briefly do all the time. The latter is retried for a little while.
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use windows_sys::Win32::Foundation::{
+    ERROR_ACCESS_DENIED, ERROR_DIR_NOT_EMPTY, ERROR_LOCK_VIOLATION, ERROR_SHARING_VIOLATION,
+};
+
+/// How many times an operation is retried while the file is in use.
+const RETRIES: u32 = 5;
+
+/// The delay before the first retry, which doubles for each one after it.
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+/// Run `f`, retrying it with an exponential backoff for as long as it fails
+/// because of a transient condition, see [`is_transient`].
+pub fn retry<T>(mut f: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut delay = FIRST_RETRY_DELAY;
+    for _ in 0..RETRIES {
+        match f() {
+            Err(e) if is_transient(&e) => {
+                thread::sleep(delay);
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+    f()
+}
+
+/// Whether `e` is likely to go away by itself: the file is in use by
+/// another process, or a directory still holds files that are being
+/// deleted but are open elsewhere.
+fn is_transient(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error().map(|code| code as u32),
+        Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION | ERROR_DIR_NOT_EMPTY)
+    )
+}
+
+/// Remove the file at `path`. With `force`, a read-only file has its
+/// read-only attribute cleared so that it can be removed.
+pub fn remove_file(path: &Path, force: bool) -> io::Result<()> {
+    retry(|| fs::remove_file(path)).or_else(|e| {
+        if force && is_access_denied(&e) && clear_readonly(path)? {
+            retry(|| fs::remove_file(path))
+        } else {
+            Err(e)
+        }
+    })
+}
+
+/// Remove the empty directory at `path`. With `force`, a read-only
+/// directory has its read-only attribute cleared so that it can be removed.
+pub fn remove_dir(path: &Path, force: bool) -> io::Result<()> {
+    retry(|| fs::remove_dir(path)).or_else(|e| {
+        if force && is_access_denied(&e) && clear_readonly(path)? {
+            retry(|| fs::remove_dir(path))
+        } else {
+            Err(e)
+        }
+    })
+}
+
+fn is_access_denied(e: &io::Error) -> bool {
+    e.raw_os_error() == Some(ERROR_ACCESS_DENIED as i32)
+}
+
+/// Clear the read-only attribute of `path`, returning whether it was set.
+/// The attribute of symbolic links is left alone, as it can only be
+/// changed through the file they point to.
+fn clear_readonly(path: &Path) -> io::Result<bool> {
+    let metadata = fs::symlink_metadata(path)?;
+    let mut permissions = metadata.permissions();
+    if metadata.is_symlink() || !permissions.readonly() {
+        return Ok(false);
+    }
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)?;
+    Ok(true)
+}
@@ -10,10 +10,9 @@ pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 // This list is NOT exhaustive. This command might perform an `execvp()` to run
 // a different program. When that happens successfully, the exit status of this
-// process will be the exit status of that program.
+// process will be the exit status of that program, see `uucore::exec` for the
+// exit status when it fails.
 pub(crate) mod error_exit_status {
-    pub const NOT_FOUND: i32 = 127;
-    pub const COULD_NOT_EXECUTE: i32 = 126;
     pub const ANOTHER_ERROR: i32 = libc::EXIT_FAILURE;
 }
 
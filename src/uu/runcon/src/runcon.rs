@@ -5,17 +5,15 @@ use uucore::error::{UResult, UUsageError};
 
 use clap::{Arg, ArgAction, Command};
 use selinux::{OpaqueSecurityContext, SecurityClass, SecurityContext};
-use uucore::format_usage;
+use uucore::{exec, format_usage};
 
 use std::borrow::Cow;
 use std::ffi::{CStr, CString, OsStr, OsString};
-use std::os::raw::c_char;
+use std::io;
 use std::os::unix::ffi::OsStrExt;
-use std::{io, ptr};
 
 mod errors;
 
-use errors::error_exit_status;
 use errors::{Error, Result, RunconError};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -387,31 +385,7 @@ fn get_custom_context(
 /// compiler the only valid return type is to say "if this returns, it will
 /// always return an error".
 fn execute_command(command: &OsStr, arguments: &[OsString]) -> UResult<()> {
-    let c_command = os_str_to_c_string(command).map_err(RunconError::new)?;
-
-    let argv_storage: Vec<CString> = arguments
-        .iter()
-        .map(AsRef::as_ref)
-        .map(os_str_to_c_string)
-        .collect::<Result<_>>()
-        .map_err(RunconError::new)?;
-
-    let mut argv: Vec<*const c_char> = Vec::with_capacity(arguments.len().saturating_add(2));
-    argv.push(c_command.as_ptr());
-    argv.extend(argv_storage.iter().map(AsRef::as_ref).map(CStr::as_ptr));
-    argv.push(ptr::null());
-
-    unsafe { libc::execvp(c_command.as_ptr(), argv.as_ptr()) };
-
-    let err = io::Error::last_os_error();
-    let exit_status = if err.kind() == io::ErrorKind::NotFound {
-        error_exit_status::NOT_FOUND
-    } else {
-        error_exit_status::COULD_NOT_EXECUTE
-    };
-
-    let err = Error::from_io1("Executing command", command, err);
-    Err(RunconError::with_code(exit_status, err).into())
+    Err(exec::command_error(command, exec::exec(command, arguments)))
 }
 
 fn os_str_to_c_string(s: &OsStr) -> Result<CString> {
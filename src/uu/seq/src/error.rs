@@ -42,7 +42,6 @@ impl SeqError {
             Self::ParseError(_, e) => match e {
                 ParseNumberError::Float => "floating point argument",
                 ParseNumberError::Nan => "'not-a-number' argument",
-                ParseNumberError::Hex => "hexadecimal argument",
             },
             Self::ZeroIncrement(_) => "Zero increment value",
         }
@@ -15,13 +15,13 @@ use crate::extendedbigdecimal::ExtendedBigDecimal;
 use crate::extendedbigint::ExtendedBigInt;
 use crate::number::Number;
 use crate::number::PreciseNumber;
+use uucore::numeric::{parse_float, FloatLiteral, Radix};
 
 /// An error returned when parsing a number fails.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ParseNumberError {
     Float,
     Nan,
-    Hex,
 }
 
 /// Decide whether a given string and its parsed `BigInt` is negative zero.
@@ -38,8 +38,8 @@ fn is_minus_zero_float(s: &str, x: &BigDecimal) -> bool {
 ///
 /// # Errors
 ///
-/// This function returns an error if the input string is a variant of
-/// "NaN" or if no [`BigInt`] could be parsed from the string.
+/// This function returns an error if no [`BigInt`] could be parsed from
+/// the string.
 ///
 /// # Examples
 ///
@@ -67,33 +67,7 @@ fn parse_no_decimal_no_exponent(s: &str) -> Result<PreciseNumber, ParseNumberErr
                 ))
             }
         }
-        Err(_) => {
-            // Possibly "NaN" or "inf".
-            //
-            // TODO In Rust v1.53.0, this change
-            // https://github.com/rust-lang/rust/pull/78618 improves the
-            // parsing of floats to include being able to parse "NaN"
-            // and "inf". So when the minimum version of this crate is
-            // increased to 1.53.0, we should just use the built-in
-            // `f32` parsing instead.
-            if s.eq_ignore_ascii_case("inf") {
-                Ok(PreciseNumber::new(
-                    Number::Float(ExtendedBigDecimal::Infinity),
-                    0,
-                    0,
-                ))
-            } else if s.eq_ignore_ascii_case("-inf") {
-                Ok(PreciseNumber::new(
-                    Number::Float(ExtendedBigDecimal::MinusInfinity),
-                    0,
-                    0,
-                ))
-            } else if s.eq_ignore_ascii_case("nan") || s.eq_ignore_ascii_case("-nan") {
-                Err(ParseNumberError::Nan)
-            } else {
-                Err(ParseNumberError::Float)
-            }
-        }
+        Err(_) => Err(ParseNumberError::Float),
     }
 }
 
@@ -287,90 +261,162 @@ fn parse_decimal_and_exponent(
     }
 }
 
-/// Parse a hexadecimal integer from a string.
+/// The largest power of ten of a finite `long double`, which GNU `seq`
+/// uses for its arguments.
+const MAX_DECIMAL_EXPONENT: i64 = 4932;
+
+/// The smallest and largest powers of two of a finite, nonzero `long double`.
+const MIN_BINARY_EXPONENT: i64 = -16445;
+const MAX_BINARY_EXPONENT: i64 = 16384;
+
+/// Parse a hexadecimal number, whose exponent is a power of two.
 ///
 /// # Errors
 ///
-/// This function returns an error if no [`BigInt`] could be parsed from
-/// the string.
+/// This function returns an error if the number doesn't fit in a `long
+/// double`.
 ///
 /// # Examples
 ///
 /// ```rust,ignore
-/// let actual = "0x0".parse::<Number>().unwrap().number;
-/// let expected = Number::BigInt(BigInt::zero());
+/// let actual = "0x.8".parse::<Number>().unwrap().number;
+/// let expected = "0.5".parse::<BigDecimal>().unwrap();
 /// assert_eq!(actual, expected);
 /// ```
-fn parse_hexadecimal(s: &str) -> Result<PreciseNumber, ParseNumberError> {
-    let (is_neg, s) = if s.starts_with('-') {
-        (true, &s[3..])
-    } else {
-        (false, &s[2..])
-    };
-
-    if s.starts_with('-') || s.starts_with('+') {
-        // Even though this is more like an invalid hexadecimal number,
-        // GNU reports this as an invalid floating point number, so we
-        // use `ParseNumberError::Float` to match that behavior.
+fn parse_hexadecimal(
+    negative: bool,
+    integral: &str,
+    fractional: &str,
+    exponent: i64,
+) -> Result<PreciseNumber, ParseNumberError> {
+    let digits = [integral, fractional].concat();
+    let mut mantissa = BigInt::from_str_radix(&digits, 16).map_err(|_| ParseNumberError::Float)?;
+    let mut exponent = exponent.saturating_sub(4 * fractional.len() as i64);
+
+    if mantissa.is_zero() {
+        // In GNU `seq`, the `-w` option does not seem to work with
+        // hexadecimal arguments, so the number of integral digits is
+        // zero, except for the sign of negative zero.
+        return Ok(if negative {
+            PreciseNumber::new(Number::Int(ExtendedBigInt::MinusZero), 2, 0)
+        } else {
+            PreciseNumber::new(Number::Int(ExtendedBigInt::zero()), 0, 0)
+        });
+    }
+    let magnitude = exponent.saturating_add(mantissa.bits() as i64);
+    if !(MIN_BINARY_EXPONENT..=MAX_BINARY_EXPONENT).contains(&magnitude) {
         return Err(ParseNumberError::Float);
     }
+    if negative {
+        mantissa = -mantissa;
+    }
 
-    let num = BigInt::from_str_radix(s, 16).map_err(|_| ParseNumberError::Hex)?;
-
-    match (is_neg, num == BigInt::zero()) {
-        (true, true) => Ok(PreciseNumber::new(
-            Number::Int(ExtendedBigInt::MinusZero),
-            2,
-            0,
-        )),
-        (true, false) => Ok(PreciseNumber::new(
-            Number::Int(ExtendedBigInt::BigInt(-num)),
+    if exponent >= 0 {
+        let n = mantissa << exponent as usize;
+        return Ok(PreciseNumber::new(
+            Number::Int(ExtendedBigInt::BigInt(n)),
             0,
             0,
-        )),
-        (false, _) => Ok(PreciseNumber::new(
-            Number::Int(ExtendedBigInt::BigInt(num)),
+        ));
+    }
+    // Dividing by 2^k is multiplying by 5^k and dividing by 10^k, which
+    // gives the exact number of fractional digits.
+    while exponent < 0 && (&mantissa % 2u8).is_zero() {
+        mantissa /= 2u8;
+        exponent += 1;
+    }
+    if exponent == 0 {
+        return Ok(PreciseNumber::new(
+            Number::Int(ExtendedBigInt::BigInt(mantissa)),
             0,
             0,
-        )),
+        ));
     }
+    let scale = -exponent;
+    let x = BigDecimal::new(mantissa * BigInt::from(5u8).pow(scale as u32), scale);
+    Ok(PreciseNumber::new(
+        Number::Float(ExtendedBigDecimal::BigDecimal(x)),
+        0,
+        scale as usize,
+    ))
 }
 
 impl FromStr for PreciseNumber {
     type Err = ParseNumberError;
-    fn from_str(mut s: &str) -> Result<Self, Self::Err> {
-        // Trim leading whitespace.
-        s = s.trim_start();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, radix, integral, fractional, exponent) = match parse_float(s) {
+            Some(FloatLiteral::Finite {
+                negative,
+                radix,
+                integral,
+                fractional,
+                exponent,
+            }) => (negative, radix, integral, fractional, exponent),
+            Some(FloatLiteral::Infinity { negative: false }) => {
+                return Ok(PreciseNumber::new(
+                    Number::Float(ExtendedBigDecimal::Infinity),
+                    0,
+                    0,
+                ))
+            }
+            Some(FloatLiteral::Infinity { negative: true }) => {
+                return Ok(PreciseNumber::new(
+                    Number::Float(ExtendedBigDecimal::MinusInfinity),
+                    0,
+                    0,
+                ))
+            }
+            Some(FloatLiteral::NaN { .. }) => return Err(ParseNumberError::Nan),
+            None => return Err(ParseNumberError::Float),
+        };
 
-        // Trim a single leading "+" character.
-        if s.starts_with('+') {
-            s = &s[1..];
+        if radix == Radix::Hexadecimal {
+            return parse_hexadecimal(negative, integral, fractional, exponent);
         }
 
-        // Check if the string seems to be in hexadecimal format.
-        //
-        // May be 0x123 or -0x123, so the index `i` may be either 0 or 1.
-        if let Some(i) = s.to_lowercase().find("0x") {
-            if i <= 1 {
-                return parse_hexadecimal(s);
-            }
+        // Numbers too large for a `long double` are rejected like GNU does.
+        // The power of ten of the first significant digit is close enough.
+        let leading = integral.trim_start_matches('0');
+        let significant = fractional.trim_start_matches('0');
+        let magnitude = if !leading.is_empty() {
+            exponent.saturating_add(leading.len() as i64 - 1)
+        } else if !significant.is_empty() {
+            exponent.saturating_sub((fractional.len() - significant.len()) as i64 + 1)
+        } else {
+            exponent
+        };
+        if magnitude > MAX_DECIMAL_EXPONENT || exponent == i64::MIN || exponent == i64::MAX {
+            return Err(ParseNumberError::Float);
         }
 
-        // Find the decimal point and the exponent symbol. Parse the
+        // Write the number again without its white space and plus sign,
+        // with `.` as the decimal point and `e` before the exponent. Then
+        // find the decimal point and the exponent symbol, and parse the
         // number differently depending on its form. This is important
         // because the form of the input dictates how the output will be
         // presented.
-        match (s.find('.'), s.find('e')) {
-            // For example, "123456" or "inf".
-            (None, None) => parse_no_decimal_no_exponent(s),
+        let mut s = String::from(if negative { "-" } else { "" });
+        s.push_str(integral);
+        let mut i = None;
+        if !fractional.is_empty() {
+            i = Some(s.len());
+            s.push('.');
+            s.push_str(fractional);
+        }
+        let mut j = None;
+        if exponent != 0 {
+            j = Some(s.len());
+            s.push_str(&format!("e{}", exponent));
+        }
+        match (i, j) {
+            // For example, "123456".
+            (None, None) => parse_no_decimal_no_exponent(&s),
             // For example, "123e456" or "1e-2".
-            (None, Some(j)) => parse_exponent_no_decimal(s, j),
+            (None, Some(j)) => parse_exponent_no_decimal(&s, j),
             // For example, "123.456".
-            (Some(i), None) => parse_decimal_no_exponent(s, i),
+            (Some(i), None) => parse_decimal_no_exponent(&s, i),
             // For example, "123.456e789".
-            (Some(i), Some(j)) if i < j => parse_decimal_and_exponent(s, i, j),
-            // For example, "1e2.3" or "1.2.3".
-            _ => Err(ParseNumberError::Float),
+            (Some(i), Some(j)) => parse_decimal_and_exponent(&s, i, j),
         }
     }
 }
@@ -440,6 +486,46 @@ mod tests {
             parse("0x10"),
             Number::Int(ExtendedBigInt::BigInt("16".parse::<BigInt>().unwrap()))
         );
+        assert_eq!(
+            parse("-0X1p4"),
+            Number::Int(ExtendedBigInt::BigInt("-16".parse::<BigInt>().unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_parse_hexadecimal_big_decimal() {
+        assert_eq!(
+            parse("0x.8"),
+            Number::Float(ExtendedBigDecimal::BigDecimal(
+                "0.5".parse::<BigDecimal>().unwrap()
+            ))
+        );
+        assert_eq!(
+            parse("0x1p-3"),
+            Number::Float(ExtendedBigDecimal::BigDecimal(
+                "0.125".parse::<BigDecimal>().unwrap()
+            ))
+        );
+        assert_eq!(num_fractional_digits("0x1p-3"), 3);
+    }
+
+    #[test]
+    fn test_parse_like_strtold() {
+        assert_eq!(parse(" \t+1E2"), parse("100"));
+        assert_eq!(num_integral_digits(" +1E2"), 3);
+        assert_eq!(parse("1.5E-1"), parse("0.15"));
+        assert_eq!(
+            parse("-infinity"),
+            Number::Float(ExtendedBigDecimal::MinusInfinity)
+        );
+        assert_eq!(
+            "1e4940".parse::<PreciseNumber>().unwrap_err(),
+            ParseNumberError::Float
+        );
+        assert_eq!(
+            "1 ".parse::<PreciseNumber>().unwrap_err(),
+            ParseNumberError::Float
+        );
     }
 
     #[test]
@@ -510,7 +596,11 @@ mod tests {
     fn test_parse_invalid_hex() {
         assert_eq!(
             "0xg".parse::<PreciseNumber>().unwrap_err(),
-            ParseNumberError::Hex
+            ParseNumberError::Float
+        );
+        assert_eq!(
+            "0x1p99999".parse::<PreciseNumber>().unwrap_err(),
+            ParseNumberError::Float
         );
     }
 
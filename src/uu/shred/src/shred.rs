@@ -10,7 +10,7 @@
 
 use clap::{crate_version, Arg, ArgAction, Command};
 use rand::prelude::SliceRandom;
-use rand::Rng;
+use rand::RngCore;
 use std::cell::{Cell, RefCell};
 use std::fs;
 use std::fs::{File, OpenOptions};
@@ -19,6 +19,7 @@ use std::io::prelude::*;
 use std::path::{Path, PathBuf};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
+use uucore::rand::RandomSource;
 use uucore::{format_usage, show, show_if_err, util_name};
 
 const BLOCK_SIZE: usize = 512;
@@ -117,17 +118,11 @@ struct BytesGenerator<'a> {
     block_size: usize,
     exact: bool, // if false, every block's size is block_size
     gen_type: PassType<'a>,
-    rng: Option<RefCell<rand::rngs::ThreadRng>>,
     bytes: [u8; BLOCK_SIZE],
 }
 
 impl<'a> BytesGenerator<'a> {
     fn new(total_bytes: u64, gen_type: PassType<'a>, exact: bool) -> BytesGenerator {
-        let rng = match gen_type {
-            PassType::Random => Some(RefCell::new(rand::thread_rng())),
-            PassType::Pattern(_) => None,
-        };
-
         let bytes = [0; BLOCK_SIZE];
 
         BytesGenerator {
@@ -136,25 +131,19 @@ impl<'a> BytesGenerator<'a> {
             block_size: BLOCK_SIZE,
             exact,
             gen_type,
-            rng,
             bytes,
         }
     }
 
     pub fn reset(&mut self, total_bytes: u64, gen_type: PassType<'a>) {
-        if let PassType::Random = gen_type {
-            if self.rng.is_none() {
-                self.rng = Some(RefCell::new(rand::thread_rng()));
-            }
-        }
-
         self.total_bytes = total_bytes;
         self.gen_type = gen_type;
 
         self.bytes_generated.set(0);
     }
 
-    pub fn next(&mut self) -> Option<&[u8]> {
+    /// The next block, whose random bytes come from `rng`.
+    pub fn next(&mut self, rng: &mut RandomSource) -> Option<&[u8]> {
         // We go over the total_bytes limit when !self.exact and total_bytes isn't a multiple
         // of self.block_size
         if self.bytes_generated.get() >= self.total_bytes {
@@ -175,10 +164,7 @@ impl<'a> BytesGenerator<'a> {
         let bytes = &mut self.bytes[..this_block_size];
 
         match self.gen_type {
-            PassType::Random => {
-                let mut rng = self.rng.as_ref().unwrap().borrow_mut();
-                rng.fill(bytes);
-            }
+            PassType::Random => rng.fill_bytes(bytes),
             PassType::Pattern(pattern) => {
                 let skip = if self.bytes_generated.get() == 0 {
                     0
@@ -290,8 +276,6 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     //         - 'wipesync' => also sync each obfuscated byte to disk.
     //       The default mode is 'wipesync', but note it can be expensive.
 
-    // TODO: implement --random-source
-
     let force = matches.get_flag(options::FORCE);
     let remove = matches.get_flag(options::REMOVE);
     let size_arg = matches
@@ -301,10 +285,11 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let exact = matches.get_flag(options::EXACT) && size.is_none(); // if -s is given, ignore -x
     let zero = matches.get_flag(options::ZERO);
     let verbose = matches.get_flag(options::VERBOSE);
+    let mut rng = RandomSource::from_matches(&matches)?;
 
     for path_str in matches.get_many::<String>(options::FILE).unwrap() {
         show_if_err!(wipe_file(
-            path_str, iterations, remove, size, exact, zero, verbose, force,
+            path_str, iterations, remove, size, exact, zero, verbose, force, &mut rng,
         ));
     }
     Ok(())
@@ -339,6 +324,7 @@ pub fn uu_app() -> Command {
                 .value_name("N")
                 .help("shred this many bytes (suffixes like K, M, G accepted)"),
         )
+        .arg(uucore::rand::arguments::random_source())
         .arg(
             Arg::new(options::REMOVE)
                 .short('u')
@@ -442,6 +428,7 @@ fn wipe_file(
     zero: bool,
     verbose: bool,
     force: bool,
+    rng: &mut RandomSource,
 ) -> UResult<()> {
     // Get these potential errors out of the way first
     let path: &Path = Path::new(path_str);
@@ -488,8 +475,7 @@ fn wipe_file(
         for pattern in PATTERNS.iter().take(remainder) {
             pass_sequence.push(PassType::Pattern(pattern));
         }
-        let mut rng = rand::thread_rng();
-        pass_sequence.shuffle(&mut rng); // randomize the order of application
+        pass_sequence.shuffle(rng); // randomize the order of application
 
         let n_random = 3 + n_passes / 10; // Minimum 3 random passes; ratio of 10 after
                                           // Evenly space random passes; ensures one at the beginning and end
@@ -539,8 +525,10 @@ fn wipe_file(
                 }
             }
             // size is an optional argument for exactly how many bytes we want to shred
-            show_if_err!(do_pass(&mut file, path, &mut generator, *pass_type, size)
-                .map_err_context(|| format!("{}: File write pass failed", path.maybe_quote())));
+            show_if_err!(
+                do_pass(&mut file, path, &mut generator, *pass_type, size, rng)
+                    .map_err_context(|| format!("{}: File write pass failed", path.maybe_quote()))
+            );
             // Ignore failed writes; just keep trying
         }
     }
@@ -558,6 +546,7 @@ fn do_pass<'a>(
     generator: &mut BytesGenerator<'a>,
     generator_type: PassType<'a>,
     given_file_size: Option<u64>,
+    rng: &mut RandomSource,
 ) -> Result<(), io::Error> {
     file.rewind()?;
 
@@ -566,7 +555,7 @@ fn do_pass<'a>(
 
     generator.reset(size, generator_type);
 
-    while let Some(block) = generator.next() {
+    while let Some(block) = generator.next(rng) {
         file.write_all(block)?;
     }
 
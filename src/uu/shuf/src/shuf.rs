@@ -10,14 +10,12 @@
 use clap::{crate_version, Arg, ArgAction, Command};
 use memchr::memchr_iter;
 use rand::prelude::SliceRandom;
-use rand::RngCore;
 use std::fs::File;
 use std::io::{stdin, stdout, BufReader, BufWriter, Read, Write};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::format_usage;
-
-mod rand_read_adapter;
+use uucore::rand::RandomSource;
 
 enum Mode {
     Default(String),
@@ -37,7 +35,7 @@ static ABOUT: &str = "\
 struct Options {
     head_count: usize,
     output: Option<String>,
-    random_source: Option<String>,
+    random_source: RandomSource,
     repeat: bool,
     sep: u8,
 }
@@ -47,7 +45,6 @@ mod options {
     pub static INPUT_RANGE: &str = "input-range";
     pub static HEAD_COUNT: &str = "head-count";
     pub static OUTPUT: &str = "output";
-    pub static RANDOM_SOURCE: &str = "random-source";
     pub static REPEAT: &str = "repeat";
     pub static ZERO_TERMINATED: &str = "zero-terminated";
     pub static FILE: &str = "file";
@@ -91,9 +88,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             }
         },
         output: matches.get_one::<String>(options::OUTPUT).map(String::from),
-        random_source: matches
-            .get_one::<String>(options::RANDOM_SOURCE)
-            .map(String::from),
+        random_source: RandomSource::from_matches(&matches)?,
         repeat: matches.get_flag(options::REPEAT),
         sep: if matches.get_flag(options::ZERO_TERMINATED) {
             0x00_u8
@@ -164,13 +159,7 @@ pub fn uu_app() -> Command {
                 .help("write result to FILE instead of standard output")
                 .value_hint(clap::ValueHint::FilePath),
         )
-        .arg(
-            Arg::new(options::RANDOM_SOURCE)
-                .long(options::RANDOM_SOURCE)
-                .value_name("FILE")
-                .help("get random bytes from FILE")
-                .value_hint(clap::ValueHint::FilePath),
-        )
+        .arg(uucore::rand::arguments::random_source())
         .arg(
             Arg::new(options::REPEAT)
                 .short('r')
@@ -239,14 +228,7 @@ fn shuf_bytes(input: &mut Vec<&[u8]>, opts: Options) -> UResult<()> {
         }
     });
 
-    let mut rng = match opts.random_source {
-        Some(r) => {
-            let file = File::open(&r[..])
-                .map_err_context(|| format!("failed to open random source {}", r.quote()))?;
-            WrappedRng::RngFile(rand_read_adapter::ReadRng::new(file))
-        }
-        None => WrappedRng::RngDefault(rand::thread_rng()),
-    };
+    let mut rng = opts.random_source;
 
     if input.is_empty() {
         return Ok(());
@@ -304,38 +286,3 @@ fn parse_head_count(headcounts: Vec<String>) -> Result<usize, String> {
     }
     Ok(result)
 }
-
-enum WrappedRng {
-    RngFile(rand_read_adapter::ReadRng<File>),
-    RngDefault(rand::rngs::ThreadRng),
-}
-
-impl RngCore for WrappedRng {
-    fn next_u32(&mut self) -> u32 {
-        match self {
-            Self::RngFile(r) => r.next_u32(),
-            Self::RngDefault(r) => r.next_u32(),
-        }
-    }
-
-    fn next_u64(&mut self) -> u64 {
-        match self {
-            Self::RngFile(r) => r.next_u64(),
-            Self::RngDefault(r) => r.next_u64(),
-        }
-    }
-
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        match self {
-            Self::RngFile(r) => r.fill_bytes(dest),
-            Self::RngDefault(r) => r.fill_bytes(dest),
-        }
-    }
-
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
-        match self {
-            Self::RngFile(r) => r.try_fill_bytes(dest),
-            Self::RngDefault(r) => r.try_fill_bytes(dest),
-        }
-    }
-}
@@ -9,7 +9,7 @@
 
 use crate::{
     chunks::{self, Chunk, RecycledChunk},
-    compare_by, open, GlobalSettings, SortError,
+    compare_by, open_input, GlobalSettings, SortError,
 };
 use itertools::Itertools;
 use std::{
@@ -35,7 +35,7 @@ pub fn check(path: &OsStr, settings: &GlobalSettings) -> UResult<()> {
         // Otherwise, the line previous line must compare _less or equal_ to the next one.
         Ordering::Equal
     };
-    let file = open(path)?;
+    let file = open_input(path, settings)?;
     let (recycled_sender, recycled_receiver) = sync_channel(2);
     let (loaded_sender, loaded_receiver) = sync_channel(2);
     thread::spawn({
@@ -73,7 +73,7 @@ pub fn check(path: &OsStr, settings: &GlobalSettings) -> UResult<()> {
                 return Err(SortError::Disorder {
                     file: path.to_owned(),
                     line_number: line_idx,
-                    line: new_first.line.to_owned(),
+                    line: new_first.as_str(chunk.line_data()).to_owned(),
                     silent: settings.check_silent,
                 }
                 .into());
@@ -87,7 +87,7 @@ pub fn check(path: &OsStr, settings: &GlobalSettings) -> UResult<()> {
                 return Err(SortError::Disorder {
                     file: path.to_owned(),
                     line_number: line_idx,
-                    line: b.line.to_owned(),
+                    line: b.as_str(chunk.line_data()).to_owned(),
                     silent: settings.check_silent,
                 }
                 .into());
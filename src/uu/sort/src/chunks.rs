@@ -40,9 +40,15 @@ pub struct ChunkContents<'a> {
 
 #[derive(Debug)]
 pub struct LineData<'a> {
+    /// The text of the lines, which refer to it by the offsets of their bytes.
+    pub text: &'a str,
     pub selections: Vec<&'a str>,
     pub num_infos: Vec<NumInfo>,
     pub parsed_floats: Vec<GeneralF64ParseResult>,
+    /// The lines that are too long or too far into `text` to be referred to
+    /// by 32-bit offsets, which only happens in the chunks that grew past
+    /// [`MAX_CHUNK_LEN`].
+    pub long_lines: Vec<&'a str>,
 }
 
 impl Chunk {
@@ -113,6 +119,10 @@ impl RecycledChunk {
     }
 }
 
+/// The largest chunk that is read at once, whose lines can all be referred to
+/// with 32-bit offsets. A chunk only grows past it to hold very long lines.
+pub const MAX_CHUNK_LEN: usize = u32::MAX as usize;
+
 /// Read a chunk, parse lines and send them.
 ///
 /// No empty chunk will be sent. If we reach the end of the input, `false` is returned.
@@ -160,7 +170,7 @@ pub fn read<T: Read>(
         file,
         next_files,
         &mut buffer,
-        max_buffer_size,
+        max_buffer_size.map(|size| size.min(MAX_CHUNK_LEN)),
         carry_over.len(),
         separator,
     )?;
@@ -182,9 +192,11 @@ pub fn read<T: Read>(
             let read = std::str::from_utf8(&buffer[..read])
                 .map_err(|error| SortError::Uft8Error { error })?;
             let mut line_data = LineData {
+                text: read,
                 selections,
                 num_infos,
                 parsed_floats,
+                long_lines: Vec::new(),
             };
             parse_lines(read, &mut lines, &mut line_data, separator, settings);
             Ok(ChunkContents { lines, line_data })
@@ -209,10 +221,22 @@ fn parse_lines<'a>(
     assert!(line_data.num_infos.is_empty());
     assert!(line_data.parsed_floats.is_empty());
     let mut token_buffer = vec![];
+    let mut start = 0;
     lines.extend(
         read.split(separator as char)
             .enumerate()
-            .map(|(index, line)| Line::create(line, index, line_data, &mut token_buffer, settings)),
+            .map(|(index, line)| {
+                let line_start = start;
+                start += line.len() + 1;
+                Line::create(
+                    line_start,
+                    line,
+                    index,
+                    line_data,
+                    &mut token_buffer,
+                    settings,
+                )
+            }),
     );
 }
 
@@ -24,7 +24,7 @@ use std::{
 use itertools::Itertools;
 use uucore::error::UResult;
 
-use crate::chunks::RecycledChunk;
+use crate::chunks::{LineData, RecycledChunk};
 use crate::merge::ClosedTmpFile;
 use crate::merge::WriteableCompressedTmpFile;
 use crate::merge::WriteablePlainTmpFile;
@@ -112,17 +112,17 @@ fn reader_writer<
             merger.write_all(settings, output)?;
         }
         ReadResult::SortedSingleChunk(chunk) => {
+            let lines = chunk.lines().iter().map(|line| (line, chunk.line_data()));
             if settings.unique {
                 print_sorted(
-                    chunk.lines().iter().dedup_by(|a, b| {
-                        compare_by(a, b, settings, chunk.line_data(), chunk.line_data())
-                            == Ordering::Equal
+                    lines.dedup_by(|(a, a_data), (b, b_data)| {
+                        compare_by(a, b, settings, a_data, b_data) == Ordering::Equal
                     }),
                     settings,
                     output,
                 );
             } else {
-                print_sorted(chunk.lines().iter(), settings, output);
+                print_sorted(lines, settings, output);
             }
         }
         ReadResult::SortedTwoChunks([a, b]) => {
@@ -140,12 +140,16 @@ fn reader_writer<
                             compare_by(line_a, line_b, settings, a.line_data(), b.line_data())
                                 == Ordering::Equal
                         })
-                        .map(|(line, _)| line),
+                        .map(|(line, chunk)| (line, chunk.line_data())),
                     settings,
                     output,
                 );
             } else {
-                print_sorted(merged_iter.map(|(line, _)| line), settings, output);
+                print_sorted(
+                    merged_iter.map(|(line, chunk)| (line, chunk.line_data())),
+                    settings,
+                    output,
+                );
             }
         }
         ReadResult::EmptyInput => {
@@ -274,13 +278,18 @@ fn write<I: WriteableTmpFile>(
     separator: u8,
 ) -> UResult<I::Closed> {
     let mut tmp_file = I::create(file, compress_prog)?;
-    write_lines(chunk.lines(), tmp_file.as_write(), separator);
+    write_lines(
+        chunk.lines(),
+        chunk.line_data(),
+        tmp_file.as_write(),
+        separator,
+    );
     tmp_file.finished_writing()
 }
 
-fn write_lines<T: Write>(lines: &[Line], writer: &mut T, separator: u8) {
+fn write_lines<T: Write>(lines: &[Line], line_data: &LineData, writer: &mut T, separator: u8) {
     for s in lines {
-        writer.write_all(s.line.as_bytes()).unwrap();
+        writer.write_all(s.as_str(line_data).as_bytes()).unwrap();
         writer.write_all(&[separator]).unwrap();
     }
 }
@@ -26,7 +26,7 @@ use uucore::error::UResult;
 
 use crate::{
     chunks::{self, Chunk, RecycledChunk},
-    compare_by, open,
+    compare_by, open_input,
     tmp_dir::TmpDirWrapper,
     GlobalSettings, Output, SortError,
 };
@@ -74,7 +74,7 @@ pub fn merge<'a>(
         merge_with_file_limit::<_, _, WriteablePlainTmpFile>(
             files
                 .iter()
-                .map(|file| open(file).map(|file| PlainMergeInput { inner: file })),
+                .map(|file| open_input(file, settings).map(|file| PlainMergeInput { inner: file })),
             settings,
             tmp_dir,
         )
@@ -82,7 +82,7 @@ pub fn merge<'a>(
         merge_with_file_limit::<_, _, WriteableCompressedTmpFile>(
             files
                 .iter()
-                .map(|file| open(file).map(|file| PlainMergeInput { inner: file })),
+                .map(|file| open_input(file, settings).map(|file| PlainMergeInput { inner: file })),
             settings,
             tmp_dir,
         )
@@ -304,7 +304,7 @@ impl<'a> FileMerger<'a> {
                         }
                     }
                 }
-                current_line.print(out, settings);
+                current_line.print(file.current_chunk.line_data(), out, settings);
             });
 
             let was_last_line_for_file = file.current_chunk.lines().len() == file.line_idx + 1;
@@ -15,6 +15,7 @@
 //! From that follows the constraints of this algorithm: It is able to compare numbers in ±(1*10^[i64::MIN]..10*10^[i64::MAX]).
 
 use std::{cmp::Ordering, ops::Range};
+use uucore::human_size::suffix_power;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 enum Sign {
@@ -82,8 +83,8 @@ impl NumInfo {
 
             if Self::is_invalid_char(char, &mut had_decimal_pt, parse_settings) {
                 return if let Some(start) = start {
-                    let has_si_unit = parse_settings.accept_si_units
-                        && matches!(char, 'K' | 'k' | 'M' | 'G' | 'T' | 'P' | 'E' | 'Z' | 'Y');
+                    let has_si_unit =
+                        parse_settings.accept_si_units && suffix_power(char).is_some();
                     (
                         Self { exponent, sign },
                         start..if has_si_unit { idx + 1 } else { idx },
@@ -166,22 +167,8 @@ impl NumInfo {
     }
 }
 
-fn get_unit(unit: Option<char>) -> u8 {
-    if let Some(unit) = unit {
-        match unit {
-            'K' | 'k' => 1,
-            'M' => 2,
-            'G' => 3,
-            'T' => 4,
-            'P' => 5,
-            'E' => 6,
-            'Z' => 7,
-            'Y' => 8,
-            _ => 0,
-        }
-    } else {
-        0
-    }
+fn get_unit(unit: Option<char>) -> u32 {
+    unit.and_then(suffix_power).unwrap_or(0)
 }
 
 /// Compare two numbers according to the rules of human numeric comparison.
@@ -23,12 +23,11 @@ mod tmp_dir;
 
 use chunks::LineData;
 use clap::builder::ValueParser;
-use clap::{crate_version, Arg, ArgAction, Command};
+use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use custom_str_cmp::custom_str_cmp;
 use ext_sort::ext_sort;
 use fnv::FnvHasher;
 use numeric_str_cmp::{human_numeric_str_cmp, numeric_str_cmp, NumInfo, NumInfoParseSettings};
-use rand::{thread_rng, Rng};
 use rayon::prelude::*;
 use std::cmp::Ordering;
 use std::env;
@@ -38,6 +37,7 @@ use std::fmt::Display;
 use std::fs::{File, OpenOptions};
 use std::hash::{Hash, Hasher};
 use std::io::{stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
+use std::marker::PhantomData;
 use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
@@ -46,7 +46,10 @@ use unicode_width::UnicodeWidthStr;
 use uucore::display::Quotable;
 use uucore::error::{set_exit_code, strip_errno, UError, UResult, USimpleError, UUsageError};
 use uucore::format_usage;
+use uucore::lines::{self, skip_byte_order_mark, strip_crlf_str};
 use uucore::parse_size::{ParseSizeError, Parser};
+use uucore::posix;
+use uucore::rand::{random_source_file, RandomSource, RandomSourceError};
 use uucore::version_cmp::version_cmp;
 
 use crate::tmp_dir::TmpDirWrapper;
@@ -163,6 +166,9 @@ enum SortError {
     Uft8Error {
         error: Utf8Error,
     },
+    RandomSourceFailed {
+        error: RandomSourceError,
+    },
 }
 
 impl Error for SortError {}
@@ -227,6 +233,7 @@ impl Display for SortError {
             }
             Self::TmpDirCreationFailed => write!(f, "could not create temporary directory"),
             Self::Uft8Error { error } => write!(f, "{error}"),
+            Self::RandomSourceFailed { error } => write!(f, "{error}"),
         }
     }
 }
@@ -318,6 +325,7 @@ pub struct GlobalSettings {
     separator: Option<char>,
     threads: String,
     zero_terminated: bool,
+    crlf: bool,
     buffer_size: usize,
     compress_prog: Option<String>,
     merge_batch_size: usize,
@@ -395,6 +403,7 @@ impl Default for GlobalSettings {
             separator: None,
             threads: String::new(),
             zero_terminated: false,
+            crlf: false,
             buffer_size: DEFAULT_BUF_SIZE,
             compress_prog: None,
             merge_batch_size: 32,
@@ -493,18 +502,32 @@ enum Selection<'a> {
 
 type Field = Range<usize>;
 
+/// The length of the [`Line`]s that can't be referred to with 32-bit offsets,
+/// whose `start` is their position in [`LineData::long_lines`].
+const LONG_LINE: u32 = u32::MAX;
+
+/// A line of a chunk, as the range of its bytes in the text of the chunk,
+/// which is kept in its [`LineData`].
+///
+/// There is one for each line of the input, so it is kept this small: this
+/// is why chunks are only larger than [`chunks::MAX_CHUNK_LEN`] to hold very
+/// long lines, which are then kept in [`LineData::long_lines`] instead.
 #[derive(Clone, Debug)]
 pub struct Line<'a> {
-    line: &'a str,
-    index: usize,
+    start: u32,
+    len: u32,
+    index: u32,
+    text: PhantomData<&'a str>,
 }
 
 impl<'a> Line<'a> {
-    /// Creates a new `Line`.
+    /// Creates a new `Line`, for the `line` of the text of `line_data` that
+    /// starts at `start`.
     ///
     /// If additional data is needed for sorting it is added to `line_data`.
     /// `token_buffer` allows to reuse the allocation for tokens.
     fn create(
+        start: usize,
         line: &'a str,
         index: usize,
         line_data: &mut LineData<'a>,
@@ -512,13 +535,14 @@ impl<'a> Line<'a> {
         settings: &GlobalSettings,
     ) -> Self {
         token_buffer.clear();
+        let key = Self::key_of(line, settings);
         if settings.precomputed.needs_tokens {
-            tokenize(line, settings.separator, token_buffer);
+            tokenize(key, settings.separator, token_buffer);
         }
         for (selector, selection) in settings
             .selectors
             .iter()
-            .map(|selector| (selector, selector.get_selection(line, token_buffer)))
+            .map(|selector| (selector, selector.get_selection(key, token_buffer)))
         {
             match selection {
                 Selection::AsF64(parsed_float) => line_data.parsed_floats.push(parsed_float),
@@ -533,25 +557,68 @@ impl<'a> Line<'a> {
                 }
             }
         }
-        Self { line, index }
+        let (start, len) = match (u32::try_from(start), u32::try_from(line.len())) {
+            (Ok(start), Ok(len)) if len != LONG_LINE => (start, len),
+            _ => {
+                line_data.long_lines.push(line);
+                ((line_data.long_lines.len() - 1) as u32, LONG_LINE)
+            }
+        };
+        Self {
+            start,
+            len,
+            index: index as u32,
+            text: PhantomData,
+        }
+    }
+
+    /// The contents of this line, which is one of those of `line_data`.
+    fn as_str(&self, line_data: &LineData<'a>) -> &'a str {
+        if self.len == LONG_LINE {
+            return line_data.long_lines[self.start as usize];
+        }
+        let start = self.start as usize;
+        &line_data.text[start..start + self.len as usize]
     }
 
-    fn print(&self, writer: &mut impl Write, settings: &GlobalSettings) {
+    /// The part of this line that is compared.
+    fn key(&self, line_data: &LineData<'a>, settings: &GlobalSettings) -> &'a str {
+        Self::key_of(self.as_str(line_data), settings)
+    }
+
+    /// The part of `line` that is compared, which is all of it but with
+    /// `--crlf`.
+    fn key_of<'b>(line: &'b str, settings: &GlobalSettings) -> &'b str {
+        if settings.crlf {
+            strip_crlf_str(line)
+        } else {
+            line
+        }
+    }
+
+    /// The position of this line in its chunk, which is the one of its data
+    /// in `line_data`.
+    fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    fn print(&self, line_data: &LineData<'a>, writer: &mut impl Write, settings: &GlobalSettings) {
+        let line = self.as_str(line_data);
         if settings.zero_terminated && !settings.debug {
-            writer.write_all(self.line.as_bytes()).unwrap();
+            writer.write_all(line.as_bytes()).unwrap();
             writer.write_all(b"\0").unwrap();
         } else if !settings.debug {
-            writer.write_all(self.line.as_bytes()).unwrap();
+            writer.write_all(line.as_bytes()).unwrap();
             writer.write_all(b"\n").unwrap();
         } else {
-            self.print_debug(settings, writer).unwrap();
+            Self::print_debug(line, settings, writer).unwrap();
         }
     }
 
-    /// Writes indicators for the selections this line matched. The original line content is NOT expected
+    /// Writes indicators for the selections the line `text` matched. The original line content is NOT expected
     /// to be already printed.
     fn print_debug(
-        &self,
+        text: &str,
         settings: &GlobalSettings,
         writer: &mut impl Write,
     ) -> std::io::Result<()> {
@@ -559,18 +626,18 @@ impl<'a> Line<'a> {
         // which are not a performance problem in any case. Therefore there aren't any special performance
         // optimizations here.
 
-        let line = self.line.replace('\t', ">");
+        let line = text.replace('\t', ">");
         writeln!(writer, "{line}")?;
 
         let mut fields = vec![];
-        tokenize(self.line, settings.separator, &mut fields);
+        tokenize(text, settings.separator, &mut fields);
         for selector in &settings.selectors {
-            let mut selection = selector.get_range(self.line, Some(&fields));
+            let mut selection = selector.get_range(text, Some(&fields));
             match selector.settings.mode {
                 SortMode::Numeric | SortMode::HumanNumeric => {
                     // find out which range is used for numeric comparisons
                     let (_, num_range) = NumInfo::parse(
-                        &self.line[selection.clone()],
+                        &text[selection.clone()],
                         &NumInfoParseSettings {
                             accept_si_units: selector.settings.mode == SortMode::HumanNumeric,
                             ..Default::default()
@@ -585,14 +652,14 @@ impl<'a> Line<'a> {
                     if num_range != (0..0) {
                         // include a trailing si unit
                         if selector.settings.mode == SortMode::HumanNumeric
-                            && self.line[selection.end..initial_selection.end]
+                            && text[selection.end..initial_selection.end]
                                 .starts_with(&['k', 'K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y'][..])
                         {
                             selection.end += 1;
                         }
 
                         // include leading zeroes, a leading minus or a leading decimal point
-                        while self.line[initial_selection.start..selection.start]
+                        while text[initial_selection.start..selection.start]
                             .ends_with(&['-', '0', '.'][..])
                         {
                             selection.start -= 1;
@@ -600,7 +667,7 @@ impl<'a> Line<'a> {
                     } else {
                         // This was not a valid number.
                         // Report no match at the first non-whitespace character.
-                        let leading_whitespace = self.line[selection.clone()]
+                        let leading_whitespace = text[selection.clone()]
                             .find(|c: char| !c.is_whitespace())
                             .unwrap_or(0);
                         selection.start += leading_whitespace;
@@ -608,7 +675,7 @@ impl<'a> Line<'a> {
                     }
                 }
                 SortMode::GeneralNumeric => {
-                    let initial_selection = &self.line[selection.clone()];
+                    let initial_selection = &text[selection.clone()];
 
                     let leading = get_leading_gen(initial_selection);
 
@@ -617,7 +684,7 @@ impl<'a> Line<'a> {
                     selection.end = selection.start + leading.len();
                 }
                 SortMode::Month => {
-                    let initial_selection = &self.line[selection.clone()];
+                    let initial_selection = &text[selection.clone()];
 
                     let mut month_chars = initial_selection
                         .char_indices()
@@ -676,7 +743,7 @@ impl<'a> Line<'a> {
                     .map_or(true, |selector| selector != &FieldSelector::default()))
         {
             // A last resort comparator is in use, underline the whole line.
-            if self.line.is_empty() {
+            if text.is_empty() {
                 writeln!(writer, "^ no match for key")?;
             } else {
                 writeln!(
@@ -1043,9 +1110,85 @@ fn make_sort_mode_arg(mode: &'static str, short: char, help: &'static str) -> Ar
     arg
 }
 
+/// The parts of an obsolete key position, `FIELD[.CHAR][OPTIONS]` with `-`
+/// or `+` before it, where fields and characters are counted from zero.
+fn parse_obsolete_position(arg: &str) -> Option<(usize, Option<usize>, &str)> {
+    fn number(s: &str) -> Option<(usize, &str)> {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        Some((s[..end].parse().ok()?, &s[end..]))
+    }
+    let (field, rest) = number(&arg[1..])?;
+    let (char, options) = match rest.strip_prefix('.') {
+        Some(rest) => {
+            let (char, options) = number(rest)?;
+            (Some(char), options)
+        }
+        None => (None, rest),
+    };
+    options
+        .chars()
+        .all(|c| "bdfgiMhnRrV".contains(c))
+        .then(|| (field, char, options))
+}
+
+/// Replace the obsolete keys `+POS1 [-POS2]` in `args` with the `-k` options
+/// they stand for.
+///
+/// Like in GNU sort, `+POS1` is a key when followed by `-POS2`, unless
+/// `POSIXLY_CORRECT` is set, and always when `_POSIX2_VERSION` asks for
+/// POSIX.2-1992. Otherwise, it is a file name.
+fn replace_obsolete_keys(args: Vec<String>) -> Vec<String> {
+    let obsolete_usage = posix::obsolete_usage();
+    let posixly_correct = posix::posixly_correct();
+    let mut replaced = Vec::with_capacity(args.len());
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            replaced.push(arg);
+            replaced.extend(args);
+            break;
+        }
+        let start = if arg.starts_with('+') {
+            parse_obsolete_position(&arg)
+        } else {
+            None
+        };
+        let end = args
+            .peek()
+            .filter(|next| next.starts_with('-'))
+            .and_then(|next| parse_obsolete_position(next));
+        let (field, char, options) = match start {
+            Some(start) if obsolete_usage || (end.is_some() && !posixly_correct) => start,
+            _ => {
+                replaced.push(arg);
+                continue;
+            }
+        };
+        let mut key = format!("{}", field + 1);
+        if let Some(char) = char {
+            key.push_str(&format!(".{}", char + 1));
+        }
+        key.push_str(options);
+        if let Some((field, char, options)) = end {
+            // The end is the character before the position, so a position at
+            // the start of a field ends the key with the previous field.
+            match char {
+                Some(char) if char > 0 => key.push_str(&format!(",{}.{}", field + 1, char)),
+                _ if field > 0 => key.push_str(&format!(",{field}")),
+                _ => {}
+            }
+            key.push_str(options);
+            args.next();
+        }
+        replaced.push("-k".to_string());
+        replaced.push(key);
+    }
+    replaced
+}
+
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let args = args.collect_ignore();
+    let args = replace_obsolete_keys(args.collect_ignore());
     let mut settings = GlobalSettings::default();
 
     let matches = match uu_app().try_get_matches_from(args) {
@@ -1133,7 +1276,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             .map(|s| s.as_str())
             == Some("random")
     {
-        settings.salt = Some(get_rand_string());
+        settings.salt = Some(get_rand_string(&mut random_source(&matches)?)?);
         SortMode::Random
     } else {
         SortMode::Default
@@ -1180,20 +1323,27 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     }
 
     settings.zero_terminated = matches.get_flag(options::ZERO_TERMINATED);
+    settings.crlf = matches.get_flag(lines::arguments::OPT_CRLF);
     settings.merge = matches.get_flag(options::MERGE);
 
     settings.check = matches.contains_id(options::check::CHECK);
-    if matches.get_flag(options::check::CHECK_SILENT)
-        || matches!(
-            matches
-                .get_one::<String>(options::check::CHECK)
-                .map(|s| s.as_str()),
-            Some(options::check::SILENT | options::check::QUIET)
-        )
-    {
+    let check_silent = matches!(
+        matches
+            .get_one::<String>(options::check::CHECK)
+            .map(|s| s.as_str()),
+        Some(options::check::SILENT | options::check::QUIET)
+    );
+    if matches.get_flag(options::check::CHECK_SILENT) {
+        // Like with GNU sort, -C is --check=quiet, which can't be combined
+        // with a check that diagnoses the disorder.
+        if settings.check && !check_silent {
+            return Err(USimpleError::new(2, "options '-cC' are incompatible"));
+        }
         settings.check_silent = true;
         settings.check = true;
-    };
+    } else if check_silent {
+        settings.check_silent = true;
+    }
 
     settings.ignore_case = matches.get_flag(options::IGNORE_CASE);
 
@@ -1207,9 +1357,13 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         /* if no file, default to stdin */
         files.push("-".to_string().into());
     } else if settings.check && files.len() != 1 {
-        return Err(UUsageError::new(
+        return Err(USimpleError::new(
             2,
-            format!("extra operand {} not allowed with -c", files[1].quote()),
+            format!(
+                "extra operand {} not allowed with -{}",
+                files[1].quote(),
+                if settings.check_silent { 'C' } else { 'c' }
+            ),
         ));
     }
 
@@ -1242,7 +1396,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         for value in values {
             let selector = FieldSelector::parse(value, &settings)?;
             if selector.settings.mode == SortMode::Random && settings.salt.is_none() {
-                settings.salt = Some(get_rand_string());
+                settings.salt = Some(get_rand_string(&mut random_source(&matches)?)?);
             }
             settings.selectors.push(selector);
         }
@@ -1352,6 +1506,7 @@ pub fn uu_app() -> Command {
             'R',
             "shuffle in random order",
         ))
+        .arg(uucore::rand::arguments::random_source())
         .arg(
             Arg::new(options::DICTIONARY_ORDER)
                 .short('d')
@@ -1475,6 +1630,7 @@ pub fn uu_app() -> Command {
                 .help("line delimiter is NUL, not newline")
                 .action(ArgAction::SetTrue),
         )
+        .arg(lines::arguments::crlf())
         .arg(
             Arg::new(options::PARALLEL)
                 .long(options::PARALLEL)
@@ -1548,7 +1704,7 @@ fn exec(
             check::check(files.first().unwrap(), settings)
         }
     } else {
-        let mut lines = files.iter().map(open);
+        let mut lines = files.iter().map(|file| open_input(file, settings));
         ext_sort(&mut lines, settings, output, tmp_dir)
     }
 }
@@ -1574,13 +1730,16 @@ fn compare_by<'a>(
     for selector in &global_settings.selectors {
         let (a_str, b_str) = if !selector.needs_selection {
             // We can select the whole line.
-            (a.line, b.line)
+            (
+                a.key(a_line_data, global_settings),
+                b.key(b_line_data, global_settings),
+            )
         } else {
             let selections = (
                 a_line_data.selections
-                    [a.index * global_settings.precomputed.selections_per_line + selection_index],
+                    [a.index() * global_settings.precomputed.selections_per_line + selection_index],
                 b_line_data.selections
-                    [b.index * global_settings.precomputed.selections_per_line + selection_index],
+                    [b.index() * global_settings.precomputed.selections_per_line + selection_index],
             );
             selection_index += 1;
             selections
@@ -1607,25 +1766,25 @@ fn compare_by<'a>(
             }
             SortMode::Numeric => {
                 let a_num_info = &a_line_data.num_infos
-                    [a.index * global_settings.precomputed.num_infos_per_line + num_info_index];
+                    [a.index() * global_settings.precomputed.num_infos_per_line + num_info_index];
                 let b_num_info = &b_line_data.num_infos
-                    [b.index * global_settings.precomputed.num_infos_per_line + num_info_index];
+                    [b.index() * global_settings.precomputed.num_infos_per_line + num_info_index];
                 num_info_index += 1;
                 numeric_str_cmp((a_str, a_num_info), (b_str, b_num_info))
             }
             SortMode::HumanNumeric => {
                 let a_num_info = &a_line_data.num_infos
-                    [a.index * global_settings.precomputed.num_infos_per_line + num_info_index];
+                    [a.index() * global_settings.precomputed.num_infos_per_line + num_info_index];
                 let b_num_info = &b_line_data.num_infos
-                    [b.index * global_settings.precomputed.num_infos_per_line + num_info_index];
+                    [b.index() * global_settings.precomputed.num_infos_per_line + num_info_index];
                 num_info_index += 1;
                 human_numeric_str_cmp((a_str, a_num_info), (b_str, b_num_info))
             }
             SortMode::GeneralNumeric => {
                 let a_float = &a_line_data.parsed_floats
-                    [a.index * global_settings.precomputed.floats_per_line + parsed_float_index];
+                    [a.index() * global_settings.precomputed.floats_per_line + parsed_float_index];
                 let b_float = &b_line_data.parsed_floats
-                    [b.index * global_settings.precomputed.floats_per_line + parsed_float_index];
+                    [b.index() * global_settings.precomputed.floats_per_line + parsed_float_index];
                 parsed_float_index += 1;
                 general_numeric_compare(a_float, b_float)
             }
@@ -1651,7 +1810,11 @@ fn compare_by<'a>(
     {
         Ordering::Equal
     } else {
-        a.line.cmp(b.line)
+        // The lines that only differ by what `--crlf` ignores still go in
+        // the same order every time.
+        a.key(a_line_data, global_settings)
+            .cmp(b.key(b_line_data, global_settings))
+            .then_with(|| a.as_str(a_line_data).cmp(b.as_str(b_line_data)))
     };
 
     if global_settings.reverse {
@@ -1753,8 +1916,27 @@ fn general_numeric_compare(a: &GeneralF64ParseResult, b: &GeneralF64ParseResult)
     a.partial_cmp(b).unwrap()
 }
 
-fn get_rand_string() -> [u8; 16] {
-    thread_rng().sample(rand::distributions::Standard)
+/// The source of the random order of `-R`: the file of `--random-source`,
+/// which is only opened if it is needed, or the generator of the system.
+fn random_source(matches: &ArgMatches) -> UResult<RandomSource> {
+    match random_source_file(matches) {
+        Some(path) => RandomSource::open(path).map_err(|error| {
+            SortError::OpenFailed {
+                path: path.to_string_lossy().into_owned(),
+                error,
+            }
+            .into()
+        }),
+        None => Ok(RandomSource::os()),
+    }
+}
+
+fn get_rand_string(source: &mut RandomSource) -> UResult<[u8; 16]> {
+    let mut salt = [0; 16];
+    source
+        .read(&mut salt)
+        .map_err(|error| SortError::RandomSourceFailed { error })?;
+    Ok(salt)
 }
 
 fn get_hash<T: Hash>(t: &T) -> u64 {
@@ -1830,14 +2012,14 @@ fn month_compare(a: &str, b: &str) -> Ordering {
     }
 }
 
-fn print_sorted<'a, T: Iterator<Item = &'a Line<'a>>>(
+fn print_sorted<'a, T: Iterator<Item = (&'a Line<'a>, &'a LineData<'a>)>>(
     iter: T,
     settings: &GlobalSettings,
     output: Output,
 ) {
     let mut writer = output.into_write();
-    for line in iter {
-        line.print(&mut writer, settings);
+    for (line, line_data) in iter {
+        line.print(line_data, &mut writer, settings);
     }
 }
 
@@ -1860,6 +2042,17 @@ fn open(path: impl AsRef<OsStr>) -> UResult<Box<dyn Read + Send>> {
     }
 }
 
+/// Open an input file, without the byte order mark that starts it with
+/// `--crlf`.
+fn open_input(path: impl AsRef<OsStr>, settings: &GlobalSettings) -> UResult<Box<dyn Read + Send>> {
+    let reader = open(path)?;
+    if settings.crlf {
+        Ok(Box::new(skip_byte_order_mark(reader)))
+    } else {
+        Ok(reader)
+    }
+}
+
 fn format_error_message(error: &ParseSizeError, s: &str, option: &str) -> String {
     // NOTE:
     // GNU's sort echos affected flag, -S or --buffer-size, depending user's selection
@@ -1867,6 +2060,9 @@ fn format_error_message(error: &ParseSizeError, s: &str, option: &str) -> String
         ParseSizeError::InvalidSuffix(_) => {
             format!("invalid suffix in --{} argument {}", option, s.quote())
         }
+        ParseSizeError::AmbiguousSuffix(reason) => {
+            format!("invalid suffix in --{option} argument {reason}")
+        }
         ParseSizeError::ParseFailure(_) => format!("invalid --{} argument {}", option, s.quote()),
         ParseSizeError::SizeTooBig(_) => format!("--{} argument {} too large", option, s.quote()),
     }
@@ -1883,6 +2079,28 @@ mod tests {
         buffer
     }
 
+    #[test]
+    fn test_replace_obsolete_keys() {
+        fn replace(args: &[&str]) -> Vec<String> {
+            replace_obsolete_keys(args.iter().map(|s| s.to_string()).collect())
+        }
+        assert_eq!(replace(&["sort", "+1", "-2"]), ["sort", "-k", "2,2"]);
+        assert_eq!(
+            replace(&["sort", "+0.2n", "-1.3r"]),
+            ["sort", "-k", "1.3n,2.3r"]
+        );
+        assert_eq!(
+            replace(&["sort", "+2", "-0", "f"]),
+            ["sort", "-k", "3", "f"]
+        );
+        assert_eq!(replace(&["sort", "+1", "file"]), ["sort", "+1", "file"]);
+        assert_eq!(replace(&["sort", "+1x", "-2"]), ["sort", "+1x", "-2"]);
+        assert_eq!(
+            replace(&["sort", "--", "+1", "-2"]),
+            ["sort", "--", "+1", "-2"]
+        );
+    }
+
     #[test]
     fn test_get_hash() {
         let a = "Ted".to_string();
@@ -1894,7 +2112,7 @@ mod tests {
     fn test_random_shuffle() {
         let a = "Ted";
         let b = "Ted";
-        let c = get_rand_string();
+        let c = get_rand_string(&mut RandomSource::from_seed(1)).unwrap();
 
         assert_eq!(Ordering::Equal, random_shuffle(a, b, &c));
     }
@@ -1918,7 +2136,7 @@ mod tests {
     fn test_random_compare() {
         let a = "9";
         let b = "9";
-        let c = get_rand_string();
+        let c = get_rand_string(&mut RandomSource::from_seed(1)).unwrap();
 
         assert_eq!(Ordering::Equal, random_shuffle(a, b, &c));
     }
@@ -1962,7 +2180,26 @@ mod tests {
     fn test_line_size() {
         // We should make sure to not regress the size of the Line struct because
         // it is unconditional overhead for every line we sort.
-        assert_eq!(std::mem::size_of::<Line>(), 24);
+        assert_eq!(std::mem::size_of::<Line>(), 12);
+    }
+
+    #[test]
+    fn test_long_line() {
+        // The lines of more than 4 GiB are kept aside rather than as offsets.
+        let line_data = LineData {
+            text: "",
+            selections: vec![],
+            num_infos: vec![],
+            parsed_floats: vec![],
+            long_lines: vec!["long"],
+        };
+        let line = Line {
+            start: 0,
+            len: LONG_LINE,
+            index: 0,
+            text: PhantomData,
+        };
+        assert_eq!(line.as_str(&line_data), "long");
     }
 
     #[test]
@@ -25,6 +25,7 @@ use uucore::display::Quotable;
 use uucore::error::{FromIo, UIoError, UResult, USimpleError, UUsageError};
 use uucore::format_usage;
 use uucore::parse_size::{parse_size, ParseSizeError};
+use uucore::posix;
 use uucore::uio_error;
 
 static OPT_BYTES: &str = "bytes";
@@ -55,6 +56,15 @@ const AFTER_HELP: &str = "\
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
+    // The obsolete `-NUMBER` stands for `-l NUMBER`.
+    let args = posix::replace_obsolete_args(args, |arg| {
+        let (lines, rest) = posix::obsolete_number(arg, '-')?;
+        let mut options = vec!["-l".to_string(), lines.to_string()];
+        if !rest.is_empty() {
+            options.push(format!("-{rest}"));
+        }
+        Some(options)
+    });
     let matches = uu_app().try_get_matches_from(args)?;
     match Settings::from(&matches) {
         Ok(settings) => split(&settings),
@@ -1262,6 +1272,10 @@ where
 }
 
 fn split(settings: &Settings) -> UResult<()> {
+    // A filter that stops reading its input early is not an error.
+    if settings.filter.is_some() {
+        uucore::broken_pipe::ignore();
+    }
     let mut reader = BufReader::new(if settings.input == "-" {
         Box::new(stdin()) as Box<dyn Read>
     } else {
@@ -7,20 +7,26 @@
 
 use clap::builder::ValueParser;
 use uucore::display::Quotable;
+#[cfg(unix)]
+use uucore::entries;
 use uucore::error::{FromIo, UResult, USimpleError};
+#[cfg(unix)]
 use uucore::fs::display_permissions;
-use uucore::fsext::{
-    pretty_filetype, pretty_fstype, pretty_time, read_fs_list, statfs, BirthTime, FsMeta,
-};
+#[cfg(unix)]
+use uucore::fsext::{find_mount_point, pretty_filetype, pretty_seconds, pretty_time, BirthTime};
+use uucore::fsext::{read_fs_list, statfs, FsMeta, MountInfo};
+#[cfg(unix)]
 use uucore::libc::mode_t;
-use uucore::{entries, format_usage, show_error, show_warning};
+use uucore::{format_usage, show_error, show_warning};
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::borrow::Cow;
-use std::convert::AsRef;
 use std::ffi::{OsStr, OsString};
+#[cfg(unix)]
 use std::fs;
+#[cfg(unix)]
 use std::os::unix::fs::{FileTypeExt, MetadataExt};
+#[cfg(unix)]
 use std::os::unix::prelude::OsStrExt;
 use std::path::Path;
 
@@ -89,6 +95,9 @@ pub enum OutputType {
     Unsigned(u64),
     UnsignedHex(u64),
     UnsignedOct(u32),
+    /// Seconds and nanoseconds since the epoch, shown with as many digits
+    /// after the decimal point as the precision.
+    Seconds(i64, i64),
     Unknown,
 }
 
@@ -192,12 +201,15 @@ fn group_num(s: &str) -> Cow<str> {
     res.into()
 }
 
+// Only the file system status is available on Windows, which leaves some of
+// the state unused there.
+#[cfg_attr(windows, allow(dead_code))]
 struct Stater {
     follow: bool,
     show_fs: bool,
     from_user: bool,
     files: Vec<OsString>,
-    mount_list: Option<Vec<String>>,
+    mount_list: Option<Vec<MountInfo>>,
     default_tokens: Vec<Token>,
     default_dev_tokens: Vec<Token>,
 }
@@ -294,6 +306,27 @@ fn print_it(output: &OutputType, flags: Flags, width: usize, precision: Option<u
             );
             pad_and_print(&s, flags.left, width, padding_char);
         }
+        OutputType::Seconds(sec, nsec) => match precision {
+            Some(precision) => {
+                let seconds = pretty_seconds(*sec, *nsec, precision);
+                let prefix = if seconds.starts_with('-') {
+                    ""
+                } else if flags.sign {
+                    "+"
+                } else if flags.space {
+                    " "
+                } else {
+                    ""
+                };
+                let padding = if flags.zero && !flags.left {
+                    Padding::Zero
+                } else {
+                    Padding::Space
+                };
+                pad_and_print(&format!("{prefix}{seconds}"), flags.left, width, padding);
+            }
+            None => print_it(&OutputType::Integer(*sec), flags, width, None),
+        },
         OutputType::Unknown => print!("?"),
     }
 }
@@ -358,6 +391,11 @@ impl Stater {
                                 }
                                 j += offset;
                             }
+                            // The seconds since the epoch are shown to
+                            // the nanosecond when the precision is only '.'.
+                            None if matches!(chars[j], 'W' | 'X' | 'Y' | 'Z') => {
+                                precision = Some(9);
+                            }
                             None => precision = Some(0),
                         }
                         check_bound(format_str, bound, old, j)?;
@@ -456,15 +494,10 @@ impl Stater {
             // mount points aren't displayed when showing filesystem information
             None
         } else {
-            let mut mount_list = read_fs_list()
-                .map_err_context(|| "cannot read table of mounted file systems".into())?
-                .iter()
-                .map(|mi| mi.mount_dir.clone())
-                .collect::<Vec<String>>();
-            // Reverse sort. The longer comes first.
-            mount_list.sort();
-            mount_list.reverse();
-            Some(mount_list)
+            Some(
+                read_fs_list()
+                    .map_err_context(|| "cannot read table of mounted file systems".into())?,
+            )
         };
 
         Ok(Self {
@@ -478,24 +511,11 @@ impl Stater {
         })
     }
 
-    fn find_mount_point<P: AsRef<Path>>(&self, p: P) -> Option<String> {
-        let path = p.as_ref().canonicalize().ok()?;
-
-        for root in self.mount_list.as_ref()? {
-            if path.starts_with(root) {
-                return Some(root.clone());
-            }
-        }
-        None
-    }
-
     fn exec(&self) -> i32 {
-        let mut stdin_is_fifo = false;
-        if cfg!(unix) {
-            if let Ok(md) = fs::metadata("/dev/stdin") {
-                stdin_is_fifo = md.file_type().is_fifo();
-            }
-        }
+        #[cfg(unix)]
+        let stdin_is_fifo = fs::metadata("/dev/stdin").map_or(false, |md| md.file_type().is_fifo());
+        #[cfg(not(unix))]
+        let stdin_is_fifo = false;
 
         let mut ret = 0;
         for f in &self.files {
@@ -517,151 +537,12 @@ impl Stater {
         };
 
         if !self.show_fs {
-            let result = if self.follow || stdin_is_fifo && display_name == "-" {
-                fs::metadata(&file)
-            } else {
-                fs::symlink_metadata(&file)
-            };
-            match result {
-                Ok(meta) => {
-                    let file_type = meta.file_type();
-                    let tokens = if self.from_user
-                        || !(file_type.is_char_device() || file_type.is_block_device())
-                    {
-                        &self.default_tokens
-                    } else {
-                        &self.default_dev_tokens
-                    };
-
-                    for t in tokens.iter() {
-                        match *t {
-                            Token::Char(c) => print!("{c}"),
-                            Token::Directive {
-                                flag,
-                                width,
-                                precision,
-                                format,
-                            } => {
-                                let output = match format {
-                                    // access rights in octal
-                                    'a' => OutputType::UnsignedOct(0o7777 & meta.mode()),
-                                    // access rights in human readable form
-                                    'A' => OutputType::Str(display_permissions(&meta, true)),
-                                    // number of blocks allocated (see %B)
-                                    'b' => OutputType::Unsigned(meta.blocks()),
-
-                                    // the size in bytes of each block reported by %b
-                                    // FIXME: blocksize differs on various platform
-                                    // See coreutils/gnulib/lib/stat-size.h ST_NBLOCKSIZE // spell-checker:disable-line
-                                    'B' => OutputType::Unsigned(512),
-
-                                    // device number in decimal
-                                    'd' => OutputType::Unsigned(meta.dev()),
-                                    // device number in hex
-                                    'D' => OutputType::UnsignedHex(meta.dev()),
-                                    // raw mode in hex
-                                    'f' => OutputType::UnsignedHex(meta.mode() as u64),
-                                    // file type
-                                    'F' => OutputType::Str(
-                                        pretty_filetype(meta.mode() as mode_t, meta.len())
-                                            .to_owned(),
-                                    ),
-                                    // group ID of owner
-                                    'g' => OutputType::Unsigned(meta.gid() as u64),
-                                    // group name of owner
-                                    'G' => {
-                                        let group_name = entries::gid2grp(meta.gid())
-                                            .unwrap_or_else(|_| "UNKNOWN".to_owned());
-                                        OutputType::Str(group_name)
-                                    }
-                                    // number of hard links
-                                    'h' => OutputType::Unsigned(meta.nlink()),
-                                    // inode number
-                                    'i' => OutputType::Unsigned(meta.ino()),
-                                    // mount point
-                                    'm' => OutputType::Str(self.find_mount_point(&file).unwrap()),
-                                    // file name
-                                    'n' => OutputType::Str(display_name.to_string()),
-                                    // quoted file name with dereference if symbolic link
-                                    'N' => {
-                                        let file_name = if file_type.is_symlink() {
-                                            let dst = match fs::read_link(&file) {
-                                                Ok(path) => path,
-                                                Err(e) => {
-                                                    println!("{e}");
-                                                    return 1;
-                                                }
-                                            };
-                                            format!("{} -> {}", display_name.quote(), dst.quote())
-                                        } else {
-                                            display_name.to_string()
-                                        };
-                                        OutputType::Str(file_name)
-                                    }
-                                    // optimal I/O transfer size hint
-                                    'o' => OutputType::Unsigned(meta.blksize()),
-                                    // total size, in bytes
-                                    's' => OutputType::Integer(meta.len() as i64),
-                                    // major device type in hex, for character/block device special
-                                    // files
-                                    't' => OutputType::UnsignedHex(meta.rdev() >> 8),
-                                    // minor device type in hex, for character/block device special
-                                    // files
-                                    'T' => OutputType::UnsignedHex(meta.rdev() & 0xff),
-                                    // user ID of owner
-                                    'u' => OutputType::Unsigned(meta.uid() as u64),
-                                    // user name of owner
-                                    'U' => {
-                                        let user_name = entries::uid2usr(meta.uid())
-                                            .unwrap_or_else(|_| "UNKNOWN".to_owned());
-                                        OutputType::Str(user_name)
-                                    }
-
-                                    // time of file birth, human-readable; - if unknown
-                                    'w' => OutputType::Str(meta.pretty_birth()),
-
-                                    // time of file birth, seconds since Epoch; 0 if unknown
-                                    'W' => OutputType::Unsigned(meta.birth()),
-
-                                    // time of last access, human-readable
-                                    'x' => OutputType::Str(pretty_time(
-                                        meta.atime(),
-                                        meta.atime_nsec(),
-                                    )),
-                                    // time of last access, seconds since Epoch
-                                    'X' => OutputType::Integer(meta.atime()),
-                                    // time of last data modification, human-readable
-                                    'y' => OutputType::Str(pretty_time(
-                                        meta.mtime(),
-                                        meta.mtime_nsec(),
-                                    )),
-                                    // time of last data modification, seconds since Epoch
-                                    'Y' => OutputType::Integer(meta.mtime()),
-                                    // time of last status change, human-readable
-                                    'z' => OutputType::Str(pretty_time(
-                                        meta.ctime(),
-                                        meta.ctime_nsec(),
-                                    )),
-                                    // time of last status change, seconds since Epoch
-                                    'Z' => OutputType::Integer(meta.ctime()),
-
-                                    _ => OutputType::Unknown,
-                                };
-                                print_it(&output, flag, width, precision);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    show_error!("cannot stat {}: {}", display_name.quote(), e);
-                    return 1;
-                }
-            }
+            self.do_stat_file(&file, &display_name, stdin_is_fifo)
         } else {
             #[cfg(unix)]
             let p = file.as_bytes();
             #[cfg(not(unix))]
-            let p = file.into_string().unwrap();
+            let p = &file;
             match statfs(p) {
                 Ok(meta) => {
                     let tokens = &self.default_tokens;
@@ -699,7 +580,7 @@ impl Stater {
                                     // file system type in hex
                                     't' => OutputType::UnsignedHex(meta.fs_type() as u64),
                                     // file system type in human readable form
-                                    'T' => OutputType::Str(pretty_fstype(meta.fs_type()).into()),
+                                    'T' => OutputType::Str(meta.fs_type_name().into()),
                                     _ => OutputType::Unknown,
                                 };
 
@@ -717,8 +598,178 @@ impl Stater {
                     return 1;
                 }
             }
+            0
+        }
+    }
+
+    #[cfg(unix)]
+    fn do_stat_file(&self, file: &OsStr, display_name: &str, stdin_is_fifo: bool) -> i32 {
+        let result = if self.follow || stdin_is_fifo && display_name == "-" {
+            fs::metadata(file)
+        } else {
+            fs::symlink_metadata(file)
+        };
+        let mut ret = 0;
+        match result {
+            Ok(meta) => {
+                let file_type = meta.file_type();
+                let tokens = if self.from_user
+                    || !(file_type.is_char_device() || file_type.is_block_device())
+                {
+                    &self.default_tokens
+                } else {
+                    &self.default_dev_tokens
+                };
+
+                for t in tokens.iter() {
+                    match *t {
+                        Token::Char(c) => print!("{c}"),
+                        Token::Directive {
+                            flag,
+                            width,
+                            precision,
+                            format,
+                        } => {
+                            let output = match format {
+                                // access rights in octal
+                                'a' => OutputType::UnsignedOct(0o7777 & meta.mode()),
+                                // access rights in human readable form
+                                'A' => OutputType::Str(display_permissions(&meta, true)),
+                                // number of blocks allocated (see %B)
+                                'b' => OutputType::Unsigned(meta.blocks()),
+
+                                // the size in bytes of each block reported by %b
+                                // FIXME: blocksize differs on various platform
+                                // See coreutils/gnulib/lib/stat-size.h ST_NBLOCKSIZE // spell-checker:disable-line
+                                'B' => OutputType::Unsigned(512),
+
+                                // device number in decimal
+                                'd' => OutputType::Unsigned(meta.dev()),
+                                // device number in hex
+                                'D' => OutputType::UnsignedHex(meta.dev()),
+                                // raw mode in hex
+                                'f' => OutputType::UnsignedHex(meta.mode() as u64),
+                                // file type
+                                'F' => OutputType::Str(
+                                    pretty_filetype(meta.mode() as mode_t, meta.len()).to_owned(),
+                                ),
+                                // group ID of owner
+                                'g' => OutputType::Unsigned(meta.gid() as u64),
+                                // group name of owner
+                                'G' => {
+                                    let group_name = entries::gid2grp(meta.gid())
+                                        .unwrap_or_else(|_| "UNKNOWN".to_owned());
+                                    OutputType::Str(group_name)
+                                }
+                                // number of hard links
+                                'h' => OutputType::Unsigned(meta.nlink()),
+                                // inode number
+                                'i' => OutputType::Unsigned(meta.ino()),
+                                // mount point
+                                'm' => {
+                                    let mounts = self.mount_list.as_deref().unwrap_or_default();
+                                    match find_mount_point(file, self.follow, mounts) {
+                                        Ok(mount_point) => OutputType::Str(
+                                            mount_point.to_string_lossy().into_owned(),
+                                        ),
+                                        Err(e) => {
+                                            show_error!(
+                                                "cannot find the mount point of {}: {}",
+                                                display_name.quote(),
+                                                e
+                                            );
+                                            ret = 1;
+                                            OutputType::Unknown
+                                        }
+                                    }
+                                }
+                                // file name
+                                'n' => OutputType::Str(display_name.to_string()),
+                                // quoted file name with dereference if symbolic link
+                                'N' => {
+                                    let file_name = if file_type.is_symlink() {
+                                        let dst = match fs::read_link(file) {
+                                            Ok(path) => path,
+                                            Err(e) => {
+                                                println!("{e}");
+                                                return 1;
+                                            }
+                                        };
+                                        format!("{} -> {}", display_name.quote(), dst.quote())
+                                    } else {
+                                        display_name.to_string()
+                                    };
+                                    OutputType::Str(file_name)
+                                }
+                                // optimal I/O transfer size hint
+                                'o' => OutputType::Unsigned(meta.blksize()),
+                                // total size, in bytes
+                                's' => OutputType::Integer(meta.len() as i64),
+                                // major device type in hex, for character/block device special
+                                // files
+                                't' => OutputType::UnsignedHex(meta.rdev() >> 8),
+                                // minor device type in hex, for character/block device special
+                                // files
+                                'T' => OutputType::UnsignedHex(meta.rdev() & 0xff),
+                                // user ID of owner
+                                'u' => OutputType::Unsigned(meta.uid() as u64),
+                                // user name of owner
+                                'U' => {
+                                    let user_name = entries::uid2usr(meta.uid())
+                                        .unwrap_or_else(|_| "UNKNOWN".to_owned());
+                                    OutputType::Str(user_name)
+                                }
+
+                                // time of file birth, human-readable; - if unknown
+                                'w' => OutputType::Str(meta.pretty_birth()),
+
+                                // time of file birth, seconds since Epoch; 0 if unknown
+                                'W' => {
+                                    let (sec, nsec) = meta.birth().unwrap_or_default();
+                                    OutputType::Seconds(sec as i64, nsec.into())
+                                }
+
+                                // time of last access, human-readable
+                                'x' => {
+                                    OutputType::Str(pretty_time(meta.atime(), meta.atime_nsec()))
+                                }
+                                // time of last access, seconds since Epoch
+                                'X' => OutputType::Seconds(meta.atime(), meta.atime_nsec()),
+                                // time of last data modification, human-readable
+                                'y' => {
+                                    OutputType::Str(pretty_time(meta.mtime(), meta.mtime_nsec()))
+                                }
+                                // time of last data modification, seconds since Epoch
+                                'Y' => OutputType::Seconds(meta.mtime(), meta.mtime_nsec()),
+                                // time of last status change, human-readable
+                                'z' => {
+                                    OutputType::Str(pretty_time(meta.ctime(), meta.ctime_nsec()))
+                                }
+                                // time of last status change, seconds since Epoch
+                                'Z' => OutputType::Seconds(meta.ctime(), meta.ctime_nsec()),
+
+                                _ => OutputType::Unknown,
+                            };
+                            print_it(&output, flag, width, precision);
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                show_error!("cannot stat {}: {}", display_name.quote(), e);
+                return 1;
+            }
         }
-        0
+        ret
+    }
+
+    #[cfg(windows)]
+    fn do_stat_file(&self, _file: &OsStr, display_name: &str, _stdin_is_fifo: bool) -> i32 {
+        show_error!(
+            "cannot stat {}: only file system status (-f) is supported on Windows",
+            display_name.quote()
+        );
+        1
     }
 
     fn default_format(show_fs: bool, terse: bool, show_dev_type: bool) -> String {
@@ -809,9 +860,7 @@ for details about the options it supports.
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let stater = Stater::new(&matches)?;
     let exit_status = stater.exec();
@@ -827,6 +876,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::DEREFERENCE)
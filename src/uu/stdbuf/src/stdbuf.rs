@@ -17,7 +17,7 @@ use tempfile::tempdir;
 use tempfile::TempDir;
 use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
 use uucore::parse_size::parse_size;
-use uucore::{crash, format_usage};
+use uucore::{crash, exec, format_usage};
 
 static ABOUT: &str =
     "Run COMMAND, with modified buffering operations for its standard streams.\n\n\
@@ -161,7 +161,8 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let options = ProgramOptions::try_from(&matches).map_err(|e| UUsageError::new(125, e.0))?;
 
     let mut command_values = matches.get_many::<String>(options::COMMAND).unwrap();
-    let mut command = process::Command::new(command_values.next().unwrap());
+    let program = command_values.next().unwrap();
+    let mut command = process::Command::new(program);
     let command_params: Vec<&str> = command_values.map(|s| s.as_ref()).collect();
 
     let mut tmp_dir = tempdir().unwrap();
@@ -174,7 +175,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
     let mut process = command
         .spawn()
-        .map_err_context(|| "failed to execute process".to_string())?;
+        .map_err(|err| exec::run_error(program.as_str(), err))?;
     let status = process.wait().map_err_context(String::new)?;
     match status.code() {
         Some(i) => {
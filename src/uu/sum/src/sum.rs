@@ -11,6 +11,7 @@ use clap::{crate_version, Arg, ArgAction, Command};
 use std::fs::File;
 use std::io::{stdin, Read};
 use std::path::Path;
+use uucore::checksum::{self, Algorithm};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError};
 use uucore::{format_usage, show};
@@ -26,51 +27,20 @@ const fn div_ceil(a: usize, b: usize) -> usize {
     (a + b - 1) / b
 }
 
-fn bsd_sum(mut reader: Box<dyn Read>) -> (usize, u16) {
-    let mut buf = [0; 4096];
-    let mut bytes_read = 0;
-    let mut checksum: u16 = 0;
-    loop {
-        match reader.read(&mut buf) {
-            Ok(n) if n != 0 => {
-                bytes_read += n;
-                for &byte in buf[..n].iter() {
-                    checksum = (checksum >> 1) + ((checksum & 1) << 15);
-                    checksum = checksum.wrapping_add(u16::from(byte));
-                }
-            }
-            _ => break,
-        }
-    }
-
-    // Report blocks read in terms of 1024-byte blocks.
-    let blocks_read = div_ceil(bytes_read, 1024);
-    (blocks_read, checksum)
-}
-
-fn sysv_sum(mut reader: Box<dyn Read>) -> (usize, u16) {
-    let mut buf = [0; 4096];
-    let mut bytes_read = 0;
-    let mut ret = 0u32;
-
-    loop {
-        match reader.read(&mut buf) {
-            Ok(n) if n != 0 => {
-                bytes_read += n;
-                for &byte in buf[..n].iter() {
-                    ret = ret.wrapping_add(u32::from(byte));
-                }
-            }
-            _ => break,
-        }
-    }
-
-    ret = (ret & 0xffff) + (ret >> 16);
-    ret = (ret & 0xffff) + (ret >> 16);
-
-    // Report blocks read in terms of 512-byte blocks.
-    let blocks_read = div_ceil(bytes_read, 512);
-    (blocks_read, ret as u16)
+/// Compute the checksum of `reader`, the contents of the file `name`, with
+/// `algo` (BSD or System V), along with the number of `block_size`-byte
+/// blocks read.
+fn sum(
+    name: &str,
+    mut reader: Box<dyn Read>,
+    algo: Algorithm,
+    block_size: usize,
+) -> UResult<(usize, String)> {
+    let mut digest = algo.create_digest();
+    let bytes_read = checksum::digest_reader(&mut *digest, &mut reader, true)
+        .map_err_context(|| name.maybe_quote().to_string())?;
+    let blocks_read = div_ceil(bytes_read as usize, block_size);
+    Ok((blocks_read, digest.result_str()))
 }
 
 fn open(name: &str) -> UResult<Box<dyn Read>> {
@@ -127,16 +97,25 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
                 continue;
             }
         };
-        let (blocks, sum) = if sysv {
-            sysv_sum(reader)
+        // Report blocks read in terms of 512-byte blocks for System V and
+        // 1024-byte blocks for BSD.
+        let result = if sysv {
+            sum(file, reader, Algorithm::SysV, 512)
         } else {
-            bsd_sum(reader)
+            sum(file, reader, Algorithm::Bsd, 1024)
+        };
+        let (blocks, sum) = match result {
+            Ok(r) => r,
+            Err(error) => {
+                show!(error);
+                continue;
+            }
         };
 
         if print_names {
-            println!("{sum:0width$} {blocks:width$} {file}");
+            println!("{sum:0>width$} {blocks:width$} {file}");
         } else {
-            println!("{sum:0width$} {blocks:width$}");
+            println!("{sum:0>width$} {blocks:width$}");
         }
     }
     Ok(())
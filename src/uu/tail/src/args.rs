@@ -16,7 +16,7 @@ use std::ffi::OsString;
 use std::time::Duration;
 use uucore::error::{UResult, USimpleError, UUsageError};
 use uucore::parse_size::{parse_size, ParseSizeError};
-use uucore::{format_usage, show_warning};
+use uucore::{format_usage, posix, show_warning};
 
 const ABOUT: &str = "\
     Print the last 10 lines of each FILE to standard output.\n\
@@ -306,7 +306,13 @@ pub fn arg_iterate<'a>(
     let first = args.next().unwrap();
     if let Some(second) = args.next() {
         if let Some(s) = second.to_str() {
-            match parse::parse_obsolete(s) {
+            // Since POSIX.1-2001, `+NUM` is a file name.
+            let obsolete = if s.starts_with('+') && !posix::obsolete_usage() {
+                None
+            } else {
+                parse::parse_obsolete(s)
+            };
+            match obsolete {
                 Some(Ok(iter)) => Ok(Box::new(vec![first].into_iter().chain(iter).chain(args))),
                 Some(Err(e)) => Err(UUsageError::new(
                     1,
@@ -12,9 +12,12 @@ pub enum ParseError {
 }
 /// Parses obsolete syntax
 /// tail -NUM\[kmzv\] // spell-checker:disable-line
+/// tail +NUM\[kmzv\] // spell-checker:disable-line
 pub fn parse_obsolete(src: &str) -> Option<Result<impl Iterator<Item = OsString>, ParseError>> {
     let mut chars = src.char_indices();
-    if let Some((_, '-')) = chars.next() {
+    if let Some((_, sign @ ('-' | '+'))) = chars.next() {
+        // Counting from the start keeps the sign in the option-argument.
+        let from_start = if sign == '+' { "+" } else { "" };
         let mut num_end = 0usize;
         let mut has_num = false;
         let mut last_char = 0 as char;
@@ -78,10 +81,10 @@ pub fn parse_obsolete(src: &str) -> Option<Result<impl Iterator<Item = OsString>
                             Some(n) => n,
                             None => return Some(Err(ParseError::Overflow)),
                         };
-                        options.push(OsString::from(format!("{num}")));
+                        options.push(OsString::from(format!("{from_start}{num}")));
                     } else {
                         options.push(OsString::from("-n"));
-                        options.push(OsString::from(format!("{num}")));
+                        options.push(OsString::from(format!("{from_start}{num}")));
                     }
                     Some(Ok(options.into_iter()))
                 }
@@ -131,6 +134,12 @@ mod tests {
         );
     }
     #[test]
+    fn test_parse_numbers_obsolete_from_start() {
+        assert_eq!(obsolete("+5"), obsolete_result(&["-n", "+5"]));
+        assert_eq!(obsolete("+2c"), obsolete_result(&["-c", "+2"]));
+        assert_eq!(obsolete("+1vk"), obsolete_result(&["-v", "-c", "+1024"]));
+    }
+    #[test]
     fn test_parse_errors_obsolete() {
         assert_eq!(obsolete("-5n"), Some(Err(ParseError::Syntax)));
         assert_eq!(obsolete("-5c5"), Some(Err(ParseError::Syntax)));
@@ -32,10 +32,11 @@ use paths::{FileExtTail, HeaderPrinter, Input, InputKind, MetadataExtTail};
 use same_file::Handle;
 use std::cmp::Ordering;
 use std::fs::File;
-use std::io::{self, stdin, stdout, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::io::{self, stdout, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use uucore::display::Quotable;
 use uucore::error::{get_exit_code, set_exit_code, FromIo, UError, UResult, USimpleError};
+use uucore::input::Reader;
 use uucore::{show, show_error};
 
 #[uucore::main]
@@ -146,23 +147,19 @@ fn tail_file(
         match File::open(path) {
             Ok(mut file) => {
                 header_printer.print_input(input);
-                let mut reader;
-                if !settings.presume_input_pipe
+                let reader: Box<dyn BufRead> = if !settings.presume_input_pipe
                     && file.is_seekable(if input.is_stdin() { offset } else { 0 })
                     && metadata.as_ref().unwrap().get_block_size() > 0
                 {
                     bounded_tail(&mut file, settings);
-                    reader = BufReader::new(file);
+                    // Only what is appended is read from now on.
+                    Box::new(BufReader::new(file))
                 } else {
-                    reader = BufReader::new(file);
+                    let mut reader = Reader::from_file(file).buffered();
                     unbounded_tail(&mut reader, settings)?;
-                }
-                observer.add_path(
-                    path,
-                    input.display_name.as_str(),
-                    Some(Box::new(reader)),
-                    true,
-                )?;
+                    Box::new(reader)
+                };
+                observer.add_path(path, input.display_name.as_str(), Some(reader), true)?;
             }
             Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
                 observer.add_bad_path(path, input.display_name.as_str(), false)?;
@@ -216,7 +213,7 @@ fn tail_stdin(
         None => {
             header_printer.print_input(input);
             if !paths::stdin_is_bad_fd() {
-                let mut reader = BufReader::new(stdin());
+                let mut reader = Reader::stdin().buffered();
                 unbounded_tail(&mut reader, settings)?;
                 observer.add_stdin(input.display_name.as_str(), Some(Box::new(reader)), true)?;
             } else {
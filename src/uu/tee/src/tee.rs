@@ -10,6 +10,9 @@ use retain_mut::RetainMut;
 use std::fs::OpenOptions;
 use std::io::{copy, sink, stdin, stdout, Error, ErrorKind, Read, Result, Write};
 use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
 use uucore::display::Quotable;
 use uucore::error::UResult;
 use uucore::{format_usage, show_error};
@@ -22,6 +25,10 @@ use uucore::libc;
 static ABOUT: &str = "Copy standard input to each FILE, and also to standard output.";
 const USAGE: &str = "{} [OPTION]... [FILE]...";
 
+/// How many chunks of the input can wait for a FILE that is slower than the
+/// others, when its write errors don't stop tee.
+const QUEUE_LEN: usize = 128;
+
 mod options {
     pub const APPEND: &str = "append";
     pub const IGNORE_INTERRUPTS: &str = "ignore-interrupts";
@@ -150,47 +157,42 @@ fn ignore_interrupts() -> Result<()> {
     Ok(())
 }
 
-#[cfg(unix)]
-fn enable_pipe_errors() -> Result<()> {
-    let ret = unsafe { libc::signal(libc::SIGPIPE, libc::SIG_DFL) };
-    if ret == libc::SIG_ERR {
-        return Err(Error::new(ErrorKind::Other, ""));
-    }
-    Ok(())
-}
-
-#[cfg(not(unix))]
-fn enable_pipe_errors() -> Result<()> {
-    // Do nothing.
-    Ok(())
-}
-
 fn tee(options: &Options) -> Result<()> {
     if options.ignore_interrupts {
         ignore_interrupts()?;
     }
-    if options.output_error.is_none() {
-        enable_pipe_errors()?;
+    // With -p, a reader going away is handled like any other output error.
+    if options.output_error.is_some() {
+        uucore::broken_pipe::ignore();
     }
 
-    let mut writers: Vec<NamedWriter> = options
+    // A write error that stops tee must be seen before much more of the
+    // input is copied, so the FILEs are then handed one chunk at a time.
+    let queue_len = match options.output_error {
+        Some(OutputErrorMode::Exit | OutputErrorMode::ExitNoPipe) => 0,
+        _ => QUEUE_LEN,
+    };
+    let mut writers: Vec<Output> = options
         .files
         .clone()
         .into_iter()
         .map(|file| {
-            Ok(NamedWriter {
-                name: file.clone(),
-                inner: open(file, options.append, options.output_error.as_ref())?,
-            })
+            Ok(Output::Thread(ThreadWriter::spawn(
+                NamedWriter {
+                    name: file.clone(),
+                    inner: open(file, options.append, options.output_error.as_ref())?,
+                },
+                queue_len,
+            )))
         })
-        .collect::<Result<Vec<NamedWriter>>>()?;
+        .collect::<Result<Vec<Output>>>()?;
 
     writers.insert(
         0,
-        NamedWriter {
+        Output::Direct(NamedWriter {
             name: "'standard output'".to_owned(),
             inner: Box::new(stdout()),
-        },
+        }),
     );
 
     let mut output = MultiWriter::new(writers, options.output_error.clone());
@@ -208,7 +210,10 @@ fn tee(options: &Options) -> Result<()> {
         _ => Ok(()),
     };
 
-    if res.is_err() || output.flush().is_err() || output.error_occurred() {
+    // The FILEs are waited for even when the copy was stopped, so that
+    // their errors are reported.
+    let flushed = output.flush();
+    if res.is_err() || flushed.is_err() || output.error_occurred() {
         Err(Error::from(ErrorKind::Other))
     } else {
         Ok(())
@@ -219,9 +224,9 @@ fn open(
     name: String,
     append: bool,
     output_error: Option<&OutputErrorMode>,
-) -> Result<Box<dyn Write>> {
+) -> Result<Box<dyn Write + Send>> {
     let path = PathBuf::from(name.clone());
-    let inner: Box<dyn Write> = {
+    let inner: Box<dyn Write + Send> = {
         let mut options = OpenOptions::new();
         let mode = if append {
             options.append(true)
@@ -239,17 +244,17 @@ fn open(
             }
         }
     };
-    Ok(Box::new(NamedWriter { inner, name }) as Box<dyn Write>)
+    Ok(Box::new(NamedWriter { inner, name }) as Box<dyn Write + Send>)
 }
 
 struct MultiWriter {
-    writers: Vec<NamedWriter>,
+    writers: Vec<Output>,
     output_error_mode: Option<OutputErrorMode>,
     ignored_errors: usize,
 }
 
 impl MultiWriter {
-    fn new(writers: Vec<NamedWriter>, output_error_mode: Option<OutputErrorMode>) -> Self {
+    fn new(writers: Vec<Output>, output_error_mode: Option<OutputErrorMode>) -> Self {
         Self {
             writers,
             output_error_mode,
@@ -265,29 +270,29 @@ impl MultiWriter {
 fn process_error(
     mode: Option<&OutputErrorMode>,
     f: Error,
-    writer: &NamedWriter,
+    name: &str,
     ignored_errors: &mut usize,
 ) -> Result<()> {
     match mode {
         Some(OutputErrorMode::Warn) => {
-            show_error!("{}: {}", writer.name.maybe_quote(), f);
+            show_error!("{}: {}", name.maybe_quote(), f);
             *ignored_errors += 1;
             Ok(())
         }
         Some(OutputErrorMode::WarnNoPipe) | None => {
             if f.kind() != ErrorKind::BrokenPipe {
-                show_error!("{}: {}", writer.name.maybe_quote(), f);
+                show_error!("{}: {}", name.maybe_quote(), f);
                 *ignored_errors += 1;
             }
             Ok(())
         }
         Some(OutputErrorMode::Exit) => {
-            show_error!("{}: {}", writer.name.maybe_quote(), f);
+            show_error!("{}: {}", name.maybe_quote(), f);
             Err(f)
         }
         Some(OutputErrorMode::ExitNoPipe) => {
             if f.kind() != ErrorKind::BrokenPipe {
-                show_error!("{}: {}", writer.name.maybe_quote(), f);
+                show_error!("{}: {}", name.maybe_quote(), f);
                 Err(f)
             } else {
                 Ok(())
@@ -301,11 +306,12 @@ impl Write for MultiWriter {
         let mut aborted = None;
         let mode = self.output_error_mode.clone();
         let mut errors = 0;
+        let chunk: Arc<[u8]> = Arc::from(buf);
         RetainMut::retain_mut(&mut self.writers, |writer| {
-            let result = writer.write_all(buf);
+            let result = writer.write_chunk(&chunk);
             match result {
                 Err(f) => {
-                    if let Err(e) = process_error(mode.as_ref(), f, writer, &mut errors) {
+                    if let Err(e) = process_error(mode.as_ref(), f, writer.name(), &mut errors) {
                         if aborted.is_none() {
                             aborted = Some(e);
                         }
@@ -333,10 +339,10 @@ impl Write for MultiWriter {
         let mode = self.output_error_mode.clone();
         let mut errors = 0;
         RetainMut::retain_mut(&mut self.writers, |writer| {
-            let result = writer.flush();
+            let result = writer.finish();
             match result {
                 Err(f) => {
-                    if let Err(e) = process_error(mode.as_ref(), f, writer, &mut errors) {
+                    if let Err(e) = process_error(mode.as_ref(), f, writer.name(), &mut errors) {
                         if aborted.is_none() {
                             aborted = Some(e);
                         }
@@ -356,7 +362,7 @@ impl Write for MultiWriter {
 }
 
 struct NamedWriter {
-    inner: Box<dyn Write>,
+    inner: Box<dyn Write + Send>,
     pub name: String,
 }
 
@@ -370,6 +376,87 @@ impl Write for NamedWriter {
     }
 }
 
+/// Where a copy of the input goes.
+enum Output {
+    /// Written to by the thread reading the input, like the standard output,
+    /// which tee can't get ahead of anyway.
+    Direct(NamedWriter),
+    /// Written to by a thread of its own.
+    Thread(ThreadWriter),
+}
+
+impl Output {
+    fn name(&self) -> &str {
+        match self {
+            Self::Direct(writer) => &writer.name,
+            Self::Thread(writer) => &writer.name,
+        }
+    }
+
+    fn write_chunk(&mut self, chunk: &Arc<[u8]>) -> Result<()> {
+        match self {
+            Self::Direct(writer) => writer.write_all(chunk),
+            Self::Thread(writer) => writer.send(chunk.clone()),
+        }
+    }
+
+    /// Write out what is left for the output.
+    fn finish(&mut self) -> Result<()> {
+        match self {
+            Self::Direct(writer) => writer.flush(),
+            Self::Thread(writer) => writer.join(),
+        }
+    }
+}
+
+/// A writer that writes the chunks of the input it is sent from a thread
+/// of its own, in the order they were sent, so that a slow FILE only holds
+/// up the others once `queue_len` chunks are waiting for it.
+struct ThreadWriter {
+    name: String,
+    sender: Option<SyncSender<Arc<[u8]>>>,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl ThreadWriter {
+    fn spawn(mut writer: NamedWriter, queue_len: usize) -> Self {
+        let (sender, receiver) = sync_channel::<Arc<[u8]>>(queue_len);
+        let name = writer.name.clone();
+        let thread = thread::spawn(move || {
+            for chunk in receiver {
+                writer.write_all(&chunk)?;
+            }
+            writer.flush()
+        });
+        Self {
+            name,
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Queue `chunk`, or return the error that stopped the thread.
+    fn send(&mut self, chunk: Arc<[u8]>) -> Result<()> {
+        match &self.sender {
+            Some(sender) if sender.send(chunk).is_ok() => Ok(()),
+            // The thread only stops early when a write fails.
+            _ => self.join(),
+        }
+    }
+
+    /// Wait for the queued chunks to be written, and return the error of
+    /// the first write that failed.
+    fn join(&mut self) -> Result<()> {
+        self.sender = None;
+        match self.thread.take() {
+            Some(thread) => thread
+                .join()
+                .unwrap_or_else(|_| Err(Error::new(ErrorKind::Other, "writer thread panicked"))),
+            None => Ok(()),
+        }
+    }
+}
+
 struct NamedReader {
     inner: Box<dyn Read>,
 }
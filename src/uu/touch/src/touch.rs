@@ -19,6 +19,15 @@ use time::macros::{format_description, offset, time};
 use time::Duration;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult, USimpleError};
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    windows
+))]
+use uucore::fsext::statfs;
+#[cfg(any(target_os = "linux", target_os = "android", windows))]
+use uucore::fsext::FsMeta;
 use uucore::{format_usage, show};
 
 static ABOUT: &str = "Update the access and modification times of each FILE to the current time.";
@@ -91,6 +100,12 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
             (timestamp, timestamp)
         };
 
+    let time = matches.get_one::<String>(options::TIME).map(|s| s.as_str());
+    let only_atime =
+        matches.get_flag(options::ACCESS) || matches!(time, Some("access" | "atime" | "use"));
+    let only_mtime =
+        matches.get_flag(options::MODIFICATION) || matches!(time, Some("modify" | "mtime"));
+
     for filename in files {
         // FIXME: find a way to avoid having to clone the path
         let pathbuf = if filename == "-" {
@@ -134,38 +149,18 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
 
         // If changing "only" atime or mtime, grab the existing value of the other.
         // Note that "-a" and "-m" may be passed together; this is not an xor.
-        if matches.get_flag(options::ACCESS)
-            || matches.get_flag(options::MODIFICATION)
-            || matches.contains_id(options::TIME)
-        {
+        if only_atime || only_mtime {
             let st = stat(path, !matches.get_flag(options::NO_DEREF))?;
-            let time = matches
-                .get_one::<String>(options::TIME)
-                .map(|s| s.as_str())
-                .unwrap_or("");
-
-            if !(matches.get_flag(options::ACCESS)
-                || time.contains(&"access".to_owned())
-                || time.contains(&"atime".to_owned())
-                || time.contains(&"use".to_owned()))
-            {
+            if !only_atime {
                 atime = st.0;
             }
-
-            if !(matches.get_flag(options::MODIFICATION)
-                || time.contains(&"modify".to_owned())
-                || time.contains(&"mtime".to_owned()))
-            {
+            if !only_mtime {
                 mtime = st.1;
             }
         }
 
-        if matches.get_flag(options::NO_DEREF) {
-            set_symlink_file_times(path, atime, mtime)
-        } else {
-            filetime::set_file_times(path, atime, mtime)
-        }
-        .map_err_context(|| format!("setting times of {}", path.quote()))?;
+        set_times(path, atime, mtime, matches.get_flag(options::NO_DEREF))
+            .map_err_context(|| format!("setting times of {}", path.quote()))?;
     }
 
     Ok(())
@@ -245,7 +240,7 @@ pub fn uu_app() -> Command {
                      equivalent to -m",
                 )
                 .value_name("WORD")
-                .value_parser(["access", "atime", "use"]),
+                .value_parser(["access", "atime", "use", "modify", "mtime"]),
         )
         .arg(
             Arg::new(ARG_FILES)
@@ -261,6 +256,97 @@ pub fn uu_app() -> Command {
         ]))
 }
 
+/// The earliest and the latest times that the FAT file systems can keep,
+/// from 1980-01-01 to 2107-12-31 23:59:58, in steps of 2 seconds.
+const FAT_TIME_RANGE: (i64, i64) = (315_532_800, 4_354_819_198);
+
+/// The time closest to `time` that a FAT file system can keep.
+fn fat_time(time: FileTime) -> FileTime {
+    let seconds = time
+        .unix_seconds()
+        .clamp(FAT_TIME_RANGE.0, FAT_TIME_RANGE.1);
+    FileTime::from_unix_time(seconds - seconds.rem_euclid(2), 0)
+}
+
+/// Whether setting the times failed because the file system can't keep them.
+fn is_unrepresentable(e: &std::io::Error) -> bool {
+    #[cfg(unix)]
+    {
+        use uucore::libc::{EINVAL, EOVERFLOW, ERANGE};
+        matches!(e.raw_os_error(), Some(EINVAL | EOVERFLOW | ERANGE))
+    }
+    #[cfg(not(unix))]
+    {
+        e.kind() == std::io::ErrorKind::InvalidInput
+    }
+}
+
+/// Whether `path` is on a FAT file system.
+#[cfg(any(target_os = "linux", target_os = "android", windows))]
+fn is_on_fat(path: &Path) -> bool {
+    #[cfg(unix)]
+    let path = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes()
+    };
+    // The magic numbers of `msdos` and of `fat`.
+    matches!(statfs(path).map(|fs| fs.fs_type()), Ok(0x4D44 | 0x4006))
+}
+
+/// Whether `path` is on a FAT file system.
+#[cfg(target_vendor = "apple")]
+fn is_on_fat(path: &Path) -> bool {
+    use std::ffi::CStr;
+    use std::os::unix::ffi::OsStrExt;
+    statfs(path.as_os_str().as_bytes()).map_or(false, |fs| {
+        let name = unsafe { CStr::from_ptr(fs.f_fstypename.as_ptr()) };
+        name.to_bytes() == b"msdos"
+    })
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    windows
+)))]
+fn is_on_fat(_path: &Path) -> bool {
+    false
+}
+
+/// Set the times of `path`, or of the symbolic link itself with `no_deref`.
+fn set_times(path: &Path, atime: FileTime, mtime: FileTime, no_deref: bool) -> std::io::Result<()> {
+    let set = |atime, mtime| {
+        if no_deref {
+            set_symlink_file_times(path, atime, mtime)
+        } else {
+            filetime::set_file_times(path, atime, mtime)
+        }
+    };
+    set_or_round_for_fat(set, || is_on_fat(path), atime, mtime)
+}
+
+/// Set the times with `set`. When a FAT file system, as `on_fat` tells,
+/// can't keep them, like a time in the far future, set the closest ones
+/// that it can instead.
+fn set_or_round_for_fat<S, F>(
+    set: S,
+    on_fat: F,
+    atime: FileTime,
+    mtime: FileTime,
+) -> std::io::Result<()>
+where
+    S: Fn(FileTime, FileTime) -> std::io::Result<()>,
+    F: FnOnce() -> bool,
+{
+    match set(atime, mtime) {
+        Err(e) if is_unrepresentable(&e) && on_fat() => {
+            set(fat_time(atime), fat_time(mtime)).map_err(|_| e)
+        }
+        result => result,
+    }
+}
+
 fn stat(path: &Path, follow: bool) -> UResult<(FileTime, FileTime)> {
     let metadata = if follow {
         fs::symlink_metadata(path)
@@ -583,6 +669,56 @@ fn pathbuf_from_stdout() -> UResult<PathBuf> {
 
 #[cfg(test)]
 mod tests {
+    use super::{set_or_round_for_fat, FAT_TIME_RANGE};
+    use filetime::FileTime;
+    use std::cell::RefCell;
+
+    fn unrepresentable() -> std::io::Error {
+        #[cfg(unix)]
+        return std::io::Error::from_raw_os_error(uucore::libc::EOVERFLOW);
+        #[cfg(not(unix))]
+        return std::io::ErrorKind::InvalidInput.into();
+    }
+
+    /// Set the times with a file system that keeps only the times of FAT,
+    /// and return the times that it was asked to set.
+    fn set_on_fat_like(on_fat: bool, time: FileTime) -> (std::io::Result<()>, Vec<FileTime>) {
+        let calls = RefCell::new(Vec::new());
+        let set = |atime: FileTime, mtime: FileTime| {
+            calls.borrow_mut().push(mtime);
+            let seconds = mtime.unix_seconds();
+            if seconds < FAT_TIME_RANGE.0 || seconds > FAT_TIME_RANGE.1 || seconds % 2 != 0 {
+                return Err(unrepresentable());
+            }
+            assert_eq!(atime, mtime);
+            Ok(())
+        };
+        let result = set_or_round_for_fat(set, || on_fat, time, time);
+        (result, calls.into_inner())
+    }
+
+    #[test]
+    fn test_set_times_rounds_on_fat() {
+        let (result, calls) = set_on_fat_like(true, FileTime::from_unix_time(1_000_000_001, 5));
+        assert!(result.is_ok());
+        assert_eq!(calls[1], FileTime::from_unix_time(1_000_000_000, 0));
+
+        let (result, calls) = set_on_fat_like(true, FileTime::from_unix_time(i64::MAX / 2, 0));
+        assert!(result.is_ok());
+        assert_eq!(calls[1], FileTime::from_unix_time(FAT_TIME_RANGE.1, 0));
+
+        let (result, calls) = set_on_fat_like(true, FileTime::from_unix_time(-1, 0));
+        assert!(result.is_ok());
+        assert_eq!(calls[1], FileTime::from_unix_time(FAT_TIME_RANGE.0, 0));
+    }
+
+    #[test]
+    fn test_set_times_fails_elsewhere() {
+        let (result, calls) = set_on_fat_like(false, FileTime::from_unix_time(1_000_000_001, 5));
+        assert!(result.is_err());
+        assert_eq!(calls.len(), 1);
+    }
+
     #[cfg(windows)]
     #[test]
     fn test_get_pathbuf_from_stdout_fails_if_stdout_is_not_a_file() {
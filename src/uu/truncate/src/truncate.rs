@@ -7,7 +7,7 @@
 
 // spell-checker:ignore (ToDO) RFILE refsize rfilename fsize tsize
 use clap::{crate_version, Arg, ArgAction, Command};
-use std::fs::{metadata, OpenOptions};
+use std::fs::{metadata, File, OpenOptions};
 use std::io::ErrorKind;
 #[cfg(unix)]
 use std::os::unix::fs::FileTypeExt;
@@ -77,6 +77,7 @@ static ABOUT: &str = "Shrink or extend the size of each file to the specified si
 const USAGE: &str = "{} [OPTION]... [FILE]...";
 
 pub mod options {
+    pub static ALLOCATE: &str = "allocate";
     pub static IO_BLOCKS: &str = "io-blocks";
     pub static NO_CREATE: &str = "no-create";
     pub static REFERENCE: &str = "reference";
@@ -108,16 +109,13 @@ fn get_long_usage() -> String {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)
-        .map_err(|e| {
-            e.print().expect("Error writing clap::Error");
-            match e.kind() {
-                clap::error::ErrorKind::DisplayHelp | clap::error::ErrorKind::DisplayVersion => 0,
-                _ => 1,
-            }
-        })?;
+    let matches = uu_app().try_get_matches_from(args).map_err(|e| {
+        e.print().expect("Error writing clap::Error");
+        match e.kind() {
+            clap::error::ErrorKind::DisplayHelp | clap::error::ErrorKind::DisplayVersion => 0,
+            _ => 1,
+        }
+    })?;
 
     let files: Vec<String> = matches
         .get_many::<String>(options::ARG_FILES)
@@ -127,13 +125,14 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     if files.is_empty() {
         return Err(UUsageError::new(1, "missing file operand"));
     } else {
+        let allocate = matches.get_flag(options::ALLOCATE);
         let io_blocks = matches.get_flag(options::IO_BLOCKS);
         let no_create = matches.get_flag(options::NO_CREATE);
         let reference = matches
             .get_one::<String>(options::REFERENCE)
             .map(String::from);
         let size = matches.get_one::<String>(options::SIZE).map(String::from);
-        truncate(no_create, io_blocks, reference, size, &files)
+        truncate(no_create, io_blocks, allocate, reference, size, &files)
     }
 }
 
@@ -142,7 +141,17 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
+        .arg(
+            Arg::new(options::ALLOCATE)
+                .long(options::ALLOCATE)
+                .help(
+                    "allocate the blocks of each file up to its new size instead of \
+            leaving holes, like for database or virtual machine images",
+                )
+                .action(ArgAction::SetTrue),
+        )
         .arg(
             Arg::new(options::IO_BLOCKS)
                 .short('o')
@@ -197,14 +206,128 @@ pub fn uu_app() -> Command {
 /// than the number of bytes in the file, then the file will be
 /// truncated and any bytes beyond `size` will be lost.
 ///
+/// If `allocate` is true, then the blocks of the file up to `size` are
+/// allocated, so that the file has no holes.
+///
 /// # Errors
 ///
 /// If the file could not be opened, or there was a problem setting the
-/// size of the file.
-fn file_truncate(filename: &str, create: bool, size: u64) -> std::io::Result<()> {
+/// size of the file or allocating its blocks.
+fn file_truncate(filename: &str, create: bool, size: u64, allocate: bool) -> UResult<()> {
     let path = Path::new(filename);
-    let f = OpenOptions::new().write(true).create(create).open(path)?;
+    let f = OpenOptions::new()
+        .write(true)
+        .create(create)
+        .open(path)
+        .map_err_context(|| format!("cannot open {} for writing", filename.quote()))?;
     f.set_len(size)
+        .map_err_context(|| format!("failed to truncate {} at {} bytes", filename.quote(), size))?;
+    if allocate {
+        allocate_file(&f, size)
+            .map_err_context(|| format!("failed to allocate space for {}", filename.quote()))?;
+    }
+    Ok(())
+}
+
+/// Allocate the blocks of `file` from its start to `size`, which is its
+/// length.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+fn allocate_file(file: &File, size: u64) -> std::io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    if size == 0 {
+        return Ok(());
+    }
+    let size =
+        libc::off_t::try_from(size).map_err(|_| std::io::Error::from_raw_os_error(libc::EFBIG))?;
+    // posix_fallocate returns the error instead of setting errno.
+    match unsafe { libc::posix_fallocate(file.as_raw_fd(), 0, size) } {
+        0 => Ok(()),
+        errno => Err(std::io::Error::from_raw_os_error(errno)),
+    }
+}
+
+/// Allocate the blocks of `file` from its start to `size`, which is its
+/// length.
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn allocate_file(file: &File, size: u64) -> std::io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::io::AsRawFd;
+
+    let allocated = file.metadata()?.blocks() * 512;
+    if size <= allocated {
+        return Ok(());
+    }
+    let mut store = libc::fstore_t {
+        fst_flags: libc::F_ALLOCATECONTIG,
+        fst_posmode: libc::F_PEOFPOSMODE,
+        fst_offset: 0,
+        fst_length: libc::off_t::try_from(size - allocated)
+            .map_err(|_| std::io::Error::from_raw_os_error(libc::EFBIG))?,
+        fst_bytesalloc: 0,
+    };
+    // Contiguous blocks are better, but any will do.
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_PREALLOCATE, &store) } == -1 {
+        store.fst_flags = libc::F_ALLOCATEALL;
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_PREALLOCATE, &store) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Allocate the blocks of `file` from its start to `size`, which is its
+/// length.
+///
+/// This sets the allocation size of the file rather than calling
+/// `SetFileValidData`, which needs a privilege and would expose the
+/// former contents of the blocks.
+#[cfg(windows)]
+fn allocate_file(file: &File, size: u64) -> std::io::Result<()> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FileAllocationInfo, SetFileInformationByHandle, FILE_ALLOCATION_INFO,
+    };
+
+    let info = FILE_ALLOCATION_INFO {
+        AllocationSize: i64::try_from(size)
+            .map_err(|_| std::io::Error::from(ErrorKind::InvalidInput))?,
+    };
+    let ok = unsafe {
+        SetFileInformationByHandle(
+            file.as_raw_handle() as _,
+            FileAllocationInfo,
+            &info as *const FILE_ALLOCATION_INFO as *const _,
+            std::mem::size_of::<FILE_ALLOCATION_INFO>() as u32,
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "macos",
+    target_os = "ios",
+    windows
+)))]
+fn allocate_file(_file: &File, _size: u64) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        ErrorKind::Unsupported,
+        "allocating space is not supported on this platform",
+    ))
 }
 
 /// Truncate files to a size relative to a given file.
@@ -216,7 +339,7 @@ fn file_truncate(filename: &str, create: bool, size: u64) -> std::io::Result<()>
 /// be three kilobytes larger than the size of the reference file".
 ///
 /// If `create` is true, then each file will be created if it does not
-/// already exist.
+/// already exist. If `allocate` is true, then their blocks are allocated.
 ///
 /// # Errors
 ///
@@ -229,6 +352,7 @@ fn truncate_reference_and_size(
     size_string: &str,
     filenames: &[String],
     create: bool,
+    allocate: bool,
 ) -> UResult<()> {
     let mode = match parse_mode_and_size(size_string) {
         Err(e) => return Err(USimpleError::new(1, format!("Invalid number: {e}"))),
@@ -267,8 +391,7 @@ fn truncate_reference_and_size(
             ));
         }
 
-        file_truncate(filename, create, tsize)
-            .map_err_context(|| format!("cannot open {} for writing", filename.quote()))?;
+        file_truncate(filename, create, tsize, allocate)?;
     }
     Ok(())
 }
@@ -278,7 +401,7 @@ fn truncate_reference_and_size(
 /// `rfilename` is the name of the reference file.
 ///
 /// If `create` is true, then each file will be created if it does not
-/// already exist.
+/// already exist. If `allocate` is true, then their blocks are allocated.
 ///
 /// # Errors
 ///
@@ -290,6 +413,7 @@ fn truncate_reference_file_only(
     rfilename: &str,
     filenames: &[String],
     create: bool,
+    allocate: bool,
 ) -> UResult<()> {
     let metadata = metadata(rfilename).map_err(|e| match e.kind() {
         ErrorKind::NotFound => USimpleError::new(
@@ -313,8 +437,7 @@ fn truncate_reference_file_only(
                 ),
             ));
         }
-        file_truncate(filename, create, tsize)
-            .map_err_context(|| format!("cannot open {} for writing", filename.quote()))?;
+        file_truncate(filename, create, tsize, allocate)?;
     }
     Ok(())
 }
@@ -328,7 +451,7 @@ fn truncate_reference_file_only(
 /// its current size".
 ///
 /// If `create` is true, then each file will be created if it does not
-/// already exist.
+/// already exist. If `allocate` is true, then their blocks are allocated.
 ///
 /// # Errors
 ///
@@ -336,7 +459,12 @@ fn truncate_reference_file_only(
 /// the size of at least one file.
 ///
 /// If at least one file is a named pipe (also known as a fifo).
-fn truncate_size_only(size_string: &str, filenames: &[String], create: bool) -> UResult<()> {
+fn truncate_size_only(
+    size_string: &str,
+    filenames: &[String],
+    create: bool,
+    allocate: bool,
+) -> UResult<()> {
     let mode = parse_mode_and_size(size_string)
         .map_err(|e| USimpleError::new(1, format!("Invalid number: {e}")))?;
     if let TruncateMode::RoundDown(0) | TruncateMode::RoundUp(0) = mode {
@@ -357,18 +485,11 @@ fn truncate_size_only(size_string: &str, filenames: &[String], create: bool) ->
                 }
                 m.len()
             }
+            Err(e) if e.kind() == ErrorKind::NotFound && !create => continue,
             Err(_) => 0,
         };
         let tsize = mode.to_size(fsize);
-        match file_truncate(filename, create, tsize) {
-            Ok(_) => continue,
-            Err(e) if e.kind() == ErrorKind::NotFound && !create => continue,
-            Err(e) => {
-                return Err(
-                    e.map_err_context(|| format!("cannot open {} for writing", filename.quote()))
-                )
-            }
-        }
+        file_truncate(filename, create, tsize, allocate)?;
     }
     Ok(())
 }
@@ -376,6 +497,7 @@ fn truncate_size_only(size_string: &str, filenames: &[String], create: bool) ->
 fn truncate(
     no_create: bool,
     _: bool,
+    allocate: bool,
     reference: Option<String>,
     size: Option<String>,
     filenames: &[String],
@@ -388,10 +510,12 @@ fn truncate(
     // - no reference file given and no size given,
     match (reference, size) {
         (Some(rfilename), Some(size_string)) => {
-            truncate_reference_and_size(&rfilename, &size_string, filenames, create)
+            truncate_reference_and_size(&rfilename, &size_string, filenames, create, allocate)
+        }
+        (Some(rfilename), None) => {
+            truncate_reference_file_only(&rfilename, filenames, create, allocate)
         }
-        (Some(rfilename), None) => truncate_reference_file_only(&rfilename, filenames, create),
-        (None, Some(size_string)) => truncate_size_only(&size_string, filenames, create),
+        (None, Some(size_string)) => truncate_size_only(&size_string, filenames, create, allocate),
         (None, None) => unreachable!(), // this case cannot happen anymore because it's handled by clap
     }
 }
@@ -19,7 +19,7 @@ use std::str::from_utf8;
 use unicode_width::UnicodeWidthChar;
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UError, UResult};
-use uucore::{crash, crash_if_err, format_usage};
+use uucore::{format_usage, show};
 
 static USAGE: &str = "{} [OPTION]... [FILE]...";
 static ABOUT: &str = "Convert blanks in each FILE to tabs, writing to standard output.\n\n\
@@ -214,16 +214,12 @@ pub fn uu_app() -> Command {
         )
 }
 
-fn open(path: &str) -> BufReader<Box<dyn Read + 'static>> {
-    let file_buf;
+fn open(path: &str) -> UResult<BufReader<Box<dyn Read + 'static>>> {
     if path == "-" {
-        BufReader::new(Box::new(stdin()) as Box<dyn Read>)
+        Ok(BufReader::new(Box::new(stdin()) as Box<dyn Read>))
     } else {
-        file_buf = match File::open(path) {
-            Ok(a) => a,
-            Err(e) => crash!(1, "{}: {}", path.maybe_quote(), e),
-        };
-        BufReader::new(Box::new(file_buf) as Box<dyn Read>)
+        let file_buf = File::open(path).map_err_context(|| path.maybe_quote().to_string())?;
+        Ok(BufReader::new(Box::new(file_buf) as Box<dyn Read>))
     }
 }
 
@@ -245,7 +241,7 @@ fn write_tabs(
     prevtab: bool,
     init: bool,
     amode: bool,
-) {
+) -> std::io::Result<()> {
     // This conditional establishes the following:
     // We never turn a single space before a non-blank into
     // a tab, unless it's at the start of the line.
@@ -256,15 +252,16 @@ fn write_tabs(
                 break;
             }
 
-            crash_if_err!(1, output.write_all(b"\t"));
+            output.write_all(b"\t")?;
             scol += nts;
         }
     }
 
     while col > scol {
-        crash_if_err!(1, output.write_all(b" "));
+        output.write_all(b" ")?;
         scol += 1;
     }
+    Ok(())
 }
 
 #[derive(PartialEq, Eq, Debug)]
@@ -326,7 +323,13 @@ fn unexpand(options: &Options) -> std::io::Result<()> {
     let lastcol = if ts.len() > 1 { *ts.last().unwrap() } else { 0 };
 
     for file in &options.files {
-        let mut fh = open(file);
+        let mut fh = match open(file) {
+            Ok(fh) => fh,
+            Err(e) => {
+                show!(e);
+                continue;
+            }
+        };
 
         while match fh.read_until(b'\n', &mut buf) {
             Ok(s) => s > 0,
@@ -349,7 +352,7 @@ fn unexpand(options: &Options) -> std::io::Result<()> {
                         pctype == CharType::Tab,
                         init,
                         true,
-                    );
+                    )?;
                     output.write_all(&buf[byte..])?;
                     scol = col;
                     break;
@@ -384,7 +387,7 @@ fn unexpand(options: &Options) -> std::io::Result<()> {
                             pctype == CharType::Tab,
                             init,
                             options.aflag,
-                        );
+                        )?;
                         init = false; // no longer at the start of a line
                         col = if ctype == CharType::Other {
                             // use computed width
@@ -413,7 +416,7 @@ fn unexpand(options: &Options) -> std::io::Result<()> {
                 pctype == CharType::Tab,
                 init,
                 true,
-            );
+            )?;
             output.flush()?;
             buf.truncate(0); // clear out the buffer
         }
@@ -10,13 +10,15 @@
 
 use clap::{crate_version, Arg, ArgAction, ArgMatches, Command};
 use std::fs::File;
-use std::io::{self, stdin, stdout, BufRead, BufReader, BufWriter, Read, Write};
+use std::io::{self, stdin, stdout, BufReader, BufWriter, Read, Write};
 use std::path::Path;
 use std::str::FromStr;
 use strum_macros::{AsRefStr, EnumString};
 use uucore::display::Quotable;
 use uucore::error::{FromIo, UResult, USimpleError, UUsageError};
 use uucore::format_usage;
+use uucore::lines::{self, records, LineEnding, Record};
+use uucore::posix;
 
 static ABOUT: &str = "Report or omit repeated lines.";
 const USAGE: &str = "{} [OPTION]... [INPUT [OUTPUT]]...";
@@ -55,6 +57,7 @@ struct Uniq {
     slice_start: Option<usize>,
     slice_stop: Option<usize>,
     ignore_case: bool,
+    crlf: bool,
     zero_terminated: bool,
 }
 
@@ -75,7 +78,8 @@ impl Uniq {
         let mut first_line_printed = false;
         let mut group_count = 1;
         let line_terminator = self.get_line_terminator();
-        let mut lines = reader.split(line_terminator).map(get_line_string);
+        let line_ending = LineEnding::from_zero_flag(self.zero_terminated);
+        let mut lines = records(reader, line_ending, self.crlf).map(get_line_string);
         let mut line = match lines.next() {
             Some(l) => l?,
             None => return Ok(()),
@@ -85,18 +89,18 @@ impl Uniq {
         // and if needed, print `line` based on the command line options provided
         for next_line in lines {
             let next_line = next_line?;
-            if self.cmp_keys(&line, &next_line) {
+            if self.cmp_keys(line.key(), next_line.key()) {
                 if (group_count == 1 && !self.repeats_only)
                     || (group_count > 1 && !self.uniques_only)
                 {
-                    self.print_line(writer, &line, group_count, first_line_printed)?;
+                    self.print_line(writer, &line.text, group_count, first_line_printed)?;
                     first_line_printed = true;
                 }
                 line = next_line;
                 group_count = 1;
             } else {
                 if self.all_repeated {
-                    self.print_line(writer, &line, group_count, first_line_printed)?;
+                    self.print_line(writer, &line.text, group_count, first_line_printed)?;
                     first_line_printed = true;
                     line = next_line;
                 }
@@ -104,7 +108,7 @@ impl Uniq {
             }
         }
         if (group_count == 1 && !self.repeats_only) || (group_count > 1 && !self.uniques_only) {
-            self.print_line(writer, &line, group_count, first_line_printed)?;
+            self.print_line(writer, &line.text, group_count, first_line_printed)?;
             first_line_printed = true;
         }
         if (self.delimiters == Delimiters::Append || self.delimiters == Delimiters::Both)
@@ -222,10 +226,27 @@ impl Uniq {
     }
 }
 
-fn get_line_string(io_line: io::Result<Vec<u8>>) -> UResult<String> {
-    let line_bytes = io_line.map_err_context(|| "failed to split lines".to_string())?;
-    String::from_utf8(line_bytes)
-        .map_err(|e| USimpleError::new(1, format!("failed to convert line to utf8: {e}")))
+/// A line of the input, without its line terminator.
+struct Line {
+    text: String,
+    key_len: usize,
+}
+
+impl Line {
+    /// The part of the line that is compared, which is all of it but with
+    /// `--crlf`.
+    fn key(&self) -> &str {
+        &self.text[..self.key_len]
+    }
+}
+
+fn get_line_string(record: io::Result<Record>) -> UResult<Line> {
+    let record = record.map_err_context(|| "failed to split lines".to_string())?;
+    let (mut bytes, line_len, key_len) = record.into_parts();
+    bytes.truncate(line_len);
+    let text = String::from_utf8(bytes)
+        .map_err(|e| USimpleError::new(1, format!("failed to convert line to utf8: {e}")))?;
+    Ok(Line { text, key_len })
 }
 
 fn opt_parsed<T: FromStr>(opt_name: &str, matches: &ArgMatches) -> UResult<Option<T>> {
@@ -255,9 +276,26 @@ fn get_long_usage() -> String {
 
 #[uucore::main]
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    // The obsolete `-NUMBER` stands for `-f NUMBER`, and `+NUMBER` for
+    // `-s NUMBER`, which is a file name since POSIX.1-2001.
+    let obsolete_usage = posix::obsolete_usage();
+    let args = posix::replace_obsolete_args(args, |arg| {
+        if let Some((fields, rest)) = posix::obsolete_number(arg, '-') {
+            let mut options = vec!["-f".to_string(), fields.to_string()];
+            if !rest.is_empty() {
+                options.push(format!("-{rest}"));
+            }
+            Some(options)
+        } else {
+            match posix::obsolete_number(arg, '+') {
+                Some((chars, "")) if obsolete_usage => {
+                    Some(vec!["-s".to_string(), chars.to_string()])
+                }
+                _ => None,
+            }
+        }
+    });
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let files: Vec<String> = matches
         .get_many::<String>(ARG_FILES)
@@ -285,6 +323,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         slice_start: opt_parsed(options::SKIP_CHARS, &matches)?,
         slice_stop: opt_parsed(options::CHECK_CHARS, &matches)?,
         ignore_case: matches.get_flag(options::IGNORE_CASE),
+        crlf: matches.get_flag(lines::arguments::OPT_CRLF),
         zero_terminated: matches.get_flag(options::ZERO_TERMINATED),
     };
 
@@ -306,6 +345,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::ALL_REPEATED)
@@ -356,6 +396,7 @@ pub fn uu_app() -> Command {
                 .help("prefix lines by the number of occurrences")
                 .action(ArgAction::SetTrue),
         )
+        .arg(lines::arguments::crlf())
         .arg(
             Arg::new(options::IGNORE_CASE)
                 .short('i')
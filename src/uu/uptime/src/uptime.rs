@@ -112,11 +112,11 @@ fn process_utmpx() -> (Option<time_t>, usize) {
 
     for line in Utmpx::iter_all_records() {
         match line.record_type() {
-            USER_PROCESS => nusers += 1,
+            USER_PROCESS if line.is_user_process() => nusers += 1,
             BOOT_TIME => {
                 let dt = line.login_time();
-                if dt.second() > 0 {
-                    boot_time = Some(dt.second() as time_t);
+                if dt.unix_timestamp() > 0 {
+                    boot_time = Some(dt.unix_timestamp() as time_t);
                 }
             }
             _ => continue,
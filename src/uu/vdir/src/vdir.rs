@@ -39,6 +39,7 @@ pub fn uumain(args: impl uucore::Args) -> UResult<()> {
         && !matches.get_flag(options::format::LONG_NO_OWNER)
         && !matches.get_flag(options::format::LONG_NUMERIC_UID_GID)
         && !matches.get_flag(options::format::ONE_LINE)
+        && !matches.get_flag(options::JSON)
     {
         default_format_style = true;
     }
@@ -4,7 +4,7 @@ use super::WordCountable;
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
 use std::fs::OpenOptions;
-use std::io::{self, ErrorKind, Read};
+use std::io::{self, ErrorKind};
 
 #[cfg(unix)]
 use libc::S_IFREG;
@@ -18,7 +18,6 @@ use libc::S_IFIFO;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 use uucore::pipes::{pipe, splice, splice_exact};
 
-const BUF_SIZE: usize = 16 * 1024;
 #[cfg(any(target_os = "linux", target_os = "android"))]
 const SPLICE_SIZE: usize = 128 * 1024;
 
@@ -104,7 +103,7 @@ pub(crate) fn count_bytes_fast<T: WordCountable>(handle: &mut T) -> (usize, Opti
     }
 
     // Fall back on `read`, but without the overhead of counting words and lines.
-    let mut buf = [0_u8; BUF_SIZE];
+    let mut buf = vec![0_u8; handle.buffer_size()];
     loop {
         match handle.read(&mut buf) {
             Ok(0) => return (byte_count, None),
@@ -123,14 +122,14 @@ pub(crate) fn count_bytes_fast<T: WordCountable>(handle: &mut T) -> (usize, Opti
 ///
 /// # Arguments
 ///
-/// * `R` - A Reader from which the UTF-8 stream will be read.
+/// * `T` - A Reader from which the UTF-8 stream will be read.
 pub(crate) fn count_bytes_chars_and_lines_fast<
-    R: Read,
+    T: WordCountable,
     const COUNT_BYTES: bool,
     const COUNT_CHARS: bool,
     const COUNT_LINES: bool,
 >(
-    handle: &mut R,
+    handle: &mut T,
 ) -> (WordCount, Option<io::Error>) {
     /// Mask of the value bits of a continuation byte
     const CONT_MASK: u8 = 0b0011_1111u8;
@@ -138,7 +137,7 @@ pub(crate) fn count_bytes_chars_and_lines_fast<
     const TAG_CONT_U8: u8 = 0b1000_0000u8;
 
     let mut total = WordCount::default();
-    let mut buf = [0; BUF_SIZE];
+    let mut buf = vec![0; handle.buffer_size()];
     loop {
         match handle.read(&mut buf) {
             Ok(0) => return (total, None),
@@ -1,10 +1,10 @@
 //! Traits and implementations for iterating over lines in a file-like object.
 //!
-//! This module provides a [`WordCountable`] trait and implementations
-//! for some common file-like objects. Use the [`WordCountable::buffered`]
+//! This module provides a [`WordCountable`] trait and its implementation
+//! for the standard input and the files. Use the [`WordCountable::buffered`]
 //! method to get an iterator over lines of a file-like object.
-use std::fs::File;
-use std::io::{BufRead, BufReader, Read, StdinLock};
+use std::io::{BufRead, BufReader, Read};
+use uucore::input::Reader;
 
 #[cfg(unix)]
 use std::os::unix::io::AsRawFd;
@@ -13,25 +13,26 @@ use std::os::unix::io::AsRawFd;
 pub trait WordCountable: AsRawFd + Read {
     type Buffered: BufRead;
     fn buffered(self) -> Self::Buffered;
+    /// The size of the buffers to read this in.
+    fn buffer_size(&self) -> usize;
 }
 
 #[cfg(not(unix))]
 pub trait WordCountable: Read {
     type Buffered: BufRead;
     fn buffered(self) -> Self::Buffered;
+    /// The size of the buffers to read this in.
+    fn buffer_size(&self) -> usize;
 }
 
-impl WordCountable for StdinLock<'_> {
-    type Buffered = Self;
+impl WordCountable for Reader {
+    type Buffered = BufReader<Self>;
 
     fn buffered(self) -> Self::Buffered {
-        self
+        Reader::buffered(self)
     }
-}
-impl WordCountable for File {
-    type Buffered = BufReader<Self>;
 
-    fn buffered(self) -> Self::Buffered {
-        BufReader::new(self)
+    fn buffer_size(&self) -> usize {
+        Reader::buffer_size(self)
     }
 }
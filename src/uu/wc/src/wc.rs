@@ -29,6 +29,7 @@ use std::io::{self, Read, Write};
 use std::path::PathBuf;
 
 use uucore::error::{UError, UResult, USimpleError};
+use uucore::input::Reader;
 use uucore::quoting_style::{escape_name, QuotingStyle};
 
 /// The minimum character width for formatting counts when reading from stdin.
@@ -491,15 +492,13 @@ enum CountResult {
 fn word_count_from_input(input: &Input, settings: &Settings) -> CountResult {
     match input {
         Input::Stdin(_) => {
-            let stdin = io::stdin();
-            let stdin_lock = stdin.lock();
-            let count = word_count_from_reader(stdin_lock, settings);
+            let count = word_count_from_reader(Reader::stdin(), settings);
             match count {
                 (total, Some(error)) => CountResult::Interrupted(total, error),
                 (total, None) => CountResult::Success(total),
             }
         }
-        Input::Path(path) => match File::open(path) {
+        Input::Path(path) => match Reader::open(path) {
             Err(error) => CountResult::Failure(error),
             Ok(file) => match word_count_from_reader(file, settings) {
                 (total, Some(error)) => CountResult::Interrupted(total, error),
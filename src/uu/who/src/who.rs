@@ -17,7 +17,6 @@ use std::borrow::Cow;
 use std::ffi::CStr;
 use std::fmt::Write;
 use std::os::unix::fs::MetadataExt;
-use std::path::PathBuf;
 use uucore::format_usage;
 
 mod options {
@@ -58,9 +57,7 @@ fn get_long_usage() -> String {
 pub fn uumain(args: impl uucore::Args) -> UResult<()> {
     let args = args.collect_ignore();
 
-    let matches = uu_app()
-        .after_help(get_long_usage())
-        .try_get_matches_from(args)?;
+    let matches = uu_app().try_get_matches_from(args)?;
 
     let files: Vec<String> = matches
         .get_many::<String>(options::FILE)
@@ -155,6 +152,7 @@ pub fn uu_app() -> Command {
         .version(crate_version!())
         .about(ABOUT)
         .override_usage(format_usage(USAGE))
+        .after_help(get_long_usage())
         .infer_long_args(true)
         .arg(
             Arg::new(options::ALL)
@@ -468,11 +466,9 @@ impl Who {
     }
 
     fn print_user(&self, ut: &Utmpx) -> UResult<()> {
-        let mut p = PathBuf::from("/dev");
-        p.push(ut.tty_device().as_str());
         let mesg;
         let last_change;
-        match p.metadata() {
+        match ut.tty_metadata() {
             Ok(meta) => {
                 #[cfg(all(
                     not(target_os = "android"),
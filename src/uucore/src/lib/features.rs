@@ -1,30 +1,46 @@
 // features ~ feature-gated modules (core/bundler file)
 
+#[cfg(feature = "capabilities")]
+pub mod capabilities;
+#[cfg(feature = "checksum")]
+pub mod checksum;
 #[cfg(feature = "encoding")]
 pub mod encoding;
+#[cfg(feature = "exec")]
+pub mod exec;
 #[cfg(feature = "fs")]
 pub mod fs;
 #[cfg(feature = "fsext")]
 pub mod fsext;
+#[cfg(feature = "input")]
+pub mod input;
 #[cfg(feature = "lines")]
 pub mod lines;
 #[cfg(feature = "memo")]
 pub mod memo;
+#[cfg(feature = "mode")]
+pub mod mode;
+#[cfg(feature = "numeric")]
+pub mod numeric;
+#[cfg(feature = "rand")]
+pub mod rand;
 #[cfg(feature = "ringbuffer")]
 pub mod ringbuffer;
 #[cfg(feature = "memo")]
 mod tokenize;
+#[cfg(feature = "trash")]
+pub mod trash;
 
 // * (platform-specific) feature-gated modules
-// ** non-windows (i.e. Unix + Fuchsia)
-#[cfg(all(not(windows), feature = "mode"))]
-pub mod mode;
+// ** unix and windows
+#[cfg(all(any(unix, windows), feature = "perms"))]
+pub mod perms;
 
 // ** unix-only
 #[cfg(all(unix, feature = "entries"))]
 pub mod entries;
-#[cfg(all(unix, feature = "perms"))]
-pub mod perms;
+#[cfg(all(unix, feature = "fsxattr"))]
+pub mod fsxattr;
 #[cfg(all(unix, feature = "pipes"))]
 pub mod pipes;
 #[cfg(all(unix, feature = "process"))]
@@ -37,7 +53,6 @@ pub mod signals;
     not(target_os = "android"),
     not(target_os = "fuchsia"),
     not(target_os = "redox"),
-    not(target_env = "musl"),
     feature = "utmpx"
 ))]
 pub mod utmpx;
@@ -0,0 +1,112 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Whether the process may do what is otherwise reserved to the superuser.
+//!
+//! Some utilities behave differently depending on it, like GNU coreutils do:
+//! `cp --preserve=ownership` fails when the owner of a copy can't be set by
+//! a process that is allowed to set it, and silently keeps the owner it got
+//! otherwise.
+//!
+//! On Linux, the privileges are the capabilities in the effective set of the
+//! process. On other Unix systems, only the superuser has them, and on
+//! Windows, the members of the Administrators group.
+
+// spell-checker:ignore CapEff
+
+/// A privileged operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Changing the owner of any file, and its group to any group.
+    Chown,
+    /// Changing the mode and the timestamps of the files owned by others.
+    Fowner,
+    /// Administering the system, which includes setting the extended
+    /// attributes of the `security` namespace.
+    SysAdmin,
+}
+
+impl Capability {
+    /// The number of the capability in the Linux capability sets.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn number(self) -> u32 {
+        match self {
+            Self::Chown => 0,
+            Self::Fowner => 3,
+            Self::SysAdmin => 21,
+        }
+    }
+}
+
+/// Whether the process has `capability`.
+pub fn has(capability: Capability) -> bool {
+    platform::has(capability)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod platform {
+    use super::Capability;
+    use std::fs;
+
+    pub(super) fn has(capability: Capability) -> bool {
+        match fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| effective(&status))
+        {
+            Some(set) => set & (1 << capability.number()) != 0,
+            // Without `/proc`, assume the usual set of the superuser.
+            None => unsafe { libc::geteuid() == 0 },
+        }
+    }
+
+    /// The effective capability set in the contents of `/proc/self/status`.
+    pub(super) fn effective(status: &str) -> Option<u64> {
+        let set = status
+            .lines()
+            .find_map(|line| line.strip_prefix("CapEff:"))?;
+        u64::from_str_radix(set.trim(), 16).ok()
+    }
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+mod platform {
+    use super::Capability;
+
+    pub(super) fn has(_capability: Capability) -> bool {
+        unsafe { libc::geteuid() == 0 }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::Capability;
+    use windows_sys::Win32::UI::Shell::IsUserAnAdmin;
+
+    pub(super) fn has(_capability: Capability) -> bool {
+        unsafe { IsUserAnAdmin() != 0 }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::Capability;
+
+    pub(super) fn has(_capability: Capability) -> bool {
+        false
+    }
+}
+
+#[cfg(all(test, any(target_os = "linux", target_os = "android")))]
+mod tests {
+    use super::platform::effective;
+
+    #[test]
+    fn test_effective() {
+        let status = "Name:\tcp\nCapInh:\t0000000000000000\nCapPrm:\t000001ffffffffff\n\
+                      CapEff:\t0000000000000009\nCapBnd:\t000001ffffffffff\n";
+        assert_eq!(effective(status), Some(9));
+        assert_eq!(effective("Name:\tcp\n"), None);
+    }
+}
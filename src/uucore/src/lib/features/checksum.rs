@@ -0,0 +1,610 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+// spell-checker:ignore (words) fadvise fstatfs madvise mmap munmap sysv
+
+//! Compute and check checksums and message digests.
+//!
+//! This module contains the functionality shared by `hashsum` (and its
+//! `md5sum`, `sha256sum`, ... personalities), `cksum` and `sum`:
+//!
+//! - the [`Digest`] trait and its implementations, in [`digest`]
+//! - the [`Algorithm`] registry, mapping algorithm names to digests
+//! - streaming hashing of readers ([`digest_reader()`]) and files
+//!   ([`digest_file()`]), the latter choosing between `mmap` and `read`
+//!   according to a [`ReadStrategy`]
+//! - parsing of the lines of a checksum file, as read by `--check`
+//!   ([`parse_checksum_line()`])
+//!
+//! # Usage example
+//!
+//! ```
+//! use uucore::checksum::{self, Algorithm};
+//!
+//! let algo = Algorithm::from_name("sha256").unwrap();
+//! let mut digest = algo.create_digest();
+//! let size = checksum::digest_reader(&mut *digest, &mut &b"hello\n"[..], false).unwrap();
+//! assert_eq!(size, 6);
+//! assert_eq!(
+//!     checksum::digest_result(&mut *digest, algo.output_bits()),
+//!     "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03"
+//! );
+//! ```
+
+pub mod digest;
+
+pub use self::digest::{Digest, DigestWriter};
+
+use std::fs::File;
+use std::io::{self, Read};
+
+/// The checksum algorithms known to uutils.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Algorithm {
+    /// The BSD `sum` checksum (`sum -r`)
+    Bsd,
+    /// The System V `sum` checksum (`sum -s`)
+    SysV,
+    /// The POSIX `cksum` CRC
+    Crc,
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+    Blake2b,
+    Blake3,
+    Sha3_224,
+    Sha3_256,
+    Sha3_384,
+    Sha3_512,
+    /// SHAKE128, whose output size is chosen by the caller
+    Shake128,
+    /// SHAKE256, whose output size is chosen by the caller
+    Shake256,
+}
+
+impl Algorithm {
+    /// All known algorithms, in the order in which they are listed to users.
+    pub const ALL: &'static [Self] = &[
+        Self::SysV,
+        Self::Bsd,
+        Self::Crc,
+        Self::Md5,
+        Self::Sha1,
+        Self::Sha224,
+        Self::Sha256,
+        Self::Sha384,
+        Self::Sha512,
+        Self::Blake2b,
+        Self::Blake3,
+        Self::Sha3_224,
+        Self::Sha3_256,
+        Self::Sha3_384,
+        Self::Sha3_512,
+        Self::Shake128,
+        Self::Shake256,
+    ];
+
+    /// Look up an algorithm by its lowercase command line name, e.g.
+    /// `"sha256"` or `"sha3-512"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|algo| algo.name() == name)
+    }
+
+    /// The lowercase name used to select this algorithm on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bsd => "bsd",
+            Self::SysV => "sysv",
+            Self::Crc => "crc",
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha1",
+            Self::Sha224 => "sha224",
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+            Self::Blake2b => "blake2b",
+            Self::Blake3 => "blake3",
+            Self::Sha3_224 => "sha3-224",
+            Self::Sha3_256 => "sha3-256",
+            Self::Sha3_384 => "sha3-384",
+            Self::Sha3_512 => "sha3-512",
+            Self::Shake128 => "shake128",
+            Self::Shake256 => "shake256",
+        }
+    }
+
+    /// The name used in BSD-style (`--tag`) checksum lines, e.g.
+    /// `SHA256 (file) = ...`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Bsd => "BSD",
+            Self::SysV => "SYSV",
+            Self::Crc => "CRC",
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA1",
+            Self::Sha224 => "SHA224",
+            Self::Sha256 => "SHA256",
+            Self::Sha384 => "SHA384",
+            Self::Sha512 => "SHA512",
+            Self::Blake2b => "BLAKE2",
+            Self::Blake3 => "BLAKE3",
+            Self::Sha3_224 => "SHA3-224",
+            Self::Sha3_256 => "SHA3-256",
+            Self::Sha3_384 => "SHA3-384",
+            Self::Sha3_512 => "SHA3-512",
+            Self::Shake128 => "SHAKE128",
+            Self::Shake256 => "SHAKE256",
+        }
+    }
+
+    /// The size of the digest in bits, or 0 for the variable-length SHAKE
+    /// algorithms.
+    pub fn output_bits(self) -> usize {
+        self.create_digest().output_bits()
+    }
+
+    /// Create a fresh digest for this algorithm.
+    pub fn create_digest(self) -> Box<dyn Digest> {
+        match self {
+            Self::Bsd => Box::new(digest::Bsd::new()),
+            Self::SysV => Box::new(digest::SysV::new()),
+            Self::Crc => Box::new(digest::Crc::new()),
+            Self::Md5 => Box::new(md5::Md5::new()),
+            Self::Sha1 => Box::new(sha1::Sha1::new()),
+            Self::Sha224 => Box::new(sha2::Sha224::new()),
+            Self::Sha256 => Box::new(sha2::Sha256::new()),
+            Self::Sha384 => Box::new(sha2::Sha384::new()),
+            Self::Sha512 => Box::new(sha2::Sha512::new()),
+            Self::Blake2b => Box::new(blake2b_simd::State::new()),
+            Self::Blake3 => Box::new(blake3::Hasher::new()),
+            Self::Sha3_224 => Box::new(sha3::Sha3_224::new()),
+            Self::Sha3_256 => Box::new(sha3::Sha3_256::new()),
+            Self::Sha3_384 => Box::new(sha3::Sha3_384::new()),
+            Self::Sha3_512 => Box::new(sha3::Sha3_512::new()),
+            Self::Shake128 => Box::new(sha3::Shake128::new()),
+            Self::Shake256 => Box::new(sha3::Shake256::new()),
+        }
+    }
+}
+
+/// How [`digest_file()`] reads the contents of a file.
+///
+/// The mappings are only made when they are asked for: a file that another
+/// process truncates while it is mapped makes the process crash with
+/// `SIGBUS`, which never happens with `read`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReadStrategy {
+    /// Map large regular files of local file systems into memory and read
+    /// everything else
+    Auto,
+    /// Map regular files into memory whenever possible
+    Mmap,
+    /// Always use `read(2)`
+    Read,
+}
+
+impl Default for ReadStrategy {
+    fn default() -> Self {
+        Self::Read
+    }
+}
+
+impl ReadStrategy {
+    /// Get the strategy from its name, as given to `--io-mode`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Self::Auto),
+            "mmap" => Some(Self::Mmap),
+            "read" => Some(Self::Read),
+            _ => None,
+        }
+    }
+}
+
+/// Regular files at least this large are mapped into memory by
+/// [`ReadStrategy::Auto`]. Below that, the cost of setting up the mapping
+/// outweighs the copy that `read` makes.
+pub const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Reset `digest` and feed it everything read from `reader`.
+///
+/// If `binary` is `false` and the operating system is Windows, "\r\n" is
+/// replaced with "\n" before hashing, see [`DigestWriter`].
+///
+/// Returns the number of bytes read.
+pub fn digest_reader<R: Read + ?Sized>(
+    digest: &mut dyn Digest,
+    reader: &mut R,
+    binary: bool,
+) -> io::Result<u64> {
+    digest.reset();
+
+    // In order to support replacing "\r\n", we must call `finalize()`
+    // in order to support the possibility that the last character read
+    // from the reader was "\r". (This character gets buffered by
+    // `DigestWriter` and only written if the following character is
+    // "\n". But when "\r" is the last character read, we need to force
+    // it to be written.)
+    let mut digest_writer = DigestWriter::new(digest, binary);
+    let mut buf = vec![0; READ_BUFFER_SIZE];
+    let mut size = 0;
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        io::Write::write_all(&mut digest_writer, &buf[..n])?;
+        size += n as u64;
+    }
+    digest_writer.finalize();
+    Ok(size)
+}
+
+/// Reset `digest` and feed it the contents of `file`, reading it according
+/// to `strategy`.
+///
+/// Mapping a file that is truncated by another process while it is being
+/// hashed makes the process crash, which is why mappings are opt-in, and
+/// why [`ReadStrategy::Auto`] only maps large files, where the speed-up is
+/// worth it, and not those of network file systems, where it is more likely
+/// to happen.
+///
+/// The kernel is told that the file is read sequentially and, once it has
+/// been hashed, that the pages of a large file won't be needed anymore, so
+/// that verifying many large files doesn't evict everything else from the
+/// page cache.
+///
+/// Returns the number of bytes read.
+pub fn digest_file(
+    digest: &mut dyn Digest,
+    file: &File,
+    binary: bool,
+    strategy: ReadStrategy,
+) -> io::Result<u64> {
+    let mmap_len = match strategy {
+        ReadStrategy::Read => None,
+        ReadStrategy::Auto | ReadStrategy::Mmap => {
+            let metadata = file.metadata()?;
+            let threshold = if strategy == ReadStrategy::Auto {
+                MMAP_THRESHOLD
+            } else {
+                1
+            };
+            if metadata.is_file()
+                && metadata.len() >= threshold
+                && (strategy == ReadStrategy::Mmap || !is_remote(file))
+            {
+                usize::try_from(metadata.len()).ok()
+            } else {
+                None
+            }
+        }
+    };
+    advise(file, Advice::Sequential);
+    let mut result = None;
+    if let Some(len) = mmap_len {
+        result = digest_mmap(digest, file, len, binary)?;
+    }
+    let size = match result {
+        Some(size) => size,
+        None => digest_reader(digest, &mut &*file, binary)?,
+    };
+    if size >= MMAP_THRESHOLD {
+        advise(file, Advice::DontNeed);
+    }
+    Ok(size)
+}
+
+/// The hints given to the kernel about the way a file is read.
+#[derive(Clone, Copy)]
+enum Advice {
+    Sequential,
+    DontNeed,
+}
+
+/// Give `advice` about the whole of `file` to the kernel. Being only a hint,
+/// it can fail without consequence.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn advise(file: &File, advice: Advice) {
+    use std::os::unix::io::AsRawFd;
+
+    let advice = match advice {
+        Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+        Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+    };
+    // SAFETY: posix_fadvise doesn't access memory.
+    unsafe {
+        libc::posix_fadvise(file.as_raw_fd(), 0, 0, advice);
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn advise(_file: &File, _advice: Advice) {}
+
+/// Whether `file` is on a network file system.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn is_remote(file: &File) -> bool {
+    use std::os::unix::io::AsRawFd;
+
+    // The magic numbers of statfs(2), which libc doesn't all define.
+    const NFS: u32 = 0x6969;
+    const SMB: u32 = 0x517b;
+    const SMB2: u32 = 0xfe53_4d42;
+    const CIFS: u32 = 0xff53_4d42;
+    const CEPH: u32 = 0x00c3_6400;
+    const FUSE: u32 = 0x6573_5546;
+
+    let mut stat = std::mem::MaybeUninit::<libc::statfs>::uninit();
+    // SAFETY: `stat` is large enough for fstatfs to write to, and is only
+    // read when it succeeds.
+    let stat = unsafe {
+        if libc::fstatfs(file.as_raw_fd(), stat.as_mut_ptr()) != 0 {
+            return false;
+        }
+        stat.assume_init()
+    };
+    // f_type is signed on some platforms, where these don't fit.
+    let kind = stat.f_type as u32;
+    [NFS, SMB, SMB2, CIFS, CEPH, FUSE].contains(&kind)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn is_remote(_file: &File) -> bool {
+    false
+}
+
+/// Hash `len` bytes of `file` through a read-only memory mapping.
+///
+/// Returns `None` if the file cannot be mapped, in which case the caller
+/// should fall back to reading it.
+#[cfg(unix)]
+fn digest_mmap(
+    digest: &mut dyn Digest,
+    file: &File,
+    len: usize,
+    binary: bool,
+) -> io::Result<Option<u64>> {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: the mapping is private and read-only, and is unmapped before
+    // returning. `len` is non-zero, as required by mmap.
+    let ptr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            len,
+            libc::PROT_READ,
+            libc::MAP_PRIVATE,
+            file.as_raw_fd(),
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Ok(None);
+    }
+    // SAFETY: `ptr` points to a mapping of `len` readable bytes. The advice
+    // is only a hint, so its result is irrelevant.
+    let data = unsafe {
+        libc::madvise(ptr, len, libc::MADV_SEQUENTIAL);
+        std::slice::from_raw_parts(ptr as *const u8, len)
+    };
+
+    digest.reset();
+    let mut digest_writer = DigestWriter::new(digest, binary);
+    let result = io::Write::write_all(&mut digest_writer, data);
+    digest_writer.finalize();
+
+    // SAFETY: `data` is not used past this point.
+    unsafe {
+        libc::munmap(ptr, len);
+    }
+    result.map(|_| Some(len as u64))
+}
+
+#[cfg(not(unix))]
+fn digest_mmap(
+    _digest: &mut dyn Digest,
+    _file: &File,
+    _len: usize,
+    _binary: bool,
+) -> io::Result<Option<u64>> {
+    Ok(None)
+}
+
+/// Get the result of `digest` as it is printed by the checksum utilities.
+///
+/// `output_bits` is only used for the variable-length SHAKE algorithms,
+/// whose [`Digest::output_bits()`] is 0.
+pub fn digest_result(digest: &mut dyn Digest, output_bits: usize) -> String {
+    if digest.output_bits() > 0 {
+        digest.result_str()
+    } else {
+        // Assume it's SHAKE.  result_str() doesn't work with shake (as of 8/30/2016)
+        let mut bytes = vec![0; (output_bits + 7) / 8];
+        digest.result(&mut bytes);
+        hex::encode(bytes)
+    }
+}
+
+/// A line of a checksum file, as parsed by [`parse_checksum_line()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecksumLine<'a> {
+    /// The expected digest, in lowercase hex
+    pub digest: String,
+    /// The name of the file to check
+    pub filename: &'a str,
+    /// Whether the file should be read in binary mode
+    pub binary: bool,
+}
+
+/// Parse a `line` of a checksum file for the algorithm tagged `algo_tag`
+/// (see [`Algorithm::tag()`]) with digests of `output_bits` bits.
+///
+/// Both the GNU format (`<digest> <file>` or `<digest> *<file>` for binary
+/// mode) and the BSD format produced by `--tag` (`<tag> (<file>) = <digest>`)
+/// are accepted. If `output_bits` is 0, digests of any length are accepted.
+///
+/// Returns `None` if the line is improperly formatted.
+pub fn parse_checksum_line<'a>(
+    line: &'a str,
+    algo_tag: &str,
+    output_bits: usize,
+) -> Option<ChecksumLine<'a>> {
+    parse_gnu_line(line, output_bits).or_else(|| parse_bsd_line(line, algo_tag, output_bits))
+}
+
+/// Length of the hex digest at the start of `s`, or `None` if there is no
+/// valid digest of `output_bits` bits there.
+fn hex_digest_len(s: &str, output_bits: usize) -> Option<usize> {
+    let hex_len = s.bytes().take_while(u8::is_ascii_hexdigit).count();
+    let expected = output_bits / 4;
+    match expected {
+        0 if hex_len > 0 => Some(hex_len),
+        0 => None,
+        _ if hex_len >= expected => Some(expected),
+        _ => None,
+    }
+}
+
+fn parse_gnu_line(line: &str, output_bits: usize) -> Option<ChecksumLine<'_>> {
+    let len = hex_digest_len(line, output_bits)?;
+    let (digest, rest) = line.split_at(len);
+    let rest = rest.strip_prefix(' ')?;
+    let binary = match rest.chars().next()? {
+        ' ' => false,
+        '*' => true,
+        _ => return None,
+    };
+    Some(ChecksumLine {
+        digest: digest.to_ascii_lowercase(),
+        filename: &rest[1..],
+        binary,
+    })
+}
+
+fn parse_bsd_line<'a>(
+    line: &'a str,
+    algo_tag: &str,
+    output_bits: usize,
+) -> Option<ChecksumLine<'a>> {
+    let rest = line.strip_prefix(algo_tag)?.strip_prefix(" (")?;
+    // The file name may itself contain ") = ", so use the last separator
+    // that is followed by a valid digest, which ends the line.
+    let mut end = rest.len();
+    while let Some(pos) = rest[..end].rfind(") = ") {
+        let digest = &rest[pos + 4..];
+        if hex_digest_len(digest, output_bits) == Some(digest.len()) {
+            return Some(ChecksumLine {
+                digest: digest.to_ascii_lowercase(),
+                filename: &rest[..pos],
+                binary: true,
+            });
+        }
+        end = pos;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_algorithm_names() {
+        for algo in Algorithm::ALL {
+            assert_eq!(Algorithm::from_name(algo.name()), Some(*algo));
+        }
+        assert_eq!(Algorithm::from_name("SHA256"), None);
+        assert_eq!(Algorithm::Sha3_256.output_bits(), 256);
+        assert_eq!(Algorithm::Shake128.output_bits(), 0);
+    }
+
+    #[test]
+    fn test_digest_result() {
+        let mut digest = Algorithm::Shake128.create_digest();
+        digest_reader(&mut *digest, &mut &b""[..], true).unwrap();
+        assert_eq!(digest_result(&mut *digest, 16), "7f9c");
+
+        let mut digest = Algorithm::Crc.create_digest();
+        let size = digest_reader(&mut *digest, &mut &b"hello\n"[..], true).unwrap();
+        assert_eq!(size, 6);
+        assert_eq!(digest_result(&mut *digest, 0), "3015617425");
+    }
+
+    #[test]
+    fn test_read_strategy_from_name() {
+        assert_eq!(ReadStrategy::from_name("auto"), Some(ReadStrategy::Auto));
+        assert_eq!(ReadStrategy::from_name("mmap"), Some(ReadStrategy::Mmap));
+        assert_eq!(ReadStrategy::from_name("read"), Some(ReadStrategy::Read));
+        assert_eq!(ReadStrategy::from_name("direct"), None);
+        assert_eq!(ReadStrategy::default(), ReadStrategy::Read);
+    }
+
+    #[test]
+    fn test_digest_file_strategies() {
+        let contents: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let mut file = tempfile::tempfile().unwrap();
+        io::Write::write_all(&mut file, &contents).unwrap();
+
+        let mut expected = Algorithm::Md5.create_digest();
+        digest_reader(&mut *expected, &mut &contents[..], true).unwrap();
+        let expected = expected.result_str();
+
+        for strategy in [ReadStrategy::Auto, ReadStrategy::Mmap, ReadStrategy::Read] {
+            let mut digest = Algorithm::Md5.create_digest();
+            io::Seek::rewind(&mut &file).unwrap();
+            let size = digest_file(&mut *digest, &file, true, strategy).unwrap();
+            assert_eq!(size, contents.len() as u64);
+            assert_eq!(digest.result_str(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_gnu_line() {
+        // Too long for the expected size
+        assert_eq!(parse_checksum_line("0123ABcd  file name", "MD5", 16), None);
+        assert_eq!(
+            parse_checksum_line("0123ABcd  file name", "MD5", 32),
+            Some(ChecksumLine {
+                digest: "0123abcd".to_string(),
+                filename: "file name",
+                binary: false,
+            })
+        );
+        assert_eq!(
+            parse_checksum_line("0123 *file", "SHAKE128", 0),
+            Some(ChecksumLine {
+                digest: "0123".to_string(),
+                filename: "file",
+                binary: true,
+            })
+        );
+        assert_eq!(parse_checksum_line("0123 file", "MD5", 16), None);
+        assert_eq!(parse_checksum_line("012  file", "MD5", 16), None);
+    }
+
+    #[test]
+    fn test_parse_bsd_line() {
+        assert_eq!(
+            parse_checksum_line("MD5 (a) = b) = 0123", "MD5", 16),
+            Some(ChecksumLine {
+                digest: "0123".to_string(),
+                filename: "a) = b",
+                binary: true,
+            })
+        );
+        assert_eq!(parse_checksum_line("MD5 (a) = 0123) = zz", "MD5", 16), None);
+        assert_eq!(parse_checksum_line("MD5 (a) = 0123zz", "MD5", 16), None);
+        assert_eq!(parse_checksum_line("MD5 (a) = 01234", "MD5", 16), None);
+        assert_eq!(parse_checksum_line("SHA1 (a) = 0123", "MD5", 16), None);
+        assert_eq!(parse_checksum_line("MD5 (a) = 012", "MD5", 16), None);
+    }
+}
@@ -1,4 +1,4 @@
-// spell-checker:ignore memmem
+// spell-checker:ignore memmem sysv
 //! Implementations of digest functions, like md5 and sha1.
 //!
 //! The [`Digest`] trait represents the interface for providing inputs
@@ -6,6 +6,10 @@
 //! [`DigestWriter`] struct provides a wrapper around [`Digest`] that
 //! implements the [`Write`] trait, for use in situations where calling
 //! [`write`] would be useful.
+//!
+//! Besides the cryptographic hashes, this module implements the
+//! traditional checksums of `cksum` ([`Crc`]) and `sum` ([`Bsd`] and
+//! [`SysV`]), whose results are reported in decimal rather than hex.
 use std::io::Write;
 
 use hex::encode;
@@ -144,6 +148,172 @@ impl_digest_common!(sha3::Sha3_512, 512);
 impl_digest_shake!(sha3::Shake128);
 impl_digest_shake!(sha3::Shake256);
 
+// NOTE: CRC_TABLE_LEN *must* be <= 256 as we cast 0..CRC_TABLE_LEN to u8
+const CRC_TABLE_LEN: usize = 256;
+const CRC_TABLE: [u32; CRC_TABLE_LEN] = generate_crc_table();
+
+const fn generate_crc_table() -> [u32; CRC_TABLE_LEN] {
+    let mut table = [0; CRC_TABLE_LEN];
+
+    let mut i = 0;
+    while i < CRC_TABLE_LEN {
+        table[i] = crc_entry(i as u8);
+
+        i += 1;
+    }
+
+    table
+}
+
+const fn crc_entry(input: u8) -> u32 {
+    let mut crc = (input as u32) << 24;
+
+    let mut i = 0;
+    while i < 8 {
+        let if_condition = crc & 0x8000_0000;
+        let if_body = (crc << 1) ^ 0x04c1_1db7;
+        let else_body = crc << 1;
+
+        // NOTE: i feel like this is easier to understand than emulating an if statement in bitwise
+        //       ops
+        let condition_table = [else_body, if_body];
+
+        crc = condition_table[(if_condition != 0) as usize];
+        i += 1;
+    }
+
+    crc
+}
+
+/// The POSIX `cksum` CRC, which covers both the data and its length.
+pub struct Crc {
+    state: u32,
+    size: usize,
+}
+
+impl Crc {
+    #[inline]
+    fn update(crc: u32, input: u8) -> u32 {
+        (crc << 8) ^ CRC_TABLE[((crc >> 24) as usize ^ input as usize) & 0xFF]
+    }
+
+    fn finalize(&self) -> u32 {
+        let mut crc = self.state;
+        let mut length = self.size;
+        while length != 0 {
+            crc = Self::update(crc, length as u8);
+            length >>= 8;
+        }
+
+        !crc
+    }
+}
+
+impl Digest for Crc {
+    fn new() -> Self {
+        Self { state: 0, size: 0 }
+    }
+
+    fn input(&mut self, input: &[u8]) {
+        for &b in input {
+            self.state = Self::update(self.state, b);
+        }
+        self.size += input.len();
+    }
+
+    fn result(&mut self, out: &mut [u8]) {
+        out.copy_from_slice(&self.finalize().to_be_bytes());
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn output_bits(&self) -> usize {
+        32
+    }
+
+    fn result_str(&mut self) -> String {
+        self.finalize().to_string()
+    }
+}
+
+/// The BSD `sum` checksum, a 16-bit rotating checksum.
+pub struct Bsd {
+    state: u16,
+}
+
+impl Digest for Bsd {
+    fn new() -> Self {
+        Self { state: 0 }
+    }
+
+    fn input(&mut self, input: &[u8]) {
+        for &byte in input {
+            self.state = (self.state >> 1) + ((self.state & 1) << 15);
+            self.state = self.state.wrapping_add(u16::from(byte));
+        }
+    }
+
+    fn result(&mut self, out: &mut [u8]) {
+        out.copy_from_slice(&self.state.to_be_bytes());
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn output_bits(&self) -> usize {
+        16
+    }
+
+    fn result_str(&mut self) -> String {
+        self.state.to_string()
+    }
+}
+
+/// The System V `sum` checksum, the sum of all bytes folded to 16 bits.
+pub struct SysV {
+    state: u32,
+}
+
+impl SysV {
+    fn finalize(&self) -> u16 {
+        let mut ret = self.state;
+        ret = (ret & 0xffff) + (ret >> 16);
+        ret = (ret & 0xffff) + (ret >> 16);
+        ret as u16
+    }
+}
+
+impl Digest for SysV {
+    fn new() -> Self {
+        Self { state: 0 }
+    }
+
+    fn input(&mut self, input: &[u8]) {
+        for &byte in input {
+            self.state = self.state.wrapping_add(u32::from(byte));
+        }
+    }
+
+    fn result(&mut self, out: &mut [u8]) {
+        out.copy_from_slice(&self.finalize().to_be_bytes());
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn output_bits(&self) -> usize {
+        16
+    }
+
+    fn result_str(&mut self) -> String {
+        self.finalize().to_string()
+    }
+}
+
 /// A struct that writes to a digest.
 ///
 /// This struct wraps a [`Digest`] and provides a [`Write`]
@@ -154,7 +324,7 @@ impl_digest_shake!(sha3::Shake256);
 /// implementation replaces instances of "\r\n" with "\n" before passing
 /// the input bytes to the [`digest`].
 pub struct DigestWriter<'a> {
-    digest: &'a mut Box<dyn Digest>,
+    digest: &'a mut dyn Digest,
 
     /// Whether to write to the digest in binary mode or text mode on Windows.
     ///
@@ -171,7 +341,7 @@ pub struct DigestWriter<'a> {
 }
 
 impl<'a> DigestWriter<'a> {
-    pub fn new(digest: &'a mut Box<dyn Digest>, binary: bool) -> DigestWriter {
+    pub fn new(digest: &'a mut dyn Digest, binary: bool) -> DigestWriter<'a> {
         let was_last_character_carriage_return = false;
         DigestWriter {
             digest,
@@ -255,6 +425,27 @@ impl<'a> Write for DigestWriter<'a> {
 
 #[cfg(test)]
 mod tests {
+    use super::{Bsd, Crc, Digest, SysV};
+
+    fn digest_str<D: Digest>(input: &[u8]) -> String {
+        let mut digest = D::new();
+        digest.input(input);
+        digest.result_str()
+    }
+
+    #[test]
+    fn test_crc() {
+        // Reference values from GNU cksum.
+        assert_eq!(digest_str::<Crc>(b""), "4294967295");
+        assert_eq!(digest_str::<Crc>(b"hello\n"), "3015617425");
+    }
+
+    #[test]
+    fn test_sum() {
+        // Reference values from GNU sum -r and sum -s.
+        assert_eq!(digest_str::<Bsd>(b"hello\n"), "36979");
+        assert_eq!(digest_str::<SysV>(b"hello\n"), "542");
+    }
 
     /// Test for replacing a "\r\n" sequence with "\n" when the "\r" is
     /// at the end of one block and the "\n" is at the beginning of the
@@ -264,12 +455,12 @@ mod tests {
     fn test_crlf_across_blocks() {
         use std::io::Write;
 
-        use crate::digest::Digest;
-        use crate::digest::DigestWriter;
+        use super::Digest;
+        use super::DigestWriter;
 
         // Writing "\r" in one call to `write()`, and then "\n" in another.
         let mut digest = Box::new(md5::Md5::new()) as Box<dyn Digest>;
-        let mut writer_crlf = DigestWriter::new(&mut digest, false);
+        let mut writer_crlf = DigestWriter::new(&mut *digest, false);
         writer_crlf.write_all(&[b'\r']).unwrap();
         writer_crlf.write_all(&[b'\n']).unwrap();
         writer_crlf.finalize();
@@ -277,7 +468,7 @@ mod tests {
 
         // We expect "\r\n" to be replaced with "\n" in text mode on Windows.
         let mut digest = Box::new(md5::Md5::new()) as Box<dyn Digest>;
-        let mut writer_lf = DigestWriter::new(&mut digest, false);
+        let mut writer_lf = DigestWriter::new(&mut *digest, false);
         writer_lf.write_all(&[b'\n']).unwrap();
         writer_lf.finalize();
         let result_lf = digest.result_str();
@@ -0,0 +1,98 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// spell-checker:ignore (sys/unix) execvp
+
+//! Running the command given to a utility like `env`, `nice`, `nohup`,
+//! `timeout` or `chroot`.
+//!
+//! Like GNU coreutils, a command that can't be found exits with
+//! [`EXIT_ENOENT`] (127) and one that was found but couldn't be run exits
+//! with [`EXIT_CANNOT_INVOKE`] (126). The command is looked for in `PATH`
+//! unless it contains a `/`, as `execvp` does.
+
+use std::ffi::OsStr;
+use std::io;
+
+use crate::display::Quotable;
+use crate::error::{ErrorContext, FromIo, UError, UErrorExt, EXIT_CANNOT_INVOKE, EXIT_ENOENT};
+
+/// The exit code of a utility that couldn't run its command because of `err`.
+pub fn exit_code(err: &io::Error) -> i32 {
+    if err.kind() == io::ErrorKind::NotFound {
+        EXIT_ENOENT
+    } else {
+        EXIT_CANNOT_INVOKE
+    }
+}
+
+/// The error of a utility that couldn't run `command`, phrased like
+/// `failed to run command 'foo': No such file or directory`.
+pub fn run_error<T: Quotable + ?Sized>(command: &T, err: io::Error) -> Box<dyn UError> {
+    ErrorContext::new("failed to run command")
+        .operand(command)
+        .code(exit_code(&err))
+        .io(err)
+}
+
+/// The error of a utility that couldn't run `command`, phrased like
+/// `'foo': No such file or directory` as `env` and `nice` do.
+pub fn command_error<T: Quotable + ?Sized>(command: &T, err: io::Error) -> Box<dyn UError> {
+    let code = exit_code(&err);
+    err.map_err_context(|| command.quote().to_string())
+        .with_error_code(code)
+}
+
+/// Replaces the current process with `command` run with `args`.
+///
+/// This only returns if the command couldn't be run, with the error to give
+/// to [`run_error`] or [`command_error`].
+#[cfg(unix)]
+pub fn exec<C, I, S>(command: C, args: I) -> io::Error
+where
+    C: AsRef<OsStr>,
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+
+    Command::new(command).args(args).exec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(&io::ErrorKind::NotFound.into()), 127);
+        assert_eq!(exit_code(&io::ErrorKind::PermissionDenied.into()), 126);
+    }
+
+    #[test]
+    fn test_run_error() {
+        let err = run_error("foo", io::ErrorKind::NotFound.into());
+        assert_eq!(
+            err.to_string(),
+            "failed to run command 'foo': Entity not found"
+        );
+        assert_eq!(err.code(), 127);
+    }
+
+    #[test]
+    fn test_command_error() {
+        let err = command_error("", io::ErrorKind::PermissionDenied.into());
+        assert_eq!(err.to_string(), "'': Permission denied");
+        assert_eq!(err.code(), 126);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exec_not_found() {
+        let err = exec("/nonexistent/command", ["arg"]);
+        assert_eq!(exit_code(&err), 127);
+    }
+}
@@ -8,7 +8,7 @@
 
 //! Set of functions to manage files and symlinks
 
-// spell-checker:ignore backport
+// spell-checker:ignore backport UNC
 
 #[cfg(unix)]
 use libc::{
@@ -28,6 +28,7 @@ use std::io::{Error, ErrorKind, Result as IOResult};
 #[cfg(unix)]
 use std::os::unix::{fs::MetadataExt, io::AsRawFd};
 use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 #[cfg(target_os = "windows")]
 use winapi_util::AsHandleRef;
 
@@ -135,6 +136,15 @@ impl FileInformation {
         return self.0.number_of_links();
     }
 
+    /// The device (volume on Windows) the file resides on.
+    pub fn device(&self) -> u64 {
+        #[cfg(unix)]
+        #[allow(clippy::unnecessary_cast)]
+        return self.0.st_dev as u64;
+        #[cfg(target_os = "windows")]
+        return self.0.volume_serial_number();
+    }
+
     #[cfg(unix)]
     pub fn inode(&self) -> u64 {
         #[cfg(all(not(target_os = "freebsd"), target_pointer_width = "64"))]
@@ -142,6 +152,50 @@ impl FileInformation {
         #[cfg(any(target_os = "freebsd", not(target_pointer_width = "64")))]
         return self.0.st_ino.into();
     }
+
+    /// The time of the last access to the file, to the nanosecond.
+    #[cfg(unix)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn accessed(&self) -> SystemTime {
+        system_time(self.0.st_atime as i64, self.0.st_atime_nsec as i64)
+    }
+
+    /// The time of the last modification of the file, to the nanosecond.
+    #[cfg(unix)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn modified(&self) -> SystemTime {
+        system_time(self.0.st_mtime as i64, self.0.st_mtime_nsec as i64)
+    }
+
+    /// The time of the last change of the status of the file, to the
+    /// nanosecond.
+    #[cfg(unix)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn changed(&self) -> SystemTime {
+        system_time(self.0.st_ctime as i64, self.0.st_ctime_nsec as i64)
+    }
+}
+
+/// The time `sec` seconds and `nsec` nanoseconds after the epoch, as in a
+/// `timespec`: `sec` is negative for times before the epoch, while `nsec`
+/// is always between 0 and 999999999.
+pub fn system_time(sec: i64, nsec: i64) -> SystemTime {
+    let nanos = Duration::from_nanos(nsec.clamp(0, 999_999_999) as u64);
+    if sec < 0 {
+        UNIX_EPOCH - Duration::from_secs(sec.unsigned_abs()) + nanos
+    } else {
+        UNIX_EPOCH + Duration::from_secs(sec as u64) + nanos
+    }
+}
+
+/// The time of `SOURCE_DATE_EPOCH`, a number of seconds after the epoch,
+/// which the files made by a reproducible build are not to be newer than.
+/// A value that is not a number is ignored.
+///
+/// See <https://reproducible-builds.org/specs/source-date-epoch/>.
+pub fn source_date_epoch() -> Option<SystemTime> {
+    let sec = env::var("SOURCE_DATE_EPOCH").ok()?.parse().ok()?;
+    Some(system_time(sec, 0))
 }
 
 #[cfg(unix)]
@@ -582,6 +636,202 @@ pub fn make_path_relative_to<P1: AsRef<Path>, P2: AsRef<Path>>(path: P1, to: P2)
     components.iter().collect()
 }
 
+/// Open the destination `path` of a copy for writing, creating it with
+/// `mode`, less the umask, if it does not exist, and truncating it
+/// otherwise. With `exclusive`, `path` must not exist yet.
+///
+/// On unix, like GNU `cp` and `install`, the file is opened relative to a
+/// descriptor of the directory it is in, and a symbolic link at `path` is
+/// not followed: a link swapped in after the destination was checked, like
+/// by another user of a world-writable directory such as `/tmp`, fails
+/// the copy rather than redirecting it. Callers that mean to write through
+/// a link are to resolve it first. Elsewhere, `mode` is ignored.
+pub fn create_dest(path: &Path, mode: u32, exclusive: bool) -> IOResult<fs::File> {
+    #[cfg(unix)]
+    {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::io::FromRawFd;
+
+        let name = match path.file_name() {
+            Some(name) => name,
+            // `..` or `/`, which can't be a file anyway.
+            None => return Err(Error::from_raw_os_error(libc::EISDIR)),
+        };
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let dir = fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_DIRECTORY)
+            .open(dir)?;
+        let name =
+            CString::new(name.as_bytes()).map_err(|_| Error::from(ErrorKind::InvalidInput))?;
+        let mut flags = libc::O_WRONLY | libc::O_CREAT | libc::O_NOFOLLOW | libc::O_CLOEXEC;
+        flags |= if exclusive {
+            libc::O_EXCL
+        } else {
+            libc::O_TRUNC
+        };
+        let fd = unsafe { libc::openat(dir.as_raw_fd(), name.as_ptr(), flags, mode) };
+        if fd < 0 {
+            return Err(Error::last_os_error());
+        }
+        // SAFETY: `fd` was just opened, and nothing else owns it.
+        Ok(unsafe { fs::File::from_raw_fd(fd) })
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = mode;
+        let mut options = fs::OpenOptions::new();
+        options.write(true);
+        if exclusive {
+            options.create_new(true);
+        } else {
+            options.create(true).truncate(true);
+        }
+        options.open(path)
+    }
+}
+
+/// Turn `path` into an extended-length path on Windows, like
+/// `\\?\C:\dir\file`, or `\\?\UNC\server\share\file` for a network share.
+///
+/// Most of the Windows API rejects paths longer than `MAX_PATH` (260)
+/// characters, unless they are in this form, which deep trees like those of
+/// `node_modules` easily require. The path is made absolute and normalized
+/// first, since in extended-length paths `/` is not a separator, and `.` and
+/// `..` are regular names.
+///
+/// On other platforms, `path` is returned as is.
+pub fn extended_length_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if !windows_path::is_verbatim(path) {
+        if let Ok(full) = windows_path::full_path_name(path) {
+            return windows_path::to_extended_length(&full).into();
+        }
+    }
+    path.into()
+}
+
+/// Turn `path` into an extended-length path like [`extended_length_path`]
+/// does, but only if it is too long for the Windows API otherwise.
+///
+/// This is meant to be applied to the paths given to file system functions,
+/// while messages keep showing the paths as the user gave them.
+pub fn long_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if !windows_path::is_verbatim(path) {
+        if let Ok(full) = windows_path::full_path_name(path) {
+            if windows_path::is_too_long(&full) {
+                return windows_path::to_extended_length(&full).into();
+            }
+        }
+    }
+    path.into()
+}
+
+/// Remove the prefix of an extended-length path, to show it to users the
+/// way they would write it.
+pub fn display_path(path: &Path) -> Cow<'_, Path> {
+    #[cfg(windows)]
+    if let Some(s) = path.to_str() {
+        if let Some(rest) = s.strip_prefix(windows_path::VERBATIM_UNC_PREFIX) {
+            return PathBuf::from(format!(r"\\{rest}")).into();
+        } else if let Some(rest) = s.strip_prefix(windows_path::VERBATIM_PREFIX) {
+            return Path::new(rest).into();
+        }
+    }
+    path.into()
+}
+
+#[cfg(windows)]
+mod windows_path {
+    use std::ffi::OsString;
+    use std::io;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Component, Path, PathBuf, Prefix};
+    use std::ptr;
+
+    use windows_sys::Win32::Foundation::MAX_PATH;
+    use windows_sys::Win32::Storage::FileSystem::GetFullPathNameW;
+
+    pub(super) const VERBATIM_PREFIX: &str = r"\\?\";
+    pub(super) const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+    /// Whether `path` is passed to the file system as is.
+    pub(super) fn is_verbatim(path: &Path) -> bool {
+        match path.components().next() {
+            Some(Component::Prefix(prefix)) => matches!(
+                prefix.kind(),
+                Prefix::Verbatim(_)
+                    | Prefix::VerbatimUNC(..)
+                    | Prefix::VerbatimDisk(_)
+                    | Prefix::DeviceNS(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the absolute `full` path is too long to be used as is.
+    pub(super) fn is_too_long(full: &[u16]) -> bool {
+        // Directories are limited to 12 characters less, to leave room for
+        // an 8.3 file name in them.
+        full.len() >= MAX_PATH as usize - 12
+    }
+
+    /// Add the extended-length prefix to the absolute, normalized `full`
+    /// path.
+    pub(super) fn to_extended_length(full: &[u16]) -> PathBuf {
+        let path = PathBuf::from(OsString::from_wide(full));
+        // Device names like `NUL` resolve to a device path like `\\.\NUL`.
+        if is_verbatim(&path) {
+            return path;
+        }
+        let backslash = u16::from(b'\\');
+        let extended: Vec<u16> = match full {
+            // A network share, like `\\server\share\file`
+            [a, b, rest @ ..] if *a == backslash && *b == backslash => VERBATIM_UNC_PREFIX
+                .encode_utf16()
+                .chain(rest.iter().copied())
+                .collect(),
+            _ => VERBATIM_PREFIX
+                .encode_utf16()
+                .chain(full.iter().copied())
+                .collect(),
+        };
+        PathBuf::from(OsString::from_wide(&extended))
+    }
+
+    /// The absolute and normalized form of `path`, as Windows itself would
+    /// resolve it.
+    pub(super) fn full_path_name(path: &Path) -> io::Result<Vec<u16>> {
+        let path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let mut buffer = vec![0u16; MAX_PATH as usize];
+        loop {
+            let len = unsafe {
+                GetFullPathNameW(
+                    path.as_ptr(),
+                    buffer.len() as u32,
+                    buffer.as_mut_ptr(),
+                    ptr::null_mut(),
+                )
+            } as usize;
+            if len == 0 {
+                return Err(io::Error::last_os_error());
+            } else if len > buffer.len() {
+                // The buffer was too small, and `len` is the size needed
+                buffer.resize(len, 0);
+            } else {
+                buffer.truncate(len);
+                return Ok(buffer);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
@@ -627,6 +877,29 @@ mod tests {
         },
     ];
 
+    #[test]
+    fn test_system_time() {
+        assert_eq!(system_time(0, 0), UNIX_EPOCH);
+        assert_eq!(system_time(1, 5), UNIX_EPOCH + Duration::new(1, 5));
+        assert_eq!(
+            system_time(-2, 250_000_000),
+            UNIX_EPOCH - Duration::from_millis(1750)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_information_times() {
+        let info = FileInformation::from_path(".", true).unwrap();
+        let metadata = fs::metadata(".").unwrap();
+        assert_eq!(info.modified(), metadata.modified().unwrap());
+        assert_eq!(info.accessed(), metadata.accessed().unwrap());
+        assert_eq!(
+            info.changed(),
+            system_time(metadata.ctime(), metadata.ctime_nsec())
+        );
+    }
+
     #[test]
     fn test_normalize_path() {
         for test in &NORMALIZE_PATH_TESTS {
@@ -640,6 +913,31 @@ mod tests {
         }
     }
 
+    #[cfg(not(windows))]
+    #[test]
+    fn test_long_path_unchanged() {
+        let path = Path::new("a").join("b".repeat(300));
+        assert_eq!(extended_length_path(&path), path);
+        assert_eq!(long_path(&path), path);
+        assert_eq!(display_path(&path), path);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_path() {
+        let short = Path::new(r"C:\dir\file");
+        assert_eq!(long_path(short), short);
+        assert_eq!(extended_length_path(short), Path::new(r"\\?\C:\dir\file"));
+
+        let long = PathBuf::from(format!(r"C:\{}\file", "d".repeat(300)));
+        let extended = long_path(&long);
+        assert_eq!(extended, Path::new(&format!(r"\\?\{}", long.display())));
+        assert_eq!(display_path(&extended), long);
+
+        let share = Path::new(r"\\?\UNC\server\share\file");
+        assert_eq!(display_path(share), Path::new(r"\\server\share\file"));
+    }
+
     #[cfg(unix)]
     #[test]
     fn test_display_permissions() {
@@ -693,4 +991,35 @@ mod tests {
             display_permissions_unix(S_IFCHR | S_ISVTX as mode_t | 0o054, true)
         );
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_dest() {
+        use std::io::Write;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let target = dir.join("target");
+        let link = dir.join("link");
+        fs::write(&target, "old").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        // A symbolic link is not written through.
+        let err = create_dest(&link, 0o644, false).unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::ELOOP));
+        assert!(create_dest(&link, 0o644, true).is_err());
+        assert_eq!(fs::read_to_string(&target).unwrap(), "old");
+
+        // An existing file is truncated, unless it has to be new.
+        assert!(create_dest(&target, 0o644, true).is_err());
+        create_dest(&target, 0o644, false)
+            .unwrap()
+            .write_all(b"new")
+            .unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+
+        let new = dir.join("new");
+        create_dest(&new, 0o600, true).unwrap();
+        assert_eq!(fs::metadata(&new).unwrap().mode() & 0o777, 0o600);
+    }
 }
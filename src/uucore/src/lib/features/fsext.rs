@@ -9,7 +9,7 @@
 
 //! Set of functions to manage file systems
 
-// spell-checker:ignore DATETIME subsecond (arch) bitrig ; (fs) cifs smbfs
+// spell-checker:ignore DATETIME subsecond (arch) bitrig ; (fs) cifs smbfs exfat CDFS
 
 extern crate time;
 use time::macros::format_description;
@@ -35,8 +35,9 @@ use std::os::windows::ffi::OsStrExt;
 use windows_sys::Win32::Foundation::{ERROR_NO_MORE_FILES, INVALID_HANDLE_VALUE};
 #[cfg(windows)]
 use windows_sys::Win32::Storage::FileSystem::{
-    FindFirstVolumeW, FindNextVolumeW, FindVolumeClose, GetDiskFreeSpaceW, GetDriveTypeW,
-    GetVolumeInformationW, GetVolumePathNamesForVolumeNameW, QueryDosDeviceW,
+    FindFirstVolumeW, FindNextVolumeW, FindVolumeClose, GetDiskFreeSpaceExW, GetDiskFreeSpaceW,
+    GetDriveTypeW, GetVolumeInformationW, GetVolumePathNameW, GetVolumePathNamesForVolumeNameW,
+    QueryDosDeviceW,
 };
 #[cfg(windows)]
 use windows_sys::Win32::System::WindowsProgramming::DRIVE_REMOTE;
@@ -78,11 +79,12 @@ use std::convert::{AsRef, From};
 use std::ffi::CStr;
 #[cfg(not(windows))]
 use std::ffi::CString;
-use std::io::Error as IOError;
+use std::io::{Error as IOError, Result as IOResult};
 #[cfg(unix)]
 use std::mem;
-#[cfg(not(unix))]
 use std::path::Path;
+#[cfg(unix)]
+use std::path::PathBuf;
 use std::time::UNIX_EPOCH;
 
 #[cfg(any(
@@ -123,7 +125,9 @@ pub use libc::statvfs as statfs_fn;
 
 pub trait BirthTime {
     fn pretty_birth(&self) -> String;
-    fn birth(&self) -> u64;
+    /// The seconds and nanoseconds since the epoch of the birth of the
+    /// file, if known.
+    fn birth(&self) -> Option<(u64, u32)>;
 }
 
 use std::fs::Metadata;
@@ -136,12 +140,11 @@ impl BirthTime for Metadata {
             .unwrap_or_else(|| "-".to_owned())
     }
 
-    fn birth(&self) -> u64 {
+    fn birth(&self) -> Option<(u64, u32)> {
         self.created()
             .ok()
             .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
-            .map(|e| e.as_secs())
-            .unwrap_or_default()
+            .map(|e| (e.as_secs(), e.subsec_nanos()))
     }
 }
 
@@ -220,10 +223,10 @@ impl MountInfo {
                     + 1;
                 let mut m = Self {
                     dev_id: String::new(),
-                    dev_name: raw[after_fields + 1].to_string(),
+                    dev_name: unescape_octal(raw[after_fields + 1]),
                     fs_type: raw[after_fields].to_string(),
-                    mount_root: raw[3].to_string(),
-                    mount_dir: raw[4].to_string(),
+                    mount_root: unescape_octal(raw[3]),
+                    mount_dir: unescape_octal(raw[4]),
                     mount_option: raw[5].to_string(),
                     remote: false,
                     dummy: false,
@@ -234,10 +237,10 @@ impl MountInfo {
             LINUX_MTAB => {
                 let mut m = Self {
                     dev_id: String::new(),
-                    dev_name: raw[0].to_string(),
+                    dev_name: unescape_octal(raw[0]),
                     fs_type: raw[2].to_string(),
                     mount_root: String::new(),
-                    mount_dir: raw[1].to_string(),
+                    mount_dir: unescape_octal(raw[1]),
                     mount_option: raw[3].to_string(),
                     remote: false,
                     dummy: false,
@@ -393,6 +396,31 @@ use std::io::{BufRead, BufReader};
     target_os = "openbsd"
 ))]
 use std::ptr;
+/// Undo the escaping of the fields of the Linux mount tables, where the
+/// kernel writes a space, a tab, a newline and a backslash as `\040`,
+/// `\011`, `\012` and `\134`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 4).filter(|digits| {
+            bytes[i] == b'\\'
+                && digits[0] <= b'3'
+                && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+        });
+        if let Some(digits) = escape {
+            unescaped.push(digits.iter().fold(0, |n, d| n * 8 + (d - b'0')));
+            i += 4;
+        } else {
+            unescaped.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&unescaped).into_owned()
+}
+
 #[cfg(any(
     target_vendor = "apple",
     target_os = "freebsd",
@@ -484,6 +512,115 @@ pub fn read_fs_list() -> Result<Vec<MountInfo>, std::io::Error> {
     }
 }
 
+/// Find the mounted file system that contains a given path.
+///
+/// This function returns the element of `mounts` with the longest mount
+/// directory that `path` is in, which is the last one mounted there if
+/// there are several. If there are no matches, this function returns
+/// [`None`].
+///
+/// The `path` is expected to be canonical, see [`Path::canonicalize`].
+pub fn mount_info_from_path<P>(mounts: &[MountInfo], path: P) -> Option<&MountInfo>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    mounts
+        .iter()
+        .filter(|mi| path.starts_with(&mi.mount_dir))
+        .max_by_key(|mi| mi.mount_dir.len())
+}
+
+/// Find the mount point of the file system that the file at `path` is on.
+///
+/// Like GNU `stat`, a file or directory that is mounted on, possibly with a
+/// bind mount, is its own mount point. Otherwise, the mount point is the
+/// topmost ancestor of the directory of the file that is on the same
+/// device. A symbolic link is followed only if `dereference` is true.
+#[cfg(unix)]
+pub fn find_mount_point<P>(path: P, dereference: bool, mounts: &[MountInfo]) -> IOResult<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    use std::os::unix::fs::MetadataExt;
+
+    let path = path.as_ref();
+    let metadata = if dereference {
+        std::fs::metadata(path)?
+    } else {
+        std::fs::symlink_metadata(path)?
+    };
+    let mut dir = if metadata.file_type().is_symlink() {
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+            _ => Path::new(".").canonicalize()?,
+        }
+    } else {
+        let mut resolved = path.canonicalize()?;
+        if mounts.iter().any(|mi| Path::new(&mi.mount_dir) == resolved) {
+            return Ok(resolved);
+        }
+        if !metadata.is_dir() {
+            resolved.pop();
+        }
+        resolved
+    };
+    while let Some(parent) = dir.parent() {
+        if std::fs::metadata(parent)?.dev() != metadata.dev() {
+            break;
+        }
+        dir.pop();
+    }
+    Ok(dir)
+}
+
+/// Get the identifier of the file system that the file at `path` is on.
+///
+/// This is its device number on unix and the serial number of its volume
+/// on Windows. Two files are on the same file system if and only if they
+/// have the same identifier, which is how `--one-file-system` tells the
+/// directories not to descend into. A symbolic link is followed only if
+/// `dereference` is true.
+pub fn file_system_id<P>(path: P, dereference: bool) -> IOResult<u64>
+where
+    P: AsRef<Path>,
+{
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = if dereference {
+            std::fs::metadata(path)?
+        } else {
+            std::fs::symlink_metadata(path)?
+        };
+        Ok(metadata.dev())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        use std::os::windows::io::AsRawHandle;
+        use windows_sys::Win32::Storage::FileSystem::{
+            GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS,
+            FILE_FLAG_OPEN_REPARSE_POINT,
+        };
+
+        let mut flags = FILE_FLAG_BACKUP_SEMANTICS;
+        if !dereference {
+            flags |= FILE_FLAG_OPEN_REPARSE_POINT;
+        }
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(flags)
+            .open(path)?;
+        let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+        if 0 == unsafe { GetFileInformationByHandle(file.as_raw_handle() as _, &mut info) } {
+            return Err(IOError::last_os_error());
+        }
+        Ok(info.dwVolumeSerialNumber.into())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FsUsage {
     pub blocksize: u64,
@@ -534,72 +671,28 @@ impl FsUsage {
             };
         }
     }
-    #[cfg(not(unix))]
-    pub fn new(path: &Path) -> Self {
-        let mut root_path = [0u16; MAX_PATH];
-        let success = unsafe {
-            GetVolumePathNamesForVolumeNameW(
-                //path_utf8.as_ptr(),
-                String2LPWSTR!(path.as_os_str()),
-                root_path.as_mut_ptr(),
-                root_path.len() as u32,
-                ptr::null_mut(),
-            )
-        };
-        if 0 == success {
-            crash!(
-                EXIT_ERR,
-                "GetVolumePathNamesForVolumeNameW failed: {}",
-                IOError::last_os_error()
-            );
-        }
-
-        let mut sectors_per_cluster = 0;
-        let mut bytes_per_sector = 0;
-        let mut number_of_free_clusters = 0;
-        let mut total_number_of_clusters = 0;
-
-        let success = unsafe {
-            GetDiskFreeSpaceW(
-                String2LPWSTR!(path.as_os_str()),
-                &mut sectors_per_cluster,
-                &mut bytes_per_sector,
-                &mut number_of_free_clusters,
-                &mut total_number_of_clusters,
-            )
-        };
-        if 0 == success {
-            // Fails in case of CD for example
-            // crash!(
-            //     EXIT_ERR,
-            //     "GetDiskFreeSpaceW failed: {}",
-            //     IOError::last_os_error()
-            // );
-        }
-
-        let bytes_per_cluster = sectors_per_cluster as u64 * bytes_per_sector as u64;
+    #[cfg(windows)]
+    pub fn new(statfs: StatFs) -> Self {
         Self {
-            // f_bsize      File system block size.
-            blocksize: bytes_per_cluster,
-            // f_blocks - Total number of blocks on the file system, in units of f_frsize.
-            // frsize =     Fundamental file system block size (fragment size).
-            blocks: total_number_of_clusters as u64,
-            //  Total number of free blocks.
-            bfree: number_of_free_clusters as u64,
-            //  Total number of free blocks available to non-privileged processes.
-            bavail: 0,
-            bavail_top_bit_set: ((bytes_per_sector as u64) & (1u64.rotate_right(1))) != 0,
-            // Total number of file nodes (inodes) on the file system.
-            files: 0, // Not available on windows
-            // Total number of free file nodes (inodes).
-            ffree: 0, // Meaningless on Windows
+            blocksize: statfs.block_size as u64,
+            blocks: statfs.total_blocks(),
+            bfree: statfs.free_blocks(),
+            bavail: statfs.avail_blocks(),
+            bavail_top_bit_set: false,
+            // Windows file systems have no fixed number of file nodes.
+            files: 0,
+            ffree: 0,
         }
     }
 }
 
-#[cfg(unix)]
+#[cfg(any(unix, windows))]
 pub trait FsMeta {
     fn fs_type(&self) -> i64;
+    /// The name of the file system type, like `ext2/ext3` or `NTFS`.
+    fn fs_type_name(&self) -> Cow<'_, str> {
+        pretty_fstype(self.fs_type())
+    }
     fn io_size(&self) -> u64;
     fn block_size(&self) -> i64;
     fn total_blocks(&self) -> u64;
@@ -815,6 +908,155 @@ where
     }
 }
 
+/// File system information on Windows, the counterpart of `statfs`.
+///
+/// Sizes are counted in clusters, the allocation unit of Windows file
+/// systems.
+#[cfg(windows)]
+#[derive(Debug, Clone)]
+pub struct StatFs {
+    fs_name: String,
+    serial_number: u32,
+    max_component_length: u32,
+    block_size: u32,
+    total_bytes: u64,
+    free_bytes: u64,
+    avail_bytes: u64,
+}
+
+#[cfg(windows)]
+impl FsMeta for StatFs {
+    fn fs_type(&self) -> i64 {
+        // The magic numbers that Linux uses for the same file systems, so
+        // that `%t` of `stat -f` means the same everywhere.
+        // spell-checker:disable
+        match self.fs_name.as_str() {
+            "NTFS" => 0x5346_544E,
+            "FAT" | "FAT32" => 0x4006,
+            "exFAT" => 0x2011_BAB0,
+            "CDFS" => 0x9660,
+            "UDF" => 0x1501_3346,
+            _ => 0,
+        }
+        // spell-checker:enable
+    }
+    fn fs_type_name(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.fs_name)
+    }
+    fn io_size(&self) -> u64 {
+        self.block_size as u64
+    }
+    fn block_size(&self) -> i64 {
+        self.block_size as i64
+    }
+    fn total_blocks(&self) -> u64 {
+        self.total_bytes / self.block_size as u64
+    }
+    fn free_blocks(&self) -> u64 {
+        self.free_bytes / self.block_size as u64
+    }
+    fn avail_blocks(&self) -> u64 {
+        self.avail_bytes / self.block_size as u64
+    }
+    fn total_file_nodes(&self) -> u64 {
+        0
+    }
+    fn free_file_nodes(&self) -> u64 {
+        0
+    }
+    fn fsid(&self) -> u64 {
+        self.serial_number as u64
+    }
+    fn namelen(&self) -> u64 {
+        self.max_component_length as u64
+    }
+}
+
+/// Get information about the file system that `path` is on, which can be
+/// any file or directory, or a volume name like `\\?\Volume{...}\`.
+///
+/// The available space takes the disk quota of the user into account.
+#[cfg(windows)]
+pub fn statfs<P>(path: P) -> Result<StatFs, String>
+where
+    P: AsRef<Path>,
+{
+    let path: Vec<u16> = path
+        .as_ref()
+        .as_os_str()
+        .encode_wide()
+        .chain(Some(0))
+        .collect();
+    let mut root = [0u16; MAX_PATH];
+    if 0 == unsafe { GetVolumePathNameW(path.as_ptr(), root.as_mut_ptr(), root.len() as u32) } {
+        return Err(IOError::last_os_error().to_string());
+    }
+
+    let mut serial_number = 0;
+    let mut max_component_length = 0;
+    let mut fs_name = [0u16; MAX_PATH];
+    let success = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            ptr::null_mut(),
+            0,
+            &mut serial_number,
+            &mut max_component_length,
+            ptr::null_mut(),
+            fs_name.as_mut_ptr(),
+            fs_name.len() as u32,
+        )
+    };
+    if 0 == success {
+        return Err(IOError::last_os_error().to_string());
+    }
+
+    let mut sectors_per_cluster = 0;
+    let mut bytes_per_sector = 0;
+    let mut free_clusters = 0;
+    let mut total_clusters = 0;
+    let success = unsafe {
+        GetDiskFreeSpaceW(
+            root.as_ptr(),
+            &mut sectors_per_cluster,
+            &mut bytes_per_sector,
+            &mut free_clusters,
+            &mut total_clusters,
+        )
+    };
+    if 0 == success {
+        // Fails in case of CD without a disc for example
+        return Err(IOError::last_os_error().to_string());
+    }
+
+    // The cluster counts above are capped for volumes of more than 2 TiB,
+    // so the sizes are taken from `GetDiskFreeSpaceExW` instead.
+    let mut avail_bytes = 0;
+    let mut total_bytes = 0;
+    let mut free_bytes = 0;
+    let success = unsafe {
+        GetDiskFreeSpaceExW(
+            root.as_ptr(),
+            &mut avail_bytes,
+            &mut total_bytes,
+            &mut free_bytes,
+        )
+    };
+    if 0 == success {
+        return Err(IOError::last_os_error().to_string());
+    }
+
+    Ok(StatFs {
+        fs_name: LPWSTR2String(&fs_name),
+        serial_number,
+        max_component_length,
+        block_size: (sectors_per_cluster * bytes_per_sector).max(1),
+        total_bytes,
+        free_bytes,
+        avail_bytes,
+    })
+}
+
 // match strftime "%Y-%m-%d %H:%M:%S.%f %z"
 const PRETTY_DATETIME_FORMAT: &[time::format_description::FormatItem] = format_description!(
     "\
@@ -823,6 +1065,37 @@ const PRETTY_DATETIME_FORMAT: &[time::format_description::FormatItem] = format_d
 [offset_hour sign:mandatory][offset_minute]"
 );
 
+/// Formats the time `sec` seconds and `nsec` nanoseconds after the epoch as
+/// a number of seconds with `precision` digits after the decimal point,
+/// truncated, like the `%.3Y` of GNU `stat` does.
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::fsext::pretty_seconds;
+///
+/// assert_eq!(pretty_seconds(12, 345_678_900, 3), "12.345");
+/// assert_eq!(pretty_seconds(-2, 250_000_000, 2), "-1.75");
+/// assert_eq!(pretty_seconds(-2, 250_000_000, 0), "-2");
+/// ```
+pub fn pretty_seconds(sec: i64, nsec: i64, precision: usize) -> String {
+    if precision == 0 {
+        return sec.to_string();
+    }
+    // A time before the epoch is written as the negative of its distance
+    // to the epoch.
+    let (sign, sec, nsec) = if sec < 0 && nsec > 0 {
+        ("-", (sec + 1).unsigned_abs(), 1_000_000_000 - nsec)
+    } else if sec < 0 {
+        ("-", sec.unsigned_abs(), nsec)
+    } else {
+        ("", sec.unsigned_abs(), nsec)
+    };
+    let mut fraction = format!("{nsec:09}");
+    fraction.truncate(precision);
+    format!("{sign}{sec}.{fraction:0<precision$}")
+}
+
 pub fn pretty_time(sec: i64, nsec: i64) -> String {
     // sec == seconds since UNIX_EPOCH
     // nsec == nanoseconds since (UNIX_EPOCH + sec)
@@ -1071,5 +1344,109 @@ mod tests {
 
         assert_eq!(info.fs_type, "xfs");
         assert_eq!(info.dev_name, "/dev/fs0");
+
+        // Test the escaping of white space and backslashes.
+        let info = MountInfo::new(
+            LINUX_MOUNTINFO,
+            &r"106 109 253:6 /a\134b /mnt/my\040disk\011x rw - xfs /dev/fs0 rw"
+                .split_ascii_whitespace()
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        assert_eq!(info.mount_root, r"/a\b");
+        assert_eq!(info.mount_dir, "/mnt/my disk\tx");
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn test_unescape_octal() {
+        assert_eq!(unescape_octal(r"a\040b"), "a b");
+        assert_eq!(unescape_octal(r"\134\040"), "\\ ");
+        assert_eq!(unescape_octal(r"a\04"), r"a\04");
+        assert_eq!(unescape_octal(r"a\400b"), r"a\400b");
+        assert_eq!(unescape_octal(r"a\08"), r"a\08");
+    }
+
+    mod mount_info_from_path {
+
+        use crate::fsext::{mount_info_from_path, MountInfo};
+
+        // Create a fake `MountInfo` with the given directory name.
+        fn mount_info(mount_dir: &str) -> MountInfo {
+            MountInfo {
+                dev_id: Default::default(),
+                dev_name: Default::default(),
+                fs_type: Default::default(),
+                mount_dir: String::from(mount_dir),
+                mount_option: Default::default(),
+                mount_root: Default::default(),
+                remote: Default::default(),
+                dummy: Default::default(),
+            }
+        }
+
+        #[test]
+        fn test_empty_mounts() {
+            assert!(mount_info_from_path(&[], "/").is_none());
+        }
+
+        #[test]
+        fn test_exact_match() {
+            let mounts = [mount_info("/foo")];
+            let actual = mount_info_from_path(&mounts, "/foo").unwrap();
+            assert!(std::ptr::eq(actual, &mounts[0]));
+        }
+
+        #[test]
+        fn test_prefix_match() {
+            let mounts = [mount_info("/foo")];
+            let actual = mount_info_from_path(&mounts, "/foo/bar").unwrap();
+            assert!(std::ptr::eq(actual, &mounts[0]));
+        }
+
+        #[test]
+        fn test_multiple_matches() {
+            let mounts = [mount_info("/foo"), mount_info("/foo/bar")];
+            let actual = mount_info_from_path(&mounts, "/foo/bar").unwrap();
+            assert!(std::ptr::eq(actual, &mounts[1]));
+        }
+
+        #[test]
+        fn test_last_mounted_wins() {
+            let mounts = [mount_info("/"), mount_info("/foo"), mount_info("/foo")];
+            let actual = mount_info_from_path(&mounts, "/foo/bar").unwrap();
+            assert!(std::ptr::eq(actual, &mounts[2]));
+        }
+
+        #[test]
+        fn test_no_match() {
+            let mounts = [mount_info("/foo")];
+            assert!(mount_info_from_path(&mounts, "/bar").is_none());
+        }
+
+        #[test]
+        fn test_partial_match() {
+            let mounts = [mount_info("/foo/bar")];
+            assert!(mount_info_from_path(&mounts, "/foo/baz").is_none());
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_find_mount_point() {
+        let mounts = read_fs_list().unwrap();
+        assert_eq!(
+            find_mount_point("/", false, &mounts).unwrap(),
+            Path::new("/")
+        );
+
+        let dir = std::env::temp_dir().canonicalize().unwrap();
+        let mount_point = find_mount_point(&dir, false, &mounts).unwrap();
+        assert!(dir.starts_with(&mount_point));
+        assert_eq!(
+            file_system_id(&mount_point, true).unwrap(),
+            file_system_id(&dir, true).unwrap()
+        );
     }
 }
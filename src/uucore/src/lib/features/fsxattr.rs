@@ -0,0 +1,131 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Copying of the extended attributes of files, for `cp --preserve=xattr`
+//! and the utilities that copy files like it.
+//!
+//! Like with GNU coreutils, the attributes that can't be copied don't
+//! always make the copy fail: that depends on the [`XattrPolicy`] that the
+//! caller chooses, usually from whether the user asked for the attributes
+//! or they are only part of a larger set, like with `cp -a`.
+
+// spell-checker:ignore ENOTSUP ENODATA
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+
+use crate::capabilities::{self, Capability};
+
+/// The attribute holding the SELinux context of a file.
+const SELINUX_CONTEXT: &str = "security.selinux";
+
+/// How to handle the attributes that can't be copied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XattrPolicy {
+    /// Any failure is an error, which stops the copy of the attributes, like
+    /// with an explicit `--preserve=xattr`.
+    Required,
+    /// The attributes that the file systems don't support are silently
+    /// skipped, and the first other failure is an error once all the
+    /// attributes were tried, like with `cp -a`.
+    BestEffort,
+}
+
+/// Whether the attribute `name` is worth copying by this process.
+///
+/// The attributes of the `security` namespace, like the capabilities of
+/// executables, can only be set by privileged processes, so they are
+/// skipped by the others, which would fail to set them.
+pub fn is_copied(name: &OsStr) -> bool {
+    let is_security = name
+        .to_str()
+        .map_or(false, |name| name.starts_with("security."));
+    !is_security || capabilities::has(Capability::SysAdmin)
+}
+
+/// Whether `error` means that the file system doesn't support extended
+/// attributes, or the one that was being copied.
+fn is_unsupported(error: &io::Error) -> bool {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    let codes = [libc::ENOTSUP, libc::ENODATA];
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let codes = [libc::ENOTSUP, libc::ENOATTR];
+    error
+        .raw_os_error()
+        .map_or(false, |code| codes.contains(&code))
+        || error.kind() == io::ErrorKind::Unsupported
+}
+
+/// Copy the extended attributes of `source` for which [`is_copied()`] holds
+/// to `dest`, handling the failures as `policy` says.
+///
+/// Symbolic links are not followed: the attributes of a link are copied to
+/// the link `dest` is.
+pub fn copy_xattrs(source: &Path, dest: &Path, policy: XattrPolicy) -> io::Result<()> {
+    let mut first_error = None;
+    let mut handle = |error: io::Error| match policy {
+        XattrPolicy::Required => Err(error),
+        XattrPolicy::BestEffort => {
+            if !is_unsupported(&error) && first_error.is_none() {
+                first_error = Some(error);
+            }
+            Ok(())
+        }
+    };
+
+    let names = match xattr::list(source) {
+        Ok(names) => names,
+        Err(error) => {
+            handle(error)?;
+            return Ok(());
+        }
+    };
+    for name in names.filter(|name| is_copied(name)) {
+        let result = xattr::get(source, &name).and_then(|value| match value {
+            Some(value) => xattr::set(dest, &name, &value),
+            // Removed since it was listed.
+            None => Ok(()),
+        });
+        if let Err(error) = result {
+            handle(error)?;
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Copy the SELinux context of `source`, if it has one, to `dest`.
+///
+/// The context is in the `security` namespace, which [`is_copied()`] leaves
+/// out for unprivileged processes, but the policy usually lets the owner of
+/// a file relabel it, so this is worth trying for them.
+pub fn copy_selinux_context(source: &Path, dest: &Path) -> io::Result<()> {
+    match xattr::get(source, SELINUX_CONTEXT)? {
+        Some(context) => xattr::set(dest, SELINUX_CONTEXT, &context),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::OsString;
+
+    #[test]
+    fn test_is_copied() {
+        assert!(is_copied(&OsString::from("user.comment")));
+        assert!(is_copied(&OsString::from("trusted.security.x")));
+        assert_eq!(
+            is_copied(&OsString::from("security.capability")),
+            capabilities::has(Capability::SysAdmin)
+        );
+    }
+
+    #[test]
+    fn test_is_unsupported() {
+        assert!(is_unsupported(&io::Error::from_raw_os_error(libc::ENOTSUP)));
+        assert!(!is_unsupported(&io::Error::from_raw_os_error(libc::EPERM)));
+    }
+}
@@ -0,0 +1,191 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE file
+// that was distributed with this source code.
+
+// spell-checker:ignore (sys/unix) fadvise FADV ESPIPE
+
+//! Reading the whole input of utilities like `cat`, `head`, `tail` and `wc`.
+//!
+//! A [`Reader`] is the standard input or an opened file. The regular files
+//! are read with large buffers, after telling the kernel that they will be
+//! read sequentially so that it reads ahead more of them, as GNU coreutils
+//! do. Only the regular files can be seeked.
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Stdin};
+use std::path::Path;
+
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+
+/// The size of the buffers of the regular files, which are read in few
+/// system calls with it.
+pub const FILE_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The size of the buffers of the pipes, terminals and other devices, which
+/// is the capacity of a pipe on Linux.
+pub const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+enum Source {
+    Stdin(Stdin),
+    File(File),
+}
+
+/// The standard input or a file, read by a utility.
+pub struct Reader {
+    source: Source,
+    /// The size of the file, if it is a regular one.
+    size: Option<u64>,
+}
+
+impl Reader {
+    /// The standard input, which is never seeked, even if it is a file.
+    pub fn stdin() -> Self {
+        Self {
+            source: Source::Stdin(io::stdin()),
+            size: None,
+        }
+    }
+
+    /// Opens the file at `path` for reading.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        File::open(path).map(Self::from_file)
+    }
+
+    /// Reads `file` from its current position.
+    pub fn from_file(file: File) -> Self {
+        let size = file
+            .metadata()
+            .ok()
+            .filter(|md| md.is_file())
+            .map(|md| md.len());
+        if size.is_some() {
+            advise_sequential(&file);
+        }
+        Self {
+            source: Source::File(file),
+            size,
+        }
+    }
+
+    /// Whether this is a regular file, which can be seeked.
+    pub fn is_seekable(&self) -> bool {
+        self.size.is_some()
+    }
+
+    /// The size of this file when it was opened, if it is a regular one.
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    /// The size of the buffer to read this input with: up to
+    /// [`FILE_BUFFER_SIZE`] for a regular file, which can be small, and
+    /// [`STREAM_BUFFER_SIZE`] for anything else.
+    pub fn buffer_size(&self) -> usize {
+        match self.size {
+            Some(size) => usize::try_from(size)
+                .unwrap_or(usize::MAX)
+                .saturating_add(1)
+                .clamp(STREAM_BUFFER_SIZE, FILE_BUFFER_SIZE),
+            None => STREAM_BUFFER_SIZE,
+        }
+    }
+
+    /// This input with a buffer of [`Self::buffer_size`].
+    pub fn buffered(self) -> BufReader<Self> {
+        BufReader::with_capacity(self.buffer_size(), self)
+    }
+
+    /// The file that is read, unless it is the standard input.
+    pub fn file_mut(&mut self) -> Option<&mut File> {
+        match &mut self.source {
+            Source::Stdin(_) => None,
+            Source::File(file) => Some(file),
+        }
+    }
+}
+
+impl Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match &mut self.source {
+            Source::Stdin(stdin) => stdin.read(buf),
+            Source::File(file) => file.read(buf),
+        }
+    }
+}
+
+impl Seek for Reader {
+    /// Seeks in a regular file, and fails like on a pipe for anything else.
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match &mut self.source {
+            Source::File(file) if self.size.is_some() => file.seek(pos),
+            _ => Err(not_seekable()),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn not_seekable() -> io::Error {
+    io::Error::from_raw_os_error(libc::ESPIPE)
+}
+
+#[cfg(not(unix))]
+fn not_seekable() -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported, "Illegal seek")
+}
+
+#[cfg(unix)]
+impl AsRawFd for Reader {
+    fn as_raw_fd(&self) -> RawFd {
+        match &self.source {
+            Source::Stdin(stdin) => stdin.as_raw_fd(),
+            Source::File(file) => file.as_raw_fd(),
+        }
+    }
+}
+
+/// Tells the kernel that `file` is going to be read from start to end.
+#[cfg(any(target_os = "linux", target_os = "android", target_os = "freebsd"))]
+fn advise_sequential(file: &File) {
+    // It is only a hint, whose failure doesn't matter.
+    unsafe { libc::posix_fadvise(file.as_raw_fd(), 0, 0, libc::POSIX_FADV_SEQUENTIAL) };
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "freebsd")))]
+fn advise_sequential(_file: &File) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_regular_file() {
+        let contents = std::fs::read_to_string("Cargo.toml").unwrap();
+        let mut reader = Reader::open("Cargo.toml").unwrap();
+        assert!(reader.is_seekable());
+        assert_eq!(reader.size(), Some(contents.len() as u64));
+        assert_eq!(reader.seek(SeekFrom::Start(2)).unwrap(), 2);
+
+        let line = reader.buffered().lines().next().unwrap().unwrap();
+        assert_eq!(Some(line.as_str()), contents[2..].lines().next());
+    }
+
+    #[test]
+    fn test_buffer_size() {
+        let reader = Reader {
+            source: Source::Stdin(io::stdin()),
+            size: Some(u64::MAX),
+        };
+        assert_eq!(reader.buffer_size(), FILE_BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_stdin() {
+        let mut reader = Reader::stdin();
+        assert!(!reader.is_seekable());
+        assert_eq!(reader.buffer_size(), STREAM_BUFFER_SIZE);
+        assert!(reader.seek(SeekFrom::Start(0)).is_err());
+        assert!(reader.file_mut().is_none());
+    }
+}
@@ -13,7 +13,217 @@
 //! characters. This is useful if the input data does not end with a
 //! newline character and you want to preserve the exact form of the
 //! input data.
-use std::io::BufRead;
+//!
+//! The utilities that take `-z/--zero-terminated` handle records ending
+//! with a NUL instead of lines, which is what [`LineEnding`] is for, so that
+//! file names with newlines in them can go through a whole pipeline.
+//!
+//! Those that compare lines read them as [`records`], which take `--crlf`
+//! for the text written on Windows into account.
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read};
+
+/// The byte that lines end with, which is NUL with `-z/--zero-terminated`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Newline = b'\n',
+    Nul = 0,
+}
+
+impl LineEnding {
+    /// The line ending of a utility, depending on whether it was given
+    /// `-z/--zero-terminated`.
+    pub fn from_zero_flag(is_zero_terminated: bool) -> Self {
+        if is_zero_terminated {
+            Self::Nul
+        } else {
+            Self::Newline
+        }
+    }
+
+    /// `line` without this line ending, if it ends with it.
+    pub fn strip<'a>(&self, line: &'a [u8]) -> &'a [u8] {
+        line.strip_suffix(&[*self as u8]).unwrap_or(line)
+    }
+}
+
+impl Default for LineEnding {
+    fn default() -> Self {
+        Self::Newline
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Newline => writeln!(f),
+            Self::Nul => write!(f, "\0"),
+        }
+    }
+}
+
+/// `line`, without its line ending, without the carriage return of a
+/// Windows line ending, which is what is compared with `--crlf`.
+pub fn strip_crlf(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// [`strip_crlf`] for a line that was read as a `str`.
+pub fn strip_crlf_str(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+const BYTE_ORDER_MARK: &[u8] = "\u{feff}".as_bytes();
+
+/// Returns a reader of the text of `reader` without the UTF-8 byte order mark
+/// that it starts with, as the text written on Windows often does.
+pub fn skip_byte_order_mark<R: Read>(reader: R) -> SkipByteOrderMark<R> {
+    SkipByteOrderMark {
+        inner: reader,
+        head: Vec::new(),
+        checked: false,
+    }
+}
+
+/// A reader without the byte order mark of another.
+///
+/// This struct is created by calling [`skip_byte_order_mark`].
+pub struct SkipByteOrderMark<R> {
+    inner: R,
+    /// The first bytes of `inner` that are still to be read, which were read
+    /// to look for the byte order mark and weren't one.
+    head: Vec<u8>,
+    checked: bool,
+}
+
+impl<R: Read> Read for SkipByteOrderMark<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.checked {
+            let mut head = [0; BYTE_ORDER_MARK.len()];
+            let mut len = 0;
+            while len < head.len() {
+                match self.inner.read(&mut head[len..]) {
+                    Ok(0) => break,
+                    Ok(n) => len += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            self.checked = true;
+            if head[..len] != *BYTE_ORDER_MARK {
+                self.head = head[..len].to_vec();
+            }
+        }
+        if !self.head.is_empty() {
+            let len = self.head.len().min(buf.len());
+            buf[..len].copy_from_slice(&self.head[..len]);
+            self.head.drain(..len);
+            return Ok(len);
+        }
+        self.inner.read(buf)
+    }
+}
+
+/// Returns an iterator over the records of `reader`, which end with
+/// `line_ending`, for the utilities that compare them.
+///
+/// With `crlf`, for their `--crlf`, the text is taken as written on Windows:
+/// the byte order mark that starts it is skipped, and the carriage returns
+/// that end its lines are not part of the [keys](Record::key) of the records,
+/// although they are still written with them.
+pub fn records<R: Read>(reader: R, line_ending: LineEnding, crlf: bool) -> Records<R> {
+    let mut reader = skip_byte_order_mark(reader);
+    reader.checked = !crlf;
+    Records {
+        reader: BufReader::new(reader),
+        line_ending,
+        crlf,
+    }
+}
+
+/// An iterator over the records of a reader.
+///
+/// This struct is created by calling [`records`].
+pub struct Records<R> {
+    reader: BufReader<SkipByteOrderMark<R>>,
+    line_ending: LineEnding,
+    crlf: bool,
+}
+
+impl<R: Read> Iterator for Records<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<io::Result<Record>> {
+        let mut bytes = Vec::new();
+        match self.reader.read_until(self.line_ending as u8, &mut bytes) {
+            Ok(0) => None,
+            Ok(_) => {
+                let line_len = self.line_ending.strip(&bytes).len();
+                let key_len = if self.crlf {
+                    strip_crlf(&bytes[..line_len]).len()
+                } else {
+                    line_len
+                };
+                Some(Ok(Record {
+                    bytes,
+                    line_len,
+                    key_len,
+                }))
+            }
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// A record read by [`Records`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    bytes: Vec<u8>,
+    line_len: usize,
+    key_len: usize,
+}
+
+impl Record {
+    /// The record as it was read, with its line ending if it has one.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The record without its line ending.
+    pub fn line(&self) -> &[u8] {
+        &self.bytes[..self.line_len]
+    }
+
+    /// The part of the record that is compared: the record without its line
+    /// ending and, with `--crlf`, without a carriage return before it.
+    pub fn key(&self) -> &[u8] {
+        &self.bytes[..self.key_len]
+    }
+
+    /// The record as it was read, with the length of its line and its key.
+    pub fn into_parts(self) -> (Vec<u8>, usize, usize) {
+        (self.bytes, self.line_len, self.key_len)
+    }
+}
+
+/// The argument shared by the utilities that compare lines.
+pub mod arguments {
+    extern crate clap;
+
+    pub static OPT_CRLF: &str = "linesopt_crlf";
+
+    /// '--crlf' argument
+    pub fn crlf() -> clap::Arg {
+        clap::Arg::new(OPT_CRLF)
+            .long("crlf")
+            .help(
+                "compare the lines of text written on Windows: ignore the carriage \
+                returns that end the lines and the byte order mark that starts the text",
+            )
+            .action(clap::ArgAction::SetTrue)
+    }
+}
 
 /// Returns an iterator over the lines, including line ending characters.
 ///
@@ -84,8 +294,10 @@ impl<B: BufRead> Iterator for Lines<B> {
 
 #[cfg(test)]
 mod tests {
-    use crate::lines::lines;
-    use std::io::Cursor;
+    use crate::lines::{
+        lines, records, skip_byte_order_mark, strip_crlf, strip_crlf_str, LineEnding,
+    };
+    use std::io::{Cursor, Read};
 
     #[test]
     fn test_lines() {
@@ -110,4 +322,62 @@ mod tests {
         assert_eq!(it.next(), Some(Vec::from("z\0")));
         assert_eq!(it.next(), None);
     }
+
+    #[test]
+    fn test_line_ending() {
+        let ending = LineEnding::from_zero_flag(true);
+        assert_eq!(ending, LineEnding::Nul);
+        assert_eq!(ending.strip(b"x\0"), b"x");
+        assert_eq!(ending.strip(b"x\n"), b"x\n");
+        assert_eq!(LineEnding::default().strip(b"x\n"), b"x");
+        assert_eq!(LineEnding::Newline.to_string(), "\n");
+        assert_eq!(LineEnding::Nul.to_string(), "\0");
+    }
+
+    #[test]
+    fn test_strip_crlf() {
+        assert_eq!(strip_crlf(b"x\r"), b"x");
+        assert_eq!(strip_crlf(b"x\r\r"), b"x\r");
+        assert_eq!(strip_crlf(b"\rx"), b"\rx");
+        assert_eq!(strip_crlf_str("x\r"), "x");
+        assert_eq!(strip_crlf_str("x"), "x");
+    }
+
+    #[test]
+    fn test_skip_byte_order_mark() {
+        fn read(bytes: &[u8]) -> Vec<u8> {
+            let mut text = vec![];
+            skip_byte_order_mark(bytes).read_to_end(&mut text).unwrap();
+            text
+        }
+
+        assert_eq!(read(b"\xEF\xBB\xBFx\xEF\xBB\xBF"), b"x\xEF\xBB\xBF");
+        assert_eq!(read(b"\xEF\xBBx"), b"\xEF\xBBx");
+        assert_eq!(read(b"\xEF"), b"\xEF");
+        assert_eq!(read(b""), b"");
+    }
+
+    #[test]
+    fn test_records() {
+        let text: &[u8] = b"\xEF\xBB\xBFx\r\n\xEF\xBB\xBFy\r\nz";
+        let mut it = records(text, LineEnding::Newline, true).map(|r| r.unwrap());
+        let x = it.next().unwrap();
+        assert_eq!(x.as_bytes(), b"x\r\n");
+        assert_eq!(x.line(), b"x\r");
+        assert_eq!(x.key(), b"x");
+        // Only the byte order mark of the start of the text is skipped.
+        assert_eq!(it.next().unwrap().key(), b"\xEF\xBB\xBFy");
+        assert_eq!(it.next().unwrap().key(), b"z");
+        assert_eq!(it.next(), None);
+
+        let mut it = records(text, LineEnding::Newline, false).map(|r| r.unwrap());
+        let x = it.next().unwrap();
+        assert_eq!(x.as_bytes(), b"\xEF\xBB\xBFx\r\n");
+        assert_eq!(x.key(), b"\xEF\xBB\xBFx\r");
+
+        let mut it = records(&b"x\0y"[..], LineEnding::Nul, false).map(|r| r.unwrap());
+        assert_eq!(it.next().unwrap().key(), b"x");
+        assert_eq!(it.next().unwrap().as_bytes(), b"y");
+        assert_eq!(it.next(), None);
+    }
 }
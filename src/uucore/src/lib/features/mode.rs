@@ -6,9 +6,13 @@
 // file that was distributed with this source code.
 
 //! Set of functions to parse modes
+//!
+//! Parsing a mode works everywhere, for utilities like `chmod` that emulate
+//! modes on Windows, but there is no umask there.
 
 // spell-checker:ignore (vars) fperm srwx
 
+#[cfg(not(windows))]
 use libc::{mode_t, umask, S_IRGRP, S_IROTH, S_IRUSR, S_IWGRP, S_IWOTH, S_IWUSR};
 
 pub fn parse_numeric(fperm: u32, mut mode: &str, considering_dir: bool) -> Result<u32, String> {
@@ -130,6 +134,33 @@ fn parse_change(mode: &str, fperm: u32, considering_dir: bool) -> (u32, usize) {
     (srwx, pos)
 }
 
+/// The permissions of a file created with the `MODE` of `-m`, like by
+/// `mkdir`, `mkfifo`, `mknod` and `install`.
+///
+/// The comma-separated clauses of `mode` change the permissions `initial` in
+/// turn: the numeric ones replace them, and the symbolic ones change them
+/// like with chmod, leaving out the bits of `umask` when they don't say whose
+/// permissions they change, like `+w` but unlike `g+w`. The result is the
+/// mode of the new file, which the umask doesn't apply to anymore.
+pub fn parse_creation_mode(
+    mode: &str,
+    initial: u32,
+    umask: u32,
+    considering_dir: bool,
+) -> Result<u32, String> {
+    let digits: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    mode.split(',').try_fold(initial, |perm, clause| {
+        if clause.contains(digits) {
+            parse_numeric(perm, clause, considering_dir)
+        } else {
+            parse_symbolic(perm, clause, umask, considering_dir)
+        }
+    })
+}
+
+/// The permissions of a file other than a directory created with the `MODE`
+/// of `-m`, starting from `a=rw` like `mkfifo` and `mknod` do.
+#[cfg(not(windows))]
 pub fn parse_mode(mode: &str) -> Result<mode_t, String> {
     #[cfg(all(
         not(target_os = "freebsd"),
@@ -140,15 +171,10 @@ pub fn parse_mode(mode: &str) -> Result<mode_t, String> {
     #[cfg(any(target_os = "freebsd", target_vendor = "apple", target_os = "android"))]
     let fperm = (S_IRUSR | S_IWUSR | S_IRGRP | S_IWGRP | S_IROTH | S_IWOTH) as u32;
 
-    let arr: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
-    let result = if mode.contains(arr) {
-        parse_numeric(fperm, mode, true)
-    } else {
-        parse_symbolic(fperm, mode, get_umask(), true)
-    };
-    result.map(|mode| mode as mode_t)
+    parse_creation_mode(mode, fperm, get_umask(), false).map(|mode| mode as mode_t)
 }
 
+#[cfg(not(windows))]
 pub fn get_umask() -> u32 {
     // There's no portable way to read the umask without changing it.
     // We have to replace it and then quickly set it back, hopefully before
@@ -192,7 +218,7 @@ pub fn strip_minus_from_mode(args: &mut Vec<String>) -> bool {
     false
 }
 
-#[cfg(test)]
+#[cfg(all(test, not(windows)))]
 mod test {
 
     #[test]
@@ -206,6 +232,25 @@ mod test {
         assert_eq!(super::parse_mode("g-r").unwrap(), 0o626);
     }
 
+    #[test]
+    fn creation_modes() {
+        use super::parse_creation_mode;
+        // Without whose permissions they change, the clauses leave out the
+        // bits of the umask.
+        assert_eq!(parse_creation_mode("+w", 0o666, 0o022, false), Ok(0o666));
+        assert_eq!(parse_creation_mode("+x", 0o666, 0o022, false), Ok(0o777));
+        assert_eq!(parse_creation_mode("=w", 0o777, 0o022, true), Ok(0o200));
+        assert_eq!(parse_creation_mode("-w", 0o777, 0o022, true), Ok(0o577));
+        assert_eq!(parse_creation_mode("g+w", 0o755, 0o022, true), Ok(0o775));
+        assert_eq!(parse_creation_mode("+w", 0, 0, false), Ok(0o222));
+        assert_eq!(
+            parse_creation_mode("u=rwx,go=rx", 0o777, 0o077, true),
+            Ok(0o755)
+        );
+        assert_eq!(parse_creation_mode("600", 0o666, 0o022, false), Ok(0o600));
+        assert!(parse_creation_mode("g+q", 0o666, 0o022, false).is_err());
+    }
+
     #[test]
     fn numeric_modes() {
         assert_eq!(super::parse_mode("644").unwrap(), 0o644);
@@ -0,0 +1,524 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// spell-checker:ignore (vars) lconv localeconv setlocale strtod strtold strtoimax
+
+//! Parsing numbers like the C library does for GNU coreutils.
+//!
+//! GNU `seq`, `sleep`, `timeout` and `printf` read their numbers with
+//! `strtod`, `strtold` or `strtoimax`, so they all accept leading white
+//! space, a sign, hexadecimal numbers, infinities, and the decimal point of
+//! the locale as well as `.`. [`parse_float_prefix`] and
+//! [`parse_integer_prefix`] read the same forms, and leave it to each
+//! utility to decide what to do with the rest of the argument.
+
+use once_cell::sync::Lazy;
+
+/// The base in which the digits of a number are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+/// A floating point number, as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatLiteral<'a> {
+    /// The number `integral.fractional` in `radix`, times 10 to the power
+    /// of `exponent` for decimal numbers, or 2 for hexadecimal ones.
+    Finite {
+        negative: bool,
+        radix: Radix,
+        integral: &'a str,
+        fractional: &'a str,
+        exponent: i64,
+    },
+    Infinity {
+        negative: bool,
+    },
+    NaN {
+        negative: bool,
+    },
+}
+
+/// An integer, as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegerLiteral<'a> {
+    pub negative: bool,
+    pub radix: Radix,
+    /// The digits in `radix`, including the leading `0` of octal numbers
+    /// but not the `0x` of hexadecimal ones.
+    pub digits: &'a str,
+}
+
+static DECIMAL_POINT: Lazy<char> = Lazy::new(locale_decimal_point);
+
+/// The decimal point of the locale, from `LC_ALL`, `LC_NUMERIC` or `LANG`.
+#[cfg(unix)]
+fn locale_decimal_point() -> char {
+    use std::ffi::CStr;
+
+    // The locale of the process is left as it was.
+    unsafe {
+        let previous = libc::setlocale(libc::LC_NUMERIC, std::ptr::null());
+        let previous = (!previous.is_null()).then(|| CStr::from_ptr(previous).to_owned());
+        if libc::setlocale(libc::LC_NUMERIC, b"\0".as_ptr().cast()).is_null() {
+            return '.';
+        }
+        let conv = libc::localeconv();
+        let point = if conv.is_null() || (*conv).decimal_point.is_null() {
+            None
+        } else {
+            CStr::from_ptr((*conv).decimal_point)
+                .to_str()
+                .ok()
+                .and_then(|point| point.chars().next())
+        };
+        if let Some(previous) = previous {
+            libc::setlocale(libc::LC_NUMERIC, previous.as_ptr());
+        }
+        point.unwrap_or('.')
+    }
+}
+
+#[cfg(not(unix))]
+fn locale_decimal_point() -> char {
+    '.'
+}
+
+/// The decimal point of the locale, which is accepted along with `.`.
+pub fn decimal_point() -> char {
+    *DECIMAL_POINT
+}
+
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r')
+}
+
+/// Skips the white space and the sign that start a number.
+fn parse_sign(s: &str) -> (bool, &str) {
+    let s = s.trim_start_matches(is_space);
+    if let Some(s) = s.strip_prefix('-') {
+        (true, s)
+    } else {
+        (false, s.strip_prefix('+').unwrap_or(s))
+    }
+}
+
+/// Splits `s` after its longest prefix of chars for which `f` holds.
+fn split_while(s: &str, f: impl Fn(char) -> bool) -> (&str, &str) {
+    s.split_at(s.find(|c| !f(c)).unwrap_or(s.len()))
+}
+
+/// Strips `prefix` from `s`, ignoring the case of ASCII letters.
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(start) if start.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// Parses the exponent that starts `s` if it starts with one of `markers`,
+/// saturating it if it doesn't fit.
+fn parse_exponent<'a>(s: &'a str, markers: &[char]) -> (i64, &'a str) {
+    let after_marker = match s.strip_prefix(markers) {
+        Some(after_marker) => after_marker,
+        None => return (0, s),
+    };
+    let (negative, unsigned) = match after_marker.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (
+            false,
+            after_marker.strip_prefix('+').unwrap_or(after_marker),
+        ),
+    };
+    let (digits, rest) = split_while(unsigned, |c| c.is_ascii_digit());
+    if digits.is_empty() {
+        return (0, s);
+    }
+    let exponent = digits.bytes().fold(0i64, |exponent, digit| {
+        exponent
+            .saturating_mul(10)
+            .saturating_add(i64::from(digit - b'0'))
+    });
+    (if negative { -exponent } else { exponent }, rest)
+}
+
+/// Parses the digits of a number with a fractional part in `radix`.
+fn parse_mantissa(s: &str, hex: bool) -> Option<(&str, &str, &str)> {
+    let is_digit = |c: char| {
+        if hex {
+            c.is_ascii_hexdigit()
+        } else {
+            c.is_ascii_digit()
+        }
+    };
+    let (integral, rest) = split_while(s, is_digit);
+    let (fractional, rest) = match rest.strip_prefix(['.', decimal_point()]) {
+        Some(after_point) => {
+            let (fractional, after_fractional) = split_while(after_point, is_digit);
+            if integral.is_empty() && fractional.is_empty() {
+                return None;
+            }
+            (fractional, after_fractional)
+        }
+        None if integral.is_empty() => return None,
+        None => ("", rest),
+    };
+    Some((integral, fractional, rest))
+}
+
+/// Parses the number that `strtod` would read at the start of `s`, and
+/// returns it with the rest of `s`, or `None` if `s` doesn't start with a
+/// number.
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::numeric::{parse_float_prefix, FloatLiteral, Radix};
+///
+/// assert_eq!(
+///     parse_float_prefix(" 1.5e3s"),
+///     Some((
+///         FloatLiteral::Finite {
+///             negative: false,
+///             radix: Radix::Decimal,
+///             integral: "1",
+///             fractional: "5",
+///             exponent: 3,
+///         },
+///         "s"
+///     ))
+/// );
+/// assert_eq!(parse_float_prefix("abc"), None);
+/// ```
+pub fn parse_float_prefix(s: &str) -> Option<(FloatLiteral<'_>, &str)> {
+    let (negative, s) = parse_sign(s);
+
+    if let Some(rest) = strip_prefix_ignore_case(s, "inf") {
+        let rest = strip_prefix_ignore_case(rest, "inity").unwrap_or(rest);
+        return Some((FloatLiteral::Infinity { negative }, rest));
+    }
+    if let Some(rest) = strip_prefix_ignore_case(s, "nan") {
+        // "nan" may be followed by a sequence of letters, digits and
+        // underscores in parentheses.
+        let rest = rest
+            .strip_prefix('(')
+            .map(|inside| split_while(inside, |c| c.is_ascii_alphanumeric() || c == '_'))
+            .and_then(|(_, after)| after.strip_prefix(')'))
+            .unwrap_or(rest);
+        return Some((FloatLiteral::NaN { negative }, rest));
+    }
+
+    if let Some(after_prefix) = strip_prefix_ignore_case(s, "0x") {
+        if let Some((integral, fractional, rest)) = parse_mantissa(after_prefix, true) {
+            let (exponent, rest) = parse_exponent(rest, &['p', 'P']);
+            let number = FloatLiteral::Finite {
+                negative,
+                radix: Radix::Hexadecimal,
+                integral,
+                fractional,
+                exponent,
+            };
+            return Some((number, rest));
+        }
+        // Without hexadecimal digits, only the "0" is a number.
+    }
+
+    let (integral, fractional, rest) = parse_mantissa(s, false)?;
+    let (exponent, rest) = parse_exponent(rest, &['e', 'E']);
+    let number = FloatLiteral::Finite {
+        negative,
+        radix: Radix::Decimal,
+        integral,
+        fractional,
+        exponent,
+    };
+    Some((number, rest))
+}
+
+/// Parses `s` as a whole like [`parse_float_prefix`], or returns `None` if
+/// it isn't only a number.
+pub fn parse_float(s: &str) -> Option<FloatLiteral<'_>> {
+    match parse_float_prefix(s)? {
+        (number, "") => Some(number),
+        _ => None,
+    }
+}
+
+impl FloatLiteral<'_> {
+    /// Whether the number has a minus sign.
+    pub fn is_negative(&self) -> bool {
+        match self {
+            Self::Finite { negative, .. }
+            | Self::Infinity { negative }
+            | Self::NaN { negative } => *negative,
+        }
+    }
+
+    /// The nearest `f64` to the number.
+    pub fn to_f64(&self) -> f64 {
+        let value = match *self {
+            Self::Infinity { .. } => f64::INFINITY,
+            Self::NaN { .. } => f64::NAN,
+            Self::Finite {
+                radix: Radix::Hexadecimal,
+                integral,
+                fractional,
+                exponent,
+                ..
+            } => {
+                // The first 16 significant digits are enough for an f64.
+                let mut mantissa = 0u64;
+                let mut significant = 0;
+                let mut exponent = exponent;
+                for (i, digit) in integral.chars().chain(fractional.chars()).enumerate() {
+                    let is_fractional = i >= integral.len();
+                    if significant < 16 {
+                        mantissa = mantissa * 16 + u64::from(digit.to_digit(16).unwrap());
+                        if mantissa != 0 {
+                            significant += 1;
+                        }
+                        if is_fractional {
+                            exponent = exponent.saturating_sub(4);
+                        }
+                    } else if !is_fractional {
+                        exponent = exponent.saturating_add(4);
+                    }
+                }
+                // The power of two is applied in two steps, so that a
+                // small result doesn't become zero too early.
+                let exponent = exponent.clamp(-4000, 4000) as i32;
+                mantissa as f64 * 2f64.powi(exponent / 2) * 2f64.powi(exponent - exponent / 2)
+            }
+            Self::Finite {
+                integral,
+                fractional,
+                exponent,
+                ..
+            } => {
+                let integral = if integral.is_empty() { "0" } else { integral };
+                let fractional = if fractional.is_empty() {
+                    "0"
+                } else {
+                    fractional
+                };
+                format!("{integral}.{fractional}e{exponent}")
+                    .parse()
+                    .unwrap_or(f64::NAN)
+            }
+        };
+        if self.is_negative() {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+/// Parses the integer that `strtoimax` would read at the start of `s`, with
+/// `0x` before hexadecimal numbers and `0` before octal ones, and returns
+/// it with the rest of `s`, or `None` if `s` doesn't start with an integer.
+///
+/// # Examples
+///
+/// ```rust
+/// use uucore::numeric::{parse_integer_prefix, IntegerLiteral, Radix};
+///
+/// assert_eq!(
+///     parse_integer_prefix("0x1fg"),
+///     Some((
+///         IntegerLiteral {
+///             negative: false,
+///             radix: Radix::Hexadecimal,
+///             digits: "1f",
+///         },
+///         "g"
+///     ))
+/// );
+/// ```
+pub fn parse_integer_prefix(s: &str) -> Option<(IntegerLiteral<'_>, &str)> {
+    let (negative, s) = parse_sign(s);
+    let (radix, (digits, rest)) = match strip_prefix_ignore_case(s, "0x") {
+        Some(after_prefix) if after_prefix.starts_with(|c: char| c.is_ascii_hexdigit()) => (
+            Radix::Hexadecimal,
+            split_while(after_prefix, |c| c.is_ascii_hexdigit()),
+        ),
+        _ if s.starts_with('0') => (Radix::Octal, split_while(s, |c| matches!(c, '0'..='7'))),
+        _ => (Radix::Decimal, split_while(s, |c| c.is_ascii_digit())),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let number = IntegerLiteral {
+        negative,
+        radix,
+        digits,
+    };
+    Some((number, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal<'a>(
+        negative: bool,
+        integral: &'a str,
+        fractional: &'a str,
+        exponent: i64,
+    ) -> FloatLiteral<'a> {
+        FloatLiteral::Finite {
+            negative,
+            radix: Radix::Decimal,
+            integral,
+            fractional,
+            exponent,
+        }
+    }
+
+    fn hex<'a>(integral: &'a str, fractional: &'a str, exponent: i64) -> FloatLiteral<'a> {
+        FloatLiteral::Finite {
+            negative: false,
+            radix: Radix::Hexadecimal,
+            integral,
+            fractional,
+            exponent,
+        }
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(parse_float("1"), Some(decimal(false, "1", "", 0)));
+        assert_eq!(parse_float(" \t-1.5"), Some(decimal(true, "1", "5", 0)));
+        assert_eq!(parse_float("+.5"), Some(decimal(false, "", "5", 0)));
+        assert_eq!(parse_float("5."), Some(decimal(false, "5", "", 0)));
+        assert_eq!(parse_float("1E-3"), Some(decimal(false, "1", "", -3)));
+        assert_eq!(parse_float("1.2e+3"), Some(decimal(false, "1", "2", 3)));
+        assert_eq!(
+            parse_float("1e99999999999999999999"),
+            Some(decimal(false, "1", "", i64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_hexadecimal() {
+        assert_eq!(parse_float("0x10"), Some(hex("10", "", 0)));
+        assert_eq!(parse_float("0X1.8p1"), Some(hex("1", "8", 1)));
+        assert_eq!(parse_float("0x.8P-1"), Some(hex("", "8", -1)));
+        // Without digits, "0x" is a zero followed by an "x".
+        assert_eq!(
+            parse_float_prefix("0x"),
+            Some((decimal(false, "0", "", 0), "x"))
+        );
+        assert_eq!(
+            parse_float_prefix("0x.p1"),
+            Some((decimal(false, "0", "", 0), "x.p1"))
+        );
+    }
+
+    #[test]
+    fn test_special() {
+        for s in ["inf", "INF", "Infinity", "infINITY"] {
+            assert_eq!(
+                parse_float(s),
+                Some(FloatLiteral::Infinity { negative: false })
+            );
+        }
+        assert_eq!(
+            parse_float_prefix("-infinit"),
+            Some((FloatLiteral::Infinity { negative: true }, "init"))
+        );
+        assert_eq!(
+            parse_float("NaN(abc_1)"),
+            Some(FloatLiteral::NaN { negative: false })
+        );
+        assert_eq!(
+            parse_float_prefix("nan(a"),
+            Some((FloatLiteral::NaN { negative: false }, "(a"))
+        );
+    }
+
+    #[test]
+    fn test_incomplete() {
+        assert_eq!(parse_float_prefix(""), None);
+        assert_eq!(parse_float_prefix("."), None);
+        assert_eq!(parse_float_prefix("-"), None);
+        assert_eq!(parse_float_prefix("e5"), None);
+        assert_eq!(
+            parse_float_prefix("1 "),
+            Some((decimal(false, "1", "", 0), " "))
+        );
+        assert_eq!(
+            parse_float_prefix("1e"),
+            Some((decimal(false, "1", "", 0), "e"))
+        );
+        assert_eq!(
+            parse_float_prefix("1e+"),
+            Some((decimal(false, "1", "", 0), "e+"))
+        );
+        assert_eq!(
+            parse_float_prefix("1s"),
+            Some((decimal(false, "1", "", 0), "s"))
+        );
+        assert_eq!(
+            parse_float_prefix("1_0"),
+            Some((decimal(false, "1", "", 0), "_0"))
+        );
+    }
+
+    #[test]
+    fn test_to_f64() {
+        assert_eq!(parse_float("1.5e3").unwrap().to_f64(), 1500.0);
+        assert_eq!(parse_float("-.25").unwrap().to_f64(), -0.25);
+        assert_eq!(parse_float("0x1.8p1").unwrap().to_f64(), 3.0);
+        assert_eq!(parse_float("0x.8").unwrap().to_f64(), 0.5);
+        assert_eq!(parse_float("0x1p-1074").unwrap().to_f64(), 5e-324);
+        assert_eq!(
+            parse_float("0x123456789abcdef0123").unwrap().to_f64(),
+            0x123456789abcdef0123u128 as f64
+        );
+        assert_eq!(parse_float("1e400").unwrap().to_f64(), f64::INFINITY);
+        assert_eq!(parse_float("-inf").unwrap().to_f64(), f64::NEG_INFINITY);
+        assert!(parse_float("nan").unwrap().to_f64().is_nan());
+        assert!(parse_float("-0").unwrap().to_f64().is_sign_negative());
+    }
+
+    #[test]
+    fn test_integer() {
+        let integer = |negative, radix, digits| IntegerLiteral {
+            negative,
+            radix,
+            digits,
+        };
+        assert_eq!(
+            parse_integer_prefix(" -12"),
+            Some((integer(true, Radix::Decimal, "12"), ""))
+        );
+        assert_eq!(
+            parse_integer_prefix("0755"),
+            Some((integer(false, Radix::Octal, "0755"), ""))
+        );
+        assert_eq!(
+            parse_integer_prefix("08"),
+            Some((integer(false, Radix::Octal, "0"), "8"))
+        );
+        assert_eq!(
+            parse_integer_prefix("0x1p3"),
+            Some((integer(false, Radix::Hexadecimal, "1"), "p3"))
+        );
+        assert_eq!(
+            parse_integer_prefix("0x"),
+            Some((integer(false, Radix::Octal, "0"), "x"))
+        );
+        assert_eq!(
+            parse_integer_prefix("1.5"),
+            Some((integer(false, Radix::Decimal, "1"), ".5"))
+        );
+        assert_eq!(parse_integer_prefix(".5"), None);
+        assert_eq!(parse_integer_prefix("inf"), None);
+    }
+}
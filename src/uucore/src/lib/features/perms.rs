@@ -4,28 +4,46 @@
 // file that was distributed with this source code.
 
 //! Common functions to manage permissions
+//!
+//! Only the command line handling is shared with Windows, where owners and
+//! groups are SIDs rather than IDs.
 
+#[cfg(unix)]
 use crate::display::Quotable;
+#[cfg(unix)]
 use crate::error::strip_errno;
 use crate::error::UResult;
+#[cfg(unix)]
 use crate::error::USimpleError;
+#[cfg(unix)]
 pub use crate::features::entries;
+#[cfg(unix)]
 use crate::fs::resolve_relative_path;
+#[cfg(unix)]
 use crate::show_error;
 use clap::Arg;
 use clap::ArgMatches;
 use clap::Command;
+#[cfg(unix)]
 use libc::{self, gid_t, uid_t};
+#[cfg(unix)]
 use walkdir::WalkDir;
 
+#[cfg(unix)]
 use std::io::Error as IOError;
+#[cfg(unix)]
 use std::io::Result as IOResult;
 
+#[cfg(unix)]
 use std::ffi::CString;
+#[cfg(unix)]
 use std::fs::Metadata;
+#[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
 
+#[cfg(unix)]
 use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
 use std::path::Path;
 
 /// The various level of verbosity
@@ -43,6 +61,7 @@ pub struct Verbosity {
 }
 
 /// Actually perform the change of owner on a path
+#[cfg(unix)]
 fn chown<P: AsRef<Path>>(path: P, uid: uid_t, gid: gid_t, follow: bool) -> IOResult<()> {
     let path = path.as_ref();
     let s = CString::new(path.as_os_str().as_bytes()).unwrap();
@@ -63,6 +82,7 @@ fn chown<P: AsRef<Path>>(path: P, uid: uid_t, gid: gid_t, follow: bool) -> IORes
 /// Perform the change of owner on a path
 /// with the various options
 /// and error messages management
+#[cfg(unix)]
 pub fn wrap_chown<P: AsRef<Path>>(
     path: P,
     meta: &Metadata,
@@ -165,6 +185,7 @@ pub fn wrap_chown<P: AsRef<Path>>(
     Ok(out)
 }
 
+#[cfg(unix)]
 pub enum IfFrom {
     All,
     User(u32),
@@ -172,6 +193,7 @@ pub enum IfFrom {
     UserGroup(u32, u32),
 }
 
+#[cfg(unix)]
 #[derive(PartialEq, Eq)]
 pub enum TraverseSymlinks {
     None,
@@ -179,6 +201,7 @@ pub enum TraverseSymlinks {
     All,
 }
 
+#[cfg(unix)]
 pub struct ChownExecutor {
     pub dest_uid: Option<u32>,
     pub dest_gid: Option<u32>,
@@ -191,6 +214,7 @@ pub struct ChownExecutor {
     pub dereference: bool,
 }
 
+#[cfg(unix)]
 impl ChownExecutor {
     pub fn exec(&self) -> UResult<()> {
         let mut ret = 0;
@@ -412,22 +436,18 @@ pub mod options {
     pub const ARG_FILES: &str = "FILE";
 }
 
+#[cfg(unix)]
 type GidUidFilterParser = fn(&ArgMatches) -> UResult<(Option<u32>, Option<u32>, IfFrom)>;
 
-/// Base implementation for `chgrp` and `chown`.
+/// Parse the command line of `chgrp` or `chown`.
 ///
 /// An argument called `add_arg_if_not_reference` will be added to `command` if
-/// `args` does not contain the `--reference` option.
-/// `parse_gid_uid_and_filter` will be called to obtain the target gid and uid, and the filter,
-/// from `ArgMatches`.
-/// `groups_only` determines whether verbose output will only mention the group.
-pub fn chown_base(
+/// `args` does not contain the `--reference` option, followed by the files.
+pub fn chown_matches(
     mut command: Command,
     args: impl crate::Args,
     add_arg_if_not_reference: &'static str,
-    parse_gid_uid_and_filter: GidUidFilterParser,
-    groups_only: bool,
-) -> UResult<()> {
+) -> UResult<ArgMatches> {
     let args: Vec<_> = args.collect();
     let mut reference = false;
     let mut help = false;
@@ -460,7 +480,39 @@ pub fn chown_base(
             .required(true)
             .num_args(1..),
     );
-    let matches = command.try_get_matches_from(args)?;
+    Ok(command.try_get_matches_from(args)?)
+}
+
+/// The verbosity asked for on the command line of `chgrp` or `chown`.
+pub fn verbosity_level(matches: &ArgMatches) -> VerbosityLevel {
+    if matches.get_flag(options::verbosity::CHANGES) {
+        VerbosityLevel::Changes
+    } else if matches.get_flag(options::verbosity::SILENT)
+        || matches.get_flag(options::verbosity::QUIET)
+    {
+        VerbosityLevel::Silent
+    } else if matches.get_flag(options::verbosity::VERBOSE) {
+        VerbosityLevel::Verbose
+    } else {
+        VerbosityLevel::Normal
+    }
+}
+
+/// Base implementation for `chgrp` and `chown`.
+///
+/// The command line is parsed by [`chown_matches`].
+/// `parse_gid_uid_and_filter` will be called to obtain the target gid and uid, and the filter,
+/// from `ArgMatches`.
+/// `groups_only` determines whether verbose output will only mention the group.
+#[cfg(unix)]
+pub fn chown_base(
+    command: Command,
+    args: impl crate::Args,
+    add_arg_if_not_reference: &'static str,
+    parse_gid_uid_and_filter: GidUidFilterParser,
+    groups_only: bool,
+) -> UResult<()> {
+    let matches = chown_matches(command, args, add_arg_if_not_reference)?;
 
     let files: Vec<String> = matches
         .get_many::<String>(options::ARG_FILES)
@@ -497,17 +549,7 @@ pub fn chown_base(
         traverse_symlinks = TraverseSymlinks::None;
     }
 
-    let verbosity_level = if matches.get_flag(options::verbosity::CHANGES) {
-        VerbosityLevel::Changes
-    } else if matches.get_flag(options::verbosity::SILENT)
-        || matches.get_flag(options::verbosity::QUIET)
-    {
-        VerbosityLevel::Silent
-    } else if matches.get_flag(options::verbosity::VERBOSE) {
-        VerbosityLevel::Verbose
-    } else {
-        VerbosityLevel::Normal
-    };
+    let verbosity_level = verbosity_level(&matches);
     let (dest_gid, dest_uid, filter) = parse_gid_uid_and_filter(&matches)?;
 
     let executor = ChownExecutor {
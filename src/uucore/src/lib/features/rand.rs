@@ -0,0 +1,228 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+// spell-checker:ignore (words) getrandom urandom
+
+//! The randomness of `shuf`, `sort -R`, `shred` and `mktemp`.
+//!
+//! A [`RandomSource`] is one of
+//!
+//! - the generator of the operating system, which is `getrandom` on Linux,
+//!   or `/dev/urandom` where it is missing, and is used by default
+//! - the bytes of the file given with `--random-source`, which are read in
+//!   order, so that the same file gives the same output, as in GNU
+//!   coreutils; running out of them is an error
+//! - a generator seeded with a number, for the tests
+//!
+//! # Usage example
+//!
+//! ```
+//! use clap::Command;
+//! use uucore::rand::{self, RandomSource};
+//!
+//! let matches = Command::new("command")
+//!     .arg(rand::arguments::random_source())
+//!     .get_matches_from(vec!["command", "--random-source=Cargo.toml"]);
+//!
+//! let file = rand::random_source_file(&matches).unwrap();
+//! let mut source = RandomSource::open(file).unwrap();
+//! let mut bytes = [0; 4];
+//! source.read(&mut bytes).unwrap();
+//! assert_eq!(&bytes, b"# sp");
+//! ```
+
+use clap::ArgMatches;
+use rand::rngs::{OsRng, StdRng};
+use rand::{RngCore, SeedableRng};
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+use crate::display::Quotable;
+use crate::error::{strip_errno, FromIo, UResult};
+
+/// Arguments for random sources.
+pub mod arguments {
+    extern crate clap;
+
+    pub static OPT_RANDOM_SOURCE: &str = "randomopt_random_source";
+
+    /// '--random-source' argument
+    pub fn random_source() -> clap::Arg {
+        clap::Arg::new(OPT_RANDOM_SOURCE)
+            .long("random-source")
+            .help("get random bytes from FILE")
+            .value_name("FILE")
+            .value_parser(clap::builder::ValueParser::os_string())
+            .value_hint(clap::ValueHint::FilePath)
+    }
+}
+
+/// The file given with [`arguments::random_source()`], if any, for the
+/// utilities that report the errors of opening it in their own way.
+pub fn random_source_file(matches: &ArgMatches) -> Option<&Path> {
+    matches
+        .get_one::<OsString>(arguments::OPT_RANDOM_SOURCE)
+        .map(Path::new)
+}
+
+enum Kind {
+    Os(OsRng),
+    File(PathBuf, BufReader<File>),
+    Seeded(Box<StdRng>),
+}
+
+/// Where the random bytes of a utility come from.
+///
+/// The infallible methods of [`RngCore`], which the algorithms of `rand`
+/// like shuffling use, show the error and exit with status 1 when the bytes
+/// can't be read, as GNU utilities do. [`Self::read`] returns it instead.
+pub struct RandomSource {
+    kind: Kind,
+}
+
+impl RandomSource {
+    /// The generator of the operating system.
+    pub fn os() -> Self {
+        Self {
+            kind: Kind::Os(OsRng),
+        }
+    }
+
+    /// The bytes of the file at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        Ok(Self {
+            kind: Kind::File(path.to_path_buf(), BufReader::new(file)),
+        })
+    }
+
+    /// A generator that gives the same bytes for the same `seed`.
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            kind: Kind::Seeded(Box::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// The file given with [`arguments::random_source()`], or the generator
+    /// of the operating system if there is none. Failing to open the file is
+    /// an error like `FILE: No such file or directory`, as in GNU `shuf`.
+    pub fn from_matches(matches: &ArgMatches) -> UResult<Self> {
+        match random_source_file(matches) {
+            Some(path) => Self::open(path).map_err_context(|| path.maybe_quote().to_string()),
+            None => Ok(Self::os()),
+        }
+    }
+
+    /// Fills `dest` with random bytes.
+    pub fn read(&mut self, dest: &mut [u8]) -> Result<(), RandomSourceError> {
+        match &mut self.kind {
+            Kind::Os(rng) => rng.try_fill_bytes(dest).map_err(RandomSourceError::Os),
+            Kind::File(path, reader) => reader
+                .read_exact(dest)
+                .map_err(|e| RandomSourceError::Read(path.clone(), e)),
+            Kind::Seeded(rng) => {
+                rng.fill_bytes(dest);
+                Ok(())
+            }
+        }
+    }
+}
+
+impl RngCore for RandomSource {
+    fn next_u32(&mut self) -> u32 {
+        match &mut self.kind {
+            Kind::Os(rng) => rng.next_u32(),
+            Kind::File(..) => {
+                let mut bytes = [0; 4];
+                self.fill_bytes(&mut bytes);
+                u32::from_le_bytes(bytes)
+            }
+            Kind::Seeded(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match &mut self.kind {
+            Kind::Os(rng) => rng.next_u64(),
+            Kind::File(..) => {
+                let mut bytes = [0; 8];
+                self.fill_bytes(&mut bytes);
+                u64::from_le_bytes(bytes)
+            }
+            Kind::Seeded(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if let Err(e) = self.read(dest) {
+            crate::show_error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.read(dest).map_err(rand::Error::new)
+    }
+}
+
+/// Why random bytes couldn't be read.
+#[derive(Debug)]
+pub enum RandomSourceError {
+    /// The generator of the operating system failed.
+    Os(rand::Error),
+    /// The file failed to be read, or ended.
+    Read(PathBuf, io::Error),
+}
+
+impl fmt::Display for RandomSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Os(e) => write!(f, "cannot get random bytes: {e}"),
+            Self::Read(path, e) if e.kind() == ErrorKind::UnexpectedEof => {
+                write!(f, "{}: end of file", path.quote())
+            }
+            Self::Read(path, e) => write!(f, "{}: {}", path.quote(), strip_errno(e)),
+        }
+    }
+}
+
+impl Error for RandomSourceError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded() {
+        let (mut a, mut b) = (RandomSource::from_seed(1), RandomSource::from_seed(1));
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_ne!(
+            RandomSource::from_seed(2).next_u64(),
+            RandomSource::from_seed(1).next_u64()
+        );
+    }
+
+    #[test]
+    fn test_file() {
+        let contents = std::fs::read("Cargo.toml").unwrap();
+        let mut source = RandomSource::open("Cargo.toml").unwrap();
+        let mut bytes = [0; 8];
+        source.read(&mut bytes[..4]).unwrap();
+        assert_eq!(source.next_u32().to_le_bytes(), contents[4..8]);
+
+        let mut rest = vec![0; contents.len() - 8];
+        source.read(&mut rest).unwrap();
+        assert_eq!(rest, contents[8..]);
+        assert_eq!(
+            source.read(&mut bytes).unwrap_err().to_string(),
+            "'Cargo.toml': end of file"
+        );
+    }
+}
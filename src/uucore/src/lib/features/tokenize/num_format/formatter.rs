@@ -56,4 +56,5 @@ pub fn get_it_at(offset: usize, str_in: &str) -> PutBackN<Chars> {
 pub fn warn_incomplete_conv(pf_arg: &str) {
     // important: keep println here not print
     show_error!("{}: value not completely converted", pf_arg.maybe_quote());
+    crate::error::set_exit_code(1);
 }
@@ -4,14 +4,15 @@
 
 // spell-checker:ignore (vars) charf decf floatf intf scif strf Cninety
 
-use std::env;
 use std::vec::Vec;
 
 use crate::display::Quotable;
+use crate::error::set_exit_code;
+use crate::numeric::{parse_float_prefix, parse_integer_prefix, FloatLiteral, Radix};
 use crate::{show_error, show_warning};
 
 use super::format_field::{FieldType, FormatField};
-use super::formatter::{Base, FormatPrimitive, Formatter, InitialPrefix};
+use super::formatter::{warn_incomplete_conv, Base, FormatPrimitive, Formatter, InitialPrefix};
 use super::formatters::cninetyninehexfloatf::CninetyNineHexFloatf;
 use super::formatters::decf::Decf;
 use super::formatters::floatf::Floatf;
@@ -21,22 +22,23 @@ use super::formatters::scif::Scif;
 pub fn warn_expected_numeric(pf_arg: &str) {
     // important: keep println here not print
     show_error!("{}: expected a numeric value", pf_arg.maybe_quote());
+    set_exit_code(1);
+}
+
+fn warn_out_of_range(pf_arg: &str) {
+    show_error!("{}: Numerical result out of range", pf_arg.maybe_quote());
+    set_exit_code(1);
 }
 
 // when character constant arguments have excess characters
 // issue a warning when POSIXLY_CORRECT is not set
 fn warn_char_constant_ign(remaining_bytes: &[u8]) {
-    match env::var("POSIXLY_CORRECT") {
-        Ok(_) => {}
-        Err(e) => {
-            if let env::VarError::NotPresent = e {
-                show_warning!(
-                    "{:?}: character(s) following character \
-                     constant have been ignored",
-                    remaining_bytes
-                );
-            }
-        }
+    if !crate::posix::posixly_correct() {
+        show_warning!(
+            "{:?}: character(s) following character \
+             constant have been ignored",
+            remaining_bytes
+        );
     }
 }
 
@@ -193,6 +195,110 @@ fn get_initial_prefix(str_in: &str, field_type: &FieldType) -> InitialPrefix {
     ret
 }
 
+// the exponents of ten beyond which a long double
+// overflows to infinity or underflows to zero
+const MAX_DECIMAL_EXPONENT: i64 = 4932;
+const MIN_DECIMAL_EXPONENT: i64 = -4951;
+
+// an argument of a numeric field, read the way
+// strtoimax or strtold would read it
+enum Argument {
+    // the number written again in the plain form
+    // the formatters understand, e.g. "-0x1f" or "0.015"
+    Number(String),
+    // an infinity or not-a-number, printed as is
+    NonFinite(String),
+}
+
+// read the longest number at the start of the argument,
+// warning like GNU printf about anything else
+fn read_argument(in_str: &str, field: &FormatField) -> Argument {
+    if let FieldType::Intf = *field.field_type {
+        if let Some((number, rest)) = parse_integer_prefix(in_str) {
+            if !rest.is_empty() {
+                warn_incomplete_conv(in_str);
+            }
+            let sign = if number.negative { "-" } else { "" };
+            let prefix = if number.radix == Radix::Hexadecimal {
+                "0x"
+            } else {
+                ""
+            };
+            return Argument::Number(format!("{sign}{prefix}{}", number.digits));
+        }
+    } else if let Some((number, rest)) = parse_float_prefix(in_str) {
+        if !rest.is_empty() {
+            warn_incomplete_conv(in_str);
+        }
+        return read_float(in_str, &number, field);
+    }
+    warn_expected_numeric(in_str);
+    Argument::Number(String::from("0"))
+}
+
+fn read_float(in_str: &str, number: &FloatLiteral, field: &FormatField) -> Argument {
+    let sign = if number.is_negative() { "-" } else { "" };
+    let upper = field.field_char.is_ascii_uppercase();
+    let non_finite = |name: &str| {
+        let name = format!("{sign}{name}");
+        Argument::NonFinite(if upper { name.to_uppercase() } else { name })
+    };
+    match *number {
+        FloatLiteral::Infinity { .. } => non_finite("inf"),
+        FloatLiteral::NaN { .. } => non_finite("nan"),
+        FloatLiteral::Finite {
+            radix: Radix::Hexadecimal,
+            ..
+        } => {
+            let value = number.to_f64();
+            if value.is_infinite() {
+                warn_out_of_range(in_str);
+                non_finite("inf")
+            } else {
+                // the Display of an f64 never has an exponent
+                Argument::Number(format!("{value}"))
+            }
+        }
+        FloatLiteral::Finite {
+            integral,
+            fractional,
+            exponent,
+            ..
+        } => {
+            let digits = [integral, fractional].concat();
+            let leading_zeros = digits.len() - digits.trim_start_matches('0').len();
+            if leading_zeros == digits.len() {
+                return Argument::Number(format!("{sign}0"));
+            }
+            // the power of ten of the first significant digit
+            let magnitude =
+                exponent.saturating_add(integral.len() as i64 - leading_zeros as i64 - 1);
+            if magnitude > MAX_DECIMAL_EXPONENT {
+                warn_out_of_range(in_str);
+                return non_finite("inf");
+            }
+            if magnitude < MIN_DECIMAL_EXPONENT {
+                warn_out_of_range(in_str);
+                return Argument::Number(format!("{sign}0"));
+            }
+            // the number of digits before the decimal point,
+            // which may be negative or beyond the digits
+            let point = integral.len() as i64 + exponent;
+            Argument::Number(if point <= 0 {
+                format!("{sign}0.{}{digits}", "0".repeat(-point as usize))
+            } else if point as usize >= digits.len() {
+                format!(
+                    "{sign}{digits}{}",
+                    "0".repeat(point as usize - digits.len())
+                )
+            } else {
+                let (before, after) = digits.split_at(point as usize);
+                format!("{sign}{before}.{after}")
+            })
+        }
+    }
+}
+
 // this is the function a Sub's print will delegate to
 // if it is a numeric field, passing the field details
 // and an iterator to the argument
@@ -254,15 +360,21 @@ pub fn num_format(field: &FormatField, in_str_opt: Option<&String>) -> Option<St
             let in_str = in_str_opt.expect(
                 "please send the devs this message:
                 \n get_provided is failing to ret as Some(0) on no str ");
+            // read the number at the start of the argument the way
+            // GNU printf does, and write it again in a plain form
+            let in_str = match read_argument(in_str, field) {
+                Argument::Number(number) => number,
+                Argument::NonFinite(name) => return Some(name),
+            };
             // first get information about the beginning of the
             // numeric argument that would be useful for
             // any formatter (int or float)
             let initial_prefix = get_initial_prefix(
-                in_str,
+                &in_str,
                 field.field_type
             );
             // then get the FormatPrimitive from the Formatter
-            formatter.get_primitive(field, &initial_prefix, in_str)
+            formatter.get_primitive(field, &initial_prefix, &in_str)
         };
     // if we have a formatPrimitive, print its results
     // according to the field-char appropriate Formatter
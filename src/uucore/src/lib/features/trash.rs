@@ -0,0 +1,334 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Moving files to the trash instead of removing them for good.
+//!
+//! The trash is the one of the desktop, so that files can be restored from
+//! the file manager: the Recycle Bin on Windows, `~/.Trash` on macOS, and
+//! the trash directories of the freedesktop.org Trash specification on other
+//! Unix systems: the home trash, or for the files on another file system,
+//! the trash at the top of that file system.
+
+// spell-checker:ignore NOCONFIRMATION NOERRORUI ALLOWUNDO SHFILEOPSTRUCTW trashinfo topdir getuid
+
+use std::io;
+use std::path::Path;
+
+/// Move `path`, with all its contents if it is a directory, to the trash.
+///
+/// Symbolic links are moved themselves, not the files they point to.
+pub fn trash(path: &Path) -> io::Result<()> {
+    platform::trash(path)
+}
+
+/// The absolute path of `path`, without resolving its last component, which
+/// is what gets moved.
+#[cfg(unix)]
+fn absolute(path: &Path) -> io::Result<std::path::PathBuf> {
+    let name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "cannot move this to the trash")
+    })?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize()?,
+        _ => std::env::current_dir()?,
+    };
+    Ok(parent.join(name))
+}
+
+/// The names tried in turn for a file called `name` in the trash, until one
+/// is free: `name`, `name.2`, `name.3` and so on.
+#[cfg(unix)]
+fn candidates(name: &std::ffi::OsStr) -> impl Iterator<Item = std::ffi::OsString> + '_ {
+    (1..).map(move |n: u64| {
+        let mut candidate = name.to_os_string();
+        if n > 1 {
+            candidate.push(format!(".{n}"));
+        }
+        candidate
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::env;
+    use std::fmt::Write as _;
+    use std::fs::{self, DirBuilder, OpenOptions};
+    use std::io::{self, Write};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+    use std::path::{Path, PathBuf};
+
+    use time::macros::format_description;
+    use time::OffsetDateTime;
+
+    /// Move `path` to the home trash, or to the trash at the top of its file
+    /// system if it is on another one than the home trash, since files can
+    /// only be renamed within a file system.
+    ///
+    /// Where `path` came from is recorded in an `info/<name>.trashinfo` file
+    /// next to `files/<name>`, relative to the top of the file system for
+    /// the trash there.
+    pub fn trash(path: &Path) -> io::Result<()> {
+        let path = super::absolute(path)?;
+        let dev = path.symlink_metadata()?.dev();
+        let home = home_trash()?;
+        if device_of(&home)? == dev {
+            return trash_to(&path, &home, &path);
+        }
+        let top = top_dir(&path, dev)?;
+        let trash = top_trash(&top)?;
+        let relative = path.strip_prefix(&top).unwrap_or(&path);
+        trash_to(&path, &trash, relative)
+    }
+
+    /// Move `path` to the trash directory `trash`, recording `info_path` as
+    /// where it came from.
+    ///
+    /// The info file is created first, exclusively, which is how the
+    /// specification reserves a name in the trash.
+    fn trash_to(path: &Path, trash: &Path, info_path: &Path) -> io::Result<()> {
+        let files = trash.join("files");
+        let info = trash.join("info");
+        for dir in [&files, &info] {
+            DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        }
+
+        let name = path.file_name().unwrap_or_default();
+        for candidate in super::candidates(name) {
+            let mut info_name = candidate.clone();
+            info_name.push(".trashinfo");
+            let info_file_path = info.join(info_name);
+            let mut info_file = match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_file_path)
+            {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            };
+            let target = files.join(&candidate);
+            // A file left behind without its info file still takes the name.
+            if target.symlink_metadata().is_ok() {
+                fs::remove_file(&info_file_path)?;
+                continue;
+            }
+            let result = info_file
+                .write_all(trash_info(info_path).as_bytes())
+                .and_then(|_| fs::rename(path, &target));
+            if result.is_err() {
+                let _ = fs::remove_file(&info_file_path);
+            }
+            return result;
+        }
+        unreachable!("there is always another name to try")
+    }
+
+    /// The device of `path`, or of its closest ancestor that exists, for a
+    /// trash that is not created yet.
+    fn device_of(path: &Path) -> io::Result<u64> {
+        let mut result = path.metadata();
+        for ancestor in path.ancestors().skip(1) {
+            match result {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => result = ancestor.metadata(),
+                _ => break,
+            }
+        }
+        result.map(|metadata| metadata.dev())
+    }
+
+    /// The top directory of the file system `dev` of the absolute `path`,
+    /// which is the last of its ancestors on it.
+    fn top_dir(path: &Path, dev: u64) -> io::Result<PathBuf> {
+        let mut top = path;
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.metadata()?.dev() != dev {
+                break;
+            }
+            top = ancestor;
+        }
+        Ok(top.to_path_buf())
+    }
+
+    /// The trash of the user at the top directory `top` of a file system:
+    /// `$top/.Trash/$uid` if the administrator set up `$top/.Trash` for all
+    /// users, a directory with the sticky bit that is not a symbolic link,
+    /// and `$top/.Trash-$uid` otherwise.
+    fn top_trash(top: &Path) -> io::Result<PathBuf> {
+        // SAFETY: getuid can't fail.
+        let uid = unsafe { libc::getuid() };
+        let shared = top.join(".Trash");
+        if let Ok(metadata) = shared.symlink_metadata() {
+            if metadata.is_dir() && metadata.permissions().mode() & libc::S_ISVTX as u32 != 0 {
+                let trash = shared.join(uid.to_string());
+                match DirBuilder::new().mode(0o700).create(&trash) {
+                    Ok(()) => return Ok(trash),
+                    Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(trash),
+                    // The trash of the user is then the other one.
+                    Err(_) => {}
+                }
+            }
+        }
+        Ok(top.join(format!(".Trash-{uid}")))
+    }
+
+    /// The home trash, `$XDG_DATA_HOME/Trash`, where `$XDG_DATA_HOME` is
+    /// `~/.local/share` by default.
+    fn home_trash() -> io::Result<PathBuf> {
+        let data_home = match env::var_os("XDG_DATA_HOME") {
+            Some(dir) if Path::new(&dir).is_absolute() => PathBuf::from(dir),
+            _ => match env::var_os("HOME") {
+                Some(home) if !home.is_empty() => Path::new(&home).join(".local/share"),
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "cannot find the trash: HOME is not set",
+                    ))
+                }
+            },
+        };
+        Ok(data_home.join("Trash"))
+    }
+
+    /// The contents of the info file of a file that came from `path`, and is
+    /// deleted now.
+    fn trash_info(path: &Path) -> String {
+        let now = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
+        let date = now
+            .format(format_description!(
+                "[year]-[month]-[day]T[hour]:[minute]:[second]"
+            ))
+            .unwrap();
+        format!(
+            "[Trash Info]\nPath={}\nDeletionDate={date}\n",
+            escape(path.as_os_str().as_bytes())
+        )
+    }
+
+    /// `path` escaped like in a URL, which is how the specification stores
+    /// paths.
+    pub(super) fn escape(path: &[u8]) -> String {
+        let mut escaped = String::with_capacity(path.len());
+        for &byte in path {
+            if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+                escaped.push(byte as char);
+            } else {
+                write!(escaped, "%{byte:02X}").unwrap();
+            }
+        }
+        escaped
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::env;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+
+    /// Move `path` to `~/.Trash`, under a name that is free there.
+    pub fn trash(path: &Path) -> io::Result<()> {
+        let path = super::absolute(path)?;
+        let home = env::var_os("HOME")
+            .filter(|home| !home.is_empty())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "cannot find the trash: HOME is not set",
+                )
+            })?;
+        let trash = Path::new(&home).join(".Trash");
+        fs::create_dir_all(&trash)?;
+
+        let name = path.file_name().unwrap_or_default();
+        for candidate in super::candidates(name) {
+            let target = trash.join(candidate);
+            if target.symlink_metadata().is_err() {
+                return fs::rename(&path, target);
+            }
+        }
+        unreachable!("there is always another name to try")
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::env;
+    use std::io;
+    use std::mem;
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::Path;
+
+    use windows_sys::Win32::UI::Shell::{SHFileOperationW, SHFILEOPSTRUCTW};
+
+    // From shellapi.h
+    const FO_DELETE: u32 = 0x3;
+    const FOF_SILENT: u32 = 0x4;
+    const FOF_NOCONFIRMATION: u32 = 0x10;
+    const FOF_ALLOWUNDO: u32 = 0x40;
+    const FOF_NOERRORUI: u32 = 0x400;
+
+    /// Move `path` to the Recycle Bin.
+    ///
+    /// Volumes that have no Recycle Bin, like network shares, and files that
+    /// are too large for it, are deleted for good by the shell.
+    pub fn trash(path: &Path) -> io::Result<()> {
+        // The shell takes full paths without the `\\?\` prefix, in a list
+        // that ends with an empty string.
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            env::current_dir()?.join(path)
+        };
+        let from: Vec<u16> = path.as_os_str().encode_wide().chain([0, 0]).collect();
+
+        // SAFETY: `SHFILEOPSTRUCTW` is a plain C struct, for which all zeroes
+        // is valid.
+        let mut operation: SHFILEOPSTRUCTW = unsafe { mem::zeroed() };
+        operation.wFunc = FO_DELETE as _;
+        operation.pFrom = from.as_ptr();
+        operation.fFlags = (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT) as _;
+        let result = unsafe { SHFileOperationW(&mut operation) };
+        if result != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!("cannot move to the Recycle Bin (error {result:#x})"),
+            ));
+        }
+        if operation.fAnyOperationsAborted != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                "moving to the Recycle Bin was aborted",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use std::io;
+    use std::path::Path;
+
+    pub fn trash(_path: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "there is no trash on this platform",
+        ))
+    }
+}
+
+#[cfg(all(test, unix, not(target_os = "macos")))]
+mod tests {
+    use super::platform::escape;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(b"/home/user/a file"), "/home/user/a%20file");
+        assert_eq!(escape(b"/tmp/100%.txt"), "/tmp/100%25.txt");
+        assert_eq!(escape("/tmp/é".as_bytes()), "/tmp/%C3%A9");
+    }
+}
@@ -7,7 +7,12 @@
 //
 //! Aims to provide platform-independent methods to obtain login records
 //!
-//! **ONLY** support linux, macos and freebsd for the time being
+//! **ONLY** support linux, macos, freebsd and netbsd for the time being
+//!
+//! On musl the `utmpx` functions of the C library are stubs, so the records
+//! are read directly from the login record file instead. That file is
+//! expected to use the glibc on-disk layout, which is what most tools
+//! writing `/var/run/utmp` on Linux produce.
 //!
 //! # Examples:
 //!
@@ -33,11 +38,19 @@
 
 pub extern crate time;
 
+#[cfg(not(target_env = "musl"))]
 use std::ffi::CString;
+#[cfg(target_env = "musl")]
+use std::fs::File;
+use std::fs::Metadata;
 use std::io::Result as IOResult;
+#[cfg(target_env = "musl")]
+use std::io::{BufReader, Read};
 use std::marker::PhantomData;
+#[cfg(not(target_env = "musl"))]
 use std::os::unix::ffi::OsStrExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+#[cfg(not(target_env = "musl"))]
 use std::ptr;
 use std::sync::{Mutex, MutexGuard};
 
@@ -46,17 +59,29 @@ use libc::utmpx;
 // pub use libc::getutxid;
 // pub use libc::getutxline;
 // pub use libc::pututxline;
+#[cfg(not(target_env = "musl"))]
 pub use libc::endutxent;
+#[cfg(not(target_env = "musl"))]
 pub use libc::getutxent;
+#[cfg(not(target_env = "musl"))]
 pub use libc::setutxent;
-#[cfg(any(target_vendor = "apple", target_os = "linux", target_os = "netbsd"))]
+#[cfg(any(
+    target_vendor = "apple",
+    all(target_os = "linux", not(target_env = "musl")),
+    target_os = "netbsd"
+))]
 pub use libc::utmpxname;
 
+/// Select the login record database to read from.
+///
+/// FreeBSD has no `utmpxname()`, the database is selected with `setutxdb()`
+/// instead. Note that `setutxent()` resets it to the default database.
+///
 /// # Safety
-/// Just fixed the clippy warning. Please add description here.
+/// `file` must point to a valid NUL-terminated string.
 #[cfg(target_os = "freebsd")]
-pub unsafe extern "C" fn utmpxname(_file: *const libc::c_char) -> libc::c_int {
-    0
+pub unsafe extern "C" fn utmpxname(file: *const libc::c_char) -> libc::c_int {
+    libc::setutxdb(libc::UTXDB_ACTIVE, file)
 }
 
 use once_cell::sync::Lazy;
@@ -73,7 +98,7 @@ macro_rules! chars2string {
     };
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(target_env = "musl")))]
 mod ut {
     pub static DEFAULT_FILE: &str = "/var/run/utmp";
 
@@ -94,6 +119,28 @@ mod ut {
     pub use libc::USER_PROCESS;
 }
 
+// musl only provides the struct, the constants follow the glibc values
+#[cfg(target_env = "musl")]
+mod ut {
+    pub static DEFAULT_FILE: &str = "/var/run/utmp";
+
+    pub const UT_LINESIZE: usize = 32;
+    pub const UT_NAMESIZE: usize = 32;
+    pub const UT_IDSIZE: usize = 4;
+    pub const UT_HOSTSIZE: usize = 256;
+
+    pub const EMPTY: i16 = 0;
+    pub const RUN_LVL: i16 = 1;
+    pub const BOOT_TIME: i16 = 2;
+    pub const NEW_TIME: i16 = 3;
+    pub const OLD_TIME: i16 = 4;
+    pub const INIT_PROCESS: i16 = 5;
+    pub const LOGIN_PROCESS: i16 = 6;
+    pub const USER_PROCESS: i16 = 7;
+    pub const DEAD_PROCESS: i16 = 8;
+    pub const ACCOUNTING: i16 = 9;
+}
+
 #[cfg(target_vendor = "apple")]
 mod ut {
     pub static DEFAULT_FILE: &str = "/var/run/utmpx";
@@ -119,7 +166,7 @@ mod ut {
 
 #[cfg(target_os = "freebsd")]
 mod ut {
-    pub static DEFAULT_FILE: &str = "";
+    pub static DEFAULT_FILE: &str = "/var/run/utx.active";
 
     pub const UT_LINESIZE: usize = 16;
     pub const UT_NAMESIZE: usize = 32;
@@ -190,6 +237,15 @@ impl Utmpx {
     pub fn tty_device(&self) -> String {
         chars2string!(self.inner.ut_line)
     }
+    /// Path of the terminal device the record refers to, e.g. `/dev/pts/0`
+    pub fn tty_path(&self) -> PathBuf {
+        Path::new("/dev").join(self.tty_device())
+    }
+    /// Metadata of the terminal device, used to derive idle time and
+    /// message status
+    pub fn tty_metadata(&self) -> IOResult<Metadata> {
+        self.tty_path().metadata()
+    }
     /// A.K.A. ut.ut_tv
     pub fn login_time(&self) -> time::OffsetDateTime {
         #[cfg(all(not(target_os = "freebsd"), not(target_vendor = "apple")))]
@@ -269,6 +325,7 @@ impl Utmpx {
     ///
     /// Only one instance of [`UtmpxIter`] may be active at a time. This
     /// function will block as long as one is still active. Beware!
+    #[cfg(not(target_env = "musl"))]
     pub fn iter_all_records() -> UtmpxIter {
         let iter = UtmpxIter::new();
         unsafe {
@@ -287,6 +344,7 @@ impl Utmpx {
     /// This function affects subsequent calls to [`Utmpx::iter_all_records`].
     ///
     /// The same caveats as for [`Utmpx::iter_all_records`] apply.
+    #[cfg(not(target_env = "musl"))]
     pub fn iter_all_records_from<P: AsRef<Path>>(path: P) -> UtmpxIter {
         let iter = UtmpxIter::new();
         let path = CString::new(path.as_ref().as_os_str().as_bytes()).unwrap();
@@ -300,6 +358,8 @@ impl Utmpx {
             // So this function is pretty crazy and we don't try to detect errors.
             // Not much we can do besides pray.
             utmpxname(path.as_ptr());
+            // On FreeBSD this would switch back to the default database.
+            #[cfg(not(target_os = "freebsd"))]
             setutxent();
         }
         iter
@@ -319,12 +379,17 @@ static LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 pub struct UtmpxIter {
     #[allow(dead_code)]
     guard: MutexGuard<'static, ()>,
+    /// The login record file, read directly where getutxent() is a stub.
+    /// `None` if it couldn't be opened, which yields no records.
+    #[cfg(target_env = "musl")]
+    reader: Option<BufReader<File>>,
     /// Ensure UtmpxIter is !Send. Technically redundant because MutexGuard
     /// is also !Send.
     phantom: PhantomData<std::rc::Rc<()>>,
 }
 
 impl UtmpxIter {
+    #[cfg(not(target_env = "musl"))]
     fn new() -> Self {
         // PoisonErrors can safely be ignored
         let guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
@@ -333,8 +398,20 @@ impl UtmpxIter {
             phantom: PhantomData,
         }
     }
+
+    #[cfg(target_env = "musl")]
+    fn new(path: &Path) -> Self {
+        // PoisonErrors can safely be ignored
+        let guard = LOCK.lock().unwrap_or_else(|err| err.into_inner());
+        Self {
+            guard,
+            reader: File::open(path).ok().map(BufReader::new),
+            phantom: PhantomData,
+        }
+    }
 }
 
+#[cfg(not(target_env = "musl"))]
 impl Iterator for UtmpxIter {
     type Item = Utmpx;
     fn next(&mut self) -> Option<Self::Item> {
@@ -355,6 +432,20 @@ impl Iterator for UtmpxIter {
     }
 }
 
+#[cfg(target_env = "musl")]
+impl Iterator for UtmpxIter {
+    type Item = Utmpx;
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = [0; record::RECORD_SIZE];
+        // A truncated trailing record is ignored, like glibc does.
+        self.reader.as_mut()?.read_exact(&mut buf).ok()?;
+        Some(Utmpx {
+            inner: record::parse(&buf),
+        })
+    }
+}
+
+#[cfg(not(target_env = "musl"))]
 impl Drop for UtmpxIter {
     fn drop(&mut self) {
         unsafe {
@@ -362,3 +453,160 @@ impl Drop for UtmpxIter {
         }
     }
 }
+
+// The file most recently passed to `Utmpx::iter_all_records_from`, which is
+// what utmpxname() keeps track of on the other platforms.
+#[cfg(target_env = "musl")]
+static RECORD_FILE: Lazy<Mutex<PathBuf>> = Lazy::new(|| Mutex::new(PathBuf::from(DEFAULT_FILE)));
+
+#[cfg(target_env = "musl")]
+impl Utmpx {
+    /// Iterate through all the utmp records.
+    ///
+    /// This will use the default location, or the path [`Utmpx::iter_all_records_from`]
+    /// was most recently called with.
+    ///
+    /// Only one instance of [`UtmpxIter`] may be active at a time. This
+    /// function will block as long as one is still active. Beware!
+    pub fn iter_all_records() -> UtmpxIter {
+        let path = RECORD_FILE
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        UtmpxIter::new(&path)
+    }
+
+    /// Iterate through all the utmp records from a specific file.
+    ///
+    /// No failure is reported or detected, a file that can't be read
+    /// contains no records.
+    ///
+    /// This function affects subsequent calls to [`Utmpx::iter_all_records`].
+    ///
+    /// The same caveats as for [`Utmpx::iter_all_records`] apply.
+    pub fn iter_all_records_from<P: AsRef<Path>>(path: P) -> UtmpxIter {
+        let path = path.as_ref();
+        *RECORD_FILE.lock().unwrap_or_else(|err| err.into_inner()) = path.to_path_buf();
+        UtmpxIter::new(path)
+    }
+}
+
+/// Decoding of login records stored in the glibc on-disk layout.
+///
+/// The layout is the same on 32 and 64 bit targets: the timestamp is always
+/// stored as two 32 bit integers.
+#[cfg(all(target_os = "linux", any(target_env = "musl", test)))]
+mod record {
+    use super::utmpx;
+    use std::convert::TryInto;
+
+    pub const RECORD_SIZE: usize = 384;
+
+    const TYPE: usize = 0;
+    const PID: usize = 4;
+    const LINE: usize = 8;
+    const ID: usize = 40;
+    const USER: usize = 44;
+    const HOST: usize = 76;
+    const EXIT: usize = 332;
+    const SESSION: usize = 336;
+    const TV: usize = 340;
+
+    fn bytes<const N: usize>(record: &[u8; RECORD_SIZE], offset: usize) -> [u8; N] {
+        record[offset..offset + N].try_into().unwrap()
+    }
+
+    fn copy_chars(dest: &mut [libc::c_char], record: &[u8; RECORD_SIZE], offset: usize) {
+        let src = &record[offset..offset + dest.len()];
+        for (d, s) in dest.iter_mut().zip(src) {
+            *d = *s as libc::c_char;
+        }
+    }
+
+    // The integer types of `ut_session` and `ut_tv` differ between C libraries
+    #[allow(clippy::useless_conversion)]
+    pub fn parse(record: &[u8; RECORD_SIZE]) -> utmpx {
+        // SAFETY: utmpx only consists of integers and arrays of integers,
+        // for which all zeroes is a valid value.
+        let mut ut: utmpx = unsafe { std::mem::zeroed() };
+        ut.ut_type = i16::from_ne_bytes(bytes(record, TYPE));
+        ut.ut_pid = i32::from_ne_bytes(bytes(record, PID));
+        copy_chars(&mut ut.ut_line, record, LINE);
+        copy_chars(&mut ut.ut_id, record, ID);
+        copy_chars(&mut ut.ut_user, record, USER);
+        copy_chars(&mut ut.ut_host, record, HOST);
+        ut.ut_exit.e_termination = i16::from_ne_bytes(bytes(record, EXIT));
+        ut.ut_exit.e_exit = i16::from_ne_bytes(bytes(record, EXIT + 2));
+        ut.ut_session = i32::from_ne_bytes(bytes(record, SESSION)).into();
+        ut.ut_tv.tv_sec = i32::from_ne_bytes(bytes(record, TV)).into();
+        ut.ut_tv.tv_usec = i32::from_ne_bytes(bytes(record, TV + 4)).into();
+        ut
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::record::{self, RECORD_SIZE};
+    use super::*;
+    use std::io::Write;
+
+    fn make_record(kind: i16, user: &str, line: &str, sec: i32) -> [u8; RECORD_SIZE] {
+        let mut buf = [0; RECORD_SIZE];
+        buf[0..2].copy_from_slice(&kind.to_ne_bytes());
+        buf[4..8].copy_from_slice(&4242_i32.to_ne_bytes());
+        buf[8..8 + line.len()].copy_from_slice(line.as_bytes());
+        buf[44..44 + user.len()].copy_from_slice(user.as_bytes());
+        buf[76..85].copy_from_slice(b"localhost");
+        buf[340..344].copy_from_slice(&sec.to_ne_bytes());
+        buf
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let ut = Utmpx {
+            inner: record::parse(&make_record(USER_PROCESS, "alice", "pts/3", 1_600_000_000)),
+        };
+        assert_eq!(ut.record_type(), USER_PROCESS);
+        assert_eq!(ut.pid(), 4242);
+        assert_eq!(ut.user(), "alice");
+        assert_eq!(ut.tty_device(), "pts/3");
+        assert_eq!(ut.tty_path(), PathBuf::from("/dev/pts/3"));
+        assert_eq!(ut.host(), "localhost");
+        assert!(ut.is_user_process());
+        assert_eq!(ut.into_inner().ut_tv.tv_sec, 1_600_000_000);
+    }
+
+    #[test]
+    fn test_parse_full_width_fields() {
+        // Fields using their whole width are not NUL-terminated
+        let user = "u".repeat(UT_NAMESIZE);
+        let ut = Utmpx {
+            inner: record::parse(&make_record(LOGIN_PROCESS, &user, "tty1", 0)),
+        };
+        assert_eq!(ut.user(), user);
+        assert_eq!(ut.tty_device(), "tty1");
+        assert!(!ut.is_user_process());
+    }
+
+    #[test]
+    fn test_iter_all_records_from() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(&make_record(BOOT_TIME, "reboot", "~", 1))
+            .unwrap();
+        file.write_all(&make_record(USER_PROCESS, "bob", "pts/0", 2))
+            .unwrap();
+        // truncated record
+        file.write_all(&[0; 100]).unwrap();
+
+        let records: Vec<_> = Utmpx::iter_all_records_from(file.path())
+            .map(|ut| (ut.record_type(), ut.user()))
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (BOOT_TIME, "reboot".to_string()),
+                (USER_PROCESS, "bob".to_string())
+            ]
+        );
+    }
+}
@@ -20,42 +20,67 @@ pub use uucore_procs::*;
 
 // * cross-platform modules
 pub use crate::mods::backup_control;
+pub use crate::mods::broken_pipe;
+pub use crate::mods::completion;
 pub use crate::mods::display;
 pub use crate::mods::error;
+pub use crate::mods::human_size;
+pub use crate::mods::i18n;
 pub use crate::mods::os;
 pub use crate::mods::panic;
+pub use crate::mods::posix;
 pub use crate::mods::quoting_style;
 pub use crate::mods::ranges;
+pub use crate::mods::report;
+pub use crate::mods::update_control;
+pub use crate::mods::version;
 pub use crate::mods::version_cmp;
 
 // * string parsing modules
 pub use crate::parser::parse_glob;
 pub use crate::parser::parse_size;
+#[cfg(feature = "numeric")]
 pub use crate::parser::parse_time;
 
 // * feature-gated modules
+#[cfg(feature = "capabilities")]
+pub use crate::features::capabilities;
+#[cfg(feature = "checksum")]
+pub use crate::features::checksum;
 #[cfg(feature = "encoding")]
 pub use crate::features::encoding;
+#[cfg(feature = "exec")]
+pub use crate::features::exec;
 #[cfg(feature = "fs")]
 pub use crate::features::fs;
 #[cfg(feature = "fsext")]
 pub use crate::features::fsext;
+#[cfg(feature = "input")]
+pub use crate::features::input;
 #[cfg(feature = "lines")]
 pub use crate::features::lines;
 #[cfg(feature = "memo")]
 pub use crate::features::memo;
+#[cfg(feature = "mode")]
+pub use crate::features::mode;
+#[cfg(feature = "numeric")]
+pub use crate::features::numeric;
+#[cfg(feature = "rand")]
+pub use crate::features::rand;
 #[cfg(feature = "ringbuffer")]
 pub use crate::features::ringbuffer;
+#[cfg(feature = "trash")]
+pub use crate::features::trash;
 
 // * (platform-specific) feature-gated modules
-// ** non-windows (i.e. Unix + Fuchsia)
-#[cfg(all(not(windows), feature = "mode"))]
-pub use crate::features::mode;
+// ** unix and windows
+#[cfg(all(any(unix, windows), feature = "perms"))]
+pub use crate::features::perms;
 // ** unix-only
 #[cfg(all(unix, feature = "entries"))]
 pub use crate::features::entries;
-#[cfg(all(unix, feature = "perms"))]
-pub use crate::features::perms;
+#[cfg(all(unix, feature = "fsxattr"))]
+pub use crate::features::fsxattr;
 #[cfg(all(unix, feature = "pipes"))]
 pub use crate::features::pipes;
 #[cfg(all(unix, feature = "process"))]
@@ -67,7 +92,6 @@ pub use crate::features::signals;
     not(target_os = "android"),
     not(target_os = "fuchsia"),
     not(target_os = "redox"),
-    not(target_env = "musl"),
     feature = "utmpx"
 ))]
 pub use crate::features::utmpx;
@@ -79,17 +103,32 @@ pub use crate::features::wide;
 
 use std::ffi::OsString;
 use std::sync::atomic::Ordering;
+use std::sync::{PoisonError, RwLock};
 
 use once_cell::sync::Lazy;
 
 #[macro_export]
 macro_rules! bin {
     ($util:ident) => {
+        uucore::bin!($util, $util::uu_app);
+    };
+    ($util:ident, $uu_app:path) => {
         pub fn main() {
             use std::io::Write;
             uucore::panic::mute_sigpipe_panic(); // suppress extraneous error output for SIGPIPE failures/panics
+            uucore::broken_pipe::restore_default(); // be killed by SIGPIPE, like other programs
+            if uucore::completion::requested() {
+                uucore::completion::generate($uu_app()); // print shell completions instead of running
+            }
             let code = $util::uumain(uucore::args_os()); // execute utility code
-            std::io::stdout().flush().expect("could not flush stdout"); // (defensively) flush stdout for utility prior to exit; see <https://github.com/rust-lang/rust/issues/23818>
+
+            // (defensively) flush stdout for utility prior to exit; see <https://github.com/rust-lang/rust/issues/23818>
+            match std::io::stdout().flush() {
+                Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    std::process::exit(uucore::broken_pipe::EXIT_CODE)
+                }
+                result => result.expect("could not flush stdout"),
+            }
             std::process::exit(code);
         }
     };
@@ -126,9 +165,27 @@ static UTIL_NAME: Lazy<String> = Lazy::new(|| {
     .into_owned()
 });
 
+/// The utility that is run from another program, see [`set_util_name`].
+static UTIL_NAME_OVERRIDE: Lazy<RwLock<Option<&'static str>>> = Lazy::new(|| RwLock::new(None));
+
+/// Make [`util_name`] and [`execution_phrase`] return `name`, rather than
+/// what is found in the arguments of the process, for a utility that is run
+/// in-process by another program. `None` goes back to the arguments.
+pub fn set_util_name(name: Option<&'static str>) {
+    *UTIL_NAME_OVERRIDE
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = name;
+}
+
+fn util_name_override() -> Option<&'static str> {
+    *UTIL_NAME_OVERRIDE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
 /// Derive the utility name.
 pub fn util_name() -> &'static str {
-    &UTIL_NAME
+    util_name_override().unwrap_or(&UTIL_NAME)
 }
 
 static EXECUTION_PHRASE: Lazy<String> = Lazy::new(|| {
@@ -145,7 +202,7 @@ static EXECUTION_PHRASE: Lazy<String> = Lazy::new(|| {
 
 /// Derive the complete execution phrase for "usage".
 pub fn execution_phrase() -> &'static str {
-    &EXECUTION_PHRASE
+    util_name_override().unwrap_or(&EXECUTION_PHRASE)
 }
 
 pub trait Args: Iterator<Item = OsString> + Sized {
@@ -91,6 +91,7 @@ macro_rules! show(
         let e = $err;
         $crate::error::set_exit_code(e.code());
         eprintln!("{}: {}", $crate::util_name(), e);
+        $crate::report::record_error(&e);
     })
 );
 
@@ -150,8 +151,9 @@ macro_rules! show_if_err(
 #[macro_export]
 macro_rules! show_error(
     ($($args:tt)+) => ({
-        eprint!("{}: ", $crate::util_name());
-        eprintln!($($args)+);
+        let message = format!($($args)+);
+        eprintln!("{}: {}", $crate::util_name(), message);
+        $crate::report::record_error(&message);
     })
 );
 
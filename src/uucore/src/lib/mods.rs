@@ -1,11 +1,19 @@
 // mods ~ cross-platforms modules (core/bundler file)
 
 pub mod backup_control;
+pub mod broken_pipe;
+pub mod completion;
 pub mod display;
 pub mod error;
+pub mod human_size;
+pub mod i18n;
 pub mod os;
 pub mod panic;
+pub mod posix;
 pub mod ranges;
+pub mod report;
+pub mod update_control;
+pub mod version;
 pub mod version_cmp;
 // dir and vdir also need access to the quoting_style module
 pub mod quoting_style;
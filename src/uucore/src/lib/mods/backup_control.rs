@@ -7,7 +7,8 @@
 //!   implement backups
 //! - determination of the [backup mode][3]
 //! - determination of the [backup suffix][4]
-//! - [backup target path construction][5]
+//! - [backup target path construction][5], optionally [reserving the path][8]
+//!   for numbered backups so that concurrent writers don't clash
 //! - [Error types][6] for backup-related errors
 //! - GNU-compliant [help texts][7] for backup-related errors
 //!
@@ -26,6 +27,7 @@
 //! [5]: `get_backup_path()`
 //! [6]: `BackupError`
 //! [7]: `BACKUP_CONTROL_LONG_HELP`
+//! [8]: `create_backup_path()`
 //!
 //!
 //! # Usage example
@@ -86,7 +88,10 @@ use clap::ArgMatches;
 use std::{
     env,
     error::Error,
+    ffi::OsString,
     fmt::{Debug, Display},
+    fs, io,
+    ops::Deref,
     path::{Path, PathBuf},
 };
 
@@ -400,6 +405,11 @@ fn match_method(method: &str, origin: &str) -> UResult<BackupMode> {
     }
 }
 
+/// Determine the path `path` would be backed up to.
+///
+/// For numbered backups, the existing backups are found with a single scan of
+/// the directory containing `path`, and the number following the highest one
+/// is used, like GNU does.
 pub fn get_backup_path(
     backup_mode: BackupMode,
     backup_path: &Path,
@@ -413,33 +423,182 @@ pub fn get_backup_path(
     }
 }
 
-fn simple_backup_path(path: &Path, suffix: &str) -> PathBuf {
-    let mut p = path.to_string_lossy().into_owned();
-    p.push_str(suffix);
+/// A backup path returned by [`create_backup_path()`].
+///
+/// When the path was reserved with a placeholder, the placeholder is removed
+/// if this is dropped before the backup is [made](Self::commit), so that the
+/// failures to make the backup don't leave it behind.
+#[derive(Debug)]
+pub struct BackupPath {
+    path: PathBuf,
+    placeholder: bool,
+}
+
+impl BackupPath {
+    /// Record that the backup was made at this path, and return the path.
+    pub fn commit(mut self) -> PathBuf {
+        self.placeholder = false;
+        std::mem::take(&mut self.path)
+    }
+}
+
+impl Deref for BackupPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for BackupPath {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for BackupPath {
+    fn drop(&mut self) {
+        if self.placeholder {
+            // The placeholder of a directory is an empty directory, which
+            // isn't removed anymore once something was moved over it.
+            let _ = fs::remove_dir(&self.path).or_else(|_| fs::remove_file(&self.path));
+        }
+    }
+}
+
+/// Determine the path `path` would be backed up to and reserve it.
+///
+/// Numbered backup paths are reserved by exclusively creating an empty
+/// placeholder, which the backup is then moved or copied over. If another
+/// process created the same backup in the meantime, the next number is used
+/// instead. The placeholder of a directory is an empty directory, so that the
+/// directory can be renamed over it. The placeholder is removed again unless
+/// the returned [`BackupPath`] is committed.
+///
+/// Simple backups are not reserved, as they replace the previous backup anyway.
+pub fn create_backup_path(
+    backup_mode: BackupMode,
+    path: &Path,
+    suffix: &str,
+) -> io::Result<Option<BackupPath>> {
+    let unreserved = |path| {
+        Ok(Some(BackupPath {
+            path,
+            placeholder: false,
+        }))
+    };
+    let highest = match backup_mode {
+        BackupMode::NoBackup => return Ok(None),
+        BackupMode::SimpleBackup => return unreserved(simple_backup_path(path, suffix)),
+        BackupMode::NumberedBackup => highest_backup_number(path),
+        BackupMode::ExistingBackup => match highest_backup_number(path) {
+            0 => return unreserved(simple_backup_path(path, suffix)),
+            n => n,
+        },
+    };
+
+    let is_dir = fs::symlink_metadata(path).map_or(false, |m| m.is_dir());
+    let mut number = highest + 1;
+    loop {
+        let candidate = append_suffix(path, &format!(".~{number}~"));
+        match create_placeholder(&candidate, is_dir) {
+            Ok(placeholder) => {
+                return Ok(Some(BackupPath {
+                    path: candidate,
+                    placeholder,
+                }))
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => number += 1,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Create the placeholder that reserves `path`, returning whether one was.
+fn create_placeholder(path: &Path, is_dir: bool) -> io::Result<bool> {
+    // Windows can't rename a directory over an existing one
+    if is_dir && cfg!(unix) {
+        fs::create_dir(path).map(|_| true)
+    } else if is_dir {
+        match path.symlink_metadata() {
+            Ok(_) => Err(io::ErrorKind::AlreadyExists.into()),
+            Err(_) => Ok(false),
+        }
+    } else {
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map(|_| true)
+    }
+}
+
+fn append_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut p = OsString::from(path.as_os_str());
+    p.push(suffix);
     PathBuf::from(p)
 }
 
+fn simple_backup_path(path: &Path, suffix: &str) -> PathBuf {
+    append_suffix(path, suffix)
+}
+
 fn numbered_backup_path(path: &Path) -> PathBuf {
-    for i in 1_u64.. {
-        let path_str = &format!("{}.~{}~", path.to_string_lossy(), i);
-        let path = Path::new(path_str);
-        if !path.exists() {
-            return path.to_path_buf();
+    // The scan might have missed backups, e.g. for file names that aren't
+    // valid unicode, so make sure the path is really unused.
+    for i in highest_backup_number(path) + 1.. {
+        let path = append_suffix(path, &format!(".~{i}~"));
+        if path.symlink_metadata().is_err() {
+            return path;
         }
     }
     panic!("cannot create backup")
 }
 
 fn existing_backup_path(path: &Path, suffix: &str) -> PathBuf {
-    let test_path_str = &format!("{}.~1~", path.to_string_lossy());
-    let test_path = Path::new(test_path_str);
-    if test_path.exists() {
+    if highest_backup_number(path) > 0 {
         numbered_backup_path(path)
     } else {
         simple_backup_path(path, suffix)
     }
 }
 
+/// The highest `N` of the existing `path.~N~` backups, or `0` if there are none.
+fn highest_backup_number(path: &Path) -> u64 {
+    let name = match path.file_name().and_then(|n| n.to_str()) {
+        Some(name) => name,
+        None => return 0,
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .filter_map(|entry| {
+            let entry_name = entry.ok()?.file_name();
+            parse_backup_number(entry_name.to_str()?, name)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Parse the `N` of `name.~N~`. Like GNU, numbers with leading zeros are not
+/// considered backups.
+fn parse_backup_number(entry_name: &str, name: &str) -> Option<u64> {
+    let number = entry_name
+        .strip_prefix(name)?
+        .strip_prefix(".~")?
+        .strip_suffix('~')?;
+    if number.starts_with('0') || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    number.parse().ok()
+}
+
 //
 // Tests for this module
 //
@@ -628,4 +787,103 @@ mod tests {
         let result = determine_backup_suffix(&matches);
         assert_eq!(result, "-v");
     }
+
+    #[test]
+    fn test_parse_backup_number() {
+        assert_eq!(parse_backup_number("file.~1~", "file"), Some(1));
+        assert_eq!(parse_backup_number("file.~123~", "file"), Some(123));
+        assert_eq!(parse_backup_number("file.~01~", "file"), None);
+        assert_eq!(parse_backup_number("file.~~", "file"), None);
+        assert_eq!(parse_backup_number("file.~1a~", "file"), None);
+        assert_eq!(parse_backup_number("file.~1", "file"), None);
+        assert_eq!(parse_backup_number("files.~1~", "file"), None);
+        assert_eq!(parse_backup_number("file~", "file"), None);
+    }
+
+    #[test]
+    fn test_numbered_backup_path_uses_highest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let file = dir.join("file");
+        assert_eq!(
+            get_backup_path(BackupMode::NumberedBackup, &file, "~"),
+            Some(dir.join("file.~1~"))
+        );
+        fs::write(dir.join("file.~1~"), "").unwrap();
+        fs::write(dir.join("file.~5~"), "").unwrap();
+        fs::write(dir.join("other.~9~"), "").unwrap();
+        assert_eq!(
+            get_backup_path(BackupMode::NumberedBackup, &file, "~"),
+            Some(dir.join("file.~6~"))
+        );
+    }
+
+    #[test]
+    fn test_existing_backup_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let file = dir.join("file");
+        assert_eq!(
+            get_backup_path(BackupMode::ExistingBackup, &file, "~"),
+            Some(dir.join("file~"))
+        );
+        // Any numbered backup counts, not just the first one
+        fs::write(dir.join("file.~3~"), "").unwrap();
+        assert_eq!(
+            get_backup_path(BackupMode::ExistingBackup, &file, "~"),
+            Some(dir.join("file.~4~"))
+        );
+    }
+
+    #[test]
+    fn test_create_backup_path_reserves() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let file = dir.join("file");
+        fs::write(&file, "").unwrap();
+        let first = create_backup_path(BackupMode::NumberedBackup, &file, "~")
+            .unwrap()
+            .map(BackupPath::commit);
+        let second = create_backup_path(BackupMode::NumberedBackup, &file, "~")
+            .unwrap()
+            .map(BackupPath::commit);
+        assert_eq!(first, Some(dir.join("file.~1~")));
+        assert_eq!(second, Some(dir.join("file.~2~")));
+        assert!(dir.join("file.~1~").is_file());
+
+        assert_eq!(
+            create_backup_path(BackupMode::SimpleBackup, &file, ".bak")
+                .unwrap()
+                .map(BackupPath::commit),
+            Some(dir.join("file.bak"))
+        );
+        assert!(!dir.join("file.bak").exists());
+        assert!(create_backup_path(BackupMode::NoBackup, &file, "~")
+            .unwrap()
+            .is_none());
+
+        // The placeholder goes away if the backup isn't made
+        let third = create_backup_path(BackupMode::NumberedBackup, &file, "~")
+            .unwrap()
+            .unwrap();
+        assert!(third.is_file());
+        drop(third);
+        assert!(!dir.join("file.~3~").exists());
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_create_backup_path_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let sub = dir.join("sub");
+        fs::create_dir(&sub).unwrap();
+        let backup = create_backup_path(BackupMode::NumberedBackup, &sub, "~")
+            .unwrap()
+            .unwrap();
+        assert!(backup.is_dir());
+        fs::rename(&sub, &backup).unwrap();
+        backup.commit();
+        assert!(!sub.exists());
+    }
 }
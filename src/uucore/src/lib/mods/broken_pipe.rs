@@ -0,0 +1,83 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! What the utilities do when the reader of their output goes away, as in
+//! `seq inf | head -n 1`.
+//!
+//! Like in GNU coreutils, they are then killed by `SIGPIPE`, without a word,
+//! and the shell sees them exit with [`EXIT_CODE`]. The Rust runtime ignores
+//! `SIGPIPE`, so the binaries put back its default disposition with
+//! [`restore_default`] before running a utility.
+//!
+//! Where there is no `SIGPIPE`, or where a utility [`ignore`]s it to handle
+//! broken pipes itself, like `tee -p`, writes fail with a broken pipe error
+//! instead. A utility failing with such an error ends just as silently, with
+//! [`EXIT_CODE`] as well.
+
+// spell-checker:ignore SIGPIPE
+
+use std::error::Error;
+use std::io;
+
+/// The exit code of a utility that stopped on a broken pipe, which is the
+/// one the shell reports for a process killed by `SIGPIPE`.
+pub const EXIT_CODE: i32 = 128 + 13;
+
+/// Let the process be killed by `SIGPIPE` when it writes to a pipe that
+/// nobody reads anymore.
+pub fn restore_default() {
+    #[cfg(unix)]
+    set_handler(nix::sys::signal::SigHandler::SigDfl);
+}
+
+/// Ignore `SIGPIPE`, so that writing to a pipe that nobody reads anymore
+/// fails with a broken pipe error, which the caller handles.
+pub fn ignore() {
+    #[cfg(unix)]
+    set_handler(nix::sys::signal::SigHandler::SigIgn);
+}
+
+#[cfg(unix)]
+fn set_handler(handler: nix::sys::signal::SigHandler) {
+    use nix::sys::signal::{signal, Signal};
+
+    // SAFETY: the default and ignoring dispositions don't run any code in
+    // the signal handler. This can only fail for an invalid signal.
+    let _ = unsafe { signal(Signal::SIGPIPE, handler) };
+}
+
+/// Whether `error` comes from a broken pipe, which is an [`io::Error`] in
+/// its chain of sources, like the one of a
+/// [`UIoError`](crate::error::UIoError).
+pub fn is_broken_pipe<E: Error + ?Sized>(error: &E) -> bool {
+    let mut source = error.source();
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            if error.kind() == io::ErrorKind::BrokenPipe {
+                return true;
+            }
+        }
+        source = error.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_broken_pipe;
+    use crate::error::{FromIo, UError, USimpleError};
+    use std::io;
+
+    #[test]
+    fn test_is_broken_pipe() {
+        let error: Box<dyn UError> = io::Error::from(io::ErrorKind::BrokenPipe).into();
+        assert!(is_broken_pipe(&*error));
+        let error = io::Error::from(io::ErrorKind::BrokenPipe).map_err_context(|| "out".into());
+        assert!(is_broken_pipe(&*error));
+        let error = io::Error::from(io::ErrorKind::NotFound).map_err_context(|| "out".into());
+        assert!(!is_broken_pipe(&*error));
+        assert!(!is_broken_pipe(&*USimpleError::new(1, "Broken pipe")));
+    }
+}
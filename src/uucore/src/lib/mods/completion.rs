@@ -0,0 +1,87 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Generate shell completions at runtime.
+//!
+//! Every utility, as well as the multi-call binary, accepts the hidden
+//! `--generate-completion SHELL` mode as its first argument. Instead of
+//! running, it prints the completions of its command line for `SHELL` to
+//! stdout, so that they can be installed without a separate packaging step:
+//!
+//! ```shell
+//! ls --generate-completion bash > /usr/share/bash-completion/completions/ls
+//! ```
+//!
+//! The check happens in [`crate::bin`] and in the multi-call binary, before
+//! the utility parses its arguments, so utilities don't need to do anything.
+
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+use clap::builder::EnumValueParser;
+use clap::{Arg, Command};
+use clap_complete::Shell;
+
+/// The argument that selects the completion generation mode.
+pub const GENERATE_COMPLETION: &str = "--generate-completion";
+
+/// The position of the first argument of the utility in `argv`.
+fn first_arg_index() -> usize {
+    if crate::get_utility_is_second_arg() {
+        2
+    } else {
+        1
+    }
+}
+
+/// Whether the utility is asked to print its completions rather than run.
+pub fn requested() -> bool {
+    crate::args_os()
+        .nth(first_arg_index())
+        .map_or(false, |arg| arg == GENERATE_COMPLETION)
+}
+
+/// Print the completions of `command` for the shell given after
+/// [`GENERATE_COMPLETION`] and exit.
+///
+/// The completions are registered for the name the utility was invoked as,
+/// so that they also work for prefixed names like `uu-ls`.
+pub fn generate(command: Command) -> ! {
+    let bin_name = Path::new(crate::util_name()).file_stem().map_or_else(
+        || command.get_name().to_string(),
+        |s| s.to_string_lossy().into_owned(),
+    );
+    generate_for(
+        command,
+        &bin_name,
+        crate::args_os().skip(first_arg_index() + 1),
+    )
+}
+
+/// Print the completions of `command`, run as `bin_name`, for the shell
+/// given by `args` and exit.
+pub fn generate_for(
+    mut command: Command,
+    bin_name: &str,
+    args: impl Iterator<Item = OsString>,
+) -> ! {
+    let matches = Command::new(GENERATE_COMPLETION)
+        .bin_name(format!("{bin_name} {GENERATE_COMPLETION}"))
+        .no_binary_name(true)
+        .arg(
+            Arg::new("shell")
+                .value_name("SHELL")
+                .value_parser(EnumValueParser::<Shell>::new())
+                .required(true),
+        )
+        .get_matches_from(args);
+    let shell = *matches.get_one::<Shell>("shell").unwrap();
+
+    clap_complete::generate(shell, &mut command, bin_name, &mut io::stdout());
+    io::stdout().flush().unwrap();
+    process::exit(0);
+}
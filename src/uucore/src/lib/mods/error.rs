@@ -49,9 +49,13 @@
 //! * [`USimpleError`] may be used in small utils with simple error handling.
 //! * Using [`ExitCode`] is not recommended but can be useful for converting utils to use
 //!   [`UResult`].
+//! * Use [`ErrorContext`] to phrase messages about operands like GNU coreutils does.
+//! * Use the `EXIT_*` constants and [`ExitCodePolicy`] instead of bare numbers for exit codes
+//!   other than `1`.
 
 // spell-checker:ignore uioerror rustdoc
 
+use crate::display::Quotable;
 use clap;
 use std::{
     error::Error,
@@ -59,6 +63,68 @@ use std::{
     sync::atomic::{AtomicI32, Ordering},
 };
 
+/// Exit code of utilities running another command (`env`, `nice`, `nohup`, `timeout`, ...)
+/// when they fail themselves, before the command is run.
+pub const EXIT_CANCELED: i32 = 125;
+/// Exit code when the command to run was found but could not be invoked.
+pub const EXIT_CANNOT_INVOKE: i32 = 126;
+/// Exit code when the command to run could not be found.
+pub const EXIT_ENOENT: i32 = 127;
+/// Exit code of a utility interrupted with `SIGINT`, as reported by shells (`128 + 2`).
+pub const EXIT_INTERRUPTED: i32 = 130;
+
+/// The exit codes a utility uses for its own failures.
+///
+/// GNU coreutils use `1` for most failures, but some utilities report invalid usage or
+/// serious trouble with `2`, and utilities running another command reserve the low codes for
+/// that command.
+/// ```
+/// use uucore::error::{ExitCodePolicy, UClapError, UResult};
+///
+/// const POLICY: ExitCodePolicy = ExitCodePolicy::COMMAND_RUNNER;
+///
+/// fn uumain(args: impl uucore::Args) -> UResult<()> {
+///     let matches = clap::Command::new("test")
+///         .try_get_matches_from(args)
+///         .with_exit_code(POLICY.usage)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitCodePolicy {
+    /// Exit code for failures of the utility itself
+    pub failure: i32,
+    /// Exit code for invalid command line arguments
+    pub usage: i32,
+}
+
+impl ExitCodePolicy {
+    /// Every failure exits with `1`, which is what most utilities do.
+    pub const DEFAULT: Self = Self {
+        failure: 1,
+        usage: 1,
+    };
+
+    /// Minor problems exit with `1`, invalid usage and serious trouble with `2`, e.g. `ls`.
+    pub const SERIOUS_TROUBLE: Self = Self {
+        failure: 2,
+        usage: 2,
+    };
+
+    /// Utilities running another command exit with [`EXIT_CANCELED`] when they fail
+    /// themselves, so that the exit status of the command can be told apart.
+    pub const COMMAND_RUNNER: Self = Self {
+        failure: EXIT_CANCELED,
+        usage: EXIT_CANCELED,
+    };
+}
+
+impl Default for ExitCodePolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 static EXIT_CODE: AtomicI32 = AtomicI32::new(0);
 
 /// Get the last exit code set with [`set_exit_code`].
@@ -390,7 +456,11 @@ impl UIoError {
 
 impl UError for UIoError {}
 
-impl Error for UIoError {}
+impl Error for UIoError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.inner)
+    }
+}
 
 impl Display for UIoError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
@@ -411,9 +481,9 @@ impl Display for UIoError {
                 AddrInUse => "Address in use",
                 AddrNotAvailable => "Address not available",
                 BrokenPipe => "Broken pipe",
-                AlreadyExists => "Already exists",
+                AlreadyExists => "File exists",
                 WouldBlock => "Would block",
-                InvalidInput => "Invalid input",
+                InvalidInput => "Invalid argument",
                 InvalidData => "Invalid data",
                 TimedOut => "Timed out",
                 WriteZero => "Write zero",
@@ -562,6 +632,147 @@ impl From<nix::Error> for Box<dyn UError> {
     }
 }
 
+/// Builder for error messages phrased like the ones of GNU coreutils.
+///
+/// The message consists of an action, optionally followed by quoted operands and further
+/// details, and ends with the reason of the failure:
+/// ```
+/// use uucore::error::{ErrorContext, UError};
+/// use std::fs::File;
+///
+/// let err = ErrorContext::new("cannot open")
+///     .operand("foo")
+///     .detail("for reading")
+///     .io(File::open("/nonexistent/foo").unwrap_err());
+/// assert_eq!(err.to_string(), "cannot open 'foo' for reading: No such file or directory");
+///
+/// let err = ErrorContext::new("invalid number")
+///     .operand("x")
+///     .code(125)
+///     .build();
+/// assert_eq!(err.to_string(), "invalid number 'x'");
+/// assert_eq!(err.code(), 125);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ErrorContext {
+    message: String,
+    code: i32,
+}
+
+impl ErrorContext {
+    pub fn new<S: Into<String>>(action: S) -> Self {
+        Self {
+            message: action.into(),
+            code: 1,
+        }
+    }
+
+    /// Append an operand, quoted the way GNU coreutils quote file names.
+    pub fn operand<T: Quotable + ?Sized>(mut self, operand: &T) -> Self {
+        self.message.push(' ');
+        self.message.push_str(&operand.quote().to_string());
+        self
+    }
+
+    /// Append unquoted text, e.g. `"for reading"`.
+    pub fn detail(mut self, detail: &str) -> Self {
+        self.message.push(' ');
+        self.message.push_str(detail);
+        self
+    }
+
+    /// Set the exit code of the resulting error, `1` by default.
+    pub fn code(mut self, code: i32) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Finish the message with the description of an I/O error.
+    pub fn io(self, err: std::io::Error) -> Box<dyn UError> {
+        let err: Box<dyn UError> = Box::new(UIoError {
+            context: Some(self.message),
+            inner: err,
+        });
+        err.with_error_code(self.code)
+    }
+
+    /// Finish the message with an arbitrary reason.
+    pub fn reason<R: Display>(self, reason: R) -> Box<dyn UError> {
+        USimpleError::new(self.code, format!("{}: {}", self.message, reason))
+    }
+
+    /// Finish the message as it is.
+    pub fn build(self) -> Box<dyn UError> {
+        USimpleError::new(self.code, self.message)
+    }
+}
+
+/// An error with the message of another error, but a different exit code.
+///
+/// Created with [`UErrorExt::with_error_code`].
+#[derive(Debug)]
+pub struct UErrorWithCode {
+    code: i32,
+    inner: Box<dyn UError>,
+}
+
+impl Error for UErrorWithCode {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.inner.source()
+    }
+}
+
+impl Display for UErrorWithCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+        self.inner.fmt(f)
+    }
+}
+
+impl UError for UErrorWithCode {
+    fn code(&self) -> i32 {
+        self.code
+    }
+
+    fn usage(&self) -> bool {
+        self.inner.usage()
+    }
+}
+
+/// Extension trait to change the exit code of errors and results.
+///
+/// Use [`UClapError::with_exit_code`] for errors of `clap` instead, which keeps
+/// `--help` and `--version` exiting with `0`.
+/// ```
+/// use uucore::error::{UErrorExt, UResult, EXIT_CANCELED};
+///
+/// fn parse(s: &str) -> UResult<u32> {
+///     s.parse::<u32>()
+///         .map_err(|e| uucore::error::USimpleError::new(1, e.to_string()))
+///         .with_error_code(EXIT_CANCELED)
+/// }
+/// assert_eq!(parse("x").unwrap_err().code(), EXIT_CANCELED);
+/// ```
+pub trait UErrorExt<T> {
+    fn with_error_code(self, code: i32) -> T;
+}
+
+impl<E: Into<Box<dyn UError>>> UErrorExt<Box<dyn UError>> for E {
+    fn with_error_code(self, code: i32) -> Box<dyn UError> {
+        let inner = self.into();
+        if inner.code() == code {
+            inner
+        } else {
+            Box::new(UErrorWithCode { code, inner })
+        }
+    }
+}
+
+impl<T, E: Into<Box<dyn UError>>> UErrorExt<UResult<T>> for Result<T, E> {
+    fn with_error_code(self, code: i32) -> UResult<T> {
+        self.map_err(|e| e.with_error_code(code))
+    }
+}
+
 /// Shorthand to construct [`UIoError`]-instances.
 ///
 /// This macro serves as a convenience call to quickly construct instances of
@@ -750,6 +961,47 @@ impl Display for ClapErrorWrapper {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_context() {
+        let err = ErrorContext::new("failed to run command")
+            .operand("foo bar")
+            .code(EXIT_ENOENT)
+            .io(std::io::Error::from_raw_os_error(2));
+        assert_eq!(
+            err.to_string(),
+            "failed to run command 'foo bar': No such file or directory"
+        );
+        assert_eq!(err.code(), EXIT_ENOENT);
+
+        let err = ErrorContext::new("invalid argument")
+            .operand("x")
+            .detail("for")
+            .operand("--mode")
+            .reason("not a number");
+        assert_eq!(
+            err.to_string(),
+            "invalid argument 'x' for '--mode': not a number"
+        );
+        assert_eq!(err.code(), 1);
+    }
+
+    #[test]
+    fn test_with_error_code() {
+        let err = USimpleError::new(2, "oops").with_error_code(EXIT_CANCELED);
+        assert_eq!(err.code(), EXIT_CANCELED);
+        assert_eq!(err.to_string(), "oops");
+
+        let err = UUsageError::new(1, "bad").with_error_code(2);
+        assert!(err.usage());
+
+        let res: Result<(), std::io::Error> = Err(std::io::ErrorKind::PermissionDenied.into());
+        let err = res.with_error_code(EXIT_CANNOT_INVOKE).unwrap_err();
+        assert_eq!(err.code(), EXIT_CANNOT_INVOKE);
+        assert_eq!(err.to_string(), "Permission denied");
+    }
+
     #[test]
     #[cfg(any(target_os = "linux", target_os = "android"))]
     fn test_nix_error_conversion() {
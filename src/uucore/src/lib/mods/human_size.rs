@@ -0,0 +1,587 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Display sizes in human-readable form and determine block sizes.
+//!
+//! This module is the counterpart of [`parse_size`](crate::parse_size) for
+//! output. It provides
+//!
+//! - GNU-compatible [human-readable formatting][1] for `-h` and `--si`
+//! - [formatting][2] of block sizes, as used in the header of `df`
+//! - parsing of `--block-size` [arguments][3], and the [resolution][4] of the
+//!   unit of the sizes from them and the block size environment variables
+//!   (utility specific ones such as `DF_BLOCK_SIZE`, `BLOCK_SIZE`,
+//!   `BLOCKSIZE` and `POSIXLY_CORRECT`)
+//! - the [order][5] of the suffixes of the human-readable sizes, for
+//!   `sort -h`
+//!
+//! Utilities displaying sizes, such as `df`, `du` and `ls`, should use
+//! this module so that they agree on how sizes are shown.
+//!
+//! [1]: `human_readable()`
+//! [2]: `to_magnitude_and_suffix()`
+//! [3]: `parse_size_unit()`
+//! [4]: `resolve_size_unit()`
+//! [5]: `suffix_power()`
+//!
+//! # Usage example
+//!
+//! ```
+//! use uucore::human_size::{human_readable, HumanReadable};
+//!
+//! assert_eq!(human_readable(1000, HumanReadable::Binary), "1000");
+//! assert_eq!(human_readable(1000, HumanReadable::Decimal), "1.0k");
+//! assert_eq!(human_readable(12_300 * 1024, HumanReadable::Binary), "13M");
+//! ```
+
+use std::env;
+
+use crate::display::Quotable;
+use crate::parse_size::{parse_size, ParseSizeError};
+
+/// The first ten powers of 1024.
+const IEC_BASES: [u128; 10] = [
+    1,
+    1_024,
+    1_048_576,
+    1_073_741_824,
+    1_099_511_627_776,
+    1_125_899_906_842_624,
+    1_152_921_504_606_846_976,
+    1_180_591_620_717_411_303_424,
+    1_208_925_819_614_629_174_706_176,
+    1_237_940_039_285_380_274_899_124_224,
+];
+
+/// The first ten powers of 1000.
+const SI_BASES: [u128; 10] = [
+    1,
+    1_000,
+    1_000_000,
+    1_000_000_000,
+    1_000_000_000_000,
+    1_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000,
+    1_000_000_000_000_000_000_000_000_000,
+];
+
+/// The block size used when neither an option nor an environment variable
+/// specifies one, and `POSIXLY_CORRECT` is not set.
+pub const DEFAULT_BLOCK_SIZE: u64 = 1024;
+
+/// The block size used when `POSIXLY_CORRECT` is set.
+pub const POSIXLY_CORRECT_BLOCK_SIZE: u64 = 512;
+
+/// A mode to use in condensing the human readable display of a large number
+/// of bytes.
+///
+/// Both variants represent dynamic block sizes: as the number of bytes
+/// increases, the divisor increases as well (for example, from 1 to 1,000
+/// to 1,000,000 and so on in the case of [`HumanReadable::Decimal`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HumanReadable {
+    /// Powers of 1,000, as selected by `--si`.
+    Decimal,
+
+    /// Powers of 1,024, as selected by `-h`/`--human-readable`.
+    Binary,
+}
+
+/// The unit in which sizes are shown, as selected by `--block-size` or the
+/// environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeUnit {
+    /// A number of blocks of the given number of bytes, rounded up.
+    Blocks(u64),
+
+    /// A human-readable size, as selected by `human-readable` and `si`.
+    HumanReadable(HumanReadable),
+}
+
+impl SizeUnit {
+    /// Format a number of bytes in this unit.
+    pub fn format(self, bytes: u64) -> String {
+        match self {
+            Self::Blocks(size) => div_ceil(u128::from(bytes), u128::from(size)).to_string(),
+            Self::HumanReadable(mode) => human_readable(bytes, mode),
+        }
+    }
+}
+
+/// A SuffixType determines whether the suffixes are 1000 or 1024 based, and whether they are
+/// intended for HumanReadable mode or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuffixType {
+    Iec,
+    Si,
+    HumanReadable(HumanReadable),
+}
+
+impl SuffixType {
+    /// The first ten powers of 1024 and 1000, respectively.
+    fn bases(&self) -> [u128; 10] {
+        match self {
+            Self::Iec | Self::HumanReadable(HumanReadable::Binary) => IEC_BASES,
+            Self::Si | Self::HumanReadable(HumanReadable::Decimal) => SI_BASES,
+        }
+    }
+
+    /// Suffixes for the first nine multi-byte unit suffixes.
+    fn suffixes(&self) -> [&'static str; 9] {
+        match self {
+            // we use "kB" instead of "KB", same as GNU df
+            Self::Si => ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"],
+            Self::Iec => ["B", "K", "M", "G", "T", "P", "E", "Z", "Y"],
+            Self::HumanReadable(HumanReadable::Binary) => {
+                ["", "K", "M", "G", "T", "P", "E", "Z", "Y"]
+            }
+            Self::HumanReadable(HumanReadable::Decimal) => {
+                ["", "k", "M", "G", "T", "P", "E", "Z", "Y"]
+            }
+        }
+    }
+}
+
+/// Convert a number into a magnitude and a multi-byte unit suffix.
+///
+/// The returned string has a maximum length of 5 chars, for example: "1.1kB", "999kB", "1MB".
+pub fn to_magnitude_and_suffix(n: u128, suffix_type: SuffixType) -> String {
+    let bases = suffix_type.bases();
+    let suffixes = suffix_type.suffixes();
+    let mut i = 0;
+
+    while bases[i + 1] - bases[i] < n && i < suffixes.len() {
+        i += 1;
+    }
+
+    let quot = n / bases[i];
+    let rem = n % bases[i];
+    let suffix = suffixes[i];
+
+    if rem == 0 {
+        format!("{quot}{suffix}")
+    } else {
+        let tenths_place = rem / (bases[i] / 10);
+
+        if rem % (bases[i] / 10) == 0 {
+            format!("{quot}.{tenths_place}{suffix}")
+        } else if tenths_place + 1 == 10 || quot >= 10 {
+            format!("{}{}", quot + 1, suffix)
+        } else {
+            format!("{}.{}{}", quot, tenths_place + 1, suffix)
+        }
+    }
+}
+
+/// Format a number of bytes the way GNU utilities do for `-h` and `--si`.
+///
+/// There are a few peculiarities to how GNU formats the sizes:
+/// 1. Sizes smaller than the base (1000 or 1024) are shown as is.
+/// 2. One decimal place is given if and only if the size is smaller than 10.
+/// 3. It rounds sizes up.
+/// 4. The powers of 1024 are shown without the "i" that is commonly used to
+///    denote Kibi, Mebi, etc.
+/// 5. Kibi and Kilo are denoted differently ("K" and "k", respectively).
+pub fn human_readable(n: u64, mode: HumanReadable) -> String {
+    let suffix_type = SuffixType::HumanReadable(mode);
+    let bases = suffix_type.bases();
+    let suffixes = suffix_type.suffixes();
+    let n = u128::from(n);
+
+    let mut i = 0;
+    while i + 1 < suffixes.len() && bases[i + 1] <= n {
+        i += 1;
+    }
+    if i == 0 {
+        return n.to_string();
+    }
+
+    loop {
+        let tenths = div_ceil(n * 10, bases[i]);
+        if tenths < 100 {
+            return format!("{}.{}{}", tenths / 10, tenths % 10, suffixes[i]);
+        }
+        let whole = div_ceil(n, bases[i]);
+        // Rounding up may reach the next unit, e.g. 1048575 is "1.0M" rather
+        // than "1024K".
+        if whole < bases[1] || i + 1 == suffixes.len() {
+            return format!("{}{}", whole, suffixes[i]);
+        }
+        i += 1;
+    }
+}
+
+/// The power of the base that a suffix of [`human_readable()`] stands for,
+/// like 2 for `M`, which is 1024² with `-h` and 1000² with `--si`.
+///
+/// This is what `sort -h` orders the sizes by before their numbers, so that
+/// it sorts what the utilities show.
+///
+/// # Examples
+///
+/// ```
+/// use uucore::human_size::suffix_power;
+///
+/// assert_eq!(suffix_power('K'), Some(1));
+/// assert_eq!(suffix_power('k'), Some(1));
+/// assert_eq!(suffix_power('G'), Some(3));
+/// assert_eq!(suffix_power('B'), None);
+/// ```
+pub fn suffix_power(suffix: char) -> Option<u32> {
+    let mut buf = [0; 4];
+    let suffix = &*suffix.encode_utf8(&mut buf);
+    [HumanReadable::Binary, HumanReadable::Decimal]
+        .into_iter()
+        .find_map(|mode| {
+            SuffixType::HumanReadable(mode).suffixes()[1..]
+                .iter()
+                .position(|s| *s == suffix)
+        })
+        .map(|i| i as u32 + 1)
+}
+
+fn div_ceil(a: u128, b: u128) -> u128 {
+    (a + b - 1) / b
+}
+
+/// Parse the argument of `--block-size`.
+///
+/// This accepts the same forms as [`parse_size`], but rejects a block size
+/// of 0.
+///
+/// # Examples
+///
+/// ```
+/// use uucore::human_size::parse_block_size;
+///
+/// assert_eq!(parse_block_size("1K"), Ok(1024));
+/// assert!(parse_block_size("0").is_err());
+/// ```
+pub fn parse_block_size(s: &str) -> Result<u64, ParseSizeError> {
+    match parse_size(s)? {
+        0 => Err(ParseSizeError::ParseFailure(format!("{}", s.quote()))),
+        n => Ok(n),
+    }
+}
+
+/// Parse the argument of `--block-size`, or the value of a block size
+/// environment variable.
+///
+/// This accepts a block size, as [`parse_block_size()`] does, or
+/// `human-readable` or `si`. A leading `'`, with which GNU utilities group
+/// the digits of the sizes, is accepted and ignored.
+///
+/// # Examples
+///
+/// ```
+/// use uucore::human_size::{parse_size_unit, HumanReadable, SizeUnit};
+///
+/// assert_eq!(parse_size_unit("1K"), Ok(SizeUnit::Blocks(1024)));
+/// assert_eq!(
+///     parse_size_unit("si"),
+///     Ok(SizeUnit::HumanReadable(HumanReadable::Decimal))
+/// );
+/// ```
+pub fn parse_size_unit(s: &str) -> Result<SizeUnit, ParseSizeError> {
+    match s.strip_prefix('\'').unwrap_or(s) {
+        "human-readable" => Ok(SizeUnit::HumanReadable(HumanReadable::Binary)),
+        "si" => Ok(SizeUnit::HumanReadable(HumanReadable::Decimal)),
+        size => parse_block_size(size).map(SizeUnit::Blocks),
+    }
+}
+
+/// Get the unit of the sizes specified by the environment.
+///
+/// Like GNU, the first variable that is set among `util_var`, `BLOCK_SIZE`
+/// and `BLOCKSIZE` is used. The [default](default_block_size()) is
+/// returned if none of them is set, or if the first one set does not hold a
+/// valid unit.
+pub fn size_unit_from_env(util_var: Option<&str>) -> SizeUnit {
+    util_var
+        .into_iter()
+        .chain(["BLOCK_SIZE", "BLOCKSIZE"])
+        .find_map(|var| env::var(var).ok())
+        .and_then(|value| parse_size_unit(&value).ok())
+        .unwrap_or_else(|| SizeUnit::Blocks(default_block_size()))
+}
+
+/// Determine the unit of the sizes shown by a utility, following the
+/// precedence of GNU:
+///
+/// 1. `arg`, the argument of `--block-size`, if it was given; an invalid
+///    one is an error.
+/// 2. The first variable set among `util_var`, `BLOCK_SIZE` and
+///    `BLOCKSIZE`, as with [`size_unit_from_env()`].
+/// 3. Blocks of 512 bytes if `POSIXLY_CORRECT` is set, 1024 otherwise.
+///
+/// The options that select a unit on their own, such as `-h` or `-k`, are
+/// left to the utilities, since they differ among them.
+pub fn resolve_size_unit(
+    arg: Option<&str>,
+    util_var: Option<&str>,
+) -> Result<SizeUnit, ParseSizeError> {
+    match arg {
+        Some(arg) => parse_size_unit(arg),
+        None => Ok(size_unit_from_env(util_var)),
+    }
+}
+
+/// The block size to use when it is specified neither on the command line
+/// nor by the environment: 512 if `POSIXLY_CORRECT` is set, 1024 otherwise.
+pub fn default_block_size() -> u64 {
+    if crate::posix::posixly_correct() {
+        POSIXLY_CORRECT_BLOCK_SIZE
+    } else {
+        DEFAULT_BLOCK_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_magnitude_and_suffix_powers_of_1024() {
+        assert_eq!(to_magnitude_and_suffix(1024, SuffixType::Iec), "1K");
+        assert_eq!(to_magnitude_and_suffix(2048, SuffixType::Iec), "2K");
+        assert_eq!(to_magnitude_and_suffix(4096, SuffixType::Iec), "4K");
+        assert_eq!(to_magnitude_and_suffix(1024 * 1024, SuffixType::Iec), "1M");
+        assert_eq!(
+            to_magnitude_and_suffix(2 * 1024 * 1024, SuffixType::Iec),
+            "2M"
+        );
+        assert_eq!(
+            to_magnitude_and_suffix(1024 * 1024 * 1024, SuffixType::Iec),
+            "1G"
+        );
+        assert_eq!(
+            to_magnitude_and_suffix(34 * 1024 * 1024 * 1024, SuffixType::Iec),
+            "34G"
+        );
+    }
+
+    #[test]
+    fn test_to_magnitude_and_suffix_not_powers_of_1024() {
+        assert_eq!(to_magnitude_and_suffix(1, SuffixType::Si), "1B");
+        assert_eq!(to_magnitude_and_suffix(999, SuffixType::Si), "999B");
+
+        assert_eq!(to_magnitude_and_suffix(1000, SuffixType::Si), "1kB");
+        assert_eq!(to_magnitude_and_suffix(1001, SuffixType::Si), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(1023, SuffixType::Si), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(1025, SuffixType::Si), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(10_001, SuffixType::Si), "11kB");
+        assert_eq!(to_magnitude_and_suffix(999_000, SuffixType::Si), "999kB");
+
+        assert_eq!(to_magnitude_and_suffix(999_001, SuffixType::Si), "1MB");
+        assert_eq!(to_magnitude_and_suffix(999_999, SuffixType::Si), "1MB");
+        assert_eq!(to_magnitude_and_suffix(1_000_000, SuffixType::Si), "1MB");
+        assert_eq!(to_magnitude_and_suffix(1_000_001, SuffixType::Si), "1.1MB");
+        assert_eq!(to_magnitude_and_suffix(1_100_000, SuffixType::Si), "1.1MB");
+        assert_eq!(to_magnitude_and_suffix(1_100_001, SuffixType::Si), "1.2MB");
+        assert_eq!(to_magnitude_and_suffix(1_900_000, SuffixType::Si), "1.9MB");
+        assert_eq!(to_magnitude_and_suffix(1_900_001, SuffixType::Si), "2MB");
+        assert_eq!(to_magnitude_and_suffix(9_900_000, SuffixType::Si), "9.9MB");
+        assert_eq!(to_magnitude_and_suffix(9_900_001, SuffixType::Si), "10MB");
+        assert_eq!(
+            to_magnitude_and_suffix(999_000_000, SuffixType::Si),
+            "999MB"
+        );
+
+        assert_eq!(to_magnitude_and_suffix(999_000_001, SuffixType::Si), "1GB");
+        assert_eq!(
+            to_magnitude_and_suffix(1_000_000_000, SuffixType::Si),
+            "1GB"
+        );
+        assert_eq!(
+            to_magnitude_and_suffix(1_000_000_001, SuffixType::Si),
+            "1.1GB"
+        );
+    }
+
+    // The expected values are the output of GNU `ls -s --block-size=1 -h`
+    // (and `--si`) for files of the given sizes.
+    #[test]
+    fn test_human_readable_binary() {
+        let cases = [
+            (0, "0"),
+            (1, "1"),
+            (1000, "1000"),
+            (1023, "1023"),
+            (1024, "1.0K"),
+            (1025, "1.1K"),
+            (4096, "4.0K"),
+            (9999, "9.8K"),
+            (10_189, "10K"),
+            (10_240, "10K"),
+            (10_241, "11K"),
+            (1_025_024, "1001K"),
+            (1_048_575, "1.0M"),
+            (1_048_576, "1.0M"),
+            (12_595_200, "13M"),
+            (1_073_741_824, "1.0G"),
+            (u64::MAX, "16E"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(human_readable(n, HumanReadable::Binary), expected, "{n}");
+        }
+    }
+
+    #[test]
+    fn test_human_readable_decimal() {
+        let cases = [
+            (0, "0"),
+            (999, "999"),
+            (1000, "1.0k"),
+            (1001, "1.1k"),
+            (9999, "10k"),
+            (99_999, "100k"),
+            (999_001, "1.0M"),
+            (1_025_024, "1.1M"),
+            (12_595_200, "13M"),
+            (u64::MAX, "19E"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(human_readable(n, HumanReadable::Decimal), expected, "{n}");
+        }
+    }
+
+    #[test]
+    fn test_suffix_power() {
+        let cases = [
+            ('K', Some(1)),
+            ('k', Some(1)),
+            ('M', Some(2)),
+            ('G', Some(3)),
+            ('T', Some(4)),
+            ('P', Some(5)),
+            ('E', Some(6)),
+            ('Z', Some(7)),
+            ('Y', Some(8)),
+            ('m', None),
+            ('g', None),
+            ('B', None),
+            ('i', None),
+            ('0', None),
+        ];
+        for (suffix, expected) in cases {
+            assert_eq!(suffix_power(suffix), expected, "{suffix}");
+        }
+    }
+
+    // `sort -h` must order the sizes shown with `-h` and `--si` by their
+    // suffixes, including those that rounding up carries to the next one.
+    #[test]
+    fn test_suffix_power_of_human_readable() {
+        use HumanReadable::{Binary, Decimal};
+        let cases = [
+            (1023, Binary, 0),
+            (1024, Binary, 1),
+            (1_048_575, Binary, 2),
+            (1_048_576, Binary, 2),
+            (1_073_741_823, Binary, 3),
+            (1 << 40, Binary, 4),
+            (1 << 50, Binary, 5),
+            (1 << 60, Binary, 6),
+            (u64::MAX, Binary, 6),
+            (999, Decimal, 0),
+            (1000, Decimal, 1),
+            (999_001, Decimal, 2),
+            (999_999_999, Decimal, 3),
+            (1_000_000_000_000, Decimal, 4),
+            (1_000_000_000_000_000, Decimal, 5),
+            (1_000_000_000_000_000_000, Decimal, 6),
+            (u64::MAX, Decimal, 6),
+        ];
+        for (n, mode, expected) in cases {
+            let shown = human_readable(n, mode);
+            let power = shown.chars().next_back().and_then(suffix_power);
+            assert_eq!(power.unwrap_or(0), expected, "{n} {mode:?} {shown}");
+        }
+    }
+
+    #[test]
+    fn test_parse_block_size() {
+        assert_eq!(parse_block_size("1"), Ok(1));
+        assert_eq!(parse_block_size("K"), Ok(1024));
+        assert_eq!(parse_block_size("1KiB"), Ok(1024));
+        assert_eq!(parse_block_size("1kB"), Ok(1000));
+        assert_eq!(parse_block_size("2M"), Ok(2 * 1024 * 1024));
+        assert!(matches!(
+            parse_block_size("0"),
+            Err(ParseSizeError::ParseFailure(_))
+        ));
+        assert!(matches!(
+            parse_block_size("1x"),
+            Err(ParseSizeError::InvalidSuffix(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_size_unit() {
+        assert_eq!(parse_size_unit("4K"), Ok(SizeUnit::Blocks(4096)));
+        assert_eq!(parse_size_unit("'1"), Ok(SizeUnit::Blocks(1)));
+        assert_eq!(
+            parse_size_unit("human-readable"),
+            Ok(SizeUnit::HumanReadable(HumanReadable::Binary))
+        );
+        assert_eq!(
+            parse_size_unit("'si"),
+            Ok(SizeUnit::HumanReadable(HumanReadable::Decimal))
+        );
+        assert!(parse_size_unit("0").is_err());
+        assert!(parse_size_unit("human").is_err());
+    }
+
+    #[test]
+    fn test_size_unit_format() {
+        assert_eq!(SizeUnit::Blocks(1024).format(0), "0");
+        assert_eq!(SizeUnit::Blocks(1024).format(1), "1");
+        assert_eq!(SizeUnit::Blocks(1024).format(4096), "4");
+        assert_eq!(SizeUnit::Blocks(1).format(u64::MAX), u64::MAX.to_string());
+        assert_eq!(
+            SizeUnit::HumanReadable(HumanReadable::Binary).format(4096),
+            "4.0K"
+        );
+    }
+
+    // The variables are only changed by this test, since the tests run in
+    // parallel.
+    #[test]
+    fn test_resolve_size_unit() {
+        let util_var = Some("UUCORE_TEST_BLOCK_SIZE");
+        let resolve = |arg| resolve_size_unit(arg, util_var);
+        for var in ["UUCORE_TEST_BLOCK_SIZE", "BLOCK_SIZE", "BLOCKSIZE"] {
+            env::remove_var(var);
+        }
+        env::remove_var("POSIXLY_CORRECT");
+        assert_eq!(resolve(None), Ok(SizeUnit::Blocks(1024)));
+        env::set_var("POSIXLY_CORRECT", "1");
+        assert_eq!(resolve(None), Ok(SizeUnit::Blocks(512)));
+
+        env::set_var("BLOCKSIZE", "3");
+        assert_eq!(resolve(None), Ok(SizeUnit::Blocks(3)));
+        env::set_var("BLOCK_SIZE", "si");
+        assert_eq!(
+            resolve(None),
+            Ok(SizeUnit::HumanReadable(HumanReadable::Decimal))
+        );
+        env::set_var("UUCORE_TEST_BLOCK_SIZE", "4K");
+        assert_eq!(resolve(None), Ok(SizeUnit::Blocks(4096)));
+        assert_eq!(size_unit_from_env(None), resolve(Some("si")).unwrap());
+        assert_eq!(resolve(Some("1")), Ok(SizeUnit::Blocks(1)));
+        assert!(resolve(Some("invalid")).is_err());
+
+        // An invalid value is not skipped in favor of the next variable.
+        env::set_var("UUCORE_TEST_BLOCK_SIZE", "invalid");
+        assert_eq!(resolve(None), Ok(SizeUnit::Blocks(512)));
+
+        for var in ["UUCORE_TEST_BLOCK_SIZE", "BLOCK_SIZE", "BLOCKSIZE"] {
+            env::remove_var(var);
+        }
+        env::remove_var("POSIXLY_CORRECT");
+    }
+}
@@ -0,0 +1,215 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Translation of the messages of the utilities.
+//!
+//! The messages are looked up by key in catalogs, one per utility and
+//! language, which use a subset of the [Fluent](https://projectfluent.org)
+//! syntax: a message per line, which goes on on the indented lines after it,
+//! and comments on lines starting with `#`.
+//!
+//! ```text
+//! # A comment
+//! same-file = { $source } and { $dest } are the same file
+//! ```
+//!
+//! The English catalog of a utility, `locales/en-US.ftl` in its crate, is
+//! the reference. The [`translate!`](crate::translate) macro checks at
+//! compile time that the messages it is given are in there, with the
+//! arguments they take, and builds the English messages in.
+//!
+//! The catalogs in other languages are read at run time from
+//! `DIR/LANGUAGE/UTILITY.ftl`, like `DIR/fr-FR/cp.ftl`. `DIR` is the
+//! `UUTILS_LOCALE_DIR` environment variable, or its value when the utility
+//! was built, or `/usr/share/uutils/locales`. The languages are the ones of
+//! the locale of the messages, like with gettext: the one that `LC_ALL`,
+//! `LC_MESSAGES` or `LANG` names, in that order, preceded by the ones that
+//! `LANGUAGE` lists. A message missing from every catalog is in English.
+
+// spell-checker:ignore gettext
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::env;
+use std::fmt::{Display, Write};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// The variable naming the directory of the catalogs.
+const LOCALE_DIR_VAR: &str = "UUTILS_LOCALE_DIR";
+
+/// Where the catalogs are when `UUTILS_LOCALE_DIR` is not set.
+const DEFAULT_LOCALE_DIR: &str = match option_env!("UUTILS_LOCALE_DIR") {
+    Some(dir) => dir,
+    None => "/usr/share/uutils/locales",
+};
+
+/// The translated messages of each utility, once they were looked for.
+static CATALOGS: Lazy<Mutex<HashMap<String, HashMap<String, String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The message `key` of the utility `domain`, in the language of the user,
+/// with its placeholders replaced by `args`.
+///
+/// `default` is the English message, which is used if there is no
+/// translation. This is called by the [`translate!`](crate::translate)
+/// macro, which is what the utilities use.
+pub fn translate(domain: &str, key: &str, default: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut catalogs = CATALOGS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let catalog = catalogs
+        .entry(domain.to_string())
+        .or_insert_with(|| load(domain));
+    let message = catalog.get(key).map_or(default, String::as_str);
+    format(message, args)
+}
+
+/// The translations of the messages of `domain`, from the catalogs of all
+/// the languages of the user, the first one having the precedence.
+fn load(domain: &str) -> HashMap<String, String> {
+    let dir = env::var_os(LOCALE_DIR_VAR)
+        .map_or_else(|| PathBuf::from(DEFAULT_LOCALE_DIR), PathBuf::from);
+    let mut messages = HashMap::new();
+    for language in languages() {
+        let path = dir.join(language).join(format!("{domain}.ftl"));
+        if let Ok(content) = fs::read_to_string(path) {
+            for (key, message) in parse(&content) {
+                messages.entry(key).or_insert(message);
+            }
+        }
+    }
+    messages
+}
+
+/// The languages of the messages, from the most to the least preferred, as
+/// language tags like `fr-FR`, each followed by its language alone, like
+/// `fr`.
+fn languages() -> Vec<String> {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty());
+    let locale = match locale {
+        Some(locale) => locale,
+        None => return Vec::new(),
+    };
+    // The messages of the C locale are the English ones, as written.
+    if locale == "C" || locale == "POSIX" || locale.starts_with("C.") {
+        return Vec::new();
+    }
+    let preferred = env::var("LANGUAGE").unwrap_or_default();
+    let mut languages = Vec::new();
+    for name in preferred.split(':').chain(std::iter::once(locale.as_str())) {
+        // Like `fr_FR.UTF-8@euro`, without the codeset and the modifier.
+        let name = name.split(['.', '@']).next().unwrap_or_default();
+        if name.is_empty() {
+            continue;
+        }
+        let tag = name.replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default().to_string();
+        for tag in [tag, language] {
+            if !languages.contains(&tag) {
+                languages.push(tag);
+            }
+        }
+    }
+    languages
+}
+
+/// The messages of a catalog, by key.
+pub(crate) fn parse(content: &str) -> HashMap<String, String> {
+    let mut messages: HashMap<String, String> = HashMap::new();
+    let mut last = None;
+    // The blank lines since the last line of a message, which are part of it
+    // if it goes on after them.
+    let mut blank_lines = 0;
+    for line in content.lines() {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if line.starts_with('#') {
+            last = None;
+        } else if line.starts_with(char::is_whitespace) {
+            // The continuation of the last message.
+            if let Some(message) = last.as_ref().and_then(|key| messages.get_mut(key)) {
+                message.push_str(&"\n".repeat(blank_lines + 1));
+                message.push_str(line.trim());
+            }
+        } else if let Some((key, message)) = line.split_once('=') {
+            let key = key.trim().to_string();
+            messages.insert(key.clone(), message.trim().to_string());
+            last = Some(key);
+        }
+        blank_lines = 0;
+    }
+    messages
+}
+
+/// `message` with its placeholders, like `{ $path }`, replaced by the value
+/// of the argument of the same name. The other braces are left as they are.
+pub fn format(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut result = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let (before, placeholder) = rest.split_at(start);
+        result.push_str(before);
+        let end = match placeholder.find('}') {
+            Some(end) => end,
+            None => {
+                rest = placeholder;
+                break;
+            }
+        };
+        let name = placeholder[1..end].trim();
+        match name
+            .strip_prefix('$')
+            .and_then(|name| args.iter().find(|(arg, _)| *arg == name))
+        {
+            Some((_, value)) => {
+                let _ = write!(result, "{value}");
+            }
+            None => result.push_str(&placeholder[..=end]),
+        }
+        rest = &placeholder[end + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format, parse};
+
+    #[test]
+    fn test_parse() {
+        let messages = parse(
+            "# Messages\n\
+             same-file = { $source } and { $dest } are the same file\n\
+             \n\
+             usage = first line\n    second line\n\n    third line\n",
+        );
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages["same-file"],
+            "{ $source } and { $dest } are the same file"
+        );
+        assert_eq!(messages["usage"], "first line\nsecond line\n\nthird line");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            format(
+                "{ $source } and {$dest} are {the} same",
+                &[("source", &"a"), ("dest", &1)]
+            ),
+            "a and 1 are {the} same"
+        );
+        assert_eq!(format("{ $missing } {", &[]), "{ $missing } {");
+    }
+}
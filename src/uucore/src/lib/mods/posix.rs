@@ -0,0 +1,130 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Conformance to POSIX where the utilities usually do otherwise.
+//!
+//! Like in GNU coreutils, two environment variables are involved:
+//!
+//! * `POSIXLY_CORRECT`, which, whatever its value, makes the utilities
+//!   follow POSIX where it differs from their usual behavior, for example
+//!   `echo` interpreting backslash escapes, or block sizes of 512 bytes.
+//! * `_POSIX2_VERSION`, the version of POSIX whose option syntax is
+//!   followed, which decides whether obsolete forms like `tail +5` or
+//!   `sort +1 -2` are accepted.
+//!
+//! The obsolete forms that are still around in old scripts, like `split -100`
+//! or `uniq -2`, are rewritten into the options they stand for before the
+//! arguments are parsed, with [`replace_obsolete_args`].
+
+// spell-checker:ignore posixly
+
+use std::env;
+use std::ffi::OsString;
+
+/// The environment variable that makes the utilities follow POSIX.
+pub const POSIXLY_CORRECT: &str = "POSIXLY_CORRECT";
+
+/// POSIX.2-1992, the last version with the obsolete option forms.
+pub const OBSOLETE: u32 = 199209;
+
+/// POSIX.1-2001, which dropped the obsolete option forms.
+pub const TRADITIONAL: u32 = 200112;
+
+/// POSIX.1-2008, which is followed by default.
+pub const MODERN: u32 = 200809;
+
+/// Whether `POSIXLY_CORRECT` is set.
+pub fn posixly_correct() -> bool {
+    env::var_os(POSIXLY_CORRECT).is_some()
+}
+
+/// The version of POSIX given by `_POSIX2_VERSION`, or [`MODERN`] if it is
+/// unset or not a number.
+pub fn posix_version() -> u32 {
+    env::var("_POSIX2_VERSION")
+        .ok()
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(MODERN)
+}
+
+/// Whether the obsolete option forms of POSIX.2-1992, like `tail +5`, are
+/// accepted.
+pub fn obsolete_usage() -> bool {
+    posix_version() < TRADITIONAL
+}
+
+/// The number in an obsolete option like `-5` or `+5c`, which starts with
+/// `sign`, and the letters after it.
+pub fn obsolete_number(arg: &str, sign: char) -> Option<(&str, &str)> {
+    let rest = arg.strip_prefix(sign)?;
+    let end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    if end == 0 {
+        return None;
+    }
+    Some(rest.split_at(end))
+}
+
+/// Replace each of `args` for which `replace` returns the options that it
+/// stands for with these options, up to a `--`. The first argument, the name
+/// of the utility, is left alone.
+pub fn replace_obsolete_args<F>(
+    args: impl IntoIterator<Item = OsString>,
+    mut replace: F,
+) -> Vec<OsString>
+where
+    F: FnMut(&str) -> Option<Vec<String>>,
+{
+    let mut args = args.into_iter();
+    let mut replaced: Vec<OsString> = args.next().into_iter().collect();
+    for arg in &mut args {
+        if arg == "--" {
+            replaced.push(arg);
+            break;
+        }
+        match arg.to_str().and_then(&mut replace) {
+            Some(options) => replaced.extend(options.into_iter().map(OsString::from)),
+            None => replaced.push(arg),
+        }
+    }
+    replaced.extend(args);
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posix_version() {
+        env::set_var("_POSIX2_VERSION", "199209");
+        assert_eq!(posix_version(), OBSOLETE);
+        assert!(obsolete_usage());
+        env::set_var("_POSIX2_VERSION", "bogus");
+        assert_eq!(posix_version(), MODERN);
+        env::remove_var("_POSIX2_VERSION");
+        assert_eq!(posix_version(), MODERN);
+        assert!(!obsolete_usage());
+    }
+
+    #[test]
+    fn test_obsolete_number() {
+        assert_eq!(obsolete_number("-100", '-'), Some(("100", "")));
+        assert_eq!(obsolete_number("+5c", '+'), Some(("5", "c")));
+        assert_eq!(obsolete_number("-5", '+'), None);
+        assert_eq!(obsolete_number("-c", '-'), None);
+        assert_eq!(obsolete_number("-", '-'), None);
+    }
+
+    #[test]
+    fn test_replace_obsolete_args() {
+        let args = ["split", "-100", "file", "--", "-5"].map(OsString::from);
+        let replaced = replace_obsolete_args(args, |arg| {
+            obsolete_number(arg, '-').map(|(lines, _)| vec!["-l".to_string(), lines.to_string()])
+        });
+        assert_eq!(replaced, ["split", "-l", "100", "file", "--", "-5"]);
+    }
+}
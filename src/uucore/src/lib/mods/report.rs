@@ -0,0 +1,243 @@
+// This file is part of the uutils coreutils package.
+//
+// For the full copyright and license information, please view the LICENSE
+// file that was distributed with this source code.
+
+//! Reports of the operands that a bulk operation failed on.
+//!
+//! With `--report`, a utility prints once it is done how many of its
+//! operands it failed to process, like `rm: 4 of 1200 files failed`, after
+//! the errors it showed along the way. With `--report=json`, it prints the
+//! operands that failed and their errors as a JSON object on the standard
+//! output instead, for scripts:
+//!
+//! ```text
+//! {"operands":1200,"failed":1,"failures":[{"operand":"a","errors":["cannot remove 'a': Permission denied"]}]}
+//! ```
+//!
+//! The utility calls [`start_operand`] before processing each operand, and
+//! the errors shown with [`show!`](crate::show) and
+//! [`show_error!`](crate::show_error) until the next one are the failures of
+//! that operand. [`print`] prints the report.
+//!
+//! Reports are implemented by the following utilities:
+//!
+//! - `cp`
+//! - `mv`
+//! - `rm`
+//!
+//! # Usage example
+//!
+//! ```
+//! use clap::Command;
+//! use uucore::report::{self, ReportFormat};
+//!
+//! let matches = Command::new("command")
+//!     .arg(report::arguments::report())
+//!     .get_matches_from(vec!["command", "--report=json"]);
+//!
+//! assert_eq!(report::determine_report_format(&matches), Some(ReportFormat::Json));
+//! ```
+
+use clap::ArgMatches;
+use once_cell::sync::Lazy;
+use std::ffi::OsStr;
+use std::fmt::{Display, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+pub static REPORT_FORMAT_VALUES: &[&str] = &["summary", "json"];
+
+/// The ways to print a report.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ReportFormat {
+    /// Argument 'summary', or '--report' without argument: a line on the
+    /// standard error
+    Summary,
+    /// Argument 'json': an object on the standard output
+    Json,
+}
+
+/// An operand and the errors shown while processing it.
+struct Failure {
+    operand: String,
+    errors: Vec<String>,
+}
+
+struct Report {
+    format: ReportFormat,
+    operands: usize,
+    /// The operands, until the report is printed with only the ones that
+    /// failed.
+    failures: Vec<Failure>,
+}
+
+/// Whether a report is being made, so that showing errors is left as cheap
+/// as it is without one.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static REPORT: Lazy<Mutex<Option<Report>>> = Lazy::new(|| Mutex::new(None));
+
+/// Arguments for reports.
+pub mod arguments {
+    extern crate clap;
+
+    pub static OPT_REPORT: &str = "reportopt_report";
+
+    /// '--report' argument
+    pub fn report() -> clap::Arg {
+        clap::Arg::new(OPT_REPORT)
+            .long("report")
+            .help(
+                "print how many operands failed once done, or the failures as JSON \
+                on the standard output with FORMAT 'json'",
+            )
+            .action(clap::ArgAction::Set)
+            .value_parser(clap::builder::PossibleValuesParser::new(
+                super::REPORT_FORMAT_VALUES,
+            ))
+            .require_equals(true)
+            .num_args(0..=1)
+            .default_missing_value("summary")
+            .value_name("FORMAT")
+    }
+}
+
+/// Determine the format of the report to print, if any.
+///
+/// Takes [`clap::ArgMatches`] as argument which **must** contain the option
+/// from [`arguments::report()`]. Otherwise no report is made.
+pub fn determine_report_format(matches: &ArgMatches) -> Option<ReportFormat> {
+    match matches.get_one::<String>(arguments::OPT_REPORT)?.as_str() {
+        "json" => Some(ReportFormat::Json),
+        _ => Some(ReportFormat::Summary),
+    }
+}
+
+/// Makes a report, to print in `format`.
+pub fn enable(format: ReportFormat) {
+    *REPORT.lock().unwrap() = Some(Report {
+        format,
+        operands: 0,
+        failures: Vec::new(),
+    });
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Counts `operand`, which the next errors are the failures of.
+pub fn start_operand<S: AsRef<OsStr> + ?Sized>(operand: &S) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(report) = REPORT.lock().unwrap().as_mut() {
+        report.operands += 1;
+        report.failures.push(Failure {
+            operand: operand.as_ref().to_string_lossy().into_owned(),
+            errors: Vec::new(),
+        });
+    }
+}
+
+/// Records an error of the last operand. It is called by [`show!`](crate::show)
+/// and [`show_error!`](crate::show_error).
+#[doc(hidden)]
+pub fn record_error<T: Display + ?Sized>(error: &T) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(report) = REPORT.lock().unwrap().as_mut() {
+        if let Some(failure) = report.failures.last_mut() {
+            failure.errors.push(error.to_string());
+        }
+    }
+}
+
+/// Prints the report, if one is made.
+pub fn print() {
+    let report = match REPORT.lock().unwrap().take() {
+        Some(mut report) => {
+            // Only the operands that failed are kept.
+            report.failures.retain(|failure| !failure.errors.is_empty());
+            report
+        }
+        None => return,
+    };
+    ENABLED.store(false, Ordering::Relaxed);
+    match report.format {
+        ReportFormat::Summary => eprintln!(
+            "{}: {} of {} {} failed",
+            crate::util_name(),
+            report.failures.len(),
+            report.operands,
+            if report.operands == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        ),
+        ReportFormat::Json => println!("{}", to_json(&report)),
+    }
+}
+
+fn to_json(report: &Report) -> String {
+    let mut json = format!(
+        "{{\"operands\":{},\"failed\":{},\"failures\":[",
+        report.operands,
+        report.failures.len()
+    );
+    for (i, failure) in report.failures.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str("{\"operand\":");
+        push_json_string(&mut json, &failure.operand);
+        json.push_str(",\"errors\":[");
+        for (j, error) in failure.errors.iter().enumerate() {
+            if j > 0 {
+                json.push(',');
+            }
+            push_json_string(&mut json, error);
+        }
+        json.push_str("]}");
+    }
+    json.push_str("]}");
+    json
+}
+
+fn push_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let report = Report {
+            format: ReportFormat::Json,
+            operands: 3,
+            failures: vec![Failure {
+                operand: "a\"b".to_string(),
+                errors: vec!["first".to_string(), "second\n".to_string()],
+            }],
+        };
+        assert_eq!(
+            to_json(&report),
+            "{\"operands\":3,\"failed\":1,\"failures\":[\
+            {\"operand\":\"a\\\"b\",\"errors\":[\"first\",\"second\\n\"]}]}"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Implement GNU-style update functionality.
+//!
+//! This module implements the `--update[=WHEN]` option as described in the
+//! [GNU manual][1]. It provides
+//!
+//! - pre-defined [`clap`-Arguments][2] for inclusion in utilities that
+//!   implement updates
+//! - determination of the [update mode][3]
+//! - the [decision][4] whether an existing destination should be replaced,
+//!   taking `-n`/`--no-clobber` into account
+//! - GNU-compliant [help texts][5]
+//!
+//! Update-functionality is implemented by the following utilities:
+//!
+//! - `cp`
+//! - `mv`
+//!
+//! Prompting (`-i`) and backups (`-b`) only ever apply to destinations for
+//! which [`determine_update_action()`] returns [`UpdateAction::Replace`], so
+//! utilities should consult it before doing either.
+//!
+//!
+//! [1]: https://www.gnu.org/software/coreutils/manual/html_node/cp-invocation.html
+//! [2]: arguments
+//! [3]: `determine_update_mode()`
+//! [4]: `determine_update_action()`
+//! [5]: `UPDATE_CONTROL_LONG_HELP`
+//!
+//!
+//! # Usage example
+//!
+//! ```
+//! use clap::Command;
+//! use uucore::update_control::{self, UpdateAction, UpdateMode};
+//!
+//! let matches = Command::new("command")
+//!     .arg(update_control::arguments::update())
+//!     .arg(update_control::arguments::update_no_args())
+//!     .get_matches_from(vec!["command", "--update=none"]);
+//!
+//! let update_mode = update_control::determine_update_mode(&matches);
+//! assert_eq!(update_mode, UpdateMode::ReplaceNone);
+//!
+//! let metadata = std::fs::metadata(".").unwrap();
+//! let action = update_control::determine_update_action(update_mode, false, &metadata, &metadata);
+//! assert_eq!(action, UpdateAction::Skip);
+//! ```
+
+use clap::ArgMatches;
+use std::fs::Metadata;
+
+pub static UPDATE_CONTROL_VALUES: &[&str] = &["all", "none", "older"];
+
+pub const UPDATE_CONTROL_LONG_HELP: &str =
+    "Do not replace a non-directory that has an existing destination with the
+same or newer modification time.  WHEN may be one of:
+
+  all    replace all existing destinations (the default without --update)
+  none   replace no existing destinations, like --no-clobber but without
+         treating the skipped files as a failure
+  older  replace destinations that are older than the source (the default
+         with --update or -u)
+
+--update is ignored if --no-clobber is also given.";
+
+/// Available update modes.
+///
+/// The mapping of the update modes to the CLI arguments is annotated on the
+/// enum variants.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UpdateMode {
+    /// Argument 'all', or no update option at all
+    ReplaceAll,
+    /// Argument 'none'
+    ReplaceNone,
+    /// Argument 'older', '--update' without argument, or '-u'
+    ReplaceIfOlder,
+}
+
+/// What to do with a destination that already exists.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UpdateAction {
+    /// Replace the destination, after prompting and backing it up if the
+    /// utility was asked to.
+    Replace,
+    /// Leave the destination alone and carry on as if it had been replaced.
+    Skip,
+    /// Leave the destination alone and report that not all files were
+    /// processed, as `-n`/`--no-clobber` does.
+    SkipWithFailure,
+}
+
+/// Arguments for update-related functionality.
+///
+/// Rather than implementing the `clap`-Arguments for every utility, it is
+/// recommended to include the `clap` arguments via the functions provided here.
+/// This way the update-specific arguments are handled uniformly across
+/// utilities and can be maintained in one central place.
+pub mod arguments {
+    use clap::ArgAction;
+
+    extern crate clap;
+
+    pub static OPT_UPDATE: &str = "updateopt_update";
+    pub static OPT_UPDATE_NO_ARG: &str = "updateopt_u";
+
+    /// '--update' argument
+    pub fn update() -> clap::Arg {
+        clap::Arg::new(OPT_UPDATE)
+            .long("update")
+            .help(
+                "replace only existing destination files selected by WHEN \
+                (default: older); see below",
+            )
+            .action(clap::ArgAction::Set)
+            .value_parser(clap::builder::PossibleValuesParser::new(
+                super::UPDATE_CONTROL_VALUES,
+            ))
+            .require_equals(true)
+            .num_args(0..=1)
+            .default_missing_value("older")
+            .value_name("WHEN")
+            .overrides_with(OPT_UPDATE_NO_ARG)
+    }
+
+    /// '-u' argument
+    pub fn update_no_args() -> clap::Arg {
+        clap::Arg::new(OPT_UPDATE_NO_ARG)
+            .short('u')
+            .help("like --update but does not accept an argument")
+            .action(ArgAction::SetTrue)
+            .overrides_with(OPT_UPDATE)
+    }
+}
+
+/// Determine the "mode" for the update operation to perform.
+///
+/// Takes [`clap::ArgMatches`] as argument which **must** contain the options
+/// from [`arguments::update()`] and [`arguments::update_no_args()`]. Otherwise
+/// the `ReplaceAll` mode is returned unconditionally.
+///
+/// `-u` is equivalent to `--update=older`, and the last of `-u` and
+/// `--update` given is the one that applies, as in GNU.
+///
+/// # Examples
+///
+/// ```
+/// use clap::Command;
+/// use uucore::update_control::{self, UpdateMode};
+///
+/// let matches = Command::new("command")
+///     .arg(update_control::arguments::update())
+///     .arg(update_control::arguments::update_no_args())
+///     .get_matches_from(vec!["command", "-u", "--update=all"]);
+///
+/// let update_mode = update_control::determine_update_mode(&matches);
+/// assert_eq!(update_mode, UpdateMode::ReplaceAll)
+/// ```
+pub fn determine_update_mode(matches: &ArgMatches) -> UpdateMode {
+    if let Some(when) = matches
+        .try_get_one::<String>(arguments::OPT_UPDATE)
+        .ok()
+        .flatten()
+    {
+        match when.as_str() {
+            "all" => UpdateMode::ReplaceAll,
+            "none" => UpdateMode::ReplaceNone,
+            "older" => UpdateMode::ReplaceIfOlder,
+            _ => unreachable!("other values are rejected by clap"),
+        }
+    } else if matches
+        .try_get_one::<bool>(arguments::OPT_UPDATE_NO_ARG)
+        .ok()
+        .flatten()
+        == Some(&true)
+    {
+        UpdateMode::ReplaceIfOlder
+    } else {
+        UpdateMode::ReplaceAll
+    }
+}
+
+/// Decide what to do with the existing destination `dest` of `source`.
+///
+/// `no_clobber` must be set if `-n`/`--no-clobber` was given, in which case
+/// the update mode is ignored, as in GNU.
+///
+/// In `ReplaceIfOlder` mode, the destination is replaced only if its
+/// modification time is strictly older than that of the source. If either
+/// modification time is unavailable on this platform, the destination is
+/// replaced.
+pub fn determine_update_action(
+    update_mode: UpdateMode,
+    no_clobber: bool,
+    source: &Metadata,
+    dest: &Metadata,
+) -> UpdateAction {
+    if no_clobber {
+        return UpdateAction::SkipWithFailure;
+    }
+    match update_mode {
+        UpdateMode::ReplaceAll => UpdateAction::Replace,
+        UpdateMode::ReplaceNone => UpdateAction::Skip,
+        UpdateMode::ReplaceIfOlder => match (source.modified(), dest.modified()) {
+            (Ok(src_time), Ok(dest_time)) if src_time <= dest_time => UpdateAction::Skip,
+            _ => UpdateAction::Replace,
+        },
+    }
+}
+
+//
+// Tests for this module
+//
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Command;
+
+    fn make_app() -> clap::Command {
+        Command::new("command")
+            .arg(arguments::update())
+            .arg(arguments::update_no_args())
+    }
+
+    // No option at all replaces everything
+    #[test]
+    fn test_update_mode_default() {
+        let matches = make_app().get_matches_from(vec!["command"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceAll);
+    }
+
+    // -u is the same as --update=older
+    #[test]
+    fn test_update_mode_short_only() {
+        let matches = make_app().get_matches_from(vec!["command", "-u"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceIfOlder);
+    }
+
+    // --update can be passed without an argument
+    #[test]
+    fn test_update_mode_long_without_args() {
+        let matches = make_app().get_matches_from(vec!["command", "--update"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceIfOlder);
+    }
+
+    // The last of --update and -u applies
+    #[test]
+    fn test_update_mode_long_after_short() {
+        let matches = make_app().get_matches_from(vec!["command", "-u", "--update=none"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceNone);
+    }
+
+    #[test]
+    fn test_update_mode_short_after_long() {
+        let matches = make_app().get_matches_from(vec!["command", "--update=all", "-u"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceIfOlder);
+    }
+
+    // --update errors on invalid argument
+    #[test]
+    fn test_update_mode_long_with_args_invalid() {
+        let result = make_app().try_get_matches_from(vec!["command", "--update=foobar"]);
+
+        assert!(result.is_err());
+    }
+
+    // Arguments that were not added to the command are treated as absent
+    #[test]
+    fn test_update_mode_without_arguments() {
+        let matches = Command::new("command").get_matches_from(vec!["command"]);
+
+        assert_eq!(determine_update_mode(&matches), UpdateMode::ReplaceAll);
+    }
+
+    #[test]
+    fn test_update_action() {
+        let metadata = std::fs::metadata(".").unwrap();
+
+        for (mode, expected) in [
+            (UpdateMode::ReplaceAll, UpdateAction::Replace),
+            (UpdateMode::ReplaceNone, UpdateAction::Skip),
+            // Equal modification times are not "older"
+            (UpdateMode::ReplaceIfOlder, UpdateAction::Skip),
+        ] {
+            assert_eq!(
+                determine_update_action(mode, false, &metadata, &metadata),
+                expected
+            );
+            // --no-clobber overrides any update mode
+            assert_eq!(
+                determine_update_action(mode, true, &metadata, &metadata),
+                UpdateAction::SkipWithFailure
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//  * This file is part of the uutils coreutils package.
+//  *
+//  * For the full copyright and license information, please view the LICENSE
+//  * file that was distributed with this source code.
+
+//! Machine-readable version information, printed by `--version=json`.
+//!
+//! Every utility reports its name and version. Utilities whose abilities
+//! depend on how they were built, or on the platform, also report the
+//! cargo features they were built with and what they support, so that
+//! deployment tools can check for them without parsing help texts. They
+//! do so by giving a function to the `main` attribute:
+//!
+//! ```ignore
+//! fn version_info() -> VersionInfo {
+//!     VersionInfo::new(env!("CARGO_PKG_VERSION"))
+//!         .feature("acl", cfg!(feature = "feat_acl"))
+//!         .capability("reflink", cfg!(target_os = "linux"))
+//! }
+//!
+//! #[uucore::main(version_info)]
+//! pub fn uumain(args: impl uucore::Args) -> UResult<()> {
+//!     ...
+//! }
+//! ```
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::path::Path;
+
+/// The argument that asks for the version information in JSON.
+pub const JSON_VERSION_ARG: &str = "--version=json";
+
+/// Whether `args`, which start with the name of the utility, ask for the
+/// version information in JSON and nothing else.
+pub fn json_requested(args: &[OsString]) -> bool {
+    args.len() == 2 && args[1] == JSON_VERSION_ARG
+}
+
+/// What `--version=json` reports about a utility.
+pub struct VersionInfo {
+    name: String,
+    version: &'static str,
+    features: Vec<&'static str>,
+    capabilities: Vec<(&'static str, bool)>,
+}
+
+impl VersionInfo {
+    /// The information of the running utility, which is at `version`.
+    pub fn new(version: &'static str) -> Self {
+        let util_name = crate::util_name();
+        let name = Path::new(util_name)
+            .file_stem()
+            .map_or_else(|| util_name.into(), |name| name.to_string_lossy());
+        Self {
+            name: name.into_owned(),
+            version,
+            features: Vec::new(),
+            capabilities: Vec::new(),
+        }
+    }
+
+    /// List the optional feature `name` if it was `enabled` at build time.
+    pub fn feature(mut self, name: &'static str, enabled: bool) -> Self {
+        if enabled {
+            self.features.push(name);
+        }
+        self
+    }
+
+    /// Record whether the utility can do `name` on this platform.
+    pub fn capability(mut self, name: &'static str, supported: bool) -> Self {
+        self.capabilities.push((name, supported));
+        self
+    }
+
+    /// The information as a JSON object, on a single line.
+    pub fn to_json(&self) -> String {
+        let mut json = format!(
+            "{{\"name\":{},\"version\":{},\"features\":[",
+            json_string(&self.name),
+            json_string(self.version)
+        );
+        let features: Vec<String> = self.features.iter().map(|f| json_string(f)).collect();
+        json.push_str(&features.join(","));
+        json.push_str("],\"capabilities\":{");
+        let capabilities: Vec<String> = self
+            .capabilities
+            .iter()
+            .map(|(name, supported)| format!("{}:{}", json_string(name), supported))
+            .collect();
+        json.push_str(&capabilities.join(","));
+        json.push_str("}}");
+        json
+    }
+}
+
+/// `s` as a JSON string, with quotes.
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let mut info = VersionInfo::new("1.2.3")
+            .feature("acl", true)
+            .feature("selinux", false)
+            .capability("reflink", true)
+            .capability("sparse", false);
+        info.name = "cp".to_string();
+        assert_eq!(
+            info.to_json(),
+            r#"{"name":"cp","version":"1.2.3","features":["acl"],"capabilities":{"reflink":true,"sparse":false}}"#
+        );
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("a\"b\\c\n\t"), r#""a\"b\\c\n\u0009""#);
+    }
+
+    #[test]
+    fn test_json_requested() {
+        let args = |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert!(json_requested(&args(&["cp", "--version=json"])));
+        assert!(!json_requested(&args(&["cp", "--version"])));
+        assert!(!json_requested(&args(&["cp", "a", "--version=json"])));
+    }
+}
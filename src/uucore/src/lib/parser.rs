@@ -1,3 +1,4 @@
 pub mod parse_glob;
 pub mod parse_size;
+#[cfg(feature = "numeric")]
 pub mod parse_time;
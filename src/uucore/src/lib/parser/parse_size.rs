@@ -5,11 +5,22 @@
 
 // spell-checker:ignore (ToDO) hdsf ghead gtail
 
+use std::env;
 use std::error::Error;
 use std::fmt;
 
 use crate::display::Quotable;
 
+/// The environment variable that makes the utilities reject the suffixes
+/// whose meaning differs among them, like `K`, which is 1024 in the sizes
+/// of `du` or `sort -S` but 1000 in the numbers of `numfmt --from=auto`.
+pub const STRICT_SUFFIXES: &str = "UUTILS_STRICT_SUFFIXES";
+
+/// Whether `UUTILS_STRICT_SUFFIXES` is set.
+pub fn strict_suffixes() -> bool {
+    env::var_os(STRICT_SUFFIXES).is_some()
+}
+
 /// Parser for sizes in SI or IEC units (multiples of 1000 or 1024 bytes).
 ///
 /// The [`Parser::parse`] function performs the parse.
@@ -88,6 +99,7 @@ impl<'parser> Parser<'parser> {
         // "block" and the Posix block size is 512. The uppercase "B"
         // means "byte".
         let mut unit: &str = &size[numeric_string.len()..];
+        let explicit_unit = !unit.is_empty();
 
         if let Some(default_unit) = self.default_unit {
             // Check if `unit` is empty then assigns `default_unit` to `unit`
@@ -115,6 +127,19 @@ impl<'parser> Parser<'parser> {
             }
         }
 
+        // With UUTILS_STRICT_SUFFIXES, the suffixes have to say whether they
+        // are powers of 1024 or 1000, where both are accepted.
+        if explicit_unit
+            && unit.len() == 1
+            && "KMGTPEZYkmgtpezy".contains(unit)
+            && self
+                .allow_list
+                .map_or(true, |list| list.contains(&format!("{unit}B").as_str()))
+            && strict_suffixes()
+        {
+            return Err(ParseSizeError::ambiguous_suffix(size, unit));
+        }
+
         let (base, exponent): (u128, u32) = match unit {
             "" => (1, 0),
             "B" if self.capital_b_bytes => (1, 0),
@@ -176,15 +201,17 @@ pub fn parse_size(size: &str) -> Result<u64, ParseSizeError> {
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ParseSizeError {
-    InvalidSuffix(String), // Suffix
-    ParseFailure(String),  // Syntax
-    SizeTooBig(String),    // Overflow
+    InvalidSuffix(String),   // Suffix
+    AmbiguousSuffix(String), // Suffix rejected with UUTILS_STRICT_SUFFIXES
+    ParseFailure(String),    // Syntax
+    SizeTooBig(String),      // Overflow
 }
 
 impl Error for ParseSizeError {
     fn description(&self) -> &str {
         match *self {
             Self::InvalidSuffix(ref s) => s,
+            Self::AmbiguousSuffix(ref s) => s,
             Self::ParseFailure(ref s) => s,
             Self::SizeTooBig(ref s) => s,
         }
@@ -194,7 +221,10 @@ impl Error for ParseSizeError {
 impl fmt::Display for ParseSizeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         let s = match self {
-            Self::InvalidSuffix(s) | Self::ParseFailure(s) | Self::SizeTooBig(s) => s,
+            Self::InvalidSuffix(s)
+            | Self::AmbiguousSuffix(s)
+            | Self::ParseFailure(s)
+            | Self::SizeTooBig(s) => s,
         };
         write!(f, "{s}")
     }
@@ -208,6 +238,19 @@ impl ParseSizeError {
         Self::InvalidSuffix(format!("{}", s.quote()))
     }
 
+    fn ambiguous_suffix(s: &str, unit: &str) -> Self {
+        // The utilities tell which argument it is, e.g.:
+        //
+        // du: invalid suffix in --block-size argument '1K': the ambiguous
+        // suffix K is rejected; write KiB for a power of 1024, or KB for a
+        // power of 1000
+        Self::AmbiguousSuffix(format!(
+            "{}: the ambiguous suffix {unit} is rejected; \
+             write {unit}iB for a power of 1024, or {unit}B for a power of 1000",
+            s.quote()
+        ))
+    }
+
     fn parse_failure(s: &str) -> Self {
         // stderr on linux (GNU coreutils 8.32) (LC_ALL=C)
         // has to be handled in the respective uutils because strings differ, e.g.:
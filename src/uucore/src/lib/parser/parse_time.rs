@@ -13,6 +13,7 @@
 use std::time::Duration;
 
 use crate::display::Quotable;
+use crate::numeric::parse_float_prefix;
 
 /// Parse a duration from a string.
 ///
@@ -20,6 +21,8 @@ use crate::display::Quotable;
 /// may contain a number with a unit specifier, like "123s" meaning
 /// one hundred twenty three seconds or "4.5d" meaning four and a half
 /// days. If no unit is specified, the unit is assumed to be seconds.
+/// The number is read like GNU does, see [`crate::numeric`], so it may
+/// also be hexadecimal, like "0x10", or infinite, like "inf".
 ///
 /// The only allowed suffixes are
 ///
@@ -47,31 +50,22 @@ use crate::display::Quotable;
 /// assert_eq!(from_str("2d"), Ok(Duration::from_secs(60 * 60 * 24 * 2)));
 /// ```
 pub fn from_str(string: &str) -> Result<Duration, String> {
-    let len = string.len();
-    if len == 0 {
-        return Err("empty string".to_owned());
-    }
-    let slice = &string[..len - 1];
-    let (numstr, times) = match string.chars().next_back().unwrap() {
-        's' => (slice, 1),
-        'm' => (slice, 60),
-        'h' => (slice, 60 * 60),
-        'd' => (slice, 60 * 60 * 24),
-        val if !val.is_alphabetic() => (string, 1),
-        _ => {
-            if string == "inf" || string == "infinity" {
-                ("inf", 1)
-            } else {
-                return Err(format!("invalid time interval {}", string.quote()));
-            }
-        }
+    let invalid = || format!("invalid time interval {}", string.quote());
+    let (number, suffix) = parse_float_prefix(string).ok_or_else(invalid)?;
+    let times = match suffix {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 60 * 60 * 24,
+        _ => return Err(invalid()),
     };
-    let num = numstr
-        .parse::<f64>()
-        .map_err(|e| format!("invalid time interval {}: {}", string.quote(), e))?;
-
-    if num < 0. {
-        return Err(format!("invalid time interval {}", string.quote()));
+    let num = number.to_f64();
+    // NaN isn't a valid interval either, but -0 is.
+    if num.is_nan() || num < 0. {
+        return Err(invalid());
+    }
+    if num >= u64::MAX as f64 {
+        return Ok(Duration::MAX);
     }
 
     const NANOS_PER_SEC: u32 = 1_000_000_000;
@@ -122,6 +116,22 @@ mod tests {
         assert!(from_str("-1").is_err());
     }
 
+    #[test]
+    fn test_gnu_forms() {
+        assert_eq!(from_str(" 1"), Ok(Duration::from_secs(1)));
+        assert_eq!(from_str("+1m"), Ok(Duration::from_secs(60)));
+        assert_eq!(from_str("0x10"), Ok(Duration::from_secs(16)));
+        assert_eq!(from_str("0x1.8p1s"), Ok(Duration::from_secs(3)));
+        assert_eq!(from_str("1e1"), Ok(Duration::from_secs(10)));
+        assert_eq!(from_str(".5"), Ok(Duration::from_millis(500)));
+        assert_eq!(from_str("-0"), Ok(Duration::ZERO));
+        assert_eq!(from_str("INF"), Ok(Duration::MAX));
+        assert_eq!(from_str("infinityd"), Ok(Duration::MAX));
+        assert!(from_str("nan").is_err());
+        assert!(from_str("1 ").is_err());
+        assert!(from_str("1ss").is_err());
+    }
+
     /// Test that capital letters are not allowed in suffixes.
     #[test]
     fn test_no_capital_letters() {
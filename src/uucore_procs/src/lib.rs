@@ -11,16 +11,38 @@ use quote::quote;
 //* ref: <https://dev.to/naufraghi/procedural-macro-in-rust-101-k3f> @@ <http://archive.is/Vbr5e>
 //* ref: [path construction from LitStr](https://oschwald.github.io/maxminddb-rust/syn/struct.LitStr.html) @@ <http://archive.is/8YDua>
 
+/// Wrap the `uumain` function of a utility, turning its errors into an
+/// exit code, answering `--version=json`, and printing the report asked
+/// for with `--report`.
+///
+/// The attribute can name a function returning the
+/// `uucore::version::VersionInfo` of the utility, for those that report
+/// features or capabilities.
 #[proc_macro_attribute]
-pub fn main(_args: TokenStream, stream: TokenStream) -> TokenStream {
+pub fn main(args: TokenStream, stream: TokenStream) -> TokenStream {
     let stream = proc_macro2::TokenStream::from(stream);
+    let version_info = if args.is_empty() {
+        quote!(uucore::version::VersionInfo::new(env!("CARGO_PKG_VERSION")))
+    } else {
+        let function = proc_macro2::TokenStream::from(args);
+        quote!(#function())
+    };
 
     let new = quote!(
         pub fn uumain(args: impl uucore::Args) -> i32 {
             #stream
-            let result = uumain(args);
-            match result {
+            let args: Vec<std::ffi::OsString> = args.collect();
+            if uucore::version::json_requested(&args) {
+                println!("{}", #version_info.to_json());
+                return 0;
+            }
+            let result = uumain(args.into_iter());
+            let code = match result {
                 Ok(()) => uucore::error::get_exit_code(),
+                // Like being killed by SIGPIPE, without a word.
+                Err(e) if uucore::broken_pipe::is_broken_pipe(&*e) => {
+                    uucore::broken_pipe::EXIT_CODE
+                }
                 Err(e) => {
                     let s = format!("{}", e);
                     if s != "" {
@@ -31,7 +53,10 @@ pub fn main(_args: TokenStream, stream: TokenStream) -> TokenStream {
                     }
                     e.code()
                 }
-            }
+            };
+            // After the error that may have stopped the utility.
+            uucore::report::print();
+            code
         }
     );
 
@@ -99,6 +124,134 @@ pub fn help_section(input: TokenStream) -> TokenStream {
     TokenTree::Literal(Literal::string(&rendered)).into()
 }
 
+/// Translate a message of the utility, with `uucore::i18n`.
+///
+/// The first argument is the key of the message, which must be in the
+/// English catalog of the utility, `locales/en-US.ftl` in its crate. The
+/// others are the values of its placeholders, by name. The English message
+/// is built in, and is what the expression evaluates to, as a `String`,
+/// when there is no translation.
+///
+/// ```rust,ignore
+/// // same-file = { $source } and { $dest } are the same file
+/// translate!("same-file", source = source.quote(), dest = dest.quote());
+/// ```
+#[proc_macro]
+pub fn translate(input: TokenStream) -> TokenStream {
+    let mut arguments: Vec<Vec<TokenTree>> = vec![Vec::new()];
+    for token in input {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => arguments.push(Vec::new()),
+            _ => arguments.last_mut().unwrap().push(token),
+        }
+    }
+    // A trailing comma.
+    if arguments.len() > 1 && arguments.last().map_or(false, Vec::is_empty) {
+        arguments.pop();
+    }
+
+    let key = match arguments[0].as_slice() {
+        [TokenTree::Literal(key)] => unquote(&key.to_string()),
+        _ => panic!("The first argument should be the key of the message, as a string literal."),
+    };
+    let mut names = Vec::new();
+    let mut values = Vec::new();
+    for argument in &arguments[1..] {
+        match argument.as_slice() {
+            [TokenTree::Ident(name), TokenTree::Punct(equals), value @ ..]
+                if equals.as_char() == '=' && !value.is_empty() =>
+            {
+                names.push(name.to_string());
+                values.push(proc_macro2::TokenStream::from(
+                    value.iter().cloned().collect::<TokenStream>(),
+                ));
+            }
+            _ => panic!("The arguments after the key should be of the form `name = value`."),
+        }
+    }
+
+    let mut path = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    path.push("locales");
+    path.push("en-US.ftl");
+    let mut catalog = String::new();
+    File::open(&path)
+        .and_then(|mut file| file.read_to_string(&mut catalog))
+        .unwrap_or_else(|e| panic!("Could not read {}: {e}", path.display()));
+    let message = find_message(&catalog, &key)
+        .unwrap_or_else(|| panic!("The message '{key}' is not in {}.", path.display()));
+    let mut placeholders = placeholders(&message);
+    placeholders.sort();
+    let mut sorted_names = names.clone();
+    sorted_names.sort();
+    if placeholders != sorted_names {
+        panic!("The message '{key}' takes the arguments {placeholders:?}, not {sorted_names:?}.");
+    }
+
+    let package = std::env::var("CARGO_PKG_NAME").unwrap();
+    let domain = package.strip_prefix("uu_").unwrap_or(&package);
+    let path = path.display().to_string();
+    let new = quote!({
+        // Build the utility again when its catalog changes.
+        const _: &str = include_str!(#path);
+        uucore::i18n::translate(
+            #domain,
+            #key,
+            #message,
+            &[#((#names, &(#values) as &dyn ::std::fmt::Display)),*],
+        )
+    });
+    TokenStream::from(new)
+}
+
+/// The message `key` of a catalog, in the format of `uucore::i18n`.
+fn find_message(catalog: &str, key: &str) -> Option<String> {
+    let mut lines = catalog.lines().skip_while(|line| {
+        line.split_once('=').map_or(true, |(k, _)| {
+            line.starts_with(char::is_whitespace) || k.trim() != key
+        })
+    });
+    let mut message = lines.next()?.split_once('=')?.1.trim().to_string();
+    let mut blank_lines = 0;
+    for line in lines.take_while(|line| line.is_empty() || line.starts_with(char::is_whitespace)) {
+        if line.trim().is_empty() {
+            blank_lines += 1;
+        } else {
+            message.push_str(&"\n".repeat(blank_lines + 1));
+            message.push_str(line.trim());
+            blank_lines = 0;
+        }
+    }
+    Some(message)
+}
+
+/// The names of the placeholders of `message`, like `path` for `{ $path }`.
+fn placeholders(message: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        if let Some(name) = rest[start + 1..end].trim().strip_prefix('$') {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    names
+}
+
+/// The contents of a string literal, which has no escapes.
+fn unquote(literal: &str) -> String {
+    literal
+        .strip_prefix('"')
+        .and_then(|l| l.strip_suffix('"'))
+        .unwrap_or_else(|| panic!("{literal} should be a string literal."))
+        .to_string()
+}
+
 /// Get an argument from the input vector of `TokenTree`.
 ///
 /// Asserts that the argument is a string literal and returns the string value,
@@ -192,7 +345,29 @@ fn parse_usage(content: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_help_section, parse_usage};
+    use super::{find_message, parse_help_section, parse_usage, placeholders};
+
+    #[test]
+    fn message_finding() {
+        let catalog = "\
+            # same = not this\n\
+            same-file = { $source } and { $dest } are the same file\n\
+            usage = first line\n    second line\n\n    third line\n\nnext = message\n";
+
+        assert_eq!(
+            find_message(catalog, "same-file").unwrap(),
+            "{ $source } and { $dest } are the same file"
+        );
+        assert_eq!(
+            find_message(catalog, "usage").unwrap(),
+            "first line\nsecond line\n\nthird line"
+        );
+        assert_eq!(find_message(catalog, "same"), None);
+        assert_eq!(
+            placeholders("{ $source } and {$dest} are {not} { $source }"),
+            ["source", "dest"]
+        );
+    }
 
     #[test]
     fn section_parsing() {
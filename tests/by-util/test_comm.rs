@@ -87,22 +87,49 @@ fn output_delimiter_nul() {
         .stdout_only_fixture("ab_delimiter_nul.expected");
 }
 
-// even though (info) documentation suggests this is an option
-// in latest GNU Coreutils comm, it actually is not.
-// this test is essentially an alarm in case some well-intending
-// developer implements it.
-//marked as unimplemented as error message not set yet.
-#[cfg_attr(not(feature = "test_unimplemented"), ignore)]
 #[test]
 fn zero_terminated() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.write("a0", "a\0b\nc\0d\0");
+    scene.fixtures.write("b0", "b\nc\0e");
     for param in ["-z", "--zero-terminated"] {
-        new_ucmd!()
-            .args(&[param, "a", "b"])
-            .fails()
-            .stderr_only("error to be defined");
+        scene
+            .ucmd()
+            .args(&[param, "a0", "b0"])
+            .succeeds()
+            .stdout_only("a\0\t\tb\nc\0d\0\te\0");
     }
 }
 
+#[test]
+fn zero_terminated_total() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.write("a0", "a\0b\0");
+    scene.fixtures.write("b0", "b\0");
+    scene
+        .ucmd()
+        .args(&["-z", "--total", "-123", "a0", "b0"])
+        .succeeds()
+        .stdout_only("1\t0\t1\ttotal\0");
+}
+
+#[test]
+fn crlf() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.write("a", "\u{feff}a\r\nb\r\n");
+    scene.fixtures.write("b", "a\nc\n");
+    scene
+        .ucmd()
+        .args(&["--crlf", "a", "b"])
+        .succeeds()
+        .stdout_only("\t\ta\r\nb\r\n\tc\n");
+    scene
+        .ucmd()
+        .args(&["a", "b"])
+        .succeeds()
+        .stdout_only("\ta\n\tc\n\u{feff}a\r\nb\r\n");
+}
+
 #[cfg_attr(not(feature = "test_unimplemented"), ignore)]
 #[test]
 fn check_order() {
@@ -150,6 +150,24 @@ fn test_cp_recurse() {
     assert_eq!(at.read(TEST_COPY_TO_FOLDER_NEW_FILE), "Hello, World!\n");
 }
 
+#[test]
+#[cfg(windows)]
+fn test_cp_recurse_beyond_max_path() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // Nested deep enough for the copy to go beyond 260 characters
+    let deep: std::path::PathBuf = ["a"].iter().chain(&["directory"; 30]).collect();
+    at.mkdir_all(deep.to_str().unwrap());
+    at.write(deep.join("file").to_str().unwrap(), "Hello, World!\n");
+
+    ucmd.args(&["-r", "a", "b"]).succeeds();
+
+    let copy = Path::new("b").join(deep.strip_prefix("a").unwrap());
+    assert_eq!(
+        at.read(copy.join("file").to_str().unwrap()),
+        "Hello, World!\n"
+    );
+}
+
 #[test]
 fn test_cp_with_dirs_t() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -500,6 +518,19 @@ fn test_cp_backup_numbered() {
     );
 }
 
+#[test]
+#[cfg(unix)]
+fn test_cp_backup_numbered_failure_leaves_no_backup() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("src");
+    // A socket can't be read to be backed up.
+    let _socket = std::os::unix::net::UnixListener::bind(at.plus("dest")).unwrap();
+
+    ucmd.args(&["--backup=numbered", "src", "dest"]).fails();
+
+    assert!(at.plus("dest.~1~").symlink_metadata().is_err());
+}
+
 #[test]
 fn test_cp_backup_existing() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -857,6 +888,52 @@ fn test_cp_parents_dest_not_directory() {
         .stderr_contains("with --parents, the destination must be a directory");
 }
 
+#[test]
+#[cfg(unix)]
+fn test_cp_parents_symlink_component() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir_all("a/real");
+    at.write("a/real/f", "f");
+    at.relative_symlink_dir("real", "a/link");
+    at.mkdir("d1");
+    at.mkdir("d2");
+
+    // Without dereferencing, the link is kept, with what it leads to.
+    scene
+        .ucmd()
+        .args(&["-P", "--parents", "a/link/f", "d1"])
+        .succeeds();
+    assert!(at.is_symlink("d1/a/link"));
+    assert_eq!(at.read("d1/a/real/f"), "f");
+
+    // Otherwise, it is replaced with the directory it points to.
+    scene
+        .ucmd()
+        .args(&["--parents", "a/link/f", "d2"])
+        .succeeds();
+    assert!(!at.is_symlink("d2/a/link"));
+    assert_eq!(at.read("d2/a/link/f"), "f");
+}
+
+#[test]
+#[cfg(unix)]
+fn test_cp_parents_preserve_parent_attributes() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("a/b");
+    at.write("a/b/f", "f");
+    at.mkdir("d");
+    at.set_mode("a/b", 0o700);
+
+    ucmd.args(&["-p", "--parents", "a/b/f", "d"]).succeeds();
+
+    assert_eq!(at.metadata("d/a/b").mode() & 0o777, 0o700);
+    assert_eq!(
+        at.metadata("d/a/b").modified().unwrap(),
+        at.metadata("a/b").modified().unwrap()
+    );
+}
+
 #[test]
 #[cfg(unix)]
 fn test_cp_writable_special_file_permissions() {
@@ -973,15 +1050,86 @@ fn test_cp_preserve_all_context_fails_on_non_selinux() {
 
 #[test]
 #[cfg(any(target_os = "android"))]
-fn test_cp_preserve_xattr_fails_on_android() {
-    // Because of the SELinux extended attributes used on Android, trying to copy extended
-    // attributes has to fail in this case, since we specify `--preserve=xattr` and this puts it
-    // into the required attributes
+fn test_cp_preserve_xattr_skips_security_on_android() {
+    // The SELinux extended attributes used on Android can't be set by
+    // unprivileged processes, which skip them even though `--preserve=xattr`
+    // makes the extended attributes required.
     new_ucmd!()
         .arg(TEST_COPY_FROM_FOLDER_FILE)
         .arg(TEST_HELLO_WORLD_DEST)
         .arg("--preserve=xattr")
-        .fails();
+        .succeeds();
+}
+
+#[test]
+#[cfg(windows)]
+fn test_cp_preserve_xattr_alternate_data_streams() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "content");
+    at.write("a:stream", "alternate");
+
+    ucmd.args(&["--preserve=xattr", "a", "b"]).succeeds();
+
+    assert_eq!(at.read("b"), "content");
+    assert_eq!(at.read("b:stream"), "alternate");
+}
+
+#[test]
+#[cfg(target_os = "macos")]
+fn test_cp_archive_resource_fork() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "content");
+    at.write("a/..namedfork/rsrc", "fork");
+
+    ucmd.args(&["-a", "a", "b"]).succeeds();
+
+    assert_eq!(at.read("b"), "content");
+    assert_eq!(at.read("b/..namedfork/rsrc"), "fork");
+}
+
+#[test]
+#[cfg(windows)]
+fn test_cp_preserve_mode_hidden_attribute() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+    scene.cmd("attrib").args(&["+h", "a"]).succeeds();
+
+    scene.ucmd().args(&["--preserve=mode", "a", "b"]).succeeds();
+
+    scene
+        .cmd("attrib")
+        .arg("b")
+        .succeeds()
+        .stdout_matches(&regex::Regex::new(r"^\s*A?\s+H\s").unwrap());
+}
+
+#[test]
+#[cfg(all(feature = "feat_acl", target_os = "linux"))]
+fn test_cp_preserve_mode_acl() {
+    use std::process::Command;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    let getfacl = |file: &str| {
+        Command::new("getfacl")
+            .args(["--omit-header", &at.plus_as_string(file)])
+            .output()
+            .unwrap()
+            .stdout
+    };
+
+    // Skip the test when the ACL tools or ACL support are missing
+    let acl_set = Command::new("setfacl")
+        .args(["-m", "u:65534:r", &at.plus_as_string("a")])
+        .status()
+        .map_or(false, |status| status.success());
+    if !acl_set {
+        return;
+    }
+
+    ucmd.args(&["--preserve=mode", "a", "b"]).succeeds();
+    assert_eq!(getfacl("a"), getfacl("b"));
 }
 
 #[test]
@@ -1312,6 +1460,20 @@ fn test_cp_preserve_timestamps() {
     assert_eq!(creation, creation2);
 }
 
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_cp_preserve_timestamps_source_date_epoch() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("new");
+    ucmd.env("SOURCE_DATE_EPOCH", "1000")
+        .args(&["--preserve=timestamps", "new", "copy"])
+        .succeeds();
+    assert_eq!(
+        FileTime::from_last_modification_time(&at.metadata("copy")),
+        FileTime::from_unix_time(1000, 0)
+    );
+}
+
 #[test]
 #[cfg(any(target_os = "linux", target_os = "android"))]
 fn test_cp_no_preserve_timestamps() {
@@ -1431,7 +1593,31 @@ fn test_cp_one_file_system() {
 }
 
 #[test]
-#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+#[cfg(target_os = "linux")]
+fn test_cp_one_file_system_mount_point_stub() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // `/proc` is a file system of its own, reached here through a link.
+    if std_fs::metadata("/proc").unwrap().dev() == at.metadata(".").dev() {
+        return;
+    }
+    at.mkdir("src");
+    at.touch("src/file");
+    at.relative_symlink_dir("/proc", "src/proc");
+
+    ucmd.args(&["-RLx", "src", "dest"]).succeeds().no_stderr();
+
+    assert!(at.file_exists("dest/file"));
+    assert!(at.dir_exists("dest/proc"));
+    assert_eq!(std_fs::read_dir(at.plus("dest/proc")).unwrap().count(), 0);
+}
+
+#[test]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+))]
 fn test_cp_reflink_always() {
     let (at, mut ucmd) = at_and_ucmd!();
     let result = ucmd
@@ -1449,7 +1635,12 @@ fn test_cp_reflink_always() {
 }
 
 #[test]
-#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+))]
 fn test_cp_reflink_auto() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.arg("--reflink=auto")
@@ -1462,7 +1653,12 @@ fn test_cp_reflink_auto() {
 }
 
 #[test]
-#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+))]
 fn test_cp_reflink_none() {
     let (at, mut ucmd) = at_and_ucmd!();
     let result = ucmd
@@ -1480,7 +1676,12 @@ fn test_cp_reflink_none() {
 }
 
 #[test]
-#[cfg(any(target_os = "linux", target_os = "android", target_os = "macos"))]
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+))]
 fn test_cp_reflink_never() {
     let (at, mut ucmd) = at_and_ucmd!();
     ucmd.arg("--reflink=never")
@@ -1613,6 +1814,54 @@ fn test_cp_sparse_always_non_empty() {
     assert_eq!(at.metadata("dst_file_sparse").blocks(), touched_block_count);
 }
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_cp_sparse_auto_keeps_holes() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    const SIZE: u64 = 64 * 1024 * 1024;
+    at.make_file("src_file1").set_len(SIZE).unwrap();
+    at.append("src_file1", "data");
+    let src_blocks = at.metadata("src_file1").blocks();
+    if src_blocks * 512 >= SIZE {
+        // The file system of the test does not support holes.
+        return;
+    }
+
+    ucmd.args(&["--reflink=never", "src_file1", "dst_file_sparse"])
+        .succeeds();
+
+    assert_eq!(at.read_bytes("dst_file_sparse"), at.read_bytes("src_file1"));
+    assert!(at.metadata("dst_file_sparse").blocks() <= src_blocks);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_cp_sparse_always_keeps_holes() {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    const SIZE: u64 = 64 * 1024 * 1024;
+    let mut file = at.make_file("src_file1");
+    file.write_all(b"head").unwrap();
+    file.seek(SeekFrom::Start(SIZE / 2)).unwrap();
+    file.write_all(b"middle").unwrap();
+    file.set_len(SIZE).unwrap();
+    drop(file);
+    let src_blocks = at.metadata("src_file1").blocks();
+    if src_blocks * 512 >= SIZE {
+        // The file system of the test does not support holes.
+        return;
+    }
+
+    ucmd.args(&["--sparse=always", "src_file1", "dst_file_sparse"])
+        .succeeds();
+
+    assert_eq!(at.read_bytes("dst_file_sparse"), at.read_bytes("src_file1"));
+    assert!(at.metadata("dst_file_sparse").blocks() <= src_blocks);
+}
+
 #[cfg(any(target_os = "linux", target_os = "android"))]
 #[test]
 fn test_cp_sparse_invalid_option() {
@@ -1802,6 +2051,80 @@ fn test_no_preserve_mode() {
     );
 }
 
+#[test]
+#[cfg(all(unix, not(target_os = "freebsd")))]
+fn test_explicit_no_preserve_mode() {
+    use std::os::unix::prelude::MetadataExt;
+
+    use uucore::mode::get_umask;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    set_permissions(at.plus("file"), PermissionsExt::from_mode(0o777)).unwrap();
+    ucmd.args(&["-a", "--no-preserve=mode", "file", "dest"])
+        .succeeds()
+        .no_stderr();
+    // Like any new file, regardless of the mode of the source
+    assert_eq!(
+        at.plus("dest").metadata().unwrap().mode() & 0o7777,
+        0o666 & !get_umask()
+    );
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "freebsd")))]
+fn test_cp_archive_no_preserve() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    at.set_mode("file", 0o700);
+    let previous = FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_times(at.plus("file"), previous, previous).unwrap();
+
+    ucmd.args(&["-a", "--no-preserve=ownership,timestamps", "file", "dest"])
+        .succeeds()
+        .no_stderr();
+
+    let metadata = at.metadata("dest");
+    assert_eq!(metadata.mode() & 0o7777, 0o700);
+    assert_ne!(FileTime::from_last_modification_time(&metadata), previous);
+}
+
+#[test]
+fn test_cp_recursive_preserve_dir_timestamps() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("src/a/b");
+    at.touch("src/a/b/file");
+    let previous = FileTime::from_unix_time(1_000_000_000, 0);
+    for dir in ["src/a/b", "src/a", "src"] {
+        filetime::set_file_times(at.plus(dir), previous, previous).unwrap();
+    }
+
+    ucmd.args(&["-R", "--preserve=timestamps", "src", "dest"])
+        .succeeds()
+        .no_stderr();
+
+    for dir in ["dest", "dest/a", "dest/a/b"] {
+        let metadata = at.metadata(dir);
+        assert_eq!(FileTime::from_last_modification_time(&metadata), previous);
+    }
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "freebsd")))]
+fn test_cp_recursive_preserve_read_only_dir() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("src/dir");
+    at.touch("src/dir/file");
+    at.set_mode("src/dir", 0o555);
+
+    ucmd.args(&["-Rp", "src", "dest"]).succeeds().no_stderr();
+
+    assert!(at.file_exists("dest/dir/file"));
+    assert_eq!(at.metadata("dest/dir").mode() & 0o777, 0o555);
+    at.set_mode("src/dir", 0o755);
+    at.set_mode("dest/dir", 0o755);
+}
+
 #[test]
 #[cfg(all(unix, not(target_os = "freebsd")))]
 fn test_preserve_mode() {
@@ -1878,6 +2201,18 @@ fn test_copy_through_dangling_symlink() {
         .stderr_only("cp: not writing through dangling symlink 'target'\n");
 }
 
+#[test]
+#[cfg(unix)]
+fn test_copy_through_symlink() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("file", "new");
+    at.write("target", "old");
+    at.symlink_file("target", "link");
+    ucmd.arg("file").arg("link").succeeds().no_output();
+    assert!(at.is_symlink("link"));
+    assert_eq!(at.read("target"), "new");
+}
+
 #[test]
 fn test_copy_through_dangling_symlink_no_dereference() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -1985,6 +2320,49 @@ fn test_cp_fifo() {
     assert!(at.is_fifo("fifo2"));
 }
 
+#[test]
+#[cfg(unix)]
+fn test_cp_socket_skipped() {
+    use std::os::unix::net::UnixListener;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+    let _listener = UnixListener::bind(at.plus("dir/socket")).unwrap();
+    ucmd.arg("-r")
+        .arg("dir")
+        .arg("dir2")
+        .succeeds()
+        .stderr_contains("cannot copy socket")
+        .no_stdout();
+    assert!(at.file_exists("dir2/file"));
+    assert!(!at.plus("dir2/socket").exists());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_cp_char_device() {
+    use std::os::unix::fs::{FileTypeExt, MetadataExt};
+
+    // Creating device nodes requires privileges
+    if !nix::unistd::geteuid().is_root() {
+        return;
+    }
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.arg("-R")
+        .arg("/dev/null")
+        .arg("null")
+        .succeeds()
+        .no_stderr()
+        .no_stdout();
+    let metadata = at.plus("null").symlink_metadata().unwrap();
+    assert!(metadata.file_type().is_char_device());
+    assert_eq!(
+        metadata.rdev(),
+        std::fs::metadata("/dev/null").unwrap().rdev()
+    );
+}
+
 #[test]
 fn test_dir_recursive_copy() {
     let scene = TestScenario::new(util_name!());
@@ -2301,6 +2679,38 @@ fn test_copy_nested_directory_to_itself_disallowed() {
         .stderr_only(expected);
 }
 
+/// Test that a symbolic link to an ancestor is not followed forever by `-RL`.
+#[cfg(not(windows))]
+#[test]
+fn test_cp_dereference_symlink_cycle() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("a/b");
+    at.touch("a/f");
+    at.relative_symlink_dir("..", "a/b/loop");
+    ucmd.args(&["-RL", "a", "c"])
+        .fails()
+        .stderr_only("cp: cannot copy cyclic symbolic link 'a/b/loop'\n");
+    assert!(at.file_exists("c/f"));
+    assert!(!at.dir_exists("c/b/loop"));
+}
+
+/// Test that `-RL` doesn't copy the destination into itself when a
+/// symbolic link points above the source.
+#[cfg(not(windows))]
+#[test]
+fn test_cp_dereference_symlink_to_destination() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("a/b");
+    at.relative_symlink_dir("../..", "a/b/loop");
+    ucmd.args(&["-RL", "a", "c"])
+        .fails()
+        .stderr_contains("cp: cannot copy a directory, 'a/b/loop/c', into itself, 'c/b/loop/c'")
+        .stderr_contains("cp: cannot copy cyclic symbolic link 'a/b/loop/a'");
+    assert!(at.dir_exists("c/b/loop"));
+    assert!(!at.dir_exists("c/b/loop/a"));
+    assert!(!at.dir_exists("c/b/loop/c"));
+}
+
 /// Test for preserving permissions when copying a directory.
 #[cfg(all(not(windows), not(target_os = "freebsd")))]
 #[test]
@@ -2490,6 +2900,32 @@ fn test_preserve_hardlink_attributes_in_directory() {
     );
 }
 
+/// Test that hard links are preserved across a file and a directory operand.
+#[test]
+#[cfg(all(unix, not(any(target_os = "android", target_os = "freebsd"))))]
+fn test_preserve_hardlinks_across_operands() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    at.mkdir("src");
+    at.touch("src/f");
+    at.hard_link("src/f", "link");
+    at.touch("src/single");
+    at.mkdir("dest");
+
+    ucmd.args(&["-a", "link", "src", "dest"])
+        .succeeds()
+        .no_output();
+
+    assert_eq!(
+        at.metadata("dest/link").ino(),
+        at.metadata("dest/src/f").ino()
+    );
+    assert_ne!(
+        at.metadata("dest/link").ino(),
+        at.metadata("dest/src/single").ino()
+    );
+}
+
 #[test]
 #[cfg(not(any(windows, target_os = "android")))]
 fn test_hard_link_file() {
@@ -2532,3 +2968,210 @@ fn test_src_base_dot() {
         .no_stdout();
     assert!(!at.dir_exists("y/x"));
 }
+
+#[test]
+fn test_cp_bwlimit() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // The first second worth of data is allowed through at once, so this
+    // doesn't wait. The rate itself is tested by the unit tests of cp.
+    at.write("src", &"x".repeat(10 * 1024));
+    ucmd.args(&["--bwlimit=10K", "src", "dest"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("dest"), at.read("src"));
+}
+
+#[test]
+fn test_cp_bwlimit_invalid() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("src");
+    ucmd.args(&["--bwlimit=fast", "src", "dest"])
+        .fails()
+        .stderr_contains("invalid --bwlimit argument 'fast'");
+    assert!(!at.file_exists("dest"));
+}
+
+#[test]
+fn test_cp_summary() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "hello");
+    at.write("b", "new");
+    at.mkdir("dir");
+    at.write("dir/b", "old");
+    ucmd.args(&["--summary", "-n", "a", "b", "missing", "dir"])
+        .fails()
+        .stdout_contains("files copied: 1\n")
+        .stdout_contains("bytes transferred: 5\n")
+        .stdout_contains("files skipped: 1\n")
+        .stdout_contains("files failed: 1\n")
+        .stdout_contains("  'missing' -> ")
+        .stdout_contains("elapsed time: ");
+    assert_eq!(at.read("dir/b"), "old");
+}
+
+#[test]
+fn test_cp_summary_recursive() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("src/sub");
+    at.write("src/a", "abc");
+    at.write("src/sub/b", "de");
+    ucmd.args(&["--summary", "-r", "src", "dest"])
+        .succeeds()
+        .stdout_contains("files copied: 2\n")
+        .stdout_contains("bytes transferred: 5\n")
+        .stdout_contains("files failed: 0\n");
+}
+
+#[test]
+fn test_cp_version_json() {
+    new_ucmd!()
+        .arg("--version=json")
+        .succeeds()
+        .stdout_contains(r#"{"name":"cp","version":""#)
+        .stdout_contains(r#""capabilities":{"reflink":"#);
+}
+
+#[test]
+fn test_cp_check_space() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "abc");
+    ucmd.args(&["--check-space", "a", "b"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("b"), "abc");
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_cp_check_space_not_enough() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // A sparse file, which is larger than most file systems.
+    at.make_file("big").set_len(15 << 40).unwrap();
+    ucmd.args(&["--check-space", "big", "b"])
+        .fails()
+        .stderr_contains("cp: not enough space in '.': 15T needed");
+    assert!(!at.file_exists("b"));
+}
+
+#[test]
+fn test_cp_arg_update_none() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "new");
+    at.write("b", "old");
+    ucmd.args(&["--update=none", "a", "b"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("b"), "old");
+}
+
+#[test]
+fn test_cp_arg_update_older() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "new");
+    at.write("b", "old");
+    let old = filetime::FileTime::from_unix_time(0, 0);
+    filetime::set_file_mtime(at.plus("b"), old).unwrap();
+    ucmd.args(&["--update=older", "a", "b"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("b"), "new");
+}
+
+#[test]
+fn test_cp_arg_update_last_wins() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    // The source is older than the destination.
+    at.write("a", "new");
+    let old = filetime::FileTime::from_unix_time(0, 0);
+    filetime::set_file_mtime(at.plus("a"), old).unwrap();
+
+    at.write("b", "old");
+    scene
+        .ucmd()
+        .args(&["--update=all", "-u", "a", "b"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("b"), "old");
+
+    scene
+        .ucmd()
+        .args(&["-u", "--update=all", "a", "b"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("b"), "new");
+}
+
+#[test]
+fn test_cp_arg_update_recursive_skips_existing() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("src");
+    at.write("src/a", "new");
+    at.write("src/b", "new");
+    at.mkdir_all("dest/src");
+    at.write("dest/src/a", "old");
+    ucmd.args(&["-r", "--update=none", "src", "dest"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("dest/src/a"), "old");
+    assert_eq!(at.read("dest/src/b"), "new");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_cp_direct() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // Not a multiple of the block size, so the tail needs special handling.
+    at.write("src", &"abc".repeat(10_000));
+    ucmd.args(&["--direct", "--reflink=never", "src", "dest"])
+        .succeeds()
+        .no_output();
+    assert_eq!(at.read("dest"), at.read("src"));
+}
+
+#[test]
+#[cfg(any(windows, target_os = "macos"))]
+fn test_cp_preserve_crtimes() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("src", "hello");
+    // Make sure the destination is created at a different time.
+    sleep(Duration::from_secs(1));
+    ucmd.args(&["--preserve=crtimes", "src", "dest"])
+        .succeeds()
+        .no_output();
+    assert_eq!(
+        at.metadata("src").created().unwrap(),
+        at.metadata("dest").created().unwrap()
+    );
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_cp_preserve_crtimes_unsupported() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.write("src", "hello");
+    scene
+        .ucmd()
+        .args(&["--preserve=crtimes", "src", "dest"])
+        .fails()
+        .stderr_contains("not supported on this platform");
+    // --preserve=all only preserves creation times where possible.
+    scene
+        .ucmd()
+        .args(&["--preserve=all", "src", "dest2"])
+        .succeeds();
+}
+
+#[test]
+fn test_cp_report() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("a");
+    at.touch("b");
+
+    ucmd.args(&["--report", "a", "missing", "b", "dir"])
+        .fails()
+        .stderr_contains("cp: 1 of 3 files failed\n");
+    assert!(at.file_exists("dir/a"));
+    assert!(at.file_exists("dir/b"));
+}
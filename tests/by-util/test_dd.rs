@@ -257,10 +257,11 @@ fn test_final_stats_noxfer() {
 
 #[test]
 fn test_final_stats_unspec() {
-    new_ucmd!()
-        .run()
-        .stderr_only("0+0 records in\n0+0 records out\n0 bytes copied, 0.0 s, 0.0 B/s\n")
-        .success();
+    let result = new_ucmd!().run();
+    result.success().no_stdout();
+    let s = result.stderr_str();
+    assert!(s.starts_with("0+0 records in\n0+0 records out\n0 bytes copied, "));
+    assert!(s.ends_with(" s, 0.0 kB/s\n"));
 }
 
 #[cfg(any(target_os = "linux", target_os = "android"))]
@@ -372,11 +373,11 @@ fn test_existing_file_truncated() {
 
 #[test]
 fn test_null_stats() {
-    new_ucmd!()
-        .args(&["if=null.txt"])
-        .run()
-        .stderr_only("0+0 records in\n0+0 records out\n0 bytes copied, 0.0 s, 0.0 B/s\n")
-        .success();
+    let result = new_ucmd!().args(&["if=null.txt"]).run();
+    result.success().no_stdout();
+    let s = result.stderr_str();
+    assert!(s.starts_with("0+0 records in\n0+0 records out\n0 bytes copied, "));
+    assert!(s.ends_with(" s, 0.0 kB/s\n"));
 }
 
 #[test]
@@ -1442,3 +1443,62 @@ fn test_sparse() {
     // number of blocks stored on disk may be zero.
     assert_eq!(at.metadata("infile").len(), at.metadata("outfile").len());
 }
+
+/// Test that the partial input blocks are gathered into full output blocks.
+#[test]
+fn test_partial_blocks_gathered() {
+    new_ucmd!()
+        .args(&["ibs=3", "obs=4", "status=noxfer"])
+        .pipe_in("abcdefghij")
+        .succeeds()
+        .stdout_is("abcdefghij")
+        .stderr_is("3+1 records in\n2+1 records out\n");
+}
+
+/// Test that `bs` overrides `ibs` and `obs` wherever it is given.
+#[test]
+fn test_bs_overrides_ibs_obs() {
+    for args in [["bs=4", "ibs=3"], ["ibs=3", "bs=4"]] {
+        new_ucmd!()
+            .args(&args)
+            .arg("status=noxfer")
+            .pipe_in("abcdefghij")
+            .succeeds()
+            .stdout_is("abcdefghij")
+            .stderr_is("2+1 records in\n2+1 records out\n");
+    }
+}
+
+/// Test that the records of `conv=block` can span several reads.
+#[test]
+fn test_block_across_reads() {
+    new_ucmd!()
+        .args(&["ibs=2", "cbs=4", "conv=block", "status=noxfer"])
+        .pipe_in("abc\nde\nf")
+        .succeeds()
+        .stdout_is("abc de  f   ")
+        .stderr_is("4+0 records in\n0+1 records out\n");
+}
+
+#[test]
+fn test_conv_excl_file_exists() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("outfile");
+    ucmd.args(&["of=outfile", "conv=excl"])
+        .fails()
+        .stderr_contains("File exists");
+}
+
+/// Test that a failing `fsync` is reported after the copy.
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fsync_dev_null_fails() {
+    new_ucmd!()
+        .args(&["of=/dev/null", "conv=fsync", "status=noxfer"])
+        .pipe_in("abc")
+        .fails()
+        .code_is(1)
+        .stderr_is(
+            "dd: fsync failed for '/dev/null': Invalid argument\n0+1 records in\n0+1 records out\n",
+        );
+}
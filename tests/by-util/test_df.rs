@@ -664,6 +664,18 @@ fn test_invalid_block_size_from_env() {
     assert_eq!(header, default_block_size_header);
 }
 
+#[test]
+fn test_human_readable_block_size_from_env() {
+    let output = new_ucmd!()
+        .arg("--output=size")
+        .env("DF_BLOCK_SIZE", "si")
+        .succeeds()
+        .stdout_move_str();
+    let header = output.lines().next().unwrap().trim().to_string();
+
+    assert_eq!(header, "Size");
+}
+
 #[test]
 fn test_ignore_block_size_from_env_in_posix_portability_mode() {
     let default_block_size_header = "1024-blocks";
@@ -70,6 +70,7 @@ fn test_internal_db() {
 #[test]
 fn test_ls_colors() {
     new_ucmd!()
+        .env("TERM", "screen")
         .arg("--print-ls-colors")
         .run()
         .stdout_is_fixture("ls_colors.expected");
@@ -206,6 +207,23 @@ export LS_COLORS
     check("[^a]_negation", "b_negation", expectation_if_match);
 }
 
+#[test]
+fn test_colorterm_matching() {
+    // The internal database has no entry for this TERM, but `COLORTERM ?*`.
+    new_ucmd!()
+        .env("TERM", "dumb")
+        .arg("-b")
+        .succeeds()
+        .stdout_only("LS_COLORS='';\nexport LS_COLORS\n");
+
+    new_ucmd!()
+        .env("TERM", "dumb")
+        .env("COLORTERM", "truecolor")
+        .arg("-b")
+        .succeeds()
+        .stdout_contains("di=01;34:");
+}
+
 fn test_helper(file_name: &str, term: &str) {
     new_ucmd!()
         .env("TERM", term)
@@ -116,6 +116,27 @@ fn test_du_invalid_size() {
     }
 }
 
+#[test]
+fn test_du_invalid_size_strict_suffixes() {
+    let ts = TestScenario::new(util_name!());
+    ts.ucmd()
+        .env("UUTILS_STRICT_SUFFIXES", "1")
+        .arg("--block-size=1K")
+        .fails()
+        .code_is(1)
+        .stderr_only(
+            "du: invalid suffix in --block-size argument '1K': the ambiguous suffix K is \
+             rejected; write KiB for a power of 1024, or KB for a power of 1000\n",
+        );
+    for size in ["1KiB", "1KB", "1024"] {
+        ts.ucmd()
+            .env("UUTILS_STRICT_SUFFIXES", "1")
+            .arg(format!("--block-size={size}"))
+            .arg("-s")
+            .succeeds();
+    }
+}
+
 #[test]
 fn test_du_basics_bad_name() {
     new_ucmd!()
@@ -262,6 +283,31 @@ fn _du_d_flag(s: &str) {
     }
 }
 
+#[test]
+fn test_du_max_depth_rolls_up_deeper_entries() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    at.mkdir_all("a/b/c");
+    at.touch("a/f1");
+    at.touch("a/b/f2");
+    at.touch("a/b/c/f3");
+
+    let result = ts.ucmd().args(&["--inodes", "-a", "-d1", "a"]).succeeds();
+    let mut lines: Vec<_> = result.stdout_str().lines().collect();
+    lines.sort_unstable();
+    let expected = if cfg!(windows) {
+        ["1\ta\\f1", "4\ta\\b", "6\ta"]
+    } else {
+        ["1\ta/f1", "4\ta/b", "6\ta"]
+    };
+    assert_eq!(lines, expected);
+
+    ts.ucmd()
+        .args(&["--inodes", "-s", "a"])
+        .succeeds()
+        .stdout_only("6\ta\n");
+}
+
 #[test]
 fn test_du_dereference() {
     let ts = TestScenario::new(util_name!());
@@ -386,6 +432,60 @@ fn test_du_h_flag_empty_file() {
         .stdout_only("0\tempty.txt\n");
 }
 
+#[test]
+fn test_du_h_flag_rounds_up() {
+    let ts = TestScenario::new(util_name!());
+    ts.fixtures.write("small", &"a".repeat(1000));
+    ts.fixtures.write("large", &"a".repeat(9999));
+    ts.ucmd()
+        .args(&["-h", "--apparent-size", "small", "large"])
+        .succeeds()
+        .stdout_only("1000\tsmall\n9.8K\tlarge\n");
+    ts.ucmd()
+        .args(&["--si", "--apparent-size", "small", "large"])
+        .succeeds()
+        .stdout_only("1.0k\tsmall\n10k\tlarge\n");
+}
+
+#[test]
+fn test_du_invalid_block_size() {
+    new_ucmd!()
+        .arg("--block-size=0")
+        .arg("empty.txt")
+        .fails()
+        .stderr_contains("invalid --block-size argument '0'");
+}
+
+#[test]
+fn test_du_block_size_from_env() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    at.write("file", &"a".repeat(5000));
+
+    ts.ucmd()
+        .env("DU_BLOCK_SIZE", "1")
+        .env("BLOCK_SIZE", "1K")
+        .arg("--apparent-size")
+        .arg("file")
+        .succeeds()
+        .stdout_only("5000\tfile\n");
+
+    ts.ucmd()
+        .env("BLOCKSIZE", "si")
+        .arg("--apparent-size")
+        .arg("file")
+        .succeeds()
+        .stdout_only("5.0k\tfile\n");
+
+    ts.ucmd()
+        .env("BLOCK_SIZE", "1")
+        .arg("--apparent-size")
+        .arg("-k")
+        .arg("file")
+        .succeeds()
+        .stdout_only("5\tfile\n");
+}
+
 #[cfg(feature = "touch")]
 #[test]
 fn test_du_time() {
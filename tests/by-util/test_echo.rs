@@ -232,3 +232,18 @@ fn test_hyphen_values_between() {
         .success()
         .stdout_is("dumdum  dum dum dum -e dum\n");
 }
+
+#[test]
+fn test_posixly_correct() {
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["-e", "a\\tb"])
+        .succeeds()
+        .stdout_only("-e a\tb\n");
+
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["-n", "a\\tb"])
+        .succeeds()
+        .stdout_only("a\tb");
+}
@@ -242,3 +242,106 @@ fn test_fail_change_directory() {
         .stderr_move_str();
     assert!(out.contains("env: cannot change directory to "));
 }
+
+#[test]
+fn test_null_delimiter_newline_in_value() {
+    new_ucmd!()
+        .args(&["-i", "-0", "A=a\nb", "B=c"])
+        .succeeds()
+        .stdout_only("A=a\nb\0B=c\0");
+}
+
+#[test]
+fn test_multiple_unset() {
+    new_ucmd!()
+        .env("A", "1")
+        .env("B", "2")
+        .env("C", "3")
+        .args(&["-u", "A", "--unset=B"])
+        .succeeds()
+        .stdout_contains("C=3")
+        .stdout_does_not_contain("A=1")
+        .stdout_does_not_contain("B=2");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_print_non_utf8_value() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let result = new_ucmd!().env("A", OsStr::from_bytes(b"\xff")).succeeds();
+    assert!(result.stdout().windows(4).any(|line| line == b"A=\xff\n"));
+}
+
+#[test]
+fn test_split_string() {
+    new_ucmd!()
+        .args(&["-i", "-S", "A=1 B='x y'  C=\"\\t\" #D=4"])
+        .succeeds()
+        .stdout_only("A=1\nB=x y\nC=\t\n");
+}
+
+#[test]
+fn test_split_string_options() {
+    // Like on the shebang line `#!/usr/bin/env -S -i A=1`.
+    new_ucmd!()
+        .env("B", "2")
+        .arg("-S -i -u B A=1")
+        .succeeds()
+        .stdout_only("A=1\n");
+    new_ucmd!()
+        .env("B", "2")
+        .args(&["--split-string=-i -u B", "-SA=1", "C=3"])
+        .succeeds()
+        .stdout_only("A=1\nC=3\n");
+}
+
+#[test]
+fn test_split_string_expansion() {
+    new_ucmd!()
+        .env("V", "a b")
+        .args(&["-S", "-i A=${V} B=\"${V}\" C='${V}'"])
+        .succeeds()
+        .stdout_only("A=a b\nB=a b\nC=${V}\n");
+}
+
+#[test]
+fn test_split_string_errors() {
+    for (string, message) in [
+        ("'a", "no terminating quote in -S string"),
+        ("a\\", "invalid backslash at end of string in -S"),
+        ("\\x", "invalid sequence '\\x' in -S"),
+        (
+            "\"\\c\"",
+            "'\\c' must not appear in double-quoted -S string",
+        ),
+        ("$V", "only ${VARNAME} expansion is supported, error at: $V"),
+    ] {
+        new_ucmd!()
+            .args(&["-S", string])
+            .fails()
+            .code_is(125)
+            .stderr_only(format!("env: {message}\n"));
+    }
+}
+
+#[test]
+fn test_command_not_found() {
+    new_ucmd!()
+        .arg("nonexistent-command")
+        .fails()
+        .code_is(127)
+        .stderr_is("env: 'nonexistent-command': No such file or directory\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_command_not_executable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    ucmd.arg("./file")
+        .fails()
+        .code_is(126)
+        .stderr_is("env: './file': Permission denied\n");
+}
@@ -388,3 +388,14 @@ fn test_comma_with_plus_4() {
         //          01234567890
         .stdout_is("a  b    c");
 }
+
+#[test]
+fn test_nonexistent_file_continues() {
+    new_ucmd!()
+        .args(&["nonexistent", "-"])
+        .pipe_in("a\tb")
+        .fails()
+        .code_is(1)
+        .stdout_is("a       b")
+        .stderr_is("expand: nonexistent: No such file or directory\n");
+}
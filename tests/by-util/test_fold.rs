@@ -548,3 +548,21 @@ fn test_obsolete_syntax() {
         .succeeds()
         .stdout_is("test1\n \ntest2\n \ntest3\n \ntest4\n \ntest5\n \ntest6\n ");
 }
+
+#[test]
+fn test_zero_terminated() {
+    new_ucmd!()
+        .args(&["-z", "-w", "3"])
+        .pipe_in("abcdefgh\0x\ny\0")
+        .succeeds()
+        .stdout_is("abc\0def\0gh\0x\ny\0");
+}
+
+#[test]
+fn test_bytewise_zero_terminated() {
+    new_ucmd!()
+        .args(&["-z", "-b", "-w", "3"])
+        .pipe_in("abcdefgh\0\0")
+        .succeeds()
+        .stdout_is("abc\0def\0gh\0\0");
+}
@@ -141,3 +141,33 @@ fn test_check_file_not_found_warning() {
 fn test_invalid_arg() {
     new_ucmd!().arg("--definitely-invalid").fails().code_is(1);
 }
+
+#[test]
+fn test_io_mode() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    at.write("testf", "foobar\n");
+    at.write(
+        "testf.sha1",
+        "988881adc9fc3655077dc2d4d757d480b5ea0e11  testf\n",
+    );
+    for mode in ["auto", "mmap", "read"] {
+        scene
+            .ccmd("sha1sum")
+            .arg(format!("--io-mode={mode}"))
+            .arg("testf")
+            .succeeds()
+            .stdout_is("988881adc9fc3655077dc2d4d757d480b5ea0e11  testf\n");
+        scene
+            .ccmd("sha1sum")
+            .args(&["--io-mode", mode, "-c", "testf.sha1"])
+            .succeeds()
+            .stdout_is("testf: OK\n");
+    }
+    scene
+        .ccmd("sha1sum")
+        .args(&["--io-mode=direct", "testf"])
+        .fails()
+        .code_is(1);
+}
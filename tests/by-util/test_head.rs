@@ -365,3 +365,18 @@ fn test_presume_input_pipe_5_chars() {
         .run()
         .stdout_is_fixture("lorem_ipsum_5_chars.expected");
 }
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_all_but_last_of_pipe_given_as_file() {
+    new_ucmd!()
+        .args(&["-n", "-2", "/dev/stdin"])
+        .pipe_in("a\nb\nc\nd\n")
+        .succeeds()
+        .stdout_is("a\nb\n");
+    new_ucmd!()
+        .args(&["-c", "-3", "/dev/stdin"])
+        .pipe_in("abcdef")
+        .succeeds()
+        .stdout_is("abc");
+}
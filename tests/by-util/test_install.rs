@@ -217,6 +217,24 @@ fn test_install_mode_symbolic() {
     assert_eq!(0o100_003_u32, PermissionsExt::mode(&permissions));
 }
 
+#[test]
+fn test_install_mode_symbolic_without_who() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file = "source_file";
+    let dest_file = "dest_file";
+
+    // The mode changes no permissions at all, not the umask or a default.
+    at.touch(file);
+    ucmd.arg(file)
+        .arg(dest_file)
+        .arg("-m+w")
+        .succeeds()
+        .no_stderr();
+
+    let permissions = at.metadata(dest_file).permissions();
+    assert_eq!(0o100_222_u32, PermissionsExt::mode(&permissions));
+}
+
 #[test]
 fn test_install_mode_failing() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -380,6 +398,39 @@ fn test_install_preserve_timestamps() {
     );
 }
 
+#[test]
+fn test_install_source_date_epoch() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.touch("new");
+    at.touch("old");
+    let old = FileTime::from_unix_time(100, 0);
+    filetime::set_file_times(at.plus("old"), old, old).unwrap();
+
+    // The files are never newer than SOURCE_DATE_EPOCH, and older ones keep
+    // their time with -p.
+    let epoch = FileTime::from_unix_time(1000, 0);
+    scene
+        .ucmd()
+        .env("SOURCE_DATE_EPOCH", "1000")
+        .args(&["new", "dir"])
+        .succeeds();
+    assert_eq!(
+        FileTime::from_last_modification_time(&at.metadata("dir/new")),
+        epoch
+    );
+    scene
+        .ucmd()
+        .env("SOURCE_DATE_EPOCH", "1000")
+        .args(&["-p", "old", "dir"])
+        .succeeds();
+    assert_eq!(
+        FileTime::from_last_modification_time(&at.metadata("dir/old")),
+        old
+    );
+}
+
 // These two tests are failing but should work
 #[test]
 fn test_install_copy_file() {
@@ -407,6 +458,37 @@ fn test_install_target_file_dev_null() {
     assert!(at.file_exists(file2));
 }
 
+#[test]
+#[cfg(unix)]
+fn test_install_replaces_symlink() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("source_file", "new");
+    at.write("target", "old");
+    at.symlink_file("target", "link");
+
+    ucmd.arg("source_file").arg("link").succeeds().no_output();
+
+    assert!(!at.is_symlink("link"));
+    assert_eq!(at.read("link"), "new");
+    assert_eq!(at.read("target"), "old");
+}
+
+#[test]
+fn test_install_replaces_hard_link() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("source_file", "new");
+    at.write("target_file", "old");
+    at.hard_link("target_file", "other_link");
+
+    ucmd.arg("source_file")
+        .arg("target_file")
+        .succeeds()
+        .no_output();
+
+    assert_eq!(at.read("target_file"), "new");
+    assert_eq!(at.read("other_link"), "old");
+}
+
 #[test]
 fn test_install_nested_paths_copy_file() {
     let (at, mut ucmd) = at_and_ucmd!();
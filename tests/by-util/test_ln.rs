@@ -715,3 +715,34 @@ fn test_symlink_remove_existing_same_src_and_dest() {
     assert!(at.file_exists("a") && !at.symlink_exists("a"));
     assert_eq!(at.read("a"), "sample");
 }
+
+#[test]
+fn test_symlink_existing_dest_fails() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+    ucmd.args(&["-s", "a", "b"])
+        .fails()
+        .stderr_contains("failed to create symbolic link 'b'");
+    assert!(!at.is_symlink("b"));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_junction() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+    ucmd.args(&["--junction", "dir", "junction"]).succeeds();
+    assert!(at.file_exists("junction/file"));
+}
+
+#[test]
+#[cfg(not(windows))]
+fn test_junction_unsupported() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    ucmd.args(&["--junction", "dir", "junction"])
+        .fails()
+        .stderr_contains("--junction is only supported on Windows");
+}
@@ -301,6 +301,48 @@ fn test_ls_allocation_size() {
     }
 }
 
+#[test]
+fn test_ls_block_size_from_env() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("file", &"a".repeat(5000));
+
+    // BLOCKSIZE only applies to the space used by the files.
+    scene
+        .ucmd()
+        .env("BLOCKSIZE", "1")
+        .arg("-l")
+        .arg("file")
+        .succeeds()
+        .stdout_contains(" 5000 ");
+
+    scene
+        .ucmd()
+        .env("BLOCK_SIZE", "1K")
+        .arg("-l")
+        .arg("file")
+        .succeeds()
+        .stdout_contains(" 5 ");
+
+    scene
+        .ucmd()
+        .env("LS_BLOCK_SIZE", "human-readable")
+        .env("BLOCK_SIZE", "1K")
+        .arg("-l")
+        .arg("file")
+        .succeeds()
+        .stdout_contains(" 4.9K ");
+
+    scene
+        .ucmd()
+        .env("BLOCK_SIZE", "1K")
+        .arg("--block-size=1")
+        .arg("-l")
+        .arg("file")
+        .succeeds()
+        .stdout_contains(" 5000 ");
+}
+
 #[test]
 fn test_ls_devices() {
     let scene = TestScenario::new(util_name!());
@@ -848,7 +890,7 @@ fn test_ls_zero() {
         .ucmd()
         .args(&["--zero", "--color=always"])
         .succeeds()
-        .stdout_only("\x1b[1;34m0-test-zero\x1b[0m\x002-test-zero\x003-test-zero\x00");
+        .stdout_only("\x1b[0m\x1b[01;34m0-test-zero\x1b[0m\x002-test-zero\x003-test-zero\x00");
 
     scene
         .ucmd()
@@ -906,7 +948,7 @@ fn test_ls_zero() {
             .args(&["--zero", "--color=always"])
             .succeeds()
             .stdout_only(
-                "\x1b[1;34m0-test-zero\x1b[0m\x001\ntest-zero\x002-test-zero\x003-test-zero\x00",
+                "\x1b[0m\x1b[01;34m0-test-zero\x1b[0m\x001\ntest-zero\x002-test-zero\x003-test-zero\x00",
             );
 
         scene
@@ -1048,8 +1090,7 @@ fn test_ls_long_symlink_color() {
     // The contents of each tuple are the expected colors and names for the link and target.
     // We will loop over the ls output and compare to those.
     // None values mean that we do not know what color to expect yet, as LS_COLOR might
-    // be set differently, and as different implementations of ls may use different codes,
-    // for example, our ls uses `[1;36m` while the GNU ls uses `[01;36m`.
+    // be set differently.
     //
     // These have been sorting according to default ls sort, and this affects the order of
     // discovery of colors, so be very careful when changing directory/file names being created.
@@ -1058,10 +1099,16 @@ fn test_ls_long_symlink_color() {
         (None, "ln-dir3", None, "./dir1/dir2/dir3"),
         // We have acquired [0, 0], which should be the link color,
         // and [0, 1], which should be the dir color, and we can compare to them from now on.
-        (None, "ln-file-invalid", Some([1, 1]), "dir1/invalid-target"),
+        // Without an orphan color, a dangling link has the link color, as in GNU ls.
+        (
+            Some([0, 0]),
+            "ln-file-invalid",
+            Some([1, 1]),
+            "dir1/invalid-target",
+        ),
         // We acquired [1, 1], the non-existent color.
         (Some([0, 0]), "ln-file1", None, "dir1/file1"),
-        (Some([1, 1]), "ln-dir-invalid", Some([1, 1]), "dir1/dir2"),
+        (Some([0, 0]), "ln-dir-invalid", Some([1, 1]), "dir1/dir2"),
         (Some([0, 0]), "ln-root", Some([0, 1]), "/"),
         (Some([0, 0]), "ln-up2", None, "../.."),
     ];
@@ -1564,6 +1611,25 @@ fn test_ls_sort_name() {
         .stdout_is(".a\n.b\na\nb\n");
 }
 
+#[test]
+fn test_ls_sort_name_bytewise_locales() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for name in ["a", "B", "_c", "Z"] {
+        at.touch(name);
+    }
+
+    // The names are compared byte by byte in the C locales, and in the
+    // locales the C library doesn't know.
+    for locale in ["C", "POSIX", "C.UTF-8", "xx_XX.UTF-8"] {
+        scene
+            .ucmd()
+            .env("LC_ALL", locale)
+            .succeeds()
+            .stdout_is("B\nZ\n_c\na\n");
+    }
+}
+
 #[test]
 fn test_ls_order_size() {
     let scene = TestScenario::new(util_name!());
@@ -1728,6 +1794,32 @@ fn test_ls_styles() {
     assert_eq!(result.stdout_str(), "test  test2\n");
 }
 
+#[test]
+fn test_ls_posix_styles() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.touch("test");
+
+    let re_long =
+        Regex::new(r"[a-z-]* \d* \w* \w* \d* \d{4}-\d{2}-\d{2} \d{2}:\d{2} test\n").unwrap();
+    let re_locale =
+        Regex::new(r"[a-z-]* \d* \w* \w* \d* [A-Z][a-z]{2} ( |\d)\d \d{2}:\d{2} test\n").unwrap();
+
+    let result = scene
+        .ucmd()
+        .arg("-l")
+        .arg("--time-style=posix-long-iso")
+        .succeeds();
+    assert!(re_long.is_match(result.stdout_str()));
+
+    let result = scene
+        .ucmd()
+        .env("POSIXLY_CORRECT", "1")
+        .arg("-l")
+        .arg("--time-style=posix-long-iso")
+        .succeeds();
+    assert!(re_locale.is_match(result.stdout_str()));
+}
+
 #[test]
 fn test_ls_order_time() {
     let scene = TestScenario::new(util_name!());
@@ -1792,9 +1884,196 @@ fn test_ls_order_time() {
     {
         let result = scene.ucmd().arg("-tc").succeeds();
         result.stdout_only("test-2\ntest-4\ntest-3\ntest-1\n");
+
+        // Without -l, -c also sorts by ctime, but with it the order is by name
+        let result = scene.ucmd().arg("-c").succeeds();
+        result.stdout_only("test-2\ntest-4\ntest-3\ntest-1\n");
+        let result = scene.ucmd().arg("-lc").succeeds();
+        let names: Vec<_> = result
+            .stdout_str()
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_whitespace().last())
+            .collect();
+        assert_eq!(names, ["test-1", "test-2", "test-3", "test-4"]);
     }
 }
 
+#[test]
+#[cfg(target_os = "linux")]
+fn test_ls_unknown_birth_time() {
+    // The files of /proc have no birth time
+    new_ucmd!()
+        .args(&["-l", "--time=birth", "/proc/version"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r" {11}\? /proc/version$").unwrap());
+    new_ucmd!()
+        .args(&["-l", "--time=birth", "--time-style=+%s", "/proc/version"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r" 0 \? /proc/version$").unwrap());
+
+    // Files with the same time are sorted by name
+    new_ucmd!()
+        .args(&["-t", "--time=birth", "/proc/version", "/proc/cpuinfo"])
+        .succeeds()
+        .stdout_only("/proc/cpuinfo\n/proc/version\n");
+}
+
+// The fields of the metadata are only looked up for the options that show
+// them.
+#[test]
+#[cfg(unix)]
+fn test_ls_long_fields() {
+    use filetime::FileTime;
+    use std::fs::Permissions;
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("file", "hello");
+    at.hard_link("file", "link");
+    std::fs::set_permissions(at.plus("file"), Permissions::from_mode(0o640)).unwrap();
+    filetime::set_file_times(
+        at.plus("file"),
+        FileTime::from_unix_time(1_000_000_000, 0),
+        FileTime::from_unix_time(1_500_000_000, 0),
+    )
+    .unwrap();
+    let md = std::fs::metadata(at.plus("file")).unwrap();
+    let blocks = (md.blocks() * 512 + 1023) / 1024;
+
+    for (time, seconds) in [
+        (None, 1_500_000_000),
+        (Some("--time=atime"), 1_000_000_000),
+        (Some("--time=ctime"), md.ctime()),
+    ] {
+        scene
+            .ucmd()
+            .args(&["-lisn", "--time-style=+%s", "file"])
+            .args(&time.into_iter().collect::<Vec<_>>())
+            .succeeds()
+            .stdout_only(format!(
+                "{} {blocks} -rw-r----- 2 {} {} 5 {seconds} file\n",
+                md.ino(),
+                md.uid(),
+                md.gid(),
+            ));
+    }
+    scene
+        .ucmd()
+        .args(&["-is", "file"])
+        .succeeds()
+        .stdout_only(format!("{} {blocks} file\n", md.ino()));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ls_time_nanoseconds() {
+    use filetime::FileTime;
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for (name, nanoseconds) in [("a", 500_000_000), ("b", 700_000_000), ("c", 123_456_789)] {
+        at.touch(name);
+        let time = FileTime::from_unix_time(1_577_836_800, nanoseconds);
+        filetime::set_file_mtime(at.plus(name), time).unwrap();
+    }
+    at.touch("old");
+    filetime::set_file_mtime(
+        at.plus("old"),
+        FileTime::from_unix_time(-315_619_200, 250_000_000),
+    )
+    .unwrap();
+
+    // The files of the same second are sorted by their nanoseconds
+    scene
+        .ucmd()
+        .arg("-t")
+        .succeeds()
+        .stdout_only("b\na\nc\nold\n");
+
+    let result = scene
+        .ucmd()
+        .args(&["-gG", "--time-style=+%s.%N %3N %%N", "c", "old"])
+        .succeeds();
+    let dates: Vec<_> = result
+        .stdout_str()
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .skip(3)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    assert_eq!(
+        dates,
+        [
+            "1577836800.123456789 123 %N c",
+            "-315619200.250000000 250 %N old"
+        ]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_ls_acl() {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("with_acl");
+    at.touch("without_acl");
+
+    // An access control list that also lets `nobody` read the file, as
+    // `setfacl -m u:nobody:r` would set it.
+    let mut acl = 2u32.to_le_bytes().to_vec();
+    for (tag, perm, id) in [
+        (0x01u16, 6u16, u32::MAX),
+        (0x02, 4, 65534),
+        (0x04, 4, u32::MAX),
+        (0x10, 4, u32::MAX),
+        (0x20, 4, u32::MAX),
+    ] {
+        acl.extend(tag.to_le_bytes());
+        acl.extend(perm.to_le_bytes());
+        acl.extend(id.to_le_bytes());
+    }
+    let path = CString::new(at.plus("with_acl").as_os_str().as_bytes()).unwrap();
+    let result = unsafe {
+        libc::setxattr(
+            path.as_ptr(),
+            b"system.posix_acl_access\0".as_ptr().cast(),
+            acl.as_ptr().cast(),
+            acl.len(),
+            0,
+        )
+    };
+    if result != 0 {
+        println!("test skipped: the file system doesn't support access control lists");
+        return;
+    }
+
+    scene
+        .ucmd()
+        .args(&["-l", "with_acl", "without_acl"])
+        .succeeds()
+        .stdout_matches(
+            &Regex::new(r"^-[rwx-]{9}\+ 1 .* with_acl\n-[rwx-]{9}  1 .* without_acl$").unwrap(),
+        );
+    scene
+        .ucmd()
+        .args(&["-l", "without_acl"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r"^-[rwx-]{9} 1 .* without_acl$").unwrap());
+    scene
+        .ucmd()
+        .args(&["with_acl"])
+        .succeeds()
+        .stdout_only("with_acl\n");
+}
+
 #[test]
 fn test_ls_non_existing() {
     new_ucmd!().arg("doesntexist").fails();
@@ -1883,9 +2162,9 @@ fn test_ls_color() {
     at.touch(&nested_file);
     at.touch("test-color");
 
-    let a_with_colors = "\x1b[1;34ma\x1b[0m";
-    let z_with_colors = "\x1b[1;34mz\x1b[0m";
-    let nested_dir_with_colors = "\x1b[1;34mnested_dir\x1b[0m"; // spell-checker:disable-line
+    let a_with_colors = "\x1b[01;34ma\x1b[0m";
+    let z_with_colors = "\x1b[01;34mz\x1b[0m";
+    let nested_dir_with_colors = "\x1b[01;34mnested_dir\x1b[0m"; // spell-checker:disable-line
 
     // Color is disabled by default
     let result = scene.ucmd().succeeds();
@@ -1931,7 +2210,127 @@ fn test_ls_color() {
         .arg("-w=15")
         .arg("-C")
         .succeeds()
-        .stdout_only(format!("{a_with_colors}  test-color\nb  {z_with_colors}\n"));
+        .stdout_only(format!(
+            "\x1b[0m{a_with_colors}  test-color\nb  {z_with_colors}\n"
+        ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_ls_color_ls_colors() {
+    use std::os::unix::fs::PermissionsExt;
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a.TAR");
+    at.touch("b.tar");
+    at.touch("exe.tar");
+    at.set_mode("exe.tar", 0o755);
+    at.mkdir("tw");
+    std::fs::set_permissions(at.plus("tw"), PermissionsExt::from_mode(0o1777)).unwrap();
+    at.mkdir("ow");
+    std::fs::set_permissions(at.plus("ow"), PermissionsExt::from_mode(0o777)).unwrap();
+    at.relative_symlink_file("nowhere", "dangling");
+    at.relative_symlink_dir("tw", "link");
+
+    // The suffixes match without regard to case, and the last one given
+    // wins. The suffix doesn't apply to an executable.
+    scene
+        .ucmd()
+        .env("LS_COLORS", "*.tar=31:*.TAR=32:ex=\\e:or=41:ow=^[")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_only(
+            "\x1b[0m\x1b[32ma.TAR\x1b[0m\n\x1b[32mb.tar\x1b[0m\n\x1b[41mdangling\x1b[0m\n\
+             \x1b[\x1bmexe.tar\x1b[0m\n\x1b[01;36mlink\x1b[0m\n\x1b[\x1bmow\x1b[0m\n\
+             \x1b[30;42mtw\x1b[0m\n",
+        );
+
+    // A link is colored like its target, and a dangling one isn't colored
+    // without `or`.
+    scene
+        .ucmd()
+        .env("LS_COLORS", "ln=target:tw=00:ow=00:mi=35")
+        .arg("--color=always")
+        .arg("-l")
+        .arg("dangling")
+        .arg("link")
+        .succeeds()
+        .stdout_contains(" dangling -> \x1b[0m\x1b[35mnowhere\x1b[0m\n")
+        .stdout_contains(" \x1b[37;44mlink\x1b[0m -> \x1b[37;44mtw\x1b[0m\n");
+
+    // Without the colors that depend on the modes, the files are colored
+    // by their types alone.
+    scene
+        .ucmd()
+        .env("LS_COLORS", "*.tar=31:ex=00:st=00:ow=00:tw=00")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_only(
+            "\x1b[0m\x1b[31ma.TAR\x1b[0m\n\x1b[31mb.tar\x1b[0m\n\x1b[01;36mdangling\x1b[0m\n\
+             \x1b[31mexe.tar\x1b[0m\n\x1b[01;36mlink\x1b[0m\n\x1b[01;34mow\x1b[0m\n\
+             \x1b[01;34mtw\x1b[0m\n",
+        );
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_ls_color_capability() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("cap");
+    at.set_mode("cap", 0o755);
+    at.touch("exe");
+    at.set_mode("exe", 0o755);
+    // Setting capabilities takes setcap, and privileges.
+    let set = std::process::Command::new("setcap")
+        .arg("cap_net_raw+ep")
+        .arg(at.plus("cap"))
+        .status();
+    if !set.map_or(false, |status| status.success()) {
+        println!("test skipped: setcap is missing or can't set capabilities");
+        return;
+    }
+
+    // The capabilities come before the executable bits.
+    scene
+        .ucmd()
+        .env("LS_COLORS", "ca=30;41:ex=01;32")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_only("\x1b[0m\x1b[30;41mcap\x1b[0m\n\x1b[01;32mexe\x1b[0m\n");
+
+    // Without `ca`, which is the default, they aren't looked up.
+    scene
+        .ucmd()
+        .env("LS_COLORS", "ex=01;32")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_only("\x1b[0m\x1b[01;32mcap\x1b[0m\n\x1b[01;32mexe\x1b[0m\n");
+}
+
+#[test]
+fn test_ls_color_invalid_ls_colors() {
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.mkdir("dir");
+
+    scene
+        .ucmd()
+        .env("LS_COLORS", "zz=01")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_is("dir\n")
+        .stderr_is(
+            "ls: unrecognized prefix: 'zz'\n\
+             ls: unparsable value for LS_COLORS environment variable\n",
+        );
+
+    scene
+        .ucmd()
+        .env("LS_COLORS", "*.tar")
+        .arg("--color=always")
+        .succeeds()
+        .stdout_is("dir\n")
+        .stderr_is("ls: unparsable value for LS_COLORS environment variable\n");
 }
 
 #[cfg(unix)]
@@ -2257,6 +2656,52 @@ fn test_ls_hidden_link_windows() {
         .stdout_contains(link);
 }
 
+#[cfg(windows)]
+#[test]
+fn test_ls_dotfile_windows() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch(".dotfile");
+
+    ucmd.succeeds().stdout_does_not_contain(".dotfile");
+}
+
+#[cfg(windows)]
+#[test]
+fn test_ls_attributes_windows() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let file = "file";
+    at.touch(file);
+    scene
+        .cmd("attrib")
+        .args(&["+r", "+a", "-h", "-s", file])
+        .succeeds();
+
+    scene
+        .ucmd()
+        .args(&["-l", "--attributes", file])
+        .succeeds()
+        .stdout_contains(" R--A ");
+}
+
+#[cfg(windows)]
+#[test]
+fn test_ls_junction_target_windows() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    scene
+        .cmd("cmd")
+        .args(&["/c", "mklink", "/j", "junction", "dir"])
+        .succeeds();
+
+    scene
+        .ucmd()
+        .args(&["-l", "junction"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r"junction -> .*\\dir\r?\n").unwrap());
+}
+
 #[cfg(windows)]
 #[test]
 fn test_ls_success_on_c_drv_root_windows() {
@@ -3199,6 +3644,38 @@ fn test_ls_context_format() {
     }
 }
 
+#[test]
+#[cfg(unix)]
+fn test_ls_context_unknown() {
+    #[cfg(feature = "feat_selinux")]
+    if selinux::kernel_support() != selinux::KernelSupport::Unsupported
+        || std::path::Path::new("/sys/fs/smackfs").is_dir()
+    {
+        println!("test skipped: Kernel has support for security contexts");
+        return;
+    }
+    let scene = TestScenario::new(util_name!());
+    scene.fixtures.touch("file");
+
+    // Without a security module, the contexts are shown as a question mark
+    scene
+        .ucmd()
+        .args(&["-Z", "file"])
+        .succeeds()
+        .stdout_only("? file\n");
+    scene
+        .ucmd()
+        .args(&["-lZ", "file"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r"^-[rwx-]{9} 1 \S+ \S+ \? 0 .* file$").unwrap());
+    // and without -Z, nothing follows the permissions
+    scene
+        .ucmd()
+        .args(&["-l", "file"])
+        .succeeds()
+        .stdout_matches(&Regex::new(r"^-[rwx-]{9} 1 \S+ \S+ 0 .* file$").unwrap());
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn test_ls_a_A() {
@@ -3297,6 +3774,38 @@ fn test_ls_dereference_looped_symlinks_recursive() {
         .stderr_contains("not listing already-listed directory");
 }
 
+#[test]
+fn test_ls_recursive_continues_after_loop() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    at.mkdir_all("loop/a");
+    at.mkdir("loop/z");
+    at.touch("loop/z/file");
+    at.relative_symlink_dir("..", "loop/a/up");
+
+    ucmd.args(&["-RL", "loop"])
+        .fails()
+        .code_is(2)
+        .stderr_is("ls: loop/a/up: not listing already-listed directory\n")
+        .stdout_is("loop:\na\nz\n\nloop/a:\nup\n\nloop/z:\nfile\n");
+}
+
+#[test]
+fn test_ls_dereference_symlink_to_ancestor_recursive() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    at.mkdir_all("a/b");
+    at.relative_symlink_dir("../..", "a/b/top");
+
+    // The loop goes through the directory that holds the one being listed,
+    // which is listed once more, down to the first directory of the loop.
+    ucmd.args(&["-RL", "a"])
+        .fails()
+        .code_is(2)
+        .stderr_is("ls: a/b/top/a: not listing already-listed directory\n")
+        .stdout_is("a:\nb\n\na/b:\ntop\n\na/b/top:\na\n");
+}
+
 #[test]
 fn test_dereference_dangling_color() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -3376,3 +3885,156 @@ fn test_tabsize_formatting() {
         .succeeds()
         .stdout_is("aaaaaaaa bbbb\ncccc     dddddddd");
 }
+
+#[test]
+fn test_ls_json() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.write("dir/file", "abc");
+    at.touch("dir/.hidden");
+    at.touch("dir/quote\"d");
+
+    let result = ucmd.args(&["--json", "dir"]).succeeds();
+    let stdout = result.stdout_str();
+    assert!(stdout.starts_with("[{\"name\":\"dir\",\"type\":\"directory\","));
+    assert!(stdout.ends_with("}]\n"));
+    assert!(stdout.contains("{\"name\":\"file\",\"type\":\"file\",\"size\":3,"));
+    assert!(stdout.contains("\"name\":\"quote\\\"d\""));
+    assert!(stdout.contains("\"mtime\":\""));
+    assert!(!stdout.contains(".hidden"));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ls_json_symlink() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    at.set_mode("file", 0o640);
+    at.relative_symlink_file("file", "link");
+
+    let result = ucmd.args(&["--json", "file", "link"]).succeeds();
+    let stdout = result.stdout_str();
+    assert!(stdout.contains("\"mode\":\"0640\""));
+    assert!(stdout.contains("\"name\":\"link\",\"type\":\"symlink\","));
+    assert!(stdout.contains("\"target\":\"file\"}"));
+}
+
+#[test]
+fn test_ls_json_all() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("dir");
+    at.touch("dir/.hidden");
+
+    let result = scene.ucmd().args(&["--json", "-a", "dir"]).succeeds();
+    assert!(result.stdout_str().contains("\"name\":\".\""));
+    assert!(result.stdout_str().contains("\"name\":\".hidden\""));
+
+    let result = scene.ucmd().args(&["--json", "-A", "dir"]).succeeds();
+    assert!(!result.stdout_str().contains("\"name\":\".\""));
+    assert!(result.stdout_str().contains("\"name\":\".hidden\""));
+}
+
+#[test]
+fn test_ls_json_recursive() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir_all("dir/sub");
+    at.touch("dir/sub/deep");
+
+    let result = scene.ucmd().args(&["--json", "dir"]).succeeds();
+    assert!(result
+        .stdout_str()
+        .contains("{\"name\":\"sub\",\"type\":\"directory\","));
+    assert!(!result.stdout_str().contains("deep"));
+
+    // The entries of the subdirectories are nested in their objects.
+    let result = scene.ucmd().args(&["--json", "-R", "dir"]).succeeds();
+    assert!(result
+        .stdout_str()
+        .contains("\"entries\":[{\"name\":\"deep\","));
+    assert!(result.stdout_str().ends_with("}]}]}]\n"));
+}
+
+#[test]
+fn test_ls_json_recursive_all_sorted() {
+    use filetime::FileTime;
+
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir_all("dir/sub");
+    at.touch("dir/sub/deep");
+    // Sort '..', then 'sub' and then '.' with -t.
+    for (path, time) in [(".", 30000), ("dir/sub", 20000), ("dir", 10000)] {
+        filetime::set_file_mtime(at.plus(path), FileTime::from_unix_time(time, 0)).unwrap();
+    }
+
+    // Only the real subdirectories are entered, wherever the dots are sorted.
+    for sort in ["-r", "-t"] {
+        let result = scene
+            .ucmd()
+            .args(&["--json", "-aR", sort, "dir"])
+            .succeeds();
+        result.no_stderr();
+        assert!(result.stdout_str().contains("{\"name\":\"deep\","));
+        assert!(!result.stdout_str().contains("\"entries\":null"));
+        assert_eq!(result.stdout_str().matches("\"name\":\"dir\"").count(), 1);
+    }
+}
+
+#[test]
+fn test_ls_git_outside_repository() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("file");
+
+    let expected = scene.ucmd().arg("-l").succeeds().stdout_move_str();
+    scene
+        .ucmd()
+        .args(&["-l", "--git"])
+        .env("GIT_CEILING_DIRECTORIES", at.root_dir_resolved())
+        .succeeds()
+        .stdout_is(expected);
+}
+
+#[test]
+fn test_ls_git() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.mkdir("repo");
+    at.write("repo/tracked", "a");
+    at.write("repo/staged", "b");
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .current_dir(at.plus("repo"))
+            .args(args)
+            .output()
+            .map_or(false, |output| output.status.success())
+    };
+    if !git(&["init", "-q"])
+        || !git(&["add", "."])
+        || !git(&[
+            "-c",
+            "user.name=u",
+            "-c",
+            "user.email=u@u",
+            "commit",
+            "-qm",
+            "c",
+        ])
+    {
+        // git is not installed.
+        return;
+    }
+    at.append("repo/tracked", "c");
+    at.append("repo/staged", "d");
+    git(&["add", "staged"]);
+    at.touch("repo/new");
+
+    let result = scene.ucmd().args(&["-l", "--git", "repo"]).succeeds();
+    let lines: Vec<_> = result.stdout_str().lines().skip(1).collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with(" -N new"));
+    assert!(lines[1].ends_with(" M- staged"));
+    assert!(lines[2].ends_with(" -M tracked"));
+}
@@ -100,7 +100,20 @@ fn test_symbolic_alteration() {
 
     ucmd.arg("-m").arg("-w").arg(TEST_DIR1).succeeds();
     let perms = at.metadata(TEST_DIR1).permissions().mode();
-    assert_eq!(perms, 0o40555);
+    // Without a "who", only the bits that the umask lets through are removed.
+    let umask = uucore::mode::get_umask();
+    assert_eq!(perms, 0o40777 & !(0o222 & !umask));
+}
+
+#[test]
+#[cfg(not(windows))]
+fn test_symbolic_relative_to_all() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    // The mode changes a=rwx, not the default mode of the directory.
+    ucmd.arg("-m").arg("g+w").arg(TEST_DIR1).succeeds();
+    let perms = at.metadata(TEST_DIR1).permissions().mode();
+    assert_eq!(perms, 0o40777);
 }
 
 #[test]
@@ -40,6 +40,25 @@ fn test_create_one_fifo_with_mode() {
     new_ucmd!().arg("abcde").arg("-m600").succeeds();
 }
 
+#[test]
+fn test_create_one_fifo_with_symbolic_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    let (at, mut ucmd) = at_and_ucmd!();
+    // The mode changes a=rw, and the umask doesn't apply to the result.
+    ucmd.arg("-m").arg("g+w").arg("abc").succeeds();
+    assert_eq!(at.metadata("abc").permissions().mode() & 0o777, 0o666);
+}
+
+#[test]
+fn test_create_one_fifo_with_special_bits() {
+    new_ucmd!()
+        .arg("-m")
+        .arg("+s")
+        .arg("abc")
+        .fails()
+        .stderr_is("mkfifo: mode must specify only file permission bits\n");
+}
+
 #[test]
 fn test_create_one_fifo_already_exists() {
     new_ucmd!()
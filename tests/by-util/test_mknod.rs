@@ -58,6 +58,22 @@ fn test_mknod_fifo_read_only() {
     assert!(ts.fixtures.metadata("test_file").permissions().readonly());
 }
 
+#[test]
+#[cfg(not(windows))]
+fn test_mknod_fifo_symbolic_mode() {
+    use std::os::unix::fs::PermissionsExt;
+    let ts = TestScenario::new(util_name!());
+    ts.ucmd()
+        .arg("-m")
+        .arg("+x")
+        .arg("test_file")
+        .arg("p")
+        .succeeds();
+    assert!(ts.fixtures.is_fifo("test_file"));
+    let perms = ts.fixtures.metadata("test_file").permissions().mode();
+    assert_eq!(perms & 0o777, 0o777);
+}
+
 #[test]
 #[cfg(not(windows))]
 fn test_mknod_fifo_invalid_extra_operand() {
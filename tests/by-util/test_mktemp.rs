@@ -826,3 +826,31 @@ fn test_default_missing_value() {
     let scene = TestScenario::new(util_name!());
     scene.ucmd().arg("-d").arg("--tmpdir").succeeds();
 }
+
+#[test]
+fn test_random_source() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let source: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    at.write_bytes("source", &source);
+    let name = scene
+        .ucmd()
+        .args(&["--random-source=source", "tmp.XXXXXX"])
+        .succeeds()
+        .stdout_move_str();
+    assert!(at.file_exists(name.trim_end()));
+    // The same bytes give the same name, which exists now.
+    scene
+        .ucmd()
+        .args(&["-u", "--random-source=source", "tmp.XXXXXX"])
+        .succeeds()
+        .stdout_only(name);
+
+    at.touch("empty");
+    scene
+        .ucmd()
+        .args(&["--random-source=empty", "tmp.XXXXXX"])
+        .fails()
+        .code_is(1)
+        .stderr_only("mktemp: 'empty': end of file\n");
+}
@@ -184,6 +184,48 @@ fn test_mv_interactive() {
     assert!(at.file_exists(file_b));
 }
 
+#[test]
+fn test_mv_interactive_all_and_quit() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+
+    at.mkdir("dir");
+    for name in ["a", "b", "c"] {
+        at.write(name, name);
+        at.write(&format!("dir/{name}"), "old");
+    }
+
+    // "a" answers yes for the next files too.
+    scene
+        .ucmd()
+        .args(&["-i", "a", "b", "c", "dir"])
+        .pipe_in("a\n")
+        .succeeds()
+        .no_stdout();
+    for name in ["a", "b", "c"] {
+        assert!(!at.file_exists(name));
+        assert_eq!(at.read(&format!("dir/{name}")), name);
+    }
+
+    for name in ["a", "b", "c"] {
+        at.write(name, "new");
+    }
+
+    // "q" keeps the destination and moves none of the next files.
+    scene
+        .ucmd()
+        .args(&["-i", "a", "b", "c", "dir"])
+        .pipe_in("y\nq\n")
+        .fails()
+        .code_is(1)
+        .no_stdout();
+    assert!(!at.file_exists("a"));
+    assert!(at.file_exists("b"));
+    assert!(at.file_exists("c"));
+    assert_eq!(at.read("dir/a"), "new");
+    assert_eq!(at.read("dir/b"), "b");
+}
+
 #[test]
 fn test_mv_arg_update_interactive() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -222,6 +264,37 @@ fn test_mv_no_clobber() {
     assert!(at.file_exists(file_b));
 }
 
+#[test]
+fn test_mv_no_clobber_verbose() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let file_a = "test_mv_no_clobber_verbose_file_a";
+    let file_b = "test_mv_no_clobber_verbose_file_b";
+    let file_c = "test_mv_no_clobber_verbose_file_c";
+
+    at.write(file_a, "a");
+    at.write(file_b, "b");
+
+    scene
+        .ucmd()
+        .arg("-nv")
+        .arg(file_a)
+        .arg(file_b)
+        .succeeds()
+        .no_stdout();
+    assert_eq!(at.read(file_b), "b");
+
+    scene
+        .ucmd()
+        .arg("-nv")
+        .arg(file_a)
+        .arg(file_c)
+        .succeeds()
+        .stdout_only(format!("'{file_a}' -> '{file_c}'\n"));
+    assert!(!at.file_exists(file_a));
+    assert_eq!(at.read(file_c), "a");
+}
+
 #[test]
 fn test_mv_replace_file() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -268,6 +341,22 @@ fn test_mv_same_file() {
         .stderr_is(format!("mv: '{file_a}' and '{file_a}' are the same file\n",));
 }
 
+#[test]
+fn test_mv_translated_message() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_translated_message_a";
+
+    at.touch(file_a);
+    ucmd.arg(file_a)
+        .arg(file_a)
+        .env("LC_ALL", "fr_FR.UTF-8")
+        .env("UUTILS_LOCALE_DIR", at.plus_as_string("locales"))
+        .fails()
+        .stderr_is(format!(
+            "mv: '{file_a}' et '{file_a}' sont le même fichier\n"
+        ));
+}
+
 #[test]
 fn test_mv_same_file_not_dot_dir() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -442,6 +531,42 @@ fn test_mv_backup_numbered() {
     assert!(at.file_exists(&format!("{file_b}.~1~")));
 }
 
+#[test]
+fn test_mv_backup_numbered_after_highest() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_backup_numbering_file_a";
+    let file_b = "test_mv_backup_numbering_file_b";
+
+    at.touch(file_a);
+    at.touch(file_b);
+    at.touch(&format!("{file_b}.~3~"));
+    ucmd.arg("--backup=numbered")
+        .arg(file_a)
+        .arg(file_b)
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.file_exists(&format!("{file_b}.~1~")));
+    assert!(at.file_exists(&format!("{file_b}.~4~")));
+}
+
+#[test]
+#[cfg(unix)]
+fn test_mv_backup_numbered_directory() {
+    let (at, mut ucmd) = at_and_ucmd!();
+
+    at.mkdir("a");
+    at.touch("a/x");
+    at.mkdir("b");
+    at.touch("b/y");
+    ucmd.args(&["-T", "--backup=numbered", "a", "b"])
+        .succeeds()
+        .no_stderr();
+
+    assert!(at.file_exists("b/x"));
+    assert!(at.file_exists("b.~1~/y"));
+}
+
 #[test]
 fn test_mv_backup_existing() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -641,6 +766,173 @@ fn test_mv_update_option() {
     assert!(!at.file_exists(file_b));
 }
 
+#[test]
+fn test_mv_update_none() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_update_none_file_a";
+    let file_b = "test_mv_update_none_file_b";
+
+    at.write(file_a, "a");
+    at.write(file_b, "b");
+
+    ucmd.arg("--update=none")
+        .arg(file_a)
+        .arg(file_b)
+        .succeeds()
+        .no_stderr();
+
+    assert_eq!(at.read(file_a), "a");
+    assert_eq!(at.read(file_b), "b");
+}
+
+#[test]
+fn test_mv_update_all() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let file_a = "test_mv_update_all_file_a";
+    let file_b = "test_mv_update_all_file_b";
+
+    at.write(file_a, "a");
+    at.write(file_b, "b");
+    let ts = time::OffsetDateTime::now_local().unwrap();
+    let now = FileTime::from_unix_time(ts.unix_timestamp(), ts.nanosecond());
+    let later = FileTime::from_unix_time(ts.unix_timestamp() + 3600, ts.nanosecond());
+    filetime::set_file_times(at.plus_as_string(file_a), now, now).unwrap();
+    filetime::set_file_times(at.plus_as_string(file_b), now, later).unwrap();
+
+    // The last of --update and -u applies.
+    scene
+        .ucmd()
+        .arg("-u")
+        .arg("--update=all")
+        .arg(file_a)
+        .arg(file_b)
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.file_exists(file_a));
+    assert_eq!(at.read(file_b), "a");
+}
+
+#[test]
+fn test_mv_dry_run() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_dry_run_file_a";
+    let file_b = "test_mv_dry_run_file_b";
+
+    at.write(file_a, "a");
+    at.write(file_b, "b");
+
+    ucmd.arg("--dry-run")
+        .arg("-b")
+        .arg(file_a)
+        .arg(file_b)
+        .succeeds()
+        .stdout_only(format!(
+            "would back up '{file_b}' to '{file_b}~'\nwould rename '{file_a}' to '{file_b}'\n"
+        ));
+
+    assert_eq!(at.read(file_a), "a");
+    assert_eq!(at.read(file_b), "b");
+    assert!(!at.file_exists(&format!("{file_b}~")));
+}
+
+#[test]
+#[cfg(not(feature = "feat_selinux"))]
+fn test_mv_context_without_selinux() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+
+    ucmd.args(&["-Z", "a", "b"])
+        .succeeds()
+        .stderr_is("mv: warning: ignoring --context; it requires an SELinux-enabled kernel\n");
+
+    assert!(!at.file_exists("a"));
+    assert!(at.file_exists("b"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_mv_cross_device_preserves_xattrs() {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::MetadataExt;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    // A tmpfs, which is another file system than the one of the tests.
+    let other_fs = match tempfile::tempdir_in("/dev/shm") {
+        Ok(dir) => dir,
+        Err(_) => return,
+    };
+    if other_fs.path().metadata().unwrap().dev() == at.metadata(".").dev() {
+        return;
+    }
+
+    let file = "test_mv_cross_device_preserves_xattrs";
+    at.write(file, "a");
+    let path = |path: &std::path::Path| CString::new(path.as_os_str().as_bytes()).unwrap();
+    let name = CString::new("user.test").unwrap();
+    let set = unsafe {
+        libc::setxattr(
+            path(&at.plus(file)).as_ptr(),
+            name.as_ptr(),
+            b"value".as_ptr().cast(),
+            5,
+            0,
+        )
+    };
+    if set != 0 {
+        // The file system of the tests doesn't support extended attributes.
+        return;
+    }
+
+    let dest = other_fs.path().join(file);
+    ucmd.arg(file).arg(&dest).succeeds().no_stderr();
+
+    assert!(!at.file_exists(file));
+    let mut value = [0u8; 16];
+    let len = unsafe {
+        libc::getxattr(
+            path(&dest).as_ptr(),
+            name.as_ptr(),
+            value.as_mut_ptr().cast(),
+            value.len(),
+        )
+    };
+    assert_eq!(&value[..len.max(0) as usize], b"value");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_mv_cross_device_keeps_hard_links() {
+    use std::os::unix::fs::MetadataExt;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    // A tmpfs, which is another file system than the one of the tests.
+    let other_fs = match tempfile::tempdir_in("/dev/shm") {
+        Ok(dir) => dir,
+        Err(_) => return,
+    };
+    if other_fs.path().metadata().unwrap().dev() == at.metadata(".").dev() {
+        return;
+    }
+
+    at.mkdir_all("dir/sub");
+    at.write("dir/a", "a");
+    at.hard_link("dir/a", "dir/sub/b");
+    at.symlink_file("a", "dir/link");
+
+    let dest = other_fs.path().join("dir");
+    ucmd.arg("dir").arg(&dest).succeeds().no_stderr();
+
+    assert!(!at.dir_exists("dir"));
+    let a = dest.join("a").metadata().unwrap();
+    let b = dest.join("sub/b").metadata().unwrap();
+    assert_eq!(a.ino(), b.ino());
+    assert_eq!(a.nlink(), 2);
+    assert!(dest.join("link").symlink_metadata().unwrap().is_symlink());
+}
+
 #[test]
 fn test_mv_target_dir() {
     let (at, mut ucmd) = at_and_ucmd!();
@@ -812,6 +1104,58 @@ fn test_mv_verbose() {
         .stdout_only(format!("'{file_a}' -> '{file_b}' (backup: '{file_b}~')\n"));
 }
 
+#[test]
+fn test_mv_progress() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_progress_file_a";
+    let file_b = "test_mv_progress_file_b";
+    at.write(file_a, "contents");
+
+    ucmd.arg("-g").arg(file_a).arg(file_b).succeeds();
+
+    assert!(!at.file_exists(file_a));
+    assert_eq!(at.read(file_b), "contents");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_mv_exchange() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_exchange_file_a";
+    let dir_b = "test_mv_exchange_dir_b";
+    at.write(file_a, "contents");
+    at.mkdir(dir_b);
+    at.touch(&format!("{dir_b}/file"));
+
+    ucmd.arg("--exchange")
+        .arg("-T")
+        .arg(file_a)
+        .arg(dir_b)
+        .succeeds()
+        .no_stderr();
+
+    assert!(at.dir_exists(file_a));
+    assert!(at.file_exists(&format!("{file_a}/file")));
+    assert_eq!(at.read(dir_b), "contents");
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn test_mv_exchange_missing_target() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    let file_a = "test_mv_exchange_missing_file_a";
+    at.touch(file_a);
+
+    ucmd.arg("--exchange")
+        .arg(file_a)
+        .arg("missing")
+        .fails()
+        .stderr_is(format!(
+            "mv: cannot exchange '{file_a}' and 'missing': No such file or directory\n"
+        ));
+    assert!(at.file_exists(file_a));
+}
+
 #[test]
 #[cfg(any(target_os = "linux", target_os = "android"))] // mkdir does not support -m on windows. Freebsd doesn't return a permission error either.
 #[cfg(features = "mkdir")]
@@ -897,6 +1241,25 @@ fn test_mv_into_self_data() {
     assert!(at.file_exists(file2));
     assert!(!at.file_exists(file1));
 }
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_mv_trash() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("a", "new");
+    at.write("b", "old");
+    at.mkdir("data");
+
+    ucmd.args(&["--trash", "a", "b"])
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.file_exists("a"));
+    assert_eq!(at.read("b"), "new");
+    assert_eq!(at.read("data/Trash/files/b"), "old");
+    assert!(at.file_exists("data/Trash/info/b.trashinfo"));
+}
+
 // Todo:
 
 // $ at.touch a b
@@ -909,3 +1272,26 @@ fn test_mv_into_self_data() {
 // $ mv -v a b
 // mv: try to overwrite 'b', overriding mode 0444 (r--r--r--)? y
 // 'a' -> 'b'
+
+#[test]
+fn test_mv_report() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("a");
+
+    ucmd.args(&["--report", "a", "missing", "dir"])
+        .fails()
+        .stderr_contains("mv: 1 of 2 files failed\n");
+    assert!(at.file_exists("dir/a"));
+}
+
+#[test]
+fn test_mv_report_json_single_source() {
+    new_ucmd!()
+        .args(&["--report=json", "missing", "b"])
+        .fails()
+        .stdout_is(
+            "{\"operands\":1,\"failed\":1,\"failures\":[{\"operand\":\"missing\",\
+            \"errors\":[\"cannot stat 'missing': No such file or directory\"]}]}\n",
+        );
+}
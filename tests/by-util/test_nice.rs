@@ -82,3 +82,22 @@ fn test_trailing_empty_adjustment() {
         "error: The argument '--adjustment <adjustment>' requires a value but none was supplied",
     );
 }
+
+#[test]
+fn test_command_not_found() {
+    new_ucmd!()
+        .args(&["-n", "1", "nonexistent-command"])
+        .fails()
+        .code_is(127)
+        .stderr_is("nice: 'nonexistent-command': No such file or directory\n");
+}
+
+#[test]
+fn test_command_not_executable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    ucmd.arg("./dir")
+        .fails()
+        .code_is(126)
+        .stderr_is("nice: './dir': Permission denied\n");
+}
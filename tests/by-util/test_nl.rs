@@ -71,3 +71,12 @@ fn test_sections_and_styles() {
     }
     // spell-checker:enable
 }
+
+#[test]
+fn test_zero_terminated() {
+    new_ucmd!()
+        .args(&["-z", "-w", "1"])
+        .pipe_in("one\0two\nlines\0")
+        .succeeds()
+        .stdout_is("1\tone\x002\ttwo\nlines\0");
+}
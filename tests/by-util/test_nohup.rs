@@ -27,3 +27,22 @@ fn test_nohup_multiple_args_and_flags() {
     assert!(at.file_exists("file1"));
     assert!(at.file_exists("file2"));
 }
+
+#[test]
+fn test_nohup_command_not_found() {
+    new_ucmd!()
+        .arg("nonexistent-command")
+        .fails()
+        .code_is(127)
+        .stderr_contains("failed to run command 'nonexistent-command': No such file or directory");
+}
+
+#[test]
+fn test_nohup_command_not_executable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    ucmd.arg("./file")
+        .fails()
+        .code_is(126)
+        .stderr_contains("failed to run command './file': Permission denied");
+}
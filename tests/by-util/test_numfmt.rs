@@ -74,6 +74,26 @@ fn test_from_auto() {
         .stdout_is("1000\n1024\n");
 }
 
+#[test]
+fn test_from_auto_strict_suffixes() {
+    new_ucmd!()
+        .env("UUTILS_STRICT_SUFFIXES", "1")
+        .args(&["--from=auto", "1Ki", "1K"])
+        .fails()
+        .code_is(2)
+        .stdout_is("1024\n")
+        .stderr_is(
+            "numfmt: ambiguous suffix in input: '1K', which --from=auto takes as a power of 1000 \
+             (use --from=si, or the 'i' suffixes for powers of 1024)\n",
+        );
+
+    new_ucmd!()
+        .env("UUTILS_STRICT_SUFFIXES", "1")
+        .args(&["--from=si", "1K"])
+        .succeeds()
+        .stdout_is("1000\n");
+}
+
 #[test]
 fn test_to_si() {
     new_ucmd!()
@@ -382,6 +382,55 @@ fn sub_float_no_octal_in() {
         .stdout_only("77.000000");
 }
 
+#[test]
+fn sub_float_like_strtold() {
+    new_ucmd!()
+        .args(&["%f %f %f %f", "1E3", " 0x1p-1", "\t+.5", "1.5e-1"])
+        .succeeds()
+        .stdout_only("1000.000000 0.500000 0.500000 0.150000");
+}
+
+#[test]
+fn sub_float_non_finite() {
+    new_ucmd!()
+        .args(&["%f %e %G %5F|", "inf", "-Infinity", "nan", "INF"])
+        .succeeds()
+        .stdout_only("inf -inf NAN   INF|");
+}
+
+#[test]
+fn sub_num_out_of_range() {
+    new_ucmd!()
+        .args(&["%f", "1e5000"])
+        .fails()
+        .code_is(1)
+        .stdout_is("inf")
+        .stderr_is("printf: 1e5000: Numerical result out of range\n");
+}
+
+#[test]
+fn sub_num_expected_numeric() {
+    new_ucmd!()
+        .args(&["%d %f", "abc", "x"])
+        .fails()
+        .code_is(1)
+        .stdout_is("0 0.000000")
+        .stderr_is("printf: abc: expected a numeric value\nprintf: x: expected a numeric value\n");
+}
+
+#[test]
+fn sub_num_incomplete_conversion() {
+    new_ucmd!()
+        .args(&["%d %f", "12abc", "1.5x"])
+        .fails()
+        .code_is(1)
+        .stdout_is("12 1.500000")
+        .stderr_is(
+            "printf: 12abc: value not completely converted\n\
+             printf: 1.5x: value not completely converted\n",
+        );
+}
+
 #[test]
 fn sub_any_asterisk_first_param() {
     new_ucmd!()
@@ -349,6 +349,102 @@ fn test_rm_interactive_never() {
     assert!(!at.file_exists(file_2));
 }
 
+#[test]
+fn test_rm_interactive_never_aliases() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+
+    ucmd.args(&["--interactive=no", "a", "--interactive=none", "b"])
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.file_exists("a"));
+    assert!(!at.file_exists("b"));
+}
+
+#[test]
+fn test_rm_interactive_once() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    for file in ["a", "b", "c", "d"] {
+        at.touch(file);
+    }
+    at.mkdir("dir");
+
+    scene
+        .ucmd()
+        .args(&["--interactive=once", "a", "b", "c", "d"])
+        .pipe_in("n")
+        .succeeds()
+        .stderr_is("rm: remove 4 arguments? ");
+    assert!(at.file_exists("a"));
+
+    scene
+        .ucmd()
+        .args(&["-I", "-r", "dir"])
+        .pipe_in("n")
+        .succeeds()
+        .stderr_is("rm: remove 1 argument recursively? ");
+    assert!(at.dir_exists("dir"));
+
+    // Three files are removed without asking.
+    scene
+        .ucmd()
+        .args(&["-I", "a", "b", "c"])
+        .succeeds()
+        .no_stderr();
+    assert!(!at.file_exists("a"));
+    assert!(at.file_exists("d"));
+}
+
+#[test]
+fn test_rm_prompt_more_overrides_prompt() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("a");
+
+    // The last of -i and -I wins.
+    scene.ucmd().args(&["-i", "-I", "a"]).succeeds().no_stderr();
+    assert!(!at.file_exists("a"));
+
+    at.touch("a");
+    scene
+        .ucmd()
+        .args(&["-I", "-i", "a"])
+        .pipe_in("n")
+        .succeeds()
+        .stderr_is("rm: remove regular empty file 'a'? ");
+    assert!(at.file_exists("a"));
+}
+
+#[test]
+fn test_rm_force_prompt_order() {
+    let scene = TestScenario::new(util_name!());
+
+    // The last of -f and -i wins.
+    scene.ucmd().args(&["-i", "-f", "missing"]).succeeds();
+    scene
+        .ucmd()
+        .args(&["-f", "-i", "missing"])
+        .fails()
+        .stderr_contains("cannot remove 'missing': No such file or directory");
+    scene
+        .ucmd()
+        .args(&["-f", "-I", "missing"])
+        .fails()
+        .stderr_contains("cannot remove 'missing': No such file or directory");
+    scene
+        .ucmd()
+        .args(&["-f", "--interactive=never", "missing"])
+        .succeeds();
+    scene
+        .ucmd()
+        .args(&["-f", "-i"])
+        .fails()
+        .stderr_contains("missing operand");
+}
+
 #[test]
 fn test_rm_descend_directory() {
     // This test descends into each directory and deletes the files and folders inside of them
@@ -518,8 +614,13 @@ fn test_rm_force_prompts_order() {
 }
 
 #[test]
-#[ignore = "issue #3722"]
+#[cfg(unix)]
 fn test_rm_directory_rights_rm1() {
+    // The permissions don't stop root
+    if nix::unistd::geteuid().is_root() {
+        println!("test skipped: running as root");
+        return;
+    }
     let (at, mut ucmd) = at_and_ucmd!();
     at.mkdir_all("b/a/p");
     at.mkdir_all("b/c");
@@ -533,6 +634,21 @@ fn test_rm_directory_rights_rm1() {
     assert!(!at.dir_exists("b/d"));
 }
 
+#[test]
+#[cfg(unix)]
+fn test_rm_recursive_does_not_follow_symlinks() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir_all("a/b");
+    at.mkdir("outside");
+    at.touch("outside/file");
+    at.symlink_dir("outside", "a/b/link");
+
+    ucmd.args(&["-r", "a"]).succeeds().no_output();
+
+    assert!(!at.dir_exists("a"));
+    assert!(at.file_exists("outside/file"));
+}
+
 #[cfg(feature = "chmod")]
 #[test]
 fn test_prompt_write_protected_yes() {
@@ -578,3 +694,190 @@ fn test_fifo_removal() {
         .timeout(Duration::from_secs(2))
         .succeeds();
 }
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_rm_recursive_deep_tree_few_fds() {
+    use rlimit::Resource;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    // Nest the directories from the bottom, to make a tree deeper than
+    // PATH_MAX without ever naming its deep paths.
+    at.mkdir_all("a/d");
+    for _ in 0..3000 {
+        at.mkdir("a/new");
+        at.rename("a/d", "a/new/d");
+        at.rename("a/new", "a/d");
+    }
+
+    ucmd.args(&["-r", "a"])
+        .with_limit(Resource::NOFILE, 64, 64)
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.dir_exists("a"));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_rm_recursive_beyond_max_path() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    // Nested deep enough to go beyond 260 characters
+    let deep: std::path::PathBuf = ["a"].iter().chain(&["directory"; 30]).collect();
+    at.mkdir_all(deep.to_str().unwrap());
+    at.touch(deep.join("file").to_str().unwrap());
+
+    ucmd.args(&["-r", "a"]).succeeds().no_stderr();
+
+    assert!(!at.dir_exists("a"));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_rm_force_read_only() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    let mut permissions = at.metadata("file").permissions();
+    permissions.set_readonly(true);
+    std::fs::set_permissions(at.plus("file"), permissions).unwrap();
+
+    ucmd.args(&["-f", "file"]).succeeds().no_stderr();
+
+    assert!(!at.file_exists("file"));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_rm_trash() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+    at.touch("file");
+
+    ucmd.args(&["--trash", "-r", "dir", "file"])
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.dir_exists("dir"));
+    assert!(!at.file_exists("file"));
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_rm_trash() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+    at.touch("file");
+    at.mkdir("data");
+
+    ucmd.args(&["--trash", "-r", "dir", "file"])
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .succeeds()
+        .no_stderr();
+
+    assert!(!at.dir_exists("dir"));
+    assert!(!at.file_exists("file"));
+    assert!(at.file_exists("data/Trash/files/dir/file"));
+    assert!(at.file_exists("data/Trash/files/file"));
+    let info = at.read("data/Trash/info/file.trashinfo");
+    assert!(info.starts_with("[Trash Info]\n"));
+    assert!(info.contains(&format!("Path={}\n", at.plus_as_string("file"))));
+    assert!(info.contains("DeletionDate="));
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_rm_trash_same_name() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("file");
+    at.touch("dir/file");
+    at.mkdir("data");
+
+    ucmd.args(&["--trash", "file", "dir/file"])
+        .env("XDG_DATA_HOME", at.plus("data"))
+        .succeeds()
+        .no_stderr();
+
+    assert!(at.file_exists("data/Trash/files/file"));
+    assert!(at.file_exists("data/Trash/files/file.2"));
+    assert!(at.file_exists("data/Trash/info/file.2.trashinfo"));
+}
+
+#[test]
+#[cfg(all(unix, not(target_os = "macos")))]
+fn test_rm_trash_not_found() {
+    // Without HOME or XDG_DATA_HOME, there is no trash to move files to.
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+
+    ucmd.args(&["--trash", "file"])
+        .fails()
+        .code_is(2)
+        .stderr_contains("cannot remove 'file'");
+
+    assert!(at.file_exists("file"));
+}
+
+#[test]
+fn test_rm_trash_no_preserve_root() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+
+    ucmd.args(&["--trash", "--no-preserve-root", "-r", "dir"])
+        .fails()
+        .stderr_contains("cannot be used with");
+
+    assert!(at.dir_exists("dir"));
+}
+
+#[test]
+fn test_rm_progress_verbose() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.mkdir("dir");
+    at.touch("dir/file");
+    at.touch("file");
+
+    // The bar is only drawn on a terminal, so the output is the same as
+    // without it.
+    ucmd.args(&["-r", "-v", "--progress", "dir", "file"])
+        .succeeds()
+        .stdout_only(format!(
+            "removed 'dir{}file'\nremoved directory 'dir'\nremoved 'file'\n",
+            std::path::MAIN_SEPARATOR
+        ));
+
+    assert!(!at.dir_exists("dir"));
+    assert!(!at.file_exists("file"));
+}
+
+#[test]
+fn test_rm_report() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+    at.touch("b");
+
+    ucmd.args(&["--report", "a", "missing", "b"])
+        .fails()
+        .no_stdout()
+        .stderr_is(
+            "rm: cannot remove 'missing': No such file or directory\n\
+            rm: 1 of 3 files failed\n",
+        );
+    assert!(!at.file_exists("a"));
+    assert!(!at.file_exists("b"));
+}
+
+#[test]
+fn test_rm_report_json() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("a");
+
+    ucmd.args(&["--report=json", "a", "missing"])
+        .fails()
+        .stdout_is(
+            "{\"operands\":2,\"failed\":1,\"failures\":[{\"operand\":\"missing\",\
+            \"errors\":[\"cannot remove 'missing': No such file or directory\"]}]}\n",
+        );
+}
@@ -84,6 +84,30 @@ fn test_hex_identifier_in_wrong_place() {
         .usage_error("invalid floating point argument: '1234ABCD0x'");
 }
 
+#[test]
+fn test_hex_float() {
+    new_ucmd!()
+        .args(&["0x.8", "3"])
+        .succeeds()
+        .stdout_only("0.5\n1.5\n2.5\n");
+}
+
+#[test]
+fn test_parse_like_strtold() {
+    new_ucmd!()
+        .args(&["1E1", " +12"])
+        .succeeds()
+        .stdout_only("10\n11\n12\n");
+    new_ucmd!()
+        .args(&["1", "1e4940"])
+        .fails()
+        .usage_error("invalid floating point argument: '1e4940'");
+    new_ucmd!()
+        .args(&["1", "2 "])
+        .fails()
+        .usage_error("invalid floating point argument: '2 '");
+}
+
 #[test]
 fn test_rejects_nan() {
     new_ucmd!()
@@ -605,7 +629,12 @@ fn run(args: &[&str], expected: &[u8]) {
     let mut child = cmd.args(args).set_stdout(Stdio::piped()).run_no_wait();
     let buf = child.stdout_exact_bytes(expected.len());
     child.close_stdout();
-    child.wait().unwrap().success();
+    let result = child.wait().unwrap();
+    #[cfg(unix)]
+    result.signal_is(libc::SIGPIPE);
+    #[cfg(not(unix))]
+    result.success();
+    result.no_stderr();
     assert_eq!(buf.as_slice(), expected);
 }
 
@@ -728,7 +757,7 @@ fn test_parse_error_hex() {
     new_ucmd!()
         .arg("0xlmnop")
         .fails()
-        .usage_error("invalid hexadecimal argument: '0xlmnop'");
+        .usage_error("invalid floating point argument: '0xlmnop'");
 }
 
 #[test]
@@ -52,3 +52,23 @@ fn test_shred_force() {
     // file_a was deleted.
     assert!(!at.file_exists(file));
 }
+
+#[test]
+fn test_shred_random_source() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.write("file", "some data");
+    at.touch("empty");
+    scene
+        .ucmd()
+        .args(&["--random-source=empty", "file"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shred: 'empty': end of file\n");
+    scene
+        .ucmd()
+        .args(&["--random-source=nonexistent", "file"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shred: nonexistent: No such file or directory\n");
+}
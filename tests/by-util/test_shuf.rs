@@ -217,3 +217,40 @@ fn test_shuf_multiple_input_line_count() {
         .count();
     assert_eq!(result_count, 5, "Output should have 5 items");
 }
+
+#[test]
+fn test_shuf_random_source() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let source: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    at.write_bytes("source", &source);
+    let first = scene
+        .ucmd()
+        .args(&["-i1-20", "--random-source=source"])
+        .succeeds()
+        .stdout_move_str();
+    scene
+        .ucmd()
+        .args(&["-i1-20", "--random-source=source"])
+        .succeeds()
+        .stdout_only(first);
+}
+
+#[test]
+fn test_shuf_random_source_errors() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    at.touch("empty");
+    scene
+        .ucmd()
+        .args(&["-i1-20", "--random-source=empty"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shuf: 'empty': end of file\n");
+    scene
+        .ucmd()
+        .args(&["-i1-20", "--random-source=nonexistent"])
+        .fails()
+        .code_is(1)
+        .stderr_only("shuf: nonexistent: No such file or directory\n");
+}
@@ -15,6 +15,22 @@ fn test_invalid_time_interval() {
         .usage_error("invalid time interval '-1'");
 }
 
+#[test]
+fn test_sleep_like_strtod() {
+    new_ucmd!()
+        .args(&[" 1E-2", "0x1p-7s", "+.01", "nan"])
+        .fails()
+        .usage_error("invalid time interval 'nan'");
+    new_ucmd!()
+        .args(&[" 1E-2", "0x1p-7s", "+.0001m"])
+        .succeeds()
+        .no_output();
+    new_ucmd!()
+        .arg("1e")
+        .fails()
+        .usage_error("invalid time interval '1e'");
+}
+
 #[test]
 fn test_sleep_no_suffix() {
     let millis_100 = Duration::from_millis(100);
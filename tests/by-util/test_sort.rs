@@ -56,6 +56,20 @@ fn test_buffer_sizes() {
     }
 }
 
+#[test]
+fn test_buffer_size_strict_suffixes() {
+    // The suffixes of the buffer size are only powers of 1024, so they
+    // aren't ambiguous.
+    for buffer_size in ["1M", "1k", "100"] {
+        new_ucmd!()
+            .env("UUTILS_STRICT_SUFFIXES", "1")
+            .args(&["-S", buffer_size])
+            .pipe_in("b\na\n")
+            .succeeds()
+            .stdout_is("a\nb\n");
+    }
+}
+
 #[test]
 fn test_invalid_buffer_size() {
     new_ucmd!()
@@ -249,6 +263,55 @@ fn test_random_ignore_case() {
         .stdout_is(input);
 }
 
+#[test]
+fn test_random_source() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let source: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    at.write_bytes("source", &source);
+    let first = scene
+        .ucmd()
+        .args(&[
+            "-R",
+            "--random-source=source",
+            "default_unsorted_ints.expected",
+        ])
+        .succeeds()
+        .stdout_move_str();
+    scene
+        .ucmd()
+        .args(&[
+            "-R",
+            "--random-source=source",
+            "default_unsorted_ints.expected",
+        ])
+        .succeeds()
+        .stdout_only(first);
+
+    at.touch("empty");
+    scene
+        .ucmd()
+        .args(&["-R", "--random-source=empty"])
+        .pipe_in("a\nb\n")
+        .fails()
+        .code_is(2)
+        .stderr_only("sort: 'empty': end of file\n");
+    scene
+        .ucmd()
+        .args(&["-R", "--random-source=nonexistent"])
+        .pipe_in("a\nb\n")
+        .fails()
+        .code_is(2)
+        .stderr_only("sort: open failed: nonexistent: No such file or directory\n");
+    // The source is only read to sort randomly.
+    scene
+        .ucmd()
+        .arg("--random-source=nonexistent")
+        .pipe_in("b\na\n")
+        .succeeds()
+        .stdout_only("a\nb\n");
+}
+
 #[test]
 fn test_numeric_floats_and_ints() {
     test_helper(
@@ -509,6 +572,21 @@ fn test_keys_multiple_ranges() {
     test_helper("keys_multiple_ranges", &["-k 2,2 -k 3,3"]);
 }
 
+#[test]
+fn test_keys_obsolete() {
+    new_ucmd!()
+        .args(&["+1", "-2"])
+        .pipe_in("b 2\na 1\nc 0\n")
+        .succeeds()
+        .stdout_is("c 0\na 1\nb 2\n");
+
+    new_ucmd!()
+        .env("POSIXLY_CORRECT", "1")
+        .args(&["+1", "-2"])
+        .pipe_in("b 2\na 1\nc 0\n")
+        .fails();
+}
+
 #[test]
 fn test_keys_no_field_match() {
     test_helper("keys_no_field_match", &["-k 4,4"]);
@@ -690,6 +768,20 @@ fn test_zero_terminated() {
     test_helper("zero-terminated", &["-z"]);
 }
 
+#[test]
+fn test_crlf() {
+    new_ucmd!()
+        .arg("--crlf")
+        .pipe_in("\u{feff}b\r\na b\r\nc\na\n")
+        .succeeds()
+        .stdout_only("a\na b\r\nb\r\nc\n");
+    new_ucmd!()
+        .args(&["--crlf", "-u", "-k2"])
+        .pipe_in("x b\r\ny a\nz b\n")
+        .succeeds()
+        .stdout_only("y a\nx b\r\n");
+}
+
 #[test]
 fn test_multiple_files() {
     new_ucmd!()
@@ -804,6 +896,51 @@ fn test_check_unique() {
         .stderr_only("sort: -:2: disorder: A\n");
 }
 
+#[test]
+fn test_unique_by_key() {
+    new_ucmd!()
+        .args(&["-u", "-k2,2"])
+        .pipe_in("a 1\nb 2\nc 1\n")
+        .succeeds()
+        .stdout_only("a 1\nb 2\n");
+
+    new_ucmd!()
+        .args(&["-c", "-u", "-k2,2"])
+        .pipe_in("a 1\nb 2\nc 2\n")
+        .fails()
+        .code_is(1)
+        .stderr_only("sort: -:3: disorder: c 2\n");
+}
+
+#[test]
+fn test_check_extra_operand() {
+    for (check_arg, short) in [("-c", 'c'), ("-C", 'C'), ("--check=quiet", 'C')] {
+        new_ucmd!()
+            .args(&[check_arg, "check_fail.txt", "check_fail.txt"])
+            .fails()
+            .code_is(2)
+            .stderr_only(format!(
+                "sort: extra operand 'check_fail.txt' not allowed with -{short}\n"
+            ));
+    }
+}
+
+#[test]
+fn test_check_and_check_silent_conflict() {
+    for check_arg in ["-c", "--check=diagnose-first"] {
+        new_ucmd!()
+            .args(&[check_arg, "-C", "check_fail.txt"])
+            .fails()
+            .code_is(2)
+            .stderr_only("sort: options '-cC' are incompatible\n");
+    }
+    new_ucmd!()
+        .args(&["--check=quiet", "-C", "check_fail.txt"])
+        .fails()
+        .code_is(1)
+        .no_output();
+}
+
 #[test]
 fn test_dictionary_and_nonprinting_conflicts() {
     let conflicting_args = ["n", "h", "g", "M"];
@@ -323,6 +323,21 @@ fn test_split_lines_number() {
         .stderr_only("split: invalid number of lines: '2fb'\n");
 }
 
+#[test]
+fn test_split_obsolete_lines() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write("file", "1\n2\n3\n4\n5\n");
+
+    ucmd.args(&["-2", "file"])
+        .succeeds()
+        .no_stderr()
+        .no_stdout();
+
+    assert_eq!(at.read("xaa"), "1\n2\n");
+    assert_eq!(at.read("xab"), "3\n4\n");
+    assert_eq!(at.read("xac"), "5\n");
+}
+
 #[test]
 fn test_split_invalid_bytes_size() {
     new_ucmd!()
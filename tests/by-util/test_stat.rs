@@ -44,6 +44,29 @@ fn test_fs_format() {
     ts.ucmd().args(&args).run().stdout_is(expected_stdout);
 }
 
+#[test]
+#[cfg(windows)]
+fn test_fs_format_windows() {
+    let result = new_ucmd!()
+        .args(&["-f", "-c", "%T %S %l", "."])
+        .succeeds()
+        .stdout_move_str();
+    let fields: Vec<&str> = result.split_whitespace().collect();
+    assert_eq!(fields.len(), 3);
+    assert!(["NTFS", "ReFS", "FAT32", "exFAT"].contains(&fields[0]));
+    assert!(fields[1].parse::<u64>().unwrap() > 0);
+    assert_eq!(fields[2], "255");
+}
+
+#[test]
+#[cfg(windows)]
+fn test_file_status_unsupported_windows() {
+    new_ucmd!()
+        .arg(".")
+        .fails()
+        .stderr_contains("only file system status (-f) is supported on Windows");
+}
+
 #[cfg(unix)]
 #[test]
 fn test_terse_normal_format() {
@@ -126,6 +149,34 @@ fn test_format_created_seconds() {
     );
 }
 
+#[cfg(unix)]
+#[test]
+fn test_seconds_precision() {
+    use filetime::FileTime;
+
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("new");
+    filetime::set_file_mtime(
+        at.plus("new"),
+        FileTime::from_unix_time(1_577_836_800, 123_456_789),
+    )
+    .unwrap();
+    at.touch("old");
+    filetime::set_file_mtime(
+        at.plus("old"),
+        FileTime::from_unix_time(-315_619_200, 250_000_000),
+    )
+    .unwrap();
+    ucmd.args(&["-c", "%Y %.Y %.0Y %.1Y %.12Y|%-15.2Y|%+.3Y", "new", "old"])
+        .succeeds()
+        .stdout_only(
+            "1577836800 1577836800.123456789 1577836800 1577836800.1 \
+             1577836800.123456789000|1577836800.12  |+1577836800.123\n\
+             -315619200 -315619199.750000000 -315619200 -315619199.7 \
+             -315619199.750000000000|-315619199.75  |-315619199.750\n",
+        );
+}
+
 #[cfg(unix)]
 #[test]
 fn test_normal_format() {
@@ -167,6 +218,37 @@ fn test_symlinks() {
     }
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_mount_point() {
+    let ts = TestScenario::new(util_name!());
+    let at = &ts.fixtures;
+    at.symlink_file("/proc/self", "link");
+
+    ts.ucmd()
+        .args(&["-c", "%m", "/"])
+        .succeeds()
+        .stdout_is("/\n");
+    ts.ucmd()
+        .args(&["-c", "%m", "/proc/self"])
+        .succeeds()
+        .stdout_is("/proc\n");
+    // A symbolic link is on the file system of its directory.
+    let expected = ts
+        .ucmd()
+        .args(&["-c", "%m", "."])
+        .succeeds()
+        .stdout_move_str();
+    ts.ucmd()
+        .args(&["-c", "%m", "link"])
+        .succeeds()
+        .stdout_is(expected);
+    ts.ucmd()
+        .args(&["-L", "-c", "%m", "link"])
+        .succeeds()
+        .stdout_is("/proc\n");
+}
+
 #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
 #[test]
 fn test_char() {
@@ -80,3 +80,15 @@ fn test_stdbuf_invalid_mode_fails() {
         }
     }
 }
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+fn test_stdbuf_command_not_found() {
+    new_ucmd!()
+        .args(&["-o0", "nonexistent-command"])
+        .fails()
+        .code_is(127)
+        .stderr_is(
+            "stdbuf: failed to run command 'nonexistent-command': No such file or directory\n",
+        );
+}
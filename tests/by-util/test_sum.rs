@@ -75,3 +75,13 @@ fn test_invalid_metadata() {
         .fails()
         .stderr_is("sum: b: No such file or directory\n");
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_read_error() {
+    new_ucmd!()
+        .arg("/proc/self/mem")
+        .fails()
+        .code_is(1)
+        .stderr_is("sum: /proc/self/mem: Input/output error\n");
+}
@@ -1053,6 +1053,24 @@ fn test_obsolete_syntax_positive_lines() {
         .stdout_is("c\nd\ne\n");
 }
 
+/// Test for reading from the NUM-th line with the POSIX.2-1992 syntax:
+/// `tail +3`, which is otherwise a file name.
+#[test]
+fn test_obsolete_syntax_from_start() {
+    new_ucmd!()
+        .env("_POSIX2_VERSION", "199209")
+        .args(&["+3"])
+        .pipe_in("a\nb\nc\nd\ne\n")
+        .succeeds()
+        .stdout_is("c\nd\ne\n");
+
+    new_ucmd!()
+        .args(&["+3"])
+        .pipe_in("a\nb\nc\nd\ne\n")
+        .fails()
+        .stderr_contains("cannot open '+3' for reading");
+}
+
 /// Test for reading all but the first NUM lines: `tail -n -10`.
 #[test]
 fn test_small_file() {
@@ -65,6 +65,23 @@ fn test_tee_append() {
     assert_eq!(at.read(file), content.repeat(2));
 }
 
+#[test]
+fn test_tee_many_chunks_in_order() {
+    // More than fits in the queues of the files.
+    let (at, mut ucmd) = at_and_ucmd!();
+    let content = (1..=500_000).map(|x| format!("{x}\n")).collect::<String>();
+    let files = ["tee_out_a", "tee_out_b", "tee_out_c"];
+
+    ucmd.args(&files)
+        .pipe_in(&content[..])
+        .succeeds()
+        .stdout_is(&content);
+
+    for file in files {
+        assert_eq!(at.read(file), content);
+    }
+}
+
 #[test]
 #[cfg(target_os = "linux")]
 fn test_tee_no_more_writeable_1() {
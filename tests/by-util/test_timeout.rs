@@ -75,7 +75,25 @@ fn test_command_empty_args() {
     new_ucmd!()
         .args(&["", ""])
         .fails()
-        .stderr_contains("timeout: empty string");
+        .usage_error("invalid time interval ''");
+}
+
+#[test]
+fn test_interval_like_strtod() {
+    new_ucmd!()
+        .args(&[" 0x1p-4", "sleep", "10"])
+        .fails()
+        .code_is(124)
+        .no_stderr();
+    new_ucmd!()
+        .args(&["1E-1s", "sleep", "10"])
+        .fails()
+        .code_is(124)
+        .no_stderr();
+    new_ucmd!()
+        .args(&["1,5", "sleep", "0"])
+        .fails()
+        .usage_error("invalid time interval '1,5'");
 }
 
 #[test]
@@ -130,3 +148,24 @@ fn test_kill_after_long() {
         .no_stdout()
         .no_stderr();
 }
+
+#[test]
+fn test_command_not_found() {
+    new_ucmd!()
+        .args(&["1", "nonexistent-command"])
+        .fails()
+        .code_is(127)
+        .stderr_is(
+            "timeout: failed to run command 'nonexistent-command': No such file or directory\n",
+        );
+}
+
+#[test]
+fn test_command_not_executable() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.touch("file");
+    ucmd.args(&["1", "./file"])
+        .fails()
+        .code_is(126)
+        .stderr_is("timeout: failed to run command './file': Permission denied\n");
+}
@@ -301,6 +301,44 @@ fn test_touch_set_only_mtime() {
     assert_eq!(mtime.unix_seconds() - start_of_year.unix_seconds(), 45240);
 }
 
+#[test]
+fn test_touch_set_only_one_time_by_word() {
+    let scene = TestScenario::new(util_name!());
+    let at = &scene.fixtures;
+    let start_of_year = str_to_filetime("%Y%m%d%H%M", "201501010000");
+
+    for (word, access) in [
+        ("access", true),
+        ("atime", true),
+        ("use", true),
+        ("modify", false),
+        ("mtime", false),
+    ] {
+        let file = format!("test_touch_time_{word}");
+        at.touch(&file);
+        scene
+            .ucmd()
+            .args(&["-t", "201501011234", &format!("--time={word}"), &file])
+            .succeeds()
+            .no_stderr();
+
+        let (atime, mtime) = get_file_times(at, &file);
+        let (changed, kept) = if access {
+            (atime, mtime)
+        } else {
+            (mtime, atime)
+        };
+        assert_eq!(changed.unix_seconds() - start_of_year.unix_seconds(), 45240);
+        assert_ne!(changed, kept);
+    }
+
+    new_ucmd!()
+        .args(&["--time=ctime", "file"])
+        .fails()
+        .code_is(1)
+        .stderr_contains("'ctime'");
+}
+
 #[test]
 fn test_touch_set_both() {
     let (at, mut ucmd) = at_and_ucmd!();
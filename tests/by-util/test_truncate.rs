@@ -368,3 +368,34 @@ fn test_fifo_error_reference_and_size() {
         .no_stdout()
         .stderr_contains("cannot open 'fifo' for writing: No such device or address");
 }
+
+#[test]
+fn test_allocate() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["--allocate", "-s", "64K", FILE1]).succeeds();
+    assert_eq!(at.metadata(FILE1).len(), 64 * 1024);
+
+    // The blocks are allocated rather than left as a hole
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        use std::os::unix::fs::MetadataExt;
+        assert!(at.metadata(FILE1).blocks() * 512 >= 64 * 1024);
+    }
+}
+
+#[test]
+fn test_allocate_shrink() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    at.write(FILE1, "1234567890");
+    ucmd.args(&["--allocate", "--size=-5", FILE1]).succeeds();
+    assert_eq!(at.read(FILE1), "12345");
+}
+
+#[test]
+fn test_allocate_no_create() {
+    let (at, mut ucmd) = at_and_ucmd!();
+    ucmd.args(&["--allocate", "-c", "-s", "1K", FILE1])
+        .succeeds()
+        .no_output();
+    assert!(!at.file_exists(FILE1));
+}
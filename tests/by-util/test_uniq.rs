@@ -48,6 +48,47 @@ fn test_stdin_counts() {
         .stdout_is_fixture("sorted-counts.expected");
 }
 
+#[test]
+fn test_obsolete_skip_fields() {
+    new_ucmd!()
+        .args(&["-1"])
+        .pipe_in("a 1\nb 1\nc 2\n")
+        .succeeds()
+        .stdout_is("a 1\nc 2\n");
+}
+
+#[test]
+fn test_crlf() {
+    new_ucmd!()
+        .args(&["--crlf", "-c"])
+        .pipe_in("\u{feff}a\r\na\nb\r\nb\r\n")
+        .succeeds()
+        .stdout_is("      2 a\r\n      2 b\r\n");
+    // Only the byte order mark that starts the text is skipped.
+    new_ucmd!()
+        .args(&["--crlf", "-c"])
+        .pipe_in("\u{feff}a\n\u{feff}a\na\n")
+        .succeeds()
+        .stdout_is("      1 a\n      1 \u{feff}a\n      1 a\n");
+    new_ucmd!()
+        .arg("-c")
+        .pipe_in("a\r\na\n")
+        .succeeds()
+        .stdout_is("      1 a\r\n      1 a\n");
+}
+
+#[test]
+fn test_obsolete_skip_chars() {
+    new_ucmd!()
+        .env("_POSIX2_VERSION", "199209")
+        .args(&["+1"])
+        .pipe_in("ax\nbx\n")
+        .succeeds()
+        .stdout_is("ax\n");
+
+    new_ucmd!().args(&["+1"]).fails().stderr_contains("+1");
+}
+
 #[test]
 fn test_stdin_skip_1_char() {
     new_ucmd!()
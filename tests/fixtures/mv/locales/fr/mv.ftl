@@ -0,0 +1,2 @@
+# Les messages de mv, pour les tests
+error-same-file = { $source } et { $target } sont le même fichier
@@ -0,0 +1,44 @@
+use std::fs::{self, File};
+use std::io::ErrorKind;
+
+use coreutils::{run, Stdio};
+
+#[test]
+#[cfg(feature = "echo")]
+fn test_run_captures_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out");
+    let stdio = Stdio {
+        stdout: Some(File::create(&path).unwrap()),
+        ..Stdio::default()
+    };
+    assert_eq!(run("echo", ["hello", "world"], stdio).unwrap(), 0);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hello world\n");
+}
+
+#[test]
+#[cfg(feature = "cat")]
+fn test_run_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(dir.path().join("in"), "from stdin\n").unwrap();
+    let stdio = Stdio {
+        stdin: Some(File::open(dir.path().join("in")).unwrap()),
+        stdout: Some(File::create(dir.path().join("out")).unwrap()),
+        ..Stdio::default()
+    };
+    let missing = dir.path().join("missing");
+    assert_eq!(
+        run("cat", ["-".as_ref(), missing.as_os_str()], stdio).unwrap(),
+        1
+    );
+    assert_eq!(
+        fs::read_to_string(dir.path().join("out")).unwrap(),
+        "from stdin\n"
+    );
+}
+
+#[test]
+fn test_run_unknown_utility() {
+    let error = run("no-such-utility", Vec::<String>::new(), Stdio::default()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+}
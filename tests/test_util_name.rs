@@ -88,3 +88,103 @@ fn util_name_single() {
         scenario.fixtures.plus("uu-sort").display()
     )));
 }
+
+#[test]
+#[cfg(feature = "ls")]
+#[cfg(any(unix, windows))]
+fn util_name_symlink_without_prefix() {
+    use std::process::Command;
+
+    let scenario = TestScenario::new("ls");
+    scenario.fixtures.touch("file");
+    symlink_file(&scenario.bin_path, scenario.fixtures.plus("ls")).unwrap();
+    let output = Command::new(scenario.fixtures.plus("ls"))
+        .arg(scenario.fixtures.plus("file"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("file"));
+}
+
+#[test]
+#[cfg(feature = "ls")]
+fn list_utils() {
+    use std::process::Command;
+
+    let scenario = TestScenario::new("ls");
+    let output = Command::new(&scenario.bin_path)
+        .arg("--list")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .any(|util| util == "ls"));
+}
+
+#[test]
+#[cfg(feature = "ls")]
+#[cfg(any(unix, windows))]
+fn install_links() {
+    use std::process::Command;
+
+    let scenario = TestScenario::new("ls");
+    let dir = scenario.fixtures.plus("bin");
+    for _ in 0..2 {
+        // Links that are already in place are left alone
+        let output = Command::new(&scenario.bin_path)
+            .arg("--install")
+            .arg(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+    let output = Command::new(dir.join(format!("ls{}", std::env::consts::EXE_SUFFIX)))
+        .arg("--version")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains(env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+#[cfg(feature = "ls")]
+fn generate_completion() {
+    use std::process::Command;
+
+    let scenario = TestScenario::new("ls");
+    let output = Command::new(&scenario.bin_path)
+        .args(["ls", "--generate-completion", "bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout).unwrap().contains("_ls()"));
+
+    let output = Command::new(&scenario.bin_path)
+        .args(["--generate-completion", "bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains("_coreutils()"));
+}
+
+#[test]
+#[cfg(feature = "ls")]
+fn manpage() {
+    use std::process::Command;
+
+    let scenario = TestScenario::new("ls");
+    let output = Command::new(&scenario.bin_path)
+        .args(["manpage", "ls"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(".TH ls 1"));
+    assert!(stdout.contains("\\-\\-almost\\-all"));
+}
@@ -36,11 +36,11 @@ mod test_chcon;
 #[path = "by-util/test_chgrp.rs"]
 mod test_chgrp;
 
-#[cfg(feature = "chmod")]
+#[cfg(all(unix, feature = "chmod"))]
 #[path = "by-util/test_chmod.rs"]
 mod test_chmod;
 
-#[cfg(feature = "chown")]
+#[cfg(all(unix, feature = "chown"))]
 #[path = "by-util/test_chown.rs"]
 mod test_chown;
 